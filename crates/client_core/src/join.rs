@@ -0,0 +1,101 @@
+//! Client half of the connection handshake (`net_core::handshake`).
+//!
+//! `Join::start` yields the `Hello`, to be sent as the first reliable message
+//! on a fresh link. Until the server answers, every delivered payload goes through
+//! `Join::handle`: a `Welcome` is adopted by the `ReplicationBuffer`
+//! (`apply_welcome`: pinned wire versions, local actor, tick rate) and a
+//! `Reject` ends the attempt. Anything else is left for
+//! `ReplicationBuffer::apply_message`.
+
+use net_core::handshake::{Hello, Reject, ServerReply, TAG_REJECT, TAG_WELCOME, Welcome};
+use net_core::snapshot::{SnapshotDecode, SnapshotEncode};
+
+use crate::replication::ReplicationBuffer;
+
+/// Where a join attempt stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinState {
+    /// `start` not called yet.
+    Idle,
+    /// `Hello` queued; waiting for the server's reply.
+    Waiting,
+    Joined(Welcome),
+    Rejected(Reject),
+}
+
+/// One handshake on one link.
+#[derive(Debug, Clone)]
+pub struct Join {
+    hello: Hello,
+    state: JoinState,
+}
+
+impl Join {
+    /// Join `zone_slug` as `player_name`, speaking this build's protocol.
+    #[must_use]
+    pub fn new(zone_slug: &str, player_name: &str) -> Self {
+        Self::with_hello(Hello::new(zone_slug, player_name))
+    }
+
+    /// Join with an explicit `Hello` (tools and tests).
+    #[must_use]
+    pub fn with_hello(hello: Hello) -> Self {
+        Self {
+            hello,
+            state: JoinState::Idle,
+        }
+    }
+
+    /// Encoded `Hello`; send it as the first reliable message on the link.
+    pub fn start(&mut self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.hello.encode(&mut buf);
+        self.state = JoinState::Waiting;
+        buf
+    }
+
+    /// Consume `bytes` (framed or raw) if it is the server's reply to our
+    /// `Hello`, applying a `Welcome` to `rep`. Returns whether it was consumed.
+    pub fn handle(&mut self, bytes: &[u8], rep: &mut ReplicationBuffer) -> bool {
+        let payload = net_core::frame::read_msg(bytes).unwrap_or(bytes);
+        if self.state != JoinState::Waiting
+            || !matches!(payload.first(), Some(&(TAG_WELCOME | TAG_REJECT)))
+        {
+            return false;
+        }
+        match ServerReply::decode(&mut &*payload) {
+            Ok(ServerReply::Welcome(w)) => {
+                log::info!("join: welcomed as actor {} at {} Hz", w.actor_id, w.tick_hz);
+                rep.apply_welcome(&w);
+                self.state = JoinState::Joined(w);
+            }
+            Ok(ServerReply::Reject(r)) => {
+                log::warn!(
+                    "join: rejected ({:?}, server protocol {})",
+                    r.reason,
+                    r.server_protocol
+                );
+                self.state = JoinState::Rejected(r);
+            }
+            Err(e) => {
+                log::warn!("join: malformed handshake reply: {e:#}");
+                return false;
+            }
+        }
+        true
+    }
+
+    #[must_use]
+    pub fn state(&self) -> &JoinState {
+        &self.state
+    }
+
+    /// The server's `Welcome`, once joined.
+    #[must_use]
+    pub fn welcome(&self) -> Option<&Welcome> {
+        match &self.state {
+            JoinState::Joined(w) => Some(w),
+            _ => None,
+        }
+    }
+}
@@ -227,6 +227,8 @@ pub mod controller {
     }
 }
 
+/// Client half of the connection handshake (`Hello` -> `Welcome`).
+pub mod join;
 /// Replication apply scaffolding and buffers.
 pub mod replication;
 /// Placeholder for client-side systems (prediction/lag-comp/etc.).
@@ -4,6 +4,12 @@
 //! - Buffer incoming snapshot deltas
 //! - Apply to client ECS/state
 //! - Invalidate GPU uploads for changed chunks
+//! - Interpolate remote actors from a time-stamped history (`SnapshotInterp`)
+//!   so presentation doesn't jitter with network timing; the local player is
+//!   left at its latest position for prediction to own
+//! - After the handshake (`apply_welcome`), pin decoding to the versions in
+//!   `Welcome`: mismatched messages are dropped, not probed; the local actor
+//!   and tick rate come from it too
 //!
 //! Filled in later when net_core types are finalized.
 
@@ -30,7 +36,23 @@ pub struct ReplicationBuffer {
     pub projectiles: Vec<ProjectileView>,
     pub hits: Vec<net_core::snapshot::HitFx>,
     pub toasts: Vec<u8>,
+    /// Boss telegraphs currently winding up (replaced by each message).
+    pub telegraphs: Vec<net_core::snapshot::TelegraphRep>,
+    /// Active status effects of actors in interest (replaced by each message).
+    pub statuses: Vec<net_core::snapshot::StatusRep>,
+    /// The local player's carried items (replaced by each message).
+    pub inventory: Vec<net_core::snapshot::InventoryItemRep>,
+    /// Stacks the local player's bag holds.
+    pub inventory_slots: u8,
+    /// The local player's loot drops in interest (replaced by each message).
+    pub loot: Vec<net_core::snapshot::LootDropRep>,
     pub hud: HudState,
+    baselines: net_core::baseline::BaselineReceiver,
+    input_seq: u32,
+    interp: SnapshotInterp,
+    negotiated: Option<net_core::handshake::WireVersions>,
+    /// The actor this client controls (`Welcome::actor_id`).
+    local_actor: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -51,68 +73,29 @@ impl ReplicationBuffer {
             Ok(p) => p,
             Err(_) => bytes,
         };
-        // Prefer actor delta snapshot (v4) first
-        let mut slice_delta_v3: &[u8] = payload;
-        if let Ok(d) = net_core::snapshot::ActorSnapshotDelta::decode(&mut slice_delta_v3) {
-            // Removals
-            if !d.removals.is_empty() {
-                self.actors.retain(|a| !d.removals.contains(&a.id));
-                self.wizards.retain(|w| !d.removals.contains(&w.id));
-                self.npcs.retain(|n| !d.removals.contains(&n.id));
-            }
-            // Build id->index map AFTER removals so indices are valid
-            let mut idx: HashMap<u32, usize> = HashMap::new();
-            for (i, a) in self.actors.iter().enumerate() {
-                idx.insert(a.id, i);
-            }
-            // Updates
-            for u in d.updates {
-                if let Some(&i) = idx.get(&u.id) {
-                    let a = &mut self.actors[i];
-                    let mut changed = false;
-                    if u.flags & 1 != 0 {
-                        a.pos.x = net_core::snapshot::dqpos(u.qpos[0]);
-                        a.pos.y = net_core::snapshot::dqpos(u.qpos[1]);
-                        a.pos.z = net_core::snapshot::dqpos(u.qpos[2]);
-                        changed = true;
-                    }
-                    if u.flags & 2 != 0 {
-                        a.yaw = net_core::snapshot::dqyaw(u.qyaw);
-                        changed = true;
-                    }
-                    if u.flags & 4 != 0 {
-                        a.hp = u.hp;
-                        changed = true;
-                    }
-                    if u.flags & 8 != 0 {
-                        a.alive = u.alive != 0;
-                        changed = true;
-                    }
-                    if changed {
-                        // Update derived views if present
-                        for w in &mut self.wizards {
-                            if w.id == a.id {
-                                w.pos = a.pos;
-                                w.yaw = a.yaw;
-                                w.hp = a.hp;
-                                w.max = a.max;
-                            }
-                        }
-                        for n in &mut self.npcs {
-                            if n.id == a.id {
-                                n.pos = a.pos;
-                                n.yaw = a.yaw;
-                                n.hp = a.hp;
-                                n.max = a.max;
-                                n.alive = a.alive;
-                            }
-                        }
-                    }
-                }
-            }
-            // Spawns
-            for a in d.spawns {
-                let av = ActorView {
+        if let Some(v) = &self.negotiated
+            && !v.accepts(payload)
+        {
+            log::debug!(
+                "replication: dropping message tag {:?} outside negotiated versions",
+                payload.first()
+            );
+            return false;
+        }
+        // Prefer the actor delta snapshot first
+        let mut slice_delta: &[u8] = payload;
+        if let Ok(d) = net_core::snapshot::ActorSnapshotDelta::decode(&mut slice_delta) {
+            // Rebuild the full actor set from the delta's baseline. Stale or
+            // reordered deltas, and deltas on a baseline we no longer hold, are
+            // ignored; the server repairs losses by diffing against the tick we
+            // ack (`last_applied_tick`).
+            let Some(set) = self.baselines.apply(&d) else {
+                return false;
+            };
+            self.input_seq = d.input_seq;
+            self.actors = set
+                .values()
+                .map(|a| ActorView {
                     id: a.id,
                     kind: a.kind,
                     faction: a.faction,
@@ -125,31 +108,10 @@ impl ReplicationBuffer {
                     hp: a.hp,
                     max: a.max,
                     alive: a.alive,
-                };
-                self.actors.push(av.clone());
-                match a.kind {
-                    0 => self.wizards.push(WizardView {
-                        id: a.id,
-                        kind: 0,
-                        pos: av.pos,
-                        yaw: av.yaw,
-                        hp: av.hp,
-                        max: av.max,
-                        is_pc: av.faction == 0,
-                    }),
-                    1 | 2 => self.npcs.push(NpcView {
-                        id: a.id,
-                        hp: av.hp,
-                        max: av.max,
-                        pos: av.pos,
-                        radius: av.radius,
-                        alive: av.alive,
-                        attack_anim: 0.0,
-                        yaw: av.yaw,
-                    }),
-                    _ => {}
-                }
-            }
+                    state: a.state,
+                })
+                .collect();
+            self.interp.push(d.tick, &self.actors);
             // After applying updates/spawns/removals, rebuild derived views from actors to ensure
             // that HP/pos/yaw/alive changes are reflected even when only updates occurred.
             {
@@ -164,10 +126,12 @@ impl ReplicationBuffer {
                             yaw: a.yaw,
                             hp: a.hp,
                             max: a.max,
-                            is_pc: a.faction == 0,
+                            is_pc: self.local_actor.map_or(a.faction == 0, |id| id == a.id),
+                            state: a.state,
                         }),
                         1 | 2 => self.npcs.push(NpcView {
                             id: a.id,
+                            archetype_id: a.archetype_id,
                             hp: a.hp,
                             max: a.max,
                             pos: a.pos,
@@ -345,9 +309,6 @@ impl ReplicationBuffer {
                     self.hud.spell_cds[id as usize] = ms;
                 }
             }
-            self.hud.burning_ms = hud.burning_ms;
-            self.hud.slow_ms = hud.slow_ms;
-            self.hud.stunned_ms = hud.stunned_ms;
             return true;
         }
         // HUD toast message
@@ -356,9 +317,114 @@ impl ReplicationBuffer {
             self.toasts.push(toast.code);
             return true;
         }
+        // Boss telegraphs (ground decals)
+        let mut tele_slice: &[u8] = payload;
+        if let Ok(t) = net_core::snapshot::TelegraphMsg::decode(&mut tele_slice) {
+            self.telegraphs = t.items;
+            return true;
+        }
+        // Status effects (buff/debuff icons)
+        let mut status_slice: &[u8] = payload;
+        if let Ok(st) = net_core::snapshot::StatusMsg::decode(&mut status_slice) {
+            self.statuses = st.items;
+            return true;
+        }
+        // Inventory (own character only)
+        let mut inv_slice: &[u8] = payload;
+        if let Ok(inv) = net_core::snapshot::InventoryMsg::decode(&mut inv_slice) {
+            self.inventory = inv.items;
+            self.inventory_slots = inv.slots;
+            return true;
+        }
+        // Loot drops (own character only)
+        let mut loot_slice: &[u8] = payload;
+        if let Ok(loot) = net_core::snapshot::LootMsg::decode(&mut loot_slice) {
+            self.loot = loot.items;
+            return true;
+        }
         false
     }
 
+    /// Newest actor snapshot tick applied; send back as `ClientCmd::Ack`.
+    #[must_use]
+    pub fn last_applied_tick(&self) -> Option<u64> {
+        self.baselines.last_applied()
+    }
+
+    /// Newest input `seq` the server had applied in the latest actor delta
+    /// (0 = none); feed to `systems::prediction::Predictor::reconcile`.
+    #[must_use]
+    pub fn last_input_seq(&self) -> u32 {
+        self.input_seq
+    }
+
+    /// Pin decoding to the wire versions agreed in the handshake `Welcome`.
+    pub fn set_negotiated(&mut self, versions: net_core::handshake::WireVersions) {
+        self.negotiated = Some(versions);
+    }
+
+    /// Versions pinned by `set_negotiated`, if any.
+    #[must_use]
+    pub fn negotiated(&self) -> Option<net_core::handshake::WireVersions> {
+        self.negotiated
+    }
+
+    /// Adopt a handshake `Welcome`: pin its wire versions, time-stamp
+    /// snapshots at its tick rate and treat its actor as the local player.
+    pub fn apply_welcome(&mut self, w: &net_core::handshake::Welcome) {
+        self.set_negotiated(w.versions);
+        self.interp.cfg.tick_hz = u32::from(w.tick_hz.max(1));
+        self.local_actor = Some(w.actor_id);
+    }
+
+    /// The actor this client controls, once welcomed.
+    #[must_use]
+    pub fn local_actor(&self) -> Option<u32> {
+        self.local_actor
+    }
+
+    /// Replace the interpolation settings (delay, extrapolation, tick rate).
+    pub fn set_interp_config(&mut self, cfg: InterpConfig) {
+        self.interp.cfg = cfg;
+    }
+
+    /// Advance the interpolation clock by `dt` seconds and rewrite
+    /// `actors`/`wizards`/`npcs` positions and yaws from the snapshot history.
+    /// Call once per frame after draining messages; without it, views hold
+    /// the latest raw snapshot. The local actor always holds the latest
+    /// snapshot: it is predicted, not played back.
+    pub fn interpolate(&mut self, dt: f32) {
+        self.interp.advance(dt);
+        let local = self.local_actor;
+        for a in &mut self.actors {
+            if Some(a.id) == local {
+                continue;
+            }
+            if let Some((pos, yaw)) = self.interp.sample(a.id) {
+                a.pos = pos;
+                a.yaw = yaw;
+            }
+        }
+        for w in &mut self.wizards {
+            if Some(w.id) == local {
+                continue;
+            }
+            if let Some((pos, yaw)) = self.interp.sample(w.id) {
+                w.pos = pos;
+                w.yaw = yaw;
+            }
+        }
+        for n in &mut self.npcs {
+            if Some(n.id) == local {
+                continue;
+            }
+            if let Some((pos, yaw)) = self.interp.sample(n.id) {
+                n.pos = pos;
+                n.yaw = yaw;
+            }
+        }
+    }
+
     /// Drain pending mesh updates accumulated from replication into a vector
     /// of (did, chunk, entry). Renderer or host applies uploads via `MeshUpload`.
     pub fn drain_mesh_updates(
@@ -373,6 +439,8 @@ impl ReplicationBuffer {
 #[derive(Debug, Clone, PartialEq)]
 pub struct NpcView {
     pub id: u32,
+    /// Archetype data id (`ArchetypeSpecDb::by_net_id`) for model selection.
+    pub archetype_id: u16,
     pub hp: i32,
     pub max: i32,
     pub pos: glam::Vec3,
@@ -396,6 +464,16 @@ pub struct ActorView {
     pub hp: i32,
     pub max: i32,
     pub alive: bool,
+    /// Replicated `ACTOR_STATE_*` bits.
+    pub state: u8,
+}
+
+impl ActorView {
+    /// Whether the server has this actor mid-dodge.
+    #[must_use]
+    pub fn dodging(&self) -> bool {
+        self.state & net_core::snapshot::ACTOR_STATE_DODGING != 0
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -404,9 +482,6 @@ pub struct HudState {
     pub mana_max: u16,
     pub gcd_ms: u16,
     pub spell_cds: [u16; 3],
-    pub burning_ms: u16,
-    pub slow_ms: u16,
-    pub stunned_ms: u16,
 }
 #[derive(Debug, Clone, PartialEq)]
 pub struct WizardView {
@@ -417,6 +492,16 @@ pub struct WizardView {
     pub hp: i32,
     pub max: i32,
     pub is_pc: bool,
+    /// Replicated `ACTOR_STATE_*` bits (drives dodge animation).
+    pub state: u8,
+}
+
+impl WizardView {
+    /// Whether the server has this wizard mid-dodge.
+    #[must_use]
+    pub fn dodging(&self) -> bool {
+        self.state & net_core::snapshot::ACTOR_STATE_DODGING != 0
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -427,6 +512,166 @@ pub struct ProjectileView {
     pub vel: glam::Vec3,
 }
 
+/// Interpolation tunables.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterpConfig {
+    /// Server tick rate used to time-stamp snapshots.
+    pub tick_hz: u32,
+    /// Render this far behind the newest snapshot (seconds).
+    pub delay_s: f32,
+    /// Extrapolate at most this far past the newest sample, then hold.
+    pub max_extrapolate_s: f32,
+    /// Render clock lag beyond which it jumps forward instead of catching up.
+    pub clock_snap_s: f32,
+    /// Max fraction the render clock runs fast/slow to absorb jitter.
+    pub max_time_scale: f32,
+    /// Samples kept per actor.
+    pub history: usize,
+}
+
+impl Default for InterpConfig {
+    fn default() -> Self {
+        Self {
+            tick_hz: 30,
+            delay_s: 0.1,
+            max_extrapolate_s: 0.25,
+            clock_snap_s: 0.5,
+            max_time_scale: 0.1,
+            history: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct InterpSample {
+    t: f64,
+    pos: Vec3,
+    yaw: f32,
+}
+
+/// Per-actor time-stamped snapshot history sampled at a render clock that
+/// trails the newest snapshot by `delay_s`.
+///
+/// The render clock advances with local frame time and is nudged (at most
+/// `max_time_scale`) toward `newest - delay_s`, so late or bursty arrivals
+/// change playback speed slightly instead of jumping.
+#[derive(Debug, Default)]
+pub struct SnapshotInterp {
+    pub cfg: InterpConfig,
+    samples: HashMap<u32, std::collections::VecDeque<InterpSample>>,
+    newest_s: Option<f64>,
+    render_s: f64,
+}
+
+impl SnapshotInterp {
+    #[must_use]
+    pub fn new(cfg: InterpConfig) -> Self {
+        Self {
+            cfg,
+            ..Self::default()
+        }
+    }
+
+    fn tick_time(&self, tick: u64) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let t = tick as f64;
+        t / f64::from(self.cfg.tick_hz.max(1))
+    }
+
+    /// Record actor state at server `tick`. Out-of-order ticks are inserted
+    /// in time order; actors absent from a newest snapshot are forgotten.
+    pub fn push(&mut self, tick: u64, actors: &[ActorView]) {
+        let t = self.tick_time(tick);
+        let newest = self.newest_s.is_none_or(|n| t > n);
+        if newest {
+            let present: std::collections::HashSet<u32> = actors.iter().map(|a| a.id).collect();
+            self.samples.retain(|id, _| present.contains(id));
+            if self.newest_s.is_none() {
+                self.render_s = t - f64::from(self.cfg.delay_s);
+            }
+            self.newest_s = Some(t);
+        }
+        for a in actors {
+            let h = self.samples.entry(a.id).or_default();
+            let at = h.partition_point(|s| s.t < t);
+            if h.get(at).is_some_and(|s| (s.t - t).abs() < 1e-9) {
+                continue;
+            }
+            h.insert(
+                at,
+                InterpSample {
+                    t,
+                    pos: a.pos,
+                    yaw: a.yaw,
+                },
+            );
+            while h.len() > self.cfg.history.max(2) {
+                h.pop_front();
+            }
+        }
+    }
+
+    /// Advance the render clock by `dt` seconds of local time.
+    pub fn advance(&mut self, dt: f32) {
+        let Some(newest) = self.newest_s else {
+            return;
+        };
+        let target = newest - f64::from(self.cfg.delay_s);
+        let err = target - self.render_s;
+        // Far behind (long stall, then a burst): jump forward. Never rewind.
+        if err > f64::from(self.cfg.clock_snap_s) {
+            self.render_s = target;
+            return;
+        }
+        // Proportional catch-up: close the error over ~1 s, bounded.
+        let k = f64::from(self.cfg.max_time_scale);
+        let scale = 1.0 + err.clamp(-k, k);
+        // Starved: stop the clock where extrapolation ends.
+        let horizon = newest + f64::from(self.cfg.max_extrapolate_s);
+        self.render_s = (self.render_s + f64::from(dt) * scale).min(horizon.max(self.render_s));
+    }
+
+    /// Current render time (server seconds).
+    #[must_use]
+    pub fn render_time(&self) -> f64 {
+        self.render_s
+    }
+
+    /// Interpolated (or bounded-extrapolated) position and yaw for `id`.
+    #[must_use]
+    pub fn sample(&self, id: u32) -> Option<(Vec3, f32)> {
+        let hist = self.samples.get(&id)?;
+        let now = self.render_s;
+        let first = hist.front()?;
+        if now <= first.t || hist.len() == 1 {
+            return Some((first.pos, first.yaw));
+        }
+        let at = hist.partition_point(|s| s.t <= now);
+        let (a, b, frac) = if at < hist.len() {
+            let (a, b) = (hist[at - 1], hist[at]);
+            (a, b, (now - a.t) / (b.t - a.t))
+        } else {
+            // Past the newest sample: continue along the last segment, capped.
+            let (a, b) = (hist[hist.len() - 2], hist[hist.len() - 1]);
+            let over = (now - b.t).min(f64::from(self.cfg.max_extrapolate_s));
+            (a, b, 1.0 + over / (b.t - a.t))
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let t = frac as f32;
+        Some((a.pos.lerp(b.pos, t), slerp_yaw(a.yaw, b.yaw, t)))
+    }
+}
+
+/// Shortest-arc yaw interpolation on the wire quantization (`qyaw`/`dqyaw`),
+/// so 359° -> 1° turns through 0° rather than the long way round.
+#[must_use]
+pub fn slerp_yaw(a: f32, b: f32, t: f32) -> f32 {
+    use net_core::snapshot::{dqyaw, qyaw};
+    let qa = qyaw(a);
+    let d = f32::from(qyaw(b).wrapping_sub(qa).cast_signed());
+    (dqyaw(qa) + dqyaw(1) * d * t).rem_euclid(std::f32::consts::TAU)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
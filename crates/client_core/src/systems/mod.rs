@@ -9,6 +9,7 @@ pub mod cursor;
 pub mod mouselook;
 pub mod move_intent;
 pub mod pc_controller;
+pub mod prediction;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,244 @@
+//! Client-side prediction and server reconciliation for PC movement.
+//!
+//! Each movement input is applied locally the moment it is issued and sent as
+//! a sequenced `ClientCmd::Move`. When an actor delta arrives carrying the
+//! server's PC position and `input_seq`, inputs up to that sequence are
+//! dropped and the rest (still in flight) are replayed on top of the
+//! authoritative position. The jump between the old and new prediction
+//! becomes a visual offset that decays over time, so corrections glide instead
+//! of popping; very large errors (teleports) snap.
+//!
+//! `step` mirrors the server's `input_apply_intents` (normalized XZ direction,
+//! base speed, run multiplier). Keep the two in sync.
+
+use std::collections::VecDeque;
+
+use glam::Vec3;
+use net_core::command::ClientCmd;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PredictParams {
+    /// Fixed step per input (one server tick).
+    pub dt: f32,
+    /// Base move speed; the server's `MoveSpeed` for the PC.
+    pub speed_mps: f32,
+    /// Speed multiplier while `run` is set.
+    pub run_mult: f32,
+    /// Time for the visual correction offset to halve.
+    pub correction_half_life_s: f32,
+    /// Corrections longer than this snap instead of smoothing.
+    pub snap_distance_m: f32,
+    /// Unacknowledged inputs kept for replay; the oldest drop beyond this.
+    pub max_pending: usize,
+}
+
+impl Default for PredictParams {
+    fn default() -> Self {
+        Self {
+            dt: 1.0 / 30.0,
+            speed_mps: 5.0,
+            run_mult: 1.6,
+            correction_half_life_s: 0.1,
+            snap_distance_m: 3.0,
+            max_pending: 64,
+        }
+    }
+}
+
+/// One sequenced movement input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveInput {
+    pub seq: u32,
+    pub dx: f32,
+    pub dz: f32,
+    pub run: bool,
+}
+
+/// Advance `pos` by one input, exactly as the server does.
+#[must_use]
+pub fn step(pos: Vec3, input: &MoveInput, p: &PredictParams) -> Vec3 {
+    let dir = Vec3::new(input.dx, 0.0, input.dz);
+    if dir.length_squared() <= 1e-6 {
+        return pos;
+    }
+    let speed = p.speed_mps * if input.run { p.run_mult } else { 1.0 };
+    pos + dir.normalize() * speed * p.dt
+}
+
+/// Wrapping comparison for input sequence numbers.
+fn seq_newer(a: u32, b: u32) -> bool {
+    a.wrapping_sub(b).cast_signed() > 0
+}
+
+/// Predicted PC position plus the inputs the server has not confirmed yet.
+#[derive(Clone, Debug)]
+pub struct Predictor {
+    pub params: PredictParams,
+    next_seq: u32,
+    last_acked: u32,
+    pending: VecDeque<MoveInput>,
+    predicted: Vec3,
+    offset: Vec3,
+}
+
+impl Predictor {
+    #[must_use]
+    pub fn new(pos: Vec3, params: PredictParams) -> Self {
+        Self {
+            params,
+            next_seq: 1,
+            last_acked: 0,
+            pending: VecDeque::new(),
+            predicted: pos,
+            offset: Vec3::ZERO,
+        }
+    }
+
+    /// Apply one input locally and return the command to send for it.
+    pub fn push_input(&mut self, dx: f32, dz: f32, run: bool) -> ClientCmd {
+        let seq = self.next_seq;
+        // 0 means "unsequenced" on the wire; skip it on wrap.
+        self.next_seq = self.next_seq.wrapping_add(1).max(1);
+        let input = MoveInput { seq, dx, dz, run };
+        self.predicted = step(self.predicted, &input, &self.params);
+        self.pending.push_back(input);
+        if self.pending.len() > self.params.max_pending {
+            self.pending.pop_front();
+        }
+        ClientCmd::Move {
+            dx,
+            dz,
+            run: u8::from(run),
+            seq,
+        }
+    }
+
+    /// Rebase the prediction on the server's PC position after it applied
+    /// inputs up to `acked_seq`. Returns the correction (new minus old
+    /// prediction); zero for a stale ack.
+    pub fn reconcile(&mut self, server_pos: Vec3, acked_seq: u32) -> Vec3 {
+        if seq_newer(self.last_acked, acked_seq) {
+            return Vec3::ZERO;
+        }
+        self.last_acked = acked_seq;
+        if acked_seq != 0 {
+            while self
+                .pending
+                .front()
+                .is_some_and(|i| !seq_newer(i.seq, acked_seq))
+            {
+                self.pending.pop_front();
+            }
+        }
+        let replayed = self
+            .pending
+            .iter()
+            .fold(server_pos, |p, i| step(p, i, &self.params));
+        let correction = replayed - self.predicted;
+        if correction.length() > self.params.snap_distance_m {
+            self.offset = Vec3::ZERO;
+        } else {
+            // Keep the displayed position where it was; `advance` bleeds it off.
+            self.offset -= correction;
+        }
+        self.predicted = replayed;
+        correction
+    }
+
+    /// Decay the visual correction offset by `dt` seconds.
+    pub fn advance(&mut self, dt: f32) {
+        let hl = self.params.correction_half_life_s.max(1e-3);
+        self.offset *= 0.5f32.powf(dt / hl);
+        if self.offset.length_squared() < 1e-8 {
+            self.offset = Vec3::ZERO;
+        }
+    }
+
+    /// Where to draw the PC: prediction plus the decaying correction offset.
+    #[must_use]
+    pub fn display_pos(&self) -> Vec3 {
+        self.predicted + self.offset
+    }
+
+    /// Predicted simulation position (no smoothing).
+    #[must_use]
+    pub fn predicted_pos(&self) -> Vec3 {
+        self.predicted
+    }
+
+    /// Inputs sent but not yet confirmed by the server.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Newest input `seq` reconciled against (0 = none yet).
+    #[must_use]
+    pub fn last_acked(&self) -> u32 {
+        self.last_acked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_matches_server_without_correction() {
+        let p = PredictParams::default();
+        let mut pr = Predictor::new(Vec3::ZERO, p);
+        let mut server = Vec3::ZERO;
+        let mut sent = Vec::new();
+        for _ in 0..10 {
+            if let ClientCmd::Move { dx, dz, run, seq } = pr.push_input(0.0, 1.0, false) {
+                sent.push(MoveInput {
+                    seq,
+                    dx,
+                    dz,
+                    run: run != 0,
+                });
+            }
+        }
+        // Server has applied the first 4 inputs.
+        for i in &sent[..4] {
+            server = step(server, i, &p);
+        }
+        let c = pr.reconcile(server, sent[3].seq);
+        assert!(c.length() < 1e-5, "{c:?}");
+        assert_eq!(pr.pending(), 6);
+        assert!((pr.predicted_pos().z - 10.0 * 5.0 * p.dt).abs() < 1e-4);
+    }
+
+    #[test]
+    fn correction_is_smoothed_then_settles() {
+        let p = PredictParams::default();
+        let mut pr = Predictor::new(Vec3::ZERO, p);
+        pr.push_input(1.0, 0.0, false);
+        let before = pr.display_pos();
+        // Server disagrees by 0.5 m (e.g., the PC was slowed).
+        let c = pr.reconcile(Vec3::new(-0.5, 0.0, 0.0), 0);
+        assert!(c.x < -0.4);
+        assert!((pr.display_pos() - before).length() < 1e-5, "no pop");
+        pr.advance(p.correction_half_life_s);
+        let half = (pr.display_pos() - pr.predicted_pos()).length();
+        assert!((half - 0.25).abs() < 1e-3, "{half}");
+        for _ in 0..30 {
+            pr.advance(p.dt);
+        }
+        assert!((pr.display_pos() - pr.predicted_pos()).length() < 0.01);
+    }
+
+    #[test]
+    fn stale_ack_is_ignored_and_teleports_snap() {
+        let p = PredictParams::default();
+        let mut pr = Predictor::new(Vec3::ZERO, p);
+        for _ in 0..3 {
+            pr.push_input(0.0, 1.0, true);
+        }
+        pr.reconcile(Vec3::ZERO, 2);
+        assert_eq!(pr.pending(), 1);
+        assert_eq!(pr.reconcile(Vec3::splat(9.0), 1), Vec3::ZERO);
+        pr.reconcile(Vec3::new(50.0, 0.0, 0.0), 3);
+        assert_eq!(pr.display_pos(), Vec3::new(50.0, 0.0, 0.0));
+    }
+}
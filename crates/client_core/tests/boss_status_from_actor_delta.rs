@@ -18,11 +18,13 @@ fn boss_status_populated_from_actor_delta() {
         hp: 220,
         max: 250,
         alive: true,
+        state: 0,
     };
     let delta = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![boss],
         updates: vec![],
         removals: vec![],
@@ -9,9 +9,6 @@ fn client_parses_hud_status() {
         mana_max: 20,
         gcd_ms: 250,
         spell_cds: vec![(0, 0), (1, 1500), (2, 500)],
-        burning_ms: 900,
-        slow_ms: 400,
-        stunned_ms: 0,
     };
     let mut buf = Vec::new();
     hud.encode(&mut buf);
@@ -24,7 +21,4 @@ fn client_parses_hud_status() {
     assert_eq!(repl.hud.mana_max, 20);
     assert_eq!(repl.hud.gcd_ms, 250);
     assert_eq!(repl.hud.spell_cds[1], 1500);
-    assert_eq!(repl.hud.burning_ms, 900);
-    assert_eq!(repl.hud.slow_ms, 400);
-    assert_eq!(repl.hud.stunned_ms, 0);
 }
@@ -0,0 +1,48 @@
+use client_core::replication::ReplicationBuffer;
+use net_core::handshake::WireVersions;
+use net_core::snapshot::{
+    INVENTORY_VERSION, InventoryItemRep, InventoryMsg, SnapshotEncode, WORN_BODY, WORN_NONE,
+};
+
+fn framed(msg: &InventoryMsg) -> Vec<u8> {
+    let mut b = Vec::new();
+    msg.encode(&mut b);
+    let mut f = Vec::new();
+    net_core::frame::write_msg(&mut f, &b);
+    f
+}
+
+#[test]
+fn inventory_replaces_and_respects_negotiated_version() {
+    let mut buf = ReplicationBuffer::default();
+    buf.set_negotiated(WireVersions::CURRENT);
+    let potions = InventoryItemRep {
+        item: "potion_healing".into(),
+        count: 2,
+        worn: WORN_NONE,
+    };
+    let armor = InventoryItemRep {
+        item: "leather_armor".into(),
+        count: 1,
+        worn: WORN_BODY,
+    };
+    let mut msg = InventoryMsg {
+        v: INVENTORY_VERSION,
+        slots: 20,
+        items: vec![potions.clone(), armor.clone()],
+    };
+    assert!(buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.inventory, vec![potions, armor.clone()]);
+    assert_eq!(buf.inventory_slots, 20);
+
+    msg.items.remove(0);
+    assert!(buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.inventory, vec![armor.clone()]);
+
+    let mut stale = WireVersions::CURRENT;
+    stale.inventory += 1;
+    buf.set_negotiated(stale);
+    msg.items.clear();
+    assert!(!buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.inventory, vec![armor]);
+}
@@ -0,0 +1,40 @@
+use client_core::replication::ReplicationBuffer;
+use net_core::handshake::WireVersions;
+use net_core::snapshot::{LOOT_VERSION, LootDropRep, LootMsg, SnapshotEncode};
+
+fn framed(msg: &LootMsg) -> Vec<u8> {
+    let mut b = Vec::new();
+    msg.encode(&mut b);
+    let mut f = Vec::new();
+    net_core::frame::write_msg(&mut f, &b);
+    f
+}
+
+#[test]
+fn loot_replaces_clears_and_respects_negotiated_version() {
+    let mut buf = ReplicationBuffer::default();
+    buf.set_negotiated(WireVersions::CURRENT);
+    let drop = LootDropRep {
+        id: 3,
+        item: "potion_healing".into(),
+        count: 2,
+        pos: [2.0, 0.6, 0.5],
+    };
+    let mut msg = LootMsg {
+        v: LOOT_VERSION,
+        items: vec![drop.clone()],
+    };
+    assert!(buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.loot, vec![drop.clone()]);
+
+    let mut stale = WireVersions::CURRENT;
+    stale.loot += 1;
+    buf.set_negotiated(stale);
+    msg.items.clear();
+    assert!(!buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.loot, vec![drop]);
+
+    buf.set_negotiated(WireVersions::CURRENT);
+    assert!(buf.apply_message(&framed(&msg)));
+    assert!(buf.loot.is_empty());
+}
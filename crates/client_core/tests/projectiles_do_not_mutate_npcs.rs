@@ -29,11 +29,13 @@ fn projectile_id_equal_to_npc_id_does_not_change_npc_view() {
         hp: 100,
         max: 100,
         alive: true,
+        state: 0,
     };
     let delta0 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![spawn],
         updates: vec![],
         removals: vec![],
@@ -45,9 +47,10 @@ fn projectile_id_equal_to_npc_id_does_not_change_npc_view() {
 
     // Next tick: projectile with same id=5 must not mutate NPC views
     let delta1 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 2,
         baseline: 1,
+        input_seq: 0,
         spawns: vec![],
         updates: vec![],
         removals: vec![],
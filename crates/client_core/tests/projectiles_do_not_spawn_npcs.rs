@@ -18,11 +18,13 @@ fn projectiles_do_not_create_npc_views() {
         hp: 100,
         max: 100,
         alive: true,
+        state: 0,
     };
     let delta0 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![spawn],
         updates: vec![],
         removals: vec![],
@@ -39,9 +41,10 @@ fn projectiles_do_not_create_npc_views() {
 
     // Now send a delta with only projectiles; npc count must not grow
     let delta1 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 2,
         baseline: 1,
+        input_seq: 0,
         spawns: vec![],
         updates: vec![],
         removals: vec![],
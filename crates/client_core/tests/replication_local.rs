@@ -68,9 +68,10 @@ fn v3_delta_populates_projectiles() {
     let mut repl = ReplicationBuffer::default();
     // Build a minimal v3 delta with one projectile
     let delta = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![ActorRep {
             id: 1,
             kind: 0,
@@ -84,6 +85,7 @@ fn v3_delta_populates_projectiles() {
             hp: 100,
             max: 100,
             alive: true,
+            state: 0,
         }],
         updates: vec![],
         removals: vec![],
@@ -3,9 +3,10 @@ use net_core::snapshot::{ActorRep, ActorSnapshotDelta, SnapshotEncode};
 #[test]
 fn apply_actor_delta_with_sparse_id_does_not_panic() {
     let delta = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![ActorRep {
             id: 100,
             kind: 1,
@@ -19,6 +20,7 @@ fn apply_actor_delta_with_sparse_id_does_not_panic() {
             hp: 30,
             max: 30,
             alive: true,
+            state: 0,
         }],
         updates: vec![],
         removals: vec![],
@@ -0,0 +1,204 @@
+//! Remote actors are sampled from a time-stamped snapshot history, so their
+//! rendered motion stays smooth when deltas arrive late, out of order, or in
+//! bursts, and bounded when they stop arriving.
+
+use client_core::replication::{InterpConfig, ReplicationBuffer, slerp_yaw};
+use net_core::baseline::{ActorSet, BaselineTracker};
+use net_core::snapshot::{ActorRep, SnapshotEncode};
+
+const HZ: u64 = 30;
+const SPEED: f32 = 3.0; // m/s along +X
+const FRAME_S: f32 = 1.0 / 60.0;
+
+fn delta_bytes(tx: &mut BaselineTracker, tick: u64) -> Vec<u8> {
+    let x = tick as f32 / HZ as f32 * SPEED;
+    let a = ActorRep {
+        id: 9,
+        kind: 1,
+        faction: 2,
+        archetype_id: 2,
+        name_id: 0,
+        unique: 0,
+        pos: [x, 0.6, 0.0],
+        yaw: 0.0,
+        radius: 0.9,
+        hp: 30,
+        max: 30,
+        alive: true,
+        state: 0,
+    };
+    let set: ActorSet = [(a.id, a)].into_iter().collect();
+    let mut out = Vec::new();
+    tx.build(tick, set, vec![], vec![]).encode(&mut out);
+    out
+}
+
+/// Play 60 fps frames; `arrivals(frame)` names the ticks delivered that frame.
+/// Returns the NPC's rendered x per frame.
+fn play(frames: u32, arrivals: impl Fn(u32) -> Vec<u64>) -> Vec<f32> {
+    let mut tx = BaselineTracker::new();
+    let mut rb = ReplicationBuffer::default();
+    rb.set_interp_config(InterpConfig::default());
+    let mut xs = Vec::new();
+    for f in 0..frames {
+        for tick in arrivals(f) {
+            rb.apply_message(&delta_bytes(&mut tx, tick));
+        }
+        rb.interpolate(FRAME_S);
+        if let Some(n) = rb.npcs.first() {
+            xs.push(n.pos.x);
+        }
+    }
+    xs
+}
+
+fn assert_smooth(xs: &[f32], what: &str) {
+    let nominal = SPEED * FRAME_S;
+    for (i, w) in xs.windows(2).enumerate().skip(1) {
+        let step = w[1] - w[0];
+        assert!(
+            step >= -1e-4,
+            "{what}: moved backwards at frame {i}: {step}"
+        );
+        assert!(
+            step <= nominal * 1.5,
+            "{what}: jumped at frame {i}: {step} (nominal {nominal})"
+        );
+    }
+}
+
+#[test]
+fn late_jittered_deltas_play_back_smoothly() {
+    // Tick k nominally lands on frame 2k; add 0..=3 frames of jitter.
+    let jitter = |k: u64| (k * 7 % 4) as u32;
+    let xs = play(240, |f| {
+        (0..120u64)
+            .filter(|&k| 2 * k as u32 + jitter(k) == f)
+            .collect()
+    });
+    assert!(xs.len() > 200);
+    assert_smooth(&xs[20..], "jitter");
+    // Rendered position trails the newest snapshot by about the delay.
+    let last = *xs.last().unwrap();
+    assert!(last > 3.0 && last < 12.0, "{last}");
+}
+
+#[test]
+fn out_of_order_deltas_do_not_rewind() {
+    // Pairs swapped: 1,0 then 3,2 ... — the older one of each pair is stale.
+    let xs = play(240, |f| {
+        if f % 4 != 0 {
+            return vec![];
+        }
+        let k = u64::from(f / 2);
+        vec![k + 1, k]
+    });
+    assert_smooth(&xs[20..], "reorder");
+}
+
+#[test]
+fn bursts_after_a_stall_do_not_snap() {
+    // Deliver in bursts of 5 ticks every 10 frames (~167 ms stalls).
+    let xs = play(300, |f| {
+        if f % 10 != 0 {
+            return vec![];
+        }
+        let k0 = u64::from(f / 10) * 5;
+        (k0..k0 + 5).collect()
+    });
+    assert_smooth(&xs[30..], "burst");
+}
+
+#[test]
+fn extrapolation_is_capped_when_deltas_stop() {
+    let xs = play(180, |f| {
+        if f < 60 {
+            vec![u64::from(f / 2)]
+        } else {
+            vec![]
+        }
+    });
+    let newest_x = 29.0 / HZ as f32 * SPEED;
+    let cap = InterpConfig::default().max_extrapolate_s * SPEED;
+    let held = *xs.last().unwrap();
+    assert!(held > newest_x, "should extrapolate a little");
+    assert!(held <= newest_x + cap + 1e-3, "ran away: {held}");
+    assert!(
+        (xs[xs.len() - 1] - xs[xs.len() - 2]).abs() < 1e-6,
+        "should hold"
+    );
+}
+
+#[test]
+fn yaw_takes_the_short_way_round() {
+    let a = 6.2f32;
+    let b = 0.1f32;
+    let mid = slerp_yaw(a, b, 0.5);
+    // Midpoint of 6.2 -> 0.1 crosses 2π (≈0.0084), not back through π.
+    let expect = ((a + b + std::f32::consts::TAU) * 0.5).rem_euclid(std::f32::consts::TAU);
+    assert!((mid - expect).abs() < 1e-3, "{mid} vs {expect}");
+    assert!((slerp_yaw(1.0, 2.0, 0.25) - 1.25).abs() < 1e-3);
+}
+
+#[test]
+fn welcome_sets_tick_rate_and_local_actor_is_not_played_back() {
+    use net_core::handshake::{PROTOCOL_VERSION, Welcome, WireVersions};
+    const WELCOME_HZ: u16 = 20;
+    let mut tx = BaselineTracker::new();
+    let mut rb = ReplicationBuffer::default();
+    rb.apply_welcome(&Welcome {
+        protocol: PROTOCOL_VERSION,
+        actor_id: 1,
+        zone_manifest_id: 0,
+        tick_hz: WELCOME_HZ,
+        versions: WireVersions::CURRENT,
+    });
+    assert_eq!(rb.local_actor(), Some(1));
+    let rep = |id: u32, kind: u8, faction: u8, x: f32| ActorRep {
+        id,
+        kind,
+        faction,
+        archetype_id: 0,
+        name_id: 0,
+        unique: 0,
+        pos: [x, 0.6, 0.0],
+        yaw: 0.0,
+        radius: 0.9,
+        hp: 30,
+        max: 30,
+        alive: true,
+        state: 0,
+    };
+    // One 20 Hz tick every three 60 fps frames; both move at SPEED.
+    let mut npc_xs = Vec::new();
+    let mut newest_x = 0.0;
+    for f in 0..180u32 {
+        if f % 3 == 0 {
+            let tick = u64::from(f / 3);
+            newest_x = tick as f32 / f32::from(WELCOME_HZ) * SPEED;
+            let set: ActorSet = [
+                (1, rep(1, 0, 0, newest_x)),
+                (2, rep(2, 0, 0, newest_x)),
+                (9, rep(9, 1, 2, newest_x)),
+            ]
+            .into_iter()
+            .collect();
+            let mut out = Vec::new();
+            tx.build(tick, set, vec![], vec![]).encode(&mut out);
+            rb.apply_message(&out);
+        }
+        rb.interpolate(FRAME_S);
+        npc_xs.push(rb.npcs[0].pos.x);
+        let me = rb.wizards.iter().find(|w| w.id == 1).unwrap();
+        assert!(me.is_pc);
+        assert_eq!(me.pos.x, newest_x, "local actor holds the latest snapshot");
+        let other = rb.wizards.iter().find(|w| w.id == 2).unwrap();
+        assert!(!other.is_pc, "another player is not the local one");
+        assert!(other.pos.x < newest_x || f < 3);
+    }
+    assert_smooth(&npc_xs[20..], "welcome tick rate");
+    // Remote actors trail by about the interpolation delay, at 20 Hz timing.
+    let lag = newest_x - *npc_xs.last().unwrap();
+    let want = InterpConfig::default().delay_s * SPEED;
+    assert!((lag - want).abs() < 0.15, "lag {lag} vs {want}");
+}
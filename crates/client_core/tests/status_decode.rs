@@ -0,0 +1,49 @@
+use client_core::replication::ReplicationBuffer;
+use net_core::handshake::WireVersions;
+use net_core::snapshot::{STATUS_VERSION, SnapshotEncode, StatusMsg, StatusRep};
+
+fn framed(msg: &StatusMsg) -> Vec<u8> {
+    let mut b = Vec::new();
+    msg.encode(&mut b);
+    let mut f = Vec::new();
+    net_core::frame::write_msg(&mut f, &b);
+    f
+}
+
+#[test]
+fn statuses_replace_and_clear() {
+    let mut buf = ReplicationBuffer::default();
+    buf.set_negotiated(WireVersions::CURRENT);
+    let burning = StatusRep {
+        actor: 3,
+        status: 1,
+        stacks: 1,
+        remaining_ms: 2500,
+    };
+    let poisoned = StatusRep {
+        actor: 3,
+        status: 4,
+        stacks: 2,
+        remaining_ms: 4000,
+    };
+    let msg = StatusMsg {
+        v: STATUS_VERSION,
+        items: vec![burning, poisoned],
+    };
+    assert!(buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.statuses, vec![burning, poisoned]);
+
+    let msg = StatusMsg {
+        v: STATUS_VERSION,
+        items: vec![poisoned],
+    };
+    assert!(buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.statuses, vec![poisoned]);
+
+    let clear = StatusMsg {
+        v: STATUS_VERSION,
+        items: vec![],
+    };
+    assert!(buf.apply_message(&framed(&clear)));
+    assert!(buf.statuses.is_empty());
+}
@@ -0,0 +1,43 @@
+use client_core::replication::ReplicationBuffer;
+use net_core::handshake::WireVersions;
+use net_core::snapshot::{
+    SnapshotEncode, TELEGRAPH_CIRCLE, TELEGRAPH_VERSION, TelegraphMsg, TelegraphRep,
+};
+
+fn framed(msg: &TelegraphMsg) -> Vec<u8> {
+    let mut b = Vec::new();
+    msg.encode(&mut b);
+    let mut f = Vec::new();
+    net_core::frame::write_msg(&mut f, &b);
+    f
+}
+
+#[test]
+fn telegraphs_replace_and_clear() {
+    let mut buf = ReplicationBuffer::default();
+    buf.set_negotiated(WireVersions::CURRENT);
+    let rep = TelegraphRep {
+        id: 1,
+        owner: 9,
+        shape: TELEGRAPH_CIRCLE,
+        pos: [4.0, 0.6, 2.0],
+        yaw: 0.0,
+        radius: 6.0,
+        half_angle: 0.0,
+        total_ms: 2500,
+        remaining_ms: 900,
+    };
+    let msg = TelegraphMsg {
+        v: TELEGRAPH_VERSION,
+        items: vec![rep.clone()],
+    };
+    assert!(buf.apply_message(&framed(&msg)));
+    assert_eq!(buf.telegraphs, vec![rep]);
+
+    let clear = TelegraphMsg {
+        v: TELEGRAPH_VERSION,
+        items: vec![],
+    };
+    assert!(buf.apply_message(&framed(&clear)));
+    assert!(buf.telegraphs.is_empty());
+}
@@ -17,11 +17,13 @@ fn v4_updates_drive_wizard_view() {
         hp: 100,
         max: 100,
         alive: true,
+        state: 0,
     };
     let delta0 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![spawn],
         updates: vec![],
         removals: vec![],
@@ -43,11 +45,13 @@ fn v4_updates_drive_wizard_view() {
         qyaw: 0,
         hp: 90,
         alive: 0,
+        state: 0,
     };
     let delta1 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 2,
         baseline: 1,
+        input_seq: 0,
         spawns: vec![],
         updates: vec![upd],
         removals: vec![],
@@ -65,9 +69,10 @@ fn v4_updates_drive_wizard_view() {
 
     // Removal
     let delta2 = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 3,
         baseline: 2,
+        input_seq: 0,
         spawns: vec![],
         updates: vec![],
         removals: vec![10],
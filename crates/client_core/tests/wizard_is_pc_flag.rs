@@ -27,6 +27,7 @@ fn wizard_is_pc_flag_set_from_faction() {
         hp: 100,
         max: 100,
         alive: true,
+        state: 0,
     };
     let npc = ActorRep {
         id: 2,
@@ -41,11 +42,13 @@ fn wizard_is_pc_flag_set_from_faction() {
         hp: 100,
         max: 100,
         alive: true,
+        state: 0,
     };
     let delta = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 1,
-        baseline: 0,
+        baseline: net_core::baseline::NO_BASELINE,
+        input_seq: 0,
         spawns: vec![pc, npc],
         updates: vec![],
         removals: vec![],
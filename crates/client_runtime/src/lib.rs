@@ -3,13 +3,25 @@
 //! This crate decouples controller + collision updates from the renderer.
 //! The renderer consumes `SceneInputs` to update the player transform and
 //! camera, without owning input semantics or collision policy.
+//!
+//! Once the renderer sends movement to a server (`predict_moves`), the player's
+//! XZ position follows the client's `Predictor`: one sequenced
+//! `ClientCmd::Move` per server tick, applied locally at once and reconciled
+//! against the server's PC position when an actor delta acks its `input_seq`
+//! (`reconcile`).
 
 use client_core::controller::PlayerController;
 use client_core::input::InputState;
+use client_core::systems::prediction::{MoveInput, PredictParams, Predictor, step};
 use collision_static::{Aabb, Capsule, StaticIndex};
 use glam::Vec3;
+use net_core::command::ClientCmd;
 use std::collections::HashMap;
 
+/// Fixed move inputs issued in one frame at most; a longer stall drops the
+/// backlog instead of sending a burst.
+const MAX_MOVES_PER_FRAME: u32 = 4;
+
 #[derive(Debug, Clone, Copy)]
 pub struct PlayerCameraRig {
     pub yaw: f32,
@@ -36,6 +48,12 @@ pub struct SceneInputs {
     input: InputState,
     ability: AbilityState,
     cam_rig: PlayerCameraRig,
+    /// Server movement prediction; `None` until the first `predict_moves`.
+    predictor: Option<Predictor>,
+    /// Frame time not yet spent on a fixed-step move input.
+    move_accum_s: f32,
+    /// Latest move intent, for drawing between fixed steps.
+    intent: MoveInput,
 }
 
 impl SceneInputs {
@@ -45,6 +63,14 @@ impl SceneInputs {
             input: InputState::default(),
             ability: AbilityState::default(),
             cam_rig: PlayerCameraRig::default(),
+            predictor: None,
+            move_accum_s: 0.0,
+            intent: MoveInput {
+                seq: 0,
+                dx: 0.0,
+                dz: 0.0,
+                run: false,
+            },
         }
     }
     pub fn apply_input(&mut self, input: &InputState) {
@@ -172,6 +198,72 @@ impl SceneInputs {
             );
             self.controller.pos = resolved;
         }
+        self.sync_predicted_xz();
+    }
+
+    /// Turn this frame's move intent (server convention: +dx left, +dz
+    /// forward) into sequenced `ClientCmd::Move`s, one per prediction step
+    /// (a server tick), each applied to the local prediction. Starts
+    /// prediction from the current position on first use.
+    pub fn predict_moves(&mut self, dt: f32, dx: f32, dz: f32, run: bool) -> Vec<ClientCmd> {
+        let pos = self.controller.pos;
+        let pr = self
+            .predictor
+            .get_or_insert_with(|| Predictor::new(pos, PredictParams::default()));
+        let step_s = pr.params.dt.max(1e-3);
+        self.intent = MoveInput {
+            seq: 0,
+            dx,
+            dz,
+            run,
+        };
+        self.move_accum_s += dt.max(0.0);
+        let mut cmds = Vec::new();
+        while self.move_accum_s >= step_s {
+            self.move_accum_s -= step_s;
+            if cmds.len() as u32 >= MAX_MOVES_PER_FRAME {
+                self.move_accum_s = 0.0;
+                break;
+            }
+            cmds.push(pr.push_input(dx, dz, run));
+        }
+        pr.advance(dt);
+        self.sync_predicted_xz();
+        cmds
+    }
+
+    /// Rebase the prediction on the server's PC position, which has applied
+    /// inputs up to `acked_seq` (`ReplicationBuffer::last_input_seq`). Ignored
+    /// before prediction starts, for unsequenced acks (0) and for acks
+    /// already seen.
+    pub fn reconcile(&mut self, server_pos: Vec3, acked_seq: u32) {
+        let Some(pr) = self.predictor.as_mut() else {
+            return;
+        };
+        if acked_seq == 0 || pr.last_acked() == acked_seq {
+            return;
+        }
+        pr.reconcile(server_pos, acked_seq);
+        self.sync_predicted_xz();
+    }
+
+    /// Move inputs sent but not yet acked by the server.
+    pub fn pending_moves(&self) -> usize {
+        self.predictor.as_ref().map_or(0, Predictor::pending)
+    }
+
+    /// While predicting, XZ comes from the prediction (plus the part of the
+    /// next step already elapsed, so motion stays smooth between ticks); Y
+    /// stays with the controller (terrain, jumps).
+    fn sync_predicted_xz(&mut self) {
+        let Some(pr) = &self.predictor else {
+            return;
+        };
+        let lead = step(Vec3::ZERO, &self.intent, &pr.params)
+            * (self.move_accum_s / pr.params.dt.max(1e-3));
+        let p = pr.display_pos() + lead;
+        self.controller.pos.x = p.x;
+        self.controller.pos.z = p.z;
     }
 }
 
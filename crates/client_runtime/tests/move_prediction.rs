@@ -0,0 +1,69 @@
+//! `SceneInputs` movement prediction: frame time becomes one sequenced
+//! `ClientCmd::Move` per server tick, the player moves at once, and server
+//! acks rebase the prediction without popping the drawn position.
+
+use client_core::systems::prediction::PredictParams;
+use client_runtime::SceneInputs;
+use glam::Vec3;
+use net_core::command::ClientCmd;
+
+fn seqs(cmds: &[ClientCmd]) -> Vec<u32> {
+    cmds.iter()
+        .filter_map(|c| match c {
+            ClientCmd::Move { seq, .. } => Some(*seq),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn moves_are_sequenced_per_tick_and_predicted() {
+    let p = PredictParams::default();
+    let mut s = SceneInputs::new(Vec3::ZERO);
+    // Three ticks' worth of frame time, in uneven frames.
+    let mut cmds = Vec::new();
+    for dt in [0.05, 0.02, 0.03 + 1e-4] {
+        cmds.extend(s.predict_moves(dt, 0.0, 1.0, false));
+    }
+    assert_eq!(seqs(&cmds), vec![1, 2, 3]);
+    assert_eq!(s.pending_moves(), 3);
+    let step = p.speed_mps * p.dt;
+    assert!((s.pos().z - 3.0 * step).abs() < 0.01, "{}", s.pos().z);
+
+    // The server applied two of them exactly as predicted: nothing moves.
+    let before = s.pos();
+    s.reconcile(Vec3::new(0.0, 0.0, 2.0 * step), 2);
+    assert_eq!(s.pending_moves(), 1);
+    assert!((s.pos() - before).length() < 1e-4);
+    // Seen acks and unsequenced ones are ignored.
+    s.reconcile(Vec3::new(9.0, 0.0, 9.0), 2);
+    s.reconcile(Vec3::new(9.0, 0.0, 9.0), 0);
+    assert!((s.pos() - before).length() < 1e-4);
+}
+
+#[test]
+fn server_corrections_glide_in() {
+    let p = PredictParams::default();
+    let mut s = SceneInputs::new(Vec3::ZERO);
+    let cmds = s.predict_moves(p.dt + 1e-4, 1.0, 0.0, false);
+    assert_eq!(seqs(&cmds), vec![1]);
+    let before = s.pos();
+    // The server held the player in place (e.g., rooted).
+    s.reconcile(Vec3::ZERO, 1);
+    assert!((s.pos() - before).length() < 1e-4, "no pop");
+    let mid = s.predict_moves(p.correction_half_life_s, 0.0, 0.0, false);
+    assert!(s.pos().x > 0.0 && s.pos().x < before.x, "{:?}", s.pos());
+    assert!(seqs(&mid).len() <= 4);
+    for _ in 0..30 {
+        s.predict_moves(p.dt, 0.0, 0.0, false);
+    }
+    assert!(s.pos().length() < 0.01, "{:?}", s.pos());
+}
+
+#[test]
+fn a_long_stall_does_not_burst_inputs() {
+    let mut s = SceneInputs::new(Vec3::ZERO);
+    let cmds = s.predict_moves(1.0, 0.0, 1.0, true);
+    assert_eq!(cmds.len(), 4);
+    assert!(s.predict_moves(0.001, 0.0, 1.0, true).is_empty());
+}
@@ -22,6 +22,8 @@ pub struct PcAnimCfg {
     pub jump_start: Option<String>,
     pub jump_loop: Option<String>,
     pub jump_land: Option<String>,
+    // Played while the server replicates the PC as dodging
+    pub dodge: Option<String>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -88,5 +90,8 @@ pub fn load_default() -> Result<PcAnimCfg> {
     if let Ok(v) = std::env::var("PC_ANIM_JUMP_LAND") {
         cfg.jump_land = Some(v);
     }
+    if let Ok(v) = std::env::var("PC_ANIM_DODGE") {
+        cfg.dodge = Some(v);
+    }
     Ok(cfg)
 }
@@ -7,14 +7,19 @@ pub struct EncounterSpec {
     #[serde(default)]
     pub pc_spawn: Option<String>,
     #[serde(default)]
+    pub props: Vec<PropSpec>,
+    #[serde(default)]
     pub npcs: Vec<NpcSpec>,
     #[serde(default)]
     pub ai: Option<AiTuning>,
+    /// How carved destructibles recover; ruins last forever when absent.
+    #[serde(default)]
+    pub destructibles: Option<DestructiblePolicy>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct NpcSpec {
-    pub archetype: String, // "Wizard" | "Zombie" | "DeathKnight" | ...
+    pub archetype: String, // `ArchetypeSpecDb` key ("Undead" | "WizardNPC" | "DeathKnight") or a unique boss
     #[serde(default)]
     pub count: u32,
     #[serde(default)]
@@ -22,6 +27,44 @@ pub struct NpcSpec {
     pub spawn: String,
     #[serde(default)]
     pub faction: Option<String>,
+    /// Hit points override; the archetype default applies when absent.
+    #[serde(default)]
+    pub hp: Option<i32>,
+    /// Seconds until a dead member is replaced; never when absent.
+    #[serde(default)]
+    pub respawn_s: Option<f32>,
+    /// Population cap: most members alive at once (defaults to `count`).
+    #[serde(default)]
+    pub max_alive: Option<u32>,
+    /// Members that chase this far from where the fight began reset and go back.
+    #[serde(default)]
+    pub leash_m: Option<f32>,
+    /// Idle wander radius around each member's spawn position.
+    #[serde(default)]
+    pub wander_m: f32,
+    /// Waypoint ids idle members walk in a loop (overrides wandering).
+    #[serde(default)]
+    pub patrol: Vec<String>,
+}
+
+/// Server-side prop placed at boot before NPCs (e.g., destructible ruins).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropSpec {
+    pub kind: String, // "demo_ruins"
+    /// Named spawn point; the prop's authored default placement when absent.
+    #[serde(default)]
+    pub spawn: Option<String>,
+}
+
+/// Zone policy for carved destructible voxels.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DestructiblePolicy {
+    /// Seconds after a carve before its voxels start to grow back; never when absent.
+    #[serde(default)]
+    pub regrow_after_s: Option<f32>,
+    /// Voxels restored per second across the zone once regrowth starts.
+    #[serde(default)]
+    pub regrow_voxels_per_s: f32,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -34,11 +77,23 @@ pub struct AiTuning {
 
 pub fn load_encounter_for_zone(_packs_root: &std::path::Path, slug: &str) -> Result<EncounterSpec> {
     // Authoring path for v1 (can be switched to a baked copy later).
-    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
-        .join("../../data/zones")
-        .join(slug)
-        .join("encounter.json");
+    let path = encounter_path(slug);
     let txt = std::fs::read_to_string(&path)
         .with_context(|| format!("read encounter: {}", path.display()))?;
     serde_json::from_str::<EncounterSpec>(&txt).context("parse encounter")
 }
+
+/// Load a zone's encounter spec, or `None` when the zone does not have one.
+pub fn find_encounter_for_zone(slug: &str) -> Result<Option<EncounterSpec>> {
+    if !encounter_path(slug).is_file() {
+        return Ok(None);
+    }
+    load_encounter_for_zone(std::path::Path::new(""), slug).map(Some)
+}
+
+fn encounter_path(slug: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../data/zones")
+        .join(slug)
+        .join("encounter.json")
+}
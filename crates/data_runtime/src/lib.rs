@@ -17,7 +17,13 @@ pub mod zone_scene;
 pub mod zone_snapshot;
 pub mod specs {
     pub mod archetypes;
+    pub mod boss_scripts;
+    pub mod factions;
+    pub mod items;
+    pub mod loot;
     pub mod projectiles;
+    pub mod statuses;
+    pub mod weapons;
 }
 pub mod scene;
 pub mod configs {
@@ -96,6 +96,14 @@ impl SpecDb {
         self.spells.get(&name_key)
     }
 
+    /// Every loaded spell once (by canonical id), in no particular order.
+    pub fn spells(&self) -> impl Iterator<Item = &SpellSpec> {
+        self.spells
+            .iter()
+            .filter(|(k, s)| **k == s.id)
+            .map(|(_, s)| s)
+    }
+
     pub fn get_class(&self, id: &str) -> Option<&ClassSpec> {
         self.classes.get(id)
     }
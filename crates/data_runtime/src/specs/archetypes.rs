@@ -1,10 +1,16 @@
 //! Archetype spawn specifications for server-side defaults.
+//!
+//! One entry per spawnable actor type (`data/config/archetypes.toml`). The
+//! server builds every actor from its entry; a zero speed, aggro radius or
+//! melee damage means the archetype lacks that component. `net_id` is
+//! replicated as `ActorRep::archetype_id` so clients can pick models and UI
+//! without a fixed enum.
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct ArchetypeSpec {
     pub radius_m: f32,
     pub move_speed_mps: f32,
@@ -12,6 +18,100 @@ pub struct ArchetypeSpec {
     pub attack_radius_m: f32,
     pub melee_damage: i32,
     pub melee_cooldown_s: f32,
+    /// Replicated archetype id (0 = unassigned).
+    #[serde(default)]
+    pub net_id: u16,
+    /// Presentation bucket: "wizard", "zombie" or "boss".
+    #[serde(default)]
+    pub kind: String,
+    /// "pc", "wizards", "undead" or "neutral".
+    #[serde(default)]
+    pub faction: String,
+    #[serde(default)]
+    pub hp: i32,
+    /// Display name; unnamed archetypes replicate without one.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Only one may exist at a time (named bosses).
+    #[serde(default)]
+    pub unique: bool,
+    /// Known spells by ability id (e.g., "fireball").
+    #[serde(default)]
+    pub spells: Vec<String>,
+    /// Mana pool; 0 with no spells means no resource pool.
+    #[serde(default)]
+    pub mana: i32,
+    #[serde(default)]
+    pub mana_regen_per_s: f32,
+    /// Stamina for dodges; 0 means the archetype cannot dodge.
+    #[serde(default)]
+    pub stamina: i32,
+    #[serde(default)]
+    pub stamina_regen_per_s: f32,
+    #[serde(default)]
+    pub gcd_s: f32,
+    /// Encounter script id in `data/bosses` (bosses only).
+    #[serde(default)]
+    pub script: Option<String>,
+    /// Armor Class weapon attacks roll against.
+    #[serde(default = "default_ac")]
+    pub ac: i32,
+    /// Weapon ids in `data/config/weapons.toml` usable via `ClientCmd::Attack`.
+    #[serde(default)]
+    pub weapons: Vec<String>,
+    /// Spell attack bonus for the archetype's spells.
+    #[serde(default = "default_spell_attack_bonus")]
+    pub spell_attack_bonus: i32,
+    /// DC targets save against for the archetype's spells.
+    #[serde(default = "default_spell_save_dc")]
+    pub spell_save_dc: i32,
+    /// Character level (cantrip damage bands).
+    #[serde(default = "default_level")]
+    pub level: u8,
+    /// Saving throw modifiers, e.g. `saves = { dex = 2 }`.
+    #[serde(default)]
+    pub saves: SaveMods,
+    /// Damage types taken at half damage (e.g., `["fire"]`).
+    #[serde(default)]
+    pub resist: Vec<String>,
+    /// SRD conditions the archetype is immune to (e.g., `["poisoned"]`);
+    /// statuses imposing them don't take.
+    #[serde(default)]
+    pub immune: Vec<String>,
+    /// Inventory size in stacks; 0 means the archetype carries nothing.
+    #[serde(default)]
+    pub bag_slots: u32,
+    /// Item ids in `data/items` carried at spawn, one per unit.
+    #[serde(default)]
+    pub items: Vec<String>,
+}
+
+/// Per-ability saving throw modifiers; omitted abilities are +0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SaveMods {
+    pub str: i8,
+    pub dex: i8,
+    pub con: i8,
+    pub int: i8,
+    pub wis: i8,
+    pub cha: i8,
+}
+
+fn default_ac() -> i32 {
+    10
+}
+
+fn default_spell_attack_bonus() -> i32 {
+    5
+}
+
+fn default_spell_save_dc() -> i32 {
+    13
+}
+
+fn default_level() -> u8 {
+    1
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -34,42 +134,168 @@ impl ArchetypeSpecDb {
             let db: Self = toml::from_str(&txt).context("parse archetypes TOML")?;
             Ok(db)
         } else {
-            // Defaults for Undead, WizardNPC (caster), DeathKnight
-            let mut db = Self::default();
-            db.entries.insert(
-                "Undead".into(),
-                ArchetypeSpec {
-                    radius_m: 0.9,
-                    move_speed_mps: 2.0,
-                    aggro_radius_m: 25.0,
-                    attack_radius_m: 0.35,
-                    melee_damage: 5,
-                    melee_cooldown_s: 0.6,
+            Ok(Self::builtin())
+        }
+    }
+
+    /// Archetype by replicated id.
+    pub fn by_net_id(&self, net_id: u16) -> Option<(&str, &ArchetypeSpec)> {
+        self.entries
+            .iter()
+            .find(|(_, s)| s.net_id == net_id && net_id != 0)
+            .map(|(k, s)| (k.as_str(), s))
+    }
+
+    /// Built-in defaults used when the data file is absent: PC, Undead,
+    /// WizardNPC (caster), DeathKnight and Nivita.
+    pub fn builtin() -> Self {
+        let caster = |spells: &[&str]| spells.iter().map(|s| s.to_string()).collect();
+        let mut db = Self::default();
+        db.entries.insert(
+            "PC".into(),
+            ArchetypeSpec {
+                radius_m: 0.7,
+                move_speed_mps: 5.0,
+                net_id: 1,
+                kind: "wizard".into(),
+                faction: "pc".into(),
+                hp: 100,
+                spells: caster(&["fire_bolt", "fireball", "magic_missile"]),
+                mana: 20,
+                mana_regen_per_s: 1.0,
+                stamina: 100,
+                stamina_regen_per_s: 20.0,
+                gcd_s: 0.30,
+                ac: 12,
+                weapons: caster(&["quarterstaff", "shortbow"]),
+                bag_slots: 20,
+                items: caster(&["potion_healing", "potion_healing", "leather_armor"]),
+                spell_attack_bonus: 5,
+                spell_save_dc: 13,
+                level: 1,
+                saves: SaveMods {
+                    dex: 2,
+                    int: 5,
+                    ..Default::default()
                 },
-            );
-            db.entries.insert(
-                "WizardNPC".into(),
-                ArchetypeSpec {
-                    radius_m: 0.7,
-                    move_speed_mps: 0.0,
-                    aggro_radius_m: 0.0,
-                    attack_radius_m: 0.0,
-                    melee_damage: 0,
-                    melee_cooldown_s: 0.0,
+                ..Default::default()
+            },
+        );
+        db.entries.insert(
+            "Undead".into(),
+            ArchetypeSpec {
+                radius_m: 0.9,
+                move_speed_mps: 2.0,
+                aggro_radius_m: 25.0,
+                attack_radius_m: 0.35,
+                melee_damage: 5,
+                melee_cooldown_s: 0.6,
+                net_id: 2,
+                kind: "zombie".into(),
+                faction: "undead".into(),
+                hp: 30,
+                ac: 8,
+                spell_attack_bonus: 5,
+                spell_save_dc: 13,
+                level: 1,
+                saves: SaveMods {
+                    wis: -2,
+                    ..Default::default()
                 },
-            );
-            db.entries.insert(
-                "DeathKnight".into(),
-                ArchetypeSpec {
-                    radius_m: 1.0,
-                    move_speed_mps: 2.2,
-                    aggro_radius_m: 40.0,
-                    attack_radius_m: 0.45,
-                    melee_damage: 18,
-                    melee_cooldown_s: 0.9,
+                immune: caster(&["poisoned"]),
+                ..Default::default()
+            },
+        );
+        db.entries.insert(
+            "DeathKnight".into(),
+            ArchetypeSpec {
+                radius_m: 1.0,
+                move_speed_mps: 2.2,
+                aggro_radius_m: 40.0,
+                attack_radius_m: 0.45,
+                melee_damage: 18,
+                melee_cooldown_s: 0.9,
+                net_id: 3,
+                kind: "boss".into(),
+                faction: "undead".into(),
+                hp: 400,
+                name: Some("Death Knight".into()),
+                spells: caster(&["fireball", "magic_missile"]),
+                mana: 40,
+                mana_regen_per_s: 0.3,
+                gcd_s: 0.40,
+                ac: 18,
+                spell_attack_bonus: 7,
+                spell_save_dc: 15,
+                level: 5,
+                saves: SaveMods {
+                    dex: 2,
+                    con: 5,
+                    wis: 2,
+                    ..Default::default()
                 },
-            );
-            Ok(db)
-        }
+                resist: caster(&["necrotic"]),
+                immune: caster(&["poisoned"]),
+                ..Default::default()
+            },
+        );
+        db.entries.insert(
+            "WizardNPC".into(),
+            ArchetypeSpec {
+                radius_m: 0.7,
+                net_id: 4,
+                kind: "wizard".into(),
+                faction: "wizards".into(),
+                hp: 100,
+                spells: caster(&["fire_bolt", "fireball", "magic_missile"]),
+                mana: 30,
+                mana_regen_per_s: 0.5,
+                gcd_s: 0.30,
+                ac: 12,
+                spell_attack_bonus: 5,
+                spell_save_dc: 13,
+                level: 1,
+                saves: SaveMods {
+                    dex: 2,
+                    int: 5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        db.entries.insert(
+            "Nivita".into(),
+            ArchetypeSpec {
+                radius_m: 0.9,
+                move_speed_mps: 2.6,
+                aggro_radius_m: 35.0,
+                attack_radius_m: 0.35,
+                melee_damage: 12,
+                melee_cooldown_s: 0.8,
+                net_id: 5,
+                kind: "boss".into(),
+                faction: "undead".into(),
+                hp: 225,
+                name: Some("Nivita, Lady of Undertide".into()),
+                unique: true,
+                script: Some("nivita".into()),
+                ac: 18,
+                spell_attack_bonus: 9,
+                spell_save_dc: 17,
+                level: 10,
+                saves: SaveMods {
+                    str: -1,
+                    dex: 1,
+                    int: 5,
+                    wis: 4,
+                    cha: 3,
+                    ..Default::default()
+                },
+                resist: caster(&["necrotic", "psychic"]),
+                immune: caster(&["charmed", "frightened"]),
+                ..Default::default()
+            },
+        );
+        db
     }
 }
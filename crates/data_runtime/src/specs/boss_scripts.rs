@@ -0,0 +1,230 @@
+//! Boss encounter scripts (`data/bosses/*.toml`).
+//!
+//! A script lists telegraphed abilities, HP-threshold phases with an ability
+//! rotation each, one-shot triggers, and an enrage timer. Archetypes opt in
+//! with `script = "<id>"`; the server runs the script while the boss is in
+//! combat. Scripts are validated on load so a typo fails at boot rather than
+//! mid-fight.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Ground shape of a telegraphed ability.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelegraphShape {
+    #[default]
+    Circle,
+    /// Cone from the boss toward its target, `angle_deg` wide.
+    Cone,
+}
+
+/// Where a telegraph is placed when the wind-up starts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelegraphAnchor {
+    /// Centered on the boss.
+    #[default]
+    #[serde(rename = "self")]
+    Caster,
+    /// Centered on the boss's current target.
+    Target,
+}
+
+/// A telegraphed AoE: the decal shows for `windup_s`, then everything hostile
+/// inside the shape takes `damage`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BossAbility {
+    #[serde(default)]
+    pub shape: TelegraphShape,
+    #[serde(default)]
+    pub at: TelegraphAnchor,
+    pub radius_m: f32,
+    /// Full cone width; ignored for circles.
+    #[serde(default)]
+    pub angle_deg: f32,
+    pub windup_s: f32,
+    pub damage: i32,
+    /// Status (`data/config/statuses.toml`) applied to everyone hit.
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Adds spawned on a ring around the boss.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddSpawn {
+    pub archetype: String,
+    pub count: u32,
+    #[serde(default = "default_add_ring_m")]
+    pub radius_m: f32,
+}
+
+fn default_add_ring_m() -> f32 {
+    6.0
+}
+
+/// A phase starts once the boss's HP drops to `hp_pct` percent or below.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BossPhase {
+    pub name: String,
+    pub hp_pct: f32,
+    /// Ability ids cast in order, looping.
+    pub rotation: Vec<String>,
+    /// Seconds between rotation casts.
+    pub interval_s: f32,
+    /// Spawned when the phase starts.
+    #[serde(default)]
+    pub adds: Vec<AddSpawn>,
+}
+
+/// Fires once per pull when all of its conditions hold.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BossTrigger {
+    /// Boss HP at or below this percent.
+    #[serde(default)]
+    pub hp_pct: Option<f32>,
+    /// Seconds since the pull.
+    #[serde(default)]
+    pub after_s: Option<f32>,
+    /// Ability to telegraph.
+    #[serde(default)]
+    pub cast: Option<String>,
+    #[serde(default)]
+    pub adds: Vec<AddSpawn>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BossScript {
+    pub id: String,
+    pub abilities: HashMap<String, BossAbility>,
+    /// Ordered by descending `hp_pct`; the first phase starts at 100.
+    pub phases: Vec<BossPhase>,
+    #[serde(default)]
+    pub triggers: Vec<BossTrigger>,
+    /// Seconds after the pull until enrage (0 = never).
+    #[serde(default)]
+    pub enrage_s: f32,
+    /// Multiplier on ability and melee damage once enraged.
+    #[serde(default = "default_enrage_mul")]
+    pub enrage_damage_mul: f32,
+    /// Rotation interval multiplier once enraged.
+    #[serde(default = "default_enrage_mul")]
+    pub enrage_interval_mul: f32,
+}
+
+fn default_enrage_mul() -> f32 {
+    1.0
+}
+
+impl BossScript {
+    /// Check phase order and that every referenced ability exists.
+    pub fn validate(&self) -> Result<()> {
+        let Some(first) = self.phases.first() else {
+            bail!("boss script '{}' has no phases", self.id);
+        };
+        if first.hp_pct < 100.0 {
+            bail!("boss script '{}': first phase must start at 100%", self.id);
+        }
+        for w in self.phases.windows(2) {
+            if w[1].hp_pct >= w[0].hp_pct {
+                bail!(
+                    "boss script '{}': phase '{}' must start below '{}'",
+                    self.id,
+                    w[1].name,
+                    w[0].name
+                );
+            }
+        }
+        let known = |a: &str| -> Result<()> {
+            if !self.abilities.contains_key(a) {
+                bail!("boss script '{}' uses unknown ability '{a}'", self.id);
+            }
+            Ok(())
+        };
+        for p in &self.phases {
+            if p.rotation.is_empty() || p.interval_s <= 0.0 {
+                bail!(
+                    "boss script '{}': phase '{}' needs a rotation and interval",
+                    self.id,
+                    p.name
+                );
+            }
+            for a in &p.rotation {
+                known(a)?;
+            }
+        }
+        for t in &self.triggers {
+            if t.hp_pct.is_none() && t.after_s.is_none() {
+                bail!("boss script '{}': trigger without a condition", self.id);
+            }
+            if let Some(a) = &t.cast {
+                known(a)?;
+            }
+        }
+        for (id, a) in &self.abilities {
+            if a.radius_m <= 0.0 || a.windup_s < 0.0 {
+                bail!("boss script '{}': ability '{id}' has a bad shape", self.id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Index of the last phase whose threshold `hp_pct` has reached.
+    pub fn phase_for(&self, hp_pct: f32) -> usize {
+        self.phases
+            .iter()
+            .rposition(|p| hp_pct <= p.hp_pct)
+            .unwrap_or(0)
+    }
+}
+
+/// Scripts by id.
+#[derive(Debug, Clone, Default)]
+pub struct BossScriptDb {
+    pub scripts: HashMap<String, BossScript>,
+}
+
+fn data_root() -> std::path::PathBuf {
+    let here = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let ws = here.join("../../data");
+    if ws.is_dir() { ws } else { here.join("data") }
+}
+
+impl BossScriptDb {
+    /// Load every script in `data/bosses`; a missing directory yields none.
+    pub fn load_default() -> Result<Self> {
+        Self::load_dir(&data_root().join("bosses"))
+    }
+
+    pub fn load_dir(dir: &std::path::Path) -> Result<Self> {
+        let mut db = Self::default();
+        if !dir.is_dir() {
+            return Ok(db);
+        }
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("read {}", dir.display()))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|x| x == "toml"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let txt = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            let script = Self::parse(&txt).with_context(|| format!("{}", path.display()))?;
+            db.scripts.insert(script.id.clone(), script);
+        }
+        Ok(db)
+    }
+
+    /// Parse and validate one script.
+    pub fn parse(txt: &str) -> Result<BossScript> {
+        let script: BossScript = toml::from_str(txt).context("parse boss script TOML")?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&BossScript> {
+        self.scripts.get(id)
+    }
+}
@@ -0,0 +1,113 @@
+//! Faction registry specifications (`data/config/factions.toml`).
+//!
+//! Each faction has a replicated `id` and a starting `standing` with players.
+//! A player's reputation with a faction starts there and moves by `hit_rep`
+//! and `kill_rep` when they hit or kill one of its members; a kill also moves
+//! their reputation with the factions named in `kill_spillover`. Reputation
+//! stays within `min_rep..=max_rep` and falls into a band: below
+//! `hostile_below` is hostile, `friendly_at` and above is friendly, anything
+//! else neutral. Non-player factions are hostile to each other when either
+//! lists the other under `hostile`.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Name of the faction every player belongs to.
+pub const PLAYER_FACTION: &str = "pc";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FactionSpec {
+    /// Replicated id (`ActorRep::faction`); unique.
+    pub id: u8,
+    /// Display name; the key when empty.
+    #[serde(default)]
+    pub name: String,
+    /// Reputation players start with.
+    #[serde(default)]
+    pub standing: i32,
+    /// Reputation change for hitting a member.
+    #[serde(default)]
+    pub hit_rep: i32,
+    /// Reputation change for killing a member.
+    #[serde(default)]
+    pub kill_rep: i32,
+    /// Reputation changes with other factions for killing a member.
+    #[serde(default)]
+    pub kill_spillover: HashMap<String, i32>,
+    /// Factions this one fights on sight.
+    #[serde(default)]
+    pub hostile: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FactionSpecDb {
+    pub hostile_below: i32,
+    pub friendly_at: i32,
+    pub min_rep: i32,
+    pub max_rep: i32,
+    pub factions: HashMap<String, FactionSpec>,
+}
+
+fn data_root() -> std::path::PathBuf {
+    let here = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let ws = here.join("../../data");
+    if ws.is_dir() { ws } else { here.join("data") }
+}
+
+impl FactionSpecDb {
+    pub fn load_default() -> Result<Self> {
+        let path = data_root().join("config/factions.toml");
+        if path.is_file() {
+            let txt = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            Self::parse(&txt)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Parse and validate a faction registry.
+    pub fn parse(txt: &str) -> Result<Self> {
+        let db: Self = toml::from_str(txt).context("parse factions TOML")?;
+        db.validate()?;
+        Ok(db)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.min_rep > self.max_rep {
+            bail!("factions: min_rep exceeds max_rep");
+        }
+        if !(self.min_rep <= self.hostile_below
+            && self.hostile_below < self.friendly_at
+            && self.friendly_at <= self.max_rep)
+        {
+            bail!("factions: need min_rep <= hostile_below < friendly_at <= max_rep");
+        }
+        if !self.factions.contains_key(PLAYER_FACTION) {
+            bail!("factions: missing the '{PLAYER_FACTION}' faction");
+        }
+        let mut seen: HashMap<u8, &str> = HashMap::new();
+        for (id, f) in &self.factions {
+            if let Some(other) = seen.insert(f.id, id) {
+                bail!("factions '{other}' and '{id}' share id {}", f.id);
+            }
+            if !(self.min_rep..=self.max_rep).contains(&f.standing) {
+                bail!("faction '{id}': standing outside min_rep..=max_rep");
+            }
+            for other in f.hostile.iter().chain(f.kill_spillover.keys()) {
+                if !self.factions.contains_key(other) {
+                    bail!("faction '{id}' names unknown faction '{other}'");
+                }
+                if other == PLAYER_FACTION {
+                    bail!("faction '{id}': players relate through reputation, not '{other}'");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&FactionSpec> {
+        self.factions.get(id)
+    }
+}
@@ -0,0 +1,184 @@
+//! Item specifications (`data/items/*.json`, one item per file).
+//!
+//! Weapons occupy the main hand and name a weapon in
+//! `data/config/weapons.toml`; while worn their `attack_bonus` adds to attacks
+//! with that weapon. Armor is worn in its `slot` and adds `ac_bonus` to the
+//! wearer's Armor Class. Consumables are used up one at a time and may `heal`
+//! (an `NdM+K` expression), restore `mana` and/or apply a `status` from
+//! `data/config/statuses.toml` to the user. Only consumables stack
+//! (`max_stack`).
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Item ids travel in client commands, which cap them at this many bytes.
+pub const MAX_ITEM_ID_BYTES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Weapon,
+    Armor,
+    Consumable,
+}
+
+/// Where a worn item goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipSlot {
+    MainHand,
+    OffHand,
+    Body,
+}
+
+impl EquipSlot {
+    pub const ALL: [Self; 3] = [Self::MainHand, Self::OffHand, Self::Body];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MainHand => "main_hand",
+            Self::OffHand => "off_hand",
+            Self::Body => "body",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|slot| slot.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemSpec {
+    pub id: String,
+    pub name: String,
+    pub kind: ItemKind,
+    #[serde(default = "one")]
+    pub max_stack: u32,
+    /// Weapon: id in `WeaponSpecDb`.
+    #[serde(default)]
+    pub weapon: String,
+    /// Weapon: added to attack rolls with it while worn.
+    #[serde(default)]
+    pub attack_bonus: i32,
+    /// Armor: where it is worn.
+    #[serde(default)]
+    pub slot: Option<EquipSlot>,
+    /// Armor: added to Armor Class while worn.
+    #[serde(default)]
+    pub ac_bonus: i32,
+    /// Consumable: hit points restored, e.g. "2d4+2".
+    #[serde(default)]
+    pub heal: String,
+    /// Consumable: mana restored.
+    #[serde(default)]
+    pub mana: i32,
+    /// Consumable: status applied to the user.
+    #[serde(default)]
+    pub status: String,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl ItemSpec {
+    /// Parse and validate one item file.
+    pub fn parse(txt: &str) -> Result<Self> {
+        let spec: Self = serde_json::from_str(txt).context("parse item JSON")?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let id = &self.id;
+        if id.is_empty() || id.len() > MAX_ITEM_ID_BYTES {
+            bail!("item '{id}': id must be 1..={MAX_ITEM_ID_BYTES} bytes");
+        }
+        if self.max_stack == 0 {
+            bail!("item '{id}': max_stack must be at least 1");
+        }
+        match self.kind {
+            ItemKind::Weapon if self.weapon.is_empty() => {
+                bail!("item '{id}': weapons name a `weapon`")
+            }
+            ItemKind::Weapon if self.slot.is_some_and(|s| s != EquipSlot::MainHand) => {
+                bail!("item '{id}': weapons go in the main hand")
+            }
+            ItemKind::Armor if self.slot.is_none() => bail!("item '{id}': armor needs a slot"),
+            ItemKind::Consumable
+                if self.heal.is_empty() && self.mana <= 0 && self.status.is_empty() =>
+            {
+                bail!("item '{id}': consumables need heal, mana or status")
+            }
+            ItemKind::Consumable if !self.heal.is_empty() && !self.heal.contains('d') => {
+                bail!("item '{id}': heal '{}' is not NdM[+K]", self.heal)
+            }
+            ItemKind::Weapon | ItemKind::Armor if self.max_stack != 1 => {
+                bail!("item '{id}': worn items don't stack")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Where the item is worn; `None` for consumables.
+    pub fn equip_slot(&self) -> Option<EquipSlot> {
+        match self.kind {
+            ItemKind::Weapon => Some(EquipSlot::MainHand),
+            ItemKind::Armor => self.slot,
+            ItemKind::Consumable => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ItemSpecDb {
+    pub items: HashMap<String, ItemSpec>,
+}
+
+fn data_root() -> std::path::PathBuf {
+    let here = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let ws = here.join("../../data");
+    if ws.is_dir() { ws } else { here.join("data") }
+}
+
+impl ItemSpecDb {
+    pub fn load_default() -> Result<Self> {
+        let dir = data_root().join("items");
+        if dir.is_dir() {
+            Self::load_dir(&dir)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Load every `*.json` in `dir`; any invalid file fails the load.
+    pub fn load_dir(dir: &std::path::Path) -> Result<Self> {
+        let mut db = Self::default();
+        let rd = std::fs::read_dir(dir).with_context(|| format!("read {}", dir.display()))?;
+        for ent in rd {
+            let path = ent?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            let txt = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            let spec = ItemSpec::parse(&txt).with_context(|| format!("load {}", path.display()))?;
+            db.insert(spec)?;
+        }
+        Ok(db)
+    }
+
+    /// Add `spec`; ids must be unique.
+    pub fn insert(&mut self, spec: ItemSpec) -> Result<()> {
+        if self.items.contains_key(&spec.id) {
+            bail!("item '{}' is defined twice", spec.id);
+        }
+        self.items.insert(spec.id.clone(), spec);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ItemSpec> {
+        self.items.get(id)
+    }
+}
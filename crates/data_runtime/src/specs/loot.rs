@@ -0,0 +1,139 @@
+//! Loot tables (`data/config/loot.toml`), keyed by archetype id.
+//!
+//! When an actor dies, every player who fought it rolls its archetype's table
+//! separately: `rolls` draws, each picking one entry with probability
+//! `weight / total weight`. An entry without an `item` drops nothing; one with
+//! an item drops `min..=max` units of it. Rolling itself is the server's job
+//! (it owns the RNG); [`LootTable::pick`] maps a draw to an entry.
+//! [`LootSpecDb::check_refs`] checks table keys and items against the
+//! archetype and item tables.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::archetypes::ArchetypeSpecDb;
+use super::items::ItemSpecDb;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LootEntry {
+    /// Item id in `data/items`; empty drops nothing.
+    #[serde(default)]
+    pub item: String,
+    pub weight: u32,
+    #[serde(default = "one")]
+    pub min: u32,
+    #[serde(default = "one")]
+    pub max: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootTable {
+    /// Draws per contributor.
+    #[serde(default = "one")]
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl LootTable {
+    fn validate(&self, id: &str) -> Result<()> {
+        if self.rolls == 0 {
+            bail!("loot '{id}': rolls must be at least 1");
+        }
+        if self.entries.is_empty() {
+            bail!("loot '{id}': no entries");
+        }
+        for e in &self.entries {
+            if e.weight == 0 {
+                bail!("loot '{id}': entry '{}' has zero weight", e.item);
+            }
+            if e.min == 0 || e.max < e.min {
+                bail!("loot '{id}': entry '{}' needs 1 <= min <= max", e.item);
+            }
+        }
+        if self
+            .entries
+            .iter()
+            .map(|e| u64::from(e.weight))
+            .sum::<u64>()
+            > u64::from(u32::MAX)
+        {
+            bail!("loot '{id}': total weight overflows");
+        }
+        Ok(())
+    }
+
+    /// Sum of all entry weights; draws are taken from `0..total_weight()`.
+    pub fn total_weight(&self) -> u32 {
+        self.entries.iter().map(|e| e.weight).sum()
+    }
+
+    /// The entry a draw of `r` (in `0..total_weight()`) lands on.
+    pub fn pick(&self, r: u32) -> Option<&LootEntry> {
+        let mut acc = 0u32;
+        self.entries.iter().find(|e| {
+            acc += e.weight;
+            r < acc
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LootSpecDb {
+    pub tables: HashMap<String, LootTable>,
+}
+
+fn data_root() -> std::path::PathBuf {
+    let here = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let ws = here.join("../../data");
+    if ws.is_dir() { ws } else { here.join("data") }
+}
+
+impl LootSpecDb {
+    pub fn load_default() -> Result<Self> {
+        let path = data_root().join("config/loot.toml");
+        if path.is_file() {
+            let txt = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            Self::parse(&txt)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Parse and validate a loot table file.
+    pub fn parse(txt: &str) -> Result<Self> {
+        let db: Self = toml::from_str(txt).context("parse loot TOML")?;
+        for (id, t) in &db.tables {
+            t.validate(id)?;
+        }
+        Ok(db)
+    }
+
+    /// Check that every table is keyed by an archetype in `archetypes` and
+    /// drops only items in `items`.
+    pub fn check_refs(&self, items: &ItemSpecDb, archetypes: &ArchetypeSpecDb) -> Result<()> {
+        for (id, t) in &self.tables {
+            if !archetypes.entries.contains_key(id) {
+                bail!("loot '{id}': no such archetype");
+            }
+            if let Some(e) = t
+                .entries
+                .iter()
+                .find(|e| !e.item.is_empty() && items.get(&e.item).is_none())
+            {
+                bail!("loot '{id}': unknown item '{}'", e.item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Table for archetype `id`, if it drops anything.
+    pub fn get(&self, id: &str) -> Option<&LootTable> {
+        self.tables.get(id)
+    }
+}
@@ -0,0 +1,148 @@
+//! Status effect specifications (`data/config/statuses.toml`).
+//!
+//! A status lasts `duration_s` (callers may override it per application) and
+//! may tick `tick_damage` every `tick_s`, scale movement speed (`speed_mul`)
+//! and outgoing damage (`damage_mul`), add to Armor Class (`ac_bonus`), and
+//! impose SRD `conditions`. Magnitudes apply once per stack. `stacking`
+//! decides what a reapplication does; targets immune to any of the status's
+//! conditions are unaffected. `net_id` identifies the status on the wire.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusKind {
+    Buff,
+    #[default]
+    Debuff,
+}
+
+/// What applying a status that is already active does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stacking {
+    /// Keep one stack; the remaining time becomes the longer of the two.
+    #[default]
+    Refresh,
+    /// Add a stack (up to `max_stacks`) and refresh the duration.
+    Stack,
+    /// Add the new duration to the remaining time, capped at
+    /// `duration_s * max_stacks`.
+    Extend,
+    /// Keep the active instance untouched.
+    Ignore,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatusSpec {
+    /// Replicated id; unique and non-zero.
+    pub net_id: u16,
+    #[serde(default)]
+    pub kind: StatusKind,
+    /// Short HUD label.
+    #[serde(default)]
+    pub icon: String,
+    pub duration_s: f32,
+    #[serde(default)]
+    pub stacking: Stacking,
+    #[serde(default = "one")]
+    pub max_stacks: u8,
+    /// Seconds between ticks; 0 disables ticking.
+    #[serde(default)]
+    pub tick_s: f32,
+    /// Damage per tick per stack.
+    #[serde(default)]
+    pub tick_damage: i32,
+    /// SRD damage type of the ticks; empty means untyped.
+    #[serde(default)]
+    pub damage_type: String,
+    #[serde(default = "unit")]
+    pub speed_mul: f32,
+    #[serde(default = "unit")]
+    pub damage_mul: f32,
+    #[serde(default)]
+    pub ac_bonus: i32,
+    /// SRD conditions imposed while active (e.g. "stunned").
+    #[serde(default)]
+    pub conditions: Vec<String>,
+}
+
+fn one() -> u8 {
+    1
+}
+
+fn unit() -> f32 {
+    1.0
+}
+
+impl StatusSpec {
+    fn validate(&self, id: &str) -> Result<()> {
+        if self.net_id == 0 {
+            bail!("status '{id}': net_id must be non-zero");
+        }
+        if self.duration_s <= 0.0 {
+            bail!("status '{id}': duration_s must be positive");
+        }
+        if self.max_stacks == 0 {
+            bail!("status '{id}': max_stacks must be at least 1");
+        }
+        if self.tick_s < 0.0 || (self.tick_damage != 0 && self.tick_s == 0.0) {
+            bail!("status '{id}': tick_damage needs a positive tick_s");
+        }
+        if !(0.0..=10.0).contains(&self.speed_mul) || !(0.0..=10.0).contains(&self.damage_mul) {
+            bail!("status '{id}': speed_mul and damage_mul must be within 0..=10");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StatusSpecDb {
+    pub statuses: HashMap<String, StatusSpec>,
+}
+
+fn data_root() -> std::path::PathBuf {
+    let here = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let ws = here.join("../../data");
+    if ws.is_dir() { ws } else { here.join("data") }
+}
+
+impl StatusSpecDb {
+    pub fn load_default() -> Result<Self> {
+        let path = data_root().join("config/statuses.toml");
+        if path.is_file() {
+            let txt = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            Self::parse(&txt)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Parse and validate a statuses table.
+    pub fn parse(txt: &str) -> Result<Self> {
+        let db: Self = toml::from_str(txt).context("parse statuses TOML")?;
+        let mut seen: HashMap<u16, &str> = HashMap::new();
+        for (id, s) in &db.statuses {
+            s.validate(id)?;
+            if let Some(other) = seen.insert(s.net_id, id) {
+                bail!("statuses '{other}' and '{id}' share net_id {}", s.net_id);
+            }
+        }
+        Ok(db)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&StatusSpec> {
+        self.statuses.get(id)
+    }
+
+    /// Look up a status by its replicated id.
+    pub fn by_net_id(&self, net_id: u16) -> Option<(&str, &StatusSpec)> {
+        self.statuses
+            .iter()
+            .find(|(_, s)| s.net_id == net_id)
+            .map(|(k, s)| (k.as_str(), s))
+    }
+}
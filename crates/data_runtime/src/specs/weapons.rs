@@ -0,0 +1,119 @@
+//! Weapon specifications (`data/config/weapons.toml`).
+//!
+//! Melee weapons hit the first hostile inside a forward arc of `arc_deg` out
+//! to `reach_m`; ranged weapons fire at an actor or along the aim direction
+//! out to `long_range_m`, with disadvantage past `range_m`. Attacks roll
+//! `d20 + attack_bonus` against the target's Armor Class; `damage` is an
+//! `NdM+K` expression and `crit` names a `sim_core::rules::dice::CritRule`.
+//! The server parses both when it loads the table and rejects it if either
+//! doesn't parse (`server_core::systems::weapons::check_weapons`).
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeaponKind {
+    #[default]
+    Melee,
+    Ranged,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WeaponSpec {
+    #[serde(default)]
+    pub kind: WeaponKind,
+    /// Damage dice, e.g. "1d8".
+    pub damage: String,
+    /// SRD damage type (e.g., "piercing"); empty means untyped.
+    #[serde(default)]
+    pub damage_type: String,
+    #[serde(default)]
+    pub attack_bonus: i32,
+    /// Seconds between attacks.
+    pub cooldown_s: f32,
+    /// Melee: reach past both bodies' radii.
+    #[serde(default)]
+    pub reach_m: f32,
+    /// Melee: full width of the swing arc.
+    #[serde(default)]
+    pub arc_deg: f32,
+    /// Ranged: normal range; beyond it attacks roll with disadvantage.
+    #[serde(default)]
+    pub range_m: f32,
+    /// Ranged: maximum range.
+    #[serde(default)]
+    pub long_range_m: f32,
+    #[serde(default = "default_crit")]
+    pub crit: String,
+}
+
+fn default_crit() -> String {
+    "nat20_double_dice".into()
+}
+
+impl WeaponSpec {
+    fn validate(&self, id: &str) -> Result<()> {
+        if !self.damage.contains('d') {
+            bail!("weapon '{id}': damage '{}' is not NdM[+K]", self.damage);
+        }
+        if self.cooldown_s <= 0.0 {
+            bail!("weapon '{id}': cooldown_s must be positive");
+        }
+        match self.kind {
+            WeaponKind::Melee if self.reach_m <= 0.0 || self.arc_deg <= 0.0 => {
+                bail!("weapon '{id}': melee needs reach_m and arc_deg")
+            }
+            WeaponKind::Ranged if self.range_m <= 0.0 || self.long_range_m < self.range_m => {
+                bail!("weapon '{id}': ranged needs 0 < range_m <= long_range_m")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Furthest an attack can land.
+    pub fn max_range_m(&self) -> f32 {
+        match self.kind {
+            WeaponKind::Melee => self.reach_m,
+            WeaponKind::Ranged => self.long_range_m,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WeaponSpecDb {
+    pub weapons: HashMap<String, WeaponSpec>,
+}
+
+fn data_root() -> std::path::PathBuf {
+    let here = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let ws = here.join("../../data");
+    if ws.is_dir() { ws } else { here.join("data") }
+}
+
+impl WeaponSpecDb {
+    pub fn load_default() -> Result<Self> {
+        let path = data_root().join("config/weapons.toml");
+        if path.is_file() {
+            let txt = std::fs::read_to_string(&path)
+                .with_context(|| format!("read {}", path.display()))?;
+            Self::parse(&txt)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Parse and validate a weapons table.
+    pub fn parse(txt: &str) -> Result<Self> {
+        let db: Self = toml::from_str(txt).context("parse weapons TOML")?;
+        for (id, w) in &db.weapons {
+            w.validate(id)?;
+        }
+        Ok(db)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&WeaponSpec> {
+        self.weapons.get(id)
+    }
+}
@@ -111,6 +111,9 @@ pub struct SaveSpec {
     pub dc: Option<i32>, // if None, use caster's spell_save_dc
     #[serde(default)]
     pub on_fail: Option<OnFail>,
+    /// A successful save takes half damage instead of none.
+    #[serde(default)]
+    pub half_on_success: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
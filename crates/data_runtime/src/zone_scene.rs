@@ -25,6 +25,60 @@ pub struct Logic {
     pub links: Vec<serde_json::Value>,
 }
 
+/// A placed marker in `logic.spawns` (trees, NPC spawn points, ...).
+///
+/// `radius_m` spreads a multi-actor spawn on a ring around `pos`; markers
+/// without it place every actor at `pos`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpawnMarker {
+    pub id: String,
+    pub kind: String,
+    pub pos: [f32; 3],
+    #[serde(default)]
+    pub yaw_deg: f32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub radius_m: f32,
+}
+
+/// A named point in `logic.waypoints`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waypoint {
+    pub id: String,
+    pub pos: [f32; 3],
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Logic {
+    /// Typed spawn markers; entries that do not parse are skipped.
+    pub fn spawn_markers(&self) -> Vec<SpawnMarker> {
+        parse_entries(&self.spawns)
+    }
+
+    /// Typed waypoints; entries that do not parse are skipped.
+    pub fn waypoint_markers(&self) -> Vec<Waypoint> {
+        parse_entries(&self.waypoints)
+    }
+}
+
+fn parse_entries<T: serde::de::DeserializeOwned>(v: &[serde_json::Value]) -> Vec<T> {
+    v.iter()
+        .filter_map(|e| serde_json::from_value(e.clone()).ok())
+        .collect()
+}
+
+/// Load `data/zones/<slug>/scene.json`, or `None` when the zone has no scene.
+pub fn load_zone_scene(slug: &str) -> Result<Option<ZoneScene>> {
+    let rel = format!("zones/{slug}/scene.json");
+    let Ok(txt) = crate::loader::read_json(&rel) else {
+        return Ok(None);
+    };
+    let scene = serde_json::from_str(&txt).with_context(|| format!("parse {rel}"))?;
+    Ok(Some(scene))
+}
+
 /// Validate a scene JSON string by attempting to deserialize into `ZoneScene`.
 pub fn validate_scene_against_schema(txt: &str) -> Result<()> {
     let _: ZoneScene = serde_json::from_str(txt).context("parse scene json")?;
@@ -8,3 +8,19 @@ fn archetypes_defaults_present() {
     let dk = db.entries.get("DeathKnight").expect("dk");
     assert!(dk.melee_damage >= 1 && dk.melee_cooldown_s > 0.0);
 }
+
+#[test]
+fn data_file_matches_builtin_defaults() {
+    let db = ArchetypeSpecDb::load_default().expect("load");
+    let builtin = ArchetypeSpecDb::builtin();
+    let mut keys: Vec<_> = db.entries.keys().collect();
+    keys.sort();
+    let mut want: Vec<_> = builtin.entries.keys().collect();
+    want.sort();
+    assert_eq!(keys, want);
+    for (k, b) in &builtin.entries {
+        let d = &db.entries[k];
+        assert_eq!(format!("{d:?}"), format!("{b:?}"), "{k}");
+        assert_eq!(db.by_net_id(b.net_id).map(|(id, _)| id), Some(k.as_str()));
+    }
+}
@@ -0,0 +1,57 @@
+use data_runtime::specs::archetypes::ArchetypeSpecDb;
+use data_runtime::specs::boss_scripts::{BossScriptDb, TelegraphAnchor, TelegraphShape};
+
+#[test]
+fn nivita_script_loads_and_is_referenced() {
+    let db = BossScriptDb::load_default().expect("load");
+    let s = db.get("nivita").expect("nivita script");
+    assert!(s.phases.len() >= 2);
+    assert_eq!(s.phases[0].hp_pct, 100.0);
+    assert!(s.enrage_s > 0.0);
+    let flay = &s.abilities["soul_flay"];
+    assert_eq!(flay.shape, TelegraphShape::Cone);
+    assert_eq!(flay.at, TelegraphAnchor::Caster);
+    let arche = ArchetypeSpecDb::load_default().expect("archetypes");
+    for spec in arche.entries.values() {
+        if let Some(id) = &spec.script {
+            assert!(
+                db.get(id).is_some(),
+                "archetype references missing script {id}"
+            );
+        }
+    }
+}
+
+#[test]
+fn phase_for_follows_thresholds() {
+    let db = BossScriptDb::load_default().expect("load");
+    let s = db.get("nivita").expect("nivita script");
+    assert_eq!(s.phase_for(100.0), 0);
+    let second = s.phases[1].hp_pct;
+    assert_eq!(s.phase_for(second + 0.1), 0);
+    assert_eq!(s.phase_for(second), 1);
+    assert_eq!(s.phase_for(0.0), s.phases.len() - 1);
+}
+
+#[test]
+fn invalid_scripts_are_rejected() {
+    let ok = r#"
+        id = "t"
+        [abilities.slam]
+        radius_m = 3.0
+        windup_s = 1.0
+        damage = 5
+        [[phases]]
+        name = "one"
+        hp_pct = 100.0
+        rotation = ["slam"]
+        interval_s = 2.0
+    "#;
+    assert!(BossScriptDb::parse(ok).is_ok());
+    let unknown = ok.replace(r#"rotation = ["slam"]"#, r#"rotation = ["slma"]"#);
+    assert!(BossScriptDb::parse(&unknown).is_err());
+    let late_start = ok.replace("hp_pct = 100.0", "hp_pct = 80.0");
+    assert!(BossScriptDb::parse(&late_start).is_err());
+    let no_cond = format!("{ok}\n[[triggers]]\ncast = \"slam\"\n");
+    assert!(BossScriptDb::parse(&no_cond).is_err());
+}
@@ -0,0 +1,47 @@
+use data_runtime::specs::factions::{FactionSpecDb, PLAYER_FACTION};
+
+#[test]
+fn factions_load_with_bands_and_relations() {
+    let db = FactionSpecDb::load_default().expect("load");
+    assert!(db.min_rep <= db.hostile_below && db.friendly_at <= db.max_rep);
+    assert_eq!(db.get(PLAYER_FACTION).expect("pc").id, 0);
+    let undead = db.get("undead").expect("undead");
+    assert!(undead.standing < db.hostile_below, "players start hostile");
+    assert!(undead.hostile.iter().any(|f| f == "wizards"));
+    assert!(undead.kill_spillover["wizards"] > 0);
+    let wizards = db.get("wizards").expect("wizards");
+    assert!(wizards.hit_rep + wizards.standing < db.hostile_below);
+    assert!((db.hostile_below..db.friendly_at).contains(&wizards.standing));
+    assert!(db.factions.len() > 4, "more than the original four");
+}
+
+#[test]
+fn invalid_factions_are_rejected() {
+    let ok = r#"
+        hostile_below = -100
+        friendly_at = 100
+        min_rep = -500
+        max_rep = 500
+
+        [factions.pc]
+        id = 0
+
+        [factions.goblins]
+        id = 1
+        standing = -200
+        hostile = ["elves"]
+
+        [factions.elves]
+        id = 2
+        kill_spillover = { goblins = 50 }
+    "#;
+    let db = FactionSpecDb::parse(ok).expect("parse");
+    assert_eq!(db.get("goblins").expect("goblins").standing, -200);
+    assert_eq!(db.get("elves").expect("elves").name, "");
+    assert!(FactionSpecDb::parse(&ok.replace("id = 2", "id = 1")).is_err());
+    assert!(FactionSpecDb::parse(&ok.replace("friendly_at = 100", "friendly_at = -100")).is_err());
+    assert!(FactionSpecDb::parse(&ok.replace("standing = -200", "standing = -900")).is_err());
+    assert!(FactionSpecDb::parse(&ok.replace("[\"elves\"]", "[\"orcs\"]")).is_err());
+    assert!(FactionSpecDb::parse(&ok.replace("goblins = 50", "pc = 50")).is_err());
+    assert!(FactionSpecDb::parse(&ok.replace("[factions.pc]", "[factions.players]")).is_err());
+}
@@ -0,0 +1,60 @@
+use data_runtime::specs::items::{EquipSlot, ItemKind, ItemSpec, ItemSpecDb};
+use data_runtime::specs::statuses::StatusSpecDb;
+use data_runtime::specs::weapons::WeaponSpecDb;
+
+#[test]
+fn items_load_and_reference_known_weapons_and_statuses() {
+    let db = ItemSpecDb::load_default().expect("load");
+    let potion = db.get("potion_healing").expect("potion");
+    assert_eq!(potion.kind, ItemKind::Consumable);
+    assert!(potion.max_stack > 1 && potion.heal.contains('d'));
+    assert_eq!(potion.equip_slot(), None);
+    let shield = db.get("shield").expect("shield");
+    assert_eq!(shield.equip_slot(), Some(EquipSlot::OffHand));
+    assert!(shield.ac_bonus > 0);
+    let staff = db.get("quarterstaff_plus_one").expect("+1 staff");
+    assert_eq!(staff.equip_slot(), Some(EquipSlot::MainHand));
+    assert_eq!(
+        (staff.weapon.as_str(), staff.attack_bonus),
+        ("quarterstaff", 1)
+    );
+
+    let weapons = WeaponSpecDb::load_default().expect("weapons");
+    let statuses = StatusSpecDb::load_default().expect("statuses");
+    for (id, item) in &db.items {
+        if item.kind == ItemKind::Weapon {
+            assert!(weapons.get(&item.weapon).is_some(), "{id}: {}", item.weapon);
+        }
+        if !item.status.is_empty() {
+            assert!(
+                statuses.get(&item.status).is_some(),
+                "{id}: {}",
+                item.status
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_items_are_rejected() {
+    let ok = r#"{ "id": "torch", "name": "Torch", "kind": "weapon", "weapon": "club" }"#;
+    let torch = ItemSpec::parse(ok).expect("parse");
+    assert_eq!(torch.max_stack, 1);
+    assert!(ItemSpec::parse(&ok.replace(r#", "weapon": "club""#, "")).is_err());
+    assert!(ItemSpec::parse(&ok.replace("}", r#", "max_stack": 5 }"#)).is_err());
+    assert!(ItemSpec::parse(&ok.replace("}", r#", "slot": "body" }"#)).is_err());
+    assert!(ItemSpec::parse(&ok.replace("torch", &"t".repeat(65))).is_err());
+    let armor = r#"{ "id": "cloak", "name": "Cloak", "kind": "armor", "ac_bonus": 1 }"#;
+    assert!(ItemSpec::parse(armor).is_err(), "armor without a slot");
+    let drink = r#"{ "id": "water", "name": "Water", "kind": "consumable" }"#;
+    assert!(
+        ItemSpec::parse(drink).is_err(),
+        "consumable that does nothing"
+    );
+    assert!(ItemSpec::parse(&drink.replace("}", r#", "heal": "lots" }"#)).is_err());
+    assert!(ItemSpec::parse(&drink.replace("}", r#", "mana": 5 }"#)).is_ok());
+
+    let mut db = ItemSpecDb::default();
+    db.insert(torch.clone()).expect("insert");
+    assert!(db.insert(torch).is_err(), "duplicate id");
+}
@@ -0,0 +1,61 @@
+use data_runtime::specs::archetypes::ArchetypeSpecDb;
+use data_runtime::specs::items::ItemSpecDb;
+use data_runtime::specs::loot::LootSpecDb;
+
+#[test]
+fn loot_tables_reference_known_archetypes_and_items() {
+    let db = LootSpecDb::load_default().expect("load");
+    let arche = ArchetypeSpecDb::load_default().expect("archetypes");
+    let items = ItemSpecDb::load_default().expect("items");
+    assert!(db.get("Undead").is_some());
+    assert!(db.get("PC").is_none(), "players drop nothing");
+    db.check_refs(&items, &arche).expect("references");
+
+    let bad_item = LootSpecDb::parse(
+        r#"
+        [tables.Undead]
+        entries = [{ weight = 1 }, { item = "no_such_item", weight = 1 }]
+        "#,
+    )
+    .expect("parse");
+    let err = bad_item.check_refs(&items, &arche).unwrap_err().to_string();
+    assert!(err.contains("no_such_item"), "{err}");
+    let bad_key = LootSpecDb::parse("[tables.Rat]\nentries = [{ weight = 1 }]\n").expect("parse");
+    let err = bad_key.check_refs(&items, &arche).unwrap_err().to_string();
+    assert!(err.contains("Rat"), "{err}");
+}
+
+#[test]
+fn draws_map_to_entries_by_weight() {
+    let db = LootSpecDb::parse(
+        r#"
+        [tables.Rat]
+        entries = [{ weight = 3 }, { item = "tail", weight = 1, max = 2 }]
+        "#,
+    )
+    .expect("parse");
+    let t = db.get("Rat").expect("rat");
+    assert_eq!((t.rolls, t.total_weight()), (1, 4));
+    let picked: Vec<&str> = (0..4)
+        .map(|r| t.pick(r).expect("entry").item.as_str())
+        .collect();
+    assert_eq!(picked, ["", "", "", "tail"]);
+    assert!(t.pick(4).is_none());
+    assert_eq!((t.entries[1].min, t.entries[1].max), (1, 2));
+}
+
+#[test]
+fn invalid_tables_are_rejected() {
+    let ok = r#"
+        [tables.Rat]
+        rolls = 1
+        entries = [{ item = "tail", weight = 1, min = 1, max = 2 }]
+    "#;
+    assert!(LootSpecDb::parse(ok).is_ok());
+    assert!(LootSpecDb::parse(&ok.replace("rolls = 1", "rolls = 0")).is_err());
+    assert!(LootSpecDb::parse(&ok.replace("weight = 1", "weight = 0")).is_err());
+    assert!(LootSpecDb::parse(&ok.replace("min = 1", "min = 0")).is_err());
+    assert!(LootSpecDb::parse(&ok.replace("max = 2", "max = 0")).is_err());
+    let empty = "[tables.Rat]\nentries = []\n";
+    assert!(LootSpecDb::parse(empty).is_err());
+}
@@ -0,0 +1,42 @@
+use data_runtime::specs::archetypes::ArchetypeSpecDb;
+use data_runtime::specs::statuses::{Stacking, StatusKind, StatusSpecDb};
+
+#[test]
+fn statuses_load_and_keep_net_ids_unique() {
+    let db = StatusSpecDb::load_default().expect("load");
+    let burning = db.get("burning").expect("burning");
+    assert!(burning.tick_s > 0.0 && burning.tick_damage > 0);
+    assert_eq!(burning.damage_type, "fire");
+    assert!(db.get("slowed").expect("slowed").speed_mul < 1.0);
+    assert_eq!(db.get("stunned").expect("stunned").conditions, ["stunned"]);
+    let poisoned = db.get("poisoned").expect("poisoned");
+    assert_eq!(poisoned.stacking, Stacking::Stack);
+    assert!(poisoned.max_stacks > 1);
+    assert_eq!(db.get("shielded").expect("shielded").kind, StatusKind::Buff);
+    let (name, _) = db.by_net_id(burning.net_id).expect("by net id");
+    assert_eq!(name, "burning");
+    let arche = ArchetypeSpecDb::load_default().expect("archetypes");
+    assert_eq!(arche.entries["Undead"].immune, ["poisoned"]);
+}
+
+#[test]
+fn invalid_statuses_are_rejected() {
+    let ok = r#"
+        [statuses.bleeding]
+        net_id = 9
+        duration_s = 4.0
+        tick_s = 1.0
+        tick_damage = 2
+    "#;
+    let db = StatusSpecDb::parse(ok).expect("parse");
+    let s = db.get("bleeding").expect("bleeding");
+    assert_eq!(s.stacking, Stacking::Refresh);
+    assert_eq!(s.max_stacks, 1);
+    assert!((s.speed_mul - 1.0).abs() < f32::EPSILON);
+    assert!(StatusSpecDb::parse(&ok.replace("net_id = 9", "net_id = 0")).is_err());
+    assert!(StatusSpecDb::parse(&ok.replace("duration_s = 4.0", "duration_s = 0.0")).is_err());
+    assert!(StatusSpecDb::parse(&ok.replace("tick_s = 1.0", "")).is_err());
+    assert!(StatusSpecDb::parse(&ok.replace("tick_damage = 2", "max_stacks = 0")).is_err());
+    let dup = format!("{ok}\n[statuses.gashed]\nnet_id = 9\nduration_s = 1.0\n");
+    assert!(StatusSpecDb::parse(&dup).is_err());
+}
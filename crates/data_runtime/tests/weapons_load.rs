@@ -0,0 +1,44 @@
+use data_runtime::specs::archetypes::ArchetypeSpecDb;
+use data_runtime::specs::weapons::{WeaponKind, WeaponSpecDb};
+
+#[test]
+fn weapons_load_and_archetypes_reference_them() {
+    let db = WeaponSpecDb::load_default().expect("load");
+    let staff = db.get("quarterstaff").expect("quarterstaff");
+    assert_eq!(staff.kind, WeaponKind::Melee);
+    assert!(staff.reach_m > 0.0 && staff.arc_deg > 0.0);
+    let bow = db.get("shortbow").expect("shortbow");
+    assert_eq!(bow.kind, WeaponKind::Ranged);
+    assert!(bow.long_range_m > bow.range_m);
+    let arche = ArchetypeSpecDb::load_default().expect("archetypes");
+    assert!(!arche.entries["PC"].weapons.is_empty());
+    for (k, spec) in &arche.entries {
+        assert!(spec.ac >= 1, "{k} has no AC");
+        for w in &spec.weapons {
+            assert!(db.get(w).is_some(), "{k} lists unknown weapon {w}");
+        }
+    }
+}
+
+#[test]
+fn invalid_weapons_are_rejected() {
+    let ok = r#"
+        [weapons.club]
+        damage = "1d4"
+        cooldown_s = 1.0
+        reach_m = 1.0
+        arc_deg = 90.0
+    "#;
+    assert!(WeaponSpecDb::parse(ok).is_ok());
+    assert!(WeaponSpecDb::parse(&ok.replace("1d4", "four")).is_err());
+    assert!(WeaponSpecDb::parse(&ok.replace("reach_m = 1.0", "")).is_err());
+    let ranged = r#"
+        [weapons.sling]
+        kind = "ranged"
+        damage = "1d4"
+        cooldown_s = 1.0
+        range_m = 30.0
+        long_range_m = 10.0
+    "#;
+    assert!(WeaponSpecDb::parse(ranged).is_err());
+}
@@ -0,0 +1,397 @@
+//! Baseline-acked delta compression for `ActorSnapshotDelta`.
+//!
+//! Scope
+//! - The server keeps a short history of what it sent to each client
+//!   (`BaselineTracker`). The client acks the newest tick it applied, and the
+//!   next delta is computed against that acked state instead of the previous
+//!   send, so lost packets are repaired by the next delta that gets through.
+//! - The client keeps a matching history (`BaselineReceiver`) and rebuilds the
+//!   full actor set from whichever baseline a delta names.
+//!
+//! Baselines
+//! - `delta.baseline == NO_BASELINE` marks a keyframe: apply to an empty set.
+//! - A delta on a baseline the receiver no longer (or never) had is dropped
+//!   and not acked. A sender that never sees an ack keeps sending keyframes,
+//!   so every stream, the in-proc demo's included, starts from one.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::snapshot::{
+    ACTOR_SNAP_DELTA_VERSION, ActorDeltaRec, ActorRep, ActorSnapshotDelta, HitFx, ProjectileRep,
+    dqpos, dqyaw, qpos, qyaw,
+};
+
+/// Baseline value for keyframe deltas (no acked state to diff against).
+pub const NO_BASELINE: u64 = u64::MAX;
+/// Ticks of history kept on each side (~2 s at 30 Hz).
+pub const HISTORY_TICKS: usize = 64;
+
+/// Actor state keyed by id; ordered so encoded deltas are deterministic.
+pub type ActorSet = BTreeMap<u32, ActorRep>;
+
+/// Encoded size of one spawn record in an `ActorSnapshotDelta`.
+pub const SPAWN_REC_BYTES: usize = 41;
+
+/// Encoded size of one update record (id + flags + present fields).
+#[must_use]
+pub fn update_rec_bytes(rec: &ActorDeltaRec) -> usize {
+    let mut n = 5;
+    if rec.flags & 1 != 0 {
+        n += 12;
+    }
+    if rec.flags & 2 != 0 {
+        n += 2;
+    }
+    if rec.flags & 4 != 0 {
+        n += 4;
+    }
+    if rec.flags & 8 != 0 {
+        n += 1;
+    }
+    if rec.flags & 16 != 0 {
+        n += 1;
+    }
+    n
+}
+
+/// Estimated bytes to bring a client holding `base` (if any) up to `cur`.
+#[must_use]
+pub fn refresh_bytes(base: Option<&ActorRep>, cur: &ActorRep) -> usize {
+    match base {
+        Some(b) => diff_rep(b, cur).map_or(0, |r| update_rec_bytes(&r)),
+        None => SPAWN_REC_BYTES,
+    }
+}
+
+/// Quantized per-field delta of `cur` against `base`; `None` when unchanged.
+#[must_use]
+pub fn diff_rep(base: &ActorRep, cur: &ActorRep) -> Option<ActorDeltaRec> {
+    let mut rec = ActorDeltaRec {
+        id: cur.id,
+        flags: 0,
+        qpos: [0; 3],
+        qyaw: 0,
+        hp: 0,
+        alive: 0,
+        state: 0,
+    };
+    let q = [qpos(cur.pos[0]), qpos(cur.pos[1]), qpos(cur.pos[2])];
+    if [qpos(base.pos[0]), qpos(base.pos[1]), qpos(base.pos[2])] != q {
+        rec.flags |= 1;
+        rec.qpos = q;
+    }
+    if qyaw(base.yaw) != qyaw(cur.yaw) {
+        rec.flags |= 2;
+        rec.qyaw = qyaw(cur.yaw);
+    }
+    if base.hp != cur.hp {
+        rec.flags |= 4;
+        rec.hp = cur.hp;
+    }
+    if base.alive != cur.alive {
+        rec.flags |= 8;
+        rec.alive = u8::from(cur.alive);
+    }
+    if base.state != cur.state {
+        rec.flags |= 16;
+        rec.state = cur.state;
+    }
+    (rec.flags != 0).then_some(rec)
+}
+
+/// Split `cur` against `base` into (spawns, updates, removals).
+#[must_use]
+pub fn diff_actors(
+    base: &ActorSet,
+    cur: &ActorSet,
+) -> (Vec<ActorRep>, Vec<ActorDeltaRec>, Vec<u32>) {
+    let mut spawns = Vec::new();
+    let mut updates = Vec::new();
+    for (id, a) in cur {
+        match base.get(id) {
+            Some(b) => updates.extend(diff_rep(b, a)),
+            None => spawns.push(a.clone()),
+        }
+    }
+    let removals = base
+        .keys()
+        .filter(|id| !cur.contains_key(id))
+        .copied()
+        .collect();
+    (spawns, updates, removals)
+}
+
+/// Apply the actor part of `d` on top of `base`.
+#[must_use]
+pub fn apply_actors(base: &ActorSet, d: &ActorSnapshotDelta) -> ActorSet {
+    let mut out = base.clone();
+    for id in &d.removals {
+        out.remove(id);
+    }
+    for u in &d.updates {
+        if let Some(a) = out.get_mut(&u.id) {
+            if u.flags & 1 != 0 {
+                a.pos = [dqpos(u.qpos[0]), dqpos(u.qpos[1]), dqpos(u.qpos[2])];
+            }
+            if u.flags & 2 != 0 {
+                a.yaw = dqyaw(u.qyaw);
+            }
+            if u.flags & 4 != 0 {
+                a.hp = u.hp;
+            }
+            if u.flags & 8 != 0 {
+                a.alive = u.alive != 0;
+            }
+            if u.flags & 16 != 0 {
+                a.state = u.state;
+            }
+        }
+    }
+    for a in &d.spawns {
+        out.insert(a.id, a.clone());
+    }
+    out
+}
+
+/// Bounded tick -> state history shared by both ends.
+#[derive(Debug, Default)]
+struct History {
+    ring: VecDeque<(u64, ActorSet)>,
+}
+
+impl History {
+    fn get(&self, tick: u64) -> Option<&ActorSet> {
+        self.ring.iter().find(|(t, _)| *t == tick).map(|(_, s)| s)
+    }
+    fn push(&mut self, tick: u64, set: ActorSet) {
+        self.ring.retain(|(t, _)| *t != tick);
+        self.ring.push_back((tick, set));
+        while self.ring.len() > HISTORY_TICKS {
+            self.ring.pop_front();
+        }
+    }
+    fn latest(&self) -> Option<&(u64, ActorSet)> {
+        self.ring.back()
+    }
+}
+
+/// Server-side per-client baseline state.
+#[derive(Debug, Default)]
+pub struct BaselineTracker {
+    sent: History,
+    acked: Option<u64>,
+}
+
+impl BaselineTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a client ack. Older or unknown ticks are ignored.
+    pub fn ack(&mut self, tick: u64) {
+        if self.acked.is_some_and(|a| tick <= a) {
+            return;
+        }
+        if self.sent.get(tick).is_some() {
+            self.acked = Some(tick);
+        } else {
+            metrics::counter!("replication.ack_ignored_total").increment(1);
+        }
+    }
+
+    /// Newest tick the client confirmed, if it is still usable as a baseline.
+    #[must_use]
+    pub fn acked(&self) -> Option<u64> {
+        self.acked.filter(|t| self.sent.get(*t).is_some())
+    }
+
+    /// Actor set most recently passed to `build` (what the client will hold
+    /// once everything in flight lands).
+    #[must_use]
+    pub fn last_sent(&self) -> Option<&ActorSet> {
+        self.sent.latest().map(|(_, s)| s)
+    }
+
+    /// Build the delta for `tick` against the last acked baseline (or a
+    /// keyframe) and remember `cur` as what the client will have once it applies it.
+    pub fn build(
+        &mut self,
+        tick: u64,
+        cur: ActorSet,
+        projectiles: Vec<ProjectileRep>,
+        hits: Vec<HitFx>,
+    ) -> ActorSnapshotDelta {
+        let empty = ActorSet::new();
+        let (baseline, base) = match self.acked() {
+            Some(t) => (t, self.sent.get(t).unwrap_or(&empty)),
+            None => (NO_BASELINE, &empty),
+        };
+        let (spawns, updates, removals) = diff_actors(base, &cur);
+        self.sent.push(tick, cur);
+        ActorSnapshotDelta {
+            v: ACTOR_SNAP_DELTA_VERSION,
+            tick,
+            baseline,
+            input_seq: 0,
+            spawns,
+            updates,
+            removals,
+            projectiles,
+            hits,
+        }
+    }
+}
+
+/// Client-side reconstruction of full actor state from acked deltas.
+#[derive(Debug, Default)]
+pub struct BaselineReceiver {
+    applied: History,
+}
+
+impl BaselineReceiver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `d` and return the reconstructed actor set, or `None` when the
+    /// delta is older than what was already applied (reordered/stale) or its
+    /// baseline is no longer held. Dropped deltas are not acked, so the
+    /// sender keeps diffing against an older ack or falls back to a keyframe.
+    pub fn apply(&mut self, d: &ActorSnapshotDelta) -> Option<&ActorSet> {
+        if let Some((latest, _)) = self.applied.latest()
+            && d.tick <= *latest
+        {
+            metrics::counter!("replication.dropped_total", "reason" => "stale").increment(1);
+            return None;
+        }
+        let empty = ActorSet::new();
+        let base = if d.baseline == NO_BASELINE {
+            &empty
+        } else if let Some(b) = self.applied.get(d.baseline) {
+            b
+        } else {
+            metrics::counter!("replication.dropped_total", "reason" => "no_baseline").increment(1);
+            return None;
+        };
+        let next = apply_actors(base, d);
+        self.applied.push(d.tick, next);
+        self.applied.latest().map(|(_, s)| s)
+    }
+
+    /// Tick to ack back to the server.
+    #[must_use]
+    pub fn last_applied(&self) -> Option<u64> {
+        self.applied.latest().map(|(t, _)| *t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rep(id: u32, x: f32, hp: i32) -> ActorRep {
+        ActorRep {
+            id,
+            kind: 1,
+            faction: 2,
+            archetype_id: 2,
+            name_id: 0,
+            unique: 0,
+            pos: [x, 0.0, 0.0],
+            yaw: 0.0,
+            radius: 0.5,
+            hp,
+            max: 30,
+            alive: true,
+            state: 0,
+        }
+    }
+
+    fn set(reps: &[ActorRep]) -> ActorSet {
+        reps.iter().map(|a| (a.id, a.clone())).collect()
+    }
+
+    #[test]
+    fn unacked_deltas_stay_keyframes_and_recover_lost_hp() {
+        let mut tx = BaselineTracker::new();
+        let mut rx = BaselineReceiver::new();
+        let d0 = tx.build(0, set(&[rep(1, 0.0, 30)]), vec![], vec![]);
+        assert_eq!(d0.baseline, NO_BASELINE);
+        rx.apply(&d0);
+        tx.ack(0);
+        // Tick 1 (hp change) is lost in transit.
+        let _lost = tx.build(1, set(&[rep(1, 0.0, 20)]), vec![], vec![]);
+        // Tick 2 is still relative to acked tick 0, so it carries the hp change.
+        let d2 = tx.build(2, set(&[rep(1, 1.0, 20)]), vec![], vec![]);
+        assert_eq!(d2.baseline, 0);
+        let got = rx.apply(&d2).expect("applied");
+        assert_eq!(got[&1].hp, 20);
+        assert!((got[&1].pos[0] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn record_sizes_match_encoding() {
+        use crate::snapshot::SnapshotEncode;
+        let a = rep(1, 0.0, 30);
+        let b = ActorRep {
+            yaw: 1.0,
+            hp: 12,
+            state: crate::snapshot::ACTOR_STATE_DODGING,
+            ..rep(1, 3.0, 30)
+        };
+        let rec = diff_rep(&a, &b).expect("changed");
+        let d = |spawns: Vec<ActorRep>, updates: Vec<ActorDeltaRec>| {
+            let mut out = Vec::new();
+            ActorSnapshotDelta {
+                v: 6,
+                tick: 0,
+                baseline: NO_BASELINE,
+                input_seq: 0,
+                spawns,
+                updates,
+                removals: vec![],
+                projectiles: vec![],
+                hits: vec![],
+            }
+            .encode(&mut out);
+            out.len()
+        };
+        let empty = d(vec![], vec![]);
+        assert_eq!(d(vec![a.clone()], vec![]) - empty, SPAWN_REC_BYTES);
+        assert_eq!(d(vec![], vec![rec]) - empty, refresh_bytes(Some(&a), &b));
+        assert_eq!(refresh_bytes(Some(&a), &a), 0);
+    }
+
+    #[test]
+    fn stale_delta_is_ignored() {
+        let mut tx = BaselineTracker::new();
+        let mut rx = BaselineReceiver::new();
+        let d0 = tx.build(0, set(&[rep(1, 0.0, 30)]), vec![], vec![]);
+        let d1 = tx.build(1, set(&[rep(1, 2.0, 30)]), vec![], vec![]);
+        assert!(rx.apply(&d1).is_some());
+        assert!(rx.apply(&d0).is_none());
+        assert_eq!(rx.last_applied(), Some(1));
+    }
+
+    #[test]
+    fn delta_on_missing_baseline_is_dropped_unacked() {
+        let mut tx = BaselineTracker::new();
+        let mut rx = BaselineReceiver::new();
+        let d0 = tx.build(0, set(&[rep(1, 0.0, 30)]), vec![], vec![]);
+        let _lost = tx.build(1, set(&[rep(1, 1.0, 30)]), vec![], vec![]);
+        assert!(rx.apply(&d0).is_some());
+        // The server saw an ack for tick 1 the client never applied.
+        tx.ack(1);
+        let d2 = tx.build(2, set(&[rep(1, 2.0, 20)]), vec![], vec![]);
+        assert_eq!(d2.baseline, 1);
+        assert!(rx.apply(&d2).is_none());
+        assert_eq!(rx.last_applied(), Some(0), "nothing new to ack");
+        // A keyframe recovers.
+        let mut fresh = BaselineTracker::new();
+        let d3 = fresh.build(3, set(&[rep(1, 3.0, 20)]), vec![], vec![]);
+        assert_eq!(rx.apply(&d3).unwrap()[&1].hp, 20);
+        assert_eq!(rx.last_applied(), Some(3));
+    }
+}
@@ -5,6 +5,17 @@
 //!   (`TAG_CLIENT_CMD`) distinct from `TickSnapshot` framing, so decoders can
 //!   quickly reject the wrong payload type.
 //! - Used by the client renderer to send cast/projectile actions to the server.
+//! - Spells are cast through one data-driven `Cast` command keyed by the spell
+//!   id in `data/spells/*.json` (e.g. `wiz.fire_bolt.srd521`), so new spells
+//!   need no wire changes.
+//! - Weapon attacks (`Attack`) name a weapon id from `data/config/weapons.toml`
+//!   and reuse `CastTarget` for aim.
+//! - `Dodge` carries only the move direction at the press; distance, stamina
+//!   cost and i-frames are server tuning.
+//! - `Equip`, `Use` and `Drop` name a carried item by its id in
+//!   `data/items/*.json`; the server owns the inventory and replicates it back
+//!   as `snapshot::InventoryMsg`. `PickUp` names one of the client's own
+//!   loot drops (`snapshot::LootMsg`) by its drop id.
 //!
 //! Extending
 //! - Add new enum variants (e.g., melee swings, toggles). Keep payloads small
@@ -12,67 +23,184 @@
 //!   with `TAG_CLIENT_CMD`. If the wire evolves, introduce a new tag.
 //! - Never include gameplay tuning in commands (e.g., damage, radius). The
 //!   server is authoritative over projectile parameters and resolves them from
-//!   data specs. Clients send only intent (kind + origin + direction).
+//!   data specs. Clients send only intent (ability id + target); the caster's
+//!   position is the server's.
 
 use crate::snapshot::SnapshotDecode;
 
 pub const TAG_CLIENT_CMD: u8 = 0xC1;
+/// Upper bound for ability, weapon and item ids (bytes of UTF-8).
+pub const MAX_ABILITY_ID_BYTES: usize = 64;
+
+/// What a cast is aimed at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CastTarget {
+    /// The caster itself (buffs, self-centered areas).
+    SelfCast,
+    /// A specific actor by replicated id.
+    Actor(u32),
+    /// A point on the ground (world space).
+    Ground([f32; 3]),
+    /// A world-space direction from the caster.
+    Direction([f32; 3]),
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ClientCmd {
-    FireBolt { pos: [f32; 3], dir: [f32; 3] },
-    Fireball { pos: [f32; 3], dir: [f32; 3] },
-    MagicMissile { pos: [f32; 3], dir: [f32; 3] },
+    /// Cast the spell with this `SpecDb` id at `target`.
+    Cast {
+        ability_id: String,
+        target: CastTarget,
+    },
+    /// Attack with the weapon with this id at `target` (melee swings use the
+    /// aim direction; ranged shots an actor or direction).
+    Attack {
+        weapon_id: String,
+        target: CastTarget,
+    },
     // Authoritative movement/aim intents
-    Move { dx: f32, dz: f32, run: u8 },
-    Aim { yaw: f32 },
+    /// One tick of movement input. `seq` increases by one per input (0 =
+    /// unsequenced); the server echoes the newest applied `seq` in
+    /// `ActorSnapshotDelta::input_seq` so the client can reconcile.
+    Move {
+        dx: f32,
+        dz: f32,
+        run: u8,
+        seq: u32,
+    },
+    Aim {
+        yaw: f32,
+    },
+    /// Dash along this move direction (same axes as `Move`); a zero direction
+    /// dashes along the actor's facing.
+    Dodge {
+        dx: f32,
+        dz: f32,
+    },
+    /// Wear the carried item with this id, or take it off if it is worn.
+    Equip {
+        item_id: String,
+    },
+    /// Use one of the carried consumable with this id.
+    Use {
+        item_id: String,
+    },
+    /// Drop up to `count` of the carried item with this id.
+    Drop {
+        item_id: String,
+        count: u16,
+    },
+    /// Pick up the loot drop with this id (`LootDropRep::id`).
+    PickUp {
+        drop_id: u32,
+    },
+    /// Newest snapshot tick the client applied (baseline for server deltas).
+    Ack {
+        tick: u64,
+    },
 }
 
 impl ClientCmd {
     pub fn encode(&self, out: &mut Vec<u8>) {
         out.push(TAG_CLIENT_CMD);
         match self {
-            ClientCmd::FireBolt { pos, dir } => {
-                out.push(0);
-                for c in pos {
-                    out.extend_from_slice(&c.to_le_bytes());
-                }
-                for c in dir {
-                    out.extend_from_slice(&c.to_le_bytes());
-                }
-            }
-            ClientCmd::Fireball { pos, dir } => {
-                out.push(1);
-                for c in pos {
-                    out.extend_from_slice(&c.to_le_bytes());
-                }
-                for c in dir {
-                    out.extend_from_slice(&c.to_le_bytes());
-                }
-            }
-            ClientCmd::MagicMissile { pos, dir } => {
-                out.push(2);
-                for c in pos {
-                    out.extend_from_slice(&c.to_le_bytes());
-                }
-                for c in dir {
-                    out.extend_from_slice(&c.to_le_bytes());
-                }
-            }
-            ClientCmd::Move { dx, dz, run } => {
+            ClientCmd::Move { dx, dz, run, seq } => {
                 out.push(3);
                 out.extend_from_slice(&dx.to_le_bytes());
                 out.extend_from_slice(&dz.to_le_bytes());
                 out.push(*run);
+                out.extend_from_slice(&seq.to_le_bytes());
             }
             ClientCmd::Aim { yaw } => {
                 out.push(4);
                 out.extend_from_slice(&yaw.to_le_bytes());
             }
+            ClientCmd::Ack { tick } => {
+                out.push(5);
+                out.extend_from_slice(&tick.to_le_bytes());
+            }
+            ClientCmd::Cast { ability_id, target } => {
+                out.push(6);
+                encode_id(out, ability_id);
+                encode_target(out, *target);
+            }
+            ClientCmd::Attack { weapon_id, target } => {
+                out.push(7);
+                encode_id(out, weapon_id);
+                encode_target(out, *target);
+            }
+            ClientCmd::Dodge { dx, dz } => {
+                out.push(8);
+                out.extend_from_slice(&dx.to_le_bytes());
+                out.extend_from_slice(&dz.to_le_bytes());
+            }
+            ClientCmd::Equip { item_id } => {
+                out.push(9);
+                encode_id(out, item_id);
+            }
+            ClientCmd::Use { item_id } => {
+                out.push(10);
+                encode_id(out, item_id);
+            }
+            ClientCmd::Drop { item_id, count } => {
+                out.push(11);
+                encode_id(out, item_id);
+                out.extend_from_slice(&count.to_le_bytes());
+            }
+            ClientCmd::PickUp { drop_id } => {
+                out.push(12);
+                out.extend_from_slice(&drop_id.to_le_bytes());
+            }
         }
     }
 }
 
+/// Length-prefixed id, truncated to `MAX_ABILITY_ID_BYTES` on a char boundary.
+pub(crate) fn encode_id(out: &mut Vec<u8>, id: &str) {
+    let mut end = id.len().min(MAX_ABILITY_ID_BYTES);
+    while !id.is_char_boundary(end) {
+        end -= 1;
+    }
+    let id = &id.as_bytes()[..end];
+    out.push(u8::try_from(id.len()).unwrap_or(u8::MAX));
+    out.extend_from_slice(id);
+}
+
+fn encode_target(out: &mut Vec<u8>, target: CastTarget) {
+    match target {
+        CastTarget::SelfCast => out.push(0),
+        CastTarget::Actor(id) => {
+            out.push(1);
+            out.extend_from_slice(&id.to_le_bytes());
+        }
+        CastTarget::Ground(v) | CastTarget::Direction(v) => {
+            out.push(if matches!(target, CastTarget::Ground(_)) {
+                2
+            } else {
+                3
+            });
+            for c in v {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Inverse of `encode_id`.
+pub(crate) fn decode_id(inp: &mut &[u8]) -> anyhow::Result<String> {
+    let n = usize::from(
+        inp.first()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("short read"))?,
+    );
+    if n > MAX_ABILITY_ID_BYTES || inp.len() < n + 1 {
+        anyhow::bail!("bad id length {n}");
+    }
+    let (id, rest) = inp[1..].split_at(n);
+    *inp = rest;
+    Ok(std::str::from_utf8(id)?.to_string())
+}
+
 impl SnapshotDecode for ClientCmd {
     fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
         use anyhow::bail;
@@ -100,23 +228,6 @@ impl SnapshotDecode for ClientCmd {
             .ok_or_else(|| anyhow::anyhow!("short read"))?;
         *inp = &inp[1..];
         let out = match kind {
-            0..=2 => {
-                let mut pos = [0.0f32; 3];
-                for v in &mut pos {
-                    *v = f32::from_le_bytes(take::<4>(inp)?);
-                }
-                let mut dir = [0.0f32; 3];
-                for v in &mut dir {
-                    *v = f32::from_le_bytes(take::<4>(inp)?);
-                }
-                if kind == 1 {
-                    Self::Fireball { pos, dir }
-                } else if kind == 2 {
-                    Self::MagicMissile { pos, dir }
-                } else {
-                    Self::FireBolt { pos, dir }
-                }
-            }
             3 => {
                 let dx = f32::from_le_bytes(take::<4>(inp)?);
                 let dz = f32::from_le_bytes(take::<4>(inp)?);
@@ -125,14 +236,154 @@ impl SnapshotDecode for ClientCmd {
                     .copied()
                     .ok_or_else(|| anyhow::anyhow!("short read"))?;
                 *inp = &inp[1..];
-                Self::Move { dx, dz, run }
+                let seq = u32::from_le_bytes(take::<4>(inp)?);
+                Self::Move { dx, dz, run, seq }
             }
             4 => {
                 let yaw = f32::from_le_bytes(take::<4>(inp)?);
                 Self::Aim { yaw }
             }
+            5 => {
+                let tick = u64::from_le_bytes(take::<8>(inp)?);
+                Self::Ack { tick }
+            }
+            6 | 7 => {
+                let id = decode_id(inp)?;
+                let vec3 = |inp: &mut &[u8]| -> anyhow::Result<[f32; 3]> {
+                    let mut v = [0.0f32; 3];
+                    for c in &mut v {
+                        *c = f32::from_le_bytes(take::<4>(inp)?);
+                    }
+                    Ok(v)
+                };
+                let target = match take::<1>(inp)?[0] {
+                    0 => CastTarget::SelfCast,
+                    1 => CastTarget::Actor(u32::from_le_bytes(take::<4>(inp)?)),
+                    2 => CastTarget::Ground(vec3(inp)?),
+                    3 => CastTarget::Direction(vec3(inp)?),
+                    t => bail!("unknown cast target {t}"),
+                };
+                if kind == 6 {
+                    Self::Cast {
+                        ability_id: id,
+                        target,
+                    }
+                } else {
+                    Self::Attack {
+                        weapon_id: id,
+                        target,
+                    }
+                }
+            }
+            8 => {
+                let dx = f32::from_le_bytes(take::<4>(inp)?);
+                let dz = f32::from_le_bytes(take::<4>(inp)?);
+                Self::Dodge { dx, dz }
+            }
+            9 => Self::Equip {
+                item_id: decode_id(inp)?,
+            },
+            10 => Self::Use {
+                item_id: decode_id(inp)?,
+            },
+            11 => Self::Drop {
+                item_id: decode_id(inp)?,
+                count: u16::from_le_bytes(take::<2>(inp)?),
+            },
+            12 => Self::PickUp {
+                drop_id: u32::from_le_bytes(take::<4>(inp)?),
+            },
             _ => anyhow::bail!("unknown client cmd kind"),
         };
         Ok(out)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cast_roundtrips_every_target() {
+        for target in [
+            CastTarget::SelfCast,
+            CastTarget::Actor(42),
+            CastTarget::Ground([1.0, 0.0, -3.5]),
+            CastTarget::Direction([0.0, 0.0, 1.0]),
+        ] {
+            let cmd = ClientCmd::Cast {
+                ability_id: "wiz.fire_bolt.srd521".into(),
+                target,
+            };
+            let mut buf = Vec::new();
+            cmd.encode(&mut buf);
+            let mut slice: &[u8] = &buf;
+            assert_eq!(ClientCmd::decode(&mut slice).unwrap(), cmd);
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn attack_roundtrips() {
+        for target in [CastTarget::Actor(7), CastTarget::Direction([1.0, 0.0, 0.0])] {
+            let cmd = ClientCmd::Attack {
+                weapon_id: "shortbow".into(),
+                target,
+            };
+            let mut buf = Vec::new();
+            cmd.encode(&mut buf);
+            let mut slice: &[u8] = &buf;
+            assert_eq!(ClientCmd::decode(&mut slice).unwrap(), cmd);
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn dodge_roundtrips() {
+        let cmd = ClientCmd::Dodge { dx: -1.0, dz: 0.5 };
+        let mut buf = Vec::new();
+        cmd.encode(&mut buf);
+        let mut slice: &[u8] = &buf;
+        assert_eq!(ClientCmd::decode(&mut slice).unwrap(), cmd);
+        assert!(slice.is_empty());
+    }
+
+    #[test]
+    fn item_commands_roundtrip() {
+        for cmd in [
+            ClientCmd::Equip {
+                item_id: "shield".into(),
+            },
+            ClientCmd::Use {
+                item_id: "potion_healing".into(),
+            },
+            ClientCmd::Drop {
+                item_id: "potion_healing".into(),
+                count: 3,
+            },
+            ClientCmd::PickUp { drop_id: 42 },
+        ] {
+            let mut buf = Vec::new();
+            cmd.encode(&mut buf);
+            let mut slice: &[u8] = &buf;
+            assert_eq!(ClientCmd::decode(&mut slice).unwrap(), cmd);
+            assert!(slice.is_empty());
+        }
+        // An id longer than its bytes is rejected.
+        let mut buf = Vec::new();
+        ClientCmd::Use {
+            item_id: "potion".into(),
+        }
+        .encode(&mut buf);
+        buf.truncate(buf.len() - 1);
+        assert!(ClientCmd::decode(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn retired_per_spell_kinds_are_rejected() {
+        let mut buf = vec![TAG_CLIENT_CMD, 0];
+        buf.extend_from_slice(&[0u8; 24]);
+        let mut slice: &[u8] = &buf;
+        assert!(ClientCmd::decode(&mut slice).is_err());
+    }
+}
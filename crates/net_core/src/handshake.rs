@@ -0,0 +1,420 @@
+//! Connection handshake: `Hello` (client) -> `Welcome` | `Reject` (server).
+//!
+//! Scope
+//! - The first reliable message on a new link is the client's `Hello` with its
+//!   protocol version, build hash, requested zone slug and player name.
+//! - The server answers with `Welcome` (assigned actor id, zone manifest id,
+//!   tick rate and the wire versions it will send) or a typed `Reject`.
+//! - After `Welcome`, both sides pin decoding to `WireVersions`: messages with
+//!   an unknown tag or a different version byte are dropped instead of being
+//!   probed against other decoders.
+//!
+//! Versioning
+//! - Bump `PROTOCOL_VERSION` whenever a message layout changes (including a
+//!   bump in any `WireVersions` field) so mismatched peers fail the handshake
+//!   rather than mis-decoding later.
+
+use crate::snapshot::{
+    ACTOR_SNAP_DELTA_VERSION, ACTOR_SNAP_VERSION, HUD_STATUS_VERSION, HUD_TOAST_VERSION,
+    INVENTORY_VERSION, LOOT_VERSION, STATUS_VERSION, SnapshotDecode, SnapshotEncode,
+    TAG_ACTOR_SNAPSHOT, TAG_ACTOR_SNAPSHOT_DELTA, TAG_HUD_STATUS, TAG_HUD_TOAST, TAG_INVENTORY,
+    TAG_LOOT, TAG_STATUS, TAG_TELEGRAPH, TELEGRAPH_VERSION,
+};
+
+pub const TAG_HELLO: u8 = 0xC2;
+pub const TAG_WELCOME: u8 = 0xC3;
+pub const TAG_REJECT: u8 = 0xC4;
+
+/// Current protocol version spoken by this build.
+/// 2: per-spell cast commands replaced by `ClientCmd::Cast`.
+/// 3: boss telegraph messages (`WireVersions::telegraph`).
+/// 4: weapon attacks (`ClientCmd::Attack`).
+/// 5: dodge (`ClientCmd::Dodge`, actor delta v6 with `ActorRep::state`).
+/// 6: status effects (`WireVersions::status`, HUD status v2).
+/// 7: inventory (`ClientCmd::Equip`/`Use`/`Drop`, `WireVersions::inventory`).
+/// 8: loot drops (`ClientCmd::PickUp`, `WireVersions::loot`).
+/// 9: `RejectReason::NameInUse`.
+pub const PROTOCOL_VERSION: u16 = 9;
+/// Upper bounds for handshake strings (bytes of UTF-8).
+pub const MAX_NAME_BYTES: usize = 32;
+pub const MAX_SLUG_BYTES: usize = 64;
+
+/// Identifier of this build (FNV-1a of the crate version and `RA_BUILD_ID`,
+/// when set at compile time). Informational unless the server requires a match.
+pub const BUILD_HASH: u64 = fnv1a(
+    env!("CARGO_PKG_VERSION").as_bytes(),
+    match option_env!("RA_BUILD_ID") {
+        Some(s) => s.as_bytes(),
+        None => b"",
+    },
+);
+
+const fn fnv1a(version: &[u8], build_id: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < version.len() + build_id.len() {
+        let byte = if i < version.len() {
+            version[i]
+        } else {
+            build_id[i - version.len()]
+        };
+        hash = (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Per-message wire versions both sides decode after the handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireVersions {
+    pub actor_snapshot: u8,
+    pub actor_delta: u8,
+    pub hud_status: u8,
+    pub hud_toast: u8,
+    pub telegraph: u8,
+    pub status: u8,
+    pub inventory: u8,
+    pub loot: u8,
+    /// Leading byte of destructible instance / chunk mesh messages.
+    pub mesh: u8,
+}
+
+impl WireVersions {
+    pub const CURRENT: Self = Self {
+        actor_snapshot: ACTOR_SNAP_VERSION,
+        actor_delta: ACTOR_SNAP_DELTA_VERSION,
+        hud_status: HUD_STATUS_VERSION,
+        hud_toast: HUD_TOAST_VERSION,
+        telegraph: TELEGRAPH_VERSION,
+        status: STATUS_VERSION,
+        inventory: INVENTORY_VERSION,
+        loot: LOOT_VERSION,
+        mesh: crate::snapshot::VERSION,
+    };
+
+    /// Whether a server->client payload carries a known tag at the pinned version.
+    #[must_use]
+    pub fn accepts(&self, payload: &[u8]) -> bool {
+        let ver = payload.get(1).copied();
+        match payload.first().copied() {
+            Some(TAG_ACTOR_SNAPSHOT_DELTA) => ver == Some(self.actor_delta),
+            Some(TAG_ACTOR_SNAPSHOT) => ver == Some(self.actor_snapshot),
+            Some(TAG_HUD_STATUS) => ver == Some(self.hud_status),
+            Some(TAG_HUD_TOAST) => ver == Some(self.hud_toast),
+            Some(TAG_TELEGRAPH) => ver == Some(self.telegraph),
+            Some(TAG_STATUS) => ver == Some(self.status),
+            Some(TAG_INVENTORY) => ver == Some(self.inventory),
+            Some(TAG_LOOT) => ver == Some(self.loot),
+            Some(b) => b == self.mesh,
+            None => false,
+        }
+    }
+}
+
+impl Default for WireVersions {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+/// Client -> server: first message on a new connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hello {
+    pub protocol: u16,
+    pub build_hash: u64,
+    pub zone_slug: String,
+    pub player_name: String,
+}
+
+impl Hello {
+    /// Hello for this build.
+    #[must_use]
+    pub fn new(zone_slug: &str, player_name: &str) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            build_hash: BUILD_HASH,
+            zone_slug: zone_slug.to_string(),
+            player_name: player_name.to_string(),
+        }
+    }
+}
+
+/// Server -> client: join accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Welcome {
+    pub protocol: u16,
+    pub actor_id: u32,
+    /// `ZoneManifest::zone_id` of the zone the client joined.
+    pub zone_manifest_id: u32,
+    pub tick_hz: u16,
+    pub versions: WireVersions,
+}
+
+/// Why the server refused a `Hello`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    VersionMismatch = 1,
+    BuildMismatch = 2,
+    UnknownZone = 3,
+    BadName = 4,
+    ServerFull = 5,
+    /// Anything other than a `Hello` arrived first.
+    Malformed = 6,
+    /// A connected player already has this name.
+    NameInUse = 7,
+}
+
+impl TryFrom<u8> for RejectReason {
+    type Error = anyhow::Error;
+    fn try_from(v: u8) -> anyhow::Result<Self> {
+        Ok(match v {
+            1 => Self::VersionMismatch,
+            2 => Self::BuildMismatch,
+            3 => Self::UnknownZone,
+            4 => Self::BadName,
+            5 => Self::ServerFull,
+            6 => Self::Malformed,
+            7 => Self::NameInUse,
+            _ => anyhow::bail!("unknown reject reason {v}"),
+        })
+    }
+}
+
+/// Server -> client: join refused; the server closes the connection after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reject {
+    pub reason: RejectReason,
+    /// Protocol the server speaks, so clients can report what to upgrade to.
+    pub server_protocol: u16,
+}
+
+/// Either server reply to a `Hello`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerReply {
+    Welcome(Welcome),
+    Reject(Reject),
+}
+
+fn take<const N: usize>(inp: &mut &[u8]) -> anyhow::Result<[u8; N]> {
+    if inp.len() < N {
+        anyhow::bail!("short read");
+    }
+    let (a, b) = inp.split_at(N);
+    *inp = b;
+    let mut buf = [0u8; N];
+    buf.copy_from_slice(a);
+    Ok(buf)
+}
+
+fn expect_tag(inp: &mut &[u8], tag: u8) -> anyhow::Result<()> {
+    let [t] = take::<1>(inp)?;
+    if t != tag {
+        anyhow::bail!("unexpected tag {t:#x}");
+    }
+    Ok(())
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str, max: usize) {
+    // Truncate on a char boundary so the receiver still sees valid UTF-8.
+    let mut end = s.len().min(max);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    let b = &s.as_bytes()[..end];
+    out.push(u8::try_from(b.len()).unwrap_or(u8::MAX));
+    out.extend_from_slice(b);
+}
+
+fn take_str(inp: &mut &[u8], max: usize) -> anyhow::Result<String> {
+    let [n] = take::<1>(inp)?;
+    let n = usize::from(n);
+    if n > max || inp.len() < n {
+        anyhow::bail!("bad string length {n}");
+    }
+    let (a, b) = inp.split_at(n);
+    *inp = b;
+    Ok(std::str::from_utf8(a)?.to_string())
+}
+
+impl SnapshotEncode for Hello {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_HELLO);
+        out.extend_from_slice(&self.protocol.to_le_bytes());
+        out.extend_from_slice(&self.build_hash.to_le_bytes());
+        put_str(out, &self.zone_slug, MAX_SLUG_BYTES);
+        put_str(out, &self.player_name, MAX_NAME_BYTES);
+    }
+}
+
+impl SnapshotDecode for Hello {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        expect_tag(inp, TAG_HELLO)?;
+        let protocol = u16::from_le_bytes(take::<2>(inp)?);
+        let build_hash = u64::from_le_bytes(take::<8>(inp)?);
+        let zone_slug = take_str(inp, MAX_SLUG_BYTES)?;
+        let player_name = take_str(inp, MAX_NAME_BYTES)?;
+        Ok(Self {
+            protocol,
+            build_hash,
+            zone_slug,
+            player_name,
+        })
+    }
+}
+
+impl SnapshotEncode for Welcome {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_WELCOME);
+        out.extend_from_slice(&self.protocol.to_le_bytes());
+        out.extend_from_slice(&self.actor_id.to_le_bytes());
+        out.extend_from_slice(&self.zone_manifest_id.to_le_bytes());
+        out.extend_from_slice(&self.tick_hz.to_le_bytes());
+        let v = &self.versions;
+        out.extend_from_slice(&[
+            v.actor_snapshot,
+            v.actor_delta,
+            v.hud_status,
+            v.hud_toast,
+            v.telegraph,
+            v.status,
+            v.inventory,
+            v.loot,
+            v.mesh,
+        ]);
+    }
+}
+
+impl SnapshotDecode for Welcome {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        expect_tag(inp, TAG_WELCOME)?;
+        let protocol = u16::from_le_bytes(take::<2>(inp)?);
+        let actor_id = u32::from_le_bytes(take::<4>(inp)?);
+        let zone_manifest_id = u32::from_le_bytes(take::<4>(inp)?);
+        let tick_hz = u16::from_le_bytes(take::<2>(inp)?);
+        let [
+            actor_snapshot,
+            actor_delta,
+            hud_status,
+            hud_toast,
+            telegraph,
+            status,
+            inventory,
+            loot,
+            mesh,
+        ] = take::<9>(inp)?;
+        Ok(Self {
+            protocol,
+            actor_id,
+            zone_manifest_id,
+            tick_hz,
+            versions: WireVersions {
+                actor_snapshot,
+                actor_delta,
+                hud_status,
+                hud_toast,
+                telegraph,
+                status,
+                inventory,
+                loot,
+                mesh,
+            },
+        })
+    }
+}
+
+impl SnapshotEncode for Reject {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_REJECT);
+        out.push(self.reason as u8);
+        out.extend_from_slice(&self.server_protocol.to_le_bytes());
+    }
+}
+
+impl SnapshotDecode for Reject {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        expect_tag(inp, TAG_REJECT)?;
+        let [r] = take::<1>(inp)?;
+        let reason = RejectReason::try_from(r)?;
+        let server_protocol = u16::from_le_bytes(take::<2>(inp)?);
+        Ok(Self {
+            reason,
+            server_protocol,
+        })
+    }
+}
+
+impl SnapshotDecode for ServerReply {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        match inp.first().copied() {
+            Some(TAG_WELCOME) => Ok(Self::Welcome(Welcome::decode(inp)?)),
+            Some(TAG_REJECT) => Ok(Self::Reject(Reject::decode(inp)?)),
+            _ => anyhow::bail!("not a handshake reply"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T: SnapshotEncode + SnapshotDecode>(m: &T) -> T {
+        let mut buf = Vec::new();
+        m.encode(&mut buf);
+        let mut slice: &[u8] = &buf;
+        let out = T::decode(&mut slice).expect("decode");
+        assert!(slice.is_empty());
+        out
+    }
+
+    #[test]
+    fn handshake_messages_roundtrip() {
+        let h = Hello::new("wizard_woods", "Ayla");
+        assert_eq!(roundtrip(&h), h);
+        let w = Welcome {
+            protocol: PROTOCOL_VERSION,
+            actor_id: 17,
+            zone_manifest_id: 3,
+            tick_hz: 30,
+            versions: WireVersions::CURRENT,
+        };
+        assert_eq!(roundtrip(&w), w);
+        let r = Reject {
+            reason: RejectReason::VersionMismatch,
+            server_protocol: 9,
+        };
+        assert_eq!(roundtrip(&r), r);
+    }
+
+    #[test]
+    fn overlong_or_invalid_strings_are_rejected() {
+        let mut buf = Vec::new();
+        Hello::new("z", "n").encode(&mut buf);
+        // Corrupt the name length to exceed the cap.
+        let name_len_at = buf.len() - 2;
+        buf[name_len_at] = u8::try_from(MAX_NAME_BYTES + 1).unwrap_or(u8::MAX);
+        assert!(Hello::decode(&mut buf.as_slice()).is_err());
+        let mut bad = Vec::new();
+        Hello::new("z", "n").encode(&mut bad);
+        let last = bad.len() - 1;
+        bad[last] = 0xFF; // invalid UTF-8
+        assert!(Hello::decode(&mut bad.as_slice()).is_err());
+    }
+
+    #[test]
+    fn pinned_versions_gate_payloads() {
+        let v = WireVersions::CURRENT;
+        assert!(v.accepts(&[TAG_ACTOR_SNAPSHOT_DELTA, ACTOR_SNAP_DELTA_VERSION]));
+        assert!(!v.accepts(&[TAG_ACTOR_SNAPSHOT_DELTA, ACTOR_SNAP_DELTA_VERSION - 1]));
+        assert!(v.accepts(&[TAG_HUD_TOAST, HUD_TOAST_VERSION, 0]));
+        assert!(v.accepts(&[TAG_TELEGRAPH, TELEGRAPH_VERSION, 0]));
+        assert!(!v.accepts(&[TAG_TELEGRAPH, TELEGRAPH_VERSION + 1, 0]));
+        assert!(v.accepts(&[TAG_STATUS, STATUS_VERSION, 0, 0]));
+        assert!(v.accepts(&[TAG_INVENTORY, INVENTORY_VERSION, 0, 0]));
+        assert!(!v.accepts(&[TAG_INVENTORY, INVENTORY_VERSION + 1, 0, 0]));
+        assert!(v.accepts(&[TAG_LOOT, LOOT_VERSION, 0]));
+        assert!(!v.accepts(&[TAG_LOOT, LOOT_VERSION + 1, 0]));
+        assert!(v.accepts(&[crate::snapshot::VERSION, 0, 0]));
+        assert!(!v.accepts(&[0x7F, 1]));
+        assert!(!v.accepts(&[]));
+    }
+}
@@ -1,7 +1,19 @@
 //! Interest management scaffolding (who gets what data).
 //!
-//! Initial implementation: simple spherical interest around a point
-//! (e.g., the client camera). Keep it dependency-light and easy to test.
+//! - `SphereInterest`: stateless spherical volume around a point.
+//! - `GridIndex` + `GridInterest`: planar spatial hash rebuilt once per tick
+//!   and a per-client member set with enter/leave hysteresis, so entities near
+//!   the boundary don't flicker in and out.
+//! - `PriorityBudget`: per-client byte budget. Candidates accumulate priority
+//!   (distance, threat, relevance) every tick they are not refreshed; the
+//!   highest accumulators are sent first, so over budget low-priority actors
+//!   update less often instead of being dropped.
+//!
+//! Keep it dependency-light and easy to test.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::snapshot::ActorRep;
 
 /// Interest providers decide whether to include an item for a given client.
 pub trait InterestProvider<T> {
@@ -13,6 +25,23 @@ pub trait HasPoint {
     fn point(&self) -> [f32; 3];
 }
 
+/// Types with a stable id, for stateful (per-client) interest.
+pub trait HasId {
+    fn id(&self) -> u32;
+}
+
+impl HasPoint for ActorRep {
+    fn point(&self) -> [f32; 3] {
+        self.pos
+    }
+}
+
+impl HasId for ActorRep {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
 /// Spherical interest volume in world coordinates.
 #[derive(Clone, Copy, Debug)]
 pub struct SphereInterest {
@@ -30,6 +59,214 @@ impl<T: HasPoint> InterestProvider<T> for SphereInterest {
     }
 }
 
+/// Items bucketed in one grid cell: (id, point).
+type Cell = Vec<(u32, [f32; 3])>;
+
+/// Planar (XZ) spatial hash of id -> point, rebuilt once per tick and shared
+/// by every client's `GridInterest`.
+#[derive(Debug, Default)]
+pub struct GridIndex {
+    cell_m: f32,
+    cells: HashMap<(i32, i32), Cell>,
+}
+
+impl GridIndex {
+    #[must_use]
+    pub fn new(cell_m: f32) -> Self {
+        Self {
+            cell_m: cell_m.max(0.5),
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Build an index over `items`.
+    pub fn from_items<'a, T: HasId + HasPoint + 'a>(
+        cell_m: f32,
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> Self {
+        let mut g = Self::new(cell_m);
+        for it in items {
+            g.insert(it.id(), it.point());
+        }
+        g
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn cell_of(&self, x: f32, z: f32) -> (i32, i32) {
+        (
+            (x / self.cell_m).floor() as i32,
+            (z / self.cell_m).floor() as i32,
+        )
+    }
+
+    pub fn insert(&mut self, id: u32, p: [f32; 3]) {
+        let c = self.cell_of(p[0], p[2]);
+        self.cells.entry(c).or_default().push((id, p));
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Visit every item within planar `radius` of `center`, with its squared
+    /// planar distance. Only the cells overlapping the radius are scanned.
+    pub fn query(&self, center: [f32; 3], radius: f32, mut f: impl FnMut(u32, [f32; 3], f32)) {
+        let r2 = radius * radius;
+        let (x0, z0) = self.cell_of(center[0] - radius, center[2] - radius);
+        let (x1, z1) = self.cell_of(center[0] + radius, center[2] + radius);
+        for cx in x0..=x1 {
+            for cz in z0..=z1 {
+                let Some(items) = self.cells.get(&(cx, cz)) else {
+                    continue;
+                };
+                for &(id, p) in items {
+                    let dx = p[0] - center[0];
+                    let dz = p[2] - center[2];
+                    let d2 = dx * dx + dz * dz;
+                    if d2 <= r2 {
+                        f(id, p, d2);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Per-client grid interest with hysteresis: an item enters within
+/// `enter_radius` and only leaves once beyond `leave_radius`.
+#[derive(Debug, Clone)]
+pub struct GridInterest {
+    pub enter_radius: f32,
+    pub leave_radius: f32,
+    members: BTreeSet<u32>,
+}
+
+impl GridInterest {
+    #[must_use]
+    pub fn new(enter_radius: f32, leave_radius: f32) -> Self {
+        Self {
+            enter_radius,
+            leave_radius: leave_radius.max(enter_radius),
+            members: BTreeSet::new(),
+        }
+    }
+
+    /// Recompute membership around `center`. Items missing from `grid`
+    /// (despawned) leave immediately.
+    pub fn update(&mut self, center: [f32; 3], grid: &GridIndex) {
+        let enter2 = self.enter_radius * self.enter_radius;
+        let mut next = BTreeSet::new();
+        grid.query(center, self.leave_radius, |id, _, d2| {
+            if d2 <= enter2 || self.members.contains(&id) {
+                next.insert(id);
+            }
+        });
+        self.members = next;
+    }
+
+    /// Current members, ordered by id.
+    #[must_use]
+    pub fn members(&self) -> &BTreeSet<u32> {
+        &self.members
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: u32) -> bool {
+        self.members.contains(&id)
+    }
+}
+
+impl<T: HasId> InterestProvider<T> for GridInterest {
+    fn in_interest(&self, item: &T) -> bool {
+        self.contains(item.id())
+    }
+}
+
+/// Inputs to an actor's replication priority for one client.
+#[derive(Clone, Copy, Debug)]
+pub struct PriorityInputs {
+    /// Planar distance to the client's viewpoint (meters).
+    pub dist_m: f32,
+    /// 0 = harmless; higher for hostiles engaging the client.
+    pub threat: f32,
+    /// Content weight (bosses and players > ambient NPCs > corpses).
+    pub relevance: f32,
+}
+
+impl PriorityInputs {
+    /// Priority gained per tick: relevance scaled up by threat and down by
+    /// distance, as `1 / (1 + dist / falloff_m)` (half at `falloff_m`, a third
+    /// at twice that).
+    #[must_use]
+    pub fn score(&self, falloff_m: f32) -> f32 {
+        let near = 1.0 / (1.0 + self.dist_m.max(0.0) / falloff_m.max(1e-3));
+        self.relevance.max(0.0) * (1.0 + self.threat.max(0.0)) * near
+    }
+}
+
+/// One actor competing for this tick's budget.
+#[derive(Clone, Copy, Debug)]
+pub struct BudgetCandidate {
+    pub id: u32,
+    /// Priority gained this tick (see `PriorityInputs::score`).
+    pub priority: f32,
+    /// Estimated encoded bytes if refreshed now (0 = nothing changed).
+    pub bytes: usize,
+    /// Always refreshed and never charged against others (e.g., own actor).
+    pub pinned: bool,
+}
+
+/// Per-client priority accumulator that fits refreshes into a byte budget.
+#[derive(Debug, Default)]
+pub struct PriorityBudget {
+    accum: HashMap<u32, f32>,
+}
+
+impl PriorityBudget {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pick which candidates to refresh this tick within `budget_bytes`.
+    /// Deferred candidates keep their accumulated priority, so every actor is
+    /// eventually refreshed; refreshed ones restart from zero. Returns the
+    /// selected ids, ordered by id.
+    pub fn select(&mut self, budget_bytes: usize, cands: &[BudgetCandidate]) -> BTreeSet<u32> {
+        let mut order: Vec<(f32, &BudgetCandidate)> = cands
+            .iter()
+            .map(|c| {
+                let acc = self.accum.get(&c.id).copied().unwrap_or(0.0) + c.priority;
+                (acc, c)
+            })
+            .collect();
+        order.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+        let mut next = HashMap::with_capacity(order.len());
+        let mut picked = BTreeSet::new();
+        let mut spent = 0usize;
+        for (acc, c) in order {
+            if c.pinned || c.bytes == 0 {
+                picked.insert(c.id);
+            } else if spent + c.bytes <= budget_bytes {
+                spent += c.bytes;
+                picked.insert(c.id);
+            } else {
+                metrics::counter!("replication.deferred_total").increment(1);
+                next.insert(c.id, acc);
+            }
+        }
+        // Candidates that left interest forget their accumulated priority.
+        self.accum = next;
+        picked
+    }
+
+    /// Accumulated priority of a deferred candidate (0 when up to date).
+    #[must_use]
+    pub fn pending(&self, id: u32) -> f32 {
+        self.accum.get(&id).copied().unwrap_or(0.0)
+    }
+}
+
 /// Helper: compute a chunk's approximate world-space center given the chunk
 /// origin and voxel size (meters per voxel).
 #[inline]
@@ -73,6 +310,126 @@ mod tests {
         assert!(!s.in_interest(&Pt([6.0, 0.0, 0.0])));
     }
 
+    struct Item(u32, [f32; 3]);
+    impl HasPoint for Item {
+        fn point(&self) -> [f32; 3] {
+            self.1
+        }
+    }
+    impl HasId for Item {
+        fn id(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn grid_query_matches_brute_force() {
+        let items: Vec<Item> = (0..400u16)
+            .map(|i| {
+                let x = f32::from(i % 20) * 3.7 - 30.0;
+                let z = f32::from(i / 20) * 4.1 - 40.0;
+                Item(u32::from(i), [x, 0.0, z])
+            })
+            .collect();
+        let g = GridIndex::from_items(8.0, &items);
+        let c = [2.5, 0.0, -3.0];
+        let r = 17.0;
+        let mut got = Vec::new();
+        g.query(c, r, |id, _, _| got.push(id));
+        got.sort_unstable();
+        let want: Vec<u32> = items
+            .iter()
+            .filter(|it| {
+                let dx = it.1[0] - c[0];
+                let dz = it.1[2] - c[2];
+                dx * dx + dz * dz <= r * r
+            })
+            .map(|it| it.0)
+            .collect();
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn grid_interest_hysteresis_prevents_flicker() {
+        let mut gi = GridInterest::new(10.0, 12.0);
+        let at = |x: f32| GridIndex::from_items(4.0, &[Item(7, [x, 0.0, 0.0])]);
+        // Outside enter radius: not yet in.
+        gi.update([0.0; 3], &at(11.0));
+        assert!(!gi.contains(7));
+        gi.update([0.0; 3], &at(9.5));
+        assert!(gi.contains(7));
+        // Jitter across the enter boundary keeps it in.
+        for x in [10.5, 9.8, 11.5, 10.2, 11.9] {
+            gi.update([0.0; 3], &at(x));
+            assert!(gi.contains(7), "flickered out at {x}");
+        }
+        gi.update([0.0; 3], &at(12.5));
+        assert!(!gi.contains(7));
+        // Despawned items leave immediately.
+        gi.update([0.0; 3], &at(5.0));
+        gi.update([0.0; 3], &GridIndex::new(4.0));
+        assert!(!gi.contains(7));
+    }
+
+    #[test]
+    fn budget_defers_low_priority_without_starving_it() {
+        let mut b = PriorityBudget::new();
+        let cands = [
+            BudgetCandidate {
+                id: 1,
+                priority: 0.0,
+                bytes: 30,
+                pinned: true,
+            },
+            BudgetCandidate {
+                id: 2,
+                priority: 1.0,
+                bytes: 30,
+                pinned: false,
+            },
+            BudgetCandidate {
+                id: 3,
+                priority: 0.25,
+                bytes: 30,
+                pinned: false,
+            },
+        ];
+        let mut sent = [0u32; 4];
+        for _ in 0..40 {
+            for id in b.select(30, &cands) {
+                sent[id as usize] += 1;
+            }
+        }
+        assert_eq!(sent[1], 40, "pinned always refreshed");
+        assert!(sent[2] > sent[3], "{sent:?}");
+        assert!(sent[3] >= 5, "low priority starved: {sent:?}");
+        assert_eq!(sent[2] + sent[3], 40, "budget fits exactly one per tick");
+    }
+
+    #[test]
+    fn priority_prefers_near_threatening_relevant() {
+        let base = PriorityInputs {
+            dist_m: 20.0,
+            threat: 0.0,
+            relevance: 1.0,
+        };
+        let near = PriorityInputs {
+            dist_m: 2.0,
+            ..base
+        };
+        let hostile = PriorityInputs {
+            threat: 1.0,
+            ..base
+        };
+        let corpse = PriorityInputs {
+            relevance: 0.25,
+            ..base
+        };
+        assert!(near.score(10.0) > base.score(10.0));
+        assert!(hostile.score(10.0) > base.score(10.0));
+        assert!(corpse.score(10.0) < base.score(10.0));
+    }
+
     #[test]
     fn chunk_center_helper_is_reasonable() {
         let c = chunk_center_world([0.0, 0.0, 0.0], 0.25, (1, 2, 3), 32);
@@ -9,10 +9,13 @@
 #![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
 pub mod apply;
+pub mod baseline;
 pub mod channel;
 pub mod command;
 pub mod frame;
+pub mod handshake;
 pub mod interest;
+pub mod link;
 pub mod snapshot;
 pub mod transport;
 
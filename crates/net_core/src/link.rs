@@ -0,0 +1,553 @@
+//! Packet link layer: reliable-ordered and unreliable-sequenced channels.
+//!
+//! Scope
+//! - Sits between message payloads (snapshots, commands, HUD) and a
+//!   `transport::Transport`. Each transport unit is one framed link packet.
+//! - Reliable messages (spawns, destructible instances, toasts) carry a
+//!   per-channel sequence, are resent until acked, and are delivered exactly
+//!   once, in order.
+//! - Unreliable messages (`ActorSnapshotDelta`, HUD status) are sent once on
+//!   a stream (`send_on`); the receiver drops anything older than the newest
+//!   already delivered on the same stream, so the latest state of each wins
+//!   without one kind of message shadowing another.
+//!
+//! Wire (little-endian, inside a `frame` envelope)
+//! - `TAG_LINK_PACKET`, version, u8 flags (bit 0: ack fields valid)
+//! - u16 packet seq, u16 ack (latest remote seq), u32 `ack_bits` (previous 32)
+//! - u16 message count, then per message: u8 channel, u8 stream, u16 msg seq,
+//!   u32 len, bytes. Unreliable sequences count per stream; reliable messages
+//!   use stream 0.
+//!
+//! A message travels in one transport unit, so `send` rejects payloads over
+//! `LinkConfig::max_message_bytes` (by default, what fits one UDP datagram)
+//! instead of queueing a reliable message no transport can ever deliver.
+//!
+//! The endpoint is a pure state machine driven by the caller's clock, so tests
+//! can run it deterministically against a lossy transport.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::transport::{Transport, TrySendError};
+
+pub const TAG_LINK_PACKET: u8 = 0xD1;
+const LINK_VERSION: u8 = 2;
+/// Sent-packet history used to map incoming acks back to reliable messages.
+const SENT_RING: usize = 1024;
+const PACKET_HEADER_BYTES: usize = 13;
+const FLAG_HAS_ACK: u8 = 1;
+const MSG_HEADER_BYTES: usize = 8;
+/// `frame` envelope around each packet: magic and length.
+const FRAME_HEADER_BYTES: usize = 8;
+/// Largest payload that fits one UDP datagram (`transport::udp::MAX_DATAGRAM`)
+/// alone in a packet.
+pub const MAX_MESSAGE_BYTES: usize =
+    65_507 - FRAME_HEADER_BYTES - PACKET_HEADER_BYTES - MSG_HEADER_BYTES;
+
+/// Delivery class of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// Resent until acked; delivered once and in send order.
+    Reliable,
+    /// Sent once; stale arrivals are dropped.
+    Unreliable,
+}
+
+impl Channel {
+    fn to_u8(self) -> u8 {
+        match self {
+            Channel::Reliable => 0,
+            Channel::Unreliable => 1,
+        }
+    }
+}
+
+/// Tunables for an endpoint.
+#[derive(Debug, Clone)]
+pub struct LinkConfig {
+    /// Resend an unacked reliable message after this many milliseconds.
+    pub resend_ms: u64,
+    /// Soft cap for one packet; a single larger message is sent alone.
+    pub max_packet_bytes: usize,
+    /// Max unacked reliable messages; `send` reports `Full` beyond this.
+    pub max_in_flight: usize,
+    /// Largest payload `send` accepts; bigger ones report `TooLarge`.
+    pub max_message_bytes: usize,
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self {
+            resend_ms: 100,
+            max_packet_bytes: 16 * 1024,
+            max_in_flight: 1024,
+            max_message_bytes: MAX_MESSAGE_BYTES,
+        }
+    }
+}
+
+struct Pending {
+    seq: u16,
+    payload: Vec<u8>,
+    last_sent_ms: Option<u64>,
+}
+
+struct SentPacket {
+    seq: u16,
+    reliable: Vec<u16>,
+}
+
+/// One side of a link. Queue messages with `send`, exchange packets with
+/// `flush`/`receive` (or `pump` against a transport), and read with `recv`.
+pub struct Endpoint {
+    cfg: LinkConfig,
+    // Outbound
+    next_pkt_seq: u16,
+    next_rel_seq: u16,
+    /// Next unreliable sequence, per stream.
+    next_unrel_seq: HashMap<u8, u16>,
+    reliable_out: VecDeque<Pending>,
+    unreliable_out: Vec<(u8, u16, Vec<u8>)>,
+    sent: Vec<Option<SentPacket>>,
+    // Inbound
+    remote_seq: Option<u16>,
+    ack_bits: u32,
+    ack_dirty: bool,
+    rel_expected: u16,
+    rel_buffer: BTreeMap<u16, Vec<u8>>,
+    /// Newest unreliable sequence delivered, per stream.
+    unrel_latest: HashMap<u8, u16>,
+    inbox: VecDeque<(Channel, Vec<u8>)>,
+}
+
+/// `a` is newer than `b` under u16 wraparound.
+#[inline]
+#[must_use]
+pub fn seq_newer(a: u16, b: u16) -> bool {
+    a != b && a.wrapping_sub(b) < 0x8000
+}
+
+impl Endpoint {
+    #[must_use]
+    pub fn new(cfg: LinkConfig) -> Self {
+        Self {
+            cfg,
+            next_pkt_seq: 0,
+            next_rel_seq: 0,
+            next_unrel_seq: HashMap::new(),
+            reliable_out: VecDeque::new(),
+            unreliable_out: Vec::new(),
+            sent: (0..SENT_RING).map(|_| None).collect(),
+            remote_seq: None,
+            ack_bits: 0,
+            ack_dirty: false,
+            rel_expected: 0,
+            rel_buffer: BTreeMap::new(),
+            unrel_latest: HashMap::new(),
+            inbox: VecDeque::new(),
+        }
+    }
+
+    /// Queue a message on `ch` (unreliable: on stream 0). Payloads over
+    /// `max_message_bytes` fail with `TooLarge`; reliable sends also fail with
+    /// `Full` when too many messages are awaiting acks.
+    pub fn send(&mut self, ch: Channel, payload: Vec<u8>) -> Result<(), TrySendError> {
+        self.send_on(ch, 0, payload)
+    }
+
+    /// Like `send`, but unreliable messages are sequenced on `stream`: only a
+    /// newer message on the same stream makes one stale. Callers typically
+    /// use the message tag. Reliable messages ignore `stream`.
+    pub fn send_on(
+        &mut self,
+        ch: Channel,
+        stream: u8,
+        payload: Vec<u8>,
+    ) -> Result<(), TrySendError> {
+        if payload.len() > self.cfg.max_message_bytes {
+            metrics::counter!("link.dropped_total", "reason" => "too_large").increment(1);
+            return Err(TrySendError::TooLarge);
+        }
+        match ch {
+            Channel::Reliable => {
+                if self.reliable_out.len() >= self.cfg.max_in_flight {
+                    metrics::counter!("link.dropped_total", "reason" => "in_flight").increment(1);
+                    return Err(TrySendError::Full);
+                }
+                let seq = self.next_rel_seq;
+                self.next_rel_seq = self.next_rel_seq.wrapping_add(1);
+                self.reliable_out.push_back(Pending {
+                    seq,
+                    payload,
+                    last_sent_ms: None,
+                });
+            }
+            Channel::Unreliable => {
+                let next = self.next_unrel_seq.entry(stream).or_insert(0);
+                let seq = *next;
+                *next = next.wrapping_add(1);
+                self.unreliable_out.push((stream, seq, payload));
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of reliable messages not yet acked by the peer.
+    #[must_use]
+    pub fn unacked(&self) -> usize {
+        self.reliable_out.len()
+    }
+
+    /// Pop the next delivered message.
+    pub fn recv(&mut self) -> Option<(Channel, Vec<u8>)> {
+        self.inbox.pop_front()
+    }
+
+    /// Build framed packets for everything due at `now_ms`: new and resendable
+    /// reliable messages, all queued unreliable messages, and a bare ack when
+    /// the peer is owed one.
+    pub fn flush(&mut self, now_ms: u64) -> Vec<Vec<u8>> {
+        let mut msgs: Vec<(Channel, u8, u16, Vec<u8>)> = Vec::new();
+        for p in &mut self.reliable_out {
+            let due = p
+                .last_sent_ms
+                .is_none_or(|t| now_ms.saturating_sub(t) >= self.cfg.resend_ms);
+            if due {
+                if p.last_sent_ms.is_some() {
+                    metrics::counter!("link.resent_total").increment(1);
+                }
+                p.last_sent_ms = Some(now_ms);
+                msgs.push((Channel::Reliable, 0, p.seq, p.payload.clone()));
+            }
+        }
+        for (stream, seq, payload) in self.unreliable_out.drain(..) {
+            msgs.push((Channel::Unreliable, stream, seq, payload));
+        }
+        let mut out = Vec::new();
+        if msgs.is_empty() {
+            if self.ack_dirty {
+                out.push(self.write_packet(&[]));
+            }
+            return out;
+        }
+        let mut batch: Vec<(Channel, u8, u16, Vec<u8>)> = Vec::new();
+        let mut size = PACKET_HEADER_BYTES;
+        for m in msgs {
+            let len = MSG_HEADER_BYTES + m.3.len();
+            let full =
+                size + len > self.cfg.max_packet_bytes || batch.len() == usize::from(u16::MAX);
+            if !batch.is_empty() && full {
+                out.push(self.write_packet(&batch));
+                batch.clear();
+                size = PACKET_HEADER_BYTES;
+            }
+            size += len;
+            batch.push(m);
+        }
+        out.push(self.write_packet(&batch));
+        out
+    }
+
+    fn write_packet(&mut self, msgs: &[(Channel, u8, u16, Vec<u8>)]) -> Vec<u8> {
+        let seq = self.next_pkt_seq;
+        self.next_pkt_seq = self.next_pkt_seq.wrapping_add(1);
+        let mut p = Vec::with_capacity(
+            PACKET_HEADER_BYTES
+                + msgs
+                    .iter()
+                    .map(|m| MSG_HEADER_BYTES + m.3.len())
+                    .sum::<usize>(),
+        );
+        p.push(TAG_LINK_PACKET);
+        p.push(LINK_VERSION);
+        p.push(if self.remote_seq.is_some() {
+            FLAG_HAS_ACK
+        } else {
+            0
+        });
+        p.extend_from_slice(&seq.to_le_bytes());
+        p.extend_from_slice(&self.remote_seq.unwrap_or(0).to_le_bytes());
+        p.extend_from_slice(&self.ack_bits.to_le_bytes());
+        let count = u16::try_from(msgs.len()).unwrap_or(u16::MAX);
+        p.extend_from_slice(&count.to_le_bytes());
+        for (ch, stream, mseq, payload) in msgs {
+            p.push(ch.to_u8());
+            p.push(*stream);
+            p.extend_from_slice(&mseq.to_le_bytes());
+            let len = u32::try_from(payload.len()).unwrap_or(u32::MAX);
+            p.extend_from_slice(&len.to_le_bytes());
+            p.extend_from_slice(payload);
+        }
+        self.sent[usize::from(seq) % SENT_RING] = Some(SentPacket {
+            seq,
+            reliable: msgs
+                .iter()
+                .filter(|m| m.0 == Channel::Reliable)
+                .map(|m| m.2)
+                .collect(),
+        });
+        self.ack_dirty = false;
+        let mut framed = Vec::with_capacity(p.len() + 8);
+        crate::frame::write_msg(&mut framed, &p);
+        framed
+    }
+
+    /// Ingest one framed packet from the peer.
+    pub fn receive(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        use anyhow::bail;
+        fn take<const N: usize>(inp: &mut &[u8]) -> anyhow::Result<[u8; N]> {
+            if inp.len() < N {
+                anyhow::bail!("short read");
+            }
+            let (a, b) = inp.split_at(N);
+            *inp = b;
+            let mut buf = [0u8; N];
+            buf.copy_from_slice(a);
+            Ok(buf)
+        }
+        let mut inp = crate::frame::read_msg(bytes)?;
+        let [tag, ver, flags] = take::<3>(&mut inp)?;
+        if tag != TAG_LINK_PACKET {
+            bail!("not a link packet: tag {tag:#x}");
+        }
+        if ver != LINK_VERSION {
+            bail!("link version {ver} unsupported");
+        }
+        let seq = u16::from_le_bytes(take::<2>(&mut inp)?);
+        let ack = u16::from_le_bytes(take::<2>(&mut inp)?);
+        let ack_bits = u32::from_le_bytes(take::<4>(&mut inp)?);
+        let count = u16::from_le_bytes(take::<2>(&mut inp)?);
+        let mut msgs = Vec::with_capacity(usize::from(count).min(256));
+        for _ in 0..count {
+            let [ch, stream] = take::<2>(&mut inp)?;
+            let mseq = u16::from_le_bytes(take::<2>(&mut inp)?);
+            let len = u32::from_le_bytes(take::<4>(&mut inp)?) as usize;
+            if inp.len() < len {
+                bail!("short message payload");
+            }
+            let (payload, rest) = inp.split_at(len);
+            inp = rest;
+            let ch = match ch {
+                0 => Channel::Reliable,
+                1 => Channel::Unreliable,
+                other => bail!("unknown link channel {other}"),
+            };
+            msgs.push((ch, stream, mseq, payload));
+        }
+        // Whole packet parsed; only now mutate state.
+        self.note_remote_seq(seq);
+        // Ack anything carrying data (even duplicates: our earlier ack may have
+        // been lost). Bare acks are not acked back, which would ping-pong.
+        if !msgs.is_empty() {
+            self.ack_dirty = true;
+        }
+        if flags & FLAG_HAS_ACK != 0 {
+            self.apply_acks(ack, ack_bits);
+        }
+        for (ch, stream, mseq, payload) in msgs {
+            match ch {
+                Channel::Reliable => self.accept_reliable(mseq, payload),
+                Channel::Unreliable => {
+                    let latest = self.unrel_latest.get(&stream).copied();
+                    if latest.is_none_or(|l| seq_newer(mseq, l)) {
+                        self.unrel_latest.insert(stream, mseq);
+                        self.inbox
+                            .push_back((Channel::Unreliable, payload.to_vec()));
+                    } else {
+                        metrics::counter!("link.dropped_total", "reason" => "stale").increment(1);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Record an incoming packet sequence for acking.
+    fn note_remote_seq(&mut self, seq: u16) {
+        let Some(latest) = self.remote_seq else {
+            self.remote_seq = Some(seq);
+            self.ack_bits = 0;
+            return;
+        };
+        if seq_newer(seq, latest) {
+            let shift = u32::from(seq.wrapping_sub(latest));
+            self.ack_bits = if shift > 32 {
+                0
+            } else {
+                // Previous latest becomes bit (shift - 1).
+                let carried = self.ack_bits.checked_shl(shift).unwrap_or(0);
+                carried | (1u32 << (shift - 1))
+            };
+            self.remote_seq = Some(seq);
+        } else {
+            let back = u32::from(latest.wrapping_sub(seq));
+            if (1..=32).contains(&back) {
+                self.ack_bits |= 1u32 << (back - 1);
+            }
+        }
+    }
+
+    fn apply_acks(&mut self, ack: u16, bits: u32) {
+        self.ack_packet(ack);
+        for i in 0..32u16 {
+            if bits & (1u32 << i) != 0 {
+                self.ack_packet(ack.wrapping_sub(i + 1));
+            }
+        }
+    }
+
+    fn ack_packet(&mut self, seq: u16) {
+        let slot = &mut self.sent[usize::from(seq) % SENT_RING];
+        let Some(sp) = slot.take_if(|sp| sp.seq == seq) else {
+            return;
+        };
+        if !sp.reliable.is_empty() {
+            self.reliable_out.retain(|p| !sp.reliable.contains(&p.seq));
+        }
+    }
+
+    fn accept_reliable(&mut self, mseq: u16, payload: &[u8]) {
+        let ahead = mseq.wrapping_sub(self.rel_expected);
+        if ahead >= 0x8000 {
+            metrics::counter!("link.dropped_total", "reason" => "duplicate").increment(1);
+            return;
+        }
+        if usize::from(ahead) >= self.cfg.max_in_flight {
+            // Outside the receive window; the sender will resend it.
+            metrics::counter!("link.dropped_total", "reason" => "window").increment(1);
+            return;
+        }
+        self.rel_buffer
+            .entry(mseq)
+            .or_insert_with(|| payload.to_vec());
+        while let Some(p) = self.rel_buffer.remove(&self.rel_expected) {
+            self.inbox.push_back((Channel::Reliable, p));
+            self.rel_expected = self.rel_expected.wrapping_add(1);
+        }
+    }
+
+    /// Flush due packets into `xport`, then ingest everything it has queued.
+    /// Malformed inbound packets are counted and skipped.
+    pub fn pump(&mut self, xport: &dyn Transport, now_ms: u64) -> Result<(), TrySendError> {
+        let mut result = Ok(());
+        for pkt in self.flush(now_ms) {
+            match xport.try_send(pkt) {
+                Ok(()) => {}
+                // Lost packets are recovered by resend (reliable) or superseded.
+                Err(TrySendError::Full | TrySendError::TooLarge) => {
+                    metrics::counter!("link.dropped_total", "reason" => "send").increment(1);
+                }
+                Err(TrySendError::Disconnected) => result = Err(TrySendError::Disconnected),
+            }
+        }
+        while let Some(bytes) = xport.try_recv() {
+            if self.receive(&bytes).is_err() {
+                metrics::counter!("link.dropped_total", "reason" => "malformed").increment(1);
+            }
+        }
+        result
+    }
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self::new(LinkConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(a: &mut Endpoint, b: &mut Endpoint, now: u64) {
+        for p in a.flush(now) {
+            b.receive(&p).expect("recv");
+        }
+        for p in b.flush(now) {
+            a.receive(&p).expect("recv");
+        }
+    }
+
+    #[test]
+    fn reliable_is_acked_and_retired() {
+        let mut a = Endpoint::default();
+        let mut b = Endpoint::default();
+        a.send(Channel::Reliable, b"hello".to_vec()).unwrap();
+        exchange(&mut a, &mut b, 0);
+        assert_eq!(b.recv(), Some((Channel::Reliable, b"hello".to_vec())));
+        assert_eq!(a.unacked(), 0);
+        // Nothing left to say once acks settle.
+        exchange(&mut a, &mut b, 1000);
+        assert!(a.flush(2000).is_empty());
+    }
+
+    #[test]
+    fn stale_unreliable_is_dropped() {
+        let mut a = Endpoint::default();
+        let mut b = Endpoint::default();
+        a.send(Channel::Unreliable, b"old".to_vec()).unwrap();
+        let old = a.flush(0);
+        a.send(Channel::Unreliable, b"new".to_vec()).unwrap();
+        let new = a.flush(1);
+        b.receive(&new[0]).unwrap();
+        b.receive(&old[0]).unwrap();
+        assert_eq!(b.recv(), Some((Channel::Unreliable, b"new".to_vec())));
+        assert_eq!(b.recv(), None);
+    }
+
+    #[test]
+    fn unreliable_streams_are_sequenced_apart() {
+        let mut a = Endpoint::default();
+        let mut b = Endpoint::default();
+        a.send_on(Channel::Unreliable, 1, b"snap".to_vec()).unwrap();
+        let snap = a.flush(0);
+        a.send_on(Channel::Unreliable, 2, b"hud".to_vec()).unwrap();
+        a.send_on(Channel::Unreliable, 2, b"hud2".to_vec()).unwrap();
+        let hud = a.flush(1);
+        // A newer HUD packet does not make the older snapshot stale.
+        b.receive(&hud[0]).unwrap();
+        b.receive(&snap[0]).unwrap();
+        assert_eq!(b.recv(), Some((Channel::Unreliable, b"hud".to_vec())));
+        assert_eq!(b.recv(), Some((Channel::Unreliable, b"hud2".to_vec())));
+        assert_eq!(b.recv(), Some((Channel::Unreliable, b"snap".to_vec())));
+        // Within a stream, latest still wins.
+        a.send_on(Channel::Unreliable, 1, b"old".to_vec()).unwrap();
+        let old = a.flush(2);
+        a.send_on(Channel::Unreliable, 1, b"new".to_vec()).unwrap();
+        b.receive(&a.flush(3)[0]).unwrap();
+        b.receive(&old[0]).unwrap();
+        assert_eq!(b.recv(), Some((Channel::Unreliable, b"new".to_vec())));
+        assert_eq!(b.recv(), None);
+    }
+
+    #[test]
+    fn oversized_messages_are_rejected_not_queued() {
+        let mut a = Endpoint::default();
+        let mut b = Endpoint::default();
+        let big = vec![7u8; crate::transport::udp::MAX_DATAGRAM + 1];
+        assert!(matches!(
+            a.send(Channel::Reliable, big.clone()),
+            Err(TrySendError::TooLarge)
+        ));
+        assert!(matches!(
+            a.send(Channel::Unreliable, big),
+            Err(TrySendError::TooLarge)
+        ));
+        assert_eq!(a.unacked(), 0);
+        // The largest accepted message still fits one datagram.
+        let max = vec![7u8; MAX_MESSAGE_BYTES];
+        a.send(Channel::Reliable, max.clone()).unwrap();
+        let pkts = a.flush(0);
+        assert_eq!(pkts.len(), 1);
+        assert_eq!(pkts[0].len(), crate::transport::udp::MAX_DATAGRAM);
+        b.receive(&pkts[0]).unwrap();
+        assert_eq!(b.recv(), Some((Channel::Reliable, max)));
+    }
+
+    #[test]
+    fn seq_newer_wraps() {
+        assert!(seq_newer(1, 0));
+        assert!(seq_newer(0, u16::MAX));
+        assert!(!seq_newer(u16::MAX, 0));
+        assert!(!seq_newer(5, 5));
+    }
+}
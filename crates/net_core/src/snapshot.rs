@@ -30,11 +30,14 @@ pub struct ChunkMeshDelta {
     pub indices: Vec<u32>,
 }
 
-const VERSION: u8 = 1;
-const ACTOR_SNAP_VERSION: u8 = 2;
-const ACTOR_SNAP_DELTA_VERSION: u8 = 4;
+/// Leading version byte of the (untagged) destructible instance and chunk mesh messages.
+pub const VERSION: u8 = 1;
+pub const ACTOR_SNAP_VERSION: u8 = 2;
+pub const ACTOR_SNAP_DELTA_VERSION: u8 = 6;
 pub const TAG_ACTOR_SNAPSHOT: u8 = 0xA2;
 pub const TAG_ACTOR_SNAPSHOT_DELTA: u8 = 0xA3;
+/// `ActorRep::state` bit: the actor is mid-dodge (dash with i-frames).
+pub const ACTOR_STATE_DODGING: u8 = 1;
 // Legacy TickSnapshot tag removed; ActorSnapshot v2 is canonical.
 const MAX_MESH_ELEMS: usize = 262_144; // conservative cap to prevent OOM
 
@@ -159,6 +162,9 @@ pub struct ActorRep {
     pub hp: i32,
     pub max: i32,
     pub alive: bool,
+    /// Presentation state bits (`ACTOR_STATE_*`) that drive client animation.
+    /// Not carried by `ActorSnapshot` v2.
+    pub state: u8,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -277,6 +283,7 @@ impl SnapshotDecode for ActorSnapshot {
                 hp,
                 max,
                 alive,
+                state: 0,
             });
         }
         let np = u32::from_le_bytes(take::<4>(inp)?) as usize;
@@ -312,18 +319,22 @@ impl SnapshotDecode for ActorSnapshot {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ActorDeltaRec {
     pub id: u32,
-    pub flags: u8, // 1=pos,2=yaw,4=hp,8=alive
+    pub flags: u8, // 1=pos,2=yaw,4=hp,8=alive,16=state
     pub qpos: [i32; 3],
     pub qyaw: u16,
     pub hp: i32,
     pub alive: u8,
+    pub state: u8,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ActorSnapshotDelta {
-    pub v: u8, // must be 3
+    pub v: u8, // must be 6
     pub tick: u64,
     pub baseline: u64,
+    /// Newest client input (`ClientCmd::Move::seq`) applied before this
+    /// tick's state was captured; 0 when none. Per-client, for reconciliation.
+    pub input_seq: u32,
     pub spawns: Vec<ActorRep>,
     pub updates: Vec<ActorDeltaRec>,
     pub removals: Vec<u32>,
@@ -334,16 +345,24 @@ pub struct ActorSnapshotDelta {
 /// Lightweight hit effect for client VFX without client-side gameplay.
 #[derive(Debug, Clone, PartialEq)]
 pub struct HitFx {
-    pub kind: u8,      // 0=Firebolt, 1=Fireball (optional), 2=MagicMissile, etc.
+    pub kind: u8,      // 0=Firebolt, 1=Fireball (optional), 2=MagicMissile, HITFX_WEAPON_*
     pub pos: [f32; 3], // world-space position
 }
 
+/// `HitFx::kind` for a weapon attack that hit.
+pub const HITFX_WEAPON_HIT: u8 = 3;
+/// `HitFx::kind` for a weapon attack that missed (at the target's position).
+pub const HITFX_WEAPON_MISS: u8 = 4;
+/// `HitFx::kind` for a critical weapon hit.
+pub const HITFX_WEAPON_CRIT: u8 = 5;
+
 impl SnapshotEncode for ActorSnapshotDelta {
     fn encode(&self, out: &mut Vec<u8>) {
         out.push(TAG_ACTOR_SNAPSHOT_DELTA);
         out.push(self.v);
         out.extend_from_slice(&self.tick.to_le_bytes());
         out.extend_from_slice(&self.baseline.to_le_bytes());
+        out.extend_from_slice(&self.input_seq.to_le_bytes());
         // spawns (v4 layout only)
         let ns = u32::try_from(self.spawns.len()).unwrap_or(0);
         out.extend_from_slice(&ns.to_le_bytes());
@@ -362,6 +381,7 @@ impl SnapshotEncode for ActorSnapshotDelta {
             out.extend_from_slice(&a.hp.to_le_bytes());
             out.extend_from_slice(&a.max.to_le_bytes());
             out.push(u8::from(a.alive));
+            out.push(a.state);
         }
         // updates
         let nu = u32::try_from(self.updates.len()).unwrap_or(0);
@@ -383,6 +403,9 @@ impl SnapshotEncode for ActorSnapshotDelta {
             if u.flags & 8 != 0 {
                 out.push(u.alive);
             }
+            if u.flags & 16 != 0 {
+                out.push(u.state);
+            }
         }
         // removals
         let nr = u32::try_from(self.removals.len()).unwrap_or(0);
@@ -447,6 +470,7 @@ impl SnapshotDecode for ActorSnapshotDelta {
         }
         let tick = u64::from_le_bytes(take::<8>(inp)?);
         let baseline = u64::from_le_bytes(take::<8>(inp)?);
+        let input_seq = u32::from_le_bytes(take::<4>(inp)?);
         // spawns
         let ns = u32::from_le_bytes(take::<4>(inp)?) as usize;
         let mut spawns = Vec::with_capacity(ns);
@@ -484,6 +508,7 @@ impl SnapshotDecode for ActorSnapshotDelta {
                 None => anyhow::bail!("short read"),
             };
             *inp = &inp[1..];
+            let [state] = take::<1>(inp)?;
             spawns.push(ActorRep {
                 id,
                 kind,
@@ -497,6 +522,7 @@ impl SnapshotDecode for ActorSnapshotDelta {
                 hp,
                 max,
                 alive,
+                state,
             });
         }
         // updates
@@ -537,6 +563,10 @@ impl SnapshotDecode for ActorSnapshotDelta {
                     b
                 };
             }
+            let mut state = 0u8;
+            if flags & 16 != 0 {
+                [state] = take::<1>(inp)?;
+            }
             updates.push(ActorDeltaRec {
                 id,
                 flags,
@@ -544,6 +574,7 @@ impl SnapshotDecode for ActorSnapshotDelta {
                 qyaw,
                 hp,
                 alive,
+                state,
             });
         }
         // removals
@@ -597,6 +628,7 @@ impl SnapshotDecode for ActorSnapshotDelta {
             v,
             tick,
             baseline,
+            input_seq,
             spawns,
             updates,
             removals,
@@ -635,7 +667,7 @@ pub fn dqyaw(y: u16) -> f32 {
 // ---------------------------------------------------------------------------
 
 pub const TAG_HUD_STATUS: u8 = 0xB1;
-pub const HUD_STATUS_VERSION: u8 = 1;
+pub const HUD_STATUS_VERSION: u8 = 2;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct HudStatusMsg {
@@ -644,9 +676,6 @@ pub struct HudStatusMsg {
     pub mana_max: u16,
     pub gcd_ms: u16,
     pub spell_cds: Vec<(u8, u16)>,
-    pub burning_ms: u16,
-    pub slow_ms: u16,
-    pub stunned_ms: u16,
 }
 
 impl SnapshotEncode for HudStatusMsg {
@@ -662,9 +691,6 @@ impl SnapshotEncode for HudStatusMsg {
             out.push(*id);
             out.extend_from_slice(&ms.to_le_bytes());
         }
-        out.extend_from_slice(&self.burning_ms.to_le_bytes());
-        out.extend_from_slice(&self.slow_ms.to_le_bytes());
-        out.extend_from_slice(&self.stunned_ms.to_le_bytes());
     }
 }
 
@@ -715,18 +741,12 @@ impl SnapshotDecode for HudStatusMsg {
             let ms = u16::from_le_bytes(take::<2>(inp)?);
             spell_cds.push((id, ms));
         }
-        let burning_ms = u16::from_le_bytes(take::<2>(inp)?);
-        let slow_ms = u16::from_le_bytes(take::<2>(inp)?);
-        let stunned_ms = u16::from_le_bytes(take::<2>(inp)?);
         Ok(HudStatusMsg {
             v,
             mana,
             mana_max,
             gcd_ms,
             spell_cds,
-            burning_ms,
-            slow_ms,
-            stunned_ms,
         })
     }
 }
@@ -781,6 +801,355 @@ impl SnapshotDecode for HudToastMsg {
         Ok(HudToastMsg { v, code })
     }
 }
+
+// ---------------------------------------------------------------------------
+// Boss telegraphs (ground decals for wind-up windows)
+// ---------------------------------------------------------------------------
+
+pub const TAG_TELEGRAPH: u8 = 0xB3;
+pub const TELEGRAPH_VERSION: u8 = 1;
+
+/// Telegraph shape: a filled circle around `pos`.
+pub const TELEGRAPH_CIRCLE: u8 = 0;
+/// Telegraph shape: a cone from `pos` facing `yaw`, spanning `2 * half_angle`.
+pub const TELEGRAPH_CONE: u8 = 1;
+
+/// One active wind-up window; the ability resolves when `remaining_ms` hits 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelegraphRep {
+    pub id: u32,
+    /// Actor casting the ability.
+    pub owner: u32,
+    pub shape: u8,
+    pub pos: [f32; 3],
+    pub yaw: f32,
+    pub radius: f32,
+    /// Cone half angle in radians (0 for circles).
+    pub half_angle: f32,
+    pub total_ms: u16,
+    pub remaining_ms: u16,
+}
+
+/// Telegraphs in a client's interest this tick. Sent unreliably while any are
+/// active, plus one empty message after the last one resolves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelegraphMsg {
+    pub v: u8,
+    pub items: Vec<TelegraphRep>,
+}
+
+impl SnapshotEncode for TelegraphMsg {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_TELEGRAPH);
+        out.push(self.v);
+        let n = u8::try_from(self.items.len()).unwrap_or(u8::MAX);
+        out.push(n);
+        for t in self.items.iter().take(usize::from(n)) {
+            out.extend_from_slice(&t.id.to_le_bytes());
+            out.extend_from_slice(&t.owner.to_le_bytes());
+            out.push(t.shape);
+            for c in t.pos {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+            out.extend_from_slice(&t.yaw.to_le_bytes());
+            out.extend_from_slice(&t.radius.to_le_bytes());
+            out.extend_from_slice(&t.half_angle.to_le_bytes());
+            out.extend_from_slice(&t.total_ms.to_le_bytes());
+            out.extend_from_slice(&t.remaining_ms.to_le_bytes());
+        }
+    }
+}
+
+impl SnapshotDecode for TelegraphMsg {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        use anyhow::bail;
+        fn take<const N: usize>(inp: &mut &[u8]) -> anyhow::Result<[u8; N]> {
+            if inp.len() < N {
+                anyhow::bail!("short read");
+            }
+            let (a, b) = inp.split_at(N);
+            *inp = b;
+            let mut buf = [0u8; N];
+            buf.copy_from_slice(a);
+            Ok(buf)
+        }
+        let f32_le =
+            |inp: &mut &[u8]| -> anyhow::Result<f32> { Ok(f32::from_le_bytes(take::<4>(inp)?)) };
+        let [tag] = take::<1>(inp)?;
+        if tag != TAG_TELEGRAPH {
+            bail!("not a Telegraph tag");
+        }
+        let [v] = take::<1>(inp)?;
+        if v != TELEGRAPH_VERSION {
+            bail!("unsupported version: {v}");
+        }
+        let [n] = take::<1>(inp)?;
+        let mut items = Vec::with_capacity(usize::from(n));
+        for _ in 0..n {
+            let id = u32::from_le_bytes(take::<4>(inp)?);
+            let owner = u32::from_le_bytes(take::<4>(inp)?);
+            let [shape] = take::<1>(inp)?;
+            let pos = [f32_le(inp)?, f32_le(inp)?, f32_le(inp)?];
+            let yaw = f32_le(inp)?;
+            let radius = f32_le(inp)?;
+            let half_angle = f32_le(inp)?;
+            let total_ms = u16::from_le_bytes(take::<2>(inp)?);
+            let remaining_ms = u16::from_le_bytes(take::<2>(inp)?);
+            items.push(TelegraphRep {
+                id,
+                owner,
+                shape,
+                pos,
+                yaw,
+                radius,
+                half_angle,
+                total_ms,
+                remaining_ms,
+            });
+        }
+        Ok(TelegraphMsg { v, items })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Status effects (buff/debuff icons)
+// ---------------------------------------------------------------------------
+
+pub const TAG_STATUS: u8 = 0xB4;
+pub const STATUS_VERSION: u8 = 1;
+
+/// One active status on one actor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusRep {
+    pub actor: u32,
+    /// `StatusSpec::net_id` from `data/config/statuses.toml`.
+    pub status: u16,
+    pub stacks: u8,
+    pub remaining_ms: u16,
+}
+
+/// Active statuses on the actors in a client's interest. Sent unreliably while
+/// any are active, plus one empty message after the last one ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMsg {
+    pub v: u8,
+    pub items: Vec<StatusRep>,
+}
+
+impl SnapshotEncode for StatusMsg {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_STATUS);
+        out.push(self.v);
+        let n = u16::try_from(self.items.len()).unwrap_or(u16::MAX);
+        out.extend_from_slice(&n.to_le_bytes());
+        for s in self.items.iter().take(usize::from(n)) {
+            out.extend_from_slice(&s.actor.to_le_bytes());
+            out.extend_from_slice(&s.status.to_le_bytes());
+            out.push(s.stacks);
+            out.extend_from_slice(&s.remaining_ms.to_le_bytes());
+        }
+    }
+}
+
+impl SnapshotDecode for StatusMsg {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        use anyhow::bail;
+        fn take<const N: usize>(inp: &mut &[u8]) -> anyhow::Result<[u8; N]> {
+            if inp.len() < N {
+                anyhow::bail!("short read");
+            }
+            let (a, b) = inp.split_at(N);
+            *inp = b;
+            let mut buf = [0u8; N];
+            buf.copy_from_slice(a);
+            Ok(buf)
+        }
+        let [tag] = take::<1>(inp)?;
+        if tag != TAG_STATUS {
+            bail!("not a Status tag");
+        }
+        let [v] = take::<1>(inp)?;
+        if v != STATUS_VERSION {
+            bail!("unsupported version: {v}");
+        }
+        let n = u16::from_le_bytes(take::<2>(inp)?);
+        let mut items = Vec::with_capacity(usize::from(n).min(inp.len() / 9));
+        for _ in 0..n {
+            let actor = u32::from_le_bytes(take::<4>(inp)?);
+            let status = u16::from_le_bytes(take::<2>(inp)?);
+            let [stacks] = take::<1>(inp)?;
+            let remaining_ms = u16::from_le_bytes(take::<2>(inp)?);
+            items.push(StatusRep {
+                actor,
+                status,
+                stacks,
+                remaining_ms,
+            });
+        }
+        Ok(StatusMsg { v, items })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Inventory (owner only)
+// ---------------------------------------------------------------------------
+
+pub const TAG_INVENTORY: u8 = 0xB5;
+pub const INVENTORY_VERSION: u8 = 1;
+
+/// `InventoryItemRep::worn` values.
+pub const WORN_NONE: u8 = 0;
+pub const WORN_MAIN_HAND: u8 = 1;
+pub const WORN_OFF_HAND: u8 = 2;
+pub const WORN_BODY: u8 = 3;
+
+/// One carried stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryItemRep {
+    /// Item id from `data/items/*.json`.
+    pub item: String,
+    pub count: u16,
+    /// Slot the item is worn in (`WORN_*`).
+    pub worn: u8,
+}
+
+/// The receiving client's whole inventory. Sent reliably when it changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryMsg {
+    pub v: u8,
+    /// Stacks the bag holds.
+    pub slots: u8,
+    pub items: Vec<InventoryItemRep>,
+}
+
+impl SnapshotEncode for InventoryMsg {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_INVENTORY);
+        out.push(self.v);
+        out.push(self.slots);
+        let n = u8::try_from(self.items.len()).unwrap_or(u8::MAX);
+        out.push(n);
+        for it in self.items.iter().take(usize::from(n)) {
+            crate::command::encode_id(out, &it.item);
+            out.extend_from_slice(&it.count.to_le_bytes());
+            out.push(it.worn);
+        }
+    }
+}
+
+impl SnapshotDecode for InventoryMsg {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        use anyhow::bail;
+        fn take<const N: usize>(inp: &mut &[u8]) -> anyhow::Result<[u8; N]> {
+            if inp.len() < N {
+                anyhow::bail!("short read");
+            }
+            let (a, b) = inp.split_at(N);
+            *inp = b;
+            let mut buf = [0u8; N];
+            buf.copy_from_slice(a);
+            Ok(buf)
+        }
+        let [tag, v, slots, n] = take::<4>(inp)?;
+        if tag != TAG_INVENTORY {
+            bail!("not an Inventory tag");
+        }
+        if v != INVENTORY_VERSION {
+            bail!("unsupported version: {v}");
+        }
+        let mut items = Vec::with_capacity(usize::from(n));
+        for _ in 0..n {
+            let item = crate::command::decode_id(inp)?;
+            let count = u16::from_le_bytes(take::<2>(inp)?);
+            let [worn] = take::<1>(inp)?;
+            items.push(InventoryItemRep { item, count, worn });
+        }
+        Ok(InventoryMsg { v, slots, items })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Loot drops (owner only)
+// ---------------------------------------------------------------------------
+
+pub const TAG_LOOT: u8 = 0xB6;
+pub const LOOT_VERSION: u8 = 1;
+
+/// One item lying on the ground for the receiving client to pick up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootDropRep {
+    /// Server drop id, named by `ClientCmd::PickUp`.
+    pub id: u32,
+    /// Item id from `data/items/*.json`.
+    pub item: String,
+    pub count: u16,
+    pub pos: [f32; 3],
+}
+
+/// The receiving client's drops in interest. Personal: nobody else sees
+/// them. Sent reliably when the set changes; an empty message clears it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootMsg {
+    pub v: u8,
+    pub items: Vec<LootDropRep>,
+}
+
+impl SnapshotEncode for LootMsg {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TAG_LOOT);
+        out.push(self.v);
+        let n = u8::try_from(self.items.len()).unwrap_or(u8::MAX);
+        out.push(n);
+        for d in self.items.iter().take(usize::from(n)) {
+            out.extend_from_slice(&d.id.to_le_bytes());
+            crate::command::encode_id(out, &d.item);
+            out.extend_from_slice(&d.count.to_le_bytes());
+            for c in d.pos {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+    }
+}
+
+impl SnapshotDecode for LootMsg {
+    fn decode(inp: &mut &[u8]) -> anyhow::Result<Self> {
+        use anyhow::bail;
+        fn take<const N: usize>(inp: &mut &[u8]) -> anyhow::Result<[u8; N]> {
+            if inp.len() < N {
+                anyhow::bail!("short read");
+            }
+            let (a, b) = inp.split_at(N);
+            *inp = b;
+            let mut buf = [0u8; N];
+            buf.copy_from_slice(a);
+            Ok(buf)
+        }
+        let [tag, v, n] = take::<3>(inp)?;
+        if tag != TAG_LOOT {
+            bail!("not a Loot tag");
+        }
+        if v != LOOT_VERSION {
+            bail!("unsupported version: {v}");
+        }
+        let mut items = Vec::with_capacity(usize::from(n));
+        for _ in 0..n {
+            let id = u32::from_le_bytes(take::<4>(inp)?);
+            let item = crate::command::decode_id(inp)?;
+            let count = u16::from_le_bytes(take::<2>(inp)?);
+            let mut pos = [0.0f32; 3];
+            for c in &mut pos {
+                *c = f32::from_le_bytes(take::<4>(inp)?);
+            }
+            items.push(LootDropRep {
+                id,
+                item,
+                count,
+                pos,
+            });
+        }
+        Ok(LootMsg { v, items })
+    }
+}
 #[derive(Debug, Clone, PartialEq)]
 pub struct WizardRep {
     pub id: u32,
@@ -936,6 +1305,7 @@ mod tests {
             v: ACTOR_SNAP_DELTA_VERSION,
             tick: 42,
             baseline: 40,
+            input_seq: 7,
             spawns: vec![ActorRep {
                 id: 1,
                 kind: 0,
@@ -949,14 +1319,16 @@ mod tests {
                 hp: 99,
                 max: 100,
                 alive: true,
+                state: ACTOR_STATE_DODGING,
             }],
             updates: vec![ActorDeltaRec {
                 id: 1,
-                flags: 1 | 2 | 4 | 8,
+                flags: 1 | 2 | 4 | 8 | 16,
                 qpos: [qpos(1.0), qpos(2.0), qpos(3.0)],
                 qyaw: qyaw(0.5),
                 hp: 98,
                 alive: 1,
+                state: ACTOR_STATE_DODGING,
             }],
             removals: vec![2, 3],
             projectiles: vec![ProjectileRep {
@@ -977,8 +1349,9 @@ mod tests {
         assert_eq!(dec.v, ACTOR_SNAP_DELTA_VERSION);
         assert_eq!(dec.tick, 42);
         assert_eq!(dec.baseline, 40);
-        assert_eq!(dec.spawns.len(), 1);
-        assert_eq!(dec.updates.len(), 1);
+        assert_eq!(dec.input_seq, 7);
+        assert_eq!(dec.spawns, delta.spawns);
+        assert_eq!(dec.updates, delta.updates);
         assert_eq!(dec.removals, vec![2, 3]);
         assert_eq!(dec.projectiles.len(), 1);
         assert_eq!(dec.hits.len(), 1);
@@ -1004,9 +1377,6 @@ mod tests {
             mana_max: 20,
             gcd_ms: 250,
             spell_cds: vec![(0, 0), (1, 1500), (2, 500)],
-            burning_ms: 1000,
-            slow_ms: 500,
-            stunned_ms: 0,
         };
         let mut buf = Vec::new();
         msg.encode(&mut buf);
@@ -1015,6 +1385,85 @@ mod tests {
         assert_eq!(msg, dec);
     }
 
+    #[test]
+    fn status_roundtrip() {
+        let msg = StatusMsg {
+            v: STATUS_VERSION,
+            items: vec![
+                StatusRep {
+                    actor: 7,
+                    status: 1,
+                    stacks: 1,
+                    remaining_ms: 2500,
+                },
+                StatusRep {
+                    actor: 9,
+                    status: 4,
+                    stacks: 3,
+                    remaining_ms: 600,
+                },
+            ],
+        };
+        let mut buf = Vec::new();
+        msg.encode(&mut buf);
+        let dec = StatusMsg::decode(&mut buf.as_slice()).expect("decode");
+        assert_eq!(msg, dec);
+        buf.truncate(buf.len() - 1);
+        assert!(StatusMsg::decode(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn inventory_roundtrip() {
+        let msg = InventoryMsg {
+            v: INVENTORY_VERSION,
+            slots: 20,
+            items: vec![
+                InventoryItemRep {
+                    item: "potion_healing".into(),
+                    count: 3,
+                    worn: WORN_NONE,
+                },
+                InventoryItemRep {
+                    item: "shield".into(),
+                    count: 1,
+                    worn: WORN_OFF_HAND,
+                },
+            ],
+        };
+        let mut buf = Vec::new();
+        msg.encode(&mut buf);
+        let dec = InventoryMsg::decode(&mut buf.as_slice()).expect("decode");
+        assert_eq!(msg, dec);
+        buf.truncate(buf.len() - 1);
+        assert!(InventoryMsg::decode(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn loot_roundtrip() {
+        let msg = LootMsg {
+            v: LOOT_VERSION,
+            items: vec![LootDropRep {
+                id: 7,
+                item: "potion_healing".into(),
+                count: 2,
+                pos: [1.0, 0.6, -3.5],
+            }],
+        };
+        let mut buf = Vec::new();
+        msg.encode(&mut buf);
+        let dec = LootMsg::decode(&mut buf.as_slice()).expect("decode");
+        assert_eq!(msg, dec);
+        buf.truncate(buf.len() - 1);
+        assert!(LootMsg::decode(&mut buf.as_slice()).is_err());
+        let clear = LootMsg {
+            v: LOOT_VERSION,
+            items: Vec::new(),
+        };
+        let mut buf = Vec::new();
+        clear.encode(&mut buf);
+        assert_eq!(LootMsg::decode(&mut buf.as_slice()).expect("decode"), clear);
+    }
+
     #[test]
     fn hud_status_rejects_bad() {
         let buf = vec![0xEE, 1, 0, 0];
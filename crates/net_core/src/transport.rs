@@ -2,14 +2,31 @@
 //!
 //! Implementations:
 //! - `LocalLoopbackTransport`: in-proc bounded channels for demo/local server
-//! - (future) `WebSocketTransport`: remote connection
+//! - `udp::UdpTransport` / `udp::UdpListener`: native UDP, one frame per datagram
+//! - `ws::WsTransport` / `ws::WsListener`: native WebSocket (server + native client)
+//! - `ws_web::WsWebTransport`: browser WebSocket client (wasm32 only)
+//! - `lossy::LossyTransport`: seeded loss/reorder wrapper for tests
+//!
+//! Every transport carries whole messages produced by `frame::write_msg`.
+//! Socket-backed receivers validate the frame header and drop malformed input
+//! (`transport.rejected_total`), so callers can keep using `frame::read_msg`.
 
 use crate::channel;
 
+pub mod lossy;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod udp;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ws;
+#[cfg(target_arch = "wasm32")]
+pub mod ws_web;
+
 #[derive(Debug)]
 pub enum TrySendError {
     Full,
     Disconnected,
+    /// Message exceeds what the transport can carry in one unit (e.g., a datagram).
+    TooLarge,
 }
 
 /// Minimal transport trait for byte messages.
@@ -19,6 +36,16 @@ pub trait Transport: Send + Sync {
     fn depth(&self) -> usize;
 }
 
+/// Returns true if `bytes` is a well-formed frame; counts rejections by transport kind.
+pub(crate) fn accept_frame(bytes: &[u8], kind: &'static str) -> bool {
+    if crate::frame::read_msg(bytes).is_ok() {
+        true
+    } else {
+        metrics::counter!("transport.rejected_total", "kind" => kind).increment(1);
+        false
+    }
+}
+
 /// In-process loopback using crossbeam bounded channels.
 #[derive(Clone)]
 pub struct LocalLoopbackTransport {
@@ -0,0 +1,132 @@
+//! Lossy/reordering wrapper for any `Transport` (tests and soak tools).
+//!
+//! Outbound messages pass through a seeded `LossModel`: each one may be
+//! dropped, duplicated, or held back for a few subsequent sends so it arrives
+//! out of order. The same seed always yields the same fate sequence, which
+//! keeps loss tests deterministic.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use super::{Transport, TrySendError};
+
+/// Seeded packet fate model.
+#[derive(Debug, Clone)]
+pub struct LossModel {
+    /// Probability a message is silently dropped.
+    pub loss: f32,
+    /// Probability a surviving message is delayed (reordered).
+    pub reorder: f32,
+    /// A delayed message is released after 1..=`max_delay` later sends.
+    pub max_delay: u32,
+    /// Probability a surviving message is delivered twice.
+    pub duplicate: f32,
+    rng: u64,
+}
+
+impl LossModel {
+    #[must_use]
+    pub fn new(seed: u64, loss: f32, reorder: f32) -> Self {
+        Self {
+            loss,
+            reorder,
+            max_delay: 3,
+            duplicate: 0.0,
+            rng: seed,
+        }
+    }
+
+    /// `SplitMix64` step mapped to [0, 1).
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f32(&mut self) -> f32 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+struct State {
+    model: LossModel,
+    /// (sends remaining before release, bytes)
+    held: VecDeque<(u32, Vec<u8>)>,
+}
+
+/// Applies a `LossModel` to everything sent through `inner`.
+pub struct LossyTransport<T: Transport> {
+    inner: T,
+    state: Mutex<State>,
+}
+
+impl<T: Transport> LossyTransport<T> {
+    pub fn new(inner: T, model: LossModel) -> Self {
+        Self {
+            inner,
+            state: Mutex::new(State {
+                model,
+                held: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Release everything still held back (e.g., at the end of a test).
+    pub fn flush_held(&self) {
+        let held: Vec<Vec<u8>> = match self.state.lock() {
+            Ok(mut st) => st.held.drain(..).map(|(_, b)| b).collect(),
+            Err(_) => return,
+        };
+        for b in held {
+            let _ = self.inner.try_send(b);
+        }
+    }
+}
+
+impl<T: Transport> Transport for LossyTransport<T> {
+    fn try_send(&self, bytes: Vec<u8>) -> Result<(), TrySendError> {
+        let mut release = Vec::new();
+        {
+            let Ok(mut st) = self.state.lock() else {
+                return Err(TrySendError::Disconnected);
+            };
+            // Age held messages; anything due goes out after this send.
+            for h in &mut st.held {
+                h.0 = h.0.saturating_sub(1);
+            }
+            while st.held.front().is_some_and(|h| h.0 == 0) {
+                if let Some((_, b)) = st.held.pop_front() {
+                    release.push(b);
+                }
+            }
+            if st.model.next_f32() < st.model.loss {
+                metrics::counter!("transport.lossy_dropped_total").increment(1);
+            } else if st.model.next_f32() < st.model.reorder {
+                let span = st.model.max_delay.max(1);
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    clippy::cast_precision_loss
+                )]
+                let delay = 1 + (st.model.next_f32() * span as f32) as u32;
+                let at = st.held.partition_point(|h| h.0 <= delay);
+                st.held.insert(at, (delay.min(span), bytes));
+            } else {
+                if st.model.next_f32() < st.model.duplicate {
+                    release.insert(0, bytes.clone());
+                }
+                release.insert(0, bytes);
+            }
+        }
+        for b in release {
+            self.inner.try_send(b)?;
+        }
+        Ok(())
+    }
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.inner.try_recv()
+    }
+    fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+}
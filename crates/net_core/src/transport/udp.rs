@@ -0,0 +1,241 @@
+//! UDP transport (native only).
+//!
+//! Each datagram carries exactly one framed message (`frame::write_msg`).
+//! Delivery is unreliable and unordered; sequencing/acks belong to the layer
+//! above. Datagrams that fail frame validation are dropped on receive.
+//!
+//! - `UdpTransport`: client side, a socket connected to a single server.
+//! - `UdpListener`: server side, demultiplexes one socket into per-peer
+//!   `UdpPeerTransport`s. A peer is accepted on its first valid datagram.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use super::{Transport, TrySendError, accept_frame};
+
+/// Largest payload we put in a single datagram (IPv4 limit minus headers).
+pub const MAX_DATAGRAM: usize = 65_507;
+/// Per-peer receive queue cap; the oldest datagram is dropped beyond this.
+const INBOX_CAP: usize = 4096;
+
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn recv_buf() -> Box<[u8]> {
+    vec![0u8; MAX_DATAGRAM].into_boxed_slice()
+}
+
+fn push_capped(q: &mut VecDeque<Vec<u8>>, bytes: Vec<u8>) {
+    if q.len() >= INBOX_CAP {
+        q.pop_front();
+        metrics::counter!("replication.dropped_total", "reason" => "udp_inbox_full").increment(1);
+    }
+    q.push_back(bytes);
+}
+
+fn map_send_err(e: &std::io::Error) -> TrySendError {
+    match e.kind() {
+        ErrorKind::WouldBlock => TrySendError::Full,
+        _ => TrySendError::Disconnected,
+    }
+}
+
+/// Client-side UDP transport connected to a single peer.
+pub struct UdpTransport {
+    sock: UdpSocket,
+    /// Receive buffer reused by every `pump`.
+    buf: Mutex<Box<[u8]>>,
+    inbox: Mutex<VecDeque<Vec<u8>>>,
+}
+
+impl UdpTransport {
+    /// Bind `bind` (use port 0 for ephemeral) and connect to `peer`.
+    pub fn connect(bind: impl ToSocketAddrs, peer: impl ToSocketAddrs) -> anyhow::Result<Self> {
+        let sock = UdpSocket::bind(bind)?;
+        sock.connect(peer)?;
+        sock.set_nonblocking(true)?;
+        Ok(Self {
+            sock,
+            buf: Mutex::new(recv_buf()),
+            inbox: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    pub fn local_addr(&self) -> anyhow::Result<SocketAddr> {
+        Ok(self.sock.local_addr()?)
+    }
+
+    /// Move all datagrams currently queued in the socket into the inbox.
+    fn pump(&self) {
+        let mut buf = lock(&self.buf);
+        let mut inbox = lock(&self.inbox);
+        loop {
+            match self.sock.recv(&mut buf) {
+                Ok(n) => {
+                    if accept_frame(&buf[..n], "udp") {
+                        push_capped(&mut inbox, buf[..n].to_vec());
+                    }
+                }
+                // ICMP port-unreachable from an earlier send; keep draining.
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused => {}
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl Transport for UdpTransport {
+    fn try_send(&self, bytes: Vec<u8>) -> Result<(), TrySendError> {
+        if bytes.len() > MAX_DATAGRAM {
+            return Err(TrySendError::TooLarge);
+        }
+        self.sock
+            .send(&bytes)
+            .map(|_| ())
+            .map_err(|e| map_send_err(&e))
+    }
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.pump();
+        lock(&self.inbox).pop_front()
+    }
+    fn depth(&self) -> usize {
+        self.pump();
+        lock(&self.inbox).len()
+    }
+}
+
+#[derive(Default)]
+struct Peers {
+    inboxes: HashMap<SocketAddr, VecDeque<Vec<u8>>>,
+    pending: VecDeque<SocketAddr>,
+}
+
+struct Shared {
+    sock: UdpSocket,
+    /// Receive buffer reused by every `pump`.
+    buf: Mutex<Box<[u8]>>,
+    peers: Mutex<Peers>,
+}
+
+impl Shared {
+    /// Route all queued datagrams to their peer inboxes, registering new peers.
+    fn pump(&self) {
+        let mut buf = lock(&self.buf);
+        let mut peers = lock(&self.peers);
+        loop {
+            match self.sock.recv_from(&mut buf) {
+                Ok((n, from)) => {
+                    if !accept_frame(&buf[..n], "udp") {
+                        continue;
+                    }
+                    let peers = &mut *peers;
+                    let inbox = peers.inboxes.entry(from).or_insert_with(|| {
+                        peers.pending.push_back(from);
+                        VecDeque::new()
+                    });
+                    push_capped(inbox, buf[..n].to_vec());
+                }
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused => {}
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Server-side UDP endpoint that hands out one transport per remote peer.
+pub struct UdpListener {
+    shared: Arc<Shared>,
+}
+
+impl UdpListener {
+    pub fn bind(addr: impl ToSocketAddrs) -> anyhow::Result<Self> {
+        let sock = UdpSocket::bind(addr)?;
+        sock.set_nonblocking(true)?;
+        Ok(Self {
+            shared: Arc::new(Shared {
+                sock,
+                buf: Mutex::new(recv_buf()),
+                peers: Mutex::new(Peers::default()),
+            }),
+        })
+    }
+
+    pub fn local_addr(&self) -> anyhow::Result<SocketAddr> {
+        Ok(self.shared.sock.local_addr()?)
+    }
+
+    /// Return a transport for the next peer that has sent a valid frame, if any.
+    /// The peer's first datagram stays queued on the returned transport.
+    #[must_use]
+    pub fn try_accept(&self) -> Option<UdpPeerTransport> {
+        self.shared.pump();
+        let peer = lock(&self.shared.peers).pending.pop_front()?;
+        Some(UdpPeerTransport {
+            shared: Arc::clone(&self.shared),
+            peer,
+        })
+    }
+}
+
+/// Per-peer view of a `UdpListener` socket. Dropping it forgets the peer; a
+/// later datagram from the same address is accepted as a new peer.
+pub struct UdpPeerTransport {
+    shared: Arc<Shared>,
+    peer: SocketAddr,
+}
+
+impl UdpPeerTransport {
+    #[must_use]
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer
+    }
+}
+
+impl Transport for UdpPeerTransport {
+    fn try_send(&self, bytes: Vec<u8>) -> Result<(), TrySendError> {
+        if bytes.len() > MAX_DATAGRAM {
+            return Err(TrySendError::TooLarge);
+        }
+        self.shared
+            .sock
+            .send_to(&bytes, self.peer)
+            .map(|_| ())
+            .map_err(|e| map_send_err(&e))
+    }
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.shared.pump();
+        lock(&self.shared.peers)
+            .inboxes
+            .get_mut(&self.peer)
+            .and_then(VecDeque::pop_front)
+    }
+    fn depth(&self) -> usize {
+        self.shared.pump();
+        lock(&self.shared.peers)
+            .inboxes
+            .get(&self.peer)
+            .map_or(0, VecDeque::len)
+    }
+}
+
+impl Drop for UdpPeerTransport {
+    fn drop(&mut self) {
+        lock(&self.shared.peers).inboxes.remove(&self.peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn rejects_oversize_datagram() {
+        let srv = UdpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = srv.local_addr().expect("addr");
+        let cli = UdpTransport::connect("127.0.0.1:0", addr).expect("connect");
+        let big = vec![0u8; MAX_DATAGRAM + 1];
+        assert!(matches!(cli.try_send(big), Err(TrySendError::TooLarge)));
+    }
+}
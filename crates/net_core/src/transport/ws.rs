@@ -0,0 +1,213 @@
+//! WebSocket transport (native only, via `tungstenite`).
+//!
+//! Each binary WebSocket message carries exactly one framed message
+//! (`frame::write_msg`). Streams run non-blocking after the handshake so the
+//! `Transport` methods never stall a server tick.
+//!
+//! - `WsListener`: server side; accepts browser (`ws_web`) and native clients.
+//! - `WsTransport::connect`: native client (tools, tests, headless bots).
+
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use tungstenite::handshake::server::{NoCallback, ServerHandshake};
+use tungstenite::handshake::{HandshakeError, MidHandshake};
+use tungstenite::{Error as WsError, Message, WebSocket};
+
+use super::{Transport, TrySendError, accept_frame};
+
+/// Upper bound on a handshake before the connection is abandoned.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn would_block(e: &WsError) -> bool {
+    matches!(e, WsError::Io(io) if io.kind() == ErrorKind::WouldBlock)
+}
+
+type PendingWs = MidHandshake<ServerHandshake<TcpStream, NoCallback>>;
+
+#[derive(Default)]
+struct Accepting {
+    /// Handshakes still waiting on the peer, with their start time.
+    pending: Vec<(PendingWs, Instant)>,
+    /// Upgraded connections not yet handed out.
+    ready: VecDeque<WebSocket<TcpStream>>,
+}
+
+/// Non-blocking TCP listener that upgrades accepted connections to WebSocket.
+pub struct WsListener {
+    inner: TcpListener,
+    accepting: Mutex<Accepting>,
+}
+
+impl WsListener {
+    pub fn bind(addr: impl ToSocketAddrs) -> anyhow::Result<Self> {
+        let inner = TcpListener::bind(addr)?;
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner,
+            accepting: Mutex::new(Accepting::default()),
+        })
+    }
+
+    pub fn local_addr(&self) -> anyhow::Result<SocketAddr> {
+        Ok(self.inner.local_addr()?)
+    }
+
+    /// Number of connections still in their handshake.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        lock(&self.accepting).pending.len()
+    }
+
+    /// Take new connections and advance pending handshakes without blocking;
+    /// returns one upgraded connection, if any is ready. Call until `None` to
+    /// drain. Failed or timed-out handshakes are dropped.
+    pub fn try_accept(&self) -> anyhow::Result<Option<WsTransport>> {
+        let mut acc = lock(&self.accepting);
+        let acc = &mut *acc;
+        loop {
+            let stream = match self.inner.accept() {
+                Ok((s, _peer)) => s,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e.into()),
+            };
+            stream.set_nonblocking(true)?;
+            stream.set_nodelay(true)?;
+            match tungstenite::accept(stream) {
+                Ok(ws) => acc.ready.push_back(ws),
+                Err(HandshakeError::Interrupted(mid)) => acc.pending.push((mid, Instant::now())),
+                Err(HandshakeError::Failure(_)) => handshake_failed("error"),
+            }
+        }
+        for (mid, t0) in std::mem::take(&mut acc.pending) {
+            match mid.handshake() {
+                Ok(ws) => acc.ready.push_back(ws),
+                Err(HandshakeError::Interrupted(mid)) if t0.elapsed() < HANDSHAKE_TIMEOUT => {
+                    acc.pending.push((mid, t0));
+                }
+                Err(HandshakeError::Interrupted(_)) => handshake_failed("timeout"),
+                Err(HandshakeError::Failure(_)) => handshake_failed("error"),
+            }
+        }
+        acc.ready
+            .pop_front()
+            .map(WsTransport::from_socket)
+            .transpose()
+    }
+}
+
+fn handshake_failed(reason: &'static str) {
+    metrics::counter!("transport.ws_handshake_failed_total", "reason" => reason).increment(1);
+}
+
+/// WebSocket connection carrying framed replication messages.
+pub struct WsTransport {
+    ws: Mutex<WebSocket<TcpStream>>,
+    inbox: Mutex<VecDeque<Vec<u8>>>,
+    closed: AtomicBool,
+}
+
+impl WsTransport {
+    /// Connect to a `WsListener` at `addr` (e.g., `127.0.0.1:7000`).
+    pub fn connect(addr: impl ToSocketAddrs) -> anyhow::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let peer = stream.peer_addr()?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+        let (ws, _resp) = tungstenite::client(format!("ws://{peer}/"), stream)
+            .map_err(|e| anyhow::anyhow!("websocket handshake failed: {e}"))?;
+        Self::from_socket(ws)
+    }
+
+    fn from_socket(ws: WebSocket<TcpStream>) -> anyhow::Result<Self> {
+        ws.get_ref().set_read_timeout(None)?;
+        ws.get_ref().set_nonblocking(true)?;
+        Ok(Self {
+            ws: Mutex::new(ws),
+            inbox: Mutex::new(VecDeque::new()),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    pub fn peer_addr(&self) -> anyhow::Result<SocketAddr> {
+        Ok(lock(&self.ws).get_ref().peer_addr()?)
+    }
+
+    /// True once the peer closed the connection or an I/O error occurred.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// Read all available messages into the inbox and flush pending writes.
+    fn pump(&self) {
+        if self.is_closed() {
+            return;
+        }
+        let mut ws = lock(&self.ws);
+        let mut inbox = lock(&self.inbox);
+        loop {
+            match ws.read() {
+                Ok(Message::Binary(b)) => {
+                    if accept_frame(&b, "ws") {
+                        inbox.push_back(b);
+                    }
+                }
+                Ok(Message::Close(_)) => {
+                    self.closed.store(true, Ordering::Relaxed);
+                    break;
+                }
+                // Text/ping/pong: tungstenite queues pong replies itself
+                Ok(_) => {}
+                Err(e) if would_block(&e) => break,
+                Err(_) => {
+                    self.closed.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+        }
+        if let Err(e) = ws.flush()
+            && !would_block(&e)
+        {
+            self.closed.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Transport for WsTransport {
+    fn try_send(&self, bytes: Vec<u8>) -> Result<(), TrySendError> {
+        if self.is_closed() {
+            return Err(TrySendError::Disconnected);
+        }
+        match lock(&self.ws).send(Message::Binary(bytes)) {
+            Ok(()) => Ok(()),
+            // Queued in the write buffer; flushed on the next send/pump.
+            Err(e) if would_block(&e) => Ok(()),
+            Err(WsError::WriteBufferFull(_)) => {
+                metrics::counter!("replication.dropped_total", "reason" => "ws_write_full")
+                    .increment(1);
+                Err(TrySendError::Full)
+            }
+            Err(_) => {
+                self.closed.store(true, Ordering::Relaxed);
+                Err(TrySendError::Disconnected)
+            }
+        }
+    }
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.pump();
+        lock(&self.inbox).pop_front()
+    }
+    fn depth(&self) -> usize {
+        self.pump();
+        lock(&self.inbox).len()
+    }
+}
@@ -0,0 +1,88 @@
+//! Browser WebSocket client transport (wasm32 only).
+//!
+//! Wraps `web_sys::WebSocket` in binary mode. Incoming binary messages are
+//! queued by the `onmessage` callback and drained via `Transport::try_recv`.
+//! Pair with `ws::WsListener` on the server.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{BinaryType, MessageEvent, WebSocket};
+
+use super::{Transport, TrySendError, accept_frame};
+
+/// Bytes allowed in the browser's send buffer before `try_send` reports `Full`.
+const MAX_BUFFERED_BYTES: u32 = 4 * 1024 * 1024;
+
+pub struct WsWebTransport {
+    ws: WebSocket,
+    inbox: Rc<RefCell<VecDeque<Vec<u8>>>>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+// SAFETY: wasm32 builds without atomics are single-threaded; the socket and
+// its callback never leave the main thread. Required by `Transport: Send + Sync`.
+unsafe impl Send for WsWebTransport {}
+unsafe impl Sync for WsWebTransport {}
+
+impl WsWebTransport {
+    /// Open a connection to `url` (e.g., `ws://127.0.0.1:7000/`). The socket
+    /// connects asynchronously; `try_send` reports `Full` until it is open.
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        let ws = WebSocket::new(url).map_err(|e| anyhow::anyhow!("websocket open: {e:?}"))?;
+        ws.set_binary_type(BinaryType::Arraybuffer);
+        let inbox: Rc<RefCell<VecDeque<Vec<u8>>>> = Rc::new(RefCell::new(VecDeque::new()));
+        let sink = Rc::clone(&inbox);
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+            if let Ok(buf) = e.data().dyn_into::<js_sys::ArrayBuffer>() {
+                let bytes = js_sys::Uint8Array::new(&buf).to_vec();
+                if accept_frame(&bytes, "ws_web") {
+                    sink.borrow_mut().push_back(bytes);
+                }
+            }
+        });
+        ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        Ok(Self {
+            ws,
+            inbox,
+            _on_message: on_message,
+        })
+    }
+
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.ws.ready_state() == WebSocket::OPEN
+    }
+}
+
+impl Transport for WsWebTransport {
+    fn try_send(&self, bytes: Vec<u8>) -> Result<(), TrySendError> {
+        match self.ws.ready_state() {
+            WebSocket::CONNECTING => return Err(TrySendError::Full),
+            WebSocket::OPEN => {}
+            _ => return Err(TrySendError::Disconnected),
+        }
+        if self.ws.buffered_amount() > MAX_BUFFERED_BYTES {
+            return Err(TrySendError::Full);
+        }
+        self.ws
+            .send_with_u8_array(&bytes)
+            .map_err(|_| TrySendError::Disconnected)
+    }
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.inbox.borrow_mut().pop_front()
+    }
+    fn depth(&self) -> usize {
+        self.inbox.borrow().len()
+    }
+}
+
+impl Drop for WsWebTransport {
+    fn drop(&mut self) {
+        self.ws.set_onmessage(None);
+        let _ = self.ws.close();
+    }
+}
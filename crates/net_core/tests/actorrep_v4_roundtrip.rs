@@ -15,11 +15,13 @@ fn actorrep_v4_roundtrip() {
         hp: 9,
         max: 10,
         alive: true,
+        state: 0,
     };
     let delta = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 9,
         baseline: 8,
+        input_seq: 0,
         spawns: vec![spawn],
         updates: vec![],
         removals: vec![],
@@ -30,7 +32,7 @@ fn actorrep_v4_roundtrip() {
     delta.encode(&mut buf);
     let mut slice: &[u8] = &buf;
     let d2 = ActorSnapshotDelta::decode(&mut slice).expect("decode v4");
-    assert_eq!(d2.v, 4);
+    assert_eq!(d2.v, 6);
     assert_eq!(d2.tick, 9);
     assert_eq!(d2.baseline, 8);
     assert_eq!(d2.spawns.len(), 1);
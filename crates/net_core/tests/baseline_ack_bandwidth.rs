@@ -0,0 +1,159 @@
+//! Byte-count benchmark: baseline-acked deltas vs full snapshots under loss.
+//!
+//! Run with `--nocapture` to see the numbers.
+
+use net_core::baseline::{ActorSet, BaselineReceiver, BaselineTracker};
+use net_core::command::ClientCmd;
+use net_core::link::{Channel, Endpoint};
+use net_core::snapshot::{
+    ActorRep, ActorSnapshot, ActorSnapshotDelta, SnapshotDecode, SnapshotEncode,
+    TAG_ACTOR_SNAPSHOT, TAG_ACTOR_SNAPSHOT_DELTA, qpos,
+};
+use net_core::transport::lossy::{LossModel, LossyTransport};
+use net_core::transport::{LocalLoopbackTransport, Transport};
+
+const ACTORS: u32 = 64;
+const TICKS: u64 = 300;
+const TICK_MS: u64 = 33;
+
+/// Deterministic world: a quarter of the actors move each tick; hp ticks down
+/// now and then so some changes are one-off (lost unless re-sent).
+fn world(tick: u64) -> ActorSet {
+    (0..ACTORS)
+        .map(|id| {
+            let moves = (tick + u64::from(id)) / 4;
+            #[allow(clippy::cast_precision_loss)]
+            let x = moves as f32 * 0.25;
+            let hp = 100 - i32::try_from((tick + u64::from(id)) / 50).unwrap_or(0);
+            let a = ActorRep {
+                id,
+                kind: 1,
+                faction: 2,
+                archetype_id: 2,
+                name_id: 0,
+                unique: 0,
+                pos: [x, 0.6, f32::from(u16::try_from(id).unwrap_or(0))],
+                yaw: 0.0,
+                radius: 0.9,
+                hp,
+                max: 100,
+                alive: true,
+                state: 0,
+            };
+            (id, a)
+        })
+        .collect()
+}
+
+enum Mode {
+    AckedDeltas,
+    FullSnapshots,
+}
+
+struct Outcome {
+    bytes: usize,
+    client: ActorSet,
+}
+
+fn run(mode: &Mode, seed: u64, loss: f32) -> Outcome {
+    let (a, b) = LocalLoopbackTransport::new(8192);
+    let srv_x = LossyTransport::new(a, LossModel::new(seed, loss, 0.1));
+    let cli_x = LossyTransport::new(b, LossModel::new(seed ^ 0x5EED, loss, 0.1));
+    let mut srv = Endpoint::default();
+    let mut cli = Endpoint::default();
+    let mut tracker = BaselineTracker::new();
+    let mut receiver = BaselineReceiver::new();
+    let mut client = ActorSet::new();
+    let mut bytes = 0usize;
+    // A few quiet ticks at the end let the last losses get repaired.
+    for tick in 0..TICKS + 10 {
+        let now = tick * TICK_MS;
+        let cur = world(tick.min(TICKS));
+        let mut msg = Vec::new();
+        match mode {
+            Mode::AckedDeltas => tracker.build(tick, cur, vec![], vec![]).encode(&mut msg),
+            Mode::FullSnapshots => ActorSnapshot {
+                v: 2,
+                tick,
+                actors: cur.into_values().collect(),
+                projectiles: vec![],
+            }
+            .encode(&mut msg),
+        }
+        srv.send(Channel::Unreliable, msg).expect("queue");
+        for pkt in srv.flush(now) {
+            bytes += pkt.len();
+            let _ = srv_x.try_send(pkt);
+        }
+        while let Some(p) = cli_x.try_recv() {
+            cli.receive(&p).expect("client packet");
+        }
+        while let Some((_, m)) = cli.recv() {
+            let mut slice: &[u8] = &m;
+            match m.first().copied() {
+                Some(TAG_ACTOR_SNAPSHOT_DELTA) => {
+                    let d = ActorSnapshotDelta::decode(&mut slice).expect("delta");
+                    if let Some(set) = receiver.apply(&d) {
+                        client = set.clone();
+                    }
+                }
+                Some(TAG_ACTOR_SNAPSHOT) => {
+                    let s = ActorSnapshot::decode(&mut slice).expect("snapshot");
+                    client = s.actors.into_iter().map(|a| (a.id, a)).collect();
+                }
+                _ => {}
+            }
+        }
+        if let Some(tick) = receiver.last_applied() {
+            let mut ack = Vec::new();
+            ClientCmd::Ack { tick }.encode(&mut ack);
+            cli.send(Channel::Unreliable, ack).expect("ack");
+        }
+        cli.pump(&cli_x, now).expect("client pump");
+        while let Some(p) = srv_x.try_recv() {
+            srv.receive(&p).expect("server packet");
+        }
+        while let Some((_, m)) = srv.recv() {
+            let mut slice: &[u8] = &m;
+            if let Ok(ClientCmd::Ack { tick }) = ClientCmd::decode(&mut slice) {
+                tracker.ack(tick);
+            }
+        }
+    }
+    Outcome { bytes, client }
+}
+
+fn assert_converged(client: &ActorSet) {
+    let truth = world(TICKS);
+    assert_eq!(client.len(), truth.len());
+    for (id, t) in &truth {
+        let c = &client[id];
+        assert_eq!(c.hp, t.hp, "actor {id} hp");
+        for k in 0..3 {
+            assert_eq!(qpos(c.pos[k]), qpos(t.pos[k]), "actor {id} pos[{k}]");
+        }
+    }
+}
+
+#[test]
+fn acked_deltas_beat_full_snapshots_under_loss() {
+    for (seed, loss) in [(1u64, 0.05f32), (2, 0.15), (3, 0.30)] {
+        let acked = run(&Mode::AckedDeltas, seed, loss);
+        let full = run(&Mode::FullSnapshots, seed, loss);
+        assert_converged(&acked.client);
+        assert_converged(&full.client);
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = acked.bytes as f64 / full.bytes as f64;
+        println!(
+            "loss={:>4.0}%: acked deltas {:>8} B, full snapshots {:>8} B ({:.1}%)",
+            loss * 100.0,
+            acked.bytes,
+            full.bytes,
+            ratio * 100.0
+        );
+        assert!(
+            ratio < 0.5,
+            "acked deltas should be well under half of full snapshots (got {ratio:.2})"
+        );
+    }
+}
@@ -5,9 +5,10 @@ use net_core::snapshot::{
 #[test]
 fn hitfx_roundtrip_in_actor_delta() {
     let delta = ActorSnapshotDelta {
-        v: 4,
+        v: 6,
         tick: 100,
         baseline: 90,
+        input_seq: 0,
         spawns: vec![],
         updates: vec![],
         removals: vec![],
@@ -0,0 +1,82 @@
+use net_core::link::{Channel, Endpoint};
+use net_core::transport::LocalLoopbackTransport;
+use net_core::transport::lossy::{LossModel, LossyTransport};
+
+const TICK_MS: u64 = 33;
+
+struct Pair {
+    srv: LossyTransport<LocalLoopbackTransport>,
+    cli: LossyTransport<LocalLoopbackTransport>,
+}
+
+impl Pair {
+    fn new(seed: u64, loss: f32, reorder: f32) -> Self {
+        let (a, b) = LocalLoopbackTransport::new(8192);
+        Self {
+            srv: LossyTransport::new(a, LossModel::new(seed, loss, reorder)),
+            cli: LossyTransport::new(b, LossModel::new(seed ^ 0xA5A5, loss, reorder)),
+        }
+    }
+}
+
+/// Drive both endpoints; the server queues one reliable and one unreliable
+/// message per tick for `sends` ticks. Returns the (reliable, unreliable)
+/// payloads seen by the client, in delivery order, plus the server endpoint.
+fn run(seed: u64, loss: f32, reorder: f32, sends: u32) -> (Vec<u32>, Vec<u32>, Endpoint) {
+    let pair = Pair::new(seed, loss, reorder);
+    let mut srv = Endpoint::default();
+    let mut cli = Endpoint::default();
+    let mut rel = Vec::new();
+    let mut unrel = Vec::new();
+    let mut now = 0u64;
+    let mut i = 0u32;
+    // Keep ticking after the last send until every reliable message is acked.
+    while i < sends || srv.unacked() > 0 {
+        if i < sends {
+            srv.send(Channel::Reliable, i.to_le_bytes().to_vec())
+                .expect("in flight");
+            srv.send(Channel::Unreliable, i.to_le_bytes().to_vec())
+                .expect("unreliable");
+            i += 1;
+        }
+        srv.pump(&pair.srv, now).expect("srv pump");
+        cli.pump(&pair.cli, now).expect("cli pump");
+        while let Some((ch, p)) = cli.recv() {
+            let v = u32::from_le_bytes(p.as_slice().try_into().expect("u32"));
+            match ch {
+                Channel::Reliable => rel.push(v),
+                Channel::Unreliable => unrel.push(v),
+            }
+        }
+        now += TICK_MS;
+        assert!(now < 600_000, "link failed to converge (seed {seed})");
+    }
+    (rel, unrel, srv)
+}
+
+#[test]
+fn reliable_arrives_once_in_order_under_loss_and_reorder() {
+    for seed in [1u64, 7, 42, 1234, 0xDEAD_BEEF] {
+        let (rel, _unrel, srv) = run(seed, 0.2, 0.2, 300);
+        let want: Vec<u32> = (0..300).collect();
+        assert_eq!(rel, want, "seed {seed}");
+        assert_eq!(srv.unacked(), 0);
+    }
+}
+
+#[test]
+fn unreliable_is_latest_wins_under_reorder() {
+    let (_rel, unrel, _srv) = run(99, 0.1, 0.4, 300);
+    // Never delivers a stale message after a newer one.
+    assert!(unrel.windows(2).all(|w| w[0] < w[1]), "{unrel:?}");
+    // Loss is real but most traffic survives.
+    assert!(unrel.len() < 300);
+    assert!(unrel.len() > 150, "only {} delivered", unrel.len());
+}
+
+#[test]
+fn lossless_link_delivers_everything() {
+    let (rel, unrel, _srv) = run(5, 0.0, 0.0, 100);
+    assert_eq!(rel.len(), 100);
+    assert_eq!(unrel.len(), 100);
+}
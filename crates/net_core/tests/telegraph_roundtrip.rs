@@ -0,0 +1,58 @@
+use net_core::snapshot::{
+    SnapshotDecode, SnapshotEncode, TELEGRAPH_CIRCLE, TELEGRAPH_CONE, TELEGRAPH_VERSION,
+    TelegraphMsg, TelegraphRep,
+};
+
+#[test]
+fn telegraph_msg_roundtrip() {
+    let msg = TelegraphMsg {
+        v: TELEGRAPH_VERSION,
+        items: vec![
+            TelegraphRep {
+                id: 7,
+                owner: 42,
+                shape: TELEGRAPH_CIRCLE,
+                pos: [3.0, 0.6, -1.0],
+                yaw: 0.0,
+                radius: 6.0,
+                half_angle: 0.0,
+                total_ms: 2500,
+                remaining_ms: 1200,
+            },
+            TelegraphRep {
+                id: 8,
+                owner: 42,
+                shape: TELEGRAPH_CONE,
+                pos: [0.0, 0.6, 0.0],
+                yaw: 1.5,
+                radius: 12.0,
+                half_angle: 0.5,
+                total_ms: 2000,
+                remaining_ms: 2000,
+            },
+        ],
+    };
+    let mut buf = Vec::new();
+    msg.encode(&mut buf);
+    let mut slice: &[u8] = &buf;
+    let m2 = TelegraphMsg::decode(&mut slice).expect("decode");
+    assert!(slice.is_empty());
+    assert_eq!(m2, msg);
+
+    // Truncated payloads fail instead of yielding partial records.
+    let mut short: &[u8] = &buf[..buf.len() - 1];
+    assert!(TelegraphMsg::decode(&mut short).is_err());
+}
+
+#[test]
+fn empty_telegraph_msg_clears() {
+    let msg = TelegraphMsg {
+        v: TELEGRAPH_VERSION,
+        items: vec![],
+    };
+    let mut buf = Vec::new();
+    msg.encode(&mut buf);
+    assert_eq!(buf.len(), 3);
+    let m2 = TelegraphMsg::decode(&mut buf.as_slice()).expect("decode");
+    assert!(m2.items.is_empty());
+}
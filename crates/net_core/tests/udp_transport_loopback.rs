@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+use net_core::frame::{read_msg, write_msg};
+use net_core::snapshot::{ActorSnapshotDelta, HitFx, SnapshotDecode, SnapshotEncode};
+use net_core::transport::Transport;
+use net_core::transport::udp::{UdpListener, UdpTransport};
+
+fn framed(payload: &[u8]) -> Vec<u8> {
+    let mut f = Vec::new();
+    write_msg(&mut f, payload);
+    f
+}
+
+fn recv_within(t: &dyn Transport, dur: Duration) -> Option<Vec<u8>> {
+    let t0 = Instant::now();
+    while t0.elapsed() < dur {
+        if let Some(b) = t.try_recv() {
+            return Some(b);
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    None
+}
+
+#[test]
+fn udp_carries_framed_snapshot_both_ways() {
+    let listener = UdpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let cli = UdpTransport::connect("127.0.0.1:0", addr).expect("connect");
+    cli.try_send(framed(b"hello")).expect("send hello");
+
+    // Server accepts the peer on its first datagram, which stays queued
+    let t0 = Instant::now();
+    let peer = loop {
+        if let Some(p) = listener.try_accept() {
+            break p;
+        }
+        assert!(t0.elapsed() < Duration::from_secs(2), "no peer accepted");
+        std::thread::sleep(Duration::from_millis(1));
+    };
+    assert_eq!(peer.peer_addr(), cli.local_addr().expect("cli addr"));
+    let hello = recv_within(&peer, Duration::from_secs(2)).expect("hello");
+    assert_eq!(read_msg(&hello).expect("frame"), b"hello");
+
+    let delta = ActorSnapshotDelta {
+        v: 6,
+        tick: 7,
+        baseline: 6,
+        input_seq: 0,
+        spawns: vec![],
+        updates: vec![],
+        removals: vec![3],
+        projectiles: vec![],
+        hits: vec![HitFx {
+            kind: 1,
+            pos: [1.0, 2.0, 3.0],
+        }],
+    };
+    let mut p = Vec::new();
+    delta.encode(&mut p);
+    peer.try_send(framed(&p)).expect("send delta");
+    let got = recv_within(&cli, Duration::from_secs(2)).expect("delta");
+    let mut slice: &[u8] = read_msg(&got).expect("frame");
+    let dec = ActorSnapshotDelta::decode(&mut slice).expect("decode");
+    assert_eq!(dec, delta);
+}
+
+#[test]
+fn udp_listener_demuxes_peers_and_drops_garbage() {
+    let listener = UdpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let a = UdpTransport::connect("127.0.0.1:0", addr).expect("a");
+    let b = UdpTransport::connect("127.0.0.1:0", addr).expect("b");
+    // Unframed bytes never create a peer
+    a.try_send(b"garbage".to_vec()).expect("send garbage");
+    a.try_send(framed(b"from-a")).expect("send a");
+    b.try_send(framed(b"from-b")).expect("send b");
+
+    let mut peers = Vec::new();
+    let t0 = Instant::now();
+    while peers.len() < 2 && t0.elapsed() < Duration::from_secs(2) {
+        if let Some(p) = listener.try_accept() {
+            peers.push(p);
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(peers.len(), 2, "both peers accepted");
+    for p in &peers {
+        let msg = recv_within(p, Duration::from_secs(2)).expect("msg");
+        let payload = read_msg(&msg).expect("frame");
+        let want: &[u8] = if p.peer_addr() == a.local_addr().expect("a addr") {
+            b"from-a"
+        } else {
+            b"from-b"
+        };
+        assert_eq!(payload, want);
+        assert_eq!(p.depth(), 0, "garbage was dropped");
+    }
+}
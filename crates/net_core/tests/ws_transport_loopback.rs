@@ -0,0 +1,124 @@
+use std::time::{Duration, Instant};
+
+use net_core::command::ClientCmd;
+use net_core::frame::{read_msg, write_msg};
+use net_core::snapshot::SnapshotDecode;
+use net_core::transport::Transport;
+use net_core::transport::ws::{WsListener, WsTransport};
+
+fn recv_within(t: &dyn Transport, dur: Duration) -> Option<Vec<u8>> {
+    let t0 = Instant::now();
+    while t0.elapsed() < dur {
+        if let Some(b) = t.try_recv() {
+            return Some(b);
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    None
+}
+
+#[test]
+fn ws_carries_framed_commands_and_replies() {
+    let listener = WsListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    // Client handshake blocks until the server side accepts; run it on a thread.
+    let cli = std::thread::spawn(move || WsTransport::connect(addr).expect("connect"));
+    let t0 = Instant::now();
+    let srv = loop {
+        if let Some(t) = listener.try_accept().expect("accept") {
+            break t;
+        }
+        assert!(t0.elapsed() < Duration::from_secs(2), "no connection");
+        std::thread::sleep(Duration::from_millis(1));
+    };
+    let cli = cli.join().expect("client thread");
+
+    let cmd = ClientCmd::Move {
+        dx: 0.5,
+        dz: -1.0,
+        run: 1,
+        seq: 42,
+    };
+    let mut p = Vec::new();
+    cmd.encode(&mut p);
+    let mut f = Vec::new();
+    write_msg(&mut f, &p);
+    cli.try_send(f).expect("send cmd");
+    let got = recv_within(&srv, Duration::from_secs(2)).expect("cmd");
+    let mut slice: &[u8] = read_msg(&got).expect("frame");
+    assert_eq!(ClientCmd::decode(&mut slice).expect("decode"), cmd);
+
+    // Burst of replies arrives in order on a stream transport
+    for i in 0u8..32 {
+        let mut f = Vec::new();
+        write_msg(&mut f, &[i]);
+        srv.try_send(f).expect("send reply");
+    }
+    for i in 0u8..32 {
+        let got = recv_within(&cli, Duration::from_secs(2)).expect("reply");
+        assert_eq!(read_msg(&got).expect("frame"), &[i]);
+    }
+}
+
+#[test]
+fn ws_reports_disconnect_after_peer_drops() {
+    let listener = WsListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let cli = std::thread::spawn(move || WsTransport::connect(addr).expect("connect"));
+    let t0 = Instant::now();
+    let srv = loop {
+        if let Some(t) = listener.try_accept().expect("accept") {
+            break t;
+        }
+        assert!(t0.elapsed() < Duration::from_secs(2), "no connection");
+        std::thread::sleep(Duration::from_millis(1));
+    };
+    drop(cli.join().expect("client thread"));
+    let t0 = Instant::now();
+    while !srv.is_closed() && t0.elapsed() < Duration::from_secs(2) {
+        let _ = srv.try_recv();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(srv.is_closed());
+}
+
+#[test]
+fn silent_client_does_not_stall_accepts() {
+    let listener = WsListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    // Connects but never sends its upgrade request.
+    let _silent = std::net::TcpStream::connect(addr).expect("tcp connect");
+    let cli = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        WsTransport::connect(addr).expect("connect")
+    });
+    let t0 = Instant::now();
+    let mut ticks = 0u32;
+    let srv = loop {
+        let tick = Instant::now();
+        let got = listener.try_accept().expect("accept");
+        assert!(tick.elapsed() < Duration::from_millis(100), "accept blocked");
+        ticks += 1;
+        if let Some(t) = got {
+            break t;
+        }
+        assert!(t0.elapsed() < Duration::from_secs(2), "no connection");
+        std::thread::sleep(Duration::from_millis(1));
+    };
+    assert!(ticks > 1);
+    assert_eq!(listener.pending(), 1, "the silent client is still waiting");
+    let cli = cli.join().expect("client thread");
+    let mut f = Vec::new();
+    write_msg(&mut f, &[7]);
+    cli.try_send(f).expect("send");
+    let got = recv_within(&srv, Duration::from_secs(2)).expect("frame");
+    assert_eq!(read_msg(&got).expect("frame"), &[7]);
+
+    // Abandoned once the handshake timeout passes.
+    let t0 = Instant::now();
+    while listener.pending() > 0 && t0.elapsed() < Duration::from_secs(5) {
+        assert!(listener.try_accept().expect("accept").is_none());
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(listener.pending(), 0);
+}
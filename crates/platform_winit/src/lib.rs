@@ -229,9 +229,8 @@ struct App {
     #[cfg(target_arch = "wasm32")]
     t0: web_time::Instant,
     tick: u32,
-    // Delta baseline for interest/deltas (per local client)
-    baseline_tick: u64,
-    baseline: std::collections::HashMap<u32, net_core::snapshot::ActorRep>,
+    // Actor delta baselines acked by the local client
+    baseline: net_core::baseline::BaselineTracker,
     interest_radius_m: f32,
     // Simple server-side rate limiter for client commands
     #[cfg(not(target_arch = "wasm32"))]
@@ -239,8 +238,14 @@ struct App {
     #[cfg(target_arch = "wasm32")]
     last_sec_start: web_time::Instant,
     cmds_this_sec: u32,
+    // Newest sequenced Move applied; echoed as `input_seq` for client prediction
+    last_move_seq: u32,
     // Track which destructible instances have been sent to the client
     sent_destr_instances: std::collections::HashSet<u64>,
+    // `Inventory::rev` of the local PC last sent to the client
+    sent_inventory_rev: Option<u32>,
+    // Loot drops of the local PC last sent to the client
+    sent_loot: Vec<net_core::snapshot::LootDropRep>,
     #[allow(dead_code)]
     boot: BootMode,
     #[allow(dead_code)]
@@ -262,8 +267,7 @@ impl Default for App {
             #[cfg(target_arch = "wasm32")]
             last_time: None,
             tick: 0,
-            baseline_tick: 0,
-            baseline: std::collections::HashMap::new(),
+            baseline: net_core::baseline::BaselineTracker::new(),
             interest_radius_m: 40.0,
             #[cfg(not(target_arch = "wasm32"))]
             last_sec_start: std::time::Instant::now(),
@@ -274,7 +278,10 @@ impl Default for App {
             #[cfg(target_arch = "wasm32")]
             t0: web_time::Instant::now(),
             cmds_this_sec: 0,
+            last_move_seq: 0,
             sent_destr_instances: std::collections::HashSet::new(),
+            sent_inventory_rev: None,
+            sent_loot: Vec::new(),
             boot: BootMode::Picker,
             picker: Default::default(),
             builder: Default::default(),
@@ -410,6 +417,8 @@ impl ApplicationHandler for App {
                 let (tx_cli, rx_cli) = _cli.split();
                 state.set_replication_rx(rx_cli);
                 state.set_command_tx(tx_cli);
+                #[cfg(feature = "demo_server")]
+                state.begin_join(&detect_zone_slug().unwrap_or_default(), DEMO_PLAYER_NAME);
                 self.transport_srv = Some(_srv);
             }
             #[cfg(not(target_arch = "wasm32"))]
@@ -478,8 +487,8 @@ impl ApplicationHandler for App {
                 }
                 self.last_time = Some(std::time::Instant::now());
                 self.tick = 0;
-                self.baseline_tick = 0;
-                self.baseline = std::collections::HashMap::new();
+                // Fresh renderer: restart from a keyframe
+                self.baseline = net_core::baseline::BaselineTracker::new();
                 // Temp: widen interest culling radius to include far casters/targets in demo
                 self.interest_radius_m = 60.0;
                 self.last_sec_start = std::time::Instant::now();
@@ -1042,6 +1051,10 @@ impl ApplicationHandler for App {
                             if let Some(st) = self.state.as_mut() {
                                 st.set_replication_rx(rx_cli);
                                 st.set_command_tx(tx_cli);
+                                st.begin_join(
+                                    &detect_zone_slug().unwrap_or_default(),
+                                    DEMO_PLAYER_NAME,
+                                );
                             }
                             self.transport_srv = Some(srv);
                             // Spawn demo server content similar to native path
@@ -1082,14 +1095,18 @@ impl ApplicationHandler for App {
                         Ok(p) => p,
                         Err(_) => &bytes,
                     };
+                    if let Some(reply) = demo_welcome(srv, payload) {
+                        let _ = srv_xport.try_send(reply);
+                        continue;
+                    }
                     let mut slice: &[u8] = payload;
                     if let Ok(cmd) = net_core::command::ClientCmd::decode(&mut slice) {
-                        // Rate limit only spell-cast commands; Move/Aim are intents (state).
+                        // Rate limit only cast/attack/use commands; Move/Aim are intents (state).
                         let rate_limited = matches!(
                             cmd,
-                            net_core::command::ClientCmd::FireBolt { .. }
-                                | net_core::command::ClientCmd::Fireball { .. }
-                                | net_core::command::ClientCmd::MagicMissile { .. }
+                            net_core::command::ClientCmd::Cast { .. }
+                                | net_core::command::ClientCmd::Attack { .. }
+                                | net_core::command::ClientCmd::Use { .. }
                         );
                         if rate_limited {
                             let now = {
@@ -1113,55 +1130,69 @@ impl ApplicationHandler for App {
                             self.cmds_this_sec += 1;
                         }
                         match cmd {
-                            net_core::command::ClientCmd::FireBolt { pos, dir } => {
-                                let p = glam::vec3(pos[0], pos[1], pos[2]);
-                                let d = glam::vec3(dir[0], dir[1], dir[2]).normalize_or_zero();
-                                log::info!(
-                                    "cmd: FireBolt at ({:.2},{:.2},{:.2}) dir=({:.2},{:.2},{:.2})",
-                                    p.x,
-                                    p.y,
-                                    p.z,
-                                    d.x,
-                                    d.y,
-                                    d.z
-                                );
-                                srv.enqueue_cast(p, d, server_core::SpellId::Firebolt);
-                            }
-                            net_core::command::ClientCmd::Fireball { pos, dir } => {
-                                let p = glam::vec3(pos[0], pos[1], pos[2]);
-                                let d = glam::vec3(dir[0], dir[1], dir[2]).normalize_or_zero();
-                                log::info!(
-                                    "cmd: Fireball at ({:.2},{:.2},{:.2}) dir=({:.2},{:.2},{:.2})",
-                                    p.x,
-                                    p.y,
-                                    p.z,
-                                    d.x,
-                                    d.y,
-                                    d.z
-                                );
-                                srv.enqueue_cast(p, d, server_core::SpellId::Fireball);
+                            net_core::command::ClientCmd::Cast { ability_id, target } => {
+                                log::info!("cmd: Cast {ability_id} at {target:?}");
+                                if let Some(pc) = srv.pc_actor
+                                    && let Err(e) =
+                                        srv.enqueue_ability_cast(pc, &ability_id, target)
+                                {
+                                    log::debug!("cmd: Cast {ability_id} rejected: {e:?}");
+                                }
                             }
-                            net_core::command::ClientCmd::MagicMissile { pos, dir } => {
-                                let p = glam::vec3(pos[0], pos[1], pos[2]);
-                                let d = glam::vec3(dir[0], dir[1], dir[2]).normalize_or_zero();
-                                log::info!(
-                                    "cmd: MagicMissile at ({:.2},{:.2},{:.2}) dir=({:.2},{:.2},{:.2})",
-                                    p.x,
-                                    p.y,
-                                    p.z,
-                                    d.x,
-                                    d.y,
-                                    d.z
-                                );
-                                srv.enqueue_cast(p, d, server_core::SpellId::MagicMissile);
+                            net_core::command::ClientCmd::Attack { weapon_id, target } => {
+                                if let Some(pc) = srv.pc_actor
+                                    && let Err(e) =
+                                        srv.enqueue_weapon_attack(pc, &weapon_id, target)
+                                {
+                                    log::debug!("cmd: Attack {weapon_id} rejected: {e:?}");
+                                }
                             }
-                            net_core::command::ClientCmd::Move { dx, dz, run } => {
+                            net_core::command::ClientCmd::Move { dx, dz, run, seq } => {
                                 let runb = run != 0;
                                 srv.apply_move_intent(dx, dz, runb);
+                                if seq != 0 {
+                                    self.last_move_seq = seq;
+                                }
                             }
                             net_core::command::ClientCmd::Aim { yaw } => {
                                 srv.apply_aim_intent(yaw);
                             }
+                            net_core::command::ClientCmd::Dodge { dx, dz } => {
+                                if let Some(pc) = srv.pc_actor {
+                                    srv.apply_dodge_intent_for(pc, dx, dz);
+                                }
+                            }
+                            net_core::command::ClientCmd::Equip { item_id } => {
+                                if let Some(pc) = srv.pc_actor
+                                    && let Err(e) = srv.equip_item(pc, &item_id)
+                                {
+                                    log::debug!("cmd: Equip {item_id} rejected: {e:?}");
+                                }
+                            }
+                            net_core::command::ClientCmd::Use { item_id } => {
+                                if let Some(pc) = srv.pc_actor
+                                    && let Err(e) = srv.use_item(pc, &item_id)
+                                {
+                                    log::debug!("cmd: Use {item_id} rejected: {e:?}");
+                                }
+                            }
+                            net_core::command::ClientCmd::Drop { item_id, count } => {
+                                if let Some(pc) = srv.pc_actor
+                                    && let Err(e) = srv.drop_item(pc, &item_id, u32::from(count))
+                                {
+                                    log::debug!("cmd: Drop {item_id} rejected: {e:?}");
+                                }
+                            }
+                            net_core::command::ClientCmd::PickUp { drop_id } => {
+                                if let Some(pc) = srv.pc_actor
+                                    && let Err(e) = srv.pick_up(pc, drop_id)
+                                {
+                                    log::debug!("cmd: PickUp {drop_id} rejected: {e:?}");
+                                }
+                            }
+                            net_core::command::ClientCmd::Ack { tick } => {
+                                self.baseline.ack(tick);
+                            }
                         }
                     }
                 }
@@ -1209,8 +1240,6 @@ impl ApplicationHandler for App {
                     );
                 }
                 let tick64 = self.tick as u64;
-                // Always build v3 deltas with interest limiting and send after stepping
-                let asnap = srv.tick_snapshot_actors(tick64);
                 // Interest center: authoritative PC position from server when available
                 let center = if let Some(pc_id) = srv.pc_actor
                     && let Some(pc) = srv.ecs.get(pc_id)
@@ -1222,160 +1251,26 @@ impl ApplicationHandler for App {
                         .copied()
                         .unwrap_or(glam::vec3(0.0, 0.0, 0.0))
                 };
-                let r2 = self.interest_radius_m * self.interest_radius_m;
-                let mut cur: std::collections::HashMap<u32, net_core::snapshot::ActorRep> =
-                    std::collections::HashMap::new();
-                for a in asnap.actors {
-                    let dx = a.pos[0] - center.x;
-                    let dz = a.pos[2] - center.z;
-                    if dx * dx + dz * dz <= r2 {
-                        cur.insert(a.id, a);
-                    }
-                }
-                // spawns/removals/updates
-                let mut spawns = Vec::new();
-                let mut removals = Vec::new();
-                let mut updates = Vec::new();
-                for (id, a) in &cur {
-                    if let Some(b) = self.baseline.get(id) {
-                        let mut flags = 0u8;
-                        let mut rec = net_core::snapshot::ActorDeltaRec {
-                            id: *id,
-                            flags: 0,
-                            qpos: [0; 3],
-                            qyaw: 0,
-                            hp: 0,
-                            alive: 0,
-                        };
-                        let qpx = net_core::snapshot::qpos(a.pos[0]);
-                        let qpy = net_core::snapshot::qpos(a.pos[1]);
-                        let qpz = net_core::snapshot::qpos(a.pos[2]);
-                        if net_core::snapshot::qpos(b.pos[0]) != qpx
-                            || net_core::snapshot::qpos(b.pos[1]) != qpy
-                            || net_core::snapshot::qpos(b.pos[2]) != qpz
-                        {
-                            flags |= 1;
-                            rec.qpos = [qpx, qpy, qpz];
-                        }
-                        let qy = net_core::snapshot::qyaw(a.yaw);
-                        if net_core::snapshot::qyaw(b.yaw) != qy {
-                            flags |= 2;
-                            rec.qyaw = qy;
-                        }
-                        if b.hp != a.hp {
-                            flags |= 4;
-                            rec.hp = a.hp;
-                        }
-                        if b.alive != a.alive {
-                            flags |= 8;
-                            rec.alive = u8::from(a.alive);
-                        }
-                        if flags != 0 {
-                            rec.flags = flags;
-                            updates.push(rec);
-                        }
-                    } else {
-                        spawns.push(a.clone());
-                    }
-                }
-                for id in self.baseline.keys() {
-                    if !cur.contains_key(id) {
-                        removals.push(*id);
-                    }
-                }
-                // Projectiles: interest-limited to same center/radius
-                let mut projectiles = Vec::new();
-                for c in srv.ecs.iter() {
-                    if let (Some(proj), Some(vel)) = (c.projectile.as_ref(), c.velocity.as_ref()) {
-                        let dx = c.tr.pos.x - center.x;
-                        let dz = c.tr.pos.z - center.z;
-                        if dx * dx + dz * dz <= r2 {
-                            projectiles.push(net_core::snapshot::ProjectileRep {
-                                id: c.id.0,
-                                kind: match proj.kind {
-                                    server_core::ProjKind::Firebolt => 0,
-                                    server_core::ProjKind::Fireball => 1,
-                                    server_core::ProjKind::MagicMissile => 2,
-                                },
-                                pos: [c.tr.pos.x, c.tr.pos.y, c.tr.pos.z],
-                                vel: [vel.v.x, vel.v.y, vel.v.z],
-                            });
-                        }
-                    }
-                }
-                let delta = net_core::snapshot::ActorSnapshotDelta {
-                    v: 4,
-                    tick: tick64,
-                    baseline: self.baseline_tick,
-                    spawns,
-                    updates,
-                    removals,
-                    projectiles,
-                    hits: {
-                        let mut v = Vec::new();
-                        // drain server-side hitfx for this frame
-                        std::mem::swap(&mut v, &mut srv.fx_hits);
-                        v
-                    },
-                };
+                // Interest-limited delta against the tick the renderer last acked
+                let mut delta = server_core::session::local_actor_delta(
+                    srv,
+                    &mut self.baseline,
+                    tick64,
+                    center,
+                    self.interest_radius_m,
+                );
+                delta.input_seq = self.last_move_seq;
                 let mut p4 = Vec::new();
                 delta.encode(&mut p4);
                 let mut f4 = Vec::with_capacity(p4.len() + 8);
                 net_core::frame::write_msg(&mut f4, &p4);
                 metrics::counter!("net.bytes_sent_total", "dir" => "tx").increment(f4.len() as u64);
                 let _ = srv_xport.try_send(f4);
-                // update baseline
-                self.baseline = cur;
-                self.baseline_tick = tick64;
                 // Send HUD status for local PC
                 if let Some(pc_id) = srv.pc_actor
                     && let Some(pc) = srv.ecs.get(pc_id)
                 {
-                    let mana = pc
-                        .pool
-                        .as_ref()
-                        .map(|p| p.mana)
-                        .unwrap_or(0)
-                        .clamp(0, u16::MAX as i32) as u16;
-                    let mana_max = pc
-                        .pool
-                        .as_ref()
-                        .map(|p| p.max)
-                        .unwrap_or(0)
-                        .clamp(0, u16::MAX as i32) as u16;
-                    let gcd_ms = (pc.cooldowns.as_ref().map(|c| c.gcd_ready).unwrap_or(0.0)
-                        * 1000.0)
-                        .clamp(0.0, u16::MAX as f32) as u16;
-                    let cd = |sid: server_core::SpellId| -> f32 {
-                        pc.cooldowns
-                            .as_ref()
-                            .and_then(|c| c.per_spell.get(&sid).copied())
-                            .unwrap_or(0.0)
-                    };
-                    let spell_cds = vec![
-                        (0u8, (cd(server_core::SpellId::Firebolt) * 1000.0) as u16),
-                        (1u8, (cd(server_core::SpellId::Fireball) * 1000.0) as u16),
-                        (
-                            2u8,
-                            (cd(server_core::SpellId::MagicMissile) * 1000.0) as u16,
-                        ),
-                    ];
-                    let burning_ms =
-                        (pc.burning.as_ref().map(|b| b.remaining_s).unwrap_or(0.0) * 1000.0) as u16;
-                    let slow_ms =
-                        (pc.slow.as_ref().map(|s| s.remaining_s).unwrap_or(0.0) * 1000.0) as u16;
-                    let stunned_ms =
-                        (pc.stunned.as_ref().map(|s| s.remaining_s).unwrap_or(0.0) * 1000.0) as u16;
-                    let hud = net_core::snapshot::HudStatusMsg {
-                        v: net_core::snapshot::HUD_STATUS_VERSION,
-                        mana,
-                        mana_max,
-                        gcd_ms,
-                        spell_cds,
-                        burning_ms,
-                        slow_ms,
-                        stunned_ms,
-                    };
+                    let hud = server_core::session::hud_status_for(pc, &srv.abilities);
                     let mut hb = Vec::new();
                     hud.encode(&mut hb);
                     let mut fh = Vec::with_capacity(hb.len() + 8);
@@ -1384,8 +1279,11 @@ impl ApplicationHandler for App {
                         .increment(fh.len() as u64);
                     let _ = srv_xport.try_send(fh);
                 }
-                // Drain HUD toasts and send messages
-                while let Some(code) = srv.hud_toasts.pop() {
+                // Drain HUD toasts and send the local PC's
+                while let Some((to, code)) = srv.hud_toasts.pop() {
+                    if srv.pc_actor != Some(to) {
+                        continue;
+                    }
                     let toast = net_core::snapshot::HudToastMsg {
                         v: net_core::snapshot::HUD_TOAST_VERSION,
                         code,
@@ -1398,6 +1296,69 @@ impl ApplicationHandler for App {
                         .increment(ft.len() as u64);
                     let _ = srv_xport.try_send(ft);
                 }
+                // Boss telegraphs: the local link is lossless, so send every tick
+                {
+                    let tele = net_core::snapshot::TelegraphMsg {
+                        v: net_core::snapshot::TELEGRAPH_VERSION,
+                        items: server_core::systems::boss::telegraph_reps(srv),
+                    };
+                    let mut tb = Vec::new();
+                    tele.encode(&mut tb);
+                    let mut ft = Vec::with_capacity(tb.len() + 8);
+                    net_core::frame::write_msg(&mut ft, &tb);
+                    metrics::counter!("net.bytes_sent_total", "dir" => "tx")
+                        .increment(ft.len() as u64);
+                    let _ = srv_xport.try_send(ft);
+                }
+                // Status effects: likewise sent every tick
+                {
+                    let st = net_core::snapshot::StatusMsg {
+                        v: net_core::snapshot::STATUS_VERSION,
+                        items: server_core::systems::status::status_reps(srv),
+                    };
+                    let mut sb = Vec::new();
+                    st.encode(&mut sb);
+                    let mut fs = Vec::with_capacity(sb.len() + 8);
+                    net_core::frame::write_msg(&mut fs, &sb);
+                    metrics::counter!("net.bytes_sent_total", "dir" => "tx")
+                        .increment(fs.len() as u64);
+                    let _ = srv_xport.try_send(fs);
+                }
+                // Inventory: only when it changed
+                if let Some(inv) = srv
+                    .pc_actor
+                    .and_then(|id| srv.ecs.get(id))
+                    .and_then(|pc| pc.inventory.as_ref())
+                    && self.sent_inventory_rev != Some(inv.rev)
+                {
+                    self.sent_inventory_rev = Some(inv.rev);
+                    let msg = server_core::systems::inventory::inventory_msg(inv);
+                    let mut ib = Vec::new();
+                    msg.encode(&mut ib);
+                    let mut fi = Vec::with_capacity(ib.len() + 8);
+                    net_core::frame::write_msg(&mut fi, &ib);
+                    metrics::counter!("net.bytes_sent_total", "dir" => "tx")
+                        .increment(fi.len() as u64);
+                    let _ = srv_xport.try_send(fi);
+                }
+                // Loot drops: only when they changed
+                if let Some(pc) = srv.pc_actor {
+                    let loot = server_core::systems::loot::loot_reps(srv, pc);
+                    if loot != self.sent_loot {
+                        self.sent_loot = loot.clone();
+                        let msg = net_core::snapshot::LootMsg {
+                            v: net_core::snapshot::LOOT_VERSION,
+                            items: loot,
+                        };
+                        let mut lb = Vec::new();
+                        msg.encode(&mut lb);
+                        let mut fl = Vec::with_capacity(lb.len() + 8);
+                        net_core::frame::write_msg(&mut fl, &lb);
+                        metrics::counter!("net.bytes_sent_total", "dir" => "tx")
+                            .increment(fl.len() as u64);
+                        let _ = srv_xport.try_send(fl);
+                    }
+                }
                 // Destructible replication: send instances once, deltas per change
                 if srv.destruct_bootstrap_instances_outstanding {
                     let insts = srv.all_destructible_instances();
@@ -1559,6 +1520,43 @@ fn detect_zone_slug() -> Option<String> {
     None
 }
 
+/// Name the renderer joins the in-process demo server under.
+#[cfg(feature = "demo_server")]
+const DEMO_PLAYER_NAME: &str = "Player";
+/// The demo server steps once per rendered frame.
+#[cfg(feature = "demo_server")]
+const DEMO_TICK_HZ: u16 = 60;
+
+/// Answer the renderer's `Hello` on the demo loopback: the server's PC is the
+/// local actor. Returns the framed `Welcome`, or `None` for other messages.
+#[cfg(feature = "demo_server")]
+fn demo_welcome(srv: &server_core::ServerState, payload: &[u8]) -> Option<Vec<u8>> {
+    use net_core::handshake::{Hello, PROTOCOL_VERSION, TAG_HELLO, Welcome, WireVersions};
+    if payload.first() != Some(&TAG_HELLO) {
+        return None;
+    }
+    let mut slice = payload;
+    let hello = Hello::decode(&mut slice).ok()?;
+    let pc = srv.pc_actor?;
+    log::info!(
+        "demo server: welcome '{}' as actor {}",
+        hello.player_name,
+        pc.0
+    );
+    let welcome = Welcome {
+        protocol: PROTOCOL_VERSION,
+        actor_id: pc.0,
+        zone_manifest_id: 0,
+        tick_hz: DEMO_TICK_HZ,
+        versions: WireVersions::CURRENT,
+    };
+    let mut p = Vec::new();
+    welcome.encode(&mut p);
+    let mut framed = Vec::with_capacity(p.len() + 8);
+    net_core::frame::write_msg(&mut framed, &p);
+    Some(framed)
+}
+
 #[cfg(all(feature = "demo_server", not(target_arch = "wasm32")))]
 impl App {
     fn pump_demo_server(&mut self) {
@@ -1571,13 +1569,17 @@ impl App {
                     Ok(p) => p,
                     Err(_) => &bytes,
                 };
+                if let Some(reply) = demo_welcome(srv, payload) {
+                    let _ = srv_xport.try_send(reply);
+                    continue;
+                }
                 let mut slice: &[u8] = payload;
                 if let Ok(cmd) = net_core::command::ClientCmd::decode(&mut slice) {
                     let rate_limited = matches!(
                         cmd,
-                        net_core::command::ClientCmd::FireBolt { .. }
-                            | net_core::command::ClientCmd::Fireball { .. }
-                            | net_core::command::ClientCmd::MagicMissile { .. }
+                        net_core::command::ClientCmd::Cast { .. }
+                            | net_core::command::ClientCmd::Attack { .. }
+                            | net_core::command::ClientCmd::Use { .. }
                     );
                     if rate_limited {
                         let now = {
@@ -1600,27 +1602,53 @@ impl App {
                         self.cmds_this_sec += 1;
                     }
                     match cmd {
-                        net_core::command::ClientCmd::FireBolt { pos, dir } => {
-                            let p = glam::vec3(pos[0], pos[1], pos[2]);
-                            let d = glam::vec3(dir[0], dir[1], dir[2]).normalize_or_zero();
-                            srv.enqueue_cast(p, d, server_core::SpellId::Firebolt);
-                        }
-                        net_core::command::ClientCmd::Fireball { pos, dir } => {
-                            let p = glam::vec3(pos[0], pos[1], pos[2]);
-                            let d = glam::vec3(dir[0], dir[1], dir[2]).normalize_or_zero();
-                            srv.enqueue_cast(p, d, server_core::SpellId::Fireball);
+                        net_core::command::ClientCmd::Cast { ability_id, target } => {
+                            if let Some(pc) = srv.pc_actor {
+                                let _ = srv.enqueue_ability_cast(pc, &ability_id, target);
+                            }
                         }
-                        net_core::command::ClientCmd::MagicMissile { pos, dir } => {
-                            let p = glam::vec3(pos[0], pos[1], pos[2]);
-                            let d = glam::vec3(dir[0], dir[1], dir[2]).normalize_or_zero();
-                            srv.enqueue_cast(p, d, server_core::SpellId::MagicMissile);
+                        net_core::command::ClientCmd::Attack { weapon_id, target } => {
+                            if let Some(pc) = srv.pc_actor {
+                                let _ = srv.enqueue_weapon_attack(pc, &weapon_id, target);
+                            }
                         }
-                        net_core::command::ClientCmd::Move { dx, dz, run } => {
+                        net_core::command::ClientCmd::Move { dx, dz, run, seq } => {
                             srv.apply_move_intent(dx, dz, run != 0);
+                            if seq != 0 {
+                                self.last_move_seq = seq;
+                            }
                         }
                         net_core::command::ClientCmd::Aim { yaw } => {
                             srv.apply_aim_intent(yaw);
                         }
+                        net_core::command::ClientCmd::Dodge { dx, dz } => {
+                            if let Some(pc) = srv.pc_actor {
+                                srv.apply_dodge_intent_for(pc, dx, dz);
+                            }
+                        }
+                        net_core::command::ClientCmd::Equip { item_id } => {
+                            if let Some(pc) = srv.pc_actor {
+                                let _ = srv.equip_item(pc, &item_id);
+                            }
+                        }
+                        net_core::command::ClientCmd::Use { item_id } => {
+                            if let Some(pc) = srv.pc_actor {
+                                let _ = srv.use_item(pc, &item_id);
+                            }
+                        }
+                        net_core::command::ClientCmd::Drop { item_id, count } => {
+                            if let Some(pc) = srv.pc_actor {
+                                let _ = srv.drop_item(pc, &item_id, u32::from(count));
+                            }
+                        }
+                        net_core::command::ClientCmd::PickUp { drop_id } => {
+                            if let Some(pc) = srv.pc_actor {
+                                let _ = srv.pick_up(pc, drop_id);
+                            }
+                        }
+                        net_core::command::ClientCmd::Ack { tick } => {
+                            self.baseline.ack(tick);
+                        }
                     }
                 }
             }
@@ -1644,7 +1672,6 @@ impl App {
             let _wiz_pos: Vec<glam::Vec3> = state.wizard_positions();
             srv.step_authoritative(dt);
             let tick64 = self.tick as u64;
-            let asnap = srv.tick_snapshot_actors(tick64);
             let center = if let Some(pc_id) = srv.pc_actor
                 && let Some(pc) = srv.ecs.get(pc_id)
             {
@@ -1656,105 +1683,19 @@ impl App {
                     .copied()
                     .unwrap_or(glam::vec3(0.0, 0.0, 0.0))
             };
-            let r2 = self.interest_radius_m * self.interest_radius_m;
-            let mut cur = std::collections::HashMap::new();
-            for a in asnap.actors {
-                let dx = a.pos[0] - center.x;
-                let dz = a.pos[2] - center.z;
-                if dx * dx + dz * dz <= r2 {
-                    cur.insert(a.id, a);
-                }
-            }
-            let mut spawns = Vec::new();
-            let mut removals = Vec::new();
-            let mut updates = Vec::new();
-            for (id, a) in &cur {
-                if let Some(b) = self.baseline.get(id) {
-                    let mut flags = 0u8;
-                    let mut rec = net_core::snapshot::ActorDeltaRec {
-                        id: *id,
-                        flags: 0,
-                        qpos: [0; 3],
-                        qyaw: 0,
-                        hp: 0,
-                        alive: 0,
-                    };
-                    let qpx = net_core::snapshot::qpos(a.pos[0]);
-                    let qpy = net_core::snapshot::qpos(a.pos[1]);
-                    let qpz = net_core::snapshot::qpos(a.pos[2]);
-                    if net_core::snapshot::qpos(b.pos[0]) != qpx
-                        || net_core::snapshot::qpos(b.pos[1]) != qpy
-                        || net_core::snapshot::qpos(b.pos[2]) != qpz
-                    {
-                        flags |= 1;
-                        rec.qpos = [qpx, qpy, qpz];
-                    }
-                    let qy = net_core::snapshot::qyaw(a.yaw);
-                    if net_core::snapshot::qyaw(b.yaw) != qy {
-                        flags |= 2;
-                        rec.qyaw = qy;
-                    }
-                    if b.hp != a.hp {
-                        flags |= 4;
-                        rec.hp = a.hp;
-                    }
-                    if b.alive != a.alive {
-                        flags |= 8;
-                        rec.alive = u8::from(a.alive);
-                    }
-                    if flags != 0 {
-                        rec.flags = flags;
-                        updates.push(rec);
-                    }
-                } else {
-                    spawns.push(a.clone());
-                }
-            }
-            for id in self.baseline.keys() {
-                if !cur.contains_key(id) {
-                    removals.push(*id);
-                }
-            }
-            let mut projectiles = Vec::new();
-            for c in srv.ecs.iter() {
-                if let (Some(proj), Some(vel)) = (c.projectile.as_ref(), c.velocity.as_ref()) {
-                    let dx = c.tr.pos.x - center.x;
-                    let dz = c.tr.pos.z - center.z;
-                    if dx * dx + dz * dz <= r2 {
-                        projectiles.push(net_core::snapshot::ProjectileRep {
-                            id: c.id.0,
-                            kind: match proj.kind {
-                                server_core::ProjKind::Firebolt => 0,
-                                server_core::ProjKind::Fireball => 1,
-                                server_core::ProjKind::MagicMissile => 2,
-                            },
-                            pos: [c.tr.pos.x, c.tr.pos.y, c.tr.pos.z],
-                            vel: [vel.v.x, vel.v.y, vel.v.z],
-                        });
-                    }
-                }
-            }
-            let delta = net_core::snapshot::ActorSnapshotDelta {
-                v: 4,
-                tick: tick64,
-                baseline: self.baseline_tick,
-                spawns,
-                updates,
-                removals,
-                projectiles,
-                hits: {
-                    let mut v = Vec::new();
-                    std::mem::swap(&mut v, &mut srv.fx_hits);
-                    v
-                },
-            };
+            let mut delta = server_core::session::local_actor_delta(
+                srv,
+                &mut self.baseline,
+                tick64,
+                center,
+                self.interest_radius_m,
+            );
+            delta.input_seq = self.last_move_seq;
             let mut p4 = Vec::new();
             delta.encode(&mut p4);
             let mut f4 = Vec::with_capacity(p4.len() + 8);
             net_core::frame::write_msg(&mut f4, &p4);
             let _ = srv_xport.try_send(f4);
-            self.baseline = cur;
-            self.baseline_tick = tick64;
             self.tick = self.tick.wrapping_add(1);
             if let Some(win) = &self.window {
                 win.request_redraw();
@@ -298,11 +298,15 @@ pub struct Renderer {
     pc_mat_bg: Option<wgpu::BindGroup>,
     pc_prev_pos: glam::Vec3,
     pc_anim_cfg: data_runtime::configs::pc_animations::PcAnimCfg,
+    // Status effect icons/kinds by replicated id
+    status_specs: data_runtime::specs::statuses::StatusSpecDb,
     pc_anim_missing_warned: HashSet<String>,
     // Jump state tracking for animation selection
     pc_prev_airborne: bool,
     pc_jump_start_time: Option<f32>,
     pc_land_start_time: Option<f32>,
+    // Start of the replicated dodge (ACTOR_STATE_DODGING) on the PC
+    pc_dodge_start_time: Option<f32>,
     // Cast phase moments for PC rig
     pc_cast_shoot_time: Option<f32>,
     pc_cast_end_time: Option<f32>,
@@ -401,6 +405,8 @@ pub struct Renderer {
     root_node: Option<usize>,
     // Raw Shift state; we derive effective sprint per-frame
     shift_down: bool,
+    // F pressed since the last intent send; sent as `ClientCmd::Dodge`
+    dodge_queued: bool,
 
     // Projectile + particle pools
     projectiles: Vec<Projectile>,
@@ -497,6 +503,8 @@ pub struct Renderer {
     // --- Replication (local loop) ---
     repl_rx: Option<net_core::channel::Rx>,
     repl_buf: client_core::replication::ReplicationBuffer,
+    // Handshake in flight or done; its `Welcome` configures `repl_buf`
+    join: Option<client_core::join::Join>,
     #[allow(dead_code)]
     boss_status_next_emit: f32,
 
@@ -647,6 +655,20 @@ impl Renderer {
     pub fn set_command_tx(&mut self, tx: net_core::channel::Tx) {
         self.cmd_tx = Some(tx);
     }
+    /// Send `Hello` on the command channel. The server's `Welcome` pins wire
+    /// versions, tick rate and the local actor when it is drained.
+    pub fn begin_join(&mut self, zone_slug: &str, player_name: &str) {
+        let Some(tx) = &self.cmd_tx else {
+            log::warn!("join: no command channel; not sending Hello");
+            return;
+        };
+        let mut join = client_core::join::Join::new(zone_slug, player_name);
+        let payload = join.start();
+        let mut framed = Vec::with_capacity(payload.len() + 8);
+        net_core::frame::write_msg(&mut framed, &payload);
+        let _ = tx.try_send(framed);
+        self.join = Some(join);
+    }
     #[inline]
     pub fn is_vox_onepath(&self) -> bool {
         self.vox_onepath_ui.is_some()
@@ -1250,6 +1272,7 @@ impl Renderer {
         self.pc_prev_airborne = false;
         self.pc_jump_start_time = None;
         self.pc_land_start_time = None;
+        self.pc_dodge_start_time = None;
         self.pc_cast_shoot_time = None;
         self.pc_cast_end_time = None;
         self.cam_yaw_prev = 0.0;
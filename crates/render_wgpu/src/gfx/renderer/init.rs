@@ -1786,6 +1786,11 @@ pub async fn new_renderer(window: &Window) -> anyhow::Result<crate::gfx::Rendere
 
     // Load explicit PC animation name mapping (optional)
     let pc_anim_cfg = data_runtime::configs::pc_animations::load_default().unwrap_or_default();
+    let status_specs =
+        data_runtime::specs::statuses::StatusSpecDb::load_default().unwrap_or_else(|e| {
+            log::warn!("renderer: statuses.toml: {e}");
+            Default::default()
+        });
 
     // Ghost preview buffers (unit cube + single-instance buffer)
     let (ghost_vb, ghost_ib, ghost_index_count) = crate::gfx::mesh::create_cube(&device);
@@ -1997,6 +2002,7 @@ pub async fn new_renderer(window: &Window) -> anyhow::Result<crate::gfx::Rendere
         destruct_instances: scene_build.destruct_instances,
         repl_rx: None,
         repl_buf: Default::default(),
+        join: None,
         boss_status_next_emit: 0.0,
         voxel_model_bg,
         debris_vb,
@@ -2026,6 +2032,7 @@ pub async fn new_renderer(window: &Window) -> anyhow::Result<crate::gfx::Rendere
         pc_prev_airborne: false,
         pc_jump_start_time: None,
         pc_land_start_time: None,
+        pc_dodge_start_time: None,
         pc_cast_shoot_time: None,
         pc_cast_end_time: None,
         cam_yaw_prev: 0.0,
@@ -2033,6 +2040,7 @@ pub async fn new_renderer(window: &Window) -> anyhow::Result<crate::gfx::Rendere
         cam_face_reset_at: 0.0,
         cam_prev_panic: false,
         shift_down: false,
+        dodge_queued: false,
         pc_cast_time,
         magic_missile_cast_time: mm_cast_time,
         magic_missile_cd_dur: mm_cd_dur,
@@ -2119,6 +2127,7 @@ pub async fn new_renderer(window: &Window) -> anyhow::Result<crate::gfx::Rendere
         _sorc_tex_view,
         _sorc_sampler,
         pc_anim_cfg,
+        status_specs,
         pc_anim_missing_warned: Default::default(),
         zone_batches: None,
         // Picker overlay state
@@ -70,6 +70,13 @@ impl Renderer {
                         // based on forward-only gating in render loop
                         self.shift_down = pressed;
                     }
+                    // F: dodge along the current move input (facing when idle);
+                    // the server spends stamina and grants i-frames.
+                    PhysicalKey::Code(KeyCode::KeyF) if self.pc_alive => {
+                        if pressed {
+                            self.dodge_queued = true;
+                        }
+                    }
                     PhysicalKey::Code(KeyCode::Digit1) | PhysicalKey::Code(KeyCode::Numpad1)
                         if self.pc_alive && allow_casting =>
                     {
@@ -85,14 +92,12 @@ impl Renderer {
                                     // Use the character's facing (controller yaw), not camera forward.
                                     let yaw = self.scene_inputs.yaw();
                                     let fwd = glam::vec3(yaw.sin(), 0.0, yaw.cos());
-                                    let p = self.scene_inputs.pos();
-                                    let (h, _n) =
-                                        crate::gfx::terrain::height_at(&self.terrain_cpu, p.x, p.z);
-                                    // Chest-ish origin, nudged forward slightly
-                                    let pos = glam::vec3(p.x, h + 1.4, p.z) + fwd * 0.25;
-                                    let cmd = net_core::command::ClientCmd::FireBolt {
-                                        pos: [pos.x, pos.y, pos.z],
-                                        dir: [fwd.x, fwd.y, fwd.z],
+                                    // The server launches from the PC's authoritative position.
+                                    let cmd = net_core::command::ClientCmd::Cast {
+                                        ability_id: spell_id.into(),
+                                        target: net_core::command::CastTarget::Direction([
+                                            fwd.x, fwd.y, fwd.z,
+                                        ]),
                                     };
                                     let mut payload = Vec::new();
                                     cmd.encode(&mut payload);
@@ -132,13 +137,12 @@ impl Renderer {
                                 if let Some(tx) = &self.cmd_tx {
                                     let yaw = self.scene_inputs.yaw();
                                     let fwd = glam::vec3(yaw.sin(), 0.0, yaw.cos());
-                                    let p = self.scene_inputs.pos();
-                                    let (h, _n) =
-                                        crate::gfx::terrain::height_at(&self.terrain_cpu, p.x, p.z);
-                                    let pos = glam::vec3(p.x, h + 1.4, p.z) + fwd * 0.25;
-                                    let cmd = net_core::command::ClientCmd::MagicMissile {
-                                        pos: [pos.x, pos.y, pos.z],
-                                        dir: [fwd.x, fwd.y, fwd.z],
+                                    // The server launches from the PC's authoritative position.
+                                    let cmd = net_core::command::ClientCmd::Cast {
+                                        ability_id: spell_id.into(),
+                                        target: net_core::command::CastTarget::Direction([
+                                            fwd.x, fwd.y, fwd.z,
+                                        ]),
                                     };
                                     let mut payload = Vec::new();
                                     cmd.encode(&mut payload);
@@ -176,13 +180,12 @@ impl Renderer {
                                 if let Some(tx) = &self.cmd_tx {
                                     let yaw = self.scene_inputs.yaw();
                                     let fwd = glam::vec3(yaw.sin(), 0.0, yaw.cos());
-                                    let p = self.scene_inputs.pos();
-                                    let (h, _n) =
-                                        crate::gfx::terrain::height_at(&self.terrain_cpu, p.x, p.z);
-                                    let pos = glam::vec3(p.x, h + 1.4, p.z) + fwd * 0.25;
-                                    let cmd = net_core::command::ClientCmd::Fireball {
-                                        pos: [pos.x, pos.y, pos.z],
-                                        dir: [fwd.x, fwd.y, fwd.z],
+                                    // The server launches from the PC's authoritative position.
+                                    let cmd = net_core::command::ClientCmd::Cast {
+                                        ability_id: spell_id.into(),
+                                        target: net_core::command::CastTarget::Direction([
+                                            fwd.x, fwd.y, fwd.z,
+                                        ]),
                                     };
                                     let mut payload = Vec::new();
                                     cmd.encode(&mut payload);
@@ -204,6 +207,36 @@ impl Renderer {
                             }
                         }
                     }
+                    // 4/5: weapon attacks (quarterstaff swing, shortbow shot); the
+                    // server rolls to hit and replicates the result as HitFx.
+                    PhysicalKey::Code(
+                        KeyCode::Digit4 | KeyCode::Numpad4 | KeyCode::Digit5 | KeyCode::Numpad5,
+                    ) if self.pc_alive && allow_casting => {
+                        if pressed && let Some(tx) = &self.cmd_tx {
+                            let weapon_id = if matches!(
+                                event.physical_key,
+                                PhysicalKey::Code(KeyCode::Digit4 | KeyCode::Numpad4)
+                            ) {
+                                "quarterstaff"
+                            } else {
+                                "shortbow"
+                            };
+                            log::info!("input: attack with {weapon_id}");
+                            let yaw = self.scene_inputs.yaw();
+                            let fwd = glam::vec3(yaw.sin(), 0.0, yaw.cos());
+                            let cmd = net_core::command::ClientCmd::Attack {
+                                weapon_id: weapon_id.into(),
+                                target: net_core::command::CastTarget::Direction([
+                                    fwd.x, fwd.y, fwd.z,
+                                ]),
+                            };
+                            let mut payload = Vec::new();
+                            cmd.encode(&mut payload);
+                            let mut framed = Vec::with_capacity(payload.len() + 8);
+                            net_core::frame::write_msg(&mut framed, &payload);
+                            let _ = tx.try_send(framed);
+                        }
+                    }
                     // R: respawn only when dead; no other action bindings
                     PhysicalKey::Code(KeyCode::KeyR) => {
                         if pressed && !self.pc_alive {
@@ -163,6 +163,11 @@ pub fn render_impl(
             let mut total = 0usize;
             for b in &msgs {
                 total += b.len();
+                if let Some(join) = r.join.as_mut()
+                    && join.handle(b, &mut r.repl_buf)
+                {
+                    continue;
+                }
                 let _ = r.repl_buf.apply_message(b);
             }
             metrics::counter!("net.bytes_recv_total", "dir" => "rx").increment(total as u64);
@@ -182,6 +187,11 @@ pub fn render_impl(
                     r.repl_buf.npcs.len()
                 );
             }
+            // Rebase local movement prediction on the server's PC position
+            if let Some(pcw) = r.repl_buf.wizards.iter().find(|w| w.is_pc) {
+                r.scene_inputs
+                    .reconcile(pcw.pos, r.repl_buf.last_input_seq());
+            }
             let updates = r.repl_buf.drain_mesh_updates();
             use client_core::upload::MeshUpload;
             for (did, chunk, entry) in updates {
@@ -264,6 +274,9 @@ pub fn render_impl(
                 });
             }
         }
+        // Sample remote actors from the snapshot history so they move smoothly
+        // between (possibly jittery) deltas.
+        r.repl_buf.interpolate(dt);
         // Projectiles: renderer is presentation-only. Prefer replicated projectiles, but
         // keep locally spawned PC projectiles alive briefly to avoid the "eaten immediately"
         // artifact when a cast is accepted locally and replication arrives a frame later.
@@ -369,14 +382,24 @@ pub fn render_impl(
                     let y = pos.y.max(hgt + 0.05);
                     glam::vec3(pos.x, y, pos.z)
                 };
+                // Weapon misses get a dull grey puff; crits a bigger flash.
+                let (core, spark, size) = match h.kind {
+                    net_core::snapshot::HITFX_WEAPON_MISS => {
+                        ([0.5, 0.5, 0.55], [0.4, 0.4, 0.45], 0.04)
+                    }
+                    net_core::snapshot::HITFX_WEAPON_CRIT => {
+                        ([2.4, 1.4, 0.5], [2.0, 1.1, 0.4], 0.1)
+                    }
+                    _ => ([1.8, 1.2, 0.4], [1.6, 0.9, 0.3], 0.06),
+                };
                 // Bright core flash
                 r.particles.push(crate::gfx::fx::Particle {
                     pos,
                     vel: glam::Vec3::new(0.0, 0.6, 0.0),
                     age: 0.0,
                     life: 0.12,
-                    size: 0.06,
-                    color: [1.8, 1.2, 0.4],
+                    size,
+                    color: core,
                 });
                 // Small radial burst (deterministic, 8 spokes)
                 let spokes = 8;
@@ -389,7 +412,7 @@ pub fn render_impl(
                         age: 0.0,
                         life: 0.12,
                         size: 0.015,
-                        color: [1.6, 0.9, 0.3],
+                        color: spark,
                     });
                 }
             }
@@ -728,13 +751,18 @@ pub fn render_impl(
         // Net intent expects LEFT positive for dx, FORWARD positive for dz
         let dx = -v.x;
         let dz = v.y;
-        // Move intent
-        {
-            let cmd = net_core::command::ClientCmd::Move {
-                dx,
-                dz,
-                run: u8::from(r.input.run),
-            };
+        // Move intent: one sequenced input per server tick, predicted locally
+        // and reconciled when replication acks its seq
+        for cmd in r.scene_inputs.predict_moves(dt, dx, dz, r.input.run) {
+            let mut payload = Vec::new();
+            cmd.encode(&mut payload);
+            let mut framed = Vec::with_capacity(payload.len() + 8);
+            net_core::frame::write_msg(&mut framed, &payload);
+            let _ = tx.try_send(framed);
+        }
+        // Dodge along the same intent; the server resolves it next tick
+        if std::mem::take(&mut r.dodge_queued) {
+            let cmd = net_core::command::ClientCmd::Dodge { dx, dz };
             let mut payload = Vec::new();
             cmd.encode(&mut payload);
             let mut framed = Vec::with_capacity(payload.len() + 8);
@@ -750,6 +778,15 @@ pub fn render_impl(
             net_core::frame::write_msg(&mut framed, &payload);
             let _ = tx.try_send(framed);
         }
+        // Ack the newest applied actor delta so the server diffs against it
+        if let Some(tick) = r.repl_buf.last_applied_tick() {
+            let cmd = net_core::command::ClientCmd::Ack { tick };
+            let mut payload = Vec::new();
+            cmd.encode(&mut payload);
+            let mut framed = Vec::with_capacity(payload.len() + 8);
+            net_core::frame::write_msg(&mut framed, &payload);
+            let _ = tx.try_send(framed);
+        }
     }
 
     // Keep model base identity to avoid moving instances globally
@@ -2052,6 +2089,30 @@ pub fn render_impl(
                     cd3_secs,
                     cast_label,
                 );
+                // Buff/debuff icons for the PC
+                if let Some(pc_id) = r.repl_buf.wizards.iter().find(|w| w.is_pc).map(|w| w.id) {
+                    let icons: Vec<crate::gfx::ui::StatusIcon> = r
+                        .repl_buf
+                        .statuses
+                        .iter()
+                        .filter(|s| s.actor == pc_id)
+                        .filter_map(|s| {
+                            let (name, spec) = r.status_specs.by_net_id(s.status)?;
+                            Some(crate::gfx::ui::StatusIcon {
+                                label: if spec.icon.is_empty() {
+                                    name
+                                } else {
+                                    spec.icon.as_str()
+                                },
+                                buff: spec.kind == data_runtime::specs::statuses::StatusKind::Buff,
+                                remaining_s: f32::from(s.remaining_ms) / 1000.0,
+                                stacks: s.stacks,
+                            })
+                        })
+                        .collect();
+                    r.hud
+                        .append_status_icons(r.size.width, r.size.height, &icons);
+                }
             } else {
                 r.hud.reset();
             }
@@ -1541,6 +1541,33 @@ impl Renderer {
                 }
             }
         }
+        // Dodge override: the server flags the dash; play the roll once over it
+        let dodging = self.repl_buf.wizards.iter().any(|w| w.is_pc && w.dodging());
+        if !dodging {
+            self.pc_dodge_start_time = None;
+        } else {
+            let start = *self.pc_dodge_start_time.get_or_insert(time_global);
+            let want = self.pc_anim_cfg.dodge.as_deref().unwrap_or("Roll");
+            let name = if pc_cpu.animations.contains_key(want) {
+                Some(want.to_string())
+            } else {
+                pc_cpu
+                    .animations
+                    .keys()
+                    .find(|k| {
+                        let low = k.to_lowercase();
+                        ["roll", "dodge", "dash"].iter().any(|s| low.contains(s))
+                    })
+                    .cloned()
+            };
+            if let Some(name) = name
+                && let Some(clip) = pc_cpu.animations.get(&name)
+            {
+                let elapsed = (time_global - start).max(0.0);
+                chosen_time = Some(elapsed.min(clip.duration.max(0.0)));
+                chosen_name = Some(name);
+            }
+        }
 
         // Desired names by situation (exact match preferred) if jump/cast didn't take over
         let strafing_only = (self.input.strafe_left || self.input.strafe_right)
@@ -1758,9 +1785,11 @@ impl Renderer {
                                 }
                                 // Send authoritative command to server
                                 if let Some(tx) = &self.cmd_tx {
-                                    let cmd = net_core::command::ClientCmd::FireBolt {
-                                        pos: [spawn.x, spawn.y, spawn.z],
-                                        dir: [dir_w.x, dir_w.y, dir_w.z],
+                                    let cmd = net_core::command::ClientCmd::Cast {
+                                        ability_id: "wiz.fire_bolt.srd521".into(),
+                                        target: net_core::command::CastTarget::Direction([
+                                            dir_w.x, dir_w.y, dir_w.z,
+                                        ]),
                                     };
                                     let mut payload = Vec::new();
                                     cmd.encode(&mut payload);
@@ -1811,9 +1840,11 @@ impl Renderer {
                                     return;
                                 }
                                 if let Some(tx) = &self.cmd_tx {
-                                    let cmd = net_core::command::ClientCmd::MagicMissile {
-                                        pos: [spawn.x, spawn.y, spawn.z],
-                                        dir: [dir_w.x, dir_w.y, dir_w.z],
+                                    let cmd = net_core::command::ClientCmd::Cast {
+                                        ability_id: "wiz.magic_missile.srd521".into(),
+                                        target: net_core::command::CastTarget::Direction([
+                                            dir_w.x, dir_w.y, dir_w.z,
+                                        ]),
                                     };
                                     let mut payload = Vec::new();
                                     cmd.encode(&mut payload);
@@ -1851,9 +1882,11 @@ impl Renderer {
                                     return;
                                 }
                                 if let Some(tx) = &self.cmd_tx {
-                                    let cmd = net_core::command::ClientCmd::Fireball {
-                                        pos: [spawn.x, spawn.y, spawn.z],
-                                        dir: [dir_w.x, dir_w.y, dir_w.z],
+                                    let cmd = net_core::command::ClientCmd::Cast {
+                                        ability_id: "wiz.fireball.srd521".into(),
+                                        target: net_core::command::CastTarget::Direction([
+                                            dir_w.x, dir_w.y, dir_w.z,
+                                        ]),
                                     };
                                     let mut payload = Vec::new();
                                     cmd.encode(&mut payload);
@@ -1063,6 +1063,14 @@ pub struct Hud {
     text_verts: Vec<TextVertex>,
 }
 
+/// One buff/debuff icon for `Hud::append_status_icons`.
+pub struct StatusIcon<'a> {
+    pub label: &'a str,
+    pub buff: bool,
+    pub remaining_s: f32,
+    pub stacks: u8,
+}
+
 impl Hud {
     pub fn new(device: &wgpu::Device, color_format: wgpu::TextureFormat) -> anyhow::Result<Self> {
         // Pipelines
@@ -1682,6 +1690,65 @@ impl Hud {
         self.text_vcount = self.text_verts.len() as u32;
     }
 
+    /// Append a row of status icons below the mana bar: green for buffs, red
+    /// for debuffs, with the label, seconds left and stack count.
+    pub fn append_status_icons(&mut self, surface_w: u32, surface_h: u32, icons: &[StatusIcon]) {
+        let size = 34.0f32;
+        let gap = 6.0f32;
+        let y0 = 56.0f32;
+        let mut x0 = 10.0f32;
+        for icon in icons {
+            let x1 = x0 + size;
+            let y1 = y0 + size;
+            self.push_rect(
+                surface_w,
+                surface_h,
+                x0 - 2.0,
+                y0 - 2.0,
+                x1 + 2.0,
+                y1 + 2.0,
+                [0.05, 0.05, 0.05, 0.95],
+            );
+            let col = if icon.buff {
+                [0.15, 0.55, 0.2, 0.9]
+            } else {
+                [0.6, 0.15, 0.12, 0.9]
+            };
+            self.push_rect(surface_w, surface_h, x0, y0, x1, y1, col);
+            self.push_text_line(
+                surface_w,
+                surface_h,
+                x0 + 3.0,
+                y0 + 14.0,
+                icon.label,
+                [1.0, 1.0, 1.0, 0.95],
+            );
+            let secs = format!("{:.0}", icon.remaining_s.ceil());
+            self.push_text_line(
+                surface_w,
+                surface_h,
+                x0 + 3.0,
+                y1 - 3.0,
+                &secs,
+                [0.95, 0.95, 0.8, 0.95],
+            );
+            if icon.stacks > 1 {
+                let n = format!("x{}", icon.stacks);
+                self.push_text_line(
+                    surface_w,
+                    surface_h,
+                    x1 - 16.0,
+                    y1 - 3.0,
+                    &n,
+                    [1.0, 0.9, 0.4, 1.0],
+                );
+            }
+            x0 = x1 + gap;
+        }
+        self.bars_vcount = self.bars_verts.len() as u32;
+        self.text_vcount = self.text_verts.len() as u32;
+    }
+
     pub fn queue(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
         // Upload bars
         let bbytes: &[u8] = bytemuck::cast_slice(&self.bars_verts);
@@ -0,0 +1,293 @@
+//! Data-driven ability table behind `ClientCmd::Cast`.
+//!
+//! Built from the spells in `SpecDb` (`data/spells/*.json`). Each entry
+//! resolves what `cast_system` needs: cost, cooldown, GCD, range, which
+//! `CastTarget`s the spell accepts, and how it is delivered (projectile kind
+//! and speed). Lookups accept the canonical id (`wiz.fire_bolt.srd521`), its
+//! name segment (`fire_bolt`, the one before the source tag), or the display
+//! name. `SpecDb::get_spell` keys the last segment instead, which is the
+//! source tag and shared by every SRD spell.
+//!
+//! The three built-in spells keep their `SpellId` variants (AI loadouts, HUD
+//! cooldown slots) and their bespoke projectile behavior, but take cost and
+//! cooldowns from their specs like every other spell; `Specs::spells` only
+//! fills in a built-in spell the data directory lacks. Every other spell is
+//! `SpellId::Data(index)` and resolves entirely from its spec. Either way the
+//! spec's attack, save and damage dice become the ability's `SpellRules`.
+
+use std::collections::HashMap;
+
+use data_runtime::spell::SpellSpec as SpellData;
+use glam::Vec3;
+use net_core::command::CastTarget;
+use sim_core::rules::spell::SpellRules;
+
+use crate::{ProjKind, SpellId, SpellsSpec};
+
+const FT_TO_M: f32 = 0.3048;
+
+/// Which `CastTarget`s a spell accepts (from the spec's `targeting`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Targeting {
+    /// `self`, `self-cone`: only `CastTarget::SelfCast`.
+    SelfOnly,
+    /// `unit`: an actor, or a direction to search along.
+    Unit,
+    /// `point-ground`: a ground point, or a direction.
+    Ground,
+    /// `unit-or-object` and anything else: any non-self target.
+    Any,
+}
+
+impl Targeting {
+    fn from_spec(s: &str) -> Self {
+        match s {
+            _ if s.starts_with("self") => Self::SelfOnly,
+            "unit" => Self::Unit,
+            "point-ground" => Self::Ground,
+            _ => Self::Any,
+        }
+    }
+
+    pub fn accepts(self, target: &CastTarget) -> bool {
+        match (self, target) {
+            (Self::SelfOnly, CastTarget::SelfCast) => true,
+            (Self::SelfOnly, _) | (_, CastTarget::SelfCast) => false,
+            (_, CastTarget::Direction(_)) => true,
+            (Self::Unit, CastTarget::Actor(_)) | (Self::Ground, CastTarget::Ground(_)) => true,
+            (Self::Any, _) => true,
+            _ => false,
+        }
+    }
+}
+
+/// How a spell is delivered in the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbilityProjectile {
+    pub kind: ProjKind,
+    /// Launch speed from the spell spec; `None` uses the kind's server tuning.
+    pub speed_mps: Option<f32>,
+}
+
+/// One castable spell, resolved from its spec.
+#[derive(Debug, Clone)]
+pub struct Ability {
+    /// Canonical spec id (e.g., `wiz.fire_bolt.srd521`).
+    pub id: String,
+    pub spell: SpellId,
+    pub cost: i32,
+    pub cooldown_s: f32,
+    pub gcd_s: f32,
+    pub range_m: f32,
+    pub targeting: Targeting,
+    /// `None`: the server has no delivery for this spell yet; casts are refused.
+    pub projectile: Option<AbilityProjectile>,
+    /// Attack, save and damage dice from the spec (`systems::spells`).
+    pub rules: SpellRules,
+}
+
+/// Why a `Cast` could not be queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastReject {
+    UnknownAbility,
+    BadTarget,
+    OutOfRange,
+    NoCaster,
+}
+
+impl CastReject {
+    /// Short label for metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UnknownAbility => "unknown_ability",
+            Self::BadTarget => "bad_target",
+            Self::OutOfRange => "out_of_range",
+            Self::NoCaster => "no_caster",
+        }
+    }
+}
+
+/// Spells indexed by every key `SpecDb` accepts.
+#[derive(Debug, Clone, Default)]
+pub struct AbilityDb {
+    abilities: Vec<Ability>,
+    by_key: HashMap<String, usize>,
+}
+
+/// `fire_bolt` in `wiz.fire_bolt.srd521`; ids without a source tag as-is.
+fn name_segment(id: &str) -> &str {
+    id.rsplit('.').nth(1).unwrap_or(id)
+}
+
+fn builtin_for(id: &str) -> Option<SpellId> {
+    match name_segment(id) {
+        "fire_bolt" => Some(SpellId::Firebolt),
+        "fireball" => Some(SpellId::Fireball),
+        "magic_missile" => Some(SpellId::MagicMissile),
+        _ => None,
+    }
+}
+
+/// Mana cost from `resource_cost`: a number, `{ "mana": n }`, or null.
+fn mana_cost(spec: &SpellData) -> i32 {
+    spec.resource_cost
+        .as_ref()
+        .and_then(|v| v.get("mana").or(Some(v)))
+        .and_then(|v| v.as_i64())
+        .and_then(|n| i32::try_from(n).ok())
+        .unwrap_or(0)
+        .max(0)
+}
+
+impl AbilityDb {
+    /// Load every spell under `data/spells`.
+    pub fn load_default() -> Self {
+        let specs = data_runtime::specdb::SpecDb::load_default();
+        let mut spells: Vec<&SpellData> = specs.spells().collect();
+        // Stable `SpellId::Data` indices regardless of directory order.
+        spells.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut db = Self::default();
+        for s in spells {
+            db.insert(s);
+        }
+        db
+    }
+
+    /// Add (or replace) a spell; returns its handle.
+    pub fn insert(&mut self, spec: &SpellData) -> SpellId {
+        let ix = self
+            .by_key
+            .get(&spec.id)
+            .copied()
+            .unwrap_or(self.abilities.len());
+        let builtin = builtin_for(&spec.id);
+        let spell = builtin.unwrap_or(SpellId::Data(u16::try_from(ix).unwrap_or(u16::MAX)));
+        let projectile = match builtin {
+            Some(SpellId::Firebolt) => Some((ProjKind::Firebolt, None)),
+            Some(SpellId::Fireball) => Some((ProjKind::Fireball, None)),
+            Some(SpellId::MagicMissile) => Some((ProjKind::MagicMissile, None)),
+            _ => spec.projectile.as_ref().filter(|p| p.enabled).map(|p| {
+                // Data spells reuse the direct or exploding projectile paths.
+                let kind = if spec.tags.iter().any(|t| t == "aoe") {
+                    ProjKind::Fireball
+                } else {
+                    ProjKind::Firebolt
+                };
+                (kind, Some(p.speed_mps))
+            }),
+        }
+        .map(|(kind, speed_mps)| AbilityProjectile { kind, speed_mps });
+        let ability = Ability {
+            id: spec.id.clone(),
+            spell,
+            cost: mana_cost(spec),
+            cooldown_s: spec.cooldown_s.max(0.0),
+            gcd_s: spec.gcd_s.max(0.0),
+            range_m: spec.range_ft as f32 * FT_TO_M,
+            targeting: Targeting::from_spec(&spec.targeting),
+            projectile,
+            rules: SpellRules::from_spec(spec),
+        };
+        if ix == self.abilities.len() {
+            self.abilities.push(ability);
+        } else {
+            self.abilities[ix] = ability;
+        }
+        let last = name_segment(&spec.id).to_string();
+        let name_key = spec.name.to_ascii_lowercase().replace(' ', "_");
+        for key in [spec.id.clone(), last, name_key] {
+            self.by_key.insert(key, ix);
+        }
+        spell
+    }
+
+    /// Add the built-in spells the data directory did not provide, tuned from
+    /// the server's fallback table.
+    pub fn add_missing_builtins(&mut self, t: &SpellsSpec) {
+        for (spell, id, kind, range_ft, s) in [
+            (
+                SpellId::Firebolt,
+                "wiz.fire_bolt.srd521",
+                ProjKind::Firebolt,
+                120.0,
+                t.firebolt,
+            ),
+            (
+                SpellId::Fireball,
+                "wiz.fireball.srd521",
+                ProjKind::Fireball,
+                150.0,
+                t.fireball,
+            ),
+            (
+                SpellId::MagicMissile,
+                "wiz.magic_missile.srd521",
+                ProjKind::MagicMissile,
+                120.0,
+                t.magic_missile,
+            ),
+        ] {
+            if self.abilities.iter().any(|a| a.spell == spell) {
+                continue;
+            }
+            let ix = self.abilities.len();
+            self.abilities.push(Ability {
+                id: id.to_string(),
+                spell,
+                cost: s.cost,
+                cooldown_s: s.cd_s,
+                gcd_s: s.gcd_s,
+                range_m: range_ft * FT_TO_M,
+                targeting: Targeting::Any,
+                projectile: Some(AbilityProjectile {
+                    kind,
+                    speed_mps: None,
+                }),
+                rules: SpellRules::default(),
+            });
+            self.by_key.insert(id.to_string(), ix);
+            self.by_key.insert(name_segment(id).to_string(), ix);
+        }
+    }
+
+    /// Resolve a wire `ability_id`.
+    pub fn resolve(&self, ability_id: &str) -> Option<&Ability> {
+        let ix = self.by_key.get(ability_id).or_else(|| {
+            let last = name_segment(ability_id);
+            self.by_key.get(last).or_else(|| {
+                self.by_key
+                    .get(&ability_id.to_ascii_lowercase().replace(' ', "_"))
+            })
+        })?;
+        self.abilities.get(*ix)
+    }
+
+    /// Ability for a spell handle.
+    pub fn get(&self, spell: SpellId) -> Option<&Ability> {
+        match spell {
+            SpellId::Data(ix) => self.abilities.get(usize::from(ix)),
+            _ => self.abilities.iter().find(|a| a.spell == spell),
+        }
+    }
+
+    /// Every ability, in handle order.
+    pub fn iter(&self) -> impl Iterator<Item = &Ability> {
+        self.abilities.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.abilities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.abilities.is_empty()
+    }
+}
+
+/// Planar launch direction from `origin` toward `target` (falling back to
+/// `facing` when they coincide) and the distance to the target's edge.
+pub(crate) fn aim_at(origin: Vec3, facing: Vec3, target: Vec3, radius: f32) -> (Vec3, f32) {
+    let d = Vec3::new(target.x - origin.x, 0.0, target.z - origin.z);
+    let dist = (d.length() - radius).max(0.0);
+    (d.try_normalize().unwrap_or(facing), dist)
+}
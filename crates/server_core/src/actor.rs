@@ -5,6 +5,7 @@ use glam::Vec3;
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ActorId(pub u32);
 
+/// Coarse presentation bucket; gameplay comes from the actor's archetype.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ActorKind {
     Wizard,
@@ -12,12 +13,29 @@ pub enum ActorKind {
     Boss,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum Faction {
-    Pc,
-    Wizards,
-    Undead,
-    Neutral,
+impl ActorKind {
+    /// Bucket named by an archetype's `kind` ("wizard", "zombie", "boss").
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "wizard" => Some(Self::Wizard),
+            "zombie" => Some(Self::Zombie),
+            "boss" => Some(Self::Boss),
+            _ => None,
+        }
+    }
+}
+
+/// Faction id from `data/config/factions.toml` (`combat::FactionRegistry`),
+/// replicated as `ActorRep::faction`. The constants are the factions server
+/// code refers to directly; the registry checks the data agrees.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Faction(pub u8);
+
+impl Faction {
+    pub const PC: Self = Self(0);
+    pub const WIZARDS: Self = Self(1);
+    pub const UNDEAD: Self = Self(2);
+    pub const NEUTRAL: Self = Self(3);
 }
 
 /// Preferred terminology in docs and new code paths.
@@ -0,0 +1,282 @@
+//! Spawning actors from archetype data (`ArchetypeSpecDb`).
+//!
+//! `spawn_archetype` builds an actor entirely from its archetype entry: kind,
+//! faction, HP, radius, Armor Class, saving throws, resistances, condition
+//! immunities, and whichever of movement, aggro, melee, spellbook (with
+//! spellcasting numbers), mana pool and weapons the entry enables. Per-spawn tweaks (zone HP, yaw, faction) go
+//! through `ArchetypeOverrides`. Non-PC spawns are nudged out of the PC safety
+//! bubble, destructibles and other actors. The entry's `net_id` is replicated
+//! as `ActorRep::archetype_id`; an entry naming a `script` gets a `BossRun`.
+//! `check_archetypes` rejects data with an unknown kind or faction at load.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use data_runtime::specs::archetypes::{ArchetypeSpec, ArchetypeSpecDb, SaveMods};
+use glam::Vec3;
+
+use crate::actor::{ActorId, ActorKind, Faction, Health, Transform};
+use crate::combat::FactionRegistry;
+use crate::systems::boss::BossRun;
+use crate::{ServerState, SpellId, ecs};
+
+/// Per-spawn changes to an archetype's data; `None` keeps the data value.
+#[derive(Clone, Debug, Default)]
+pub struct ArchetypeOverrides {
+    pub hp: Option<i32>,
+    pub radius: Option<f32>,
+    pub yaw: Option<f32>,
+    pub faction: Option<Faction>,
+    pub name: Option<String>,
+    pub aggro_m: Option<f32>,
+    pub melee_cooldown_s: Option<f32>,
+}
+
+/// Archetypes the named spawn helpers (`spawn_pc`, `spawn_undead`, ...) use.
+const BUILTIN: [&str; 4] = ["PC", "Undead", "DeathKnight", "WizardNPC"];
+
+/// Check that every archetype names a known kind and faction, and that the
+/// entries the named spawn helpers use exist.
+pub fn check_archetypes(db: &ArchetypeSpecDb, factions: &FactionRegistry) -> Result<()> {
+    if let Some(id) = BUILTIN.iter().find(|id| !db.entries.contains_key(**id)) {
+        bail!("archetype '{id}' missing");
+    }
+    for (id, spec) in &db.entries {
+        if ActorKind::parse(&spec.kind).is_none() {
+            bail!("archetype '{id}': unknown kind '{}'", spec.kind);
+        }
+        if factions.id(&spec.faction).is_none() {
+            bail!("archetype '{id}': unknown faction '{}'", spec.faction);
+        }
+    }
+    Ok(())
+}
+
+impl ServerState {
+    /// Spawn archetype `id` at `pos`. `None` if the archetype is unknown or
+    /// names an unknown kind or faction. Unique archetypes return the live
+    /// instance if one exists.
+    pub fn spawn_archetype(
+        &mut self,
+        id: &str,
+        pos: Vec3,
+        ov: &ArchetypeOverrides,
+    ) -> Option<ActorId> {
+        let Some(spec) = self.specs_arche.entries.get(id).cloned() else {
+            log::warn!("server: unknown archetype '{id}'");
+            return None;
+        };
+        if spec.unique
+            && let Some(live) = self.live_unique(id)
+        {
+            return Some(live);
+        }
+        self.spawn_from_spec(id, &spec, pos, ov)
+    }
+
+    /// The living actor of archetype `id`, if any (unique archetypes have at
+    /// most one).
+    pub fn live_unique(&self, id: &str) -> Option<ActorId> {
+        let net_id = self.specs_arche.entries.get(id)?.net_id;
+        self.ecs
+            .iter()
+            .find(|a| a.hp.alive() && a.archetype_id == net_id)
+            .map(|a| a.id)
+    }
+
+    /// Like `spawn_archetype` without the uniqueness check, for the named
+    /// spawn helpers. Panics if `id` is missing or invalid, which
+    /// `check_archetypes` rules out at load.
+    #[allow(clippy::expect_used)]
+    pub(crate) fn spawn_builtin_archetype(
+        &mut self,
+        id: &str,
+        pos: Vec3,
+        ov: &ArchetypeOverrides,
+    ) -> ActorId {
+        let spec = self.specs_arche.entries.get(id).cloned();
+        spec.and_then(|spec| self.spawn_from_spec(id, &spec, pos, ov))
+            .expect("built-in archetypes are checked at load")
+    }
+
+    fn spawn_from_spec(
+        &mut self,
+        id: &str,
+        spec: &ArchetypeSpec,
+        pos: Vec3,
+        ov: &ArchetypeOverrides,
+    ) -> Option<ActorId> {
+        let Some(kind) = ActorKind::parse(&spec.kind) else {
+            log::warn!("server: archetype '{id}' has unknown kind '{}'", spec.kind);
+            return None;
+        };
+        let Some(faction) = ov
+            .faction
+            .or_else(|| self.factions.registry.id(&spec.faction))
+        else {
+            log::warn!(
+                "server: archetype '{id}' has unknown faction '{}'",
+                spec.faction
+            );
+            return None;
+        };
+        let radius = ov.radius.unwrap_or(spec.radius_m);
+        let hp = ov.hp.unwrap_or(spec.hp).max(1);
+        let pos = if faction == Faction::PC {
+            pos
+        } else {
+            let p = crate::push_out_of_pc_bubble(self, pos);
+            let p = crate::push_out_of_destructibles(self, p);
+            crate::push_out_of_actors(self, p, radius)
+        };
+        let known: Vec<SpellId> = spec
+            .spells
+            .iter()
+            .filter_map(|s| {
+                let spell = self.abilities.resolve(s).map(|a| a.spell);
+                if spell.is_none() {
+                    log::warn!("server: archetype '{id}' lists unknown spell '{s}'");
+                }
+                spell
+            })
+            .collect();
+        let weapons: Vec<String> = spec
+            .weapons
+            .iter()
+            .filter(|w| {
+                let ok = self.specs_weapons.get(w).is_some();
+                if !ok {
+                    log::warn!("server: archetype '{id}' lists unknown weapon '{w}'");
+                }
+                ok
+            })
+            .cloned()
+            .collect();
+        let resist: Vec<ecs_core::components::DamageType> = spec
+            .resist
+            .iter()
+            .filter_map(|t| {
+                let dt = ecs_core::parse::parse_damage_type(t);
+                if dt.is_none() {
+                    log::warn!("server: archetype '{id}' resists unknown damage type '{t}'");
+                }
+                dt
+            })
+            .collect();
+        let immune: Vec<ecs_core::components::Condition> = spec
+            .immune
+            .iter()
+            .filter_map(|c| {
+                let cond = ecs_core::parse::parse_condition(c);
+                if cond.is_none() {
+                    log::warn!("server: archetype '{id}' is immune to unknown condition '{c}'");
+                }
+                cond
+            })
+            .collect();
+        let aid = self.ecs.spawn(
+            kind,
+            faction,
+            Transform {
+                pos,
+                yaw: ov.yaw.unwrap_or(0.0),
+                radius,
+            },
+            Health { hp, max: hp },
+        );
+        if let Some(a) = self.ecs.get_mut(aid) {
+            a.archetype_id = spec.net_id;
+            a.name = ov.name.clone().or_else(|| spec.name.clone());
+            if spec.move_speed_mps > 0.0 {
+                a.move_speed = Some(ecs::MoveSpeed {
+                    mps: spec.move_speed_mps,
+                });
+            }
+            let aggro_m = ov.aggro_m.unwrap_or(spec.aggro_radius_m);
+            if aggro_m > 0.0 {
+                a.aggro = Some(ecs::AggroRadius { m: aggro_m });
+            }
+            if spec.melee_damage > 0 {
+                a.attack = Some(ecs::AttackRadius {
+                    m: spec.attack_radius_m,
+                });
+                a.melee = Some(ecs::Melee {
+                    damage: spec.melee_damage,
+                    cooldown_s: ov.melee_cooldown_s.unwrap_or(spec.melee_cooldown_s),
+                    ready_in_s: 0.0,
+                });
+            }
+            if spec.mana > 0 || spec.stamina > 0 || !known.is_empty() {
+                a.pool = Some(ecs::ResourcePool {
+                    mana: spec.mana,
+                    max: spec.mana,
+                    regen_per_s: spec.mana_regen_per_s,
+                    mana_frac: 0.0,
+                    stamina: spec.stamina,
+                    stamina_max: spec.stamina,
+                    stamina_regen_per_s: spec.stamina_regen_per_s,
+                    stamina_frac: 0.0,
+                });
+                a.cooldowns = Some(ecs::Cooldowns {
+                    gcd_s: spec.gcd_s,
+                    gcd_ready: 0.0,
+                    per_spell: HashMap::new(),
+                });
+            }
+            if !known.is_empty() {
+                a.spellbook = Some(ecs::Spellbook { known });
+                a.spellcasting = Some(ecs::Spellcasting {
+                    attack_bonus: spec.spell_attack_bonus,
+                    save_dc: spec.spell_save_dc,
+                    level: spec.level.max(1),
+                });
+            }
+            a.armor = Some(ecs_core::components::ArmorClass { ac: spec.ac });
+            a.saves = Some(saving_throws(&spec.saves));
+            if !resist.is_empty() {
+                a.resist = Some(ecs_core::components::Resistances { damage: resist });
+            }
+            if !immune.is_empty() {
+                a.immune = Some(ecs_core::components::Immunities { conditions: immune });
+            }
+            if !weapons.is_empty() || spec.bag_slots > 0 {
+                a.weapons = Some(ecs::Weapons {
+                    known: weapons,
+                    ready_in_s: 0.0,
+                });
+            }
+            if spec.bag_slots > 0 {
+                a.inventory = Some(ecs::Inventory::new(spec.bag_slots as usize));
+            }
+        }
+        for item in &spec.items {
+            if self.give_item(aid, item, 1) == 0 {
+                log::warn!("server: archetype '{id}' can't carry item '{item}'");
+            }
+        }
+        if let Some(sid) = &spec.script {
+            match self.boss_scripts.get(sid) {
+                Some(script) => {
+                    let run = BossRun::new(Arc::new(script.clone()));
+                    if let Some(a) = self.ecs.get_mut(aid) {
+                        a.boss = Some(run);
+                    }
+                }
+                None => log::warn!("server: archetype '{id}' names unknown boss script '{sid}'"),
+            }
+        }
+        Some(aid)
+    }
+}
+
+fn saving_throws(m: &SaveMods) -> ecs_core::components::SavingThrows {
+    ecs_core::components::SavingThrows {
+        str_mod: m.str,
+        dex_mod: m.dex,
+        con_mod: m.con,
+        int_mod: m.int,
+        wis_mod: m.wis,
+        cha_mod: m.cha,
+    }
+}
@@ -0,0 +1,267 @@
+//! Dedicated headless server.
+//!
+//! Accepts WebSocket and/or UDP clients, admits each through the session
+//! layer's `Hello`/`Welcome` handshake (which gives it its own PC), and steps
+//! the authoritative schedule at a fixed rate. With `--zone`, clients must
+//! request that zone. `--record FILE` logs the session for replay (rewritten
+//! every few seconds); `--replay FILE` verifies such a log headlessly and exits.
+//! `--characters DIR` keeps player characters in DIR across restarts;
+//! `--ruins DIR` keeps the zone's carved destructibles there (restored at boot,
+//! rewritten every minute; a recording made with both embeds the restored
+//! ruins). `--seed N` derives the combat and loot dice from N instead of the
+//! default world seed. SIGINT/SIGTERM stop the loop; characters, ruins and the
+//! recording are then saved one last time. Logging and the optional metrics
+//! exporter follow `data/config/telemetry.toml`.
+//!
+//! Usage: `server [--ws ADDR] [--udp ADDR] [--zone SLUG] [--hz N] [--record FILE]
+//!        [--characters DIR] [--ruins DIR] [--seed N]`
+//!        `server --replay FILE`
+//! Defaults: `--ws 0.0.0.0:7777 --udp 0.0.0.0:7778 --hz 30`, no zone.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, bail};
+use net_core::transport::udp::UdpListener;
+use net_core::transport::ws::WsListener;
+use server_core::ServerState;
+use server_core::session::{SessionConfig, SessionHost};
+
+struct Args {
+    ws: Option<String>,
+    udp: Option<String>,
+    zone: Option<String>,
+    hz: u32,
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    characters: Option<PathBuf>,
+    ruins: Option<PathBuf>,
+    seed: Option<u64>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut args = Args {
+        ws: Some("0.0.0.0:7777".into()),
+        udp: Some("0.0.0.0:7778".into()),
+        zone: None,
+        hz: 30,
+        record: None,
+        replay: None,
+        characters: None,
+        ruins: None,
+        seed: None,
+    };
+    let mut it = std::env::args().skip(1);
+    while let Some(flag) = it.next() {
+        let mut value = || it.next().with_context(|| format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--ws" => args.ws = Some(value()?).filter(|v| v != "off"),
+            "--udp" => args.udp = Some(value()?).filter(|v| v != "off"),
+            "--zone" => args.zone = Some(value()?),
+            "--hz" => args.hz = value()?.parse().context("--hz expects an integer")?,
+            "--record" => args.record = Some(value()?.into()),
+            "--replay" => args.replay = Some(value()?.into()),
+            "--characters" => args.characters = Some(value()?.into()),
+            "--ruins" => args.ruins = Some(value()?.into()),
+            "--seed" => args.seed = Some(value()?.parse().context("--seed expects an integer")?),
+            "-h" | "--help" => {
+                eprintln!(
+                    "usage: server [--ws ADDR|off] [--udp ADDR|off] [--zone SLUG] [--hz N] \
+                     [--record FILE] [--characters DIR] [--ruins DIR] [--seed N] | --replay FILE"
+                );
+                std::process::exit(0);
+            }
+            other => bail!("unknown argument: {other}"),
+        }
+    }
+    if args.hz == 0 {
+        bail!("--hz must be > 0");
+    }
+    Ok(args)
+}
+
+fn main() -> anyhow::Result<()> {
+    let (cfg, cfg_err) = match data_runtime::configs::telemetry::load_default() {
+        Ok(cfg) => (cfg, None),
+        Err(e) => (Default::default(), Some(e)),
+    };
+    let _telemetry = server_core::telemetry::init_telemetry(&cfg)?;
+    if let Some(e) = cfg_err {
+        log::warn!("server: telemetry config unreadable, using defaults: {e:#}");
+    }
+    let args = parse_args()?;
+    if let Some(path) = &args.replay {
+        let rec = server_core::replay::ReplayLog::load(path)?;
+        let ticks = server_core::replay::verify(&rec)
+            .with_context(|| format!("replay {}", path.display()))?;
+        log::info!("server: replay {} matched {ticks} ticks", path.display());
+        return Ok(());
+    }
+
+    let ws = match &args.ws {
+        Some(addr) => Some(WsListener::bind(addr).with_context(|| format!("bind ws {addr}"))?),
+        None => None,
+    };
+    let udp = match &args.udp {
+        Some(addr) => Some(UdpListener::bind(addr).with_context(|| format!("bind udp {addr}"))?),
+        None => None,
+    };
+    if ws.is_none() && udp.is_none() {
+        bail!("no listeners enabled");
+    }
+    if let Some(l) = &ws {
+        log::info!("server: ws listening on {}", l.local_addr()?);
+    }
+    if let Some(l) = &udp {
+        log::info!("server: udp listening on {}", l.local_addr()?);
+    }
+
+    let mut srv = ServerState::new();
+    if let Some(seed) = args.seed {
+        srv.seed_rngs(seed);
+    }
+    if let Some(slug) = &args.zone
+        && !server_core::zones::boot_with_zone(&mut srv, slug)
+    {
+        log::warn!("server: zone '{slug}' has no server content");
+    }
+    let ruins = match (&args.ruins, &args.zone) {
+        (Some(dir), Some(slug)) => {
+            let store = server_core::destructible::persist::DestructibleStore::open(dir)?;
+            match store.load(slug) {
+                Ok(snap) => {
+                    if let Some(snap) = snap {
+                        let r = srv.restore_destructibles(&snap);
+                        log::info!(
+                            "server: restored {} carved voxels ({} chunks rejected, {} proxies skipped)",
+                            r.voxels,
+                            r.chunks_rejected,
+                            r.proxies_skipped
+                        );
+                    }
+                    Some((store, slug.clone()))
+                }
+                Err(e) => {
+                    // Keep the file for inspection rather than overwriting it.
+                    log::warn!("server: ruins not restored or saved: {e:#}");
+                    None
+                }
+            }
+        }
+        (Some(_), None) => {
+            log::warn!("server: --ruins needs --zone; ignored");
+            None
+        }
+        (None, _) => None,
+    };
+    let zone_manifest_id = match &args.zone {
+        Some(slug) => match data_runtime::zone::load_zone_manifest(slug) {
+            Ok(m) => m.zone_id,
+            Err(e) => {
+                log::warn!("server: zone '{slug}' manifest unavailable: {e:#}");
+                0
+            }
+        },
+        None => 0,
+    };
+    let defaults = SessionConfig::default();
+    let spawn_center = args
+        .zone
+        .as_deref()
+        .and_then(|slug| server_core::zones::pc_spawn(&srv, slug))
+        .unwrap_or(defaults.spawn_center);
+    let mut host = SessionHost::new(SessionConfig {
+        tick_hz: args.hz,
+        zone_slug: args.zone.clone(),
+        zone_manifest_id,
+        spawn_center,
+        ..defaults
+    });
+    if let Some(dir) = &args.characters {
+        let store = server_core::persist::CharacterStore::open(dir)?;
+        log::info!("server: characters in {}", dir.display());
+        host.set_character_store(store);
+    }
+    // After the ruins restore: the header embeds them, so `--replay` carves
+    // the same holes back in before playing.
+    if args.record.is_some() {
+        host.start_recording(&srv);
+    }
+    // Rewrite the recording every ~10 s so a killed server still leaves a log.
+    let save_every = u64::from(args.hz) * 10;
+    let ruins_every = u64::from(args.hz) * 60;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    for sig in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        signal_hook::flag::register(sig, Arc::clone(&stop))
+            .context("install shutdown signal handler")?;
+    }
+
+    let dt = 1.0 / args.hz as f32;
+    let period = Duration::from_secs_f32(dt);
+    let mut next = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        if let Some(l) = &ws {
+            loop {
+                match l.try_accept() {
+                    Ok(Some(t)) => {
+                        let _ = host.accept(Box::new(t));
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        log::warn!("server: ws accept failed: {e:#}");
+                        break;
+                    }
+                }
+            }
+        }
+        if let Some(l) = &udp {
+            while let Some(t) = l.try_accept() {
+                let _ = host.accept(Box::new(t));
+            }
+        }
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(dt);
+        host.broadcast(&mut srv);
+        if let (Some(path), Some(rec)) = (&args.record, host.recording())
+            && host.tick().is_multiple_of(save_every)
+            && let Err(e) = rec.save(path)
+        {
+            log::warn!("server: saving recording failed: {e:#}");
+        }
+        if let Some((store, slug)) = &ruins
+            && host.tick().is_multiple_of(ruins_every)
+            && let Err(e) = store.save(&srv.capture_destructibles(slug))
+        {
+            log::warn!("server: saving ruins failed: {e:#}");
+        }
+
+        next += period;
+        let now = Instant::now();
+        if next > now {
+            std::thread::sleep(next - now);
+        } else {
+            // Fell behind; don't try to catch up with a burst of ticks.
+            next = now;
+        }
+    }
+
+    log::info!("server: shutting down at tick {}", host.tick());
+    let saved = host.save_characters(&srv);
+    log::info!("server: saved {saved} characters");
+    if let Some((store, slug)) = &ruins {
+        match store.save(&srv.capture_destructibles(slug)) {
+            Ok(()) => log::info!("server: saved ruins for '{slug}'"),
+            Err(e) => log::warn!("server: saving ruins failed: {e:#}"),
+        }
+    }
+    if let (Some(path), Some(rec)) = (&args.record, host.recording()) {
+        match rec.save(path) {
+            Ok(()) => log::info!("server: saved recording to {}", path.display()),
+            Err(e) => log::warn!("server: saving recording failed: {e:#}"),
+        }
+    }
+    Ok(())
+}
@@ -1,27 +1,300 @@
-//! Faction rules and hostility helpers.
+//! Factions, reputation and hostility (`data/config/factions.toml`).
+//!
+//! [`FactionRegistry`] resolves faction names to ids and knows which
+//! non-player factions fight each other. Players (`Faction::PC`) instead hold
+//! a [`Reputation`] with every faction, starting at the faction's `standing`
+//! and moved by hits and kills; its [`Standing`] band decides whether that
+//! faction and the player are hostile. [`Factions`] keeps the registry and
+//! each player's reputation; every hostility check on the server goes through
+//! [`Factions::hostile`] (targeting, aggro) or [`Factions::can_harm`] (whether
+//! a hit lands). Reputation is stashed by character name when a player leaves
+//! and restored when that character joins again.
 
-use crate::actor::Faction;
+use std::collections::HashMap;
 
-#[inline]
-pub fn are_hostile(a: Faction, b: Faction) -> bool {
-    use Faction::*;
-    matches!(
-        (a, b),
-        (Pc, Undead) | (Undead, Pc) | (Wizards, Undead) | (Undead, Wizards)
-    )
+use anyhow::{Result, bail};
+use data_runtime::specs::factions::{FactionSpecDb, PLAYER_FACTION};
+
+use crate::actor::{ActorId, Faction};
+
+/// Reputation band of a player with a faction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Standing {
+    Hostile,
+    Neutral,
+    Friendly,
+}
+
+#[derive(Clone, Debug)]
+pub struct FactionDef {
+    pub key: String,
+    pub name: String,
+    pub standing: i32,
+    pub hit_rep: i32,
+    pub kill_rep: i32,
+    pub kill_spillover: Vec<(Faction, i32)>,
+}
+
+/// Factions by id with their mutual hostility and reputation bands.
+#[derive(Clone, Debug, Default)]
+pub struct FactionRegistry {
+    defs: HashMap<Faction, FactionDef>,
+    by_key: HashMap<String, Faction>,
+    /// Unordered pairs of non-player factions that fight on sight.
+    hostile: Vec<(Faction, Faction)>,
+    hostile_below: i32,
+    friendly_at: i32,
+    min_rep: i32,
+    max_rep: i32,
+}
+
+impl FactionRegistry {
+    /// Build from specs; the factions with `Faction` constants must keep
+    /// their ids.
+    pub fn from_specs(db: &FactionSpecDb) -> Result<Self> {
+        for (key, id) in [
+            (PLAYER_FACTION, Faction::PC),
+            ("wizards", Faction::WIZARDS),
+            ("undead", Faction::UNDEAD),
+            ("neutral", Faction::NEUTRAL),
+        ] {
+            if db.get(key).map(|f| f.id) != Some(id.0) {
+                bail!("factions: '{key}' must exist with id {}", id.0);
+            }
+        }
+        let by_key: HashMap<String, Faction> = db
+            .factions
+            .iter()
+            .map(|(k, f)| (k.to_ascii_lowercase(), Faction(f.id)))
+            .collect();
+        let id = |k: &String| by_key[&k.to_ascii_lowercase()];
+        let mut hostile = Vec::new();
+        let mut defs = HashMap::new();
+        for (key, f) in &db.factions {
+            let me = Faction(f.id);
+            hostile.extend(f.hostile.iter().map(|o| (me, id(o))));
+            let def = FactionDef {
+                key: key.clone(),
+                name: if f.name.is_empty() {
+                    key.clone()
+                } else {
+                    f.name.clone()
+                },
+                standing: f.standing,
+                hit_rep: f.hit_rep,
+                kill_rep: f.kill_rep,
+                kill_spillover: f.kill_spillover.iter().map(|(o, d)| (id(o), *d)).collect(),
+            };
+            defs.insert(me, def);
+        }
+        Ok(Self {
+            defs,
+            by_key,
+            hostile,
+            hostile_below: db.hostile_below,
+            friendly_at: db.friendly_at,
+            min_rep: db.min_rep,
+            max_rep: db.max_rep,
+        })
+    }
+
+    /// Faction by data key (case-insensitive).
+    pub fn id(&self, key: &str) -> Option<Faction> {
+        self.by_key.get(&key.to_ascii_lowercase()).copied()
+    }
+
+    pub fn get(&self, f: Faction) -> Option<&FactionDef> {
+        self.defs.get(&f)
+    }
+
+    /// Default hostility between two factions, before any player's
+    /// reputation: non-player pairs per `hostile`, players per each faction's
+    /// starting standing.
+    pub fn hostile(&self, a: Faction, b: Faction) -> bool {
+        match (a == Faction::PC, b == Faction::PC) {
+            (true, true) => false,
+            (true, false) => self.band(self.standing(b)) == Standing::Hostile,
+            (false, true) => self.band(self.standing(a)) == Standing::Hostile,
+            (false, false) => self
+                .hostile
+                .iter()
+                .any(|&(x, y)| (x, y) == (a, b) || (x, y) == (b, a)),
+        }
+    }
+
+    /// Reputation players start with.
+    pub fn standing(&self, f: Faction) -> i32 {
+        self.defs.get(&f).map_or(0, |d| d.standing)
+    }
+
+    pub fn band(&self, rep: i32) -> Standing {
+        if rep < self.hostile_below {
+            Standing::Hostile
+        } else if rep >= self.friendly_at {
+            Standing::Friendly
+        } else {
+            Standing::Neutral
+        }
+    }
+
+    fn clamp(&self, rep: i32) -> i32 {
+        rep.clamp(self.min_rep, self.max_rep)
+    }
+
+    /// One row per faction plus the bands, independent of map order (the
+    /// replay header hashes these).
+    pub(crate) fn spec_rows(&self) -> Vec<String> {
+        let mut rows: Vec<String> = self
+            .defs
+            .iter()
+            .map(|(f, d)| {
+                let mut spill = d.kill_spillover.clone();
+                spill.sort_unstable();
+                let mut foes: Vec<Faction> = self
+                    .hostile
+                    .iter()
+                    .filter_map(|&(x, y)| (x == *f).then_some(y))
+                    .collect();
+                foes.sort_unstable();
+                format!(
+                    "{}={}/{}/{}/{}/{} spill={spill:?} hostile={foes:?}",
+                    f.0, d.key, d.name, d.standing, d.hit_rep, d.kill_rep
+                )
+            })
+            .collect();
+        rows.push(format!(
+            "bands={}/{}/{}/{}",
+            self.hostile_below, self.friendly_at, self.min_rep, self.max_rep
+        ));
+        rows
+    }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
-pub struct FactionState {
-    pub pc_vs_wizards_hostile: bool,
+/// A player's reputation with the factions they have affected; the rest sit
+/// at their starting standing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reputation {
+    values: HashMap<Faction, i32>,
 }
 
-impl FactionState {
-    pub fn effective_hostile(&self, a: Faction, b: Faction) -> bool {
-        use Faction::*;
-        match (a, b) {
-            (Pc, Wizards) | (Wizards, Pc) => self.pc_vs_wizards_hostile,
-            _ => are_hostile(a, b),
+impl Reputation {
+    pub fn get(&self, f: Faction) -> Option<i32> {
+        self.values.get(&f).copied()
+    }
+
+    pub fn set(&mut self, f: Faction, rep: i32) {
+        self.values.insert(f, rep);
+    }
+
+    /// Factions with a recorded value, in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (Faction, i32)> + '_ {
+        let mut v: Vec<(Faction, i32)> = self.values.iter().map(|(f, r)| (*f, *r)).collect();
+        v.sort_unstable();
+        v.into_iter()
+    }
+}
+
+/// The registry plus every player's reputation.
+#[derive(Clone, Debug, Default)]
+pub struct Factions {
+    pub registry: FactionRegistry,
+    players: HashMap<ActorId, Reputation>,
+    /// Reputation of characters that left, by character name.
+    saved: HashMap<String, Reputation>,
+}
+
+impl Factions {
+    pub fn new(registry: FactionRegistry) -> Self {
+        Self {
+            registry,
+            ..Default::default()
+        }
+    }
+
+    /// Whether actors `a` and `b` (of factions `fa`/`fb`) fight: players by
+    /// their reputation band with the other side, everyone else by registry.
+    pub fn hostile(&self, a: ActorId, fa: Faction, b: ActorId, fb: Faction) -> bool {
+        match (fa == Faction::PC, fb == Faction::PC) {
+            (true, true) => false,
+            (true, false) => self.standing(a, fb) == Standing::Hostile,
+            (false, true) => self.standing(b, fa) == Standing::Hostile,
+            (false, false) => self.registry.hostile(fa, fb),
+        }
+    }
+
+    /// Whether `a`'s attacks land on `b`: hostiles, and players hitting
+    /// factions they are merely neutral with.
+    pub fn can_harm(&self, a: ActorId, fa: Faction, b: ActorId, fb: Faction) -> bool {
+        self.hostile(a, fa, b, fb)
+            || (fa == Faction::PC && fb != Faction::PC && self.standing(a, fb) == Standing::Neutral)
+    }
+
+    /// `player`'s reputation with `f`.
+    pub fn reputation(&self, player: ActorId, f: Faction) -> i32 {
+        self.players
+            .get(&player)
+            .and_then(|r| r.get(f))
+            .unwrap_or_else(|| self.registry.standing(f))
+    }
+
+    pub fn standing(&self, player: ActorId, f: Faction) -> Standing {
+        self.registry.band(self.reputation(player, f))
+    }
+
+    /// Move `player`'s reputation with `f` by `delta` (clamped); returns the
+    /// new value.
+    pub fn adjust(&mut self, player: ActorId, f: Faction, delta: i32) -> i32 {
+        let rep = self.registry.clamp(self.reputation(player, f) + delta);
+        self.players.entry(player).or_default().set(f, rep);
+        rep
+    }
+
+    /// `player` hit a member of `victim`. Hitting a faction that is already
+    /// hostile changes nothing, so damage over time does not drain reputation.
+    pub fn on_hit(&mut self, player: ActorId, victim: Faction) {
+        let delta = self.registry.get(victim).map_or(0, |d| d.hit_rep);
+        if delta != 0 && self.standing(player, victim) != Standing::Hostile {
+            self.adjust(player, victim, delta);
+        }
+    }
+
+    /// `player` killed a member of `victim`.
+    pub fn on_kill(&mut self, player: ActorId, victim: Faction) {
+        let Some(def) = self.registry.get(victim) else {
+            return;
+        };
+        let changes: Vec<(Faction, i32)> = std::iter::once((victim, def.kill_rep))
+            .chain(def.kill_spillover.iter().copied())
+            .filter(|(_, d)| *d != 0)
+            .collect();
+        for (f, d) in changes {
+            self.adjust(player, f, d);
+        }
+    }
+
+    /// `player`'s reputation entries, if any changed from the defaults.
+    pub fn player(&self, player: ActorId) -> Option<&Reputation> {
+        self.players.get(&player)
+    }
+
+    /// Replace `player`'s reputation (e.g. loaded from the character store).
+    pub fn set_player(&mut self, player: ActorId, rep: Reputation) {
+        self.players.insert(player, rep);
+    }
+
+    /// Attach `character`'s saved reputation to the actor now playing it.
+    pub fn join(&mut self, player: ActorId, character: &str) {
+        if let Some(r) = self.saved.remove(character) {
+            self.players.insert(player, r);
+        }
+    }
+
+    /// Detach `player`, keeping its reputation under `character` (if named).
+    pub fn leave(&mut self, player: ActorId, character: Option<&str>) -> Option<Reputation> {
+        let rep = self.players.remove(&player)?;
+        if let Some(name) = character {
+            self.saved.insert(name.to_string(), rep.clone());
         }
+        Some(rep)
     }
 }
@@ -134,6 +134,8 @@ pub struct DebrisSpawn {
     pub positions_m: Vec<DVec3>,
     pub velocities_mps: Vec<DVec3>,
     pub masses: Vec<Mass>,
+    /// Every voxel the carve cleared (debris samples only a subset).
+    pub cleared: Vec<UVec3>,
 }
 
 /// Carve a sphere and spawn debris from removed voxels. Selection and jitter are seeded.
@@ -149,6 +151,7 @@ pub fn carve_and_spawn_debris(
     let mut rng = SmallRng::seed_from_u64(hash64(global_seed, impact_id));
     let total = removed.centers_m.len();
     let take = total.min(max_debris);
+    let cleared = removed.voxels;
     let mut positions = removed.centers_m;
     // Reservoir-sample a subset if needed
     if total > take {
@@ -185,6 +188,7 @@ pub fn carve_and_spawn_debris(
         positions_m: positions,
         velocities_mps: velocities,
         masses,
+        cleared,
     }
 }
 
@@ -325,3 +329,5 @@ pub mod config {
 
 // Submodule with ECS registry/state
 pub mod state;
+// Per-zone save/restore of carved voxels
+pub mod persist;
@@ -0,0 +1,270 @@
+//! Per-zone persistence of carved destructibles.
+//!
+//! A [`DestructibleSnapshot`] is a compacted copy of every proxy's scars (the
+//! voxels carves cleared that have not grown back) together with the
+//! `VoxelGrid::chunk_occ_hash` of each chunk they touch.
+//! `ServerState::capture_destructibles` takes one; at boot
+//! `ServerState::restore_destructibles` replays it onto the freshly built,
+//! pristine zone. Each chunk must hash as saved once its voxels are cleared
+//! again (and every voxel to clear must still be solid); otherwise the zone's
+//! geometry changed or the file is damaged, so that chunk is put back as
+//! built and the mismatch is logged. Scar ages carry over, so regrowth picks
+//! up where it stopped; time the server is down does not count. A recording
+//! started after the restore embeds the snapshot in its header
+//! (`replay::ReplayHeader::ruins`), so playback starts from the same ruins.
+//!
+//! [`DestructibleStore`] keeps one JSON file per zone slug, written the same
+//! way as character files (temporary file, then rename).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use glam::UVec3;
+use serde::{Deserialize, Serialize};
+use voxel_proxy::VoxelGrid;
+
+use super::state::{DestructibleId, Scar};
+use crate::ServerState;
+
+/// Bump when the snapshot layout changes.
+pub const DESTRUCTIBLE_FORMAT_VERSION: u32 = 1;
+
+/// Carved state of one zone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DestructibleSnapshot {
+    pub version: u32,
+    pub zone: String,
+    /// Damaged proxies only, by id.
+    pub proxies: Vec<ProxySnapshot>,
+}
+
+/// Scars of one proxy and the occupancy hashes they produce.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxySnapshot {
+    pub did: u64,
+    /// Grid dimensions the voxel indices refer to.
+    pub dims: [u32; 3],
+    /// Every chunk holding a scarred voxel, in chunk order.
+    pub chunks: Vec<ChunkHash>,
+    /// Oldest first.
+    pub scars: Vec<Scar>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkHash {
+    pub chunk: [u32; 3],
+    pub occ_hash: u64,
+}
+
+/// What `restore_destructibles` applied and what it refused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    pub voxels: usize,
+    /// Chunks left pristine because their hash or voxels did not match.
+    pub chunks_rejected: usize,
+    /// Proxies that are gone or changed size.
+    pub proxies_skipped: usize,
+}
+
+impl DestructibleSnapshot {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).context("encode destructibles")
+    }
+
+    pub fn from_json(txt: &str) -> Result<Self> {
+        let snap: Self = serde_json::from_str(txt).context("parse destructibles JSON")?;
+        if snap.version != DESTRUCTIBLE_FORMAT_VERSION {
+            bail!(
+                "destructible format {} (this server reads {DESTRUCTIBLE_FORMAT_VERSION})",
+                snap.version
+            );
+        }
+        Ok(snap)
+    }
+}
+
+/// Zone snapshots on disk, one file per slug.
+#[derive(Debug, Clone)]
+pub struct DestructibleStore {
+    dir: PathBuf,
+}
+
+impl DestructibleStore {
+    /// Use (and create if needed) `dir`.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path_for(&self, zone: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", crate::persist::file_stem(zone)))
+    }
+
+    /// The saved state of `zone`, or `None` if it was never saved.
+    pub fn load(&self, zone: &str) -> Result<Option<DestructibleSnapshot>> {
+        let path = self.path_for(zone);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let txt =
+            std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        let snap = DestructibleSnapshot::from_json(&txt)
+            .with_context(|| format!("load {}", path.display()))?;
+        if snap.zone != zone {
+            bail!("{} holds '{}', not '{zone}'", path.display(), snap.zone);
+        }
+        Ok(Some(snap))
+    }
+
+    /// Write `snap` atomically (temporary file, then rename).
+    pub fn save(&self, snap: &DestructibleSnapshot) -> Result<()> {
+        crate::persist::write_atomic(&self.path_for(&snap.zone), &snap.to_json()?)?;
+        metrics::counter!("persist.destruct_saves_total").increment(1);
+        Ok(())
+    }
+}
+
+impl ServerState {
+    /// Snapshot every damaged proxy for `zone`.
+    pub fn capture_destructibles(&self, zone: &str) -> DestructibleSnapshot {
+        let mut proxies: Vec<_> = self
+            .destruct_registry
+            .proxies
+            .values()
+            .filter(|p| !p.scars.is_empty())
+            .collect();
+        proxies.sort_unstable_by_key(|p| p.did.0);
+        let proxies = proxies
+            .into_iter()
+            .map(|p| {
+                let grid = &p.grid;
+                let chunks: BTreeSet<[u32; 3]> = p
+                    .scars
+                    .iter()
+                    .flat_map(|s| &s.voxels)
+                    .filter_map(|i| grid.coords(*i as usize))
+                    .map(|v| chunk_key(grid, v))
+                    .collect();
+                ProxySnapshot {
+                    did: p.did.0,
+                    dims: grid.dims().to_array(),
+                    chunks: chunks
+                        .into_iter()
+                        .map(|c| ChunkHash {
+                            chunk: c,
+                            occ_hash: grid.chunk_occ_hash(UVec3::from(c)),
+                        })
+                        .collect(),
+                    scars: p.scars.iter().cloned().collect(),
+                }
+            })
+            .collect();
+        DestructibleSnapshot {
+            version: DESTRUCTIBLE_FORMAT_VERSION,
+            zone: zone.to_string(),
+            proxies,
+        }
+    }
+
+    /// Carve `snap` back into the registered proxies (built pristine for the
+    /// zone). Restored scars go ahead of any the proxies already have.
+    pub fn restore_destructibles(&mut self, snap: &DestructibleSnapshot) -> RestoreReport {
+        let mut report = RestoreReport::default();
+        for ps in &snap.proxies {
+            let did = DestructibleId(ps.did);
+            let Some(proxy) = self.destruct_registry.proxies.get_mut(&did) else {
+                log::warn!("persist: '{}' has no destructible {}", snap.zone, ps.did);
+                report.proxies_skipped += 1;
+                continue;
+            };
+            let grid = &mut proxy.grid;
+            let in_range = ps
+                .scars
+                .iter()
+                .flat_map(|s| &s.voxels)
+                .all(|i| grid.coords(*i as usize).is_some());
+            if grid.dims().to_array() != ps.dims || !in_range {
+                log::warn!(
+                    "persist: destructible {} in '{}' is {:?}, saved as {:?}",
+                    ps.did,
+                    snap.zone,
+                    grid.dims(),
+                    ps.dims
+                );
+                report.proxies_skipped += 1;
+                continue;
+            }
+            let saved: HashMap<[u32; 3], u64> =
+                ps.chunks.iter().map(|c| (c.chunk, c.occ_hash)).collect();
+            let mut cleared: BTreeMap<[u32; 3], Vec<UVec3>> = BTreeMap::new();
+            let mut bad: BTreeSet<[u32; 3]> = BTreeSet::new();
+            for i in ps.scars.iter().flat_map(|s| &s.voxels) {
+                let Some(v) = grid.coords(*i as usize) else {
+                    continue;
+                };
+                if grid.is_solid(v.x, v.y, v.z) {
+                    grid.set(v.x, v.y, v.z, false);
+                    cleared.entry(chunk_key(grid, v)).or_default().push(v);
+                } else {
+                    bad.insert(chunk_key(grid, v));
+                }
+            }
+            for (c, voxels) in &cleared {
+                if !bad.contains(c) && saved.get(c) == Some(&grid.chunk_occ_hash(UVec3::from(*c))) {
+                    continue;
+                }
+                for v in voxels {
+                    grid.set(v.x, v.y, v.z, true);
+                }
+                bad.insert(*c);
+            }
+            let kept = |i: &u32| {
+                grid.coords(*i as usize)
+                    .is_some_and(|v| !bad.contains(&chunk_key(grid, v)))
+            };
+            let mut scars: VecDeque<Scar> = ps
+                .scars
+                .iter()
+                .map(|s| Scar {
+                    age_s: s.age_s,
+                    voxels: s.voxels.iter().copied().filter(kept).collect(),
+                })
+                .filter(|s| !s.voxels.is_empty())
+                .collect();
+            let restored: usize = scars.iter().map(|s| s.voxels.len()).sum();
+            for c in &bad {
+                log::warn!(
+                    "persist: destructible {} chunk {c:?} in '{}' does not match its save; left as built",
+                    ps.did,
+                    snap.zone
+                );
+            }
+            report.voxels += restored;
+            report.chunks_rejected += bad.len();
+            scars.extend(proxy.scars.drain(..));
+            proxy.scars = scars;
+            let chunks: Vec<UVec3> = cleared.keys().map(|c| UVec3::from(*c)).collect();
+            crate::systems::destructible::mark_nav_dirty(&mut self.nav, &proxy.grid, &chunks);
+            proxy.dirty.0.extend(chunks);
+            proxy
+                .dirty
+                .0
+                .extend(proxy.grid.pop_dirty_chunks(usize::MAX));
+        }
+        metrics::counter!("persist.destruct_voxels_restored_total").increment(report.voxels as u64);
+        metrics::counter!("persist.destruct_chunks_rejected_total")
+            .increment(report.chunks_rejected as u64);
+        report
+    }
+}
+
+fn chunk_key(grid: &VoxelGrid, v: UVec3) -> [u32; 3] {
+    grid.chunk_of(v.x, v.y, v.z).to_array()
+}
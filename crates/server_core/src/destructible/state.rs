@@ -4,11 +4,14 @@
 //! - Holds per-proxy voxel grids, dirty chunk sets, mesh caches, and colliders.
 //! - Collects `CarveRequest` from gameplay systems and applies them during tick.
 //! - Produces compact `ChunkMeshDelta` records for replication.
+//! - Remembers what each carve cleared (`Scar`) so ruins can be saved per zone
+//!   (`destructible::persist`) and regrow under the zone's `RegrowPolicy`.
 
 use ecs_core::components::{ChunkDirty, ChunkMesh};
 use glam::{Mat4, Vec3};
 use net_core::snapshot::ChunkMeshDelta;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use voxel_proxy::VoxelGrid;
 
 use super::config::DestructibleConfig;
@@ -23,6 +26,23 @@ pub struct WorldAabb {
     pub max: Vec3,
 }
 
+/// Voxels cleared by one carve, as grid indices (`VoxelGrid::index`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scar {
+    /// Seconds of server time since the carve.
+    pub age_s: f32,
+    pub voxels: Vec<u32>,
+}
+
+/// How carved voxels grow back. The default keeps ruins forever.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RegrowPolicy {
+    /// A scar starts to regrow this long after its carve; never when `None`.
+    pub after_s: Option<f32>,
+    /// Voxels restored per second across the zone, oldest scar first.
+    pub voxels_per_s: f32,
+}
+
 /// Per-instance proxy state. Heavy voxel data lives here; ECS entities hold only lightweight refs.
 pub struct DestructibleProxy {
     pub did: DestructibleId,
@@ -34,6 +54,8 @@ pub struct DestructibleProxy {
     pub world_from_object: Mat4,
     pub object_from_world: Mat4,
     pub world_aabb: WorldAabb,
+    /// Carves not yet regrown, oldest first.
+    pub scars: VecDeque<Scar>,
 }
 
 impl DestructibleProxy {
@@ -55,6 +77,7 @@ impl DestructibleProxy {
             world_from_object,
             object_from_world,
             world_aabb,
+            scars: VecDeque::new(),
         };
         // Mark all chunks dirty on creation so we produce initial meshes/deltas
         let dims = proxy.grid.dims();
@@ -81,6 +104,12 @@ pub struct DestructibleRegistry {
     pub cfg: DestructibleConfig,
     /// Chunks touched (meshed or removed) this tick; used to prioritize collider refresh.
     pub touched_this_tick: Vec<(DestructibleId, glam::UVec3)>,
+    pub regrow: RegrowPolicy,
+    /// Fractional regrowth budget carried between ticks.
+    pub regrow_carry: f32,
+    /// Bumped whenever `proxies` gains or loses entries (`insert_proxy`);
+    /// lets the nav grid skip re-deriving its obstacle set every tick.
+    pub generation: u64,
 }
 
 impl std::fmt::Debug for DestructibleRegistry {
@@ -88,6 +117,7 @@ impl std::fmt::Debug for DestructibleRegistry {
         f.debug_struct("DestructibleRegistry")
             .field("proxies", &self.proxies.len())
             .field("pending_mesh_deltas", &self.pending_mesh_deltas.len())
+            .field("regrow", &self.regrow)
             .finish()
     }
 }
@@ -96,5 +126,6 @@ impl DestructibleRegistry {
     /// Register a proxy into the registry.
     pub fn insert_proxy(&mut self, proxy: DestructibleProxy) {
         self.proxies.insert(proxy.did, proxy);
+        self.generation += 1;
     }
 }
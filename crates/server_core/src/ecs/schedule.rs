@@ -9,12 +9,16 @@ use glam::{Vec2, Vec3};
 use crate::ServerState;
 use crate::actor::{ActorId, Faction};
 use crate::ecs::geom::segment_hits_circle_xz;
+use crate::systems::spells::{resolve_area_hits, resolve_spell_hit};
+use ecs_core::components::DamageType;
 
 #[derive(Copy, Clone, Debug)]
 pub struct DamageEvent {
     pub src: Option<ActorId>,
     pub dst: ActorId,
     pub amount: i32,
+    /// Typed damage is halved against a matching `Resistances` entry.
+    pub damage_type: Option<DamageType>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -41,7 +45,6 @@ pub struct HitEvent {
 }
 
 #[derive(Copy, Clone, Debug)]
-#[allow(dead_code)]
 pub struct DeathEvent {
     pub id: ActorId,
     pub killer: Option<ActorId>,
@@ -58,8 +61,9 @@ pub struct Ctx {
     pub hits: Vec<HitEvent>,
     // Server-auth VFX hits to replicate this tick
     pub fx_hits: Vec<net_core::snapshot::HitFx>,
-    // HUD toast codes (transient per-tick; platform drains and sends messages)
-    pub hud_toasts: Vec<u8>,
+    // HUD toast codes and the actor each is for (transient per-tick; platform
+    // drains and sends messages)
+    pub hud_toasts: Vec<(ActorId, u8)>,
     // Per-tick destructible carve requests (from projectiles/explosions)
     pub carves: Vec<ecs_core::components::CarveRequest>,
     pub deaths: Vec<DeathEvent>,
@@ -71,18 +75,30 @@ pub struct Schedule;
 
 impl Schedule {
     pub fn run(&mut self, srv: &mut ServerState, ctx: &mut Ctx) {
+        let _s = tracing::info_span!("system", name = "dodge_tick").entered();
+        crate::systems::dodge::dodge_tick(srv, ctx.dt);
+        drop(_s);
         let _s = tracing::info_span!("system", name = "input_apply_intents").entered();
         input_apply_intents(srv, ctx);
         drop(_s);
         let _s = tracing::info_span!("system", name = "cooldown_and_mana_tick").entered();
         cooldown_and_mana_tick(srv, ctx);
         drop(_s);
+        let _s = tracing::info_span!("system", name = "threat_update").entered();
+        crate::systems::threat::threat_update(srv, ctx.dt);
+        drop(_s);
+        let _s = tracing::info_span!("system", name = "boss_script_tick").entered();
+        crate::systems::boss::boss_script_tick(srv, ctx);
+        drop(_s);
         let _s = tracing::info_span!("system", name = "ai_caster_cast_and_face").entered();
         ai_caster_cast_and_face(srv, ctx);
         drop(_s);
         let _s = tracing::info_span!("system", name = "cast_system").entered();
         cast_system(srv, ctx);
         drop(_s);
+        let _s = tracing::info_span!("system", name = "weapon_attacks").entered();
+        crate::systems::weapons::weapon_attacks(srv, ctx);
+        drop(_s);
         let _s = tracing::info_span!("system", name = "ingest_projectile_spawns").entered();
         ingest_projectile_spawns(srv, ctx);
         drop(_s);
@@ -92,9 +108,18 @@ impl Schedule {
         let _s = tracing::info_span!("system", name = "spatial.rebuild").entered();
         ctx.spatial.rebuild(srv);
         drop(_s);
+        let _s = tracing::info_span!("system", name = "status_tick").entered();
+        crate::systems::status::status_tick(srv, ctx);
+        drop(_s);
         let _s = tracing::info_span!("system", name = "effects_tick").entered();
         effects_tick(srv, ctx);
         drop(_s);
+        let _s = tracing::info_span!("system", name = "nav_refresh").entered();
+        crate::nav::nav_refresh(srv);
+        drop(_s);
+        let _s = tracing::info_span!("system", name = "spawn_groups").entered();
+        crate::systems::spawn_groups::spawn_groups_tick(srv, ctx.dt);
+        drop(_s);
         let _s = tracing::info_span!("system", name = "ai_move_hostiles").entered();
         ai_move_hostiles(srv, ctx);
         drop(_s);
@@ -126,17 +151,24 @@ impl Schedule {
         destructible_from_projectiles(srv, ctx);
         destructible_from_explosions(srv, ctx);
         crate::systems::destructible::destructible_apply_carves(srv, ctx);
+        crate::systems::destructible::destructible_regrow(srv, ctx);
         crate::systems::destructible::destructible_remesh_budgeted(srv);
         crate::systems::destructible::destructible_refresh_colliders(srv);
         let _s = tracing::info_span!("system", name = "aoe_apply_explosions").entered();
         aoe_apply_explosions(srv, ctx);
         drop(_s);
-        let _s = tracing::info_span!("system", name = "faction_flip_on_pc_hits_wizards").entered();
-        faction_flip_on_pc_hits_wizards(srv, ctx);
+        let _s = tracing::info_span!("system", name = "reputation_from_hits").entered();
+        crate::systems::reputation::reputation_from_hits(srv, ctx);
         drop(_s);
         let _s = tracing::info_span!("system", name = "apply_damage_to_ecs").entered();
         apply_damage_to_ecs(srv, ctx);
         drop(_s);
+        let _s = tracing::info_span!("system", name = "reputation_from_kills").entered();
+        crate::systems::reputation::reputation_from_kills(srv, ctx);
+        drop(_s);
+        let _s = tracing::info_span!("system", name = "loot_tick").entered();
+        crate::systems::loot::loot_tick(srv, ctx);
+        drop(_s);
         // death_fx_and_flags(srv, ctx); // hook reserved for SFX/analytics
         let _s = tracing::info_span!("system", name = "cleanup").entered();
         cleanup(srv, ctx);
@@ -145,10 +177,12 @@ impl Schedule {
     }
 }
 
-fn targets_by_faction(srv: &ServerState, f: crate::actor::Faction) -> Vec<(ActorId, Vec3, f32)> {
+/// Alive non-projectile actors hostile to `id` (of faction `f`).
+fn hostiles_of(srv: &ServerState, id: ActorId, f: Faction) -> Vec<(ActorId, Vec3, f32)> {
     srv.ecs
         .iter()
-        .filter(|a| a.hp.alive() && a.faction == f)
+        .filter(|a| a.hp.alive() && a.projectile.is_none())
+        .filter(|a| srv.factions.hostile(id, f, a.id, a.faction))
         .map(|a| (a.id, a.tr.pos, a.tr.radius))
         .collect()
 }
@@ -159,7 +193,10 @@ fn ingest_projectile_spawns(srv: &mut ServerState, ctx: &mut Ctx) {
     }
     let pending: Vec<_> = srv.pending_projectiles.drain(..).collect();
     for cmd in pending {
-        let spec = srv.projectile_spec(cmd.kind);
+        let mut spec = srv.projectile_spec(cmd.kind);
+        if let Some(v) = cmd.speed_mps {
+            spec.speed_mps = v;
+        }
         let dir_n = cmd.dir.normalize_or_zero();
         let yaw = dir_n.x.atan2(dir_n.z);
         let spawn_pos = cmd.pos + dir_n * 0.35; // offset forward to avoid immediate self-collision
@@ -168,12 +205,13 @@ fn ingest_projectile_spawns(srv: &mut ServerState, ctx: &mut Ctx) {
             let owner_team = cmd
                 .owner
                 .and_then(|id| srv.ecs.get(id).map(|a| a.faction))
-                .unwrap_or(crate::actor::Faction::Pc);
+                .unwrap_or(crate::actor::Faction::PC);
+            let owner = cmd.owner.unwrap_or(ActorId(u32::MAX));
             let mut cands: Vec<(f32, ActorId)> = srv
                 .ecs
                 .iter()
-                .filter(|a| a.hp.alive() && a.id != cmd.owner.unwrap_or(ActorId(u32::MAX)))
-                .filter(|a| srv.factions.effective_hostile(owner_team, a.faction))
+                .filter(|a| a.hp.alive() && a.id != owner)
+                .filter(|a| srv.factions.hostile(owner, owner_team, a.id, a.faction))
                 .map(|a| {
                     let dx = a.tr.pos.x - cmd.pos.x;
                     let dz = a.tr.pos.z - cmd.pos.z;
@@ -199,7 +237,7 @@ fn ingest_projectile_spawns(srv: &mut ServerState, ctx: &mut Ctx) {
                 let comps = crate::ecs::world::Components {
                     id: crate::actor::ActorId(0),
                     kind: crate::actor::ActorKind::Zombie, // placeholder; projectiles are ephemeral, kind unused
-                    faction: crate::actor::Faction::Neutral,
+                    faction: crate::actor::Faction::NEUTRAL,
                     name: None,
                     tr: crate::actor::Transform {
                         pos: spawn_pos,
@@ -224,10 +262,22 @@ fn ingest_projectile_spawns(srv: &mut ServerState, ctx: &mut Ctx) {
                     cooldowns: None,
                     intent_move: None,
                     intent_aim: None,
-                    burning: None,
-                    slow: None,
-                    stunned: None,
+                    intent_dodge: None,
+                    statuses: Vec::new(),
+                    immune: None,
                     despawn_after: None,
+                    nav: None,
+                    member: None,
+                    threat: None,
+                    boss: None,
+                    armor: None,
+                    weapons: None,
+                    saves: None,
+                    resist: None,
+                    spellcasting: None,
+                    dodge: None,
+                    inventory: None,
+                    archetype_id: 0,
                 };
                 ctx.cmd.spawns.push(comps);
             }
@@ -236,7 +286,7 @@ fn ingest_projectile_spawns(srv: &mut ServerState, ctx: &mut Ctx) {
             let comps = crate::ecs::world::Components {
                 id: crate::actor::ActorId(0),
                 kind: crate::actor::ActorKind::Zombie, // unused for projectile
-                faction: crate::actor::Faction::Neutral,
+                faction: crate::actor::Faction::NEUTRAL,
                 name: None,
                 tr: crate::actor::Transform {
                     pos: spawn_pos,
@@ -261,10 +311,22 @@ fn ingest_projectile_spawns(srv: &mut ServerState, ctx: &mut Ctx) {
                 cooldowns: None,
                 intent_move: None,
                 intent_aim: None,
-                burning: None,
-                slow: None,
-                stunned: None,
+                intent_dodge: None,
+                statuses: Vec::new(),
+                immune: None,
                 despawn_after: None,
+                nav: None,
+                member: None,
+                threat: None,
+                boss: None,
+                armor: None,
+                weapons: None,
+                saves: None,
+                resist: None,
+                spellcasting: None,
+                dodge: None,
+                inventory: None,
+                archetype_id: 0,
             };
             ctx.cmd.spawns.push(comps);
         }
@@ -330,33 +392,6 @@ fn homing_update(srv: &mut ServerState, ctx: &mut Ctx) {
 fn effects_tick(srv: &mut ServerState, ctx: &mut Ctx) {
     let dt = ctx.dt;
     for c in srv.ecs.iter_mut() {
-        // Burning ticks damage
-        if let Some(mut b) = c.burning {
-            if b.remaining_s > 0.0 {
-                let dmg = ((b.dps as f32) * dt).floor() as i32;
-                if dmg > 0 {
-                    ctx.dmg.push(DamageEvent {
-                        src: b.src,
-                        dst: c.id,
-                        amount: dmg,
-                    });
-                }
-                b.remaining_s = (b.remaining_s - dt).max(0.0);
-                c.burning = if b.remaining_s > 0.0 { Some(b) } else { None };
-            } else {
-                c.burning = None;
-            }
-        }
-        // Slow decay
-        if let Some(mut s) = c.slow {
-            s.remaining_s = (s.remaining_s - dt).max(0.0);
-            c.slow = if s.remaining_s > 0.0 { Some(s) } else { None };
-        }
-        // Stun decay
-        if let Some(mut s) = c.stunned {
-            s.remaining_s = (s.remaining_s - dt).max(0.0);
-            c.stunned = if s.remaining_s > 0.0 { Some(s) } else { None };
-        }
         // Despawn timers tick in cleanup
         if let Some(mut d) = c.despawn_after {
             d.seconds = (d.seconds - dt).max(0.0);
@@ -374,27 +409,48 @@ fn cooldown_and_mana_tick(srv: &mut ServerState, ctx: &Ctx) {
                 *v = (*v - dt).max(0.0);
             }
         }
-        if let Some(pool) = c.pool.as_mut()
-            && pool.regen_per_s > 0.0
-            && pool.mana < pool.max
-        {
-            pool.mana_frac += pool.regen_per_s * dt;
-            // Account for float error around exact 1.0 boundaries
-            if pool.mana_frac + 1e-6 >= 1.0 {
-                let whole = ((pool.mana_frac + 1e-6).floor()) as i32;
-                if whole > 0 {
-                    pool.mana = (pool.mana + whole).min(pool.max);
-                    pool.mana_frac -= whole as f32;
-                }
-            }
-            if pool.mana >= pool.max {
-                pool.mana = pool.max;
-                pool.mana_frac = 0.0;
-            }
+        if let Some(w) = c.weapons.as_mut() {
+            w.ready_in_s = (w.ready_in_s - dt).max(0.0);
+        }
+        if let Some(pool) = c.pool.as_mut() {
+            regen(
+                &mut pool.mana,
+                pool.max,
+                &mut pool.mana_frac,
+                pool.regen_per_s,
+                dt,
+            );
+            regen(
+                &mut pool.stamina,
+                pool.stamina_max,
+                &mut pool.stamina_frac,
+                pool.stamina_regen_per_s,
+                dt,
+            );
         }
     }
 }
 
+/// Regenerate `value` toward `max` at `per_s`, carrying fractions in `frac`.
+fn regen(value: &mut i32, max: i32, frac: &mut f32, per_s: f32, dt: f32) {
+    if per_s <= 0.0 || *value >= max {
+        return;
+    }
+    *frac += per_s * dt;
+    // Account for float error around exact 1.0 boundaries
+    if *frac + 1e-6 >= 1.0 {
+        let whole = ((*frac + 1e-6).floor()) as i32;
+        if whole > 0 {
+            *value = (*value + whole).min(max);
+            *frac -= whole as f32;
+        }
+    }
+    if *value >= max {
+        *value = max;
+        *frac = 0.0;
+    }
+}
+
 pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
     if srv.pending_casts.is_empty() {
         return;
@@ -407,14 +463,23 @@ pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
         let bypass_gating = std::env::var("RA_SKIP_CAST_GATING")
             .map(|v| v == "1")
             .unwrap_or(false);
-        let (cost, cd_s, gcd_s) = srv.spell_cost_cooldown(cmd.spell);
+        // Cost, cooldown and delivery all come from the spell's ability entry.
+        let Some(ability) = srv.abilities.get(cmd.spell) else {
+            continue;
+        };
+        let (cost, cd_s, gcd_s) = (ability.cost, ability.cooldown_s, ability.gcd_s);
+        let Some(projectile) = ability.projectile else {
+            // Known spell, but the server has no way to deliver it yet.
+            metrics::counter!("cast.rejected_total", "reason" => "no_delivery").increment(1);
+            continue;
+        };
         let Some(c) = srv.ecs.get_mut(caster) else {
             continue;
         };
         // Spellbook check: caster must know the spell
         if let Some(book) = c.spellbook.as_ref() {
             let known =
-                book.known.contains(&cmd.spell) || book.known.contains(&spell_id_map(cmd.spell));
+                book.known.contains(&cmd.spell);
             if !known {
                 if std::env::var("RA_LOG_CASTS").ok().as_deref() == Some("1") {
                     log::info!("srv: cast rejected {:?} (unknown spell)", cmd.spell);
@@ -435,8 +500,8 @@ pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
 
         if !bypass_gating {
             // Stun blocks casting
-            if c.stunned.is_some() {
-                log::info!("srv: cast rejected (stunned)");
+            if c.incapacitated() {
+                log::info!("srv: cast rejected (incapacitated)");
                 continue;
             }
             // Cooldown & mana checks
@@ -451,7 +516,7 @@ pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
                     ok = false;
                 }
                 if ok {
-                    cd.gcd_ready = cd.gcd_s.max(0.0);
+                    cd.gcd_ready = gcd_s.max(0.0);
                     cd.per_spell.insert(cmd.spell, cd_s.max(0.0));
                 }
                 // refresh for logging after potential writes
@@ -469,9 +534,9 @@ pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
                 mana_after = Some(pool.mana);
             }
             if !ok {
-                // Emit HUD toast for insufficient mana for the local PC caster
-                if not_enough_mana && c.faction == crate::actor::Faction::Pc {
-                    ctx.hud_toasts.push(1u8); // 1 = Not enough mana
+                // Emit HUD toast for insufficient mana to the PC that cast
+                if not_enough_mana && c.faction == crate::actor::Faction::PC {
+                    ctx.hud_toasts.push((caster, 1u8)); // 1 = Not enough mana
                 }
                 if std::env::var("RA_LOG_CASTS").ok().as_deref() == Some("1") {
                     let mb = mana_before.unwrap_or(-1);
@@ -497,18 +562,14 @@ pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
         let pos = cmd.pos;
         let dir = cmd.dir;
         let _ = c;
-        // Translate spell to projectiles (no borrow of caster's components beyond id)
-        match spell {
-            crate::SpellId::Firebolt => {
-                srv.spawn_projectile_from(caster, pos, dir, crate::ProjKind::Firebolt)
-            }
-            crate::SpellId::Fireball => {
-                srv.spawn_projectile_from(caster, pos, dir, crate::ProjKind::Fireball)
-            }
-            crate::SpellId::MagicMissile => {
-                srv.spawn_projectile_from(caster, pos, dir, crate::ProjKind::MagicMissile)
-            }
-        }
+        // Deliver as the ability's projectile (no borrow of caster's components beyond id)
+        srv.pending_projectiles.push(crate::PendingProjectile {
+            pos,
+            dir,
+            kind: projectile.kind,
+            owner: Some(caster),
+            speed_mps: projectile.speed_mps,
+        });
         if std::env::var("RA_LOG_CASTS").ok().as_deref() == Some("1") {
             log::info!(
                 target: "server_core::ecs::schedule",
@@ -519,49 +580,36 @@ pub fn cast_system(srv: &mut ServerState, ctx: &mut Ctx) {
     }
 }
 
-#[inline]
-fn spell_id_map(s: crate::SpellId) -> crate::SpellId {
-    s
-}
-
 fn ai_move_hostiles(srv: &mut ServerState, ctx: &Ctx) {
-    // Build target set: NPC Wizards and the PC (Faction::Pc)
-    let mut wiz = targets_by_faction(srv, crate::actor::Faction::Wizards);
-    let mut pc = targets_by_faction(srv, crate::actor::Faction::Pc);
-    wiz.append(&mut pc);
-    if wiz.is_empty() {
-        return;
-    }
-    // Any alive actor with MoveSpeed + AggroRadius and hostile to Wizards
+    // Any alive NPC with MoveSpeed + AggroRadius chases the hostiles it sees
     let mover_ids: Vec<ActorId> = srv
         .ecs
         .iter()
         .filter(|a| a.hp.alive() && a.move_speed.is_some() && a.aggro.is_some())
-        .filter(|a| {
-            srv.factions
-                .effective_hostile(a.faction, crate::actor::Faction::Wizards)
-        })
+        .filter(|a| a.faction != Faction::PC && !a.is_leash_returning())
         .map(|a| a.id)
         .collect();
     for uid in mover_ids {
-        let (pos, rad, speed, extra, aggro_m, stunned) = if let Some(a) = srv.ecs.get(uid) {
-            (
-                a.tr.pos,
-                a.tr.radius,
-                a.move_speed.map(|s| s.mps).unwrap_or(2.0) * a.slow.map(|s| s.mul).unwrap_or(1.0),
-                a.attack.map(|r| r.m).unwrap_or(0.35),
-                a.aggro.map(|ag| ag.m),
-                a.stunned.is_some(),
-            )
-        } else {
-            continue;
-        };
-        if stunned {
+        let (pos, rad, speed, extra, aggro_m, incapacitated, faction) =
+            if let Some(a) = srv.ecs.get(uid) {
+                (
+                    a.tr.pos,
+                    a.tr.radius,
+                    a.move_speed.map(|s| s.mps).unwrap_or(2.0) * a.speed_mul(),
+                    a.attack.map(|r| r.m).unwrap_or(0.35),
+                    a.aggro.map(|ag| ag.m),
+                    a.incapacitated(),
+                    a.faction,
+                )
+            } else {
+                continue;
+            };
+        if incapacitated {
             continue;
         }
-        // Find nearest wizard
+        // Find nearest hostile
         let mut best: Option<(f32, Vec3, f32)> = None;
-        for (_tid, p, r) in &wiz {
+        for (_tid, p, r) in &hostiles_of(srv, uid, faction) {
             let dx = p.x - pos.x;
             let dz = p.z - pos.z;
             let d2 = dx * dx + dz * dz;
@@ -574,16 +622,38 @@ fn ai_move_hostiles(srv: &mut ServerState, ctx: &Ctx) {
                 best = Some((d2, *p, *r));
             }
         }
+        // The threat target wins over the nearest hostile
+        if let Some((_, p, r)) = crate::systems::threat::target_of(srv, uid) {
+            best = Some((0.0, p, r));
+        }
         if let Some((_d2, tp, tr)) = best {
             let to = Vec3::new(tp.x - pos.x, 0.0, tp.z - pos.z);
             let dist = to.length();
             let contact = rad + tr + extra;
             if dist > contact + 0.02 {
-                let step = (speed * ctx.dt).min(dist - contact);
-                if step > 1e-4
-                    && let Some(a) = srv.ecs.get_mut(uid)
-                {
-                    a.tr.pos += to.normalize_or_zero() * step;
+                let Some(a) = srv.ecs.get_mut(uid) else {
+                    continue;
+                };
+                // Straight at the target when the way is clear, else along the
+                // next leg of a planned route around obstacles.
+                let here = Vec2::new(pos.x, pos.z);
+                let goal = Vec2::new(tp.x, tp.z);
+                let Some(next) = crate::nav::steer(&srv.nav, &mut a.nav, here, goal, rad, ctx.dt)
+                else {
+                    continue;
+                };
+                let (dir, room) = if next == goal {
+                    (to.normalize_or_zero(), dist - contact)
+                } else {
+                    let leg = next - here;
+                    (
+                        Vec3::new(leg.x, 0.0, leg.y).normalize_or_zero(),
+                        leg.length(),
+                    )
+                };
+                let step = (speed * ctx.dt).min(room);
+                if step > 1e-4 {
+                    a.tr.pos += dir * step;
                 }
             }
         }
@@ -591,20 +661,17 @@ fn ai_move_hostiles(srv: &mut ServerState, ctx: &Ctx) {
 }
 
 fn melee_apply_when_contact(srv: &mut ServerState, ctx: &mut Ctx) {
-    let wiz = targets_by_faction(srv, crate::actor::Faction::Wizards);
-    // Any hostile actor that has a Melee component
+    // Any NPC that has a Melee component swings at the nearest hostile NPC or
+    // its threat target
     let attacker_ids: Vec<ActorId> = srv
         .ecs
         .iter()
         .filter(|a| a.hp.alive() && a.melee.is_some())
-        .filter(|a| {
-            srv.factions
-                .effective_hostile(a.faction, crate::actor::Faction::Wizards)
-        })
+        .filter(|a| a.faction != Faction::PC && !a.is_leash_returning())
         .map(|a| a.id)
         .collect();
     for uid in attacker_ids {
-        let (pos_u, rad_u, extra, mut cd_ready, cd_total, dmg, stunned) =
+        let (pos_u, rad_u, extra, mut cd_ready, cd_total, dmg, incapacitated, faction) =
             if let Some(a) = srv.ecs.get(uid) {
                 (
                     a.tr.pos,
@@ -613,23 +680,32 @@ fn melee_apply_when_contact(srv: &mut ServerState, ctx: &mut Ctx) {
                     a.melee.map(|m| m.ready_in_s).unwrap_or(0.0),
                     a.melee.map(|m| m.cooldown_s).unwrap_or(0.6),
                     a.melee.map(|m| m.damage).unwrap_or(5),
-                    a.stunned.is_some(),
+                    a.incapacitated(),
+                    a.faction,
                 )
             } else {
                 continue;
             };
-        if stunned {
+        if incapacitated {
             continue;
         }
+        // Players are fought through the threat table below
         let mut best: Option<(ActorId, f32, Vec3, f32)> = None;
-        for (tid, p, r) in &wiz {
+        let npcs = hostiles_of(srv, uid, faction)
+            .into_iter()
+            .filter(|(tid, _, _)| srv.ecs.get(*tid).is_some_and(|t| t.faction != Faction::PC));
+        for (tid, p, r) in npcs {
             let dx = p.x - pos_u.x;
             let dz = p.z - pos_u.z;
             let d2 = dx * dx + dz * dz;
             if best.as_ref().map(|(_, b, _, _)| d2 < *b).unwrap_or(true) {
-                best = Some((*tid, d2, *p, *r));
+                best = Some((tid, d2, p, r));
             }
         }
+        // The threat target wins over the nearest hostile
+        if let Some((tid, p, r)) = crate::systems::threat::target_of(srv, uid) {
+            best = Some((tid, 0.0, p, r));
+        }
         if let Some((tid, _d2, tp, tr)) = best {
             let to = Vec3::new(tp.x - pos_u.x, 0.0, tp.z - pos_u.z);
             let dist = to.length();
@@ -637,11 +713,17 @@ fn melee_apply_when_contact(srv: &mut ServerState, ctx: &mut Ctx) {
             // Cooldown update
             cd_ready = (cd_ready - ctx.dt).max(0.0);
             if dist <= reach && cd_ready <= 0.0 {
-                ctx.dmg.push(DamageEvent {
-                    src: Some(uid),
-                    dst: tid,
-                    amount: dmg,
-                });
+                // A dodging target's i-frames eat the swing.
+                if srv.ecs.get(tid).is_some_and(|t| t.invulnerable()) {
+                    metrics::counter!("dodge.avoided_total", "source" => "melee").increment(1);
+                } else {
+                    ctx.dmg.push(DamageEvent {
+                        src: Some(uid),
+                        dst: tid,
+                        amount: dmg,
+                        damage_type: None,
+                    });
+                }
                 // write back cooldown
                 if let Some(u) = srv.ecs.get_mut(uid)
                     && let Some(m) = &mut u.melee
@@ -667,7 +749,7 @@ fn separate_undead(srv: &mut ServerState) {
     let ids: Vec<ActorId> = srv
         .ecs
         .iter()
-        .filter(|a| a.hp.alive() && a.faction == crate::actor::Faction::Undead)
+        .filter(|a| a.hp.alive() && a.faction == crate::actor::Faction::UNDEAD)
         .map(|a| a.id)
         .collect();
     let n = ids.len();
@@ -731,19 +813,19 @@ fn ai_caster_cast_and_face(srv: &mut ServerState, _ctx: &mut Ctx) {
         .ecs
         .iter()
         .filter(|a| a.hp.alive() && a.spellbook.is_some() && a.pool.is_some())
-        .filter(|a| a.faction != crate::actor::Faction::Pc)
+        .filter(|a| a.faction != crate::actor::Faction::PC)
         .map(|a| a.id)
         .collect();
     for cid in caster_ids {
-        let (cpos, cfaction, stunned) = if let Some(c) = srv.ecs.get(cid) {
-            (c.tr.pos, c.faction, c.stunned.is_some())
+        let (cpos, cfaction, incapacitated) = if let Some(c) = srv.ecs.get(cid) {
+            (c.tr.pos, c.faction, c.incapacitated())
         } else {
             continue;
         };
         // Face nearest hostile
         let mut best: Option<(f32, Vec3, f32, crate::actor::Faction)> = None;
-        for (_id, p, r, tf) in &alive {
-            if !srv.factions.effective_hostile(cfaction, *tf) {
+        for (tid, p, r, tf) in &alive {
+            if !srv.factions.hostile(cid, cfaction, *tid, *tf) {
                 continue;
             }
             let dx = p.x - cpos.x;
@@ -753,6 +835,13 @@ fn ai_caster_cast_and_face(srv: &mut ServerState, _ctx: &mut Ctx) {
                 best = Some((d2, *p, *r, *tf));
             }
         }
+        // ...unless the threat table names a target
+        if let Some((tid, p, r)) = crate::systems::threat::target_of(srv, cid)
+            && let Some((_, _, _, tf)) = alive.iter().find(|a| a.0 == tid)
+        {
+            let (dx, dz) = (p.x - cpos.x, p.z - cpos.z);
+            best = Some((dx * dx + dz * dz, p, r, *tf));
+        }
         let Some((d2, tp, tr, _tf)) = best else {
             continue;
         };
@@ -775,7 +864,7 @@ fn ai_caster_cast_and_face(srv: &mut ServerState, _ctx: &mut Ctx) {
                 });
             }
         }
-        if stunned {
+        if incapacitated {
             continue;
         }
         let dist = d2.sqrt();
@@ -793,8 +882,8 @@ fn ai_caster_cast_and_face(srv: &mut ServerState, _ctx: &mut Ctx) {
             .max(0.0);
         // Count hostiles near target within FB AoE radius
         let mut near_count = 0usize;
-        for (_id, p, _r, tf) in &alive {
-            if !srv.factions.effective_hostile(cfaction, *tf) {
+        for (tid, p, _r, tf) in &alive {
+            if !srv.factions.hostile(cid, cfaction, *tid, *tf) {
                 continue;
             }
             if (*p - tp).length() <= fb_aoe_r {
@@ -862,7 +951,7 @@ fn input_apply_intents(srv: &mut ServerState, ctx: &mut Ctx) {
         }
         // Move intent
         if let Some(mov) = c.intent_move.take() {
-            if c.stunned.is_some() {
+            if c.incapacitated() || c.dodge.is_some() {
                 continue;
             }
             let mut dir = Vec3::new(mov.dx, 0.0, mov.dz);
@@ -871,8 +960,7 @@ fn input_apply_intents(srv: &mut ServerState, ctx: &mut Ctx) {
             }
             dir = dir.normalize();
             let base = c.move_speed.map(|s| s.mps).unwrap_or(5.0);
-            let speed =
-                base * if mov.run { 1.6 } else { 1.0 } * c.slow.map(|s| s.mul).unwrap_or(1.0);
+            let speed = base * if mov.run { 1.6 } else { 1.0 } * c.speed_mul();
             c.tr.pos += dir * speed * dt;
         }
     }
@@ -915,7 +1003,7 @@ fn projectile_collision_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
             list.push((c.id, p0, p1, proj.kind, c.owner.map(|o| o.id), proj.age_s));
         }
     }
-    let mut to_apply_slow: Vec<ActorId> = Vec::new();
+    let mut to_apply_status: Vec<(ActorId, Option<ActorId>)> = Vec::new();
     for (pid, p0, p1, kind, owner, age_s) in list {
         // Arming delay: skip collisions briefly to prevent immediate detonation on spawn and
         // ensure at least one snapshot includes the projectile for visuals.
@@ -926,7 +1014,7 @@ fn projectile_collision_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
         }
         let owner_team = owner
             .and_then(|id| srv.ecs.get(id).map(|a| a.faction))
-            .unwrap_or(Faction::Pc);
+            .unwrap_or(Faction::PC);
         // test against actors (broad-phase via spatial grid)
         let mut hit_any = false;
         let seg_a = Vec2::new(p0.x, p0.z);
@@ -947,11 +1035,13 @@ fn projectile_collision_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
             {
                 continue;
             }
-            // Allow PC→Wizard hits even if faction matrix is neutral (demo parity)
-            let target_team = a.faction;
-            let hostile = srv.factions.effective_hostile(owner_team, target_team)
-                || (owner_team == Faction::Pc && target_team == Faction::Wizards);
-            if !hostile {
+            // Dodge i-frames: the projectile flies on through.
+            if a.invulnerable() {
+                continue;
+            }
+            // Players can hit factions they are not (yet) hostile with
+            let owner_id = owner.unwrap_or(ActorId(u32::MAX));
+            if !srv.factions.can_harm(owner_id, owner_team, a.id, a.faction) {
                 continue;
             }
             if segment_hits_circle_xz(p0, p1, a.tr.pos, a.tr.radius) {
@@ -964,22 +1054,41 @@ fn projectile_collision_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
                         });
                     }
                     _ => {
-                        ctx.dmg.push(DamageEvent {
-                            src: owner,
-                            dst: a.id,
-                            amount: projectile_damage(srv, kind),
-                        });
-                        let kind_byte = match kind {
-                            crate::ProjKind::Firebolt => 0u8,
-                            crate::ProjKind::Fireball => 1u8,
-                            crate::ProjKind::MagicMissile => 2u8,
+                        // Fire Bolt rolls to hit; a miss still spends the bolt.
+                        let aid = a.id;
+                        let resolved = match kind {
+                            crate::ProjKind::Firebolt => {
+                                resolve_spell_hit(srv, crate::SpellId::Firebolt, owner, aid)
+                            }
+                            _ => None,
                         };
-                        ctx.fx_hits.push(net_core::snapshot::HitFx {
-                            kind: kind_byte,
-                            pos: [p1.x, p1.y, p1.z],
-                        });
-                        if matches!(kind, crate::ProjKind::MagicMissile) {
-                            to_apply_slow.push(a.id);
+                        let (amount, damage_type, landed) = match resolved {
+                            Some((out, dt)) => (out.damage, dt, out.hit()),
+                            None => (
+                                projectile_damage(srv, kind),
+                                Some(projectile_damage_type(kind)),
+                                true,
+                            ),
+                        };
+                        if landed {
+                            ctx.dmg.push(DamageEvent {
+                                src: owner,
+                                dst: aid,
+                                amount,
+                                damage_type,
+                            });
+                            let kind_byte = match kind {
+                                crate::ProjKind::Firebolt => 0u8,
+                                crate::ProjKind::Fireball => 1u8,
+                                crate::ProjKind::MagicMissile => 2u8,
+                            };
+                            ctx.fx_hits.push(net_core::snapshot::HitFx {
+                                kind: kind_byte,
+                                pos: [p1.x, p1.y, p1.z],
+                            });
+                            if matches!(kind, crate::ProjKind::MagicMissile) {
+                                to_apply_status.push((aid, owner));
+                            }
                         }
                     }
                 }
@@ -1028,7 +1137,7 @@ fn projectile_collision_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
                         .ecs
                         .get(owner_id)
                         .map(|a| a.faction)
-                        .unwrap_or(crate::actor::Faction::Pc);
+                        .unwrap_or(crate::actor::Faction::PC);
                     if act.faction == owner_team {
                         continue;
                     }
@@ -1059,10 +1168,8 @@ fn projectile_collision_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
             tracing::info!(candidates = cand_ids.len(), hits = local_hits, kind = ?kind, "proj-collision");
         }
     }
-    for id in to_apply_slow {
-        if let Some(t) = srv.ecs.get_mut(id) {
-            t.apply_slow(srv.specs.effects.mm_slow_mul, srv.specs.effects.mm_slow_s);
-        }
+    for (id, src) in to_apply_status {
+        srv.apply_status(id, srv.specs.effects.mm_status, src);
     }
 }
 
@@ -1188,13 +1295,20 @@ pub fn destructible_from_explosions_for_test(srv: &mut ServerState, ctx: &mut Ct
 
 pub fn system_names_for_test() -> Vec<&'static str> {
     vec![
+        "dodge_tick",
         "input_apply_intents",
         "cooldown_and_mana_tick",
+        "threat_update",
+        "boss_script_tick",
         "ai_caster_cast_and_face",
         "cast_system",
+        "weapon_attacks",
         "ingest_projectile_spawns",
         "spatial.rebuild",
+        "status_tick",
         "effects_tick",
+        "nav_refresh",
+        "spawn_groups",
         "ai_move_hostiles",
         "separate_undead",
         "melee_apply_when_contact",
@@ -1205,11 +1319,14 @@ pub fn system_names_for_test() -> Vec<&'static str> {
         "destructible_from_projectiles",
         "destructible_from_explosions",
         "destructible_apply_carves",
+        "destructible_regrow",
         "destructible_remesh_budgeted",
         "destructible_refresh_colliders",
         "aoe_apply_explosions",
-        "faction_flip_on_pc_hits_wizards",
+        "reputation_from_hits",
         "apply_damage_to_ecs",
+        "reputation_from_kills",
+        "loot_tick",
         "cleanup",
     ]
 }
@@ -1221,7 +1338,8 @@ fn aoe_apply_explosions(srv: &mut ServerState, ctx: &mut Ctx) {
             .iter()
             .map(|a| (a.id, a.tr.pos, a.hp.alive()))
             .collect();
-        let mut burning_ids = Vec::new();
+        let mut hit_ids = Vec::new();
+        let mut harmed = Vec::new();
         for (aid, pos, alive) in &snapshot {
             if !*alive {
                 continue;
@@ -1237,57 +1355,68 @@ fn aoe_apply_explosions(srv: &mut ServerState, ctx: &mut Ctx) {
                 e.r2
             };
             if dx * dx + dz * dz <= r_eff2 {
-                // Hostility override for PC→Wizard AoE in demo parity
                 let owner_team = e
                     .src
                     .and_then(|id| srv.ecs.get(id).map(|a| a.faction))
-                    .unwrap_or(Faction::Pc);
+                    .unwrap_or(Faction::PC);
                 let target_team = srv
                     .ecs
                     .get(*aid)
                     .map(|a| a.faction)
-                    .unwrap_or(Faction::Undead);
-                let hostile = srv.factions.effective_hostile(owner_team, target_team)
-                    || (owner_team == Faction::Pc && target_team == Faction::Wizards);
-                if hostile {
-                    ctx.dmg.push(DamageEvent {
-                        src: e.src,
-                        dst: *aid,
-                        amount: projectile_damage_aoe(srv),
-                    });
+                    .unwrap_or(Faction::UNDEAD);
+                let owner_id = e.src.unwrap_or(ActorId(u32::MAX));
+                if srv
+                    .factions
+                    .can_harm(owner_id, owner_team, *aid, target_team)
+                {
+                    harmed.push(*aid);
                 }
-                burning_ids.push(*aid);
+                hit_ids.push(*aid);
             }
         }
-        for id in burning_ids {
-            if let Some(t) = srv.ecs.get_mut(id) {
-                t.apply_burning(
-                    srv.specs.effects.fireball_burn_dps,
-                    srv.specs.effects.fireball_burn_s,
-                    e.src,
-                );
-            }
+        // Each target saves (DEX, half on success); the blast rolls damage once.
+        let (hits, damage_type) =
+            match resolve_area_hits(srv, crate::SpellId::Fireball, e.src, &harmed) {
+                Some(hits) => hits,
+                None => {
+                    let amount = projectile_damage_aoe(srv);
+                    let hits = harmed.iter().map(|&id| (id, amount)).collect();
+                    (hits, Some(DamageType::Fire))
+                }
+            };
+        for (dst, amount) in hits {
+            ctx.dmg.push(DamageEvent {
+                src: e.src,
+                dst,
+                amount,
+                damage_type,
+            });
         }
-    }
-}
-
-fn faction_flip_on_pc_hits_wizards(srv: &mut ServerState, ctx: &mut Ctx) {
-    for d in &ctx.dmg {
-        if let Some(src) = d.src
-            && let (Some(sa), Some(v)) = (srv.ecs.get(src), srv.ecs.get(d.dst))
-            && sa.faction == Faction::Pc
-            && v.faction == Faction::Wizards
-        {
-            srv.factions.pc_vs_wizards_hostile = true;
+        for id in hit_ids {
+            srv.apply_status(id, srv.specs.effects.fireball_status, e.src);
         }
     }
 }
 
 fn apply_damage_to_ecs(srv: &mut ServerState, ctx: &mut Ctx) {
     for d in ctx.dmg.drain(..) {
+        let resistant = srv.ecs.get(d.dst).is_some_and(|a| {
+            d.damage_type
+                .is_some_and(|t| a.resist.as_ref().is_some_and(|r| r.damage.contains(&t)))
+        });
+        // Outgoing damage scales with the source's statuses (e.g. poisoned).
+        let mul = d
+            .src
+            .and_then(|s| srv.ecs.get(s))
+            .map_or(1.0, |a| a.damage_mul());
+        let amount =
+            sim_core::rules::spell::resist((d.amount as f32 * mul).round() as i32, resistant);
+        if let Some(src) = d.src {
+            crate::systems::threat::on_damage(srv, src, d.dst, amount);
+        }
         if let Some(a) = srv.ecs.get_mut(d.dst) {
             let pre = a.hp.hp;
-            a.hp.hp = (a.hp.hp - d.amount).max(0);
+            a.hp.hp = (a.hp.hp - amount).max(0);
             if pre > 0 && a.hp.hp == 0 {
                 ctx.deaths.push(DeathEvent {
                     id: a.id,
@@ -1329,6 +1458,14 @@ fn projectile_damage(srv: &ServerState, kind: crate::ProjKind) -> i32 {
     srv.projectile_spec(kind).damage
 }
 
+#[inline]
+fn projectile_damage_type(kind: crate::ProjKind) -> DamageType {
+    match kind {
+        crate::ProjKind::Firebolt | crate::ProjKind::Fireball => DamageType::Fire,
+        crate::ProjKind::MagicMissile => DamageType::Force,
+    }
+}
+
 #[inline]
 fn projectile_damage_aoe(srv: &ServerState) -> i32 {
     srv.projectile_spec(crate::ProjKind::Fireball).damage
@@ -1470,12 +1607,14 @@ fn homing_acquire_targets(srv: &mut ServerState, ctx: &mut Ctx) {
     }
 
     for pid in proj_ids {
-        let (p_pos, owner_team, homing) = if let Some(c) = srv.ecs.get(pid) {
-            let team = c
-                .owner
-                .and_then(|o| srv.ecs.get(o.id).map(|a| a.faction))
-                .unwrap_or(crate::actor::Faction::Pc);
-            (c.tr.pos, team, c.homing)
+        let (p_pos, owner_id, owner_team, homing) = if let Some(c) = srv.ecs.get(pid) {
+            let owner_id = c.owner.map_or(ActorId(u32::MAX), |o| o.id);
+            let team = srv
+                .ecs
+                .get(owner_id)
+                .map(|a| a.faction)
+                .unwrap_or(crate::actor::Faction::PC);
+            (c.tr.pos, owner_id, team, c.homing)
         } else {
             continue;
         };
@@ -1504,7 +1643,7 @@ fn homing_acquire_targets(srv: &mut ServerState, ctx: &mut Ctx) {
         let mut best: Option<(f32, ActorId)> = None;
         for aid in ctx.spatial.query_circle(center, hm.max_range_m) {
             if let Some((apos, ateam)) = alive.get(&aid) {
-                if !srv.factions.effective_hostile(owner_team, *ateam) {
+                if !srv.factions.hostile(owner_id, owner_team, aid, *ateam) {
                     continue;
                 }
                 let dx = apos.x - p_pos.x;
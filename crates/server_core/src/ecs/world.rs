@@ -1,7 +1,10 @@
 use glam::Vec3;
 
 use crate::actor::{ActorId, ActorKind, Faction, Health, Transform};
-use std::collections::HashMap;
+use data_runtime::specs::items::EquipSlot;
+use ecs_core::components::{Condition, DamageType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Copy, Clone, Debug)]
 pub struct MoveSpeed {
@@ -33,6 +36,8 @@ pub struct Entity(u32);
 pub struct Components {
     pub id: ActorId,
     pub kind: ActorKind,
+    /// Replicated archetype id (`ArchetypeSpec::net_id`); 0 for ad hoc spawns.
+    pub archetype_id: u16,
     pub faction: Faction,
     pub name: Option<String>,
     pub tr: Transform,
@@ -51,11 +56,34 @@ pub struct Components {
     // Intents (authoritative inputs)
     pub intent_move: Option<IntentMove>,
     pub intent_aim: Option<IntentAim>,
+    pub intent_dodge: Option<IntentDodge>,
     // Effects & lifecycle
-    pub burning: Option<Burning>,
-    pub slow: Option<Slow>,
-    pub stunned: Option<Stunned>,
+    /// Active status effects (`systems::status`).
+    pub statuses: Vec<ActiveStatus>,
+    /// Conditions whose statuses don't take on this actor.
+    pub immune: Option<ecs_core::components::Immunities>,
     pub despawn_after: Option<DespawnAfter>,
+    /// Planned route around obstacles (set by hostile movement when needed).
+    pub nav: Option<crate::nav::NavAgent>,
+    /// Zone spawn group membership (leash and idle movement state).
+    pub member: Option<GroupMember>,
+    /// Threat table for AI-driven NPCs (`systems::threat`).
+    pub threat: Option<crate::systems::threat::ThreatTable>,
+    /// Encounter script state for scripted bosses (`systems::boss`).
+    pub boss: Option<crate::systems::boss::BossRun>,
+    /// Armor Class weapon attacks roll against (`systems::weapons`).
+    pub armor: Option<ecs_core::components::ArmorClass>,
+    pub weapons: Option<Weapons>,
+    /// Saving throw modifiers against spells (`systems::spells`).
+    pub saves: Option<ecs_core::components::SavingThrows>,
+    /// Damage types this actor takes half damage from.
+    pub resist: Option<ecs_core::components::Resistances>,
+    /// Spell attack bonus, save DC and level for this actor's spells.
+    pub spellcasting: Option<Spellcasting>,
+    /// Dash in progress (`systems::dodge`).
+    pub dodge: Option<Dodge>,
+    /// Carried and worn items (`systems::inventory`).
+    pub inventory: Option<Inventory>,
 }
 
 #[derive(Default, Debug)]
@@ -94,6 +122,7 @@ impl WorldEcs {
         self.ents.push(Components {
             id,
             kind,
+            archetype_id: 0,
             faction,
             name: None,
             tr,
@@ -111,10 +140,21 @@ impl WorldEcs {
             cooldowns: None,
             intent_move: None,
             intent_aim: None,
-            burning: None,
-            slow: None,
-            stunned: None,
+            intent_dodge: None,
+            statuses: Vec::new(),
+            immune: None,
             despawn_after: None,
+            nav: None,
+            member: None,
+            threat: None,
+            boss: None,
+            armor: None,
+            weapons: None,
+            saves: None,
+            resist: None,
+            spellcasting: None,
+            dodge: None,
+            inventory: None,
         });
         id
     }
@@ -158,9 +198,12 @@ impl WorldEcs {
         self.ents.retain(|c| c.hp.alive());
     }
 
-    /// Helper: find nearest hostile actor to `pos` within optional max radius^2.
+    /// Helper: find the actor nearest to `pos` (within optional max radius^2)
+    /// that is hostile to `id` of `faction`.
     pub fn nearest_hostile(
         &self,
+        factions: &crate::combat::Factions,
+        id: ActorId,
         faction: Faction,
         pos: Vec3,
         max_r2: Option<f32>,
@@ -170,7 +213,7 @@ impl WorldEcs {
             if !c.hp.alive() {
                 continue;
             }
-            if !hostile_default(faction, c.faction) {
+            if !factions.hostile(id, faction, c.id, c.faction) {
                 continue;
             }
             let dx = c.tr.pos.x - pos.x;
@@ -190,50 +233,98 @@ impl WorldEcs {
 }
 
 impl Components {
-    pub fn apply_burning(&mut self, dps: i32, dur: f32, src: Option<ActorId>) {
-        self.burning = Some(match self.burning {
-            Some(b) => Burning {
-                dps: b.dps.max(dps),
-                remaining_s: b.remaining_s.max(dur),
-                src: src.or(b.src),
-            },
-            None => Burning {
-                dps,
-                remaining_s: dur,
-                src,
-            },
-        });
+    /// Leashed spawn group member walking home; it ignores targets meanwhile.
+    pub fn is_leash_returning(&self) -> bool {
+        self.member.is_some_and(|m| m.returning)
     }
-    pub fn apply_slow(&mut self, mul: f32, dur: f32) {
-        let m = mul.clamp(0.0, 1.0);
-        self.slow = Some(match self.slow {
-            Some(s) => Slow {
-                mul: s.mul.min(m),
-                remaining_s: s.remaining_s.max(dur),
-            },
-            None => Slow {
-                mul: m,
-                remaining_s: dur,
-            },
-        });
+    /// Inside a dodge's i-frame window: projectiles pass through and melee
+    /// misses.
+    pub fn invulnerable(&self) -> bool {
+        self.dodge.is_some_and(|d| d.iframes_s > 0.0)
     }
-    pub fn apply_stun(&mut self, dur: f32) {
-        self.stunned = Some(match self.stunned {
-            Some(s) => Stunned {
-                remaining_s: s.remaining_s.max(dur),
-            },
-            None => Stunned { remaining_s: dur },
-        });
+    /// The active status with replicated id `net_id`.
+    pub fn status(&self, net_id: u16) -> Option<&ActiveStatus> {
+        self.statuses.iter().find(|s| s.id == net_id)
+    }
+    pub fn has_condition(&self, c: Condition) -> bool {
+        self.statuses.iter().any(|s| s.fx.conditions.contains(c))
+    }
+    /// Stunned, paralyzed, petrified, unconscious or incapacitated: the actor
+    /// can't move, cast, attack or dodge.
+    pub fn incapacitated(&self) -> bool {
+        use Condition::*;
+        [Stunned, Paralyzed, Petrified, Unconscious, Incapacitated]
+            .into_iter()
+            .any(|c| self.has_condition(c))
+    }
+    /// Attacks against the actor roll with advantage.
+    pub fn attacked_with_advantage(&self) -> bool {
+        use Condition::*;
+        [
+            Stunned,
+            Paralyzed,
+            Petrified,
+            Unconscious,
+            Restrained,
+            Blinded,
+        ]
+        .into_iter()
+        .any(|c| self.has_condition(c))
+    }
+    /// Movement speed multiplier; grappled or restrained actors can't move.
+    pub fn speed_mul(&self) -> f32 {
+        if self.has_condition(Condition::Grappled) || self.has_condition(Condition::Restrained) {
+            return 0.0;
+        }
+        self.statuses
+            .iter()
+            .map(|s| s.fx.speed_mul.powi(i32::from(s.stacks)))
+            .product()
+    }
+    /// Multiplier on damage this actor deals.
+    pub fn damage_mul(&self) -> f32 {
+        self.statuses
+            .iter()
+            .map(|s| s.fx.damage_mul.powi(i32::from(s.stacks)))
+            .product()
+    }
+    /// Armor Class granted (or taken) by statuses.
+    pub fn ac_bonus(&self) -> i32 {
+        self.statuses
+            .iter()
+            .map(|s| s.fx.ac_bonus * i32::from(s.stacks))
+            .sum()
+    }
+    /// Armor Class attacks roll against: the actor's own, plus worn items and
+    /// statuses.
+    pub fn armor_class(&self) -> i32 {
+        let worn = self.inventory.as_ref().map_or(0, |i| i.fx.ac_bonus);
+        self.armor
+            .map_or(crate::systems::weapons::DEFAULT_AC, |a| a.ac)
+            + worn
+            + self.ac_bonus()
+    }
+    /// The worn main-hand item is a `weapon`.
+    pub fn wields(&self, weapon: &str) -> bool {
+        self.inventory
+            .as_ref()
+            .and_then(|i| i.fx.weapon.as_ref())
+            .is_some_and(|(w, _)| w == weapon)
+    }
+    /// Attack bonus the worn main-hand item adds to attacks with `weapon`.
+    pub fn weapon_bonus(&self, weapon: &str) -> i32 {
+        self.inventory
+            .as_ref()
+            .and_then(|i| i.fx.weapon.as_ref())
+            .filter(|(w, _)| w == weapon)
+            .map_or(0, |(_, b)| *b)
+    }
+    /// Immune to at least one of `conditions`.
+    pub fn immune_to_any(&self, conditions: ConditionSet) -> bool {
+        self.immune
+            .as_ref()
+            .is_some_and(|i| i.conditions.iter().any(|&c| conditions.contains(c)))
     }
-}
-
-#[inline]
-fn hostile_default(a: Faction, b: Faction) -> bool {
-    use Faction::*;
-    matches!(
-        (a, b),
-        (Pc, Undead) | (Undead, Pc) | (Wizards, Undead) | (Undead, Wizards)
-    )
 }
 
 // ----------------------------------------------------------------------------
@@ -267,23 +358,56 @@ pub struct Homing {
 
 // Status effects --------------------------------------------------------------
 
+/// One status effect on an actor (`systems::status`).
 #[derive(Copy, Clone, Debug)]
-pub struct Burning {
-    pub dps: i32,
+pub struct ActiveStatus {
+    /// `StatusSpec::net_id`.
+    pub id: u16,
+    pub stacks: u8,
     pub remaining_s: f32,
-    pub src: Option<crate::actor::ActorId>,
+    /// Seconds until the next damage tick.
+    pub next_tick_s: f32,
+    pub src: Option<ActorId>,
+    /// Per-stack effect, resolved from the spec when applied.
+    pub fx: StatusFx,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct Slow {
-    /// Multiply base MoveSpeed.mps by this factor (0.0..=1.0).
-    pub mul: f32,
-    pub remaining_s: f32,
+/// What one stack of a status does.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StatusFx {
+    pub tick_s: f32,
+    pub tick_damage: i32,
+    pub damage_type: Option<DamageType>,
+    pub speed_mul: f32,
+    pub damage_mul: f32,
+    pub ac_bonus: i32,
+    pub conditions: ConditionSet,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct Stunned {
-    pub remaining_s: f32,
+/// Set of SRD conditions.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConditionSet(u16);
+
+impl ConditionSet {
+    pub fn insert(&mut self, c: Condition) {
+        self.0 |= 1 << c as u16;
+    }
+    pub fn contains(self, c: Condition) -> bool {
+        self.0 & (1 << c as u16) != 0
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl FromIterator<Condition> for ConditionSet {
+    fn from_iter<I: IntoIterator<Item = Condition>>(iter: I) -> Self {
+        let mut set = Self::default();
+        for c in iter {
+            set.insert(c);
+        }
+        set
+    }
 }
 
 // Lifecycle -------------------------------------------------------------------
@@ -293,6 +417,20 @@ pub struct DespawnAfter {
     pub seconds: f32,
 }
 
+/// Slot in a zone spawn group (`systems::spawn_groups`).
+#[derive(Copy, Clone, Debug)]
+pub struct GroupMember {
+    pub group: u32,
+    pub slot: u32,
+    /// Where the current pull started; leashing walks back here.
+    pub anchor: Vec3,
+    /// Leashed and walking back to `anchor`; targets are ignored meanwhile.
+    pub returning: bool,
+    pub wander_goal: Option<Vec3>,
+    pub pause_s: f32,
+    pub patrol_idx: u32,
+}
+
 // ----------------------------------------------------------------------------
 // Intents (authoritative inputs)
 // ----------------------------------------------------------------------------
@@ -309,6 +447,25 @@ pub struct IntentAim {
     pub yaw: f32,
 }
 
+/// Requested dodge direction (same axes as `IntentMove`).
+#[derive(Copy, Clone, Debug)]
+pub struct IntentDodge {
+    pub dx: f32,
+    pub dz: f32,
+}
+
+/// A dash in progress (`systems::dodge`).
+#[derive(Copy, Clone, Debug)]
+pub struct Dodge {
+    /// Unit direction on the XZ plane.
+    pub dir: Vec3,
+    pub speed_mps: f32,
+    /// Seconds of movement left.
+    pub remaining_s: f32,
+    /// Seconds of invulnerability left.
+    pub iframes_s: f32,
+}
+
 // ----------------------------------------------------------------------------
 // Casting-related components
 // ----------------------------------------------------------------------------
@@ -325,6 +482,11 @@ pub struct ResourcePool {
     pub regen_per_s: f32,
     /// Fractional accumulator for mana regeneration to avoid truncation per tick
     pub mana_frac: f32,
+    /// Stamina spent by dodges (`systems::dodge`).
+    pub stamina: i32,
+    pub stamina_max: i32,
+    pub stamina_regen_per_s: f32,
+    pub stamina_frac: f32,
 }
 
 #[derive(Clone, Debug)]
@@ -334,6 +496,94 @@ pub struct Cooldowns {
     pub per_spell: HashMap<crate::SpellId, f32>,
 }
 
+/// Spellcasting numbers for `sim_core::rules::spell` (`systems::spells`).
+/// The default is a first-level SRD wizard (+5 to hit, DC 13).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Spellcasting {
+    pub attack_bonus: i32,
+    pub save_dc: i32,
+    /// Character level, for cantrip damage bands.
+    pub level: u8,
+}
+
+impl Default for Spellcasting {
+    fn default() -> Self {
+        Self {
+            attack_bonus: 5,
+            save_dc: 13,
+            level: 1,
+        }
+    }
+}
+
+/// Weapons an actor can attack with (`systems::weapons`).
+#[derive(Clone, Debug)]
+pub struct Weapons {
+    /// Ids in `WeaponSpecDb`.
+    pub known: Vec<String>,
+    /// Seconds until the next attack; shared by all weapons.
+    pub ready_in_s: f32,
+}
+
+// ----------------------------------------------------------------------------
+// Items
+// ----------------------------------------------------------------------------
+
+/// A stack of items by `ItemSpecDb` id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u32,
+}
+
+/// Carried items and what is worn (`systems::inventory`).
+#[derive(Clone, Debug, Default)]
+pub struct Inventory {
+    /// Stacks in the order they were picked up; at most `slots` of them.
+    pub items: Vec<ItemStack>,
+    pub slots: usize,
+    /// Worn item per slot. Worn items stay in `items`.
+    pub equipped: BTreeMap<EquipSlot, String>,
+    /// What the worn items add, refreshed whenever `equipped` changes.
+    pub fx: EquipFx,
+    /// Bumped on every change; sessions resend the inventory when it moves.
+    pub rev: u32,
+}
+
+impl Inventory {
+    pub fn new(slots: usize) -> Self {
+        Self {
+            slots,
+            ..Default::default()
+        }
+    }
+
+    /// Units of `item` carried.
+    pub fn count(&self, item: &str) -> u32 {
+        self.items
+            .iter()
+            .filter(|s| s.item == item)
+            .map(|s| s.count)
+            .sum()
+    }
+
+    /// The slot `item` is worn in.
+    pub fn worn_in(&self, item: &str) -> Option<EquipSlot> {
+        self.equipped
+            .iter()
+            .find(|(_, id)| *id == item)
+            .map(|(slot, _)| *slot)
+    }
+}
+
+/// Modifiers from worn items.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EquipFx {
+    pub ac_bonus: i32,
+    /// Main-hand weapon id (`WeaponSpecDb`) and the item's attack bonus.
+    pub weapon: Option<(String, i32)>,
+}
+
 #[derive(Default)]
 pub struct CmdBuf {
     pub spawns: Vec<Components>,
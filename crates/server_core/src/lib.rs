@@ -5,23 +5,31 @@
 //! - Grid raycast via Amanatides & Woo DDA
 //! - Carve impact sphere + spawn debris with seeded RNG
 
-use ecs_core::components as ec;
 pub mod actor;
 pub mod combat;
 pub use actor::*;
 pub use combat::*;
 use glam::Vec3;
+pub mod abilities;
+pub mod archetype;
 pub mod destructible;
 pub mod ecs;
 pub mod jobs;
+pub mod nav;
+pub mod persist;
+pub mod replay;
 pub mod scene_build;
+pub mod session;
 pub mod systems;
+pub mod telemetry;
 pub mod zones;
 // (destructible module disabled)
 
 // ----------------------------------------------------------------------------
 // Specs (tuning tables)
 // ----------------------------------------------------------------------------
+pub use sim_core::sim::components::threat::ThreatRules;
+
 #[derive(Debug, Clone, Copy)]
 pub struct SpellSpec {
     pub cost: i32,
@@ -29,6 +37,8 @@ pub struct SpellSpec {
     pub gcd_s: f32,
 }
 
+/// Tuning for built-in spells missing from `data/spells`; spells in the data
+/// use their spec's cost and cooldowns.
 #[derive(Debug, Clone, Copy)]
 pub struct SpellsSpec {
     pub firebolt: SpellSpec,
@@ -36,12 +46,13 @@ pub struct SpellsSpec {
     pub magic_missile: SpellSpec,
 }
 
+/// Statuses (`data/config/statuses.toml`) spell hits apply.
 #[derive(Debug, Clone, Copy)]
 pub struct EffectsSpec {
-    pub fireball_burn_dps: i32,
-    pub fireball_burn_s: f32,
-    pub mm_slow_mul: f32,
-    pub mm_slow_s: f32,
+    /// Applied to everything inside a Fireball explosion.
+    pub fireball_status: &'static str,
+    /// Applied to Magic Missile targets.
+    pub mm_status: &'static str,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -51,11 +62,25 @@ pub struct HomingSpec {
     pub reacquire: bool,
 }
 
+/// Dodge dash tuning (`systems::dodge`).
+#[derive(Debug, Clone, Copy)]
+pub struct DodgeSpec {
+    /// Stamina spent per dodge.
+    pub stamina_cost: i32,
+    pub distance_m: f32,
+    pub duration_s: f32,
+    /// Invulnerability window from the start of the dash.
+    pub iframes_s: f32,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Specs {
     pub spells: SpellsSpec,
     pub effects: EffectsSpec,
     pub homing: HomingSpec,
+    /// NPC threat generation, decay and target switching.
+    pub threat: ThreatRules,
+    pub dodge: DodgeSpec,
 }
 
 impl Default for Specs {
@@ -79,38 +104,27 @@ impl Default for Specs {
                 },
             },
             effects: EffectsSpec {
-                fireball_burn_dps: 6,
-                fireball_burn_s: 3.0,
-                mm_slow_mul: 0.7,
-                mm_slow_s: 2.0,
+                fireball_status: "burning",
+                mm_status: "slowed",
             },
             homing: HomingSpec {
                 mm_turn_rate: 3.5,
                 mm_max_range_m: 35.0,
                 reacquire: true,
             },
+            threat: ThreatRules::default(),
+            dodge: DodgeSpec {
+                stamina_cost: 25,
+                distance_m: 4.0,
+                duration_s: 0.30,
+                iframes_s: 0.25,
+            },
         }
     }
 }
 
 // Legacy NPC types removed. Use ActorStore (Zombie/Boss kinds).
 
-/// Stored boss stats built from data_runtime config (no ECS world yet).
-#[derive(Debug, Clone)]
-pub struct NivitaStats {
-    pub name: String,
-    pub ac: i32,
-    pub abilities: ec::Abilities,
-    pub saves: ec::SavingThrows,
-    pub defenses: ec::Defenses,
-    pub legendary: ec::LegendaryResist,
-    pub spellbook: ec::Spellbook,
-    pub radius: f32,
-    pub height: f32,
-    pub team: Option<String>,
-    pub team_id: Option<u32>,
-}
-
 /// Minimal boss status used by clients.
 #[derive(Debug, Clone)]
 pub struct BossStatus {
@@ -128,7 +142,7 @@ pub struct BossStatus {
 /// IMPORTANT: The server is authoritative over all projectile tuning
 /// (speed, lifetime, AoE radius, damage). Clients must never supply
 /// gameplay parameters — they only request a kind.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProjKind {
     Firebolt,
     Fireball,
@@ -142,13 +156,20 @@ pub struct PendingProjectile {
     pub dir: Vec3,
     pub kind: ProjKind,
     pub owner: Option<ActorId>,
+    /// Launch speed override from a spell spec (`None` = the kind's tuning).
+    pub speed_mps: Option<f32>,
 }
 
+/// Spell handle used for spellbooks and cooldowns.
+///
+/// The built-ins have named variants; every other spell in `data/spells` is
+/// `Data(index)` into `ServerState::abilities`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SpellId {
     Firebolt,
     Fireball,
     MagicMissile,
+    Data(u16),
 }
 
 #[derive(Debug, Clone)]
@@ -191,19 +212,58 @@ fn segment_hits_circle_xz(p0: Vec3, p1: Vec3, center: Vec3, radius: f32) -> bool
 
 // Legacy hit events removed.
 
+/// A seeded random stream (attack and damage rolls, loot rolls). Each
+/// stream keeps its seed so a recording can store it and a replay roll the
+/// same dice.
+#[derive(Debug, Clone)]
+pub struct CombatRng(pub rand_chacha::ChaCha8Rng, u64);
+
+impl CombatRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(rand::SeedableRng::seed_from_u64(seed), seed)
+    }
+
+    /// The stream named `stream` under `world_seed`; different names give
+    /// unrelated seeds.
+    pub fn derived(world_seed: u64, stream: &str) -> Self {
+        let mut h = 0xcbf2_9ce4_8422_2325_u64;
+        for &b in world_seed.to_le_bytes().iter().chain(stream.as_bytes()) {
+            h = (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Self::seeded(h)
+    }
+
+    /// The seed this stream started from.
+    pub fn seed(&self) -> u64 {
+        self.1
+    }
+}
+
+impl Default for CombatRng {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+/// World seed the random streams derive from unless `seed_rngs` is called.
+pub const DEFAULT_WORLD_SEED: u64 = 0;
+
 #[derive(Debug, Default)]
 pub struct ServerState {
-    /// Unique boss handle if spawned (e.g., Nivita).
-    pub nivita_actor_id: Option<ActorId>,
-    /// Snapshot of Nivita's boss stats/components for replication/logging.
-    pub nivita_stats: Option<NivitaStats>,
     /// Pending projectile spawns from input (consumed by schedule)
     pub pending_projectiles: Vec<PendingProjectile>,
     /// Pending casts (server-authoritative gating)
     pub pending_casts: Vec<CastCmd>,
+    /// Pending weapon attacks (resolved by `systems::weapons`).
+    pub pending_attacks: Vec<systems::weapons::AttackCmd>,
+    /// Attack and damage rolls.
+    pub combat_rng: CombatRng,
+    /// Loot rolls (`systems::loot`); kept apart so kills don't shift combat dice.
+    pub loot_rng: CombatRng,
     /// New authoritative ECS world (phase 1)
     pub ecs: ecs::WorldEcs,
-    pub factions: FactionState,
+    /// Faction registry (`data/config/factions.toml`) and player reputation.
+    pub factions: Factions,
     /// Cached PC actor id (spawned during sync)
     pub pc_actor: Option<ActorId>,
     /// Tuning tables for spells, effects, and homing.
@@ -212,41 +272,125 @@ pub struct ServerState {
     pub specs_arche: data_runtime::specs::archetypes::ArchetypeSpecDb,
     /// Cached projectile specs (loaded once).
     pub specs_proj: data_runtime::specs::projectiles::ProjectileSpecDb,
+    /// Boss encounter scripts from `data/bosses` (loaded once).
+    pub boss_scripts: data_runtime::specs::boss_scripts::BossScriptDb,
+    /// Weapon specs from `data/config/weapons.toml` (loaded once).
+    pub specs_weapons: data_runtime::specs::weapons::WeaponSpecDb,
+    /// Item specs from `data/items` (loaded once).
+    pub specs_items: data_runtime::specs::items::ItemSpecDb,
+    /// Loot tables from `data/config/loot.toml` (loaded once).
+    pub specs_loot: data_runtime::specs::loot::LootSpecDb,
+    /// Items on the ground, each for one player (`systems::loot`).
+    pub drops: systems::loot::WorldDrops,
+    /// Status effects from `data/config/statuses.toml` (loaded once).
+    pub statuses: systems::status::StatusDb,
+    /// Castable spells resolved from `data/spells` (loaded once).
+    pub abilities: abilities::AbilityDb,
     /// Frame-local hit effects emitted by projectile collisions (drained by platform).
     pub fx_hits: Vec<net_core::snapshot::HitFx>,
-    /// Frame-local HUD toasts emitted by systems (drained by platform).
-    pub hud_toasts: Vec<u8>,
+    /// Frame-local HUD toasts emitted by systems, with the actor each is for
+    /// (drained by platform).
+    pub hud_toasts: Vec<(ActorId, u8)>,
     // Destructible ECS runtime
     pub destruct_registry: crate::destructible::state::DestructibleRegistry,
     pub destruct_instances: Vec<scene_build::DestructibleWorldAabb>,
     pub destruct_bootstrap_instances_outstanding: bool,
+    /// Zone static colliders (walls, props) that NPCs path around.
+    pub static_colliders: Option<collision_static::StaticIndex>,
+    /// Navigation grid over static colliders and destructibles.
+    pub nav: nav::NavGrid,
+    /// Zone spawn groups (respawn, leash, idle movement).
+    pub spawn_groups: Vec<systems::spawn_groups::SpawnGroup>,
 }
 
 impl ServerState {
     // Legacy AoE removed; production path is in ECS schedule systems.
     pub fn new() -> Self {
-        let specs_arche =
-            data_runtime::specs::archetypes::ArchetypeSpecDb::load_default().unwrap_or_default();
+        let factions = data_runtime::specs::factions::FactionSpecDb::load_default()
+            .and_then(|db| FactionRegistry::from_specs(&db))
+            .map(Factions::new)
+            .unwrap_or_else(|e| {
+                log::warn!("server: factions not loaded: {e:#}");
+                Default::default()
+            });
+        // An archetype of unknown kind or faction is a data error; fall back to
+        // the built-in archetypes rather than spawn actors as something else.
+        let specs_arche = data_runtime::specs::archetypes::ArchetypeSpecDb::load_default()
+            .and_then(|db| archetype::check_archetypes(&db, &factions.registry).map(|()| db))
+            .unwrap_or_else(|e| {
+                log::warn!("server: archetypes not loaded, using built-ins: {e:#}");
+                data_runtime::specs::archetypes::ArchetypeSpecDb::builtin()
+            });
         let specs_proj =
             data_runtime::specs::projectiles::ProjectileSpecDb::load_default().unwrap_or_default();
+        let boss_scripts = data_runtime::specs::boss_scripts::BossScriptDb::load_default()
+            .unwrap_or_else(|e| {
+                log::warn!("server: boss scripts not loaded: {e:#}");
+                Default::default()
+            });
+        let specs_weapons = data_runtime::specs::weapons::WeaponSpecDb::load_default()
+            .and_then(|db| systems::weapons::check_weapons(&db).map(|()| db))
+            .unwrap_or_else(|e| {
+                log::warn!("server: weapons not loaded: {e:#}");
+                Default::default()
+            });
+        let specs_items = data_runtime::specs::items::ItemSpecDb::load_default()
+            .unwrap_or_else(|e| {
+                log::warn!("server: items not loaded: {e:#}");
+                Default::default()
+            });
+        // A table keyed by an unknown archetype or dropping an unknown item is
+        // a data error; drop all loot rather than spawn drops nobody can use.
+        let specs_loot = data_runtime::specs::loot::LootSpecDb::load_default()
+            .and_then(|db| db.check_refs(&specs_items, &specs_arche).map(|()| db))
+            .unwrap_or_else(|e| {
+                log::warn!("server: loot tables not loaded: {e:#}");
+                Default::default()
+            });
+        let statuses = data_runtime::specs::statuses::StatusSpecDb::load_default()
+            .map(|db| systems::status::StatusDb::from_specs(&db))
+            .unwrap_or_else(|e| {
+                log::warn!("server: statuses not loaded: {e:#}");
+                Default::default()
+            });
+        let specs = Specs::default();
+        let mut abilities = abilities::AbilityDb::load_default();
+        abilities.add_missing_builtins(&specs.spells);
         Self {
-            nivita_actor_id: None,
-            nivita_stats: None,
             pending_projectiles: Vec::new(),
             pending_casts: Vec::new(),
+            pending_attacks: Vec::new(),
+            combat_rng: CombatRng::derived(DEFAULT_WORLD_SEED, "combat"),
+            loot_rng: CombatRng::derived(DEFAULT_WORLD_SEED, "loot"),
             ecs: ecs::WorldEcs::default(),
-            factions: FactionState::default(),
+            factions,
             pc_actor: None,
-            specs: Specs::default(),
+            specs,
             specs_arche,
             specs_proj,
+            boss_scripts,
+            specs_weapons,
+            specs_items,
+            specs_loot,
+            drops: Default::default(),
+            statuses,
+            abilities,
             fx_hits: Vec::new(),
             hud_toasts: Vec::new(),
             destruct_registry: crate::destructible::state::DestructibleRegistry::default(),
             destruct_instances: Vec::new(),
             destruct_bootstrap_instances_outstanding: false,
+            static_colliders: None,
+            nav: nav::NavGrid::default(),
+            spawn_groups: Vec::new(),
         }
     }
+    /// Restart the combat and loot streams from seeds derived from
+    /// `world_seed`, one per stream.
+    pub fn seed_rngs(&mut self, world_seed: u64) {
+        self.combat_rng = CombatRng::derived(world_seed, "combat");
+        self.loot_rng = CombatRng::derived(world_seed, "loot");
+    }
     /// Provide world AABBs for all known destructible instances as net records.
     pub fn all_destructible_instances(&self) -> Vec<net_core::snapshot::DestructibleInstance> {
         self.destruct_instances
@@ -267,7 +411,8 @@ impl ServerState {
     }
     // Legacy actor rebuild removed. Actors are authoritative.
 
-    /// Apply AoE to actors, skipping self-damage for PC-owned sources and flipping factions when PC hits Wizards.
+    /// Apply AoE to actors, skipping self-damage for PC-owned sources and
+    /// charging the PC reputation with every faction it hits.
     #[allow(dead_code)]
     fn apply_aoe_at_actors(
         &mut self,
@@ -281,7 +426,7 @@ impl ServerState {
         let mut hits = 0usize;
         for a in self.ecs.iter_mut() {
             // Skip self-damage for PC-owned AoE
-            if let (Some(crate::actor::Faction::Pc), Some(src)) = (src_team, source)
+            if let (Some(crate::actor::Faction::PC), Some(src)) = (src_team, source)
                 && a.id == src
             {
                 continue;
@@ -291,10 +436,10 @@ impl ServerState {
             if dx * dx + dz * dz <= r2 && a.hp.alive() {
                 a.hp.hp = (a.hp.hp - damage).max(0);
                 hits += 1;
-                if let Some(crate::actor::Faction::Pc) = src_team
-                    && a.faction == crate::actor::Faction::Wizards
+                if let (Some(crate::actor::Faction::PC), Some(src)) = (src_team, source)
+                    && a.faction != crate::actor::Faction::PC
                 {
-                    self.factions.pc_vs_wizards_hostile = true;
+                    self.factions.on_hit(src, a.faction);
                 }
             }
         }
@@ -320,84 +465,24 @@ impl ServerState {
                 },
             };
             if need_spawn {
-                let id = self.ecs.spawn(
-                    ActorKind::Wizard,
-                    crate::actor::Faction::Pc,
-                    Transform {
-                        pos: p0,
-                        yaw: 0.0,
-                        radius: 0.7,
-                    },
-                    Health { hp: 100, max: 100 },
-                );
+                let id = self.spawn_pc(p0);
                 self.pc_actor = Some(id);
-                // Attach basic casting resources to PC
-                if let Some(pc) = self.ecs.get_mut(id) {
-                    pc.pool = Some(ecs::ResourcePool {
-                        mana: 20,
-                        max: 20,
-                        regen_per_s: 1.0,
-                        mana_frac: 0.0,
-                    });
-                    use std::collections::HashMap;
-                    pc.cooldowns = Some(ecs::Cooldowns {
-                        gcd_s: 0.30,
-                        gcd_ready: 0.0,
-                        per_spell: HashMap::new(),
-                    });
-                    pc.spellbook = Some(ecs::Spellbook {
-                        known: vec![SpellId::Firebolt, SpellId::Fireball, SpellId::MagicMissile],
-                    });
-                }
             } else if let Some(id) = self.pc_actor
                 && let Some(a) = self.ecs.get_mut(id)
             {
                 a.tr.pos = p0;
             }
         }
-        // Extra wizard positions correspond to NPC wizards (Faction::Wizards)
+        // Extra wizard positions correspond to NPC wizards (Faction::WIZARDS)
         let need = wiz_pos.len().saturating_sub(1);
         let mut npc_ids: Vec<ActorId> = self
             .ecs
             .iter()
-            .filter(|a| a.kind == ActorKind::Wizard && a.faction == crate::actor::Faction::Wizards)
+            .filter(|a| a.kind == ActorKind::Wizard && a.faction == crate::actor::Faction::WIZARDS)
             .map(|a| a.id)
             .collect();
         while npc_ids.len() < need {
-            let id = self.ecs.spawn(
-                ActorKind::Wizard,
-                crate::actor::Faction::Wizards,
-                Transform {
-                    pos: Vec3::ZERO,
-                    yaw: 0.0,
-                    radius: 0.7,
-                },
-                Health { hp: 100, max: 100 },
-            );
-            // Attach basic casting resources to NPC wizard for AI
-            if let Some(w) = self.ecs.get_mut(id) {
-                if w.pool.is_none() {
-                    w.pool = Some(ecs::ResourcePool {
-                        mana: 20,
-                        max: 20,
-                        regen_per_s: 0.5,
-                        mana_frac: 0.0,
-                    });
-                }
-                if w.cooldowns.is_none() {
-                    use std::collections::HashMap;
-                    w.cooldowns = Some(ecs::Cooldowns {
-                        gcd_s: 0.30,
-                        gcd_ready: 0.0,
-                        per_spell: HashMap::new(),
-                    });
-                }
-                if w.spellbook.is_none() {
-                    w.spellbook = Some(ecs::Spellbook {
-                        known: vec![SpellId::Firebolt, SpellId::Fireball, SpellId::MagicMissile],
-                    });
-                }
-            }
+            let id = self.spawn_wizard_npc(Vec3::ZERO);
             npc_ids.push(id);
         }
         for (i, id) in npc_ids.into_iter().enumerate() {
@@ -417,12 +502,24 @@ impl ServerState {
             dir,
             kind,
             owner,
+            speed_mps: None,
         });
     }
 
     /// Enqueue a cast; cast system will validate and translate to projectiles.
     pub fn enqueue_cast(&mut self, pos: Vec3, dir: Vec3, spell: SpellId) {
         let caster = self.pc_actor; // local demo assumes one PC caster
+        self.enqueue_cast_for(caster, pos, dir, spell);
+    }
+
+    /// Enqueue a cast on behalf of a specific caster (multi-client sessions).
+    pub fn enqueue_cast_for(
+        &mut self,
+        caster: Option<ActorId>,
+        pos: Vec3,
+        dir: Vec3,
+        spell: SpellId,
+    ) {
         if std::env::var("RA_LOG_CASTS")
             .map(|v| v == "1")
             .unwrap_or(false)
@@ -437,6 +534,56 @@ impl ServerState {
         });
     }
 
+    /// Resolve a data-driven `ClientCmd::Cast` for `caster` and queue it for
+    /// `cast_system`. The origin is the caster's server position; the target
+    /// must match the spell's targeting and be within its range.
+    pub fn enqueue_ability_cast(
+        &mut self,
+        caster: ActorId,
+        ability_id: &str,
+        target: net_core::command::CastTarget,
+    ) -> Result<SpellId, abilities::CastReject> {
+        use abilities::CastReject;
+        use net_core::command::CastTarget;
+        let ab = self
+            .abilities
+            .resolve(ability_id)
+            .ok_or(CastReject::UnknownAbility)?;
+        if !ab.targeting.accepts(&target) {
+            return Err(CastReject::BadTarget);
+        }
+        let c = self.ecs.get(caster).ok_or(CastReject::NoCaster)?;
+        let origin = c.tr.pos;
+        let facing = Vec3::new(c.tr.yaw.sin(), 0.0, c.tr.yaw.cos());
+        let point = match target {
+            CastTarget::SelfCast => None,
+            CastTarget::Direction(_) => None,
+            CastTarget::Ground(p) => Some((Vec3::from(p), 0.0)),
+            CastTarget::Actor(id) => {
+                let t = self
+                    .ecs
+                    .get(ActorId(id))
+                    .filter(|t| t.hp.alive())
+                    .ok_or(CastReject::BadTarget)?;
+                Some((t.tr.pos, t.tr.radius))
+            }
+        };
+        let dir = match (target, point) {
+            (_, Some((p, r))) => {
+                let (dir, dist) = abilities::aim_at(origin, facing, p, r);
+                if dist > ab.range_m {
+                    return Err(CastReject::OutOfRange);
+                }
+                dir
+            }
+            (CastTarget::Direction(d), None) => Vec3::from(d).try_normalize().unwrap_or(facing),
+            _ => facing,
+        };
+        let spell = ab.spell;
+        self.enqueue_cast_for(Some(caster), origin, dir, spell);
+        Ok(spell)
+    }
+
     /// Convenience: enqueue a projectile owned by a specific caster.
     pub fn spawn_projectile_from(&mut self, caster: ActorId, pos: Vec3, dir: Vec3, kind: ProjKind) {
         self.pending_projectiles.push(PendingProjectile {
@@ -444,6 +591,7 @@ impl ServerState {
             dir,
             kind,
             owner: Some(caster),
+            speed_mps: None,
         });
     }
     /// Spawn the PC actor at a chosen position if missing; attach casting resources.
@@ -453,52 +601,39 @@ impl ServerState {
         {
             return id;
         }
-        let id = self.ecs.spawn(
-            ActorKind::Wizard,
-            crate::actor::Faction::Pc,
-            Transform {
-                pos,
-                yaw: 0.0,
-                radius: 0.7,
-            },
-            Health { hp: 100, max: 100 },
-        );
+        let id = self.spawn_pc(pos);
         self.pc_actor = Some(id);
-        if let Some(pc) = self.ecs.get_mut(id) {
-            pc.pool = Some(ecs::ResourcePool {
-                mana: 20,
-                max: 20,
-                regen_per_s: 1.0,
-                mana_frac: 0.0,
-            });
-            use std::collections::HashMap;
-            pc.cooldowns = Some(ecs::Cooldowns {
-                gcd_s: 0.30,
-                gcd_ready: 0.0,
-                per_spell: HashMap::new(),
-            });
-            pc.spellbook = Some(ecs::Spellbook {
-                known: vec![SpellId::Firebolt, SpellId::Fireball, SpellId::MagicMissile],
-            });
-            pc.move_speed = Some(ecs::MoveSpeed { mps: 5.0 });
-        }
         id
     }
 
+    /// Spawn an additional player-controlled actor without touching `pc_actor`.
+    /// Used by the session layer, where every connection owns its own PC.
+    pub fn spawn_pc(&mut self, pos: Vec3) -> ActorId {
+        self.spawn_builtin_archetype("PC", pos, &Default::default())
+    }
+
     /// Set a movement intent on the PC actor (consumed by schedule at start of tick).
     pub fn apply_move_intent(&mut self, dx: f32, dz: f32, run: bool) {
-        if let Some(id) = self.pc_actor
-            && let Some(pc) = self.ecs.get_mut(id)
-        {
-            pc.intent_move = Some(crate::ecs::IntentMove { dx, dz, run });
+        if let Some(id) = self.pc_actor {
+            self.apply_move_intent_for(id, dx, dz, run);
         }
     }
     /// Set an aim/yaw intent on the PC actor.
     pub fn apply_aim_intent(&mut self, yaw: f32) {
-        if let Some(id) = self.pc_actor
-            && let Some(pc) = self.ecs.get_mut(id)
-        {
-            pc.intent_aim = Some(crate::ecs::IntentAim { yaw });
+        if let Some(id) = self.pc_actor {
+            self.apply_aim_intent_for(id, yaw);
+        }
+    }
+    /// Set a movement intent on a specific actor.
+    pub fn apply_move_intent_for(&mut self, id: ActorId, dx: f32, dz: f32, run: bool) {
+        if let Some(c) = self.ecs.get_mut(id) {
+            c.intent_move = Some(crate::ecs::IntentMove { dx, dz, run });
+        }
+    }
+    /// Set an aim/yaw intent on a specific actor.
+    pub fn apply_aim_intent_for(&mut self, id: ActorId, yaw: f32) {
+        if let Some(c) = self.ecs.get_mut(id) {
+            c.intent_aim = Some(crate::ecs::IntentAim { yaw });
         }
     }
     /// Resolve server-authoritative projectile spec. Falls back to baked defaults
@@ -592,20 +727,10 @@ impl ServerState {
     }
 
     fn spell_cost_cooldown(&self, spell: SpellId) -> (i32, f32, f32) {
-        match spell {
-            SpellId::Firebolt => {
-                let s = &self.specs.spells.firebolt;
-                (s.cost, s.cd_s, s.gcd_s)
-            }
-            SpellId::Fireball => {
-                let s = &self.specs.spells.fireball;
-                (s.cost, s.cd_s, s.gcd_s)
-            }
-            SpellId::MagicMissile => {
-                let s = &self.specs.spells.magic_missile;
-                (s.cost, s.cd_s, s.gcd_s)
-            }
-        }
+        self.abilities
+            .get(spell)
+            .map(|a| (a.cost, a.cooldown_s, a.gcd_s))
+            .unwrap_or((0, 0.0, 0.0))
     }
     /// Step server-authoritative systems: NPC AI/melee, wizard casts, projectile
     /// integration/collision. Collisions reduce HP for both NPCs and wizards.
@@ -628,292 +753,48 @@ impl ServerState {
         }
         // Destructible runtime disabled
     }
-    /// Spawn an Undead actor (legacy NPC replacement)
+    /// Spawn an Undead actor (`spawn_archetype("Undead")` with radius and HP).
     pub fn spawn_undead(&mut self, pos: Vec3, radius: f32, hp: i32) -> ActorId {
-        let mut pos = push_out_of_pc_bubble(self, pos);
-        pos = push_out_of_destructibles(self, pos);
-        pos = push_out_of_actors(self, pos, radius);
-        let id = self.ecs.spawn(
-            ActorKind::Zombie,
-            crate::actor::Faction::Undead,
-            Transform {
-                pos,
-                yaw: 0.0,
-                radius,
-            },
-            Health { hp, max: hp },
-        );
-        // Defaults for undead
-        if let Some(a) = self.ecs.get_mut(id) {
-            let spec = self.specs_arche.entries.get("Undead").cloned().unwrap_or(
-                data_runtime::specs::archetypes::ArchetypeSpec {
-                    radius_m: radius,
-                    move_speed_mps: 2.0,
-                    aggro_radius_m: 25.0,
-                    attack_radius_m: 0.35,
-                    melee_damage: 5,
-                    melee_cooldown_s: 0.6,
-                },
-            );
-            a.move_speed = Some(ecs::MoveSpeed {
-                mps: spec.move_speed_mps,
-            });
-            a.aggro = Some(ecs::AggroRadius {
-                m: spec.aggro_radius_m,
-            });
-            a.attack = Some(ecs::AttackRadius {
-                m: spec.attack_radius_m,
-            });
-            a.melee = Some(ecs::Melee {
-                damage: spec.melee_damage,
-                cooldown_s: spec.melee_cooldown_s,
-                ready_in_s: 0.0,
-            });
-        }
-        id
+        let ov = archetype::ArchetypeOverrides {
+            hp: Some(hp),
+            radius: Some(radius),
+            ..Default::default()
+        };
+        self.spawn_builtin_archetype("Undead", pos, &ov)
     }
     /// Spawn a Death Knight (boss-like hostile). Not unique by design.
     pub fn spawn_death_knight(&mut self, pos: Vec3) -> ActorId {
-        let mut pos = push_out_of_pc_bubble(self, pos);
-        pos = push_out_of_destructibles(self, pos);
-        pos = push_out_of_actors(self, pos, 1.0);
-        let id = self.ecs.spawn(
-            ActorKind::Boss,
-            crate::actor::Faction::Undead,
-            Transform {
-                pos,
-                yaw: 0.0,
-                radius: 1.0,
-            },
-            Health { hp: 400, max: 400 },
-        );
-        if let Some(a) = self.ecs.get_mut(id) {
-            a.name = Some("Death Knight".to_string());
-            let spec = self
-                .specs_arche
-                .entries
-                .get("DeathKnight")
-                .cloned()
-                .unwrap_or(data_runtime::specs::archetypes::ArchetypeSpec {
-                    radius_m: 1.0,
-                    move_speed_mps: 2.2,
-                    aggro_radius_m: 40.0,
-                    attack_radius_m: 0.45,
-                    melee_damage: 18,
-                    melee_cooldown_s: 0.9,
-                });
-            a.move_speed = Some(ecs::MoveSpeed {
-                mps: spec.move_speed_mps,
-            });
-            a.aggro = Some(ecs::AggroRadius {
-                m: spec.aggro_radius_m,
-            });
-            a.attack = Some(ecs::AttackRadius {
-                m: spec.attack_radius_m,
-            });
-            a.melee = Some(ecs::Melee {
-                damage: spec.melee_damage,
-                cooldown_s: spec.melee_cooldown_s,
-                ready_in_s: 0.0,
-            });
-            // Give DK a basic spell kit (Fireball + MagicMissile)
-            a.spellbook = Some(ecs::Spellbook {
-                known: vec![SpellId::Fireball, SpellId::MagicMissile],
-            });
-            a.pool = Some(ecs::ResourcePool {
-                mana: 40,
-                max: 40,
-                regen_per_s: 0.3,
-                mana_frac: 0.0,
-            });
-            use std::collections::HashMap;
-            a.cooldowns = Some(ecs::Cooldowns {
-                gcd_s: 0.40,
-                gcd_ready: 0.0,
-                per_spell: HashMap::new(),
-            });
-        }
-        id
+        self.spawn_builtin_archetype("DeathKnight", pos, &Default::default())
     }
-
-    // Destructible runtime disabled
-
-    // Destructible runtime disabled
     /// Spawn an NPC wizard (hostile to Undead) for demo or scripted scenes.
     pub fn spawn_wizard_npc(&mut self, pos: Vec3) -> ActorId {
-        let mut pos = push_out_of_pc_bubble(self, pos);
-        pos = push_out_of_destructibles(self, pos);
-        pos = push_out_of_actors(self, pos, 0.7);
-        let id = self.ecs.spawn(
-            ActorKind::Wizard,
-            crate::actor::Faction::Wizards,
-            Transform {
-                pos,
-                yaw: 0.0,
-                radius: 0.7,
-            },
-            Health { hp: 100, max: 100 },
-        );
-        if let Some(w) = self.ecs.get_mut(id) {
-            // Stationary caster by default; give casting resources
-            use std::collections::HashMap;
-            w.pool = Some(ecs::ResourcePool {
-                mana: 30,
-                max: 30,
-                regen_per_s: 0.5,
-                mana_frac: 0.0,
-            });
-            w.cooldowns = Some(ecs::Cooldowns {
-                gcd_s: 0.30,
-                gcd_ready: 0.0,
-                per_spell: HashMap::new(),
-            });
-            w.spellbook = Some(ecs::Spellbook {
-                known: vec![SpellId::Firebolt, SpellId::Fireball, SpellId::MagicMissile],
-            });
-            // Apply archetype radius if present
-            let spec = self.specs_arche.entries.get("WizardNPC").cloned();
-            if let Some(sp) = spec {
-                w.tr.radius = sp.radius_m;
-            }
-            // Clamp to minimum collision radius to avoid tunneling misses
-            w.tr.radius = w.tr.radius.max(0.7);
-        }
-        id
+        // Clamp to minimum collision radius to avoid tunneling misses
+        let r = self
+            .specs_arche
+            .entries
+            .get("WizardNPC")
+            .map_or(0.7, |s| s.radius_m);
+        let ov = archetype::ArchetypeOverrides {
+            radius: Some(r.max(0.7)),
+            ..Default::default()
+        };
+        self.spawn_builtin_archetype("WizardNPC", pos, &ov)
     }
     /// Spawn the unique boss "Nivita, Lady of Undertide" if not present.
     /// Returns the NPC id if spawned or already present.
     pub fn spawn_nivita_unique(&mut self, pos: Vec3) -> Option<ActorId> {
-        if let Some(id) = self.nivita_actor_id {
-            return Some(id);
-        }
-        let cfg = match data_runtime::configs::npc_unique::load_nivita() {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("server: failed to load nivita config: {e:#}");
-                return None;
-            }
-        };
-        let hp_mid = (cfg.hp_range.0 + cfg.hp_range.1) / 2;
-        let radius = cfg.radius_m.unwrap_or(0.9);
-        // Respect PC safety bubble when placing the boss
-        let pos = push_out_of_pc_bubble(self, pos);
-        let id = self.ecs.spawn(
-            ActorKind::Boss,
-            crate::actor::Faction::Undead,
-            Transform {
-                pos,
-                yaw: 0.0,
-                radius,
-            },
-            Health {
-                hp: hp_mid,
-                max: hp_mid,
-            },
-        );
-        if let Some(a) = self.ecs.get_mut(id) {
-            a.name = Some(cfg.name.clone());
-            a.move_speed = Some(ecs::MoveSpeed { mps: 2.6 });
-            a.aggro = Some(ecs::AggroRadius { m: 35.0 });
-            a.attack = Some(ecs::AttackRadius { m: 0.35 });
-            a.melee = Some(ecs::Melee {
-                damage: 12,
-                cooldown_s: 0.8,
-                ready_in_s: 0.0,
-            });
-        }
-        // Build and store boss stats snapshot for replication/logging
-        let ab = ec::Abilities {
-            str: cfg.abilities.str,
-            dex: cfg.abilities.dex,
-            con: cfg.abilities.con,
-            int: cfg.abilities.int,
-            wis: cfg.abilities.wis,
-            cha: cfg.abilities.cha,
-            prof: cfg.abilities.proficiency,
-        };
-        let mod_of = |v: i8| ((v as i16 - 10) / 2) as i8;
-        let saves = if let Some(s) = cfg.saves.as_ref() {
-            ec::SavingThrows {
-                str_mod: s.str,
-                dex_mod: s.dex,
-                con_mod: s.con,
-                int_mod: s.int,
-                wis_mod: s.wis,
-                cha_mod: s.cha,
-            }
-        } else {
-            ec::SavingThrows {
-                str_mod: mod_of(ab.str),
-                dex_mod: mod_of(ab.dex),
-                con_mod: mod_of(ab.con),
-                int_mod: mod_of(ab.int) + ab.prof,
-                wis_mod: mod_of(ab.wis) + ab.prof,
-                cha_mod: mod_of(ab.cha) + ab.prof,
-            }
-        };
-        let resist: Vec<ec::DamageType> = cfg
-            .defenses
-            .resistances
-            .iter()
-            .filter_map(|s| ecs_core::parse::parse_damage_type(s))
-            .collect();
-        let immune: Vec<ec::Condition> = cfg
-            .defenses
-            .immunities
-            .iter()
-            .filter_map(|s| ecs_core::parse::parse_condition(s))
-            .collect();
-        let reset = match cfg.legendary.resets.as_deref() {
-            Some("per_encounter") => ec::ResetRule::PerEncounter,
-            _ => ec::ResetRule::LongRest,
-        };
-        let lres = ec::LegendaryResist::new(cfg.legendary.resist_per_day, reset);
-        let spell_ids = |v: &[String]| v.iter().map(|s| ec::SpellId(s.clone())).collect();
-        let book = ec::Spellbook {
-            cantrips: spell_ids(&cfg.spellbook.cantrips),
-            level_1_3: spell_ids(&cfg.spellbook.level_1_3),
-            level_4_5: spell_ids(&cfg.spellbook.level_4_5),
-            signature: spell_ids(&cfg.spellbook.signature),
-        };
-        let team_id = match cfg.team.as_deref() {
-            Some("enemy_raid") => Some(2u32),
-            Some("players") => Some(1u32),
-            _ => None,
-        };
-        self.nivita_stats = Some(NivitaStats {
-            name: cfg.name.clone(),
-            ac: i32::from(cfg.defenses.ac),
-            abilities: ab,
-            saves,
-            defenses: ec::Defenses { resist, immune },
-            legendary: lres,
-            spellbook: book,
-            radius,
-            height: cfg.height_m.unwrap_or(1.9),
-            team: cfg.team.clone(),
-            team_id,
-        });
-        self.nivita_actor_id = Some(id);
-        log::info!(
-            "server: spawned unique boss '{}' (hp={}..{}, ac={}) as {:?}",
-            cfg.name,
-            cfg.hp_range.0,
-            cfg.hp_range.1,
-            cfg.defenses.ac,
-            id
-        );
-        metrics::counter!("boss.nivita.spawns_total").increment(1);
-        Some(id)
+        self.spawn_archetype("Nivita", pos, &archetype::ArchetypeOverrides::default())
+    }
+    /// The live Nivita, if any.
+    pub fn nivita_actor(&self) -> Option<ActorId> {
+        self.live_unique("Nivita")
     }
     /// Lightweight status for UI/replication.
     pub fn nivita_status(&self) -> Option<BossStatus> {
-        let id = self.nivita_actor_id?;
-        let n = self.ecs.get(id)?;
-        let stats = self.nivita_stats.as_ref()?;
+        let n = self.ecs.get(self.nivita_actor()?)?;
         Some(BossStatus {
-            name: stats.name.clone(),
-            ac: stats.ac,
+            name: n.name.clone().unwrap_or_default(),
+            ac: n.armor_class(),
             hp: n.hp.hp,
             max: n.hp.max,
             pos: n.tr.pos,
@@ -936,6 +817,13 @@ impl ServerState {
     /// the nearest wizard.
     // Legacy TickSnapshot removed; actor-centric snapshot is canonical.
     pub fn tick_snapshot_actors(&self, tick: u64) -> net_core::snapshot::ActorSnapshot {
+        let unique: std::collections::HashSet<u16> = self
+            .specs_arche
+            .entries
+            .values()
+            .filter(|s| s.unique)
+            .map(|s| s.net_id)
+            .collect();
         // Only true actors (non-projectiles) are included in the actor list.
         // Projectiles are carried separately under `projectiles` and must never
         // be represented as NPC spawns to the client.
@@ -950,29 +838,27 @@ impl ServerState {
                     ActorKind::Zombie => 1,
                     ActorKind::Boss => 2,
                 },
-                faction: match a.faction {
-                    crate::actor::Faction::Pc => 0,
-                    crate::actor::Faction::Wizards => 1,
-                    crate::actor::Faction::Undead => 2,
-                    crate::actor::Faction::Neutral => 3,
-                },
-                archetype_id: match a.kind {
-                    ActorKind::Wizard => 1,
-                    ActorKind::Zombie => 2,
-                    ActorKind::Boss => 3,
+                faction: a.faction.0,
+                // Archetype data id; ad hoc spawns fall back to their bucket.
+                archetype_id: match (a.archetype_id, a.kind) {
+                    (0, ActorKind::Wizard) => 1,
+                    (0, ActorKind::Zombie) => 2,
+                    (0, ActorKind::Boss) => 3,
+                    (id, _) => id,
                 },
                 name_id: if a.name.is_some() { 1 } else { 0 },
-                unique: if Some(a.id) == self.nivita_actor_id {
-                    1
-                } else {
-                    0
-                },
+                unique: u8::from(unique.contains(&a.archetype_id)),
                 pos: [a.tr.pos.x, a.tr.pos.y, a.tr.pos.z],
                 yaw: a.tr.yaw,
                 radius: a.tr.radius,
                 hp: a.hp.hp,
                 max: a.hp.max,
                 alive: a.hp.alive(),
+                state: if a.dodge.is_some() {
+                    net_core::snapshot::ACTOR_STATE_DODGING
+                } else {
+                    0
+                },
             })
             .collect();
         let mut projectiles: Vec<net_core::snapshot::ProjectileRep> = Vec::new();
@@ -1202,6 +1088,7 @@ mod tests_actor {
             dir: Vec3::new(0.0, 0.0, 1.0),
             kind: ProjKind::Firebolt,
             owner: None,
+            speed_mps: None,
         });
         let mut ctx = crate::ecs::schedule::Ctx::default();
         let mut sched = crate::ecs::schedule::Schedule;
@@ -0,0 +1,678 @@
+//! Grid navigation for server NPCs.
+//!
+//! The walkable area is an XZ grid rasterized from the zone's static colliders
+//! (`ServerState::static_colliders`) and the solid voxels of every destructible
+//! proxy. A cell is blocked when an obstacle overlaps it within the walk band
+//! (`NavConfig::block_min_y..block_max_y`). A clearance field (distance to the
+//! nearest blocked cell) lets agents of any radius share one grid.
+//!
+//! `find_path` runs A* over 8-connected cells (no corner cutting) and pulls the
+//! cell path taut with line-of-sight checks, so agents walk straight between
+//! the few corners that matter. Carves mark their footprint dirty;
+//! `nav_refresh` re-rasterizes just that rect against the obstacles that reach
+//! it, recomputes clearance in a band around the cells that flipped, bumps
+//! `version`, and records the changed rect so agents whose routes cross it
+//! re-plan. Clearance is capped at `NavConfig::max_clearance_m`, which bounds
+//! that band.
+//!
+//! Outside the grid everything is open. With no obstacles the grid is empty
+//! and movement stays a straight line.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use collision_static::{ShapeRef, StaticIndex};
+use glam::{Vec2, Vec3};
+
+use crate::ServerState;
+use crate::destructible::state::{DestructibleProxy, DestructibleRegistry};
+
+/// Orthogonal and diagonal step costs (integer A*).
+const COST_ORTH: u32 = 10;
+const COST_DIAG: u32 = 14;
+
+#[derive(Debug, Clone, Copy)]
+pub struct NavConfig {
+    pub cell_m: f32,
+    /// Obstacles only block if they overlap this height band (world Y).
+    pub block_min_y: f32,
+    pub block_max_y: f32,
+    /// Open border added around the obstacles' footprint.
+    pub margin_m: f32,
+    /// A* node budget per query.
+    pub max_expansions: usize,
+    /// Clearance saturates here; agents must be narrower.
+    pub max_clearance_m: f32,
+}
+
+impl Default for NavConfig {
+    fn default() -> Self {
+        Self {
+            cell_m: 0.5,
+            block_min_y: 0.3,
+            block_max_y: 2.0,
+            margin_m: 16.0,
+            max_expansions: 20_000,
+            max_clearance_m: 4.0,
+        }
+    }
+}
+
+/// Axis-aligned XZ rectangle in world meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavRect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl NavRect {
+    pub fn union(self, o: Self) -> Self {
+        Self {
+            min: self.min.min(o.min),
+            max: self.max.max(o.max),
+        }
+    }
+
+    pub fn overlaps(&self, o: &Self) -> bool {
+        self.min.x <= o.max.x
+            && self.max.x >= o.min.x
+            && self.min.y <= o.max.y
+            && self.max.y >= o.min.y
+    }
+
+    fn around(points: impl IntoIterator<Item = Vec2>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|p| Self { min: p, max: p })
+            .reduce(Self::union)
+    }
+}
+
+/// A planned route: taut waypoints from the agent toward `goal`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavPath {
+    pub waypoints: Vec<Vec2>,
+    /// Goal the path was planned for.
+    pub goal: Vec2,
+    /// False when the goal was unreachable; the path ends as close as it gets.
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NavGrid {
+    pub cfg: NavConfig,
+    origin: Vec2,
+    w: usize,
+    h: usize,
+    blocked: Vec<bool>,
+    /// Meters from each cell's center to the nearest blocked cell's edge.
+    clearance: Vec<f32>,
+    /// Bumped whenever blocked cells change.
+    pub version: u64,
+    /// Rect changed by the last bump (`None` for full rebuilds).
+    pub last_change: Option<NavRect>,
+    dirty: Option<NavRect>,
+    /// Destructible ids and static collider count the grid was built from.
+    sources: (Vec<u64>, usize),
+    /// Registry generation and static collider count `sources` was checked
+    /// at; `nav_refresh` re-derives `sources` only when these move.
+    sources_key: (u64, usize),
+}
+
+impl NavGrid {
+    /// Rasterize the given obstacles into a fresh grid.
+    pub fn build(cfg: NavConfig, stat: Option<&StaticIndex>, destr: &DestructibleRegistry) -> Self {
+        let sources = sources_of(stat, destr);
+        let sources_key = sources_key(stat, destr);
+        let footprints = stat
+            .into_iter()
+            .flat_map(|i| i.colliders.iter())
+            .map(|c| (c.aabb.min, c.aabb.max))
+            .chain(
+                destr
+                    .proxies
+                    .values()
+                    .map(|p| (p.world_aabb.min, p.world_aabb.max)),
+            )
+            .flat_map(|(a, b)| [Vec2::new(a.x, a.z), Vec2::new(b.x, b.z)]);
+        let Some(bounds) = NavRect::around(footprints) else {
+            return Self {
+                cfg,
+                sources,
+                sources_key,
+                ..Default::default()
+            };
+        };
+        let origin = bounds.min - Vec2::splat(cfg.margin_m);
+        let size = bounds.max - bounds.min + Vec2::splat(2.0 * cfg.margin_m);
+        let w = (size.x / cfg.cell_m).ceil().max(1.0) as usize;
+        let h = (size.y / cfg.cell_m).ceil().max(1.0) as usize;
+        let mut g = Self {
+            cfg,
+            origin,
+            w,
+            h,
+            blocked: vec![false; w * h],
+            clearance: vec![f32::INFINITY; w * h],
+            version: 1,
+            last_change: None,
+            dirty: None,
+            sources,
+            sources_key,
+        };
+        g.rasterize(0..w, 0..h, stat, destr);
+        g.update_clearance(0..w, 0..h);
+        g
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.w == 0 || self.h == 0
+    }
+
+    /// Queue a world rect for re-rasterization on the next `nav_refresh`.
+    pub fn mark_dirty(&mut self, r: NavRect) {
+        self.dirty = Some(self.dirty.map_or(r, |d| d.union(r)));
+    }
+
+    fn cell_of(&self, p: Vec2) -> Option<(usize, usize)> {
+        let c = ((p - self.origin) / self.cfg.cell_m).floor();
+        (c.x >= 0.0 && c.y >= 0.0 && (c.x as usize) < self.w && (c.y as usize) < self.h)
+            .then_some((c.x as usize, c.y as usize))
+    }
+
+    fn center(&self, x: usize, z: usize) -> Vec2 {
+        self.origin + (Vec2::new(x as f32, z as f32) + 0.5) * self.cfg.cell_m
+    }
+
+    /// Clearance at `p` in meters (infinite outside the grid).
+    pub fn clearance_at(&self, p: Vec2) -> f32 {
+        self.cell_of(p)
+            .map_or(f32::INFINITY, |(x, z)| self.clearance[z * self.w + x])
+    }
+
+    /// Whether an agent of `radius` can stand at `p`.
+    pub fn walkable(&self, p: Vec2, radius: f32) -> bool {
+        self.clearance_at(p) >= radius
+    }
+
+    /// Whether an agent of `radius` can walk the straight segment `a`->`b`.
+    pub fn line_clear(&self, a: Vec2, b: Vec2, radius: f32) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let d = b - a;
+        let n = (d.length() / (self.cfg.cell_m * 0.25)).ceil().max(1.0) as usize;
+        (0..=n).all(|i| self.walkable(a + d * (i as f32 / n as f32), radius))
+    }
+
+    /// A* from `start` to `goal` for an agent of `radius`, pulled taut. If the
+    /// goal can't be reached the path ends at the closest reachable cell.
+    /// `None` only when the start is outside the grid and can't be placed.
+    pub fn find_path(&self, start: Vec2, goal: Vec2, radius: f32) -> Option<NavPath> {
+        if self.line_clear(start, goal, radius) {
+            return Some(NavPath {
+                waypoints: vec![goal],
+                goal,
+                complete: true,
+            });
+        }
+        let clamp = |p: Vec2| {
+            let max = self.origin + Vec2::new(self.w as f32, self.h as f32) * self.cfg.cell_m;
+            p.clamp(self.origin, max - Vec2::splat(1e-3))
+        };
+        let (sx, sz) = self.cell_of(clamp(start))?;
+        let s = sz * self.w + sx;
+        let gi = self.nearest_open(self.cell_of(clamp(goal))?, radius);
+        let (gx, gz) = (gi % self.w, gi / self.w);
+        let h = |i: usize| {
+            let dx = (i % self.w).abs_diff(gx) as u32;
+            let dz = (i / self.w).abs_diff(gz) as u32;
+            COST_ORTH * dx.max(dz) + (COST_DIAG - COST_ORTH) * dx.min(dz)
+        };
+        let ok = |i: usize| self.clearance[i] >= radius;
+        let mut open = BinaryHeap::new();
+        let mut g: HashMap<usize, u32> = HashMap::new();
+        let mut parent: HashMap<usize, usize> = HashMap::new();
+        g.insert(s, 0);
+        open.push(Reverse((h(s), 0u32, s)));
+        let mut best = (h(s), s);
+        let mut expansions = 0usize;
+        while let Some(Reverse((_, gc, i))) = open.pop() {
+            if g.get(&i).is_some_and(|&cur| gc > cur) {
+                continue;
+            }
+            if i == gi {
+                best = (0, i);
+                break;
+            }
+            expansions += 1;
+            if expansions > self.cfg.max_expansions {
+                break;
+            }
+            let (x, z) = ((i % self.w) as isize, (i / self.w) as isize);
+            for (dx, dz) in [
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ] {
+                let (nx, nz) = (x + dx, z + dz);
+                if nx < 0 || nz < 0 || nx >= self.w as isize || nz >= self.h as isize {
+                    continue;
+                }
+                let ni = nz as usize * self.w + nx as usize;
+                // Agents squeezed against a wall may still step away from it.
+                if !ok(ni) && self.clearance[ni] <= self.clearance[i] {
+                    continue;
+                }
+                let diag = dx != 0 && dz != 0;
+                if diag
+                    && !(ok(z as usize * self.w + nx as usize)
+                        && ok(nz as usize * self.w + x as usize))
+                {
+                    continue;
+                }
+                let ng = gc + if diag { COST_DIAG } else { COST_ORTH };
+                if g.get(&ni).is_none_or(|&cur| ng < cur) {
+                    g.insert(ni, ng);
+                    parent.insert(ni, i);
+                    let hn = h(ni);
+                    if hn < best.0 {
+                        best = (hn, ni);
+                    }
+                    open.push(Reverse((ng + hn, ng, ni)));
+                }
+            }
+        }
+        let complete = best.1 == gi;
+        let mut cells = vec![best.1];
+        while let Some(&p) = parent.get(cells.last()?) {
+            cells.push(p);
+        }
+        cells.reverse();
+        let mut pts: Vec<Vec2> = cells
+            .iter()
+            .skip(1)
+            .map(|&i| self.center(i % self.w, i / self.w))
+            .collect();
+        if complete {
+            pts.pop();
+            pts.push(goal);
+        }
+        Some(NavPath {
+            waypoints: self.string_pull(start, &pts, radius),
+            goal,
+            complete,
+        })
+    }
+
+    /// Closest cell to `(x, z)` an agent of `radius` fits in (a goal hugging a
+    /// wall is approached from open ground); the cell itself if none is near.
+    fn nearest_open(&self, (x, z): (usize, usize), radius: f32) -> usize {
+        let reach = (radius / self.cfg.cell_m).ceil() as usize + 2;
+        let mut best: Option<(usize, usize)> = None;
+        for nz in z.saturating_sub(reach)..(z + reach + 1).min(self.h) {
+            for nx in x.saturating_sub(reach)..(x + reach + 1).min(self.w) {
+                let i = nz * self.w + nx;
+                let d2 = nx.abs_diff(x).pow(2) + nz.abs_diff(z).pow(2);
+                if self.clearance[i] >= radius && best.is_none_or(|(b, _)| d2 < b) {
+                    best = Some((d2, i));
+                }
+            }
+        }
+        best.map_or(z * self.w + x, |(_, i)| i)
+    }
+
+    /// Greedy string pulling: keep only the corners line of sight can't skip.
+    fn string_pull(&self, start: Vec2, pts: &[Vec2], radius: f32) -> Vec<Vec2> {
+        let mut out = Vec::new();
+        let mut anchor = start;
+        let mut i = 0;
+        while i < pts.len() {
+            let mut k = i;
+            while k + 1 < pts.len() && self.line_clear(anchor, pts[k + 1], radius) {
+                k += 1;
+            }
+            out.push(pts[k]);
+            anchor = pts[k];
+            i = k + 1;
+        }
+        out
+    }
+
+    fn rasterize(
+        &mut self,
+        xs: std::ops::Range<usize>,
+        zs: std::ops::Range<usize>,
+        stat: Option<&StaticIndex>,
+        destr: &DestructibleRegistry,
+    ) {
+        if xs.is_empty() || zs.is_empty() {
+            return;
+        }
+        let (lo, hi) = (self.cfg.block_min_y, self.cfg.block_max_y);
+        let half = self.cfg.cell_m * 0.5;
+        // Only obstacles reaching the rasterized rect are tested per cell.
+        let area = NavRect {
+            min: self.center(xs.start, zs.start) - half,
+            max: self.center(xs.end - 1, zs.end - 1) + half,
+        };
+        let footprint = |a: Vec3, b: Vec3| NavRect {
+            min: Vec2::new(a.x, a.z),
+            max: Vec2::new(b.x, b.z),
+        };
+        let statics: Vec<_> = stat
+            .into_iter()
+            .flat_map(|i| i.colliders.iter())
+            .filter(|c| c.aabb.max.y > lo && c.aabb.min.y < hi)
+            .filter(|c| footprint(c.aabb.min, c.aabb.max).overlaps(&area))
+            .collect();
+        let proxies: Vec<_> = destr
+            .proxies
+            .values()
+            .filter(|p| footprint(p.world_aabb.min, p.world_aabb.max).overlaps(&area))
+            .collect();
+        for z in zs {
+            for x in xs.clone() {
+                let c = self.center(x, z);
+                let cell = NavRect {
+                    min: c - half,
+                    max: c + half,
+                };
+                let hit_static = statics.iter().any(|s| {
+                    let fp = NavRect {
+                        min: Vec2::new(s.aabb.min.x, s.aabb.min.z),
+                        max: Vec2::new(s.aabb.max.x, s.aabb.max.z),
+                    };
+                    fp.overlaps(&cell) && shape_covers(&s.shape, c, half)
+                });
+                self.blocked[z * self.w + x] = hit_static || voxels_cover(&proxies, &cell, lo, hi);
+            }
+        }
+    }
+
+    /// Recompute clearance for the cells within `max_clearance_m` of the
+    /// rect `xs` x `zs` (the whole grid for a rebuild).
+    fn update_clearance(&mut self, xs: std::ops::Range<usize>, zs: std::ops::Range<usize>) {
+        let cell = self.cfg.cell_m;
+        let cap = self.cfg.max_clearance_m.max(0.0);
+        // Cells farther than `band` from a change keep their (capped) value;
+        // the window adds another band so every blocked cell within reach of
+        // the written cells is seen.
+        let band = (cap / cell).ceil() as usize + 1;
+        let grow = |r: &std::ops::Range<usize>, n: usize, by: usize| {
+            r.start.saturating_sub(by)..(r.end + by).min(n)
+        };
+        let (ix, iz) = (grow(&xs, self.w, band), grow(&zs, self.h, band));
+        let (wx, wz) = (grow(&ix, self.w, band), grow(&iz, self.h, band));
+        let d = self.chamfer(wx.clone(), wz.clone());
+        let ww = wx.len();
+        for z in iz {
+            for x in ix.clone() {
+                let v = d[(z - wz.start) * ww + (x - wx.start)];
+                self.clearance[z * self.w + x] = ((v - 0.5) * cell).clamp(0.0, cap);
+            }
+        }
+    }
+
+    /// Chamfer distance transform (1, sqrt 2) over the blocked cells of a
+    /// window, in cells; row-major over the window.
+    fn chamfer(&self, xs: std::ops::Range<usize>, zs: std::ops::Range<usize>) -> Vec<f32> {
+        let (w, h) = (xs.len(), zs.len());
+        let mut d: Vec<f32> = zs
+            .flat_map(|z| xs.clone().map(move |x| (x, z)))
+            .map(|(x, z)| {
+                if self.blocked[z * self.w + x] {
+                    0.0
+                } else {
+                    f32::INFINITY
+                }
+            })
+            .collect();
+        let diag = std::f32::consts::SQRT_2;
+        for z in 0..h {
+            for x in 0..w {
+                let mut v = d[z * w + x];
+                if x > 0 {
+                    v = v.min(d[z * w + x - 1] + 1.0);
+                }
+                if z > 0 {
+                    v = v.min(d[(z - 1) * w + x] + 1.0);
+                    if x > 0 {
+                        v = v.min(d[(z - 1) * w + x - 1] + diag);
+                    }
+                    if x + 1 < w {
+                        v = v.min(d[(z - 1) * w + x + 1] + diag);
+                    }
+                }
+                d[z * w + x] = v;
+            }
+        }
+        for z in (0..h).rev() {
+            for x in (0..w).rev() {
+                let mut v = d[z * w + x];
+                if x + 1 < w {
+                    v = v.min(d[z * w + x + 1] + 1.0);
+                }
+                if z + 1 < h {
+                    v = v.min(d[(z + 1) * w + x] + 1.0);
+                    if x + 1 < w {
+                        v = v.min(d[(z + 1) * w + x + 1] + diag);
+                    }
+                    if x > 0 {
+                        v = v.min(d[(z + 1) * w + x - 1] + diag);
+                    }
+                }
+                d[z * w + x] = v;
+            }
+        }
+        d
+    }
+}
+
+fn sources_key(stat: Option<&StaticIndex>, destr: &DestructibleRegistry) -> (u64, usize) {
+    (destr.generation, stat.map_or(0, |i| i.colliders.len()))
+}
+
+fn sources_of(stat: Option<&StaticIndex>, destr: &DestructibleRegistry) -> (Vec<u64>, usize) {
+    let mut ids: Vec<u64> = destr.proxies.keys().map(|d| d.0).collect();
+    ids.sort_unstable();
+    (ids, stat.map_or(0, |i| i.colliders.len()))
+}
+
+/// Conservative test: does `shape`'s XZ footprint reach the cell at `c`?
+fn shape_covers(shape: &ShapeRef, c: Vec2, half: f32) -> bool {
+    match shape {
+        ShapeRef::Cyl(cyl) => {
+            let center = Vec2::new(cyl.center.x, cyl.center.z);
+            let q = center.clamp(c - half, c + half);
+            (q - center).length() <= cyl.radius
+        }
+        ShapeRef::Box(obb) => {
+            let local = obb.rot3x3.transpose() * (Vec3::new(c.x, obb.center.y, c.y) - obb.center);
+            let pad = half * std::f32::consts::SQRT_2;
+            local.x.abs() <= obb.half_extents.x + pad && local.z.abs() <= obb.half_extents.z + pad
+        }
+    }
+}
+
+/// Any solid voxel of any of `proxies` under `cell` within the height band?
+fn voxels_cover(proxies: &[&DestructibleProxy], cell: &NavRect, lo: f32, hi: f32) -> bool {
+    proxies.iter().any(|p| {
+        let fp = NavRect {
+            min: Vec2::new(p.world_aabb.min.x, p.world_aabb.min.z),
+            max: Vec2::new(p.world_aabb.max.x, p.world_aabb.max.z),
+        };
+        if !fp.overlaps(cell) {
+            return false;
+        }
+        // Voxel (x, y, z) spans origin + [x, x + 1) * voxel_m, as for chunk colliders.
+        let o = p.grid.origin_m().as_vec3();
+        let a = Vec3::new(cell.min.x, lo, cell.min.y) - o;
+        let b = Vec3::new(cell.max.x, hi, cell.max.y) - o;
+        let vox = p.grid.voxel_m().0 as f32;
+        let dims = p.grid.dims();
+        let range = |a: f32, b: f32, n: u32| {
+            let lo = (a.min(b) / vox).floor().max(0.0) as u32;
+            let hi = ((a.max(b) / vox).ceil().max(0.0) as u32).min(n);
+            lo..hi
+        };
+        let (xs, ys, zs) = (
+            range(a.x, b.x, dims.x),
+            range(a.y, b.y, dims.y),
+            range(a.z, b.z, dims.z),
+        );
+        zs.into_iter().any(|z| {
+            ys.clone()
+                .any(|y| xs.clone().any(|x| p.grid.is_solid(x, y, z)))
+        })
+    })
+}
+
+/// Rebuild the grid when obstacle sources change; otherwise re-rasterize the
+/// rect dirtied by carves and bump `version`. Runs after destructible carves.
+pub fn nav_refresh(srv: &mut ServerState) {
+    let stat = srv.static_colliders.as_ref();
+    let key = sources_key(stat, &srv.destruct_registry);
+    if key != srv.nav.sources_key {
+        if sources_of(stat, &srv.destruct_registry) != srv.nav.sources {
+            let version = srv.nav.version + 1;
+            srv.nav = NavGrid::build(srv.nav.cfg, stat, &srv.destruct_registry);
+            srv.nav.version = version;
+            return;
+        }
+        srv.nav.sources_key = key;
+    }
+    let Some(r) = srv.nav.dirty.take() else {
+        return;
+    };
+    let g = &mut srv.nav;
+    if g.is_empty() {
+        return;
+    }
+    let cell = |p: Vec2| ((p - g.origin) / g.cfg.cell_m).floor();
+    let (a, b) = (cell(r.min), cell(r.max));
+    let x0 = a.x.max(0.0) as usize;
+    let z0 = a.y.max(0.0) as usize;
+    let x1 = ((b.x + 1.0).max(0.0) as usize).min(g.w);
+    let z1 = ((b.y + 1.0).max(0.0) as usize).min(g.h);
+    if x0 >= x1 || z0 >= z1 {
+        return;
+    }
+    let before: Vec<bool> = (z0..z1)
+        .flat_map(|z| (x0..x1).map(move |x| (x, z)))
+        .map(|(x, z)| g.blocked[z * g.w + x])
+        .collect();
+    g.rasterize(x0..x1, z0..z1, stat, &srv.destruct_registry);
+    // Bounding box of the cells that flipped.
+    let mut flipped: Option<(usize, usize, usize, usize)> = None;
+    for (i, was) in before.into_iter().enumerate() {
+        let (x, z) = (x0 + i % (x1 - x0), z0 + i / (x1 - x0));
+        if g.blocked[z * g.w + x] != was {
+            flipped = Some(flipped.map_or((x, z, x, z), |(ax, az, bx, bz)| {
+                (ax.min(x), az.min(z), bx.max(x), bz.max(z))
+            }));
+        }
+    }
+    let Some((fx0, fz0, fx1, fz1)) = flipped else {
+        return;
+    };
+    g.update_clearance(fx0..fx1 + 1, fz0..fz1 + 1);
+    g.version += 1;
+    g.last_change = Some(r);
+    metrics::counter!("nav.replans_triggered_total").increment(1);
+}
+
+/// Per-agent navigation state (ECS component).
+#[derive(Debug, Clone)]
+pub struct NavAgent {
+    pub path: Option<NavPath>,
+    /// `NavGrid::version` the path was planned against.
+    pub version: u64,
+    /// Seconds until an incomplete or stale path may be re-planned.
+    pub replan_in_s: f32,
+}
+
+/// Goal drift (m) that forces a re-plan.
+const GOAL_DRIFT_M: f32 = 1.0;
+/// Minimum seconds between throttled re-plans.
+const REPLAN_COOLDOWN_S: f32 = 0.5;
+/// Seconds before an unreachable goal is searched for again (carves that
+/// open a route re-plan immediately).
+const PARTIAL_RETRY_S: f32 = 2.0;
+/// Waypoint arrival distance (m).
+const ARRIVE_M: f32 = 0.1;
+
+/// Next XZ point for an agent at `pos` heading to `goal`: the goal itself
+/// when the straight line is clear, else the next waypoint of its planned
+/// route. `None` when no route exists (the agent should hold position).
+pub fn steer(
+    grid: &NavGrid,
+    agent: &mut Option<NavAgent>,
+    pos: Vec2,
+    goal: Vec2,
+    radius: f32,
+    dt: f32,
+) -> Option<Vec2> {
+    if grid.line_clear(pos, goal, radius) {
+        *agent = None;
+        return Some(goal);
+    }
+    let a = agent.get_or_insert(NavAgent {
+        path: None,
+        version: 0,
+        replan_in_s: 0.0,
+    });
+    a.replan_in_s = (a.replan_in_s - dt).max(0.0);
+    let stale = match &a.path {
+        None => true,
+        Some(p) if a.version != grid.version => {
+            // Only routes that touch the changed rect (or never got there) re-plan.
+            !p.complete
+                || grid.last_change.is_none_or(|c| {
+                    NavRect::around(p.waypoints.iter().copied().chain([pos, p.goal]))
+                        .is_some_and(|r| r.overlaps(&c))
+                })
+        }
+        Some(p) => {
+            a.replan_in_s <= 0.0
+                && ((p.goal - goal).length() > GOAL_DRIFT_M
+                    || (!p.complete && p.waypoints.is_empty()))
+        }
+    };
+    if stale {
+        a.path = grid.find_path(pos, goal, radius);
+        a.replan_in_s = match &a.path {
+            Some(p) if p.complete => REPLAN_COOLDOWN_S,
+            _ => PARTIAL_RETRY_S,
+        };
+        metrics::counter!("nav.paths_planned_total").increment(1);
+    }
+    a.version = grid.version;
+    let path = a.path.as_mut()?;
+    while let Some(&w) = path.waypoints.first() {
+        if (w - pos).length() > ARRIVE_M || path.waypoints.len() == 1 {
+            break;
+        }
+        path.waypoints.remove(0);
+    }
+    // Skip ahead when a later corner is already visible.
+    if path.waypoints.len() > 1 && grid.line_clear(pos, path.waypoints[1], radius) {
+        path.waypoints.remove(0);
+    }
+    let next = *path.waypoints.first()?;
+    if !path.complete && path.waypoints.len() == 1 && (next - pos).length() <= ARRIVE_M {
+        // End of a partial route: wait here for the map or the goal to change.
+        path.waypoints.clear();
+        return None;
+    }
+    Some(if path.waypoints.len() == 1 && path.complete {
+        goal
+    } else {
+        next
+    })
+}
@@ -0,0 +1,329 @@
+//! Character persistence: a local store of player characters by name.
+//!
+//! A [`CharacterRecord`] holds what a character keeps between sessions: zone
+//! and position, HP, mana and stamina, known spells and running cooldowns (by
+//! ability id), faction reputation (by faction key), inventory and worn items
+//! (by slot).
+//! `ServerState::capture_character` copies an actor's state into a record and
+//! `ServerState::restore_character` applies one to a freshly spawned PC;
+//! numbers are clamped to the archetype's current maxima, and the saved
+//! position is only used when the character is back in the zone it was saved
+//! in.
+//!
+//! [`CharacterStore`] keeps one JSON file per character in a directory, each
+//! written to a temporary file and renamed into place so a crash never leaves
+//! a half-written character. Every file carries its format `version`; older
+//! files are upgraded on load by [`migrate`], one step per version, and files
+//! from a newer server are refused rather than silently truncated.
+//!
+//! The session layer (`SessionHost::set_character_store`) loads a character
+//! when a named client joins, saves it on disconnect and autosaves every
+//! `SessionConfig::autosave_s`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use data_runtime::specs::items::EquipSlot;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ServerState;
+use crate::actor::ActorId;
+use crate::combat::Reputation;
+
+pub use crate::ecs::ItemStack;
+
+/// Bump when the record layout changes and append the upgrade to
+/// [`MIGRATIONS`].
+/// 2: worn items (`equipped`).
+pub const CHARACTER_FORMAT_VERSION: u32 = 2;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` record to version `i + 2`.
+const MIGRATIONS: &[fn(&mut Value) -> Result<()>] = &[v1_wears_nothing];
+
+/// Version 1 records predate equipment.
+fn v1_wears_nothing(v: &mut Value) -> Result<()> {
+    let Some(obj) = v.as_object_mut() else {
+        bail!("character record is not an object");
+    };
+    obj.insert("equipped".into(), Value::Object(Default::default()));
+    Ok(())
+}
+
+/// One saved character.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterRecord {
+    pub version: u32,
+    pub name: String,
+    /// Zone slug the character was saved in.
+    pub zone: Option<String>,
+    pub pos: [f32; 3],
+    pub yaw: f32,
+    pub hp: i32,
+    pub mana: i32,
+    pub stamina: i32,
+    /// Known spells by ability id.
+    pub spells: Vec<String>,
+    /// Seconds left on the global cooldown.
+    pub gcd_s: f32,
+    /// Seconds left per ability id.
+    pub cooldowns: BTreeMap<String, f32>,
+    /// Reputation by faction key.
+    pub reputation: BTreeMap<String, i32>,
+    pub inventory: Vec<ItemStack>,
+    /// Worn item id by slot (`EquipSlot::as_str`).
+    pub equipped: BTreeMap<String, String>,
+}
+
+impl CharacterRecord {
+    /// An empty record for `name`; `capture_character` fills it in.
+    pub fn new(name: &str) -> Self {
+        Self {
+            version: CHARACTER_FORMAT_VERSION,
+            name: name.to_string(),
+            zone: None,
+            pos: [0.0; 3],
+            yaw: 0.0,
+            hp: 0,
+            mana: 0,
+            stamina: 0,
+            spells: Vec::new(),
+            gcd_s: 0.0,
+            cooldowns: BTreeMap::new(),
+            reputation: BTreeMap::new(),
+            inventory: Vec::new(),
+            equipped: BTreeMap::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("encode character")
+    }
+
+    /// Parse a record of any known version, upgrading it to the current one.
+    pub fn from_json(txt: &str) -> Result<Self> {
+        let mut v: Value = serde_json::from_str(txt).context("parse character JSON")?;
+        migrate(&mut v)?;
+        serde_json::from_value(v).context("decode character")
+    }
+}
+
+/// Upgrade a raw record to [`CHARACTER_FORMAT_VERSION`] in place.
+pub fn migrate(v: &mut Value) -> Result<()> {
+    let Some(mut version) = v.get("version").and_then(Value::as_u64) else {
+        bail!("character record has no version");
+    };
+    if version == 0 || version > u64::from(CHARACTER_FORMAT_VERSION) {
+        bail!("character format {version} (this server reads 1..={CHARACTER_FORMAT_VERSION})");
+    }
+    while version < u64::from(CHARACTER_FORMAT_VERSION) {
+        let step = MIGRATIONS[(version - 1) as usize];
+        step(v).with_context(|| format!("migrate character from version {version}"))?;
+        version += 1;
+        v["version"] = Value::from(version);
+    }
+    Ok(())
+}
+
+/// Characters on disk, one file per name.
+#[derive(Debug, Clone)]
+pub struct CharacterStore {
+    dir: PathBuf,
+}
+
+impl CharacterStore {
+    /// Use (and create if needed) `dir`.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File holding `name`; bytes outside `[A-Za-z0-9_-]` are hex-escaped.
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", file_stem(name)))
+    }
+
+    /// The saved character `name`, or `None` if it was never saved.
+    pub fn load(&self, name: &str) -> Result<Option<CharacterRecord>> {
+        let path = self.path_for(name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let txt =
+            std::fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        let rec =
+            CharacterRecord::from_json(&txt).with_context(|| format!("load {}", path.display()))?;
+        if rec.name != name {
+            bail!("{} holds '{}', not '{name}'", path.display(), rec.name);
+        }
+        Ok(Some(rec))
+    }
+
+    /// Write `rec` atomically (temporary file, then rename).
+    pub fn save(&self, rec: &CharacterRecord) -> Result<()> {
+        write_atomic(&self.path_for(&rec.name), &rec.to_json()?)?;
+        metrics::counter!("persist.saves_total").increment(1);
+        Ok(())
+    }
+}
+
+/// `name` as a file stem: bytes outside `[A-Za-z0-9_-]` become `%xx`.
+pub(crate) fn file_stem(name: &str) -> String {
+    let mut file = String::new();
+    for b in name.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'_' || b == b'-' {
+            file.push(char::from(b));
+        } else {
+            file.push_str(&format!("%{b:02x}"));
+        }
+    }
+    file
+}
+
+/// Write `txt` to a sibling `.tmp` file and rename it over `path`.
+pub(crate) fn write_atomic(path: &Path, txt: &str) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, txt).with_context(|| format!("write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("replace {}", path.display()))
+}
+
+impl ServerState {
+    /// Copy `actor`'s state into `rec`, saved as being in `zone`. Returns
+    /// false if there is no such actor.
+    pub fn capture_character(
+        &self,
+        actor: ActorId,
+        zone: Option<&str>,
+        rec: &mut CharacterRecord,
+    ) -> bool {
+        let Some(c) = self.ecs.get(actor) else {
+            return false;
+        };
+        rec.version = CHARACTER_FORMAT_VERSION;
+        rec.zone = zone.map(str::to_string);
+        rec.pos = c.tr.pos.into();
+        rec.yaw = c.tr.yaw;
+        rec.hp = c.hp.hp;
+        if let Some(p) = c.pool {
+            rec.mana = p.mana;
+            rec.stamina = p.stamina;
+        }
+        let ability_id = |s| self.abilities.get(s).map(|a| a.id.clone());
+        rec.spells = c
+            .spellbook
+            .as_ref()
+            .map(|b| b.known.iter().filter_map(|s| ability_id(*s)).collect())
+            .unwrap_or_default();
+        rec.gcd_s = c.cooldowns.as_ref().map_or(0.0, |cd| cd.gcd_ready);
+        rec.cooldowns = c
+            .cooldowns
+            .as_ref()
+            .map(|cd| {
+                cd.per_spell
+                    .iter()
+                    .filter(|(_, left)| **left > 0.0)
+                    .filter_map(|(s, left)| Some((ability_id(*s)?, *left)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        rec.reputation = self
+            .factions
+            .player(actor)
+            .map(|r| {
+                r.iter()
+                    .filter_map(|(f, v)| Some((self.factions.registry.get(f)?.key.clone(), v)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(inv) = &c.inventory {
+            rec.inventory = inv.items.clone();
+            rec.equipped = inv
+                .equipped
+                .iter()
+                .map(|(slot, id)| (slot.as_str().to_string(), id.clone()))
+                .collect();
+        }
+        true
+    }
+
+    /// Apply `rec` to `actor` (a PC spawned for it) now standing in `zone`.
+    /// A character saved dead comes back at full HP at the spawn point.
+    pub fn restore_character(&mut self, actor: ActorId, zone: Option<&str>, rec: &CharacterRecord) {
+        let spell = |id: &String| {
+            let s = self.abilities.resolve(id).map(|a| a.spell);
+            if s.is_none() {
+                log::warn!("persist: '{}' knows unknown ability '{id}'", rec.name);
+            }
+            s
+        };
+        let known: Vec<crate::SpellId> = rec.spells.iter().filter_map(spell).collect();
+        let cooldowns: Vec<(crate::SpellId, f32)> = rec
+            .cooldowns
+            .iter()
+            .filter_map(|(id, left)| Some((spell(id)?, *left)))
+            .collect();
+        let mut reputation = Reputation::default();
+        for (key, v) in &rec.reputation {
+            match self.factions.registry.id(key) {
+                Some(f) => reputation.set(f, *v),
+                None => log::warn!(
+                    "persist: '{}' has reputation with unknown '{key}'",
+                    rec.name
+                ),
+            }
+        }
+        self.factions.set_player(actor, reputation);
+        let Some(c) = self.ecs.get_mut(actor) else {
+            return;
+        };
+        let alive = rec.hp > 0;
+        if alive && rec.zone.as_deref() == zone {
+            c.tr.pos = Vec3::from(rec.pos);
+            c.tr.yaw = rec.yaw;
+        }
+        if alive {
+            c.hp.hp = rec.hp.min(c.hp.max);
+        }
+        if let Some(p) = c.pool.as_mut() {
+            p.mana = rec.mana.clamp(0, p.max);
+            p.stamina = rec.stamina.clamp(0, p.stamina_max);
+        }
+        if !rec.spells.is_empty() {
+            c.spellbook = Some(crate::ecs::Spellbook { known });
+        }
+        if let Some(cd) = c.cooldowns.as_mut() {
+            cd.gcd_ready = rec.gcd_s.clamp(0.0, cd.gcd_s.max(0.0));
+            cd.per_spell.extend(cooldowns);
+        }
+        if let Some(inv) = c.inventory.as_mut() {
+            inv.items.clear();
+            inv.equipped.clear();
+            inv.rev = inv.rev.wrapping_add(1);
+            for s in &rec.inventory {
+                if self.give_item(actor, &s.item, s.count) < s.count {
+                    log::warn!(
+                        "persist: '{}' carries more '{}' than fits",
+                        rec.name,
+                        s.item
+                    );
+                }
+            }
+            for (slot, id) in &rec.equipped {
+                let fits = EquipSlot::parse(slot).is_some_and(|slot| {
+                    self.specs_items.get(id).and_then(|i| i.equip_slot()) == Some(slot)
+                });
+                if !fits || self.equip_item(actor, id).is_err() {
+                    log::warn!("persist: '{}' can't wear '{id}' ({slot})", rec.name);
+                }
+            }
+        }
+        metrics::counter!("persist.restores_total").increment(1);
+    }
+}
@@ -0,0 +1,587 @@
+//! Session recording and deterministic playback.
+//!
+//! A `ReplayLog` holds what is needed to rebuild a session bit for bit: the
+//! zone slug, the destructible, combat and loot seeds, any carved ruins the
+//! world started with (e.g. restored by `--ruins`), hashes of the spec
+//! tables the simulation reads, and every client join, leave and inbound
+//! `ClientCmd` with the tick it arrived on. Each tick also stores a hash of
+//! the authoritative `ActorSnapshot`.
+//!
+//! `verify` boots a fresh `ServerState` for the same zone, carves the
+//! recorded ruins back in (refusing the log if they no longer fit), routes
+//! the logged commands through the session's input path, steps the schedule
+//! headlessly and checks every tick's snapshot hash against the recording.
+//! A bug report becomes a file that reproduces it, and a fixed bug becomes a
+//! regression test.
+//!
+//! Layout (little-endian): `RAREPLAY`, format version (u16), header, events
+//! (u32 count), frames (u32 count). Commands are stored in their wire
+//! encoding.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail, ensure};
+use glam::Vec3;
+use net_core::command::ClientCmd;
+use net_core::snapshot::{ActorSnapshot, SnapshotDecode, SnapshotEncode};
+
+use crate::actor::ActorId;
+use crate::destructible::persist::DestructibleSnapshot;
+use crate::session::{ClientId, Inputs, SessionConfig, despawn_client_pc, spawn_client_pc};
+use crate::{CombatRng, ServerState};
+
+const MAGIC: &[u8; 8] = b"RAREPLAY";
+/// Bump when the file layout changes.
+/// 2: weapon spec hash.
+/// 3: boss script, status, faction, item and loot table hashes.
+/// 4: combat and loot RNG seeds.
+/// 5: carved destructibles at the start of the recording.
+pub const REPLAY_FORMAT_VERSION: u16 = 5;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash = (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// FNV-1a of the snapshot's wire encoding.
+pub fn snapshot_hash(snap: &ActorSnapshot) -> u64 {
+    let mut buf = Vec::new();
+    snap.encode(&mut buf);
+    fnv1a(FNV_OFFSET, &buf)
+}
+
+/// Hashes of the spec tables the schedule reads. A replay only reproduces
+/// under the same data, so a mismatch is reported before anything runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecHashes {
+    /// Server tuning (`Specs`).
+    pub tuning: u64,
+    /// Castable spells (`AbilityDb`).
+    pub abilities: u64,
+    pub archetypes: u64,
+    pub projectiles: u64,
+    pub weapons: u64,
+    pub boss_scripts: u64,
+    pub statuses: u64,
+    /// Faction definitions and hostility (not player reputation).
+    pub factions: u64,
+    pub items: u64,
+    pub loot: u64,
+}
+
+impl SpecHashes {
+    pub fn of(srv: &ServerState) -> Self {
+        // Map-backed tables are hashed in key order so the result is stable.
+        let sorted = |mut rows: Vec<String>| {
+            rows.sort();
+            rows.iter().fold(FNV_OFFSET, |h, r| fnv1a(h, r.as_bytes()))
+        };
+        Self {
+            tuning: fnv1a(FNV_OFFSET, format!("{:?}", srv.specs).as_bytes()),
+            abilities: srv
+                .abilities
+                .iter()
+                .fold(FNV_OFFSET, |h, a| fnv1a(h, format!("{a:?}").as_bytes())),
+            archetypes: sorted(
+                srv.specs_arche
+                    .entries
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v:?}"))
+                    .collect(),
+            ),
+            projectiles: sorted(
+                srv.specs_proj
+                    .actions
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v:?}"))
+                    .collect(),
+            ),
+            weapons: sorted(
+                srv.specs_weapons
+                    .weapons
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v:?}"))
+                    .collect(),
+            ),
+            boss_scripts: sorted(
+                srv.boss_scripts
+                    .scripts
+                    .iter()
+                    .flat_map(|(k, s)| {
+                        let script = format!(
+                            "{k}={}|{:?}|{:?}|{}|{}|{}",
+                            s.id,
+                            s.phases,
+                            s.triggers,
+                            s.enrage_s,
+                            s.enrage_damage_mul,
+                            s.enrage_interval_mul
+                        );
+                        std::iter::once(script).chain(
+                            s.abilities
+                                .iter()
+                                .map(move |(a, v)| format!("{k}.{a}={v:?}")),
+                        )
+                    })
+                    .collect(),
+            ),
+            statuses: sorted(
+                srv.statuses
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v:?}"))
+                    .collect(),
+            ),
+            factions: sorted(srv.factions.registry.spec_rows()),
+            items: sorted(
+                srv.specs_items
+                    .items
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v:?}"))
+                    .collect(),
+            ),
+            loot: sorted(
+                srv.specs_loot
+                    .tables
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v:?}"))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Name of the first table that differs from `other`.
+    fn first_mismatch(&self, other: &Self) -> Option<&'static str> {
+        [
+            ("tuning", self.tuning == other.tuning),
+            ("abilities", self.abilities == other.abilities),
+            ("archetypes", self.archetypes == other.archetypes),
+            ("projectiles", self.projectiles == other.projectiles),
+            ("weapons", self.weapons == other.weapons),
+            ("boss_scripts", self.boss_scripts == other.boss_scripts),
+            ("statuses", self.statuses == other.statuses),
+            ("factions", self.factions == other.factions),
+            ("items", self.items == other.items),
+            ("loot", self.loot == other.loot),
+        ]
+        .into_iter()
+        .find(|(_, same)| !same)
+        .map(|(name, _)| name)
+    }
+}
+
+/// Everything needed to boot the same world the recording started from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayHeader {
+    pub zone_slug: Option<String>,
+    /// Destructible seed (`DestructibleConfig::seed`).
+    pub seed: u64,
+    /// `ServerState::combat_rng` seed.
+    pub combat_seed: u64,
+    /// `ServerState::loot_rng` seed.
+    pub loot_seed: u64,
+    pub tick_hz: u32,
+    /// Session cast rate limit, which decides which casts were accepted.
+    pub max_casts_per_sec: u32,
+    pub specs: SpecHashes,
+    /// Carved destructibles at the start (`None` if the zone was pristine).
+    pub ruins: Option<DestructibleSnapshot>,
+}
+
+impl ReplayHeader {
+    pub fn capture(srv: &ServerState, cfg: &SessionConfig) -> Self {
+        let ruins = srv.capture_destructibles(cfg.zone_slug.as_deref().unwrap_or_default());
+        Self {
+            zone_slug: cfg.zone_slug.clone(),
+            seed: srv.destruct_registry.cfg.seed,
+            combat_seed: srv.combat_rng.seed(),
+            loot_seed: srv.loot_rng.seed(),
+            tick_hz: cfg.tick_hz,
+            max_casts_per_sec: cfg.max_casts_per_sec,
+            specs: SpecHashes::of(srv),
+            ruins: (!ruins.proxies.is_empty()).then_some(ruins),
+        }
+    }
+}
+
+/// One logged session event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayEvent {
+    /// A client was admitted and its PC spawned.
+    Join {
+        client: u32,
+        actor: u32,
+        pos: [f32; 3],
+        name: Option<String>,
+    },
+    /// A decoded inbound command.
+    Cmd { client: u32, cmd: ClientCmd },
+    /// A client left and its PC was despawned.
+    Leave { client: u32 },
+}
+
+/// A recorded session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayLog {
+    pub header: ReplayHeader,
+    /// Events in arrival order, tagged with their tick.
+    pub events: Vec<(u64, ReplayEvent)>,
+    /// `(tick, snapshot_hash)` for every broadcast tick.
+    pub frames: Vec<(u64, u64)>,
+}
+
+impl ReplayLog {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&REPLAY_FORMAT_VERSION.to_le_bytes());
+        let h = &self.header;
+        put_opt_str(&mut out, h.zone_slug.as_deref());
+        out.extend_from_slice(&h.seed.to_le_bytes());
+        out.extend_from_slice(&h.combat_seed.to_le_bytes());
+        out.extend_from_slice(&h.loot_seed.to_le_bytes());
+        out.extend_from_slice(&h.tick_hz.to_le_bytes());
+        out.extend_from_slice(&h.max_casts_per_sec.to_le_bytes());
+        for v in [
+            h.specs.tuning,
+            h.specs.abilities,
+            h.specs.archetypes,
+            h.specs.projectiles,
+            h.specs.weapons,
+            h.specs.boss_scripts,
+            h.specs.statuses,
+            h.specs.factions,
+            h.specs.items,
+            h.specs.loot,
+        ] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        // Plain data, so encoding can't fail.
+        let ruins = h.ruins.as_ref().and_then(|r| r.to_json().ok());
+        put_opt_str(&mut out, ruins.as_deref());
+        put_len(&mut out, self.events.len());
+        for (tick, ev) in &self.events {
+            out.extend_from_slice(&tick.to_le_bytes());
+            match ev {
+                ReplayEvent::Join {
+                    client,
+                    actor,
+                    pos,
+                    name,
+                } => {
+                    out.push(0);
+                    out.extend_from_slice(&client.to_le_bytes());
+                    out.extend_from_slice(&actor.to_le_bytes());
+                    for v in pos {
+                        out.extend_from_slice(&v.to_le_bytes());
+                    }
+                    put_opt_str(&mut out, name.as_deref());
+                }
+                ReplayEvent::Cmd { client, cmd } => {
+                    out.push(1);
+                    out.extend_from_slice(&client.to_le_bytes());
+                    let mut buf = Vec::new();
+                    cmd.encode(&mut buf);
+                    put_len(&mut out, buf.len());
+                    out.extend_from_slice(&buf);
+                }
+                ReplayEvent::Leave { client } => {
+                    out.push(2);
+                    out.extend_from_slice(&client.to_le_bytes());
+                }
+            }
+        }
+        put_len(&mut out, self.frames.len());
+        for (tick, hash) in &self.frames {
+            out.extend_from_slice(&tick.to_le_bytes());
+            out.extend_from_slice(&hash.to_le_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader(bytes);
+        ensure!(r.take(MAGIC.len())? == MAGIC, "not a replay log");
+        let version = r.u16()?;
+        ensure!(
+            version == REPLAY_FORMAT_VERSION,
+            "replay format {version} (expected {REPLAY_FORMAT_VERSION})"
+        );
+        let header = ReplayHeader {
+            zone_slug: r.opt_str()?,
+            seed: r.u64()?,
+            combat_seed: r.u64()?,
+            loot_seed: r.u64()?,
+            tick_hz: r.u32()?,
+            max_casts_per_sec: r.u32()?,
+            specs: SpecHashes {
+                tuning: r.u64()?,
+                abilities: r.u64()?,
+                archetypes: r.u64()?,
+                projectiles: r.u64()?,
+                weapons: r.u64()?,
+                boss_scripts: r.u64()?,
+                statuses: r.u64()?,
+                factions: r.u64()?,
+                items: r.u64()?,
+                loot: r.u64()?,
+            },
+            ruins: r
+                .opt_str()?
+                .map(|txt| DestructibleSnapshot::from_json(&txt))
+                .transpose()
+                .context("recorded ruins")?,
+        };
+        let n = r.u32()?;
+        let mut events = Vec::new();
+        for i in 0..n {
+            let tick = r.u64()?;
+            let kind = r.u8()?;
+            let client = r.u32()?;
+            let ev = match kind {
+                0 => ReplayEvent::Join {
+                    client,
+                    actor: r.u32()?,
+                    pos: [r.f32()?, r.f32()?, r.f32()?],
+                    name: r.opt_str()?,
+                },
+                1 => {
+                    let len = r.u32()? as usize;
+                    let mut buf = r.take(len)?;
+                    let cmd = ClientCmd::decode(&mut buf)
+                        .with_context(|| format!("event {i}: command"))?;
+                    ReplayEvent::Cmd { client, cmd }
+                }
+                2 => ReplayEvent::Leave { client },
+                k => bail!("event {i}: unknown kind {k}"),
+            };
+            events.push((tick, ev));
+        }
+        let n = r.u32()?;
+        let mut frames = Vec::new();
+        for _ in 0..n {
+            frames.push((r.u64()?, r.u64()?));
+        }
+        ensure!(r.0.is_empty(), "{} trailing bytes", r.0.len());
+        Ok(Self {
+            header,
+            events,
+            frames,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_bytes()).with_context(|| format!("write {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+        Self::from_bytes(&bytes).with_context(|| format!("parse {}", path.display()))
+    }
+}
+
+fn put_len(out: &mut Vec<u8>, n: usize) {
+    out.extend_from_slice(&u32::try_from(n).unwrap_or(u32::MAX).to_le_bytes());
+}
+
+fn put_opt_str(out: &mut Vec<u8>, s: Option<&str>) {
+    match s {
+        Some(s) => {
+            out.push(1);
+            put_len(out, s.len());
+            out.extend_from_slice(s.as_bytes());
+        }
+        None => out.push(0),
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        ensure!(self.0.len() >= n, "truncated replay log");
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut a = [0u8; N];
+        a.copy_from_slice(self.take(N)?);
+        Ok(a)
+    }
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+    fn f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+    fn opt_str(&mut self) -> Result<Option<String>> {
+        if self.u8()? == 0 {
+            return Ok(None);
+        }
+        let len = self.u32()? as usize;
+        let s = std::str::from_utf8(self.take(len)?).context("string is not UTF-8")?;
+        Ok(Some(s.to_string()))
+    }
+}
+
+/// Appends session events to a `ReplayLog`; owned by `SessionHost`.
+#[derive(Debug)]
+pub struct Recorder {
+    log: ReplayLog,
+}
+
+impl Recorder {
+    pub fn new(header: ReplayHeader) -> Self {
+        Self {
+            log: ReplayLog {
+                header,
+                events: Vec::new(),
+                frames: Vec::new(),
+            },
+        }
+    }
+
+    pub(crate) fn join(
+        &mut self,
+        tick: u64,
+        client: ClientId,
+        actor: ActorId,
+        pos: Vec3,
+        name: Option<&str>,
+    ) {
+        self.log.events.push((
+            tick,
+            ReplayEvent::Join {
+                client: client.0,
+                actor: actor.0,
+                pos: pos.to_array(),
+                name: name.map(str::to_string),
+            },
+        ));
+    }
+
+    pub(crate) fn cmd(&mut self, tick: u64, client: ClientId, cmd: &ClientCmd) {
+        self.log.events.push((
+            tick,
+            ReplayEvent::Cmd {
+                client: client.0,
+                cmd: cmd.clone(),
+            },
+        ));
+    }
+
+    pub(crate) fn leave(&mut self, tick: u64, client: ClientId) {
+        self.log
+            .events
+            .push((tick, ReplayEvent::Leave { client: client.0 }));
+    }
+
+    pub(crate) fn frame(&mut self, snap: &ActorSnapshot) {
+        self.log.frames.push((snap.tick, snapshot_hash(snap)));
+    }
+
+    pub fn log(&self) -> &ReplayLog {
+        &self.log
+    }
+
+    pub fn finish(self) -> ReplayLog {
+        self.log
+    }
+}
+
+/// Replay `log` headlessly and check every recorded tick's snapshot hash.
+/// Returns the number of ticks verified; the error names the first tick
+/// that diverged.
+pub fn verify(log: &ReplayLog) -> Result<usize> {
+    let h = &log.header;
+    ensure!(h.tick_hz > 0, "tick rate must be > 0");
+    let mut srv = ServerState::new();
+    srv.destruct_registry.cfg.seed = h.seed;
+    srv.combat_rng = CombatRng::seeded(h.combat_seed);
+    srv.loot_rng = CombatRng::seeded(h.loot_seed);
+    if let Some(table) = SpecHashes::of(&srv).first_mismatch(&h.specs) {
+        bail!("spec table '{table}' differs from the recording");
+    }
+    if let Some(slug) = &h.zone_slug {
+        crate::zones::boot_with_zone(&mut srv, slug);
+    }
+    if let Some(ruins) = &h.ruins {
+        let r = srv.restore_destructibles(ruins);
+        ensure!(
+            r.chunks_rejected == 0
+                && r.proxies_skipped == 0
+                && srv.capture_destructibles(&ruins.zone) == *ruins,
+            "recorded ruins do not fit the zone ({} chunks rejected, {} proxies skipped)",
+            r.chunks_rejected,
+            r.proxies_skipped
+        );
+    }
+    let dt = 1.0 / h.tick_hz as f32;
+    let mut clients: Vec<(u32, ActorId, Inputs)> = Vec::new();
+    let mut events = log.events.iter().peekable();
+    for &(tick, want) in &log.frames {
+        for (_, _, inputs) in &mut clients {
+            inputs.begin_tick(tick, h.tick_hz);
+        }
+        while let Some((_, ev)) = events.next_if(|(t, _)| *t <= tick) {
+            match ev {
+                ReplayEvent::Join {
+                    client,
+                    actor,
+                    pos,
+                    name,
+                } => {
+                    let got = spawn_client_pc(&mut srv, Vec3::from_array(*pos), name.clone());
+                    ensure!(
+                        got.0 == *actor,
+                        "tick {tick}: client {client} spawned as actor {} (recorded {actor})",
+                        got.0
+                    );
+                    clients.push((*client, got, Inputs::new(tick)));
+                }
+                ReplayEvent::Cmd { client, cmd } => {
+                    let Some((_, actor, inputs)) = clients.iter_mut().find(|c| c.0 == *client)
+                    else {
+                        bail!("tick {tick}: command from unknown client {client}");
+                    };
+                    inputs.apply(&mut srv, *actor, cmd.clone(), h.max_casts_per_sec);
+                }
+                ReplayEvent::Leave { client } => {
+                    if let Some(ix) = clients.iter().position(|c| c.0 == *client) {
+                        let (_, actor, _) = clients.swap_remove(ix);
+                        despawn_client_pc(&mut srv, actor);
+                    }
+                }
+            }
+        }
+        for (_, actor, inputs) in &mut clients {
+            inputs.end_tick(&mut srv, *actor);
+        }
+        srv.step_authoritative(dt);
+        // Drain what `SessionHost::broadcast` drains so state stays aligned.
+        srv.fx_hits.clear();
+        srv.hud_toasts.clear();
+        srv.drain_destruct_mesh_deltas();
+        srv.destruct_bootstrap_instances_outstanding = false;
+        let got = snapshot_hash(&srv.tick_snapshot_actors(tick));
+        ensure!(
+            got == want,
+            "tick {tick}: snapshot hash {got:#018x} != recorded {want:#018x}"
+        );
+    }
+    Ok(log.frames.len())
+}
@@ -0,0 +1,1085 @@
+//! Multi-client session layer for the dedicated server.
+//!
+//! Each connection owns one PC actor and one replication baseline. Inbound
+//! `ClientCmd`s are routed to that actor (never to the singleton
+//! `ServerState::pc_actor`), and every tick each client receives its own
+//! interest-limited `ActorSnapshotDelta`, HUD status, boss telegraphs, status
+//! effects, its own inventory and loot drops, and destructible traffic.
+//!
+//! The layer is transport-agnostic: anything implementing
+//! `net_core::transport::Transport` can be attached (loopback, UDP, WebSocket).
+//! Traffic runs over a `net_core::link::Endpoint` per client: toasts,
+//! inventories and loot drops (sent when they change) and destructible
+//! instances/deltas are reliable; actor deltas, HUD status and active
+//! telegraphs/statuses are unreliable (latest wins). The empty list that
+//! clears them goes on the same stream, so a late update can't outrun it, and
+//! is repeated until the client acks a later tick. Actor deltas are
+//! baseline-acked: each client acks the tick it applied (`ClientCmd::Ack`) and
+//! is sent deltas against that.
+//!
+//! Sequenced `ClientCmd::Move` inputs are queued per client and applied one
+//! per tick; the newest applied `seq` is echoed in that client's delta
+//! (`input_seq`) so it can reconcile its predicted PC against the server.
+//!
+//! Remote clients join via `accept`: the connection stays pending until its
+//! first message, a `net_core::handshake::Hello`, is validated (protocol
+//! version, zone slug, player name, capacity). The server then spawns the PC
+//! and replies `Welcome` with the actor id, zone manifest id, tick rate and
+//! the wire versions every later message uses, or a typed `Reject` followed
+//! by a close. `connect` remains for trusted in-process clients and skips the
+//! handshake.
+//!
+//! `start_recording` logs every join, leave and inbound command with its
+//! tick, plus each tick's snapshot hash, as a `replay::ReplayLog`.
+//!
+//! With a `persist::CharacterStore` attached (`set_character_store`), a named
+//! client's character is restored when it joins, saved when it leaves and
+//! autosaved every `autosave_s`. Restored characters are not part of a
+//! recording, so sessions meant for replay should run without a store.
+//!
+//! Actor interest uses a shared `GridIndex` and a per-client `GridInterest`
+//! (enter at `interest_radius_m`, leave past `+ interest_hysteresis_m`). Within
+//! interest, a per-client `PriorityBudget` caps actor bytes per tick; actors
+//! that don't fit keep their last sent state and are refreshed on a later tick.
+//! Destructible chunk meshes are held back while their instance is out of
+//! range; the host keeps each chunk's latest mesh, so clients that walk into
+//! range or join late still receive every carve.
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use glam::Vec3;
+use net_core::baseline::{ActorSet, BaselineTracker, refresh_bytes};
+use net_core::command::ClientCmd;
+use net_core::handshake::{
+    Hello, MAX_NAME_BYTES, PROTOCOL_VERSION, Reject, RejectReason, Welcome, WireVersions,
+};
+use net_core::interest::{
+    BudgetCandidate, GridIndex, GridInterest, PriorityBudget, PriorityInputs,
+};
+use net_core::link::{Channel, Endpoint};
+use net_core::snapshot::{
+    ActorRep, ActorSnapshotDelta, ChunkMeshDelta, HudStatusMsg, HudToastMsg, LootDropRep, LootMsg,
+    SnapshotDecode, SnapshotEncode, StatusMsg, StatusRep, TelegraphMsg, TelegraphRep,
+};
+use net_core::transport::{Transport, TrySendError};
+
+use crate::abilities::AbilityDb;
+use crate::actor::{ActorId, ActorKind};
+use crate::ecs::Components;
+use crate::persist::{CharacterRecord, CharacterStore};
+use crate::replay::{Recorder, ReplayHeader, ReplayLog};
+use crate::{ServerState, SpellId};
+
+/// Queued sequenced inputs per client; older ones are dropped past this.
+const MAX_QUEUED_INPUTS: usize = 8;
+/// Ticks a rejected connection lingers so its `Reject` can be delivered.
+const REJECT_LINGER_TICKS: u64 = 15;
+/// Ticks the empty message clearing a latest-wins list is repeated, unless the
+/// client acks a later tick first.
+const CLEAR_REPEAT_TICKS: u64 = 15;
+
+/// A destructible chunk: (instance id, chunk coords).
+type ChunkKey = (u64, (u32, u32, u32));
+
+/// Opaque per-connection handle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClientId(pub u32);
+
+/// Where a latest-wins list stream (telegraphs, statuses) stands for one client.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ListStream {
+    /// Nothing to show and the clear has settled.
+    #[default]
+    Idle,
+    /// The last message listed something.
+    Shown,
+    /// Emptied at tick `since`; the clear repeats until acked past or expired.
+    Clearing { since: u64 },
+}
+
+impl ListStream {
+    /// Advance for this tick's list and say whether to send it.
+    fn due(&mut self, empty: bool, tick: u64, acked: Option<u64>) -> bool {
+        *self = match (*self, empty) {
+            (_, false) => Self::Shown,
+            (Self::Shown, true) => Self::Clearing { since: tick },
+            (Self::Clearing { since }, true)
+                if acked.is_none_or(|a| a <= since) && tick < since + CLEAR_REPEAT_TICKS =>
+            {
+                *self
+            }
+            (_, true) => Self::Idle,
+        };
+        *self != Self::Idle
+    }
+}
+
+/// Tunables for the session host.
+#[derive(Clone, Debug)]
+pub struct SessionConfig {
+    /// Fixed simulation rate used for rate limiting and idle timeouts.
+    pub tick_hz: u32,
+    /// Planar interest radius around each client's PC (meters).
+    pub interest_radius_m: f32,
+    /// Extra distance an actor must move past the radius before it leaves.
+    pub interest_hysteresis_m: f32,
+    /// Interest grid cell size (meters).
+    pub interest_cell_m: f32,
+    /// Per-client actor replication budget (bytes per second).
+    pub actor_budget_bytes_per_sec: u32,
+    /// Distance at which an actor's priority is halved (meters).
+    pub priority_falloff_m: f32,
+    /// Cast and attack commands accepted per client per second; extras are dropped.
+    pub max_casts_per_sec: u32,
+    /// Drop a client after this many seconds without inbound traffic (0 = never).
+    pub idle_timeout_s: f32,
+    /// Where new PCs are spawned; clients are spread on a small ring around it.
+    pub spawn_center: Vec3,
+    /// Zone clients must request in `Hello`; `None` accepts any slug.
+    pub zone_slug: Option<String>,
+    /// `ZoneManifest::zone_id` reported in `Welcome`.
+    pub zone_manifest_id: u32,
+    /// Connected clients beyond this are rejected with `ServerFull` (0 = unlimited).
+    pub max_clients: usize,
+    /// Reject clients whose `Hello::build_hash` differs from ours.
+    pub require_build_match: bool,
+    /// Seconds between character autosaves when a store is attached (0 = only
+    /// on disconnect).
+    pub autosave_s: f32,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            tick_hz: 30,
+            interest_radius_m: 40.0,
+            interest_hysteresis_m: 4.0,
+            interest_cell_m: 16.0,
+            actor_budget_bytes_per_sec: 48 * 1024,
+            priority_falloff_m: 10.0,
+            max_casts_per_sec: 20,
+            idle_timeout_s: 30.0,
+            spawn_center: Vec3::new(0.0, 0.6, 0.0),
+            zone_slug: None,
+            zone_manifest_id: 0,
+            max_clients: 0,
+            require_build_match: false,
+            autosave_s: 60.0,
+        }
+    }
+}
+
+struct Session {
+    id: ClientId,
+    actor: ActorId,
+    xport: Box<dyn Transport>,
+    link: Endpoint,
+    baseline: BaselineTracker,
+    interest: GridInterest,
+    budget: PriorityBudget,
+    inputs: Inputs,
+    last_rx_tick: u64,
+    sent_destr_instances: HashSet<u64>,
+    /// Chunks whose latest mesh this client has not been sent; flushed once
+    /// their instance is within interest range.
+    stale_chunks: BTreeSet<ChunkKey>,
+    telegraphs: ListStream,
+    statuses: ListStream,
+    /// `Inventory::rev` last sent to this client.
+    inventory_rev: Option<u32>,
+    /// Loot drops last sent to this client.
+    loot_shown: Vec<LootDropRep>,
+    disconnected: bool,
+    /// The stored character this client plays, if a store is attached.
+    character: Option<CharacterRecord>,
+}
+
+impl Session {
+    fn send(&mut self, ch: Channel, msg: &impl SnapshotEncode) {
+        let mut buf = Vec::new();
+        msg.encode(&mut buf);
+        // Each message kind is its own unreliable stream, keyed by its tag.
+        let stream = buf.first().copied().unwrap_or(0);
+        let len = buf.len();
+        match self.link.send_on(ch, stream, buf) {
+            Ok(()) => {}
+            Err(TrySendError::TooLarge) => {
+                log::warn!(
+                    "session: client {:?} dropped a {len}-byte message (tag {stream:#04x})",
+                    self.id
+                );
+            }
+            Err(_) => {
+                // Reliable backlog overflowed: the client cannot keep up.
+                log::warn!("session: client {:?} reliable backlog full", self.id);
+                self.disconnected = true;
+            }
+        }
+    }
+
+    fn flush(&mut self, now_ms: u64) {
+        for pkt in self.link.flush(now_ms) {
+            let len = pkt.len() as u64;
+            match self.xport.try_send(pkt) {
+                Ok(()) => {
+                    metrics::counter!("net.bytes_sent_total", "dir" => "tx").increment(len);
+                }
+                Err(TrySendError::Disconnected) => self.disconnected = true,
+                // Reliable messages are resent; unreliable ones are superseded.
+                Err(TrySendError::Full) | Err(TrySendError::TooLarge) => {
+                    metrics::counter!("session.send_dropped_total").increment(1);
+                }
+            }
+        }
+    }
+}
+
+/// A connection that has not completed the handshake yet.
+struct Pending {
+    id: ClientId,
+    xport: Box<dyn Transport>,
+    link: Endpoint,
+    opened_tick: u64,
+    /// Set once a `Reject` was queued; the connection is dropped after this tick.
+    close_at: Option<u64>,
+}
+
+/// Owns all connected clients and bridges them to a `ServerState`.
+pub struct SessionHost {
+    cfg: SessionConfig,
+    sessions: Vec<Session>,
+    pending: Vec<Pending>,
+    next_client: u32,
+    tick: u64,
+    recorder: Option<Recorder>,
+    store: Option<CharacterStore>,
+    /// Latest mesh of every remeshed chunk, for clients that come into range
+    /// or join after the carve.
+    chunk_meshes: HashMap<ChunkKey, ChunkMeshDelta>,
+}
+
+impl SessionHost {
+    pub fn new(cfg: SessionConfig) -> Self {
+        Self {
+            cfg,
+            sessions: Vec::new(),
+            pending: Vec::new(),
+            next_client: 1,
+            tick: 0,
+            recorder: None,
+            store: None,
+            chunk_meshes: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Connections still waiting on (or being refused by) the handshake.
+    #[inline]
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Current replication tick (advanced by `broadcast`).
+    #[inline]
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// PC actor owned by `client`, if connected.
+    pub fn actor_of(&self, client: ClientId) -> Option<ActorId> {
+        self.sessions
+            .iter()
+            .find(|s| s.id == client)
+            .map(|s| s.actor)
+    }
+
+    /// Start logging joins, leaves, inbound commands and per-tick snapshot
+    /// hashes for `replay::verify`. Start before the first client connects,
+    /// right after the zone is booted (and any saved ruins restored; the
+    /// header carries them), so the log replays from a clean boot.
+    pub fn start_recording(&mut self, srv: &ServerState) {
+        if !self.sessions.is_empty() {
+            log::warn!("session: recording started with clients connected; replay will diverge");
+        }
+        let header = ReplayHeader::capture(srv, &self.cfg);
+        self.recorder = Some(Recorder::new(header));
+    }
+
+    /// Load, save and autosave named clients' characters in `store`. Attach
+    /// before clients join; characters already connected are not restored.
+    pub fn set_character_store(&mut self, store: CharacterStore) {
+        self.store = Some(store);
+    }
+
+    /// Save every connected character now (e.g. before shutting down).
+    /// Returns how many were written.
+    pub fn save_characters(&mut self, srv: &ServerState) -> usize {
+        let (Some(store), zone) = (&self.store, self.cfg.zone_slug.as_deref()) else {
+            return 0;
+        };
+        self.sessions
+            .iter_mut()
+            .map(|s| save_character(store, zone, srv, s))
+            .filter(|saved| *saved)
+            .count()
+    }
+
+    /// The recording so far, if one is running.
+    pub fn recording(&self) -> Option<&ReplayLog> {
+        self.recorder.as_ref().map(Recorder::log)
+    }
+
+    /// Stop recording and return the log.
+    pub fn stop_recording(&mut self) -> Option<ReplayLog> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    /// Attach a trusted in-process connection and spawn its PC immediately,
+    /// without a handshake.
+    pub fn connect(&mut self, srv: &mut ServerState, xport: Box<dyn Transport>) -> ClientId {
+        let id = self.next_id();
+        self.admit(srv, id, xport, Endpoint::default(), None);
+        id
+    }
+
+    /// Attach a remote connection. It joins once its `Hello` is accepted by
+    /// `pump_inputs`; until then it has no actor.
+    pub fn accept(&mut self, xport: Box<dyn Transport>) -> ClientId {
+        let id = self.next_id();
+        self.pending.push(Pending {
+            id,
+            xport,
+            link: Endpoint::default(),
+            opened_tick: self.tick,
+            close_at: None,
+        });
+        id
+    }
+
+    fn next_id(&mut self) -> ClientId {
+        let id = ClientId(self.next_client);
+        self.next_client = self.next_client.wrapping_add(1);
+        id
+    }
+
+    fn admit(
+        &mut self,
+        srv: &mut ServerState,
+        id: ClientId,
+        xport: Box<dyn Transport>,
+        link: Endpoint,
+        name: Option<String>,
+    ) -> ActorId {
+        // Spread players on a small ring so they don't spawn stacked.
+        let slot = self.sessions.len() as f32;
+        let a = slot * 0.618_034 * std::f32::consts::TAU;
+        let r = if self.sessions.is_empty() { 0.0 } else { 2.0 };
+        let pos = self.cfg.spawn_center + Vec3::new(r * a.cos(), 0.0, r * a.sin());
+        // (record, whether it was saved before)
+        let character = match (&self.store, &name) {
+            (Some(store), Some(name)) => match store.load(name) {
+                Ok(Some(rec)) => Some((rec, true)),
+                Ok(None) => Some((CharacterRecord::new(name), false)),
+                Err(e) => {
+                    // Leave the file alone rather than overwrite it with a fresh character.
+                    log::warn!("session: character '{name}' not loaded: {e:#}");
+                    None
+                }
+            },
+            _ => None,
+        };
+        let actor = spawn_client_pc(srv, pos, name);
+        if let Some(rec) = &mut self.recorder {
+            let name = srv.ecs.get(actor).and_then(|c| c.name.as_deref());
+            rec.join(self.tick, id, actor, pos, name);
+        }
+        if let Some((rec, true)) = &character {
+            srv.restore_character(actor, self.cfg.zone_slug.as_deref(), rec);
+        }
+        log::info!("session: client {:?} connected -> actor {:?}", id, actor);
+        metrics::gauge!("session.clients").set((self.sessions.len() + 1) as f64);
+        self.sessions.push(Session {
+            id,
+            actor,
+            xport,
+            link,
+            baseline: BaselineTracker::new(),
+            interest: GridInterest::new(
+                self.cfg.interest_radius_m,
+                self.cfg.interest_radius_m + self.cfg.interest_hysteresis_m,
+            ),
+            budget: PriorityBudget::new(),
+            inputs: Inputs::new(self.tick),
+            last_rx_tick: self.tick,
+            sent_destr_instances: HashSet::new(),
+            stale_chunks: self.chunk_meshes.keys().copied().collect(),
+            telegraphs: ListStream::Idle,
+            statuses: ListStream::Idle,
+            inventory_rev: None,
+            loot_shown: Vec::new(),
+            disconnected: false,
+            character: character.map(|(rec, _)| rec),
+        });
+        actor
+    }
+
+    /// Check a `Hello` against this server; `Err` carries the rejection.
+    fn validate(&self, srv: &ServerState, hello: &Hello) -> Result<(), RejectReason> {
+        if hello.protocol != PROTOCOL_VERSION {
+            return Err(RejectReason::VersionMismatch);
+        }
+        if self.cfg.require_build_match && hello.build_hash != net_core::handshake::BUILD_HASH {
+            return Err(RejectReason::BuildMismatch);
+        }
+        if let Some(zone) = &self.cfg.zone_slug
+            && *zone != hello.zone_slug
+        {
+            return Err(RejectReason::UnknownZone);
+        }
+        let name = hello.player_name.trim();
+        if name.is_empty() || name.len() > MAX_NAME_BYTES || name.chars().any(char::is_control) {
+            return Err(RejectReason::BadName);
+        }
+        // One session per character: a second copy would load the same save
+        // and the last one out would overwrite the other's progress.
+        if self
+            .sessions
+            .iter()
+            .any(|s| srv.ecs.get(s.actor).and_then(|c| c.name.as_deref()) == Some(name))
+        {
+            return Err(RejectReason::NameInUse);
+        }
+        if self.cfg.max_clients > 0 && self.sessions.len() >= self.cfg.max_clients {
+            return Err(RejectReason::ServerFull);
+        }
+        Ok(())
+    }
+
+    /// Drive pending handshakes: admit valid `Hello`s, refuse the rest, and
+    /// drop connections that were refused, closed, or never said hello.
+    fn pump_handshakes(&mut self, srv: &mut ServerState) {
+        let now_ms = self.tick * 1000 / u64::from(self.cfg.tick_hz.max(1));
+        let idle_ticks = (self.cfg.idle_timeout_s * self.cfg.tick_hz as f32) as u64;
+        let mut i = 0;
+        while i < self.pending.len() {
+            let p = &mut self.pending[i];
+            let mut closed = false;
+            while let Some(bytes) = p.xport.try_recv() {
+                if p.link.receive(&bytes).is_err() {
+                    metrics::counter!("session.rejected_total", "reason" => "packet").increment(1);
+                }
+            }
+            let hello = match p.close_at {
+                None => p.link.recv().map(|(_, payload)| {
+                    let mut slice: &[u8] = &payload;
+                    Hello::decode(&mut slice).map_err(|_| RejectReason::Malformed)
+                }),
+                Some(_) => None,
+            };
+            let verdict = hello.map(|h| h.and_then(|h| self.validate(srv, &h).map(|()| h)));
+            let p = &mut self.pending[i];
+            match verdict {
+                Some(Ok(hello)) => {
+                    let p = self.pending.swap_remove(i);
+                    let name = hello.player_name.trim().to_string();
+                    let actor = self.admit(srv, p.id, p.xport, p.link, Some(name));
+                    let welcome = Welcome {
+                        protocol: PROTOCOL_VERSION,
+                        actor_id: actor.0,
+                        zone_manifest_id: self.cfg.zone_manifest_id,
+                        tick_hz: u16::try_from(self.cfg.tick_hz).unwrap_or(u16::MAX),
+                        versions: WireVersions::CURRENT,
+                    };
+                    if let Some(s) = self.sessions.last_mut() {
+                        s.send(Channel::Reliable, &welcome);
+                        s.flush(now_ms);
+                    }
+                    metrics::counter!("session.handshake_total", "result" => "welcome")
+                        .increment(1);
+                    continue;
+                }
+                Some(Err(reason)) => {
+                    log::info!("session: client {:?} rejected: {:?}", p.id, reason);
+                    metrics::counter!("session.handshake_total", "result" => "reject").increment(1);
+                    let mut buf = Vec::new();
+                    Reject {
+                        reason,
+                        server_protocol: PROTOCOL_VERSION,
+                    }
+                    .encode(&mut buf);
+                    if p.link.send(Channel::Reliable, buf).is_err() {
+                        closed = true;
+                    }
+                    p.close_at = Some(self.tick + REJECT_LINGER_TICKS);
+                }
+                None => {}
+            }
+            // Flush (and resend) until the reject is acked or the linger expires.
+            for pkt in p.link.flush(now_ms) {
+                if let Err(TrySendError::Disconnected) = p.xport.try_send(pkt) {
+                    closed = true;
+                }
+            }
+            let expired = match p.close_at {
+                Some(t) => self.tick >= t || p.link.unacked() == 0,
+                None => idle_ticks > 0 && self.tick.saturating_sub(p.opened_tick) > idle_ticks,
+            };
+            if closed || expired {
+                self.pending.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Detach a connection and despawn its PC.
+    pub fn disconnect(&mut self, srv: &mut ServerState, client: ClientId) {
+        if let Some(ix) = self.sessions.iter().position(|s| s.id == client) {
+            let mut s = self.sessions.swap_remove(ix);
+            if let Some(rec) = &mut self.recorder {
+                rec.leave(self.tick, client);
+            }
+            if let Some(store) = &self.store {
+                save_character(store, self.cfg.zone_slug.as_deref(), srv, &mut s);
+            }
+            despawn_client_pc(srv, s.actor);
+            log::info!("session: client {:?} disconnected", client);
+            metrics::gauge!("session.clients").set(self.sessions.len() as f64);
+        }
+    }
+
+    /// Drain every client's inbound commands and apply them to that client's actor.
+    /// Also reaps clients whose transport closed or went idle.
+    pub fn pump_inputs(&mut self, srv: &mut ServerState) {
+        self.pump_handshakes(srv);
+        for s in &mut self.sessions {
+            s.inputs.begin_tick(self.tick, self.cfg.tick_hz);
+            while let Some(bytes) = s.xport.try_recv() {
+                s.last_rx_tick = self.tick;
+                if s.link.receive(&bytes).is_err() {
+                    metrics::counter!("session.rejected_total", "reason" => "packet").increment(1);
+                }
+            }
+            while let Some((_, payload)) = s.link.recv() {
+                let mut slice: &[u8] = &payload;
+                let Ok(cmd) = ClientCmd::decode(&mut slice) else {
+                    metrics::counter!("session.rejected_total", "reason" => "decode").increment(1);
+                    continue;
+                };
+                if let Some(rec) = &mut self.recorder {
+                    rec.cmd(self.tick, s.id, &cmd);
+                }
+                if let ClientCmd::Ack { tick } = cmd {
+                    s.baseline.ack(tick);
+                    continue;
+                }
+                s.inputs
+                    .apply(srv, s.actor, cmd, self.cfg.max_casts_per_sec);
+            }
+            s.inputs.end_tick(srv, s.actor);
+        }
+        self.reap(srv);
+    }
+
+    /// Build and send per-client replication for the current server state, then
+    /// advance the replication tick. Call once after `step_authoritative`.
+    pub fn broadcast(&mut self, srv: &mut ServerState) {
+        let tick = self.tick;
+        let now_ms = tick * 1000 / u64::from(self.cfg.tick_hz.max(1));
+        let snap = srv.tick_snapshot_actors(tick);
+        if let Some(rec) = &mut self.recorder {
+            rec.frame(&snap);
+        }
+        let hits = std::mem::take(&mut srv.fx_hits);
+        let toasts = std::mem::take(&mut srv.hud_toasts);
+        let telegraphs = crate::systems::boss::telegraph_reps(srv);
+        let statuses = crate::systems::status::status_reps(srv);
+        for d in srv.drain_destruct_mesh_deltas() {
+            let key = (d.did, d.chunk);
+            for s in &mut self.sessions {
+                s.stale_chunks.insert(key);
+            }
+            self.chunk_meshes.insert(key, d);
+        }
+        let instances = srv.all_destructible_instances();
+        srv.destruct_bootstrap_instances_outstanding = false;
+        let r2 = self.cfg.interest_radius_m * self.cfg.interest_radius_m;
+        let grid = GridIndex::from_items(self.cfg.interest_cell_m, &snap.actors);
+        let reps: HashMap<u32, &ActorRep> = snap.actors.iter().map(|a| (a.id, a)).collect();
+        let budget_bytes = (self.cfg.actor_budget_bytes_per_sec / self.cfg.tick_hz.max(1)) as usize;
+        for s in &mut self.sessions {
+            let Some(pc) = srv.ecs.get(s.actor) else {
+                s.flush(now_ms);
+                continue;
+            };
+            let center = pc.tr.pos;
+            let in_range = |p: [f32; 3]| {
+                let dx = p[0] - center.x;
+                let dz = p[2] - center.z;
+                dx * dx + dz * dz <= r2
+            };
+            s.interest.update(center.into(), &grid);
+            let mut ids = s.interest.members().clone();
+            ids.insert(s.actor.0);
+            let last = s.baseline.last_sent();
+            let cands: Vec<BudgetCandidate> = ids
+                .iter()
+                .filter_map(|id| reps.get(id))
+                .map(|a| BudgetCandidate {
+                    id: a.id,
+                    priority: priority_for(srv, pc, a).score(self.cfg.priority_falloff_m),
+                    bytes: refresh_bytes(last.and_then(|l| l.get(&a.id)), a),
+                    pinned: a.id == s.actor.0,
+                })
+                .collect();
+            let fresh = s.budget.select(budget_bytes, &cands);
+            // Deferred actors hold their last sent state; deferred spawns wait.
+            let cur: ActorSet = cands
+                .iter()
+                .filter_map(|c| {
+                    if fresh.contains(&c.id) {
+                        reps.get(&c.id).map(|a| (*a).clone())
+                    } else {
+                        last.and_then(|l| l.get(&c.id)).cloned()
+                    }
+                    .map(|a| (c.id, a))
+                })
+                .collect();
+            // Delta against the last tick this client acked; loss is repaired by
+            // whichever later delta arrives, so the stream can stay unreliable.
+            let mut delta = s.baseline.build(
+                tick,
+                cur,
+                snap.projectiles
+                    .iter()
+                    .filter(|p| in_range(p.pos))
+                    .cloned()
+                    .collect(),
+                hits.iter().filter(|h| in_range(h.pos)).cloned().collect(),
+            );
+            delta.input_seq = s.inputs.input_seq;
+            s.send(Channel::Unreliable, &delta);
+            s.send(Channel::Unreliable, &hud_status_for(pc, &srv.abilities));
+            // Toasts go only to the client whose actor they are for.
+            let actor = s.actor;
+            for &(_, code) in toasts.iter().filter(|(to, _)| *to == actor) {
+                let toast = HudToastMsg {
+                    v: net_core::snapshot::HUD_TOAST_VERSION,
+                    code,
+                };
+                s.send(Channel::Reliable, &toast);
+            }
+            // Telegraphs stream while active; the clear shares their stream.
+            let shown: Vec<TelegraphRep> = telegraphs
+                .iter()
+                .filter(|t| {
+                    let dx = t.pos[0] - center.x;
+                    let dz = t.pos[2] - center.z;
+                    let r = self.cfg.interest_radius_m + t.radius;
+                    dx * dx + dz * dz <= r * r
+                })
+                .cloned()
+                .collect();
+            if s.telegraphs.due(shown.is_empty(), tick, s.baseline.acked()) {
+                let msg = TelegraphMsg {
+                    v: net_core::snapshot::TELEGRAPH_VERSION,
+                    items: shown,
+                };
+                s.send(Channel::Unreliable, &msg);
+            }
+            let shown: Vec<StatusRep> = statuses
+                .iter()
+                .filter(|st| ids.contains(&st.actor))
+                .copied()
+                .collect();
+            if s.statuses.due(shown.is_empty(), tick, s.baseline.acked()) {
+                let msg = StatusMsg {
+                    v: net_core::snapshot::STATUS_VERSION,
+                    items: shown,
+                };
+                s.send(Channel::Unreliable, &msg);
+            }
+            if let Some(inv) = &pc.inventory
+                && s.inventory_rev != Some(inv.rev)
+            {
+                s.inventory_rev = Some(inv.rev);
+                s.send(
+                    Channel::Reliable,
+                    &crate::systems::inventory::inventory_msg(inv),
+                );
+            }
+            let shown: Vec<LootDropRep> = crate::systems::loot::loot_reps(srv, s.actor)
+                .into_iter()
+                .filter(|d| in_range(d.pos))
+                .collect();
+            if shown != s.loot_shown {
+                s.loot_shown = shown.clone();
+                let msg = LootMsg {
+                    v: net_core::snapshot::LOOT_VERSION,
+                    items: shown,
+                };
+                s.send(Channel::Reliable, &msg);
+            }
+            // Destructibles: instances precede deltas; late joiners get all instances.
+            for d in &instances {
+                if s.sent_destr_instances.insert(d.did) {
+                    s.send(Channel::Reliable, d);
+                }
+            }
+            // Chunk meshes wait until their instance is in range, then the
+            // latest one is sent.
+            let due: Vec<ChunkKey> = s
+                .stale_chunks
+                .iter()
+                .filter(|(did, _)| {
+                    s.sent_destr_instances.contains(did)
+                        && instances.iter().find(|i| i.did == *did).is_none_or(|inst| {
+                            let cx = center.x.clamp(inst.world_min[0], inst.world_max[0]);
+                            let cz = center.z.clamp(inst.world_min[2], inst.world_max[2]);
+                            in_range([cx, center.y, cz])
+                        })
+                })
+                .copied()
+                .collect();
+            for key in due {
+                s.stale_chunks.remove(&key);
+                if let Some(d) = self.chunk_meshes.get(&key) {
+                    s.send(Channel::Reliable, d);
+                }
+            }
+            s.flush(now_ms);
+        }
+        self.tick = self.tick.wrapping_add(1);
+        let autosave_ticks = (self.cfg.autosave_s * self.cfg.tick_hz as f32) as u64;
+        if autosave_ticks > 0 && self.tick.is_multiple_of(autosave_ticks) {
+            self.save_characters(srv);
+        }
+        self.reap(srv);
+    }
+
+    fn reap(&mut self, srv: &mut ServerState) {
+        let idle_ticks = (self.cfg.idle_timeout_s * self.cfg.tick_hz as f32) as u64;
+        let gone: Vec<ClientId> = self
+            .sessions
+            .iter()
+            .filter(|s| {
+                s.disconnected
+                    || (idle_ticks > 0 && self.tick.saturating_sub(s.last_rx_tick) > idle_ticks)
+            })
+            .map(|s| s.id)
+            .collect();
+        for id in gone {
+            self.disconnect(srv, id);
+        }
+    }
+}
+
+/// Per-client command routing: queued sequenced moves and the cast rate
+/// window. Replays drive recorded commands through the same path.
+#[derive(Debug)]
+pub(crate) struct Inputs {
+    /// Sequenced move inputs waiting for a tick: (seq, dx, dz, run).
+    moves: VecDeque<(u32, f32, f32, bool)>,
+    /// Newest `seq` accepted into `moves` (reordered/duplicate inputs are dropped).
+    last_queued_seq: u32,
+    /// Newest `seq` applied to the actor; echoed as `input_seq`.
+    input_seq: u32,
+    cast_window_start: u64,
+    casts_in_window: u32,
+}
+
+impl Inputs {
+    pub(crate) fn new(tick: u64) -> Self {
+        Self {
+            moves: VecDeque::new(),
+            last_queued_seq: 0,
+            input_seq: 0,
+            cast_window_start: tick,
+            casts_in_window: 0,
+        }
+    }
+
+    /// Start a tick: the cast rate window resets once per second of ticks.
+    pub(crate) fn begin_tick(&mut self, tick: u64, tick_hz: u32) {
+        if tick.saturating_sub(self.cast_window_start) >= u64::from(tick_hz.max(1)) {
+            self.cast_window_start = tick;
+            self.casts_in_window = 0;
+        }
+    }
+
+    /// Route one command to `actor`. `Ack` is replication state, not input.
+    pub(crate) fn apply(
+        &mut self,
+        srv: &mut ServerState,
+        actor: ActorId,
+        cmd: ClientCmd,
+        max_casts_per_sec: u32,
+    ) {
+        match cmd {
+            ClientCmd::Move { dx, dz, run, seq } => {
+                let Some((dx, dz)) = intent_axes(dx, dz) else {
+                    return;
+                };
+                if seq == 0 {
+                    srv.apply_move_intent_for(actor, dx, dz, run != 0);
+                } else if seq_newer(seq, self.last_queued_seq) {
+                    self.last_queued_seq = seq;
+                    self.moves.push_back((seq, dx, dz, run != 0));
+                    if self.moves.len() > MAX_QUEUED_INPUTS {
+                        self.moves.pop_front();
+                        metrics::counter!("session.rejected_total", "reason" => "input_overflow")
+                            .increment(1);
+                    }
+                }
+            }
+            ClientCmd::Aim { yaw } => {
+                if !yaw.is_finite() {
+                    metrics::counter!("session.rejected_total", "reason" => "non_finite")
+                        .increment(1);
+                    return;
+                }
+                srv.apply_aim_intent_for(actor, yaw);
+            }
+            ClientCmd::Dodge { dx, dz } => {
+                if let Some((dx, dz)) = intent_axes(dx, dz) {
+                    srv.apply_dodge_intent_for(actor, dx, dz);
+                }
+            }
+            // Rate limit only casts, attacks and item use; Move/Aim are intents (state).
+            ClientCmd::Cast { ability_id, target } => {
+                if self.casts_in_window >= max_casts_per_sec {
+                    metrics::counter!("session.rejected_total", "reason" => "rate").increment(1);
+                    return;
+                }
+                self.casts_in_window += 1;
+                if let Err(e) = srv.enqueue_ability_cast(actor, &ability_id, target) {
+                    metrics::counter!("session.rejected_total", "reason" => e.as_str())
+                        .increment(1);
+                }
+            }
+            ClientCmd::Attack { weapon_id, target } => {
+                if self.casts_in_window >= max_casts_per_sec {
+                    metrics::counter!("session.rejected_total", "reason" => "rate").increment(1);
+                    return;
+                }
+                self.casts_in_window += 1;
+                if let Err(e) = srv.enqueue_weapon_attack(actor, &weapon_id, target) {
+                    metrics::counter!("session.rejected_total", "reason" => e.as_str())
+                        .increment(1);
+                }
+            }
+            ClientCmd::Use { item_id } => {
+                if self.casts_in_window >= max_casts_per_sec {
+                    metrics::counter!("session.rejected_total", "reason" => "rate").increment(1);
+                    return;
+                }
+                self.casts_in_window += 1;
+                if let Err(e) = srv.use_item(actor, &item_id) {
+                    metrics::counter!("session.rejected_total", "reason" => e.as_str())
+                        .increment(1);
+                }
+            }
+            ClientCmd::Equip { item_id } => {
+                if let Err(e) = srv.equip_item(actor, &item_id) {
+                    metrics::counter!("session.rejected_total", "reason" => e.as_str())
+                        .increment(1);
+                }
+            }
+            ClientCmd::Drop { item_id, count } => {
+                if let Err(e) = srv.drop_item(actor, &item_id, u32::from(count)) {
+                    metrics::counter!("session.rejected_total", "reason" => e.as_str())
+                        .increment(1);
+                }
+            }
+            ClientCmd::PickUp { drop_id } => {
+                if let Err(e) = srv.pick_up(actor, drop_id) {
+                    metrics::counter!("session.rejected_total", "reason" => e.as_str())
+                        .increment(1);
+                }
+            }
+            ClientCmd::Ack { .. } => {}
+        }
+    }
+
+    /// Finish a tick: apply at most one queued move. One input per tick keeps
+    /// server steps aligned with the client's predicted steps even when inputs
+    /// arrive in bursts.
+    pub(crate) fn end_tick(&mut self, srv: &mut ServerState, actor: ActorId) {
+        if let Some((seq, dx, dz, run)) = self.moves.pop_front() {
+            srv.apply_move_intent_for(actor, dx, dz, run);
+            self.input_seq = seq;
+        }
+    }
+}
+
+/// Movement axes from the wire, clamped to `[-1, 1]`. Non-finite axes are
+/// rejected: a NaN would otherwise reach the actor's position.
+fn intent_axes(dx: f32, dz: f32) -> Option<(f32, f32)> {
+    if !(dx.is_finite() && dz.is_finite()) {
+        metrics::counter!("session.rejected_total", "reason" => "non_finite").increment(1);
+        return None;
+    }
+    Some((dx.clamp(-1.0, 1.0), dz.clamp(-1.0, 1.0)))
+}
+
+/// Spawn a client's PC; shared by `admit` and replays.
+pub(crate) fn spawn_client_pc(srv: &mut ServerState, pos: Vec3, name: Option<String>) -> ActorId {
+    let actor = srv.spawn_pc(pos);
+    if let Some(n) = &name {
+        srv.factions.join(actor, n);
+    }
+    if let Some(c) = srv.ecs.get_mut(actor) {
+        c.name = name;
+    }
+    actor
+}
+
+/// Despawn a client's PC; shared by `disconnect` and replays. A named
+/// character keeps its reputation for when it joins again.
+pub(crate) fn despawn_client_pc(srv: &mut ServerState, actor: ActorId) {
+    let name = srv.ecs.get(actor).and_then(|c| c.name.clone());
+    srv.factions.leave(actor, name.as_deref());
+    let mut cmd = crate::ecs::CmdBuf::default();
+    cmd.despawns.push(actor);
+    srv.ecs.apply_cmds(&mut cmd);
+    if srv.pc_actor == Some(actor) {
+        srv.pc_actor = None;
+    }
+}
+
+/// Write `s`'s character to `store`; false if it has none or saving failed.
+fn save_character(
+    store: &CharacterStore,
+    zone: Option<&str>,
+    srv: &ServerState,
+    s: &mut Session,
+) -> bool {
+    let Some(rec) = s.character.as_mut() else {
+        return false;
+    };
+    if !srv.capture_character(s.actor, zone, rec) {
+        return false;
+    }
+    match store.save(rec) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("session: saving character '{}' failed: {e:#}", rec.name);
+            false
+        }
+    }
+}
+
+/// Wrapping comparison for input sequence numbers.
+fn seq_newer(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) > 0
+}
+
+/// Replication priority of actor `a` for the client controlling `pc`.
+fn priority_for(srv: &ServerState, pc: &Components, a: &ActorRep) -> PriorityInputs {
+    let dx = a.pos[0] - pc.tr.pos.x;
+    let dz = a.pos[2] - pc.tr.pos.z;
+    let dist_m = (dx * dx + dz * dz).sqrt();
+    let Some(c) = srv.ecs.get(ActorId(a.id)) else {
+        return PriorityInputs {
+            dist_m,
+            threat: 0.0,
+            relevance: 1.0,
+        };
+    };
+    // Hostiles matter more, and twice as much once they could aggro the PC.
+    let threat = if srv.factions.hostile(c.id, c.faction, pc.id, pc.faction) {
+        let aggro = c.aggro.map(|r| r.m).unwrap_or(0.0);
+        if dist_m <= aggro { 2.0 } else { 1.0 }
+    } else {
+        0.0
+    };
+    let relevance = if !a.alive {
+        0.25
+    } else if c.kind == ActorKind::Boss || a.unique != 0 {
+        3.0
+    } else if c.faction == crate::actor::Faction::PC {
+        2.0
+    } else {
+        1.0
+    };
+    PriorityInputs {
+        dist_m,
+        threat,
+        relevance,
+    }
+}
+
+/// HUD status for `pc`. Cooldown slots follow its spellbook order, one per
+/// spell the ability table can resolve (the action bar).
+pub fn hud_status_for(pc: &Components, abilities: &AbilityDb) -> HudStatusMsg {
+    let ms = |s: f32| (s * 1000.0).clamp(0.0, u16::MAX as f32) as u16;
+    let cd = |sid: SpellId| {
+        pc.cooldowns
+            .as_ref()
+            .and_then(|c| c.per_spell.get(&sid).copied())
+            .unwrap_or(0.0)
+    };
+    HudStatusMsg {
+        v: net_core::snapshot::HUD_STATUS_VERSION,
+        mana: pc
+            .pool
+            .map(|p| p.mana)
+            .unwrap_or(0)
+            .clamp(0, u16::MAX as i32) as u16,
+        mana_max: pc
+            .pool
+            .map(|p| p.max)
+            .unwrap_or(0)
+            .clamp(0, u16::MAX as i32) as u16,
+        gcd_ms: ms(pc.cooldowns.as_ref().map(|c| c.gcd_ready).unwrap_or(0.0)),
+        spell_cds: pc
+            .spellbook
+            .iter()
+            .flat_map(|b| b.known.iter().copied())
+            .filter(|&sid| abilities.get(sid).is_some())
+            .enumerate()
+            .filter_map(|(slot, sid)| Some((u8::try_from(slot).ok()?, ms(cd(sid)))))
+            .collect(),
+    }
+}
+
+/// Actor delta for the in-process demo's single client: actors and projectiles
+/// within `radius_m` of `center`, diffed against the last tick the client
+/// acked in `baseline`. Until it acks, and whenever `baseline` is a fresh
+/// tracker (a new renderer), that is a keyframe. Drains `srv.fx_hits`.
+pub fn local_actor_delta(
+    srv: &mut ServerState,
+    baseline: &mut BaselineTracker,
+    tick: u64,
+    center: Vec3,
+    radius_m: f32,
+) -> ActorSnapshotDelta {
+    let snap = srv.tick_snapshot_actors(tick);
+    let r2 = radius_m * radius_m;
+    let in_range = |p: [f32; 3]| {
+        let dx = p[0] - center.x;
+        let dz = p[2] - center.z;
+        dx * dx + dz * dz <= r2
+    };
+    let cur: ActorSet = snap
+        .actors
+        .into_iter()
+        .filter(|a| in_range(a.pos))
+        .map(|a| (a.id, a))
+        .collect();
+    let projectiles = snap
+        .projectiles
+        .into_iter()
+        .filter(|p| in_range(p.pos))
+        .collect();
+    baseline.build(tick, cur, projectiles, std::mem::take(&mut srv.fx_hits))
+}
@@ -0,0 +1,395 @@
+//! Scripted boss encounters (`data/bosses/*.toml`).
+//!
+//! Bosses whose archetype names a script carry a [`BossRun`]. `boss_script_tick`
+//! runs right after `threat_update` each tick and:
+//! - starts the encounter once the boss has a threat target, and resets it
+//!   (phase, timers, telegraphs, enrage) when the table empties, the boss
+//!   leashes or dies; a boss that is still alive also heals to full and
+//!   despawns the adds it spawned, so the next pull starts from scratch;
+//! - moves to the next phase when HP crosses its threshold, spawning the
+//!   phase's adds and casting its rotation from the top;
+//! - fires one-shot triggers and the enrage timer;
+//! - casts rotation abilities as telegraphs that resolve into `DamageEvent`s
+//!   against hostiles inside the shape once the wind-up ends, applying the
+//!   ability's status (if any) to each of them.
+//!
+//! Active telegraphs replicate as `net_core::snapshot::TelegraphMsg` built by
+//! [`telegraph_reps`].
+
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+use data_runtime::specs::boss_scripts::{AddSpawn, BossScript, TelegraphAnchor, TelegraphShape};
+use glam::{Vec2, Vec3};
+use net_core::snapshot::{TELEGRAPH_CIRCLE, TELEGRAPH_CONE, TelegraphRep};
+
+use crate::ServerState;
+use crate::actor::{ActorId, Faction};
+use crate::archetype::ArchetypeOverrides;
+use crate::ecs::schedule::{Ctx, DamageEvent};
+
+/// A telegraphed ability winding up on the ground.
+#[derive(Clone, Debug)]
+pub struct Telegraph {
+    /// Unique per boss.
+    pub id: u32,
+    pub ability: String,
+    pub shape: TelegraphShape,
+    pub center: Vec3,
+    pub yaw: f32,
+    pub radius: f32,
+    pub half_angle: f32,
+    pub total_s: f32,
+    pub left_s: f32,
+    pub damage: i32,
+    /// Status applied to everyone hit.
+    pub status: Option<String>,
+}
+
+impl Telegraph {
+    /// Whether a body of radius `r` at `p` is caught by the shape.
+    pub fn contains(&self, p: Vec3, r: f32) -> bool {
+        let d = Vec2::new(p.x - self.center.x, p.z - self.center.z);
+        let len = d.length();
+        if len > self.radius + r {
+            return false;
+        }
+        match self.shape {
+            TelegraphShape::Circle => true,
+            TelegraphShape::Cone => {
+                let fwd = Vec2::new(self.yaw.sin(), self.yaw.cos());
+                len <= r || d.dot(fwd) >= len * self.half_angle.cos()
+            }
+        }
+    }
+}
+
+/// Per-boss script state.
+#[derive(Clone, Debug)]
+pub struct BossRun {
+    pub script: Arc<BossScript>,
+    /// Index into `script.phases`.
+    pub phase: usize,
+    /// In combat; the script only runs while engaged.
+    pub engaged: bool,
+    /// Seconds since the pull.
+    pub elapsed_s: f32,
+    pub enraged: bool,
+    pub telegraphs: Vec<Telegraph>,
+    /// Adds spawned by phases and triggers since the pull.
+    pub adds: Vec<ActorId>,
+    next_cast_s: f32,
+    rotation_idx: usize,
+    fired: Vec<bool>,
+    /// Melee damage before enrage scaling.
+    base_melee: Option<i32>,
+    next_id: u32,
+}
+
+impl BossRun {
+    pub fn new(script: Arc<BossScript>) -> Self {
+        let fired = vec![false; script.triggers.len()];
+        Self {
+            script,
+            phase: 0,
+            engaged: false,
+            elapsed_s: 0.0,
+            enraged: false,
+            telegraphs: Vec::new(),
+            adds: Vec::new(),
+            next_cast_s: 0.0,
+            rotation_idx: 0,
+            fired,
+            base_melee: None,
+            next_id: 0,
+        }
+    }
+
+    pub fn phase_name(&self) -> &str {
+        self.script
+            .phases
+            .get(self.phase)
+            .map_or("", |p| p.name.as_str())
+    }
+
+    fn damage_mul(&self) -> f32 {
+        if self.enraged {
+            self.script.enrage_damage_mul
+        } else {
+            1.0
+        }
+    }
+
+    fn interval_s(&self) -> f32 {
+        let base = self
+            .script
+            .phases
+            .get(self.phase)
+            .map_or(1.0, |p| p.interval_s);
+        if self.enraged {
+            base * self.script.enrage_interval_mul
+        } else {
+            base
+        }
+    }
+}
+
+/// Per-tick boss scripting; see the module docs.
+pub fn boss_script_tick(srv: &mut ServerState, ctx: &mut Ctx) {
+    let dt = ctx.dt;
+    let ids: Vec<ActorId> = srv
+        .ecs
+        .iter()
+        .filter(|a| a.boss.is_some())
+        .map(|a| a.id)
+        .collect();
+    for id in ids {
+        let target = crate::systems::threat::target_of(srv, id);
+        let Some(c) = srv.ecs.get_mut(id) else {
+            continue;
+        };
+        let Some(mut run) = c.boss.take() else {
+            continue;
+        };
+        let alive = c.hp.alive();
+        let pos = c.tr.pos;
+        let hp_pct = c.hp.hp as f32 * 100.0 / c.hp.max.max(1) as f32;
+        let in_combat = alive && target.is_some() && !c.is_leash_returning();
+        if run.engaged && !in_combat {
+            reset(srv, ctx, id, &mut run);
+        } else if !run.engaged && in_combat {
+            run.engaged = true;
+            run.next_cast_s = run.interval_s();
+            metrics::counter!("boss.pulls_total").increment(1);
+        }
+        if run.engaged {
+            step(srv, ctx, id, pos, hp_pct, target.map(|t| t.1), &mut run, dt);
+        }
+        if let Some(c) = srv.ecs.get_mut(id) {
+            c.boss = Some(run);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn step(
+    srv: &mut ServerState,
+    ctx: &mut Ctx,
+    id: ActorId,
+    pos: Vec3,
+    hp_pct: f32,
+    target: Option<Vec3>,
+    run: &mut BossRun,
+    dt: f32,
+) {
+    let script = Arc::clone(&run.script);
+    run.elapsed_s += dt;
+
+    // Wind-ups that finished this tick hit before anything new is cast.
+    for t in &mut run.telegraphs {
+        t.left_s -= dt;
+    }
+    let (done, live): (Vec<_>, Vec<_>) = std::mem::take(&mut run.telegraphs)
+        .into_iter()
+        .partition(|t| t.left_s <= 0.0);
+    run.telegraphs = live;
+    for t in &done {
+        resolve(srv, ctx, id, t, run.damage_mul());
+    }
+
+    let want = script.phase_for(hp_pct);
+    while run.phase < want {
+        run.phase += 1;
+        run.rotation_idx = 0;
+        run.next_cast_s = 0.0;
+        let phase = &script.phases[run.phase];
+        log::info!(
+            "server: boss {:?} enters phase '{}' at {hp_pct:.0}% HP",
+            id,
+            phase.name
+        );
+        metrics::counter!("boss.phase_transitions_total").increment(1);
+        spawn_adds(srv, id, pos, &phase.adds, &mut run.adds);
+    }
+
+    for (i, trig) in script.triggers.iter().enumerate() {
+        if run.fired[i]
+            || trig.hp_pct.is_some_and(|p| hp_pct > p)
+            || trig.after_s.is_some_and(|s| run.elapsed_s < s)
+        {
+            continue;
+        }
+        run.fired[i] = true;
+        if let Some(a) = &trig.cast {
+            cast(srv, id, pos, target, run, a);
+        }
+        spawn_adds(srv, id, pos, &trig.adds, &mut run.adds);
+    }
+
+    if !run.enraged && script.enrage_s > 0.0 && run.elapsed_s >= script.enrage_s {
+        run.enraged = true;
+        if let Some(m) = srv.ecs.get_mut(id).and_then(|c| c.melee.as_mut()) {
+            run.base_melee = Some(m.damage);
+            m.damage = (m.damage as f32 * script.enrage_damage_mul).round() as i32;
+        }
+        log::info!("server: boss {:?} enraged after {:.0}s", id, run.elapsed_s);
+        metrics::counter!("boss.enrages_total").increment(1);
+    }
+
+    run.next_cast_s -= dt;
+    if run.next_cast_s <= 0.0 {
+        let rotation = &script.phases[run.phase].rotation;
+        let ability = rotation[run.rotation_idx % rotation.len()].clone();
+        run.rotation_idx = (run.rotation_idx + 1) % rotation.len();
+        run.next_cast_s += run.interval_s();
+        cast(srv, id, pos, target, run, &ability);
+    }
+}
+
+/// Back to the pre-pull state: first phase, no telegraphs, no enrage. A
+/// living boss (a wipe or leash, not a kill) also heals to full and despawns
+/// its adds.
+fn reset(srv: &mut ServerState, ctx: &mut Ctx, id: ActorId, run: &mut BossRun) {
+    if let (Some(dmg), Some(m)) = (
+        run.base_melee.take(),
+        srv.ecs.get_mut(id).and_then(|c| c.melee.as_mut()),
+    ) {
+        m.damage = dmg;
+    }
+    if let Some(c) = srv.ecs.get_mut(id)
+        && c.hp.alive()
+    {
+        c.hp.hp = c.hp.max;
+        ctx.cmd.despawns.append(&mut run.adds);
+    }
+    *run = BossRun {
+        next_id: run.next_id,
+        ..BossRun::new(Arc::clone(&run.script))
+    };
+    metrics::counter!("boss.resets_total").increment(1);
+}
+
+/// Start the wind-up for `ability`, facing the boss toward its target.
+fn cast(
+    srv: &mut ServerState,
+    id: ActorId,
+    pos: Vec3,
+    target: Option<Vec3>,
+    run: &mut BossRun,
+    ability: &str,
+) {
+    let Some(a) = run.script.abilities.get(ability) else {
+        return;
+    };
+    let face = target
+        .map(|t| t - pos)
+        .filter(|d| d.x * d.x + d.z * d.z > 1e-6);
+    let yaw = match face {
+        Some(d) => d.x.atan2(d.z),
+        None => srv.ecs.get(id).map_or(0.0, |c| c.tr.yaw),
+    };
+    if let Some(c) = srv.ecs.get_mut(id) {
+        c.tr.yaw = yaw;
+    }
+    let center = match a.at {
+        TelegraphAnchor::Caster => pos,
+        TelegraphAnchor::Target => target.unwrap_or(pos),
+    };
+    run.next_id = run.next_id.wrapping_add(1);
+    run.telegraphs.push(Telegraph {
+        id: run.next_id,
+        ability: ability.to_string(),
+        shape: a.shape,
+        center,
+        yaw,
+        radius: a.radius_m,
+        half_angle: (a.angle_deg * 0.5).to_radians(),
+        total_s: a.windup_s,
+        left_s: a.windup_s,
+        damage: a.damage,
+        status: a.status.clone(),
+    });
+    metrics::counter!("boss.telegraphs_total").increment(1);
+}
+
+fn resolve(srv: &mut ServerState, ctx: &mut Ctx, id: ActorId, t: &Telegraph, mul: f32) {
+    let Some(faction) = srv.ecs.get(id).map(|c| c.faction) else {
+        return;
+    };
+    let amount = (t.damage as f32 * mul).round() as i32;
+    let hit: Vec<ActorId> = srv
+        .ecs
+        .iter()
+        .filter(|a| {
+            a.id != id
+                && a.hp.alive()
+                && srv.factions.hostile(id, faction, a.id, a.faction)
+                && t.contains(a.tr.pos, a.tr.radius)
+        })
+        .map(|a| a.id)
+        .collect();
+    for &dst in &hit {
+        ctx.dmg.push(DamageEvent {
+            src: Some(id),
+            dst,
+            amount,
+            damage_type: None,
+        });
+    }
+    if let Some(status) = &t.status {
+        for dst in hit {
+            srv.apply_status(dst, status, Some(id));
+        }
+    }
+}
+
+/// Spawn adds on rings around the boss, on the boss's side, recording their
+/// ids in `spawned`.
+fn spawn_adds(
+    srv: &mut ServerState,
+    id: ActorId,
+    pos: Vec3,
+    adds: &[AddSpawn],
+    spawned: &mut Vec<ActorId>,
+) {
+    let faction = srv.ecs.get(id).map_or(Faction::UNDEAD, |c| c.faction);
+    for add in adds {
+        let ov = ArchetypeOverrides {
+            faction: Some(faction),
+            ..Default::default()
+        };
+        for k in 0..add.count {
+            let a = k as f32 / add.count as f32 * TAU;
+            let p = pos + Vec3::new(a.cos(), 0.0, a.sin()) * add.radius_m;
+            if let Some(a) = srv.spawn_archetype(&add.archetype, p, &ov) {
+                spawned.push(a);
+                metrics::counter!("boss.adds_spawned_total").increment(1);
+            }
+        }
+    }
+}
+
+/// Net records for every active telegraph.
+pub fn telegraph_reps(srv: &ServerState) -> Vec<TelegraphRep> {
+    let ms = |s: f32| (s.max(0.0) * 1000.0).round().min(f32::from(u16::MAX)) as u16;
+    srv.ecs
+        .iter()
+        .filter_map(|c| c.boss.as_ref().map(|b| (c.id, b)))
+        .flat_map(|(owner, b)| {
+            b.telegraphs.iter().map(move |t| TelegraphRep {
+                id: t.id,
+                owner: owner.0,
+                shape: match t.shape {
+                    TelegraphShape::Circle => TELEGRAPH_CIRCLE,
+                    TelegraphShape::Cone => TELEGRAPH_CONE,
+                },
+                pos: t.center.into(),
+                yaw: t.yaw,
+                radius: t.radius,
+                half_angle: t.half_angle,
+                total_ms: ms(t.total_s),
+                remaining_ms: ms(t.left_s),
+            })
+        })
+        .collect()
+}
@@ -1,6 +1,7 @@
 //! Destructible systems: core voxel carve/mesh/collider helpers and ECS glue.
 
 use crate::ServerState;
+use crate::destructible::state::{DestructibleId, Scar};
 use crate::destructible::{config::DestructibleConfig, queue::ChunkQueue};
 use crate::ecs::schedule::Ctx;
 use ecs_core::components::{CarveRequest, ChunkDirty, ChunkMesh, MeshCpu};
@@ -8,14 +9,15 @@ use glam::UVec3;
 use net_core::snapshot::ChunkMeshDelta;
 use voxel_proxy::VoxelGrid;
 
-/// Apply a carve request to the grid and enqueue dirty chunks.
+/// Apply a carve request to the grid and enqueue dirty chunks. Returns the
+/// voxels it cleared.
 pub fn voxel_carve(
     grid: &mut VoxelGrid,
     req: &CarveRequest,
     cfg: &DestructibleConfig,
     dirty: &mut ChunkDirty,
-) -> usize {
-    let _out = crate::destructible::carve_and_spawn_debris(
+) -> Vec<UVec3> {
+    let out = crate::destructible::carve_and_spawn_debris(
         grid,
         req.center_m,
         core_units::Length::meters(req.radius_m),
@@ -23,10 +25,8 @@ pub fn voxel_carve(
         req.impact_id as u64,
         cfg.max_debris,
     );
-    let enq = grid.pop_dirty_chunks(usize::MAX);
-    let n = enq.len();
-    dirty.0.extend(enq);
-    n
+    dirty.0.extend(grid.pop_dirty_chunks(usize::MAX));
+    out.cleared
 }
 
 /// Greedy-mesh up to `budget` chunks from `dirty` into `out_mesh`.
@@ -123,12 +123,29 @@ pub fn destructible_apply_carves(srv: &mut ServerState, ctx: &mut Ctx) {
             }
             req.center_m = os.as_dvec3();
             req.radius_m *= avg as f64;
-            let _ = voxel_carve(
+            let before = proxy.dirty.0.len();
+            let cleared = voxel_carve(
                 &mut proxy.grid,
                 &req,
                 &srv.destruct_registry.cfg,
                 &mut proxy.dirty,
             );
+            if !cleared.is_empty() {
+                let grid = &proxy.grid;
+                proxy.scars.push_back(Scar {
+                    age_s: 0.0,
+                    voxels: cleared
+                        .iter()
+                        .map(|v| grid.index(v.x, v.y, v.z) as u32)
+                        .collect(),
+                });
+            }
+            // Carves can open passages; re-rasterize the touched chunks for nav.
+            mark_nav_dirty(
+                &mut srv.nav,
+                &proxy.grid,
+                proxy.dirty.0.get(before..).unwrap_or_default(),
+            );
             metrics::counter!("destruct.carves_applied_total").increment(1);
         } else {
             keep.push(req);
@@ -137,6 +154,105 @@ pub fn destructible_apply_carves(srv: &mut ServerState, ctx: &mut Ctx) {
     ctx.carves = keep;
 }
 
+/// Re-rasterize `chunks` of `grid` for navigation after their occupancy changed.
+pub(crate) fn mark_nav_dirty(nav: &mut crate::nav::NavGrid, grid: &VoxelGrid, chunks: &[UVec3]) {
+    for c in chunks {
+        let b = collision_static::chunks::chunk_world_aabb(grid, *c);
+        nav.mark_dirty(crate::nav::NavRect {
+            min: glam::Vec2::new(b.min.x, b.min.z),
+            max: glam::Vec2::new(b.max.x, b.max.z),
+        });
+    }
+}
+
+/// Regrowth keeps this much room (meters, plus half a voxel) around live actors.
+pub const REGROW_CLEARANCE_M: f32 = 1.0;
+
+/// Age every scar by `ctx.dt` and, under the registry's `RegrowPolicy`, refill
+/// up to `voxels_per_s` carved voxels per second from the oldest ripe scars
+/// (proxies in id order). A voxel stays empty while a live actor stands
+/// within `REGROW_CLEARANCE_M` of it, so nobody is sealed into a wall.
+pub fn destructible_regrow(srv: &mut ServerState, ctx: &Ctx) {
+    let reg = &mut srv.destruct_registry;
+    for proxy in reg.proxies.values_mut() {
+        for scar in proxy.scars.iter_mut() {
+            scar.age_s += ctx.dt;
+        }
+    }
+    let Some(after_s) = reg.regrow.after_s else {
+        return;
+    };
+    reg.regrow_carry += reg.regrow.voxels_per_s.max(0.0) * ctx.dt;
+    let mut budget = reg.regrow_carry.floor() as usize;
+    reg.regrow_carry -= budget as f32;
+    if budget == 0 {
+        return;
+    }
+    let mut dids: Vec<DestructibleId> = reg
+        .proxies
+        .iter()
+        .filter(|(_, p)| p.scars.front().is_some_and(|s| s.age_s >= after_s))
+        .map(|(did, _)| *did)
+        .collect();
+    if dids.is_empty() {
+        // Nothing is ripe; don't bank budget for later.
+        reg.regrow_carry = 0.0;
+        return;
+    }
+    dids.sort_unstable_by_key(|d| d.0);
+    let actors: Vec<glam::Vec3> = srv
+        .ecs
+        .iter()
+        .filter(|a| a.hp.alive() && a.projectile.is_none())
+        .map(|a| a.tr.pos)
+        .collect();
+    let mut regrown = 0usize;
+    for did in dids {
+        if budget == 0 {
+            break;
+        }
+        let Some(proxy) = reg.proxies.get_mut(&did) else {
+            continue;
+        };
+        // Actors in the grid's frame, as `destructible_apply_carves` maps carve centers.
+        let near: Vec<glam::DVec3> = actors
+            .iter()
+            .map(|p| proxy.object_from_world.transform_point3(*p).as_dvec3())
+            .collect();
+        let grid = &mut proxy.grid;
+        let vm = grid.voxel_m().0;
+        let clear2 = (REGROW_CLEARANCE_M as f64 + 0.5 * vm).powi(2);
+        for scar in proxy.scars.iter_mut() {
+            if budget == 0 || scar.age_s < after_s {
+                break;
+            }
+            scar.voxels.retain(|&i| {
+                if budget == 0 {
+                    return true;
+                }
+                let Some(v) = grid.coords(i as usize) else {
+                    return false;
+                };
+                let center = grid.origin_m() + (v.as_dvec3() + glam::DVec3::splat(0.5)) * vm;
+                if near.iter().any(|p| p.distance_squared(center) < clear2) {
+                    return true;
+                }
+                grid.set(v.x, v.y, v.z, true);
+                budget -= 1;
+                regrown += 1;
+                false
+            });
+        }
+        proxy.scars.retain(|s| !s.voxels.is_empty());
+        let chunks = proxy.grid.pop_dirty_chunks(usize::MAX);
+        mark_nav_dirty(&mut srv.nav, &proxy.grid, &chunks);
+        proxy.dirty.0.extend(chunks);
+    }
+    if regrown > 0 {
+        metrics::counter!("destruct.voxels_regrown_total").increment(regrown as u64);
+    }
+}
+
 /// Mesh a budgeted number of dirty chunks per proxy, updating CPU mesh maps and
 /// enqueueing `ChunkMeshDelta` for replication. Deterministic order across runs.
 pub fn destructible_remesh_budgeted(srv: &mut ServerState) {
@@ -0,0 +1,113 @@
+//! Dodge (`ClientCmd::Dodge`): a short authoritative dash with i-frames.
+//!
+//! `ServerState::apply_dodge_intent_for` records the request and
+//! `dodge_tick` runs first in the schedule:
+//! - a request starts a dash when the actor is alive, not incapacitated, not
+//!   already dodging and can pay `DodgeSpec::stamina_cost` from its
+//!   `ResourcePool`; the dash follows the requested direction, or the
+//!   actor's facing when it is zero;
+//! - dashing actors move `distance_m` over `duration_s`, sliding along
+//!   `ServerState::static_colliders` (`collision_static::resolve_slide`) in
+//!   sub-steps short enough not to tunnel through thin walls, and ignore move
+//!   intents meanwhile;
+//! - for the first `iframes_s` the actor is `invulnerable`: projectiles pass
+//!   through it and melee swings miss.
+//!
+//! The dash replicates as `ACTOR_STATE_DODGING` in `ActorRep::state`, which
+//! clients use to pick the dodge animation.
+
+use collision_static::{Capsule, StaticIndex};
+use glam::Vec3;
+
+use crate::ServerState;
+use crate::actor::ActorId;
+use crate::ecs::{Dodge, IntentDodge};
+
+/// Height of the capsule swept against static colliders, from the actor's
+/// position up.
+const CAPSULE_HEIGHT_M: f32 = 1.2;
+const SLIDE_ITERS: u32 = 4;
+
+impl ServerState {
+    /// Request a dodge for `id` along (`dx`, `dz`), resolved next tick.
+    pub fn apply_dodge_intent_for(&mut self, id: ActorId, dx: f32, dz: f32) {
+        if let Some(c) = self.ecs.get_mut(id) {
+            c.intent_dodge = Some(IntentDodge { dx, dz });
+        }
+    }
+}
+
+/// Start requested dodges and advance active ones; see the module docs.
+pub fn dodge_tick(srv: &mut ServerState, dt: f32) {
+    let spec = srv.specs.dodge;
+    for c in srv.ecs.iter_mut() {
+        let Some(req) = c.intent_dodge.take() else {
+            continue;
+        };
+        let reject = if !c.hp.alive() {
+            Some("dead")
+        } else if c.incapacitated() {
+            Some("incapacitated")
+        } else if c.dodge.is_some() {
+            Some("dodging")
+        } else if c.pool.is_none_or(|p| p.stamina < spec.stamina_cost) {
+            Some("stamina")
+        } else {
+            None
+        };
+        if let Some(reason) = reject {
+            metrics::counter!("dodge.rejected_total", "reason" => reason).increment(1);
+            continue;
+        }
+        if let Some(p) = c.pool.as_mut() {
+            p.stamina -= spec.stamina_cost;
+        }
+        let facing = Vec3::new(c.tr.yaw.sin(), 0.0, c.tr.yaw.cos());
+        c.dodge = Some(Dodge {
+            dir: Vec3::new(req.dx, 0.0, req.dz)
+                .try_normalize()
+                .unwrap_or(facing),
+            speed_mps: spec.distance_m / spec.duration_s.max(1e-3),
+            remaining_s: spec.duration_s,
+            iframes_s: spec.iframes_s,
+        });
+        metrics::counter!("dodge.started_total").increment(1);
+    }
+    let statics = srv.static_colliders.as_ref();
+    for c in srv.ecs.iter_mut() {
+        let Some(mut d) = c.dodge else {
+            continue;
+        };
+        if !c.hp.alive() {
+            c.dodge = None;
+            continue;
+        }
+        let step = dt.min(d.remaining_s.max(0.0));
+        c.tr.pos = slide(c.tr.pos, d.dir * d.speed_mps * step, c.tr.radius, statics);
+        d.remaining_s -= dt;
+        d.iframes_s -= dt;
+        c.dodge = (d.remaining_s > 0.0 || d.iframes_s > 0.0).then_some(d);
+    }
+}
+
+/// `pos` moved by `delta` on XZ, sliding along `statics` in sub-steps of at
+/// most half the actor's radius.
+fn slide(pos: Vec3, delta: Vec3, radius: f32, statics: Option<&StaticIndex>) -> Vec3 {
+    let Some(idx) = statics else {
+        return pos + delta;
+    };
+    let max_step = (radius * 0.5).max(0.05);
+    let n = (delta.length() / max_step).ceil().max(1.0);
+    let mut p = pos;
+    for _ in 0..n as u32 {
+        let try_pos = p + delta / n;
+        let cap = Capsule {
+            p0: try_pos,
+            p1: try_pos + Vec3::Y * CAPSULE_HEIGHT_M,
+            radius,
+        };
+        let r = collision_static::resolve_slide(p, try_pos, &cap, idx, 0.0, SLIDE_ITERS);
+        p = Vec3::new(r.x, pos.y, r.z);
+    }
+    p
+}
@@ -0,0 +1,246 @@
+//! Inventory and equipment (`data/items/*.json`).
+//!
+//! Archetypes with `bag_slots` carry an `Inventory`: up to that many stacks of
+//! items, each stack capped at the item's `max_stack`. `ClientCmd::Equip`,
+//! `Use` and `Drop` land here and apply at once:
+//! - equipping wears a carried weapon or armor item in its slot, replacing
+//!   whatever was there; equipping a worn item takes it off. Worn items stay
+//!   in the bag. The slot's modifiers are cached as `EquipFx`, which feeds
+//!   `Components::armor_class` and weapon attack rolls (`systems::weapons`);
+//!   a worn weapon can attack even if the archetype does not know it;
+//! - using a consumable spends one unit to heal (rolled with
+//!   `ServerState::combat_rng`, and noted by fighting NPCs as healing
+//!   threat), restore mana and/or apply its status to the user.
+//!   Incapacitated actors can't use items;
+//! - dropping moves units from the bag to the ground at the actor's feet, as
+//!   a drop only they can pick up again (`systems::loot`); a worn item dropped
+//!   entirely comes off.
+//!
+//! Every change bumps `Inventory::rev`; the session sends the owner an
+//! `InventoryMsg` ([`inventory_msg`]) whenever it moved.
+
+use data_runtime::specs::items::{EquipSlot, ItemKind, ItemSpecDb};
+use net_core::snapshot::{
+    INVENTORY_VERSION, InventoryItemRep, InventoryMsg, WORN_BODY, WORN_MAIN_HAND, WORN_NONE,
+    WORN_OFF_HAND,
+};
+use sim_core::rules::dice::Dice;
+
+use crate::ServerState;
+use crate::actor::ActorId;
+use crate::ecs::{EquipFx, Inventory, ItemStack};
+
+/// Why an item command was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemReject {
+    UnknownItem,
+    NotCarried,
+    NotEquippable,
+    NotUsable,
+    Incapacitated,
+    NoActor,
+    /// No such drop, or it belongs to someone else.
+    NoDrop,
+    OutOfRange,
+    BagFull,
+}
+
+impl ItemReject {
+    /// Short label for metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UnknownItem => "unknown_item",
+            Self::NotCarried => "not_carried",
+            Self::NotEquippable => "not_equippable",
+            Self::NotUsable => "not_usable",
+            Self::Incapacitated => "incapacitated",
+            Self::NoActor => "no_actor",
+            Self::NoDrop => "no_drop",
+            Self::OutOfRange => "out_of_range",
+            Self::BagFull => "bag_full",
+        }
+    }
+}
+
+impl ServerState {
+    /// Put up to `count` of `item` in `actor`'s bag, topping up existing
+    /// stacks before opening new ones. Returns how many fit.
+    pub fn give_item(&mut self, actor: ActorId, item: &str, count: u32) -> u32 {
+        let Some(spec) = self.specs_items.get(item) else {
+            log::warn!("server: unknown item '{item}' for {actor:?}");
+            return 0;
+        };
+        let Some(inv) = self.ecs.get_mut(actor).and_then(|c| c.inventory.as_mut()) else {
+            return 0;
+        };
+        let cap = spec.max_stack.max(1);
+        let mut left = count;
+        for s in inv.items.iter_mut().filter(|s| s.item == item) {
+            let add = left.min(cap.saturating_sub(s.count));
+            s.count += add;
+            left -= add;
+        }
+        while left > 0 && inv.items.len() < inv.slots {
+            let add = left.min(cap);
+            inv.items.push(ItemStack {
+                item: item.to_string(),
+                count: add,
+            });
+            left -= add;
+        }
+        let given = count - left;
+        if given > 0 {
+            inv.rev = inv.rev.wrapping_add(1);
+            metrics::counter!("items.given_total").increment(u64::from(given));
+        }
+        given
+    }
+
+    /// Wear the carried `item` in its slot, or take it off if it is worn.
+    pub fn equip_item(&mut self, actor: ActorId, item: &str) -> Result<(), ItemReject> {
+        let spec = self.specs_items.get(item).ok_or(ItemReject::UnknownItem)?;
+        let slot = spec.equip_slot().ok_or(ItemReject::NotEquippable)?;
+        let inv = self
+            .ecs
+            .get_mut(actor)
+            .filter(|c| c.hp.alive())
+            .and_then(|c| c.inventory.as_mut())
+            .ok_or(ItemReject::NoActor)?;
+        if inv.count(item) == 0 {
+            return Err(ItemReject::NotCarried);
+        }
+        if inv.equipped.get(&slot).is_some_and(|worn| worn == item) {
+            inv.equipped.remove(&slot);
+        } else {
+            inv.equipped.insert(slot, item.to_string());
+        }
+        inv.fx = equip_fx(&self.specs_items, inv);
+        inv.rev = inv.rev.wrapping_add(1);
+        metrics::counter!("items.equipped_total", "slot" => slot.as_str()).increment(1);
+        Ok(())
+    }
+
+    /// Spend one of the carried consumable `item` on `actor`.
+    pub fn use_item(&mut self, actor: ActorId, item: &str) -> Result<(), ItemReject> {
+        let spec = self.specs_items.get(item).ok_or(ItemReject::UnknownItem)?;
+        if spec.kind != ItemKind::Consumable {
+            return Err(ItemReject::NotUsable);
+        }
+        let (heal, mana, status) = (spec.heal.clone(), spec.mana, spec.status.clone());
+        let c = self
+            .ecs
+            .get_mut(actor)
+            .filter(|c| c.hp.alive())
+            .ok_or(ItemReject::NoActor)?;
+        if c.incapacitated() {
+            return Err(ItemReject::Incapacitated);
+        }
+        let inv = c.inventory.as_mut().ok_or(ItemReject::NoActor)?;
+        if take(inv, item, 1) == 0 {
+            return Err(ItemReject::NotCarried);
+        }
+        let healed = if heal.is_empty() {
+            0
+        } else {
+            let roll = Dice::parse(&heal).roll(&mut self.combat_rng.0).max(0);
+            let before = c.hp.hp;
+            c.hp.hp = (c.hp.hp + roll).min(c.hp.max);
+            c.hp.hp - before
+        };
+        if let Some(p) = c.pool.as_mut() {
+            p.mana = (p.mana + mana).min(p.max);
+        }
+        if healed > 0 {
+            crate::systems::threat::on_heal(self, actor, actor, healed);
+        }
+        if !status.is_empty() {
+            self.apply_status(actor, &status, Some(actor));
+        }
+        metrics::counter!("items.used_total").increment(1);
+        Ok(())
+    }
+
+    /// Drop up to `count` of the carried `item` on the ground. Returns how
+    /// many went.
+    pub fn drop_item(&mut self, actor: ActorId, item: &str, count: u32) -> Result<u32, ItemReject> {
+        let c = self.ecs.get_mut(actor).ok_or(ItemReject::NoActor)?;
+        let pos = c.tr.pos;
+        let inv = c.inventory.as_mut().ok_or(ItemReject::NoActor)?;
+        let dropped = take(inv, item, count);
+        if dropped == 0 {
+            return Err(ItemReject::NotCarried);
+        }
+        inv.fx = equip_fx(&self.specs_items, inv);
+        self.drops.spawn(actor, item, dropped, pos);
+        metrics::counter!("items.dropped_total").increment(u64::from(dropped));
+        Ok(dropped)
+    }
+}
+
+/// Remove up to `count` of `item`, newest stacks first; an item no longer
+/// carried comes off. Returns how many were removed.
+fn take(inv: &mut Inventory, item: &str, count: u32) -> u32 {
+    let mut left = count;
+    for s in inv.items.iter_mut().rev().filter(|s| s.item == item) {
+        let n = left.min(s.count);
+        s.count -= n;
+        left -= n;
+    }
+    inv.items.retain(|s| s.count > 0);
+    if inv.count(item) == 0 {
+        inv.equipped.retain(|_, worn| worn != item);
+    }
+    let taken = count - left;
+    if taken > 0 {
+        inv.rev = inv.rev.wrapping_add(1);
+    }
+    taken
+}
+
+/// Modifiers of the items `inv` has on.
+pub fn equip_fx(items: &ItemSpecDb, inv: &Inventory) -> EquipFx {
+    let mut fx = EquipFx::default();
+    for (slot, id) in &inv.equipped {
+        let Some(spec) = items.get(id) else {
+            continue;
+        };
+        fx.ac_bonus += spec.ac_bonus;
+        if *slot == EquipSlot::MainHand && spec.kind == ItemKind::Weapon {
+            fx.weapon = Some((spec.weapon.clone(), spec.attack_bonus));
+        }
+    }
+    fx
+}
+
+/// What the owner of `inv` is sent.
+pub fn inventory_msg(inv: &Inventory) -> InventoryMsg {
+    let mut worn_seen = Vec::new();
+    let items = inv
+        .items
+        .iter()
+        .map(|s| {
+            // Mark one stack per worn item.
+            let worn = match inv.worn_in(&s.item) {
+                Some(slot) if !worn_seen.contains(&slot) => {
+                    worn_seen.push(slot);
+                    match slot {
+                        EquipSlot::MainHand => WORN_MAIN_HAND,
+                        EquipSlot::OffHand => WORN_OFF_HAND,
+                        EquipSlot::Body => WORN_BODY,
+                    }
+                }
+                _ => WORN_NONE,
+            };
+            InventoryItemRep {
+                item: s.item.clone(),
+                count: u16::try_from(s.count).unwrap_or(u16::MAX),
+                worn,
+            }
+        })
+        .collect();
+    InventoryMsg {
+        v: INVENTORY_VERSION,
+        slots: u8::try_from(inv.slots).unwrap_or(u8::MAX),
+        items,
+    }
+}
@@ -0,0 +1,203 @@
+//! Loot tables and world drops (`data/config/loot.toml`).
+//!
+//! `loot_tick` runs after damage is applied. For every death this tick whose
+//! archetype has a loot table, each contributor rolls the table separately:
+//! players on the victim's threat table (damage or healing threat) and a
+//! player killer, in actor id order. Rolls use `ServerState::loot_rng`, so a
+//! server booted from the same seed drops the same loot. Every item rolled
+//! becomes a `WorldDrop` owned by that player, scattered around the body.
+//!
+//! Drops are personal: only the owner is sent them (`LootMsg`, see
+//! [`loot_reps`]) and only the owner can pick them up (`ClientCmd::PickUp`,
+//! within `PICKUP_RANGE_M`), which moves them into the bag. Units that don't
+//! fit stay on the ground. Items a player drops from the bag land the same
+//! way. Drops vanish after `DROP_TTL_S`, or once their owner is gone.
+
+use data_runtime::specs::loot::LootTable;
+use glam::Vec3;
+use net_core::snapshot::LootDropRep;
+use rand::Rng;
+
+use crate::ServerState;
+use crate::actor::{ActorId, Faction};
+use crate::ecs::schedule::Ctx;
+use crate::systems::inventory::ItemReject;
+
+/// Seconds a drop lies on the ground.
+pub const DROP_TTL_S: f32 = 300.0;
+/// How close (XZ) a player must stand to pick a drop up.
+pub const PICKUP_RANGE_M: f32 = 3.0;
+/// Radius of the ring a kill's drops are scattered on.
+const SCATTER_M: f32 = 0.6;
+
+/// Items on the ground that one player may pick up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldDrop {
+    pub id: u32,
+    pub owner: ActorId,
+    pub item: String,
+    pub count: u32,
+    pub pos: Vec3,
+    pub ttl_s: f32,
+}
+
+#[derive(Debug, Default)]
+pub struct WorldDrops {
+    pub items: Vec<WorldDrop>,
+    next_id: u32,
+}
+
+impl WorldDrops {
+    /// Put `count` of `item` on the ground at `pos` for `owner`.
+    pub fn spawn(&mut self, owner: ActorId, item: &str, count: u32, pos: Vec3) -> u32 {
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.items.push(WorldDrop {
+            id: self.next_id,
+            owner,
+            item: item.to_string(),
+            count,
+            pos,
+            ttl_s: DROP_TTL_S,
+        });
+        metrics::counter!("loot.drops_total").increment(1);
+        self.next_id
+    }
+
+    pub fn get(&self, id: u32) -> Option<&WorldDrop> {
+        self.items.iter().find(|d| d.id == id)
+    }
+
+    /// Drops `owner` can see and pick up.
+    pub fn owned_by(&self, owner: ActorId) -> impl Iterator<Item = &WorldDrop> {
+        self.items.iter().filter(move |d| d.owner == owner)
+    }
+}
+
+/// Draw `table.rolls` times; returns (item, units) per item rolled, in the
+/// order first rolled.
+pub fn roll_loot(table: &LootTable, rng: &mut impl Rng) -> Vec<(String, u32)> {
+    let total = table.total_weight();
+    let mut out: Vec<(String, u32)> = Vec::new();
+    if total == 0 {
+        return out;
+    }
+    for _ in 0..table.rolls {
+        let Some(e) = table.pick(rng.random_range(0..total)) else {
+            continue;
+        };
+        if e.item.is_empty() {
+            continue;
+        }
+        let n = rng.random_range(e.min..=e.max);
+        match out.iter_mut().find(|(item, _)| *item == e.item) {
+            Some((_, c)) => *c += n,
+            None => out.push((e.item.clone(), n)),
+        }
+    }
+    out
+}
+
+/// Players who earned a share of `victim`'s loot, in actor id order.
+pub fn contributors(srv: &ServerState, victim: ActorId, killer: Option<ActorId>) -> Vec<ActorId> {
+    let mut ids: Vec<ActorId> = srv
+        .ecs
+        .get(victim)
+        .and_then(|v| v.threat.as_ref())
+        .map(|t| t.iter().map(|(k, _)| k).collect())
+        .unwrap_or_default();
+    ids.extend(killer);
+    ids.retain(|id| {
+        srv.ecs
+            .get(*id)
+            .is_some_and(|c| c.faction == Faction::PC && c.inventory.is_some())
+    });
+    ids.sort_by_key(|id| id.0);
+    ids.dedup();
+    ids
+}
+
+/// Expire old drops, then roll loot for this tick's deaths.
+pub fn loot_tick(srv: &mut ServerState, ctx: &mut Ctx) {
+    let dt = ctx.dt;
+    let ecs = &srv.ecs;
+    let before = srv.drops.items.len();
+    srv.drops.items.retain_mut(|d| {
+        d.ttl_s -= dt;
+        d.ttl_s > 0.0 && ecs.get(d.owner).is_some()
+    });
+    let expired = before - srv.drops.items.len();
+    if expired > 0 {
+        metrics::counter!("loot.expired_total").increment(expired as u64);
+    }
+    for d in &ctx.deaths {
+        let Some(v) = srv.ecs.get(d.id) else {
+            continue;
+        };
+        let pos = v.tr.pos;
+        let Some(table) = srv
+            .specs_arche
+            .by_net_id(v.archetype_id)
+            .and_then(|(id, _)| srv.specs_loot.get(id))
+            .cloned()
+        else {
+            continue;
+        };
+        let mut n = 0u32;
+        for owner in contributors(srv, d.id, d.killer) {
+            for (item, count) in roll_loot(&table, &mut srv.loot_rng.0) {
+                let a = n as f32 * 2.4;
+                n += 1;
+                let at = pos + Vec3::new(a.cos(), 0.0, a.sin()) * SCATTER_M;
+                srv.drops.spawn(owner, &item, count, at);
+            }
+        }
+    }
+}
+
+/// What `owner` is sent about its drops.
+pub fn loot_reps(srv: &ServerState, owner: ActorId) -> Vec<LootDropRep> {
+    srv.drops
+        .owned_by(owner)
+        .map(|d| LootDropRep {
+            id: d.id,
+            item: d.item.clone(),
+            count: u16::try_from(d.count).unwrap_or(u16::MAX),
+            pos: d.pos.into(),
+        })
+        .collect()
+}
+
+impl ServerState {
+    /// Move `actor`'s drop `drop_id` into its bag. Returns how many units fit;
+    /// the rest stay on the ground.
+    pub fn pick_up(&mut self, actor: ActorId, drop_id: u32) -> Result<u32, ItemReject> {
+        let c = self
+            .ecs
+            .get(actor)
+            .filter(|c| c.hp.alive())
+            .ok_or(ItemReject::NoActor)?;
+        let pos = c.tr.pos;
+        let Some(d) = self.drops.get(drop_id).filter(|d| d.owner == actor) else {
+            return Err(ItemReject::NoDrop);
+        };
+        let (dx, dz) = (d.pos.x - pos.x, d.pos.z - pos.z);
+        if dx * dx + dz * dz > PICKUP_RANGE_M * PICKUP_RANGE_M {
+            return Err(ItemReject::OutOfRange);
+        }
+        let (item, count) = (d.item.clone(), d.count);
+        if self.specs_items.get(&item).is_none() {
+            return Err(ItemReject::UnknownItem);
+        }
+        let got = self.give_item(actor, &item, count);
+        if got == 0 {
+            return Err(ItemReject::BagFull);
+        }
+        if got == count {
+            self.drops.items.retain(|d| d.id != drop_id);
+        } else if let Some(d) = self.drops.items.iter_mut().find(|d| d.id == drop_id) {
+            d.count -= got;
+        }
+        metrics::counter!("loot.picked_up_total").increment(u64::from(got));
+        Ok(got)
+    }
+}
@@ -1,3 +1,13 @@
+pub mod boss;
 pub mod destructible;
+pub mod dodge;
+pub mod inventory;
+pub mod loot;
 pub mod npc;
 pub mod projectiles;
+pub mod reputation;
+pub mod spawn_groups;
+pub mod spells;
+pub mod status;
+pub mod threat;
+pub mod weapons;
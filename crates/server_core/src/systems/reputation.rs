@@ -0,0 +1,44 @@
+//! Player reputation from combat (see `crate::combat`).
+//!
+//! `reputation_from_hits` runs just before damage is applied: every damage
+//! event a player deals to a non-player actor applies the victim faction's
+//! `hit_rep`. `reputation_from_kills` runs right after: each death credited to
+//! a player applies the victim faction's `kill_rep` and `kill_spillover`.
+//! Bands are re-read on every hostility check, so a player who turns hostile
+//! is attacked (and may be targeted) from the next tick on.
+
+use crate::ServerState;
+use crate::actor::Faction;
+use crate::ecs::schedule::Ctx;
+
+/// Players' hits this tick move their reputation with the victims' factions.
+pub fn reputation_from_hits(srv: &mut ServerState, ctx: &mut Ctx) {
+    for d in &ctx.dmg {
+        if let Some(src) = d.src
+            && let (Some(sa), Some(v)) = (srv.ecs.get(src), srv.ecs.get(d.dst))
+            && sa.faction == Faction::PC
+            && v.faction != Faction::PC
+            && v.hp.alive()
+        {
+            let victim = v.faction;
+            srv.factions.on_hit(src, victim);
+            metrics::counter!("faction.rep_changes_total", "cause" => "hit").increment(1);
+        }
+    }
+}
+
+/// Players' kills this tick move their reputation with the victims' factions
+/// and those factions' friends and foes.
+pub fn reputation_from_kills(srv: &mut ServerState, ctx: &mut Ctx) {
+    for d in &ctx.deaths {
+        if let Some(killer) = d.killer
+            && let (Some(k), Some(v)) = (srv.ecs.get(killer), srv.ecs.get(d.id))
+            && k.faction == Faction::PC
+            && v.faction != Faction::PC
+        {
+            let victim = v.faction;
+            srv.factions.on_kill(killer, victim);
+            metrics::counter!("faction.rep_changes_total", "cause" => "kill").increment(1);
+        }
+    }
+}
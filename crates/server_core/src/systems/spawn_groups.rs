@@ -0,0 +1,307 @@
+//! Zone spawn groups: respawn timers, population caps, leashing and idle
+//! wander/patrol for the NPCs a zone boots with.
+//!
+//! Each encounter NPC entry becomes a group with one slot per authored spawn
+//! position. `spawn_groups_tick` runs before hostile movement each tick and:
+//! - frees slots whose actor died or despawned, and starts one respawn timer
+//!   per missing member while the group is under its population cap;
+//! - respawns into the first free slot when a timer elapses;
+//! - leashes members pulled too far from where the fight began: they drop the
+//!   fight and their threat table, heal to full and walk back, ignoring
+//!   targets until they arrive;
+//! - walks idle members around their patrol loop or wanders them near home.
+//!
+//! Wander choices come from a per-group RNG seeded by group index, so a zone
+//! replays identically.
+
+use glam::{Vec2, Vec3};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::ServerState;
+use crate::actor::{ActorId, Faction};
+use crate::archetype::ArchetypeOverrides;
+use crate::ecs::GroupMember;
+
+/// Timers within this of zero count as elapsed (absorbs `dt` rounding).
+const TIMER_EPS_S: f32 = 1e-4;
+/// Close enough to a patrol point, wander goal or leash anchor.
+const ARRIVE_M: f32 = 0.5;
+/// Idle movement runs at this fraction of the archetype's move speed.
+const IDLE_SPEED_MUL: f32 = 0.5;
+/// Pause between wander legs, in seconds.
+const WANDER_PAUSE_S: (f32, f32) = (2.0, 5.0);
+
+/// What to spawn for a group member.
+#[derive(Clone, Debug)]
+pub struct NpcTemplate {
+    /// `ArchetypeSpecDb` key.
+    pub archetype: String,
+    pub yaw: f32,
+    pub faction: Option<Faction>,
+    pub hp: Option<i32>,
+    pub aggro_m: Option<f32>,
+    pub melee_cooldown_s: Option<f32>,
+}
+
+impl NpcTemplate {
+    pub fn new(archetype: impl Into<String>) -> Self {
+        Self {
+            archetype: archetype.into(),
+            yaw: 0.0,
+            faction: None,
+            hp: None,
+            aggro_m: None,
+            melee_cooldown_s: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpawnGroup {
+    pub template: NpcTemplate,
+    /// Authored spawn positions; members respawn into the first free one.
+    pub slots: Vec<Vec3>,
+    /// Most members alive at once (clamped to the slot count).
+    pub max_alive: usize,
+    /// Seconds from a member's death to its replacement; `None` never respawns.
+    pub respawn_s: Option<f32>,
+    /// Distance from the pull start at which members give up and reset.
+    pub leash_m: Option<f32>,
+    /// Idle wander radius around each member's slot (0 = stand still).
+    pub wander_m: f32,
+    /// Idle patrol loop; takes precedence over wandering.
+    pub patrol: Vec<Vec3>,
+    members: Vec<Option<ActorId>>,
+    timers: Vec<f32>,
+    rng: ChaCha8Rng,
+}
+
+impl SpawnGroup {
+    pub fn new(template: NpcTemplate, slots: Vec<Vec3>) -> Self {
+        let n = slots.len();
+        Self {
+            template,
+            slots,
+            max_alive: n,
+            respawn_s: None,
+            leash_m: None,
+            wander_m: 0.0,
+            patrol: Vec::new(),
+            members: vec![None; n],
+            timers: Vec::new(),
+            rng: ChaCha8Rng::seed_from_u64(0),
+        }
+    }
+
+    /// Live member ids in slot order.
+    pub fn members(&self) -> impl Iterator<Item = ActorId> + '_ {
+        self.members.iter().flatten().copied()
+    }
+
+    /// Seconds left on each pending respawn.
+    pub fn pending_respawns(&self) -> &[f32] {
+        &self.timers
+    }
+}
+
+/// Register a group and spawn its initial members (up to `max_alive`).
+/// Returns how many actors were spawned.
+pub fn add_group(srv: &mut ServerState, mut group: SpawnGroup) -> usize {
+    let gi = srv.spawn_groups.len();
+    group.max_alive = group.max_alive.min(group.slots.len());
+    group.members = vec![None; group.slots.len()];
+    group.rng = ChaCha8Rng::seed_from_u64(gi as u64);
+    srv.spawn_groups.push(group);
+    let cap = srv.spawn_groups[gi].max_alive;
+    (0..cap).filter(|&slot| spawn_member(srv, gi, slot)).count()
+}
+
+/// Spawn one actor from a template, applying its overrides.
+pub fn spawn_npc(srv: &mut ServerState, t: &NpcTemplate, pos: Vec3) -> Option<ActorId> {
+    let ov = ArchetypeOverrides {
+        hp: t.hp,
+        yaw: Some(t.yaw),
+        faction: t.faction,
+        aggro_m: t.aggro_m,
+        melee_cooldown_s: t.melee_cooldown_s,
+        ..Default::default()
+    };
+    srv.spawn_archetype(&t.archetype, pos, &ov)
+}
+
+fn spawn_member(srv: &mut ServerState, gi: usize, slot: usize) -> bool {
+    let g = &srv.spawn_groups[gi];
+    let (t, home) = (g.template.clone(), g.slots[slot]);
+    let Some(id) = spawn_npc(srv, &t, home) else {
+        return false;
+    };
+    let g = &mut srv.spawn_groups[gi];
+    g.members[slot] = Some(id);
+    let pause_s = if g.wander_m > 0.0 {
+        g.rng.random_range(0.0..WANDER_PAUSE_S.1)
+    } else {
+        0.0
+    };
+    if let Some(c) = srv.ecs.get_mut(id) {
+        c.member = Some(GroupMember {
+            group: gi as u32,
+            slot: slot as u32,
+            anchor: c.tr.pos,
+            returning: false,
+            wander_goal: None,
+            pause_s,
+            patrol_idx: 0,
+        });
+    }
+    true
+}
+
+/// Per-tick spawn group upkeep; see the module docs.
+pub fn spawn_groups_tick(srv: &mut ServerState, dt: f32) {
+    for gi in 0..srv.spawn_groups.len() {
+        respawn(srv, gi, dt);
+    }
+    leash_and_idle(srv, dt);
+}
+
+fn respawn(srv: &mut ServerState, gi: usize, dt: f32) {
+    let ecs = &srv.ecs;
+    let g = &mut srv.spawn_groups[gi];
+    for m in &mut g.members {
+        if m.is_some_and(|id| !ecs.get(id).is_some_and(|c| c.hp.alive())) {
+            *m = None;
+        }
+    }
+    let Some(delay) = g.respawn_s else {
+        return;
+    };
+    let alive = g.members.iter().flatten().count();
+    while alive + g.timers.len() < g.max_alive {
+        g.timers.push(delay);
+    }
+    for t in &mut g.timers {
+        *t -= dt;
+    }
+    let due = g.timers.iter().filter(|t| **t <= TIMER_EPS_S).count();
+    g.timers.retain(|t| *t > TIMER_EPS_S);
+    for _ in 0..due {
+        let Some(slot) = srv.spawn_groups[gi]
+            .members
+            .iter()
+            .position(Option::is_none)
+        else {
+            break;
+        };
+        if spawn_member(srv, gi, slot) {
+            metrics::counter!("zone.respawns_total").increment(1);
+        }
+    }
+}
+
+fn leash_and_idle(srv: &mut ServerState, dt: f32) {
+    // Same target set hostile movement chases: anything the member is hostile to.
+    let targets: Vec<(ActorId, Vec3, Faction)> = srv
+        .ecs
+        .iter()
+        .filter(|a| a.hp.alive() && a.projectile.is_none())
+        .map(|a| (a.id, a.tr.pos, a.faction))
+        .collect();
+    let ids: Vec<ActorId> = srv
+        .ecs
+        .iter()
+        .filter(|a| a.hp.alive() && a.member.is_some() && a.move_speed.is_some())
+        .map(|a| a.id)
+        .collect();
+    for id in ids {
+        let Some(c) = srv.ecs.get(id) else { continue };
+        let (Some(m), Some(speed)) = (c.member, c.move_speed.map(|s| s.mps)) else {
+            continue;
+        };
+        let pos = c.tr.pos;
+        let engaged = c.threat.as_ref().is_some_and(|t| t.target().is_some())
+            || c.aggro.is_some_and(|ag| {
+                targets.iter().any(|&(tid, t, tf)| {
+                    srv.factions.hostile(id, c.faction, tid, tf)
+                        && Vec2::new(t.x - pos.x, t.z - pos.z).length_squared() <= ag.m * ag.m
+                })
+            });
+        let gi = m.group as usize;
+        let g = &srv.spawn_groups[gi];
+        let (leash_m, wander_m, home) = (g.leash_m, g.wander_m, g.slots[m.slot as usize]);
+        let patrol = (!g.patrol.is_empty()).then(|| {
+            let i = m.patrol_idx as usize % g.patrol.len();
+            (i, g.patrol.len(), g.patrol[i])
+        });
+        let mut m = m;
+        if m.returning {
+            if walk(srv, id, m.anchor, speed, dt) {
+                m.returning = false;
+            }
+        } else if engaged {
+            if leash_m.is_some_and(|l| dist_xz(pos, m.anchor) > l) {
+                m.returning = true;
+                if let Some(c) = srv.ecs.get_mut(id) {
+                    c.hp.hp = c.hp.max;
+                    c.nav = None;
+                    c.threat = None;
+                }
+                metrics::counter!("zone.leash_resets_total").increment(1);
+            }
+        } else {
+            m.anchor = pos;
+            if let Some((i, n, goal)) = patrol {
+                if walk(srv, id, goal, speed * IDLE_SPEED_MUL, dt) {
+                    m.patrol_idx = ((i + 1) % n) as u32;
+                }
+            } else if wander_m > 0.0 {
+                if m.pause_s > 0.0 {
+                    m.pause_s -= dt;
+                } else if let Some(goal) = m.wander_goal {
+                    if walk(srv, id, goal, speed * IDLE_SPEED_MUL, dt) {
+                        m.wander_goal = None;
+                        m.pause_s = srv.spawn_groups[gi]
+                            .rng
+                            .random_range(WANDER_PAUSE_S.0..WANDER_PAUSE_S.1);
+                    }
+                } else {
+                    let rng = &mut srv.spawn_groups[gi].rng;
+                    let a = rng.random_range(0.0..std::f32::consts::TAU);
+                    let r = wander_m * rng.random_range(0.0f32..1.0).sqrt();
+                    m.wander_goal = Some(home + Vec3::new(r * a.cos(), 0.0, r * a.sin()));
+                }
+            }
+        }
+        if let Some(c) = srv.ecs.get_mut(id) {
+            c.member = Some(m);
+        }
+    }
+}
+
+/// Step toward `goal` (around obstacles when needed); true once within reach.
+fn walk(srv: &mut ServerState, id: ActorId, goal: Vec3, speed: f32, dt: f32) -> bool {
+    let grid = &srv.nav;
+    let Some(c) = srv.ecs.get_mut(id) else {
+        return false;
+    };
+    if dist_xz(c.tr.pos, goal) <= ARRIVE_M {
+        return true;
+    }
+    let here = Vec2::new(c.tr.pos.x, c.tr.pos.z);
+    let goal2 = Vec2::new(goal.x, goal.z);
+    let Some(next) = crate::nav::steer(grid, &mut c.nav, here, goal2, c.tr.radius, dt) else {
+        return false;
+    };
+    let leg = next - here;
+    let step = (speed * dt).min(leg.length());
+    if step > 1e-4 {
+        let dir = leg.normalize_or_zero();
+        c.tr.pos += Vec3::new(dir.x, 0.0, dir.y) * step;
+    }
+    dist_xz(c.tr.pos, goal) <= ARRIVE_M
+}
+
+#[inline]
+fn dist_xz(a: Vec3, b: Vec3) -> f32 {
+    Vec2::new(a.x - b.x, a.z - b.z).length()
+}
@@ -0,0 +1,136 @@
+//! Spell hits resolved with the shared SRD rules (`sim_core::rules::spell`).
+//!
+//! Fire Bolt bolts and Fireball blasts resolve through their spell's
+//! `SpellRules` (from `data/spells`) with `ServerState::combat_rng`, so a
+//! server and a simulator seeded alike roll the same dice:
+//! - Fire Bolt rolls a spell attack against the target's `ArmorClass` plus
+//!   status AC bonuses, with advantage against stunned or otherwise helpless
+//!   targets (`Components::attacked_with_advantage`); a natural 20 doubles
+//!   the dice.
+//! - Fireball has every target in the blast make its own DEX save against the
+//!   caster's DC, then rolls its damage once for the whole blast; targets
+//!   that save take half.
+//!
+//! Casters use their `Spellcasting` (a first-level wizard if they have none);
+//! targets use their `SavingThrows`. Data spells riding the same projectile
+//! paths use the built-in spell's rules. Resistance is applied when the
+//! `DamageEvent` lands (`apply_damage_to_ecs`).
+
+use ecs_core::components::{DamageType, SavingThrows};
+use sim_core::rules::attack::Advantage;
+use sim_core::rules::saves::SaveKind;
+use sim_core::rules::spell::{Caster, SpellOutcome, SpellRules, Target};
+
+use crate::actor::ActorId;
+use crate::ecs::{Components, Spellcasting};
+use crate::{ServerState, SpellId};
+
+/// Modifier from `saves` for a save of `kind`.
+pub fn save_mod(saves: &SavingThrows, kind: SaveKind) -> i32 {
+    i32::from(match kind {
+        SaveKind::Str => saves.str_mod,
+        SaveKind::Dex => saves.dex_mod,
+        SaveKind::Con => saves.con_mod,
+        SaveKind::Int => saves.int_mod,
+        SaveKind::Wis => saves.wis_mod,
+        SaveKind::Cha => saves.cha_mod,
+    })
+}
+
+/// Roll `spell` from `src` against `dst`. `None` when the spell has no
+/// damage rules (the caller falls back to projectile tuning) or `dst` is gone.
+pub fn resolve_spell_hit(
+    srv: &mut ServerState,
+    spell: SpellId,
+    src: Option<ActorId>,
+    dst: ActorId,
+) -> Option<(SpellOutcome, Option<DamageType>)> {
+    let rules = srv.abilities.get(spell)?.rules.clone();
+    let damage = rules.damage.as_ref()?;
+    let damage_type = ecs_core::parse::parse_damage_type(&damage.damage_type);
+    let caster = caster_of(srv, src);
+    let target = target(&rules, srv.ecs.get(dst)?);
+    let out = rules.resolve(&mut srv.combat_rng.0, &caster, &target);
+    let result = match (out.attack, out.save) {
+        (Some(a), _) if a.crit => "crit",
+        (Some(a), _) if !a.hit => "miss",
+        (_, Some(s)) if s.success => "saved",
+        _ => "hit",
+    };
+    metrics::counter!("spell.hits_total", "result" => result).increment(1);
+    log::debug!("server: {spell:?} {src:?} -> {dst:?}: {out:?} -> {result}");
+    Some((out, damage_type))
+}
+
+/// Damage each target of an area spell takes, before resistance.
+pub type AreaDamage = Vec<(ActorId, i32)>;
+
+/// Roll area `spell` from `src` against every target in `dsts`: each saves,
+/// then one damage roll is shared and reduced per target by its save. `None`
+/// when the spell has no damage rules; targets that are gone are skipped.
+pub fn resolve_area_hits(
+    srv: &mut ServerState,
+    spell: SpellId,
+    src: Option<ActorId>,
+    dsts: &[ActorId],
+) -> Option<(AreaDamage, Option<DamageType>)> {
+    let rules = srv.abilities.get(spell)?.rules.clone();
+    let damage = rules.damage.as_ref()?;
+    let damage_type = ecs_core::parse::parse_damage_type(&damage.damage_type);
+    let caster = caster_of(srv, src);
+    let mut saves = Vec::with_capacity(dsts.len());
+    for &dst in dsts {
+        let Some(t) = srv.ecs.get(dst).map(|t| target(&rules, t)) else {
+            continue;
+        };
+        saves.push((dst, rules.saving_throw(&mut srv.combat_rng.0, &caster, &t)));
+    }
+    let rolled = rules.roll_damage(&mut srv.combat_rng.0, &caster);
+    log::debug!("server: {spell:?} {src:?} rolled {rolled} for {saves:?}");
+    let hits = saves
+        .into_iter()
+        .map(|(dst, save)| {
+            let result = if save.is_some_and(|s| s.success) {
+                "saved"
+            } else {
+                "hit"
+            };
+            metrics::counter!("spell.hits_total", "result" => result).increment(1);
+            (dst, rules.after_save(rolled, save))
+        })
+        .collect();
+    Some((hits, damage_type))
+}
+
+/// `src`'s spellcasting numbers (a first-level wizard's if it has none).
+fn caster_of(srv: &ServerState, src: Option<ActorId>) -> Caster {
+    let sc = src
+        .and_then(|id| srv.ecs.get(id))
+        .and_then(|c| c.spellcasting)
+        .unwrap_or_default();
+    caster(sc)
+}
+
+/// What `t` brings to a roll against `rules`.
+fn target(rules: &SpellRules, t: &Components) -> Target {
+    Target {
+        ac: t.armor_class(),
+        save_mod: match (rules.save, t.saves.as_ref()) {
+            (Some(s), Some(saves)) => save_mod(saves, s.kind),
+            _ => 0,
+        },
+        attack_adv: if t.attacked_with_advantage() {
+            Advantage::Advantage
+        } else {
+            Advantage::Normal
+        },
+    }
+}
+
+fn caster(sc: Spellcasting) -> Caster {
+    Caster {
+        attack_bonus: sc.attack_bonus,
+        save_dc: sc.save_dc,
+        level: sc.level,
+    }
+}
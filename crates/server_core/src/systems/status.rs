@@ -0,0 +1,263 @@
+//! Status effects (`data/config/statuses.toml`).
+//!
+//! `ServerState::apply_status` puts a status on an actor. Actors immune to one
+//! of its conditions (`Components::immune`) are unaffected; reapplying an
+//! active status follows its `Stacking` rule. The spec's per-stack effect is
+//! copied onto the actor as `StatusFx`, which `Components` folds into
+//! `speed_mul`, `damage_mul`, `ac_bonus` and condition queries.
+//!
+//! `status_tick` runs after spawns are applied each tick: it emits tick damage
+//! as `DamageEvent`s attributed to whoever applied the status, counts down
+//! durations, and clears everything on death. Active statuses of the actors in
+//! a client's interest replicate as `net_core::snapshot::StatusMsg` built by
+//! [`status_reps`].
+
+use std::collections::HashMap;
+
+use data_runtime::specs::statuses::{Stacking, StatusKind, StatusSpecDb};
+use net_core::snapshot::StatusRep;
+
+use crate::ServerState;
+use crate::actor::ActorId;
+use crate::ecs::schedule::{Ctx, DamageEvent};
+use crate::ecs::{ActiveStatus, StatusFx};
+
+/// Slack for float drift when a duration or tick interval runs out.
+const EPS_S: f32 = 1e-4;
+
+/// A status resolved from its spec.
+#[derive(Copy, Clone, Debug)]
+pub struct StatusDef {
+    pub net_id: u16,
+    pub kind: StatusKind,
+    pub duration_s: f32,
+    pub stacking: Stacking,
+    pub max_stacks: u8,
+    pub fx: StatusFx,
+}
+
+/// Statuses by name, with conditions and damage types parsed.
+#[derive(Clone, Debug, Default)]
+pub struct StatusDb {
+    defs: HashMap<String, StatusDef>,
+}
+
+impl StatusDb {
+    pub fn from_specs(db: &StatusSpecDb) -> Self {
+        let defs = db
+            .statuses
+            .iter()
+            .map(|(id, s)| {
+                let conditions = s
+                    .conditions
+                    .iter()
+                    .filter_map(|c| {
+                        let cond = ecs_core::parse::parse_condition(c);
+                        if cond.is_none() {
+                            log::warn!("server: status '{id}' imposes unknown condition '{c}'");
+                        }
+                        cond
+                    })
+                    .collect();
+                let damage_type = if s.damage_type.is_empty() {
+                    None
+                } else {
+                    let dt = ecs_core::parse::parse_damage_type(&s.damage_type);
+                    if dt.is_none() {
+                        log::warn!(
+                            "server: status '{id}' ticks unknown damage type '{}'",
+                            s.damage_type
+                        );
+                    }
+                    dt
+                };
+                let def = StatusDef {
+                    net_id: s.net_id,
+                    kind: s.kind,
+                    duration_s: s.duration_s,
+                    stacking: s.stacking,
+                    max_stacks: s.max_stacks.max(1),
+                    fx: StatusFx {
+                        tick_s: s.tick_s,
+                        tick_damage: s.tick_damage,
+                        damage_type,
+                        speed_mul: s.speed_mul,
+                        damage_mul: s.damage_mul,
+                        ac_bonus: s.ac_bonus,
+                        conditions,
+                    },
+                };
+                (id.clone(), def)
+            })
+            .collect();
+        Self { defs }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StatusDef> {
+        self.defs.get(name)
+    }
+
+    /// Every status by name, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &StatusDef)> {
+        self.defs.iter().map(|(k, d)| (k.as_str(), d))
+    }
+}
+
+/// What an `apply_status` call did.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatusOutcome {
+    Applied,
+    /// Already active; the duration was refreshed or extended.
+    Refreshed,
+    /// Already active; one more stack.
+    Stacked,
+    /// Already active with `Stacking::Ignore`, or the target is dead.
+    Ignored,
+    /// The target is immune to one of the status's conditions.
+    Immune,
+    /// No such status or target.
+    Unknown,
+}
+
+impl StatusOutcome {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Refreshed => "refreshed",
+            Self::Stacked => "stacked",
+            Self::Ignored => "ignored",
+            Self::Immune => "immune",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl ServerState {
+    /// Apply status `name` to `target` for its default duration.
+    pub fn apply_status(
+        &mut self,
+        target: ActorId,
+        name: &str,
+        src: Option<ActorId>,
+    ) -> StatusOutcome {
+        self.apply_status_for(target, name, src, None)
+    }
+
+    /// Apply status `name` to `target`, overriding its duration when
+    /// `duration_s` is set.
+    pub fn apply_status_for(
+        &mut self,
+        target: ActorId,
+        name: &str,
+        src: Option<ActorId>,
+        duration_s: Option<f32>,
+    ) -> StatusOutcome {
+        let outcome = match (self.statuses.get(name), self.ecs.get_mut(target)) {
+            (Some(def), Some(c)) => apply(c, def, src, duration_s.unwrap_or(def.duration_s)),
+            _ => {
+                log::warn!("server: status '{name}' on {target:?}: unknown status or actor");
+                StatusOutcome::Unknown
+            }
+        };
+        metrics::counter!("status.applied_total", "outcome" => outcome.label()).increment(1);
+        outcome
+    }
+}
+
+fn apply(
+    c: &mut crate::ecs::Components,
+    def: &StatusDef,
+    src: Option<ActorId>,
+    dur: f32,
+) -> StatusOutcome {
+    if !c.hp.alive() || dur <= 0.0 {
+        return StatusOutcome::Ignored;
+    }
+    if c.immune_to_any(def.fx.conditions) {
+        return StatusOutcome::Immune;
+    }
+    let Some(s) = c.statuses.iter_mut().find(|s| s.id == def.net_id) else {
+        c.statuses.push(ActiveStatus {
+            id: def.net_id,
+            stacks: 1,
+            remaining_s: dur,
+            next_tick_s: def.fx.tick_s,
+            src,
+            fx: def.fx,
+        });
+        return StatusOutcome::Applied;
+    };
+    let outcome = match def.stacking {
+        Stacking::Ignore => return StatusOutcome::Ignored,
+        Stacking::Refresh => {
+            s.remaining_s = s.remaining_s.max(dur);
+            StatusOutcome::Refreshed
+        }
+        Stacking::Stack => {
+            s.remaining_s = s.remaining_s.max(dur);
+            if s.stacks < def.max_stacks {
+                s.stacks += 1;
+                StatusOutcome::Stacked
+            } else {
+                StatusOutcome::Refreshed
+            }
+        }
+        Stacking::Extend => {
+            let cap = (def.duration_s * f32::from(def.max_stacks)).max(dur);
+            s.remaining_s = (s.remaining_s + dur).min(cap);
+            StatusOutcome::Refreshed
+        }
+    };
+    s.src = src.or(s.src);
+    outcome
+}
+
+/// Tick damage, count down durations and clear statuses on death.
+pub fn status_tick(srv: &mut ServerState, ctx: &mut Ctx) {
+    let dt = ctx.dt;
+    for c in srv.ecs.iter_mut() {
+        if c.statuses.is_empty() {
+            continue;
+        }
+        if !c.hp.alive() {
+            c.statuses.clear();
+            continue;
+        }
+        for s in &mut c.statuses {
+            let elapsed = dt.min(s.remaining_s.max(0.0));
+            s.remaining_s -= dt;
+            if s.fx.tick_s <= 0.0 || s.fx.tick_damage <= 0 {
+                continue;
+            }
+            s.next_tick_s -= elapsed;
+            while s.next_tick_s <= EPS_S {
+                s.next_tick_s += s.fx.tick_s;
+                ctx.dmg.push(DamageEvent {
+                    src: s.src,
+                    dst: c.id,
+                    amount: s.fx.tick_damage * i32::from(s.stacks),
+                    damage_type: s.fx.damage_type,
+                });
+            }
+        }
+        c.statuses.retain(|s| s.remaining_s > EPS_S);
+    }
+}
+
+/// Active statuses of every live actor; `SessionHost::broadcast` filters them
+/// by interest.
+pub fn status_reps(srv: &ServerState) -> Vec<StatusRep> {
+    let ms = |s: f32| (s.max(0.0) * 1000.0).round().min(f32::from(u16::MAX)) as u16;
+    srv.ecs
+        .iter()
+        .filter(|c| c.hp.alive())
+        .flat_map(|c| {
+            c.statuses.iter().map(|s| StatusRep {
+                actor: c.id.0,
+                status: s.id,
+                stacks: s.stacks,
+                remaining_ms: ms(s.remaining_s),
+            })
+        })
+        .collect()
+}
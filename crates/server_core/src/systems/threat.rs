@@ -0,0 +1,148 @@
+//! NPC threat: who each AI-driven NPC attacks.
+//!
+//! Tables and selection rules are shared with the raid simulator
+//! (`sim_core::sim::components::threat`); tuning lives in `Specs::threat`.
+//! `threat_update` runs before NPC casting and movement each tick and:
+//! - puts hostiles inside the NPC's aggro radius on its table at zero threat,
+//!   nearest first, so the closest foe is attacked until someone earns more;
+//! - forgets foes that died, despawned or stopped being hostile, and foes that
+//!   wandered out of aggro range without earning meaningful threat;
+//! - decays threat and re-selects the target: a challenger in melee range must
+//!   exceed the current target by 110%, one at range by 130%.
+//!
+//! Damage adds threat in `apply_damage_to_ecs`; heals and taunts go through
+//! [`on_heal`] and [`taunt`]. Leashing NPCs drop their table.
+
+use glam::{Vec2, Vec3};
+
+use crate::ServerState;
+use crate::actor::{ActorId, Faction};
+use sim_core::sim::components::threat::Threat;
+
+/// Threat table keyed by server actor id.
+pub type ThreatTable = Threat<ActorId>;
+
+/// Entries outside aggro range are forgotten once their threat falls below this.
+const FORGET_THREAT: f32 = 0.5;
+/// Slack on melee reach when picking the overtake margin (matches caster AI).
+const MELEE_SLACK_M: f32 = 0.2;
+
+/// Whether an actor keeps a threat table: non-PC actors driven by AI.
+fn has_ai(c: &crate::ecs::Components) -> bool {
+    c.faction != Faction::PC && (c.aggro.is_some() || c.spellbook.is_some())
+}
+
+/// Per-tick threat upkeep and target selection; see the module docs.
+pub fn threat_update(srv: &mut ServerState, dt: f32) {
+    let rules = srv.specs.threat;
+    let foes: Vec<(ActorId, Vec3, f32, Faction)> = srv
+        .ecs
+        .iter()
+        .filter(|a| a.hp.alive())
+        .map(|a| (a.id, a.tr.pos, a.tr.radius, a.faction))
+        .collect();
+    let npc_ids: Vec<ActorId> = srv
+        .ecs
+        .iter()
+        .filter(|a| a.hp.alive() && has_ai(a))
+        .map(|a| a.id)
+        .collect();
+    for id in npc_ids {
+        let factions = &srv.factions;
+        let Some(c) = srv.ecs.get_mut(id) else {
+            continue;
+        };
+        if c.is_leash_returning() {
+            c.threat = None;
+            continue;
+        }
+        let (pos, rad, faction) = (c.tr.pos, c.tr.radius, c.faction);
+        let aggro_m = c.aggro.map_or(f32::INFINITY, |a| a.m);
+        let reach = rad + c.attack.map_or(0.35, |r| r.m) + MELEE_SLACK_M;
+        let mut near: Vec<(f32, ActorId)> = foes
+            .iter()
+            .filter(|(fid, _, _, ff)| *fid != id && factions.hostile(id, faction, *fid, *ff))
+            .map(|(fid, p, _, _)| (dist_xz(pos, *p), *fid))
+            .filter(|(d, _)| *d <= aggro_m)
+            .collect();
+        near.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let table = c.threat.get_or_insert_default();
+        for (_, fid) in &near {
+            table.engage(*fid);
+        }
+        table.decay(&rules, dt);
+        table.retain(|k, t| {
+            foes.iter()
+                .any(|(fid, _, _, ff)| *fid == k && factions.hostile(id, faction, *fid, *ff))
+                && (t >= FORGET_THREAT || near.iter().any(|(_, n)| *n == k))
+        });
+        table.select(&rules, |k| {
+            foes.iter()
+                .find(|(fid, _, _, _)| *fid == k)
+                .is_some_and(|(_, p, r, _)| dist_xz(pos, *p) <= reach + r)
+        });
+        if table.is_empty() {
+            c.threat = None;
+        }
+    }
+}
+
+/// Current threat target of `id`, if it is alive. AI systems fall back to the
+/// nearest hostile when this is `None`.
+pub fn target_of(srv: &ServerState, id: ActorId) -> Option<(ActorId, Vec3, f32)> {
+    let t = srv.ecs.get(id)?.threat.as_ref()?.target()?;
+    srv.ecs
+        .get(t)
+        .filter(|c| c.hp.alive())
+        .map(|c| (c.id, c.tr.pos, c.tr.radius))
+}
+
+/// Damage threat: `dst` remembers `src` (no-op for PCs and self-damage).
+pub fn on_damage(srv: &mut ServerState, src: ActorId, dst: ActorId, amount: i32) {
+    let rules = srv.specs.threat;
+    if src == dst {
+        return;
+    }
+    if let Some(c) = srv.ecs.get_mut(dst)
+        && has_ai(c)
+    {
+        c.threat
+            .get_or_insert_default()
+            .add_damage(&rules, src, amount);
+    }
+}
+
+/// Healing threat: every NPC fighting `healed` takes note of `healer`.
+/// Returns how many tables were touched.
+pub fn on_heal(srv: &mut ServerState, healer: ActorId, healed: ActorId, amount: i32) -> usize {
+    let rules = srv.specs.threat;
+    srv.ecs
+        .iter_mut()
+        .filter_map(|c| c.threat.as_mut())
+        .map(|t| t.add_heal(&rules, healer, healed, amount))
+        .filter(|added| *added)
+        .count()
+}
+
+/// `taunter` forces `npc` onto itself: matched to the top threat and made the
+/// target at once. Returns false if `npc` has no AI or is not hostile.
+pub fn taunt(srv: &mut ServerState, taunter: ActorId, npc: ActorId) -> bool {
+    let Some(tf) = srv.ecs.get(taunter).map(|c| c.faction) else {
+        return false;
+    };
+    let factions = &srv.factions;
+    let Some(c) = srv.ecs.get_mut(npc) else {
+        return false;
+    };
+    if !c.hp.alive() || !has_ai(c) || !factions.hostile(npc, c.faction, taunter, tf) {
+        return false;
+    }
+    c.threat.get_or_insert_default().taunt(taunter);
+    metrics::counter!("npc.taunts_total").increment(1);
+    true
+}
+
+#[inline]
+fn dist_xz(a: Vec3, b: Vec3) -> f32 {
+    Vec2::new(a.x - b.x, a.z - b.z).length()
+}
@@ -0,0 +1,322 @@
+//! Weapon attacks (`ClientCmd::Attack`, `data/config/weapons.toml`).
+//!
+//! `enqueue_weapon_attack` checks the command against the attacker's weapons
+//! (innate, or the one in its main hand) and queues it; `weapon_attacks` runs
+//! after `cast_system` and resolves each attack instantly:
+//! - melee swings pick the nearest hostile inside the weapon's arc and reach,
+//!   preferring an explicitly targeted actor;
+//! - ranged shots hit the targeted actor, or the first hostile along the aim
+//!   direction, out to long range.
+//!
+//! Explicit targets must be ones the attacker may harm (`Factions::can_harm`).
+//! `check_weapons` rejects data whose damage dice or crit rule don't parse.
+//!
+//! Hits are rolled with `sim_core::rules::attack::roll_attack` against the
+//! target's `Components::armor_class` (its `ArmorClass` plus worn items and
+//! statuses) using `ServerState::combat_rng`; a worn weapon item adds its
+//! attack bonus. Shots past normal range, or with a hostile in melee reach of
+//! the archer, roll with disadvantage; attacks on stunned or otherwise
+//! helpless targets roll with advantage. Incapacitated actors can't attack.
+//! Targets in dodge i-frames are missed without a roll. Damage is queued as a
+//! typed `DamageEvent` and the result replicates as a `HitFx`.
+
+use anyhow::{Result, bail};
+use data_runtime::specs::weapons::{WeaponKind, WeaponSpec, WeaponSpecDb};
+use glam::Vec3;
+use net_core::command::CastTarget;
+use net_core::snapshot::{HITFX_WEAPON_CRIT, HITFX_WEAPON_HIT, HITFX_WEAPON_MISS, HitFx};
+use sim_core::rules::attack::{Advantage, roll_attack};
+use sim_core::rules::dice::{CritRule, Dice};
+
+use crate::ServerState;
+use crate::actor::ActorId;
+use crate::ecs::geom::segment_hits_circle_xz;
+use crate::ecs::schedule::{Ctx, DamageEvent};
+
+/// Armor Class of actors without an `ArmorClass` component.
+pub const DEFAULT_AC: i32 = 10;
+/// Hostiles this close (past both radii) impose disadvantage on ranged shots.
+const ADJACENT_M: f32 = 1.5;
+
+/// Check that every weapon's `damage` is an `NdM[+K]` expression and its
+/// `crit` a known `CritRule`.
+pub fn check_weapons(db: &WeaponSpecDb) -> Result<()> {
+    for (id, w) in &db.weapons {
+        if Dice::try_parse(&w.damage).is_none() {
+            bail!("weapon '{id}': damage '{}' is not NdM[+K]", w.damage);
+        }
+        if CritRule::try_parse(&w.crit).is_none() {
+            bail!("weapon '{id}': unknown crit rule '{}'", w.crit);
+        }
+    }
+    Ok(())
+}
+
+/// A validated weapon attack waiting for `weapon_attacks`.
+#[derive(Clone, Debug)]
+pub struct AttackCmd {
+    pub attacker: ActorId,
+    pub weapon: String,
+    /// Explicit target, if the client named one.
+    pub target: Option<ActorId>,
+    /// Unit aim direction on the XZ plane.
+    pub dir: Vec3,
+}
+
+/// Why an `Attack` could not be queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackReject {
+    UnknownWeapon,
+    NotEquipped,
+    BadTarget,
+    OutOfRange,
+    NoAttacker,
+}
+
+impl AttackReject {
+    /// Short label for metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UnknownWeapon => "unknown_weapon",
+            Self::NotEquipped => "not_equipped",
+            Self::BadTarget => "bad_target",
+            Self::OutOfRange => "out_of_range",
+            Self::NoAttacker => "no_attacker",
+        }
+    }
+}
+
+impl ServerState {
+    /// Validate a `ClientCmd::Attack` for `attacker` and queue it for
+    /// `weapon_attacks`. Ground targets aim at the point; self targets use
+    /// the attacker's facing.
+    pub fn enqueue_weapon_attack(
+        &mut self,
+        attacker: ActorId,
+        weapon_id: &str,
+        target: CastTarget,
+    ) -> Result<(), AttackReject> {
+        let spec = self
+            .specs_weapons
+            .get(weapon_id)
+            .ok_or(AttackReject::UnknownWeapon)?;
+        let c = self
+            .ecs
+            .get(attacker)
+            .filter(|c| c.hp.alive())
+            .ok_or(AttackReject::NoAttacker)?;
+        if !c
+            .weapons
+            .as_ref()
+            .is_some_and(|w| w.known.iter().any(|k| k == weapon_id))
+            && !c.wields(weapon_id)
+        {
+            return Err(AttackReject::NotEquipped);
+        }
+        let origin = c.tr.pos;
+        let facing = Vec3::new(c.tr.yaw.sin(), 0.0, c.tr.yaw.cos());
+        let flat = |v: Vec3| Vec3::new(v.x, 0.0, v.z).try_normalize();
+        let (dir, target) = match target {
+            CastTarget::SelfCast => (facing, None),
+            CastTarget::Direction(d) => (flat(Vec3::from(d)).unwrap_or(facing), None),
+            CastTarget::Ground(p) => (flat(Vec3::from(p) - origin).unwrap_or(facing), None),
+            CastTarget::Actor(id) => {
+                let t = self
+                    .ecs
+                    .get(ActorId(id))
+                    .filter(|t| {
+                        t.hp.alive()
+                            && t.id != attacker
+                            && t.projectile.is_none()
+                            && self.factions.can_harm(attacker, c.faction, t.id, t.faction)
+                    })
+                    .ok_or(AttackReject::BadTarget)?;
+                let gap = xz_dist(origin, t.tr.pos) - c.tr.radius - t.tr.radius;
+                if gap > spec.max_range_m() {
+                    return Err(AttackReject::OutOfRange);
+                }
+                (flat(t.tr.pos - origin).unwrap_or(facing), Some(t.id))
+            }
+        };
+        self.pending_attacks.push(AttackCmd {
+            attacker,
+            weapon: weapon_id.to_string(),
+            target,
+            dir,
+        });
+        Ok(())
+    }
+}
+
+#[inline]
+fn xz_dist(a: Vec3, b: Vec3) -> f32 {
+    let (dx, dz) = (b.x - a.x, b.z - a.z);
+    (dx * dx + dz * dz).sqrt()
+}
+
+/// Resolve queued weapon attacks; see the module docs.
+pub fn weapon_attacks(srv: &mut ServerState, ctx: &mut Ctx) {
+    if srv.pending_attacks.is_empty() {
+        return;
+    }
+    let attacks: Vec<_> = srv.pending_attacks.drain(..).collect();
+    for cmd in attacks {
+        let Some(spec) = srv.specs_weapons.get(&cmd.weapon).cloned() else {
+            continue;
+        };
+        let Some(c) = srv.ecs.get_mut(cmd.attacker) else {
+            continue;
+        };
+        if !c.hp.alive() {
+            continue;
+        }
+        if c.incapacitated() {
+            metrics::counter!("weapon.rejected_total", "reason" => "incapacitated").increment(1);
+            continue;
+        }
+        let Some(w) = c.weapons.as_mut() else {
+            continue;
+        };
+        if w.ready_in_s > 0.0 {
+            metrics::counter!("weapon.rejected_total", "reason" => "cooldown").increment(1);
+            continue;
+        }
+        w.ready_in_s = spec.cooldown_s;
+        c.tr.yaw = cmd.dir.x.atan2(cmd.dir.z);
+        let Some((dst, dist)) = pick_target(srv, &cmd, &spec) else {
+            metrics::counter!("weapon.attacks_total", "result" => "whiff").increment(1);
+            continue;
+        };
+        resolve(srv, ctx, &cmd, dst, dist, &spec);
+    }
+}
+
+/// The actor the attack lands on and its edge distance from the attacker.
+fn pick_target(srv: &ServerState, cmd: &AttackCmd, spec: &WeaponSpec) -> Option<(ActorId, f32)> {
+    let a = srv.ecs.get(cmd.attacker)?;
+    let origin = a.tr.pos;
+    let gap = |p: Vec3, r: f32| (xz_dist(origin, p) - a.tr.radius - r).max(0.0);
+    let candidates = srv.ecs.iter().filter(|t| {
+        t.id != a.id
+            && t.hp.alive()
+            && t.projectile.is_none()
+            && srv.factions.can_harm(a.id, a.faction, t.id, t.faction)
+    });
+    match spec.kind {
+        WeaponKind::Melee => {
+            let half = (spec.arc_deg * 0.5).to_radians();
+            let in_swing = |p: Vec3, r: f32| {
+                let to = Vec3::new(p.x - origin.x, 0.0, p.z - origin.z);
+                let len = to.length();
+                gap(p, r) <= spec.reach_m
+                    && (len <= a.tr.radius + r || to.dot(cmd.dir) >= len * half.cos())
+            };
+            let mut best: Option<(ActorId, f32)> = None;
+            for t in candidates {
+                if !in_swing(t.tr.pos, t.tr.radius) {
+                    continue;
+                }
+                let d = gap(t.tr.pos, t.tr.radius);
+                if Some(t.id) == cmd.target {
+                    return Some((t.id, d));
+                }
+                if best.is_none_or(|(_, b)| d < b) {
+                    best = Some((t.id, d));
+                }
+            }
+            best
+        }
+        WeaponKind::Ranged => {
+            if let Some(t) = cmd.target.and_then(|id| srv.ecs.get(id))
+                && t.hp.alive()
+                && srv.factions.can_harm(a.id, a.faction, t.id, t.faction)
+            {
+                let d = gap(t.tr.pos, t.tr.radius);
+                return (d <= spec.long_range_m).then_some((t.id, d));
+            }
+            let end = origin + cmd.dir * (spec.long_range_m + a.tr.radius);
+            candidates
+                .filter(|t| segment_hits_circle_xz(origin, end, t.tr.pos, t.tr.radius))
+                .map(|t| (t.id, gap(t.tr.pos, t.tr.radius)))
+                .min_by(|l, r| l.1.total_cmp(&r.1))
+        }
+    }
+}
+
+fn resolve(
+    srv: &mut ServerState,
+    ctx: &mut Ctx,
+    cmd: &AttackCmd,
+    dst: ActorId,
+    dist: f32,
+    spec: &WeaponSpec,
+) {
+    let src = cmd.attacker;
+    let (Some(a), Some(t)) = (srv.ecs.get(src), srv.ecs.get(dst)) else {
+        return;
+    };
+    let (faction, pos, radius) = (a.faction, a.tr.pos, a.tr.radius);
+    let attack_bonus = spec.attack_bonus + a.weapon_bonus(&cmd.weapon);
+    let ac = t.armor_class();
+    let target_pos = t.tr.pos;
+    if t.invulnerable() {
+        metrics::counter!("dodge.avoided_total", "source" => "weapon").increment(1);
+        ctx.fx_hits.push(HitFx {
+            kind: HITFX_WEAPON_MISS,
+            pos: target_pos.into(),
+        });
+        return;
+    }
+    let adv = t.attacked_with_advantage();
+    let dis = spec.kind == WeaponKind::Ranged
+        && (dist > spec.range_m
+            || srv.ecs.iter().any(|h| {
+                h.id != src
+                    && h.hp.alive()
+                    && h.projectile.is_none()
+                    && h.melee.is_some()
+                    && srv.factions.hostile(src, faction, h.id, h.faction)
+                    && xz_dist(pos, h.tr.pos) - radius - h.tr.radius <= ADJACENT_M
+            }));
+    let adv = Advantage::from_sources(adv, dis);
+    let roll = roll_attack(&mut srv.combat_rng.0, adv, attack_bonus, ac);
+    let kind = if roll.hit {
+        let amount = Dice::parse(&spec.damage).roll_damage(
+            &mut srv.combat_rng.0,
+            roll.crit,
+            CritRule::parse(&spec.crit),
+        );
+        ctx.dmg.push(DamageEvent {
+            src: Some(src),
+            dst,
+            amount,
+            damage_type: ecs_core::parse::parse_damage_type(&spec.damage_type),
+        });
+        if roll.crit {
+            HITFX_WEAPON_CRIT
+        } else {
+            HITFX_WEAPON_HIT
+        }
+    } else {
+        HITFX_WEAPON_MISS
+    };
+    let result = match kind {
+        HITFX_WEAPON_CRIT => "crit",
+        HITFX_WEAPON_HIT => "hit",
+        _ => "miss",
+    };
+    metrics::counter!("weapon.attacks_total", "result" => result).increment(1);
+    log::debug!(
+        "server: {:?} attacks {:?}: d20 {} {:+} = {} vs AC {} ({adv:?}) -> {result}",
+        src,
+        dst,
+        roll.d20,
+        roll.bonus,
+        roll.total,
+        roll.ac
+    );
+    ctx.fx_hits.push(HitFx {
+        kind,
+        pos: target_pos.into(),
+    });
+}
@@ -4,8 +4,10 @@ use anyhow::Result;
 
 pub struct TelemetryGuard;
 
-pub fn init_telemetry(cfg: &data_runtime::configs::telemetry::TelemetryCfg) -> Result<TelemetryGuard> {
-    use tracing_subscriber::{fmt, EnvFilter, prelude::*};
+pub fn init_telemetry(
+    cfg: &data_runtime::configs::telemetry::TelemetryCfg,
+) -> Result<TelemetryGuard> {
+    use tracing_subscriber::{EnvFilter, fmt, prelude::*};
     let level = cfg.log_level.clone().unwrap_or_else(|| "info".to_string());
     let filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new("info"));
     // Console JSON by default
@@ -26,7 +28,8 @@ pub fn init_telemetry(cfg: &data_runtime::configs::telemetry::TelemetryCfg) -> R
                 Ok(a) => a,
                 Err(_e) => {
                     // Fallback to a safe default and record an error counter
-                    metrics::counter!("server.errors_total", "site" => "telemetry.parse_addr").increment(1);
+                    metrics::counter!("server.errors_total", "site" => "telemetry.parse_addr")
+                        .increment(1);
                     std::net::SocketAddr::from(([127, 0, 0, 1], 9100))
                 }
             };
@@ -27,7 +27,7 @@ pub fn tick_destructibles(
         let mut reqs = Vec::new();
         std::mem::swap(&mut reqs, pending_carves);
         for req in reqs {
-            let _touched = voxel_carve(grid, &req, cfg, dirty);
+            let _cleared = voxel_carve(grid, &req, cfg, dirty);
             carves_applied += 1;
         }
     }
@@ -1,31 +1,243 @@
 //! Zone boot/apply helpers. Server-authoritative spawn logic lives here.
 //!
 //! Policy: Platform and renderer must not spawn gameplay. Only zones do.
+//!
+//! Zone content is data: `data/zones/<slug>/encounter.json` lists props and
+//! NPCs by archetype and named spawn point, and `scene.json` places those
+//! points (`logic.spawns` markers first, then `logic.waypoints`). Archetypes
+//! resolve through `ArchetypeSpecDb` (`spawn_archetype`); unique bosses load
+//! their own configs.
+//! Each NPC entry becomes a spawn group (`systems::spawn_groups`) carrying
+//! its respawn timer, population cap, leash and idle wander/patrol.
+//! A zone is planned in full before anything spawns, so a bad reference
+//! leaves the server empty rather than half-populated.
+//! The encounter's `destructibles` block sets how the zone's carved ruins
+//! regrow (`destructible::state::RegrowPolicy`); without it they never do.
+
+use anyhow::{Context, Result, anyhow, bail};
+use data_runtime::encounter::EncounterSpec;
+use data_runtime::zone_scene::ZoneScene;
+use glam::Vec3;
 
 use crate::ServerState;
+use crate::actor::Faction;
+use crate::destructible::state::RegrowPolicy;
+use crate::systems::spawn_groups::{self, NpcTemplate, SpawnGroup};
 
 /// Boot a server for the given zone slug by applying its initial logic.
 /// Returns `true` if any zone-specific content was spawned.
 pub fn boot_with_zone(srv: &mut ServerState, slug: &str) -> bool {
-    match slug {
-        // Demo content zone. Keep spawns deterministic and minimal.
-        "wizard_woods" => {
-            // Spawn a few NPC rings and the demo destructible ruins as before.
-            srv.ring_spawn(8, 15.0, 20);
-            srv.ring_spawn(12, 30.0, 25);
-            srv.ring_spawn(15, 45.0, 30);
-            let wiz_count = 4usize;
-            let wiz_r = 8.0f32;
-            for i in 0..wiz_count {
-                let a = (i as f32) / (wiz_count as f32) * std::f32::consts::TAU;
-                let p = glam::vec3(wiz_r * a.cos(), 0.6, wiz_r * a.sin());
-                let _ = srv.spawn_wizard_npc(p);
+    let plan = match plan_zone(srv, slug) {
+        Ok(Some(p)) => p,
+        Ok(None) => return false,
+        Err(e) => {
+            log::warn!("zones: '{slug}' not booted: {e:#}");
+            return false;
+        }
+    };
+    apply_plan(srv, &plan) > 0
+}
+
+/// Everything a zone spawns at boot, resolved to world positions.
+#[derive(Debug, Clone, Default)]
+pub struct ZonePlan {
+    pub pc_spawn: Option<Vec3>,
+    pub props: Vec<PlannedProp>,
+    /// One group per encounter NPC entry, in spec order.
+    pub groups: Vec<SpawnGroup>,
+    pub regrow: RegrowPolicy,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedProp {
+    pub kind: String,
+    pub pos: Option<Vec3>,
+}
+
+/// Load and resolve a zone's encounter. `Ok(None)` when the zone has no
+/// encounter spec (it spawns nothing).
+pub fn plan_zone(srv: &ServerState, slug: &str) -> Result<Option<ZonePlan>> {
+    let Some(enc) = data_runtime::encounter::find_encounter_for_zone(slug)? else {
+        return Ok(None);
+    };
+    let scene = data_runtime::zone_scene::load_zone_scene(slug)?;
+    plan_encounter(srv, &enc, scene.as_ref())
+        .with_context(|| format!("zone '{slug}' encounter"))
+        .map(Some)
+}
+
+/// Resolve an encounter's spawn points, archetypes and factions.
+pub fn plan_encounter(
+    srv: &ServerState,
+    enc: &EncounterSpec,
+    scene: Option<&ZoneScene>,
+) -> Result<ZonePlan> {
+    let points = SpawnPoints::new(scene);
+    let mut plan = ZonePlan::default();
+    if let Some(name) = &enc.pc_spawn {
+        plan.pc_spawn = Some(points.get(name)?.pos);
+    }
+    for p in &enc.props {
+        if p.kind != "demo_ruins" {
+            bail!("unknown prop kind '{}'", p.kind);
+        }
+        let pos = match &p.spawn {
+            Some(name) => Some(points.get(name)?.pos),
+            None => None,
+        };
+        plan.props.push(PlannedProp {
+            kind: p.kind.clone(),
+            pos,
+        });
+    }
+    if let Some(d) = &enc.destructibles {
+        if let Some(after) = d.regrow_after_s
+            && (after < 0.0 || d.regrow_voxels_per_s <= 0.0)
+        {
+            bail!("destructibles: regrowth needs regrow_after_s >= 0 and regrow_voxels_per_s > 0");
+        }
+        plan.regrow = RegrowPolicy {
+            after_s: d.regrow_after_s,
+            voxels_per_s: d.regrow_voxels_per_s,
+        };
+    }
+    for n in &enc.npcs {
+        let Some(spec) = srv.specs_arche.entries.get(&n.archetype) else {
+            bail!("unknown archetype '{}'", n.archetype);
+        };
+        if spec.unique && !n.unique {
+            bail!(
+                "archetype '{}' is unique; set \"unique\": true",
+                n.archetype
+            );
+        }
+        let faction = n
+            .faction
+            .as_deref()
+            .map(|f| parse_faction(srv, f))
+            .transpose()?;
+        let pt = points
+            .get(&n.spawn)
+            .with_context(|| format!("npc '{}'", n.archetype))?;
+        let count = if n.unique { 1 } else { n.count.max(1) };
+        let slots = (0..count)
+            .map(|i| {
+                let a = (i as f32) / (count as f32) * std::f32::consts::TAU;
+                pt.pos + Vec3::new(pt.radius * a.cos(), 0.0, pt.radius * a.sin())
+            })
+            .collect();
+        let ai = enc.ai.as_ref();
+        let mut g = SpawnGroup::new(
+            NpcTemplate {
+                archetype: n.archetype.clone(),
+                yaw: pt.yaw,
+                faction,
+                hp: n.hp,
+                aggro_m: ai.map(|a| a.aggro_radius_m).filter(|m| *m > 0.0),
+                melee_cooldown_s: ai.map(|a| a.attack_cooldown_s).filter(|s| *s > 0.0),
+            },
+            slots,
+        );
+        if let Some(cap) = n.max_alive {
+            g.max_alive = cap as usize;
+        }
+        // Unique bosses are one-offs and never respawn.
+        g.respawn_s = n.respawn_s.filter(|_| !n.unique);
+        g.leash_m = n.leash_m;
+        g.wander_m = n.wander_m.max(0.0);
+        g.patrol = n
+            .patrol
+            .iter()
+            .map(|w| points.get(w).map(|p| p.pos))
+            .collect::<Result<_>>()
+            .with_context(|| format!("npc '{}' patrol", n.archetype))?;
+        plan.groups.push(g);
+    }
+    Ok(plan)
+}
+
+/// Spawn a resolved plan. Props go first so NPC placement avoids them.
+/// Returns the number of props and actors spawned.
+pub fn apply_plan(srv: &mut ServerState, plan: &ZonePlan) -> usize {
+    let mut spawned = 0;
+    srv.destruct_registry.regrow = plan.regrow;
+    for p in &plan.props {
+        match p.pos {
+            Some(c) => {
+                let did = srv
+                    .destruct_instances
+                    .iter()
+                    .map(|d| d.did)
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                crate::scene_build::add_demo_ruins_destructible_at(srv, c, did);
+            }
+            None => crate::scene_build::add_demo_ruins_destructible(srv),
+        }
+        spawned += 1;
+    }
+    for g in &plan.groups {
+        spawned += spawn_groups::add_group(srv, g.clone());
+    }
+    spawned
+}
+
+/// The zone's player spawn point, if its encounter names one.
+pub fn pc_spawn(srv: &ServerState, slug: &str) -> Option<Vec3> {
+    plan_zone(srv, slug).ok().flatten()?.pc_spawn
+}
+
+fn parse_faction(srv: &ServerState, s: &str) -> Result<Faction> {
+    srv.factions
+        .registry
+        .id(s)
+        .ok_or_else(|| anyhow!("unknown faction '{s}'"))
+}
+
+#[derive(Clone, Copy)]
+struct SpawnPoint {
+    pos: Vec3,
+    yaw: f32,
+    radius: f32,
+}
+
+/// Named points from a scene: spawn markers shadow waypoints of the same id.
+struct SpawnPoints(Vec<(String, SpawnPoint)>);
+
+impl SpawnPoints {
+    fn new(scene: Option<&ZoneScene>) -> Self {
+        let mut v = Vec::new();
+        if let Some(s) = scene {
+            for m in s.logic.spawn_markers() {
+                v.push((
+                    m.id,
+                    SpawnPoint {
+                        pos: Vec3::from_array(m.pos),
+                        yaw: m.yaw_deg.to_radians(),
+                        radius: m.radius_m.max(0.0),
+                    },
+                ));
+            }
+            for w in s.logic.waypoint_markers() {
+                v.push((
+                    w.id,
+                    SpawnPoint {
+                        pos: Vec3::from_array(w.pos),
+                        yaw: 0.0,
+                        radius: 0.0,
+                    },
+                ));
             }
-            let _ = srv.spawn_nivita_unique(glam::vec3(0.0, 0.6, 0.0));
-            let _dk = srv.spawn_death_knight(glam::vec3(60.0, 0.6, 0.0));
-            crate::scene_build::add_demo_ruins_destructible(srv);
-            true
         }
-        _ => false,
+        Self(v)
+    }
+
+    fn get(&self, name: &str) -> Result<SpawnPoint> {
+        self.0
+            .iter()
+            .find(|(id, _)| id == name)
+            .map(|(_, p)| *p)
+            .ok_or_else(|| anyhow!("unknown spawn point '{name}'"))
     }
 }
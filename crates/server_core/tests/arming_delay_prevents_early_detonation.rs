@@ -5,7 +5,7 @@ fn arming_delay_prevents_early_hit() {
     let mut s = server_core::ServerState::new();
     let wid = s.ecs.spawn(
         server_core::ActorKind::Wizard,
-        server_core::Faction::Wizards,
+        server_core::Faction::WIZARDS,
         server_core::Transform {
             pos: vec3(0.0, 0.6, 0.0),
             yaw: 0.0,
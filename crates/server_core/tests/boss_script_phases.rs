@@ -0,0 +1,312 @@
+#![allow(clippy::unwrap_used)]
+//! Scripted bosses run headlessly: the pull starts the script, rotation
+//! abilities telegraph before they hit, HP thresholds advance phases and spawn
+//! adds, the enrage timer scales damage, losing all targets resets the
+//! encounter, and active telegraphs replicate to clients.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{Client, Stall};
+use data_runtime::specs::boss_scripts::BossScriptDb;
+use glam::{Vec3, vec3};
+use net_core::link::Endpoint;
+use net_core::snapshot::{
+    SnapshotDecode, TAG_TELEGRAPH, TELEGRAPH_CIRCLE, TELEGRAPH_CONE, TelegraphMsg,
+};
+use net_core::transport::LocalLoopbackTransport;
+use server_core::ServerState;
+use server_core::actor::ActorId;
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::boss::{BossRun, telegraph_reps};
+
+const DT: f32 = 1.0 / 30.0;
+
+const SCRIPT: &str = r#"
+    id = "test_boss"
+    enrage_s = 20.0
+    enrage_damage_mul = 2.0
+
+    [abilities.slam]
+    shape = "circle"
+    radius_m = 5.0
+    windup_s = 1.0
+    damage = 10
+
+    [abilities.flay]
+    shape = "cone"
+    radius_m = 10.0
+    angle_deg = 60.0
+    windup_s = 1.0
+    damage = 15
+
+    [[phases]]
+    name = "one"
+    hp_pct = 100.0
+    rotation = ["slam"]
+    interval_s = 2.0
+
+    [[phases]]
+    name = "two"
+    hp_pct = 50.0
+    rotation = ["flay"]
+    interval_s = 2.0
+    adds = [{ archetype = "Undead", count = 2, radius_m = 8.0 }]
+"#;
+
+/// A stationary Undead at the origin running `script`.
+fn scripted(s: &mut ServerState, script: &str) -> ActorId {
+    let z = s.spawn_undead(Vec3::new(0.0, 0.6, 0.0), 0.9, 1000);
+    let c = s.ecs.get_mut(z).unwrap();
+    c.move_speed = None;
+    c.boss = Some(BossRun::new(Arc::new(BossScriptDb::parse(script).unwrap())));
+    z
+}
+
+fn run(s: &ServerState, boss: ActorId) -> &BossRun {
+    s.ecs.get(boss).unwrap().boss.as_ref().unwrap()
+}
+
+fn hp(s: &ServerState, id: ActorId) -> i32 {
+    s.ecs.get(id).unwrap().hp.hp
+}
+
+/// Step until `done` holds; panics after `max_s` seconds.
+fn step_until(s: &mut ServerState, max_s: f32, done: impl Fn(&ServerState) -> bool) {
+    for _ in 0..(max_s / DT) as usize {
+        if done(s) {
+            return;
+        }
+        s.step_authoritative(DT);
+    }
+    assert!(done(s), "condition not reached in {max_s}s");
+}
+
+fn undead_count(s: &ServerState) -> usize {
+    let nid = s.specs_arche.entries["Undead"].net_id;
+    s.ecs
+        .iter()
+        .filter(|a| a.hp.alive() && a.archetype_id == nid)
+        .count()
+}
+
+#[test]
+fn rotation_telegraphs_then_hits_inside_the_shape() {
+    let mut s = ServerState::new();
+    let boss = scripted(&mut s, SCRIPT);
+    s.ecs.get_mut(boss).unwrap().melee = None;
+    let near = s.spawn_pc(vec3(3.0, 0.6, 0.0));
+    let far = s.spawn_pc(vec3(12.0, 0.6, 0.0));
+    s.step_authoritative(DT);
+    assert!(run(&s, boss).engaged, "the pull starts the script");
+    assert!(telegraph_reps(&s).is_empty());
+
+    step_until(&mut s, 3.0, |s| !telegraph_reps(s).is_empty());
+    let reps = telegraph_reps(&s);
+    assert_eq!(reps.len(), 1);
+    assert_eq!((reps[0].owner, reps[0].shape), (boss.0, TELEGRAPH_CIRCLE));
+    assert_eq!(reps[0].total_ms, 1000);
+    assert_eq!(hp(&s, near), 100, "nothing lands during the wind-up");
+
+    step_until(&mut s, 1.5, |s| telegraph_reps(s).is_empty());
+    s.step_authoritative(DT);
+    assert_eq!(hp(&s, near), 90);
+    assert_eq!(hp(&s, far), 100, "outside the circle");
+}
+
+#[test]
+fn hp_thresholds_advance_phases_and_spawn_adds() {
+    let mut s = ServerState::new();
+    let boss = scripted(&mut s, SCRIPT);
+    let _pc = s.spawn_pc(vec3(3.0, 0.6, 0.0));
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase_name(), "one");
+    let before = undead_count(&s);
+
+    s.ecs.get_mut(boss).unwrap().hp.hp = 510;
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase, 0, "51% stays in phase one");
+
+    s.ecs.get_mut(boss).unwrap().hp.hp = 500;
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase_name(), "two");
+    assert_eq!(undead_count(&s), before + 2, "phase adds spawned");
+    let reps = telegraph_reps(&s);
+    assert_eq!(reps.len(), 1, "the new rotation starts at once");
+    assert_eq!(reps[0].shape, TELEGRAPH_CONE);
+
+    // Healing back up does not rewind the encounter.
+    s.ecs.get_mut(boss).unwrap().hp.hp = 1000;
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase, 1);
+}
+
+#[test]
+fn cone_hits_in_front_only() {
+    let mut s = ServerState::new();
+    let boss = scripted(&mut s, SCRIPT);
+    s.ecs.get_mut(boss).unwrap().hp.hp = 400;
+    let front = s.spawn_pc(vec3(4.0, 0.6, 0.0));
+    s.step_authoritative(DT);
+    let t = run(&s, boss).telegraphs[0].clone();
+    assert_eq!(t.ability, "flay");
+    assert!(t.contains(vec3(4.0, 0.6, 0.0), 0.7));
+    assert!(
+        t.contains(vec3(8.0, 0.6, 3.0), 0.7),
+        "inside the 60 degree arc"
+    );
+    assert!(!t.contains(vec3(-4.0, 0.6, 0.0), 0.7), "behind the boss");
+    assert!(!t.contains(vec3(0.0, 0.6, 6.0), 0.7), "off to the side");
+    assert!(!t.contains(vec3(14.0, 0.6, 0.0), 0.7), "past the reach");
+    assert_eq!(s.ecs.get(front).unwrap().hp.hp, 100);
+}
+
+#[test]
+fn enrage_scales_damage_and_losing_targets_resets() {
+    let mut s = ServerState::new();
+    let script = SCRIPT.replace("enrage_s = 20.0", "enrage_s = 3.0");
+    let boss = scripted(&mut s, &script);
+    let melee = s.ecs.get(boss).unwrap().melee.unwrap().damage;
+    let pc = s.spawn_pc(vec3(6.0, 0.6, 0.0));
+    step_until(&mut s, 3.5, |s| run(s, boss).enraged);
+    assert_eq!(s.ecs.get(boss).unwrap().melee.unwrap().damage, melee * 2);
+
+    // The next slam lands at double damage.
+    step_until(&mut s, 3.0, |s| !telegraph_reps(s).is_empty());
+    s.ecs.get_mut(pc).unwrap().tr.pos = vec3(3.0, 0.6, 0.0);
+    step_until(&mut s, 1.5, |s| telegraph_reps(s).is_empty());
+    s.step_authoritative(DT);
+    assert_eq!(hp(&s, pc), 80);
+
+    // Phase two brings adds.
+    let before = undead_count(&s);
+    s.ecs.get_mut(boss).unwrap().hp.hp = 500;
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase, 1);
+    assert_eq!(undead_count(&s), before + 2);
+
+    // Walking out of aggro with no threat earned drops the boss's table.
+    s.ecs.get_mut(pc).unwrap().tr.pos = vec3(200.0, 0.6, 0.0);
+    s.step_authoritative(DT);
+    s.step_authoritative(DT);
+    let r = run(&s, boss);
+    assert!(!r.engaged && !r.enraged && r.telegraphs.is_empty());
+    assert_eq!((r.phase, r.elapsed_s), (0, 0.0));
+    assert_eq!(s.ecs.get(boss).unwrap().melee.unwrap().damage, melee);
+    assert_eq!(hp(&s, boss), 1000, "the boss heals on reset");
+    assert_eq!(undead_count(&s), before, "and its adds are gone");
+
+    // The next pull starts over in phase one without new adds.
+    s.ecs.get_mut(pc).unwrap().tr.pos = vec3(6.0, 0.6, 0.0);
+    step_until(&mut s, 2.0, |s| run(s, boss).engaged);
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase, 0);
+    assert_eq!(undead_count(&s), before);
+}
+
+#[test]
+fn nivita_runs_her_data_script() {
+    let mut s = ServerState::new();
+    let boss = s.spawn_nivita_unique(vec3(0.0, 0.6, 0.0)).unwrap();
+    assert_eq!(run(&s, boss).script.id, "nivita");
+    let pos = s.ecs.get(boss).unwrap().tr.pos;
+    let _pc = s.spawn_pc(pos + vec3(5.0, 0.0, 0.0));
+    s.step_authoritative(DT);
+    assert!(run(&s, boss).engaged);
+    let second = run(&s, boss).script.phases[1].clone();
+    let before = undead_count(&s);
+
+    let c = s.ecs.get_mut(boss).unwrap();
+    c.hp.hp = (c.hp.max as f32 * (second.hp_pct - 1.0) / 100.0) as i32;
+    s.step_authoritative(DT);
+    assert_eq!(run(&s, boss).phase_name(), second.name);
+    let adds: u32 = second.adds.iter().map(|a| a.count).sum();
+    assert_eq!(undead_count(&s), before + adds as usize);
+}
+
+#[test]
+fn telegraphs_replicate_and_clear_on_the_wire() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig::default());
+    let boss = scripted(&mut srv, SCRIPT);
+    srv.ecs.get_mut(boss).unwrap().melee = None;
+    let (srv_end, xport) = LocalLoopbackTransport::new(1024);
+    let id = host.connect(&mut srv, Box::new(srv_end));
+    let me = host.actor_of(id).unwrap();
+    srv.ecs.get_mut(me).unwrap().tr.pos = vec3(3.0, 0.6, 0.0);
+
+    let mut link = Endpoint::default();
+    let mut seen: Vec<(u64, TelegraphMsg)> = Vec::new();
+    let ticks = (4.0 / DT) as u64;
+    for t in 0..ticks {
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(DT);
+        host.broadcast(&mut srv);
+        link.pump(&xport, t * 33).unwrap();
+        while let Some((_, payload)) = link.recv() {
+            if payload.first() == Some(&TAG_TELEGRAPH) {
+                seen.push((t, TelegraphMsg::decode(&mut payload.as_slice()).unwrap()));
+            }
+        }
+    }
+    let first = seen.iter().position(|(_, m)| !m.items.is_empty()).unwrap();
+    assert_eq!(seen[first].1.items[0].owner, boss.0);
+    let remaining: Vec<u16> = seen
+        .iter()
+        .filter_map(|(_, m)| m.items.first())
+        .map(|t| t.remaining_ms)
+        .collect();
+    assert!(
+        remaining.windows(2).all(|w| w[1] <= w[0]),
+        "wind-up counts down"
+    );
+    let cleared = seen.iter().position(|(_, m)| m.items.is_empty()).unwrap();
+    assert!(
+        seen[cleared..].iter().all(|(_, m)| m.items.is_empty()),
+        "resolved telegraph cleared"
+    );
+    // Without acks the clear repeats for a while, then the stream goes quiet.
+    let clears = seen.len() - cleared;
+    assert!(clears > 1);
+    assert!(
+        seen.last().unwrap().0 < ticks - 1,
+        "one clear burst, then silence"
+    );
+}
+
+#[test]
+fn a_stalled_update_cannot_bring_back_a_cleared_telegraph() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig::default());
+    let boss = scripted(&mut srv, SCRIPT);
+    srv.ecs.get_mut(boss).unwrap().melee = None;
+    let (id, mut c, stall) = Stall::connect(&mut host, &mut srv);
+    let me = host.actor_of(id).unwrap();
+    srv.ecs.get_mut(me).unwrap().tr.pos = vec3(3.0, 0.6, 0.0);
+    let mut tick = |srv: &mut ServerState, c: &mut Client| {
+        host.pump_inputs(srv);
+        srv.step_authoritative(DT);
+        host.broadcast(srv);
+        c.replicate(33);
+    };
+
+    for _ in 0..(3.0 / DT) as usize {
+        if !c.rep.telegraphs.is_empty() {
+            break;
+        }
+        tick(&mut srv, &mut c);
+    }
+    assert!(!c.rep.telegraphs.is_empty());
+    // Stall the last updates; the clear gets through first.
+    stall.hold(true);
+    while !telegraph_reps(&srv).is_empty() {
+        tick(&mut srv, &mut c);
+    }
+    stall.hold(false);
+    tick(&mut srv, &mut c);
+    assert!(c.rep.telegraphs.is_empty());
+    stall.release();
+    c.replicate(33);
+    assert!(c.rep.telegraphs.is_empty(), "stale update dropped");
+}
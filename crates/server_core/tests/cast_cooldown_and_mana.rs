@@ -81,3 +81,21 @@ fn magic_missile_cd_and_mana_enforced() {
         "MM should accept and cost mana after CD expires"
     );
 }
+
+#[test]
+fn gcd_comes_from_the_cast_ability() {
+    let mut s = server_core::ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    // The caster's own GCD must not override the spell's.
+    s.ecs.get_mut(pc).unwrap().cooldowns.as_mut().unwrap().gcd_s = 5.0;
+    let gcd_s = s.abilities.get(server_core::SpellId::Firebolt).unwrap().gcd_s;
+    assert!(gcd_s < 5.0);
+    s.enqueue_cast(
+        vec3(0.0, 0.6, 0.0),
+        vec3(0.0, 0.0, 1.0),
+        server_core::SpellId::Firebolt,
+    );
+    s.step_authoritative(0.016);
+    let ready = s.ecs.get(pc).unwrap().cooldowns.as_ref().unwrap().gcd_ready;
+    assert!(ready > 0.0 && ready <= gcd_s, "gcd_ready {ready} vs {gcd_s}");
+}
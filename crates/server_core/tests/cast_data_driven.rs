@@ -0,0 +1,198 @@
+#![allow(clippy::unwrap_used)]
+//! `ClientCmd::Cast` resolves everything from the spell spec: the ability id
+//! picks cost, cooldown and projectile parameters, and the target is checked
+//! against the spell's targeting and range. A spell added as data alone is
+//! castable without any code change.
+
+use glam::Vec3;
+use net_core::command::{CastTarget, ClientCmd};
+use net_core::link::{Channel, Endpoint};
+use net_core::snapshot::{HudStatusMsg, SnapshotDecode, TAG_HUD_STATUS};
+use net_core::transport::LocalLoopbackTransport;
+use server_core::abilities::{AbilityDb, CastReject};
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::{ServerState, SpellId};
+
+const DT: f32 = 1.0 / 30.0;
+
+fn projectiles(s: &ServerState) -> Vec<(Vec3, Vec3)> {
+    s.ecs
+        .iter()
+        .filter(|c| c.projectile.is_some())
+        .filter_map(|c| c.velocity.as_ref().map(|v| (c.tr.pos, v.v)))
+        .collect()
+}
+
+#[test]
+fn builtin_ability_ids_cast_from_the_server_position() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(Vec3::new(3.0, 0.6, -2.0));
+    let spell = s
+        .enqueue_ability_cast(
+            pc,
+            "wiz.fire_bolt.srd521",
+            CastTarget::Direction([1.0, 0.0, 0.0]),
+        )
+        .unwrap();
+    assert_eq!(spell, SpellId::Firebolt);
+    s.step_authoritative(DT);
+    let p = projectiles(&s);
+    assert_eq!(p.len(), 1);
+    let (pos, vel) = p[0];
+    assert!(
+        (pos.z + 2.0).abs() < 0.01,
+        "origin is the caster, got {pos}"
+    );
+    assert!(vel.x > 0.0 && vel.z.abs() < 1e-3);
+    // Cooldown from the ability entry.
+    let cd = s.ecs.get(pc).unwrap().cooldowns.as_ref().unwrap();
+    let want = s.abilities.get(SpellId::Firebolt).unwrap().cooldown_s;
+    assert!((cd.per_spell[&SpellId::Firebolt] - want).abs() < 1e-4);
+    // Short ids and display names resolve to the same ability.
+    assert_eq!(
+        s.abilities.resolve("fire_bolt").unwrap().spell,
+        SpellId::Firebolt
+    );
+    assert_eq!(
+        s.abilities.resolve("Fireball").unwrap().spell,
+        SpellId::Fireball
+    );
+}
+
+#[test]
+fn targets_are_checked_against_targeting_and_range() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(Vec3::new(0.0, 0.6, 0.0));
+    let near = s.spawn_undead(Vec3::new(0.0, 0.6, 10.0), 0.9, 30);
+    let far = s.spawn_undead(Vec3::new(0.0, 0.6, 200.0), 0.9, 30);
+    let fb = "wiz.fire_bolt.srd521";
+    assert_eq!(
+        s.enqueue_ability_cast(pc, "wiz.nope.srd521", CastTarget::SelfCast),
+        Err(CastReject::UnknownAbility)
+    );
+    assert_eq!(
+        s.enqueue_ability_cast(pc, fb, CastTarget::SelfCast),
+        Err(CastReject::BadTarget)
+    );
+    assert_eq!(
+        s.enqueue_ability_cast(pc, fb, CastTarget::Actor(far.0)),
+        Err(CastReject::OutOfRange)
+    );
+    // Fireball wants a ground point, not an actor.
+    assert_eq!(
+        s.enqueue_ability_cast(pc, "wiz.fireball.srd521", CastTarget::Actor(near.0)),
+        Err(CastReject::BadTarget)
+    );
+    assert!(s.pending_casts.is_empty());
+
+    s.enqueue_ability_cast(pc, fb, CastTarget::Actor(near.0))
+        .unwrap();
+    let cmd = s.pending_casts.last().unwrap();
+    assert!((cmd.dir - Vec3::Z).length() < 1e-4, "aimed at the target");
+}
+
+#[test]
+fn spells_without_server_delivery_are_refused_without_cost() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(Vec3::new(0.0, 0.6, 0.0));
+    let shield = s.abilities.resolve("shield").unwrap().spell;
+    s.ecs
+        .get_mut(pc)
+        .unwrap()
+        .spellbook
+        .as_mut()
+        .unwrap()
+        .known
+        .push(shield);
+    s.enqueue_ability_cast(pc, "wiz.shield.srd521", CastTarget::SelfCast)
+        .unwrap();
+    s.step_authoritative(DT);
+    assert!(projectiles(&s).is_empty());
+    let cd = s.ecs.get(pc).unwrap().cooldowns.as_ref().unwrap();
+    assert!(cd.gcd_ready <= 0.0, "a refused cast must not start the GCD");
+}
+
+#[test]
+fn new_data_spell_casts_over_the_wire() {
+    let mut srv = ServerState::new();
+    // A new spell defined only as data (derived from Fire Bolt's spec).
+    let mut spec = data_runtime::specdb::SpecDb::load_default()
+        .get_spell("wiz.fire_bolt.srd521")
+        .cloned()
+        .unwrap();
+    spec.id = "wiz.frost_lance.test".into();
+    spec.name = "Frost Lance".into();
+    spec.cooldown_s = 3.0;
+    spec.projectile.as_mut().unwrap().speed_mps = 12.0;
+    let lance = srv.abilities.insert(&spec);
+    assert!(matches!(lance, SpellId::Data(_)));
+
+    let mut host = SessionHost::new(SessionConfig {
+        interest_radius_m: 100.0,
+        ..Default::default()
+    });
+    let (srv_end, cli_end) = LocalLoopbackTransport::new(256);
+    let id = host.connect(&mut srv, Box::new(srv_end));
+    let pc = host.actor_of(id).unwrap();
+    let pc_c = srv.ecs.get_mut(pc).unwrap();
+    pc_c.spellbook.as_mut().unwrap().known.push(lance);
+
+    let mut link = Endpoint::default();
+    let mut p = Vec::new();
+    ClientCmd::Cast {
+        ability_id: "frost_lance".into(),
+        target: CastTarget::Direction([0.0, 0.0, 1.0]),
+    }
+    .encode(&mut p);
+    link.send(Channel::Reliable, p).unwrap();
+    link.pump(&cli_end, 0).unwrap();
+    host.pump_inputs(&mut srv);
+    srv.step_authoritative(DT);
+
+    let p = projectiles(&srv);
+    assert_eq!(p.len(), 1);
+    assert!((p[0].1.length() - 12.0).abs() < 1e-3, "speed from the spec");
+    let cd = srv.ecs.get(pc).unwrap().cooldowns.as_ref().unwrap();
+    assert!(
+        (cd.per_spell[&lance] - 3.0).abs() < 1e-4,
+        "cooldown from the spec"
+    );
+
+    // The HUD's cooldown slots follow the spellbook, so the new spell shows
+    // up after the three built-ins.
+    host.broadcast(&mut srv);
+    link.pump(&cli_end, 50).unwrap();
+    let hud = std::iter::from_fn(|| link.recv())
+        .find_map(|(_, m)| {
+            (m.first() == Some(&TAG_HUD_STATUS))
+                .then(|| HudStatusMsg::decode(&mut m.as_slice()).unwrap())
+        })
+        .expect("hud status");
+    let slots: Vec<u8> = hud.spell_cds.iter().map(|(slot, _)| *slot).collect();
+    assert_eq!(slots, vec![0, 1, 2, 3]);
+    assert!(hud.spell_cds[3].1 > 2900, "{:?}", hud.spell_cds);
+}
+
+#[test]
+fn builtin_spells_take_cost_and_cooldowns_from_their_specs() {
+    let s = ServerState::new();
+    let specs = data_runtime::specdb::SpecDb::load_default();
+    for spell in [SpellId::Firebolt, SpellId::Fireball, SpellId::MagicMissile] {
+        let a = s.abilities.get(spell).unwrap();
+        let spec = specs.spells().find(|d| d.id == a.id).unwrap();
+        let cost = spec
+            .resource_cost
+            .as_ref()
+            .and_then(|v| v["mana"].as_i64())
+            .unwrap_or(0);
+        assert_eq!(i64::from(a.cost), cost, "{}", a.id);
+        assert_eq!((a.cooldown_s, a.gcd_s), (spec.cooldown_s, spec.gcd_s));
+    }
+
+    // The server's table only fills in built-ins the data lacks.
+    let mut db = AbilityDb::default();
+    db.add_missing_builtins(&s.specs.spells);
+    let fb = db.get(SpellId::Fireball).unwrap();
+    assert_eq!(fb.cost, s.specs.spells.fireball.cost);
+    assert_eq!(db.resolve("fireball").unwrap().spell, SpellId::Fireball);
+}
@@ -24,7 +24,7 @@ fn magic_missile_rejects_below_cost_and_emits_toast() {
     );
     sc::ecs::schedule::cast_system(&mut s, &mut ctx);
     assert!(
-        ctx.hud_toasts.contains(&1u8),
+        ctx.hud_toasts.contains(&(s.pc_actor.unwrap(), 1u8)),
         "expected 'not enough mana' toast"
     );
 }
@@ -0,0 +1,214 @@
+#![allow(clippy::unwrap_used)]
+//! Characters survive a restart: `capture_character` + `CharacterStore`
+//! round-trip position, HP, resources, spells, cooldowns, reputation,
+//! inventory and worn items into a fresh `ServerState`; records carry a format
+//! version, older versions are migrated and unknown ones refused; the session
+//! layer restores a named client on join, saves on disconnect and autosaves.
+
+mod common;
+
+use std::path::PathBuf;
+
+use common::{DT, ZONE, join};
+use glam::vec3;
+use server_core::actor::{ActorId, Faction};
+use server_core::persist::{CHARACTER_FORMAT_VERSION, CharacterRecord, CharacterStore};
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::{ServerState, SpellId};
+
+/// A fresh, empty store directory for one test.
+fn store(tag: &str) -> CharacterStore {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("ra_characters_{}_{tag}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    CharacterStore::open(dir).unwrap()
+}
+
+/// Leave a PC in a state that differs from a fresh spawn in every saved field.
+fn wear(s: &mut ServerState, pc: ActorId) {
+    let c = s.ecs.get_mut(pc).unwrap();
+    c.tr.pos = vec3(7.5, 0.6, -3.25);
+    c.tr.yaw = 1.25;
+    c.hp.hp = c.hp.max - 17;
+    let pool = c.pool.as_mut().unwrap();
+    pool.mana = 3;
+    pool.stamina = pool.stamina_max / 2;
+    c.spellbook
+        .as_mut()
+        .unwrap()
+        .known
+        .retain(|s| *s != SpellId::Fireball);
+    let cd = c.cooldowns.as_mut().unwrap();
+    cd.gcd_ready = 0.2;
+    cd.per_spell.insert(SpellId::Firebolt, 1.5);
+    s.factions.on_hit(pc, Faction::WIZARDS);
+    s.factions.adjust(pc, Faction::UNDEAD, 250);
+    assert_eq!(s.give_item(pc, "potion_healing", 3), 3);
+    assert_eq!(s.give_item(pc, "shield", 1), 1);
+    s.equip_item(pc, "shield").unwrap();
+    s.equip_item(pc, "leather_armor").unwrap();
+}
+
+#[test]
+fn character_round_trips_into_a_fresh_server() {
+    let st = store("roundtrip");
+    let mut a = ServerState::new();
+    let pc = a.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    wear(&mut a, pc);
+    let mut rec = CharacterRecord::new("Ada");
+    assert!(a.capture_character(pc, Some(ZONE), &mut rec));
+    assert_eq!(rec.equipped.len(), 2);
+    st.save(&rec).unwrap();
+    assert!(!st.path_for("Ada").with_extension("json.tmp").exists());
+
+    let loaded = st.load("Ada").unwrap().unwrap();
+    assert_eq!(loaded, rec);
+    assert_eq!(loaded.version, CHARACTER_FORMAT_VERSION);
+    assert!(st.load("Bob").unwrap().is_none());
+
+    let mut b = ServerState::new();
+    let fresh = b.spawn_pc(vec3(0.0, 0.6, 0.0));
+    b.restore_character(fresh, Some(ZONE), &loaded);
+    let (ca, cb) = (a.ecs.get(pc).unwrap(), b.ecs.get(fresh).unwrap());
+    assert_eq!(cb.tr.pos, ca.tr.pos);
+    assert_eq!(cb.tr.yaw, ca.tr.yaw);
+    assert_eq!(cb.hp.hp, ca.hp.hp);
+    let (pa, pb) = (ca.pool.unwrap(), cb.pool.unwrap());
+    assert_eq!((pb.mana, pb.stamina), (pa.mana, pa.stamina));
+    assert_eq!(
+        cb.spellbook.as_ref().unwrap().known,
+        ca.spellbook.as_ref().unwrap().known
+    );
+    let (da, db) = (
+        ca.cooldowns.as_ref().unwrap(),
+        cb.cooldowns.as_ref().unwrap(),
+    );
+    assert_eq!(db.gcd_ready, da.gcd_ready);
+    assert_eq!(db.per_spell.get(&SpellId::Firebolt), Some(&1.5));
+    let (ia, ib) = (
+        ca.inventory.as_ref().unwrap(),
+        cb.inventory.as_ref().unwrap(),
+    );
+    assert_eq!(ib.items, ia.items);
+    assert_eq!(ib.equipped, ia.equipped);
+    assert_eq!(cb.armor_class(), ca.armor_class());
+    for f in [Faction::WIZARDS, Faction::UNDEAD, Faction::NEUTRAL] {
+        assert_eq!(
+            b.factions.reputation(fresh, f),
+            a.factions.reputation(pc, f),
+            "{f:?}"
+        );
+    }
+
+    // Capturing the restored character gives back the same record.
+    let mut again = loaded.clone();
+    assert!(b.capture_character(fresh, Some(ZONE), &mut again));
+    assert_eq!(again, loaded);
+}
+
+#[test]
+fn position_stays_behind_in_another_zone_and_the_dead_respawn_whole() {
+    let mut a = ServerState::new();
+    let pc = a.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    wear(&mut a, pc);
+    let mut rec = CharacterRecord::new("Ada");
+    a.capture_character(pc, Some(ZONE), &mut rec);
+
+    let mut b = ServerState::new();
+    let fresh = b.spawn_pc(vec3(1.0, 0.6, 1.0));
+    b.restore_character(fresh, Some("other_zone"), &rec);
+    let c = b.ecs.get(fresh).unwrap();
+    assert_eq!(c.tr.pos, vec3(1.0, 0.6, 1.0));
+    assert_eq!(c.hp.hp, rec.hp, "everything else still comes back");
+
+    rec.hp = 0;
+    let mut d = ServerState::new();
+    let fresh = d.spawn_pc(vec3(1.0, 0.6, 1.0));
+    d.restore_character(fresh, Some(ZONE), &rec);
+    let c = d.ecs.get(fresh).unwrap();
+    assert_eq!(c.tr.pos, vec3(1.0, 0.6, 1.0));
+    assert_eq!(c.hp.hp, c.hp.max);
+}
+
+#[test]
+fn records_are_versioned() {
+    let rec = CharacterRecord::new("Ada");
+    let json = rec.to_json().unwrap();
+    assert_eq!(CharacterRecord::from_json(&json).unwrap(), rec);
+    let mut v: serde_json::Value = serde_json::from_str(&json).unwrap();
+    v["version"] = serde_json::Value::from(CHARACTER_FORMAT_VERSION + 1);
+    let err = CharacterRecord::from_json(&v.to_string()).unwrap_err();
+    assert!(format!("{err:#}").contains("character format"), "{err:#}");
+    v.as_object_mut().unwrap().remove("version");
+    assert!(CharacterRecord::from_json(&v.to_string()).is_err());
+
+    // Version 1 records (before worn items) load wearing nothing.
+    let mut v1: serde_json::Value = serde_json::from_str(&json).unwrap();
+    v1["version"] = serde_json::Value::from(1);
+    v1.as_object_mut().unwrap().remove("equipped");
+    let up = CharacterRecord::from_json(&v1.to_string()).unwrap();
+    assert_eq!(up.version, CHARACTER_FORMAT_VERSION);
+    assert_eq!(up, rec);
+
+    // Names that are not safe file names still get a file of their own.
+    let st = store("names");
+    let odd = CharacterRecord::new("../Ada Lovelace");
+    st.save(&odd).unwrap();
+    assert_eq!(st.path_for(&odd.name).parent(), Some(st.dir()));
+    assert_eq!(st.load("../Ada Lovelace").unwrap().unwrap(), odd);
+    assert!(st.load("Ada Lovelace").unwrap().is_none());
+}
+
+fn host(st: &CharacterStore, autosave_s: f32) -> SessionHost {
+    let mut host = SessionHost::new(SessionConfig {
+        zone_slug: Some(ZONE.into()),
+        idle_timeout_s: 0.0,
+        autosave_s,
+        ..Default::default()
+    });
+    host.set_character_store(st.clone());
+    host
+}
+
+#[test]
+fn sessions_restore_on_join_and_save_on_disconnect() {
+    let st = store("session");
+    let mut srv = ServerState::new();
+    let mut h = host(&st, 0.0);
+    let (ada, actor) = join(&mut h, &mut srv, "Ada");
+    wear(&mut srv, actor);
+    let expected = srv.ecs.get(actor).unwrap().tr.pos;
+    assert!(st.load("Ada").unwrap().is_none(), "no autosave");
+    h.disconnect(&mut srv, ada);
+    let saved = st.load("Ada").unwrap().unwrap();
+    assert_eq!(saved.zone.as_deref(), Some(ZONE));
+
+    // A restarted server brings Ada back where and how the character left.
+    let mut srv = ServerState::new();
+    let mut h = host(&st, 0.0);
+    let (_, actor) = join(&mut h, &mut srv, "Ada");
+    let c = srv.ecs.get(actor).unwrap();
+    assert!(c.tr.pos.distance(expected) < 1e-4, "{}", c.tr.pos);
+    assert_eq!(c.hp.hp, saved.hp);
+    assert_eq!(
+        srv.factions.reputation(actor, Faction::WIZARDS),
+        saved.reputation["wizards"]
+    );
+}
+
+#[test]
+fn connected_characters_autosave() {
+    let st = store("autosave");
+    let mut srv = ServerState::new();
+    let mut h = host(&st, 1.0);
+    let (_, actor) = join(&mut h, &mut srv, "Ada");
+    srv.ecs.get_mut(actor).unwrap().hp.hp -= 5;
+    for _ in 0..30 {
+        h.pump_inputs(&mut srv);
+        srv.step_authoritative(DT);
+        h.broadcast(&mut srv);
+    }
+    let saved = st.load("Ada").unwrap().expect("autosaved after a second");
+    assert_eq!(saved.hp, srv.ecs.get(actor).unwrap().hp.hp);
+    assert_eq!(h.save_characters(&srv), 1);
+}
@@ -0,0 +1,91 @@
+#![allow(clippy::unwrap_used)]
+//! The client half of the handshake (`client_core::join::Join`) against a
+//! real `SessionHost`: the `Welcome` lands in the replication buffer, the
+//! client then decodes its own PC under the pinned versions, and a bad
+//! `Hello` ends in a typed reject.
+
+mod common;
+
+use client_core::join::{Join, JoinState};
+use common::Client;
+use net_core::handshake::{RejectReason, WireVersions};
+use net_core::link::Channel;
+use server_core::ServerState;
+use server_core::session::{SessionConfig, SessionHost};
+
+const HZ: u32 = 20;
+
+/// Attach through the handshake and send `Join`'s `Hello`.
+fn connect(host: &mut SessionHost, zone: &str, name: &str) -> (Client, Join) {
+    let (_, mut c) = Client::accept(host);
+    let mut join = Join::new(zone, name);
+    c.send_bytes(Channel::Reliable, join.start());
+    (c, join)
+}
+
+/// Deliver what arrived: handshake replies to `join`, the rest to `c.rep`.
+fn pump(c: &mut Client, join: &mut Join) {
+    for p in c.recv_all(1000 / u64::from(HZ)) {
+        if !join.handle(&p, &mut c.rep) {
+            c.rep.apply_message(&p);
+        }
+    }
+}
+
+fn host() -> SessionHost {
+    SessionHost::new(SessionConfig {
+        tick_hz: HZ,
+        interest_radius_m: 100.0,
+        zone_slug: Some("wizard_woods".into()),
+        zone_manifest_id: 3,
+        ..Default::default()
+    })
+}
+
+fn tick(host: &mut SessionHost, srv: &mut ServerState) {
+    host.pump_inputs(srv);
+    srv.step_authoritative(1.0 / HZ as f32);
+    host.broadcast(srv);
+}
+
+#[test]
+fn join_adopts_welcome_and_sees_own_pc() {
+    let mut srv = ServerState::new();
+    let mut host = host();
+    let (mut c, mut join) = connect(&mut host, "wizard_woods", "Ada");
+    assert_eq!(join.state(), &JoinState::Waiting);
+    for _ in 0..5 {
+        tick(&mut host, &mut srv);
+        pump(&mut c, &mut join);
+    }
+    let w = join.welcome().cloned().expect("welcomed");
+    assert_eq!(w.tick_hz, HZ as u16);
+    assert_eq!(w.zone_manifest_id, 3);
+    assert_eq!(c.rep.negotiated(), Some(WireVersions::CURRENT));
+    assert_eq!(c.rep.local_actor(), Some(w.actor_id));
+    let me = c
+        .rep
+        .wizards
+        .iter()
+        .find(|v| v.id == w.actor_id)
+        .expect("own PC replicated");
+    assert!(me.is_pc);
+    assert!(c.rep.wizards.iter().filter(|v| v.is_pc).count() == 1);
+}
+
+#[test]
+fn unknown_zone_is_rejected() {
+    let mut srv = ServerState::new();
+    let mut host = host();
+    let (mut c, mut join) = connect(&mut host, "campaign_builder", "Ada");
+    for _ in 0..3 {
+        tick(&mut host, &mut srv);
+        pump(&mut c, &mut join);
+    }
+    let JoinState::Rejected(r) = join.state() else {
+        panic!("expected reject, got {:?}", join.state());
+    };
+    assert_eq!(r.reason, RejectReason::UnknownZone);
+    assert!(c.rep.local_actor().is_none());
+    assert!(c.rep.negotiated().is_none());
+}
@@ -0,0 +1,171 @@
+//! Fixtures shared by the integration tests (`mod common;`). Each test binary
+//! uses only some of them.
+#![allow(clippy::unwrap_used, dead_code)]
+
+use std::sync::{Arc, Mutex};
+
+use client_core::replication::ReplicationBuffer;
+use glam::Vec3;
+use net_core::command::ClientCmd;
+use net_core::handshake::Hello;
+use net_core::link::{Channel, Endpoint};
+use net_core::snapshot::SnapshotEncode;
+use net_core::transport::{LocalLoopbackTransport, Transport, TrySendError};
+use server_core::ServerState;
+use server_core::actor::ActorId;
+use server_core::session::{ClientId, SessionHost};
+
+pub const DT: f32 = 1.0 / 30.0;
+pub const ZONE: &str = "wizard_woods";
+
+/// The client end of a loopback session, with its own link clock and
+/// replication buffer.
+pub struct Client {
+    pub xport: LocalLoopbackTransport,
+    pub link: Endpoint,
+    pub rep: ReplicationBuffer,
+    pub now_ms: u64,
+}
+
+impl Client {
+    pub fn new(xport: LocalLoopbackTransport) -> Self {
+        Self {
+            xport,
+            link: Endpoint::default(),
+            rep: ReplicationBuffer::default(),
+            now_ms: 0,
+        }
+    }
+
+    /// Attach through the handshake (`SessionHost::accept`); the client has
+    /// no actor until its `Hello` is accepted.
+    pub fn accept(host: &mut SessionHost) -> (ClientId, Self) {
+        let (srv_end, cli_end) = LocalLoopbackTransport::new(1024);
+        let id = host.accept(Box::new(srv_end));
+        (id, Self::new(cli_end))
+    }
+
+    /// Attach as a trusted connection (`SessionHost::connect`); its PC exists
+    /// straight away.
+    pub fn connect(host: &mut SessionHost, srv: &mut ServerState) -> (ClientId, Self) {
+        let (srv_end, cli_end) = LocalLoopbackTransport::new(1024);
+        let id = host.connect(srv, Box::new(srv_end));
+        (id, Self::new(cli_end))
+    }
+
+    pub fn send(&mut self, ch: Channel, msg: &impl SnapshotEncode) {
+        let mut buf = Vec::new();
+        msg.encode(&mut buf);
+        self.send_bytes(ch, buf);
+    }
+
+    pub fn cmd(&mut self, ch: Channel, cmd: &ClientCmd) {
+        let mut buf = Vec::new();
+        cmd.encode(&mut buf);
+        self.send_bytes(ch, buf);
+    }
+
+    /// Queue `buf` on `ch` and flush it to the server.
+    pub fn send_bytes(&mut self, ch: Channel, buf: Vec<u8>) {
+        self.link.send(ch, buf).unwrap();
+        self.link.pump(&self.xport, self.now_ms).unwrap();
+    }
+
+    /// Advance the link clock by `step_ms` and return every delivered payload.
+    pub fn recv_all(&mut self, step_ms: u64) -> Vec<Vec<u8>> {
+        self.now_ms += step_ms;
+        self.link.pump(&self.xport, self.now_ms).unwrap();
+        std::iter::from_fn(|| self.link.recv().map(|(_, p)| p)).collect()
+    }
+
+    /// `recv_all`, applying each payload to `rep`.
+    pub fn replicate(&mut self, step_ms: u64) -> Vec<Vec<u8>> {
+        let msgs = self.recv_all(step_ms);
+        for m in &msgs {
+            self.rep.apply_message(m);
+        }
+        msgs
+    }
+}
+
+/// Server end of a loopback that can stall outbound packets and deliver them
+/// later, behind newer ones. Clones share the same link, so the test keeps one
+/// to drive it while the host owns the other.
+#[derive(Clone)]
+pub struct Stall(Arc<StallLink>);
+
+pub struct StallLink {
+    xport: LocalLoopbackTransport,
+    /// (holding, packets held in send order)
+    held: Mutex<(bool, Vec<Vec<u8>>)>,
+}
+
+impl Stall {
+    pub fn new(xport: LocalLoopbackTransport) -> Self {
+        Self(Arc::new(StallLink {
+            xport,
+            held: Mutex::new((false, Vec::new())),
+        }))
+    }
+
+    /// Attach a trusted connection through a `Stall`.
+    pub fn connect(host: &mut SessionHost, srv: &mut ServerState) -> (ClientId, Client, Self) {
+        let (srv_end, cli_end) = LocalLoopbackTransport::new(1024);
+        let stall = Self::new(srv_end);
+        let id = host.connect(srv, Box::new(stall.clone()));
+        (id, Client::new(cli_end), stall)
+    }
+
+    /// Hold (`true`) or pass through (`false`) packets sent from now on.
+    /// Packets already held stay held.
+    pub fn hold(&self, on: bool) {
+        self.0.held.lock().unwrap().0 = on;
+    }
+
+    /// Deliver everything held, in send order.
+    pub fn release(&self) {
+        let held = std::mem::take(&mut self.0.held.lock().unwrap().1);
+        for p in held {
+            self.0.xport.try_send(p).unwrap();
+        }
+    }
+}
+
+impl Transport for Stall {
+    fn try_send(&self, bytes: Vec<u8>) -> Result<(), TrySendError> {
+        let mut held = self.0.held.lock().unwrap();
+        if held.0 {
+            held.1.push(bytes);
+            return Ok(());
+        }
+        drop(held);
+        self.0.xport.try_send(bytes)
+    }
+    fn try_recv(&self) -> Option<Vec<u8>> {
+        self.0.xport.try_recv()
+    }
+    fn depth(&self) -> usize {
+        self.0.xport.depth()
+    }
+}
+
+/// Join `ZONE` as `name` through the handshake and step one tick; returns the
+/// client and its PC. The client end is dropped, so the link goes quiet.
+pub fn join(host: &mut SessionHost, srv: &mut ServerState, name: &str) -> (ClientId, ActorId) {
+    let (id, mut c) = Client::accept(host);
+    c.send(Channel::Reliable, &Hello::new(ZONE, name));
+    host.pump_inputs(srv);
+    srv.step_authoritative(DT);
+    host.broadcast(srv);
+    (id, host.actor_of(id).unwrap())
+}
+
+/// A stationary Undead with `hp` at `pos` that neither moves nor swings back.
+pub fn dummy(s: &mut ServerState, pos: Vec3, hp: i32) -> ActorId {
+    let z = s.spawn_undead(pos, 0.9, hp);
+    let c = s.ecs.get_mut(z).unwrap();
+    c.tr.pos = pos;
+    c.move_speed = None;
+    c.melee = None;
+    z
+}
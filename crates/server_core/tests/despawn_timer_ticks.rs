@@ -4,16 +4,18 @@ use glam::Vec3;
 fn corpse_with_despawn_after_is_removed_when_timer_elapses() {
     let mut s = server_core::ServerState::new();
     s.sync_wizards(&[Vec3::new(0.0, 0.6, 0.0)]);
-    let z = s.spawn_undead(Vec3::new(0.5, 0.6, 0.5), 0.9, 10);
+    let z = s.spawn_undead(Vec3::new(0.5, 0.6, 0.5), 0.9, 1);
 
     // Kill via burning; server sets DespawnAfter { seconds: 2.0 } on death
-    {
-        let a = s.ecs.get_mut(z).unwrap();
-        a.apply_burning(100, 0.1, None); // die next tick
-    }
+    s.apply_status(z, "burning", None);
 
-    // First step should apply damage and set despawn timer
-    s.step_authoritative(0.1);
+    // The first tick should apply damage and set despawn timer
+    let mut steps = 0;
+    while s.ecs.get(z).is_some_and(|c| c.hp.alive()) {
+        assert!(steps < 10, "burning should kill within a second");
+        s.step_authoritative(0.1);
+        steps += 1;
+    }
     assert!(s.ecs.get(z).is_some(), "present during despawn delay");
 
     // Step until just before 2.0s
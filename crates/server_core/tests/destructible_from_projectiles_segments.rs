@@ -18,7 +18,7 @@ fn fireball_segment_crossing_proxy_enqueues_carve() {
     let comps = sc::ecs::Components {
         id: sc::actor::ActorId(0),
         kind: sc::actor::ActorKind::Zombie, // unused for projectile
-        faction: sc::actor::Faction::Neutral,
+        faction: sc::actor::Faction::NEUTRAL,
         name: None,
         tr: sc::actor::Transform {
             pos: end,
@@ -43,10 +43,22 @@ fn fireball_segment_crossing_proxy_enqueues_carve() {
         cooldowns: None,
         intent_move: None,
         intent_aim: None,
-        burning: None,
-        slow: None,
-        stunned: None,
+        intent_dodge: None,
+        statuses: Vec::new(),
+        immune: None,
         despawn_after: None,
+        nav: None,
+        member: None,
+        threat: None,
+        boss: None,
+        armor: None,
+        weapons: None,
+        saves: None,
+        resist: None,
+        spellcasting: None,
+        dodge: None,
+        inventory: None,
+        archetype_id: 0,
     };
     let _pid = s.ecs.spawn_from_components(comps);
 
@@ -73,7 +85,7 @@ fn firebolt_never_enqueues_carve_even_on_crossing() {
     let comps = sc::ecs::Components {
         id: sc::actor::ActorId(0),
         kind: sc::actor::ActorKind::Zombie,
-        faction: sc::actor::Faction::Neutral,
+        faction: sc::actor::Faction::NEUTRAL,
         name: None,
         tr: sc::actor::Transform {
             pos: end,
@@ -98,10 +110,22 @@ fn firebolt_never_enqueues_carve_even_on_crossing() {
         cooldowns: None,
         intent_move: None,
         intent_aim: None,
-        burning: None,
-        slow: None,
-        stunned: None,
+        intent_dodge: None,
+        statuses: Vec::new(),
+        immune: None,
         despawn_after: None,
+        nav: None,
+        member: None,
+        threat: None,
+        boss: None,
+        armor: None,
+        weapons: None,
+        saves: None,
+        resist: None,
+        spellcasting: None,
+        dodge: None,
+        inventory: None,
+        archetype_id: 0,
     };
     let _pid = s.ecs.spawn_from_components(comps);
 
@@ -0,0 +1,78 @@
+#![allow(clippy::unwrap_used)]
+//! Chunk meshes for a destructible outside a client's interest range are held
+//! back, not dropped: the client gets the latest mesh of every chunk once it
+//! walks into range, and a client that joins after the carves gets them too.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{Client, DT};
+use glam::vec3;
+use net_core::snapshot::{ChunkMeshDelta, SnapshotDecode};
+use server_core::ServerState;
+use server_core::session::{SessionConfig, SessionHost};
+
+type Meshes = BTreeMap<(u64, (u32, u32, u32)), ChunkMeshDelta>;
+
+/// Latest non-empty chunk mesh per chunk among `msgs`. (An instance and an
+/// empty mesh have the same length, so those are skipped.)
+fn collect(meshes: &mut Meshes, msgs: Vec<Vec<u8>>) {
+    for m in msgs {
+        let mut slice: &[u8] = &m;
+        if m.len() > 33
+            && let Ok(d) = ChunkMeshDelta::decode(&mut slice)
+            && slice.is_empty()
+        {
+            meshes.insert((d.did, d.chunk), d);
+        }
+    }
+}
+
+fn tick(host: &mut SessionHost, srv: &mut ServerState) {
+    host.pump_inputs(srv);
+    srv.step_authoritative(DT);
+    host.broadcast(srv);
+}
+
+#[test]
+fn meshes_follow_clients_into_range_and_late_joins() {
+    let mut srv = ServerState::new();
+    server_core::scene_build::add_demo_ruins_destructible(&mut srv);
+    let mut host = SessionHost::new(SessionConfig {
+        interest_radius_m: 20.0,
+        ..Default::default()
+    });
+    let (_, mut near) = Client::connect(&mut host, &mut srv);
+    let (far_id, mut far) = Client::connect(&mut host, &mut srv);
+    let far_pc = host.actor_of(far_id).unwrap();
+    srv.ecs.get_mut(far_pc).unwrap().tr.pos = vec3(200.0, 0.6, 0.0);
+
+    // Let the budgeted remesh finish the initial meshes.
+    let (mut seen_near, mut seen_far) = (Meshes::new(), Meshes::new());
+    for _ in 0..60 {
+        tick(&mut host, &mut srv);
+        collect(&mut seen_near, near.recv_all(50));
+        collect(&mut seen_far, far.recv_all(50));
+    }
+    assert!(!seen_near.is_empty(), "the nearby client sees the ruins");
+    assert!(seen_far.is_empty(), "out of range: meshes are held back");
+
+    // Walking into range delivers every chunk, as last meshed.
+    srv.ecs.get_mut(far_pc).unwrap().tr.pos = vec3(0.0, 0.6, 0.0);
+    for _ in 0..3 {
+        tick(&mut host, &mut srv);
+        collect(&mut seen_near, near.recv_all(50));
+        collect(&mut seen_far, far.recv_all(50));
+    }
+    assert_eq!(seen_far, seen_near);
+
+    // So does joining after the fact.
+    let (_, mut late) = Client::connect(&mut host, &mut srv);
+    let mut seen_late = Meshes::new();
+    for _ in 0..3 {
+        tick(&mut host, &mut srv);
+        collect(&mut seen_late, late.recv_all(50));
+    }
+    assert_eq!(seen_late, seen_near);
+}
@@ -0,0 +1,352 @@
+#![allow(clippy::unwrap_used)]
+//! Carved ruins survive a restart: `capture_destructibles` snapshots each
+//! proxy's scars with the `chunk_occ_hash` of the chunks they touch, the
+//! snapshot round-trips through `DestructibleStore`, and
+//! `restore_destructibles` replays it onto freshly built ruins. Chunks whose
+//! hash no longer matches stay as built. Under a zone `RegrowPolicy` scars
+//! grow back oldest first, at a bounded rate, never on top of an actor.
+//! Sessions recorded on restored ruins carry them and replay faithfully.
+
+mod common;
+
+use common::Client;
+use data_runtime::encounter::EncounterSpec;
+use ecs_core::components::CarveRequest;
+use glam::{DVec3, UVec3, Vec3};
+use net_core::command::ClientCmd;
+use net_core::link::Channel;
+use server_core::ServerState;
+use server_core::destructible::persist::{DestructibleSnapshot, DestructibleStore};
+use server_core::destructible::state::{DestructibleId, RegrowPolicy};
+use server_core::ecs::schedule::Ctx;
+use server_core::replay::{self, ReplayLog};
+use server_core::scene_build::add_demo_ruins_destructible;
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::destructible::{destructible_apply_carves, destructible_regrow};
+use server_core::zones::plan_encounter;
+
+const DT: f32 = 1.0 / 30.0;
+const ZONE: &str = "wizard_woods";
+const RUINS: DestructibleId = DestructibleId(1);
+
+fn ruins() -> ServerState {
+    let mut s = ServerState::new();
+    add_demo_ruins_destructible(&mut s);
+    s
+}
+
+/// Carve a sphere centered on voxel `v` of the demo ruins.
+fn carve_at(s: &mut ServerState, v: UVec3, radius_m: f64) {
+    let p = &s.destruct_registry.proxies[&RUINS];
+    let vm = p.grid.voxel_m().0;
+    let center = p.grid.origin_m() + (v.as_dvec3() + DVec3::splat(0.5)) * vm;
+    let ws = p.world_from_object.transform_point3(center.as_vec3());
+    let mut ctx = Ctx::default();
+    ctx.carves.push(CarveRequest {
+        did: RUINS.0,
+        center_m: ws.as_dvec3(),
+        radius_m,
+        seed: 0,
+        impact_id: 0,
+    });
+    destructible_apply_carves(s, &mut ctx);
+}
+
+/// World position of voxel `v`'s center, as regrowth sees actors.
+fn world_of(s: &ServerState, v: UVec3) -> Vec3 {
+    let p = &s.destruct_registry.proxies[&RUINS];
+    let vm = p.grid.voxel_m().0;
+    let center = p.grid.origin_m() + (v.as_dvec3() + DVec3::splat(0.5)) * vm;
+    p.world_from_object.transform_point3(center.as_vec3())
+}
+
+fn solid(s: &ServerState) -> usize {
+    s.destruct_registry.proxies[&RUINS].grid.solid_count()
+}
+
+fn hashes(s: &ServerState) -> Vec<u64> {
+    let g = &s.destruct_registry.proxies[&RUINS].grid;
+    let d = g.dims();
+    let c = g.meta().chunk;
+    let mut out = Vec::new();
+    for z in 0..d.z.div_ceil(c.z) {
+        for y in 0..d.y.div_ceil(c.y) {
+            for x in 0..d.x.div_ceil(c.x) {
+                out.push(g.chunk_occ_hash(UVec3::new(x, y, z)));
+            }
+        }
+    }
+    out
+}
+
+fn store(tag: &str) -> DestructibleStore {
+    let dir = std::env::temp_dir().join(format!("ra_ruins_{}_{tag}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    DestructibleStore::open(dir).unwrap()
+}
+
+#[test]
+fn carved_ruins_round_trip_into_a_fresh_server() {
+    let pristine = ruins();
+    let mut a = ruins();
+    carve_at(&mut a, UVec3::new(0, 12, 20), 1.0);
+    carve_at(&mut a, UVec3::new(12, 4, 1), 1.2);
+    let scars = &a.destruct_registry.proxies[&RUINS].scars;
+    assert_eq!(scars.len(), 2);
+    let carved: usize = scars.iter().map(|s| s.voxels.len()).sum();
+    assert_eq!(solid(&a), solid(&pristine) - carved);
+
+    let st = store("roundtrip");
+    let snap = a.capture_destructibles(ZONE);
+    assert_eq!(snap.proxies.len(), 1);
+    assert_eq!(snap.proxies[0].chunks.len(), 2, "one chunk per carve");
+    st.save(&snap).unwrap();
+    let loaded = st.load(ZONE).unwrap().unwrap();
+    assert_eq!(loaded, snap);
+    assert!(st.load("other_zone").unwrap().is_none());
+
+    let mut b = ruins();
+    b.destruct_registry
+        .proxies
+        .get_mut(&RUINS)
+        .unwrap()
+        .dirty
+        .0
+        .clear();
+    let r = b.restore_destructibles(&loaded);
+    assert_eq!(
+        (r.voxels, r.chunks_rejected, r.proxies_skipped),
+        (carved, 0, 0)
+    );
+    assert_eq!(solid(&b), solid(&a));
+    assert_eq!(hashes(&b), hashes(&a));
+    assert_eq!(
+        b.destruct_registry.proxies[&RUINS].scars,
+        a.destruct_registry.proxies[&RUINS].scars
+    );
+    // The restored chunks are queued for meshing and replication.
+    let dirty = &b.destruct_registry.proxies[&RUINS].dirty.0;
+    assert!(dirty.contains(&UVec3::new(0, 1, 2)) && dirty.contains(&UVec3::new(1, 0, 0)));
+    assert_eq!(b.capture_destructibles(ZONE), snap);
+}
+
+#[test]
+fn mismatched_chunks_stay_as_built() {
+    let pristine = ruins();
+    let mut a = ruins();
+    carve_at(&mut a, UVec3::new(0, 12, 20), 1.0);
+    carve_at(&mut a, UVec3::new(12, 4, 1), 1.2);
+    let mut snap = a.capture_destructibles(ZONE);
+    let first = snap.proxies[0].chunks[0];
+    snap.proxies[0].chunks[0].occ_hash ^= 1;
+
+    let mut b = ruins();
+    let r = b.restore_destructibles(&snap);
+    assert_eq!(r.chunks_rejected, 1);
+    let g = &b.destruct_registry.proxies[&RUINS].grid;
+    let c = UVec3::from(first.chunk);
+    let built = &pristine.destruct_registry.proxies[&RUINS].grid;
+    assert_eq!(g.chunk_occ_hash(c), built.chunk_occ_hash(c));
+    let other = UVec3::from(snap.proxies[0].chunks[1].chunk);
+    assert_eq!(
+        g.chunk_occ_hash(other),
+        a.destruct_registry.proxies[&RUINS]
+            .grid
+            .chunk_occ_hash(other),
+        "the other chunk is still restored"
+    );
+    assert!(solid(&b) > solid(&a) && solid(&b) < solid(&pristine));
+    // Only the restored voxels are tracked, so regrowth cannot overfill.
+    let tracked: usize = b.destruct_registry.proxies[&RUINS]
+        .scars
+        .iter()
+        .map(|s| s.voxels.len())
+        .sum();
+    assert_eq!(tracked, r.voxels);
+    assert_eq!(solid(&b) + tracked, solid(&pristine));
+
+    // A proxy that is gone or was rebuilt at another size is skipped whole.
+    let mut gone = snap.clone();
+    gone.proxies[0].did = 99;
+    let mut resized = snap.clone();
+    resized.proxies[0].dims[0] += 1;
+    for s in [gone, resized] {
+        let mut c = ruins();
+        assert_eq!(c.restore_destructibles(&s).proxies_skipped, 1);
+        assert_eq!(solid(&c), solid(&pristine));
+    }
+
+    // Unknown format versions are refused.
+    let mut v: serde_json::Value = serde_json::from_str(&snap.to_json().unwrap()).unwrap();
+    v["version"] = serde_json::Value::from(99);
+    let err = DestructibleSnapshot::from_json(&v.to_string()).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("destructible format"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn ruins_regrow_oldest_first_at_the_zone_rate() {
+    let pristine = solid(&ruins());
+    let mut s = ruins();
+    s.destruct_registry.regrow = RegrowPolicy {
+        after_s: Some(1.0),
+        voxels_per_s: 30.0,
+    };
+    carve_at(&mut s, UVec3::new(0, 12, 20), 1.0);
+    for _ in 0..15 {
+        s.step_authoritative(DT);
+    }
+    carve_at(&mut s, UVec3::new(12, 4, 1), 1.2);
+    let (first, second) = {
+        let scars = &s.destruct_registry.proxies[&RUINS].scars;
+        (scars[0].voxels.len(), scars[1].voxels.len())
+    };
+    assert!(first > 2 && second > 2);
+    let carved = solid(&s);
+
+    // Nothing regrows before the first scar is a second old.
+    for _ in 0..14 {
+        s.step_authoritative(DT);
+    }
+    assert_eq!(solid(&s), carved);
+    // Then about one voxel per tick (30/s at 30 Hz), from the older scar.
+    for _ in 0..3 {
+        s.step_authoritative(DT);
+    }
+    let regrown = solid(&s) - carved;
+    assert!((1..=3).contains(&regrown), "{regrown}");
+    assert_eq!(
+        s.destruct_registry.proxies[&RUINS].scars[0].voxels.len(),
+        first - regrown
+    );
+    assert_eq!(
+        s.destruct_registry.proxies[&RUINS].scars[1].voxels.len(),
+        second
+    );
+    for _ in 0..(30 * 10) {
+        s.step_authoritative(DT);
+    }
+    assert_eq!(solid(&s), pristine, "fully repaired");
+    assert!(s.destruct_registry.proxies[&RUINS].scars.is_empty());
+}
+
+#[test]
+fn regrowth_waits_for_actors_to_move_away_and_defaults_to_never() {
+    let mut s = ruins();
+    carve_at(&mut s, UVec3::new(0, 12, 20), 1.0);
+    let carved = solid(&s);
+    let mut ctx = Ctx {
+        dt: 60.0,
+        ..Default::default()
+    };
+    destructible_regrow(&mut s, &ctx);
+    assert_eq!(solid(&s), carved, "ruins last forever by default");
+    assert_eq!(s.destruct_registry.proxies[&RUINS].scars[0].age_s, 60.0);
+
+    s.destruct_registry.regrow = RegrowPolicy {
+        after_s: Some(1.0),
+        voxels_per_s: 1000.0,
+    };
+    let hole = world_of(&s, UVec3::new(0, 12, 20));
+    let pc = s.spawn_pc_at(hole);
+    s.ecs.get_mut(pc).unwrap().tr.pos = hole;
+    ctx.dt = 1.0;
+    destructible_regrow(&mut s, &ctx);
+    assert_eq!(solid(&s), carved, "someone stands in the hole");
+
+    s.ecs.get_mut(pc).unwrap().tr.pos = hole + Vec3::new(-10.0, 0.0, 0.0);
+    destructible_regrow(&mut s, &ctx);
+    assert!(s.destruct_registry.proxies[&RUINS].scars.is_empty());
+}
+
+#[test]
+fn zone_encounters_set_the_regrowth_policy() {
+    let s = ServerState::new();
+    let enc = |json: &str| -> EncounterSpec {
+        serde_json::from_str(&format!(r#"{{ "version": "1.0.0"{json} }}"#)).unwrap()
+    };
+    let plan = plan_encounter(&s, &enc(""), None).unwrap();
+    assert_eq!(plan.regrow, RegrowPolicy::default());
+    let plan = plan_encounter(
+        &s,
+        &enc(r#", "destructibles": { "regrow_after_s": 600, "regrow_voxels_per_s": 2.5 }"#),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        plan.regrow,
+        RegrowPolicy {
+            after_s: Some(600.0),
+            voxels_per_s: 2.5
+        }
+    );
+    assert!(
+        plan_encounter(
+            &s,
+            &enc(r#", "destructibles": { "regrow_after_s": 600 }"#),
+            None
+        )
+        .is_err(),
+        "regrowth without a rate"
+    );
+}
+
+#[test]
+fn sessions_recorded_on_restored_ruins_replay() {
+    // A previous run carved a doorway into the west wall and saved it.
+    let st = store("replay");
+    let mut before = ServerState::new();
+    assert!(server_core::zones::boot_with_zone(&mut before, ZONE));
+    for y in [1, 3] {
+        carve_at(&mut before, UVec3::new(1, y, 16), 1.5);
+    }
+    st.save(&before.capture_destructibles(ZONE)).unwrap();
+
+    // Boot, restore, then record, as `server --ruins DIR --record FILE` does.
+    let mut s = ServerState::new();
+    assert!(server_core::zones::boot_with_zone(&mut s, ZONE));
+    let r = s.restore_destructibles(&st.load(ZONE).unwrap().unwrap());
+    assert_eq!((r.chunks_rejected, r.proxies_skipped), (0, 0));
+    let mut host = SessionHost::new(SessionConfig {
+        zone_slug: Some(ZONE.into()),
+        spawn_center: server_core::zones::pc_spawn(&s, ZONE).unwrap(),
+        ..Default::default()
+    });
+    host.start_recording(&s);
+    let (id, mut c) = Client::connect(&mut host, &mut s);
+    let pc = host.actor_of(id).unwrap();
+    // Walk into the ruins; the zone's NPCs follow through the doorway.
+    for t in 0..120u32 {
+        let d = Vec3::new(0.0, 0.6, 12.0) - s.ecs.get(pc).unwrap().tr.pos;
+        let (dx, dz) = if d.length() > 0.5 {
+            (d.x, d.z)
+        } else {
+            (0.0, 0.0)
+        };
+        c.cmd(
+            Channel::Unreliable,
+            &ClientCmd::Move {
+                dx,
+                dz,
+                run: 1,
+                seq: t + 1,
+            },
+        );
+        host.pump_inputs(&mut s);
+        s.step_authoritative(DT);
+        host.broadcast(&mut s);
+        c.recv_all(33);
+    }
+    let log = host.stop_recording().unwrap();
+    assert_eq!(log.header.ruins, st.load(ZONE).unwrap());
+    let back = ReplayLog::from_bytes(&log.to_bytes()).unwrap();
+    assert_eq!(replay::verify(&back).unwrap(), 120);
+
+    // Played back on pristine ruins, the same session goes elsewhere.
+    let mut pristine = log;
+    pristine.header.ruins = None;
+    let err = replay::verify(&pristine).unwrap_err().to_string();
+    assert!(err.contains("snapshot hash"), "{err}");
+}
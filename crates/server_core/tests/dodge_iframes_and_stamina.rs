@@ -0,0 +1,221 @@
+#![allow(clippy::unwrap_used)]
+//! Dodge is server-authoritative: it spends stamina, dashes along the
+//! requested direction while sliding around static colliders, its i-frames let
+//! projectiles and melee through, and the dash replicates to clients as
+//! `ACTOR_STATE_DODGING`.
+
+use client_core::replication::ReplicationBuffer;
+use collision_static::{Aabb, CylinderY, ShapeRef, StaticCollider, StaticIndex};
+use glam::{Vec3, vec3};
+use net_core::command::ClientCmd;
+use net_core::link::{Channel, Endpoint};
+use net_core::transport::LocalLoopbackTransport;
+use server_core::actor::ActorId;
+use server_core::ecs::{Dodge, Spellcasting};
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::{ProjKind, ServerState};
+
+const DT: f32 = 1.0 / 30.0;
+
+fn hp(s: &ServerState, id: ActorId) -> i32 {
+    s.ecs.get(id).unwrap().hp.hp
+}
+
+fn stamina(s: &ServerState, id: ActorId) -> i32 {
+    s.ecs.get(id).unwrap().pool.unwrap().stamina
+}
+
+/// Hold `id` in i-frames for `secs` without moving it.
+fn iframes(s: &mut ServerState, id: ActorId, secs: f32) {
+    s.ecs.get_mut(id).unwrap().dodge = Some(Dodge {
+        dir: Vec3::X,
+        speed_mps: 0.0,
+        remaining_s: 0.0,
+        iframes_s: secs,
+    });
+}
+
+/// A 1 m radius, 3 m tall pillar at the origin.
+fn pillar() -> StaticIndex {
+    let center = vec3(0.0, 1.5, 0.0);
+    let half = vec3(1.0, 1.5, 1.0);
+    StaticIndex {
+        colliders: vec![StaticCollider {
+            aabb: Aabb {
+                min: center - half,
+                max: center + half,
+            },
+            shape: ShapeRef::Cyl(CylinderY {
+                center,
+                radius: 1.0,
+                half_height: 1.5,
+            }),
+        }],
+    }
+}
+
+#[test]
+fn dodge_dashes_along_intent_and_spends_stamina() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let full = stamina(&s, pc);
+    let cost = s.specs.dodge.stamina_cost;
+    s.apply_dodge_intent_for(pc, 1.0, 0.0);
+    s.step_authoritative(DT);
+    assert!(s.ecs.get(pc).unwrap().invulnerable());
+    assert_eq!(stamina(&s, pc), full - cost);
+
+    // A second request mid-dash is ignored, as are move intents.
+    s.apply_dodge_intent_for(pc, -1.0, 0.0);
+    s.apply_move_intent_for(pc, 0.0, 1.0, false);
+    s.step_authoritative(DT);
+    assert!(stamina(&s, pc) > full - 2 * cost, "charged once");
+    for _ in 0..20 {
+        s.step_authoritative(DT);
+    }
+    let c = s.ecs.get(pc).unwrap();
+    assert!(c.dodge.is_none());
+    assert!(
+        (c.tr.pos.x - s.specs.dodge.distance_m).abs() < 0.05,
+        "{}",
+        c.tr.pos
+    );
+    assert!(c.tr.pos.z.abs() < 1e-4);
+}
+
+#[test]
+fn dodge_needs_stamina_and_a_direction_defaults_to_facing() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    s.ecs.get_mut(pc).unwrap().pool.as_mut().unwrap().stamina = s.specs.dodge.stamina_cost - 1;
+    s.apply_dodge_intent_for(pc, 0.0, 0.0);
+    s.step_authoritative(DT);
+    assert!(s.ecs.get(pc).unwrap().dodge.is_none(), "not enough stamina");
+
+    // Stamina regenerates; a zero direction dashes along the facing (+Z).
+    for _ in 0..30 {
+        s.step_authoritative(DT);
+    }
+    s.apply_dodge_intent_for(pc, 0.0, 0.0);
+    for _ in 0..20 {
+        s.step_authoritative(DT);
+    }
+    let p = s.ecs.get(pc).unwrap().tr.pos;
+    assert!((p.z - s.specs.dodge.distance_m).abs() < 0.05, "{p}");
+}
+
+#[test]
+fn dodge_slides_around_static_colliders() {
+    let mut s = ServerState::new();
+    s.static_colliders = Some(pillar());
+    let pc = s.spawn_pc_at(vec3(-3.0, 0.6, 0.3));
+    let r = s.ecs.get(pc).unwrap().tr.radius;
+    s.apply_dodge_intent_for(pc, 1.0, 0.0);
+    for _ in 0..20 {
+        s.step_authoritative(DT);
+    }
+    let p = s.ecs.get(pc).unwrap().tr.pos;
+    assert!(
+        vec3(p.x, 0.0, p.z).length() >= 1.0 + r - 0.05,
+        "kept out of the pillar: {p}"
+    );
+    assert!(p.z > 0.3, "slid around it: {p}");
+    assert!((p.y - 0.6).abs() < 1e-4);
+}
+
+#[test]
+fn iframes_let_projectiles_through() {
+    let fire = |dodging: bool| {
+        let mut s = ServerState::new();
+        let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+        let z = s.spawn_undead(vec3(0.0, 0.6, 10.0), 0.9, 30);
+        let c = s.ecs.get_mut(z).unwrap();
+        c.tr.pos = vec3(0.0, 0.6, 10.0);
+        c.move_speed = None;
+        c.melee = None;
+        c.spellcasting = Some(Spellcasting {
+            attack_bonus: 100,
+            ..Default::default()
+        });
+        if dodging {
+            iframes(&mut s, pc, 1.0);
+        }
+        let before = hp(&s, pc);
+        s.spawn_projectile_from(z, vec3(0.0, 0.6, 6.0), -Vec3::Z, ProjKind::Firebolt);
+        for _ in 0..20 {
+            s.step_authoritative(DT);
+        }
+        before - hp(&s, pc)
+    };
+    assert!(fire(false) > 0, "the bolt lands without i-frames");
+    assert_eq!(fire(true), 0);
+}
+
+#[test]
+fn iframes_make_melee_miss() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = s.spawn_undead(vec3(0.0, 0.6, 1.0), 0.9, 30);
+    s.ecs.get_mut(z).unwrap().tr.pos = vec3(0.0, 0.6, 1.0);
+    iframes(&mut s, pc, 1.0);
+    let before = hp(&s, pc);
+    for _ in 0..25 {
+        s.step_authoritative(DT);
+    }
+    assert_eq!(hp(&s, pc), before, "swings inside the i-frames miss");
+    for _ in 0..30 {
+        s.step_authoritative(DT);
+    }
+    assert!(hp(&s, pc) < before, "swings land once they end");
+}
+
+#[test]
+fn dodge_state_replicates_to_the_client() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig {
+        interest_radius_m: 100.0,
+        ..Default::default()
+    });
+    let (srv_end, cli_end) = LocalLoopbackTransport::new(1024);
+    let id = host.connect(&mut srv, Box::new(srv_end));
+    let actor = host.actor_of(id).unwrap();
+    let mut link = Endpoint::default();
+    let mut rep = ReplicationBuffer::default();
+    let mut now_ms = 0;
+    let mut tick = |srv: &mut ServerState, host: &mut SessionHost, cmd: Option<ClientCmd>| {
+        if let Some(cmd) = cmd {
+            let mut p = Vec::new();
+            cmd.encode(&mut p);
+            link.send(Channel::Reliable, p).unwrap();
+        }
+        link.pump(&cli_end, now_ms).unwrap();
+        host.pump_inputs(srv);
+        srv.step_authoritative(DT);
+        host.broadcast(srv);
+        link.pump(&cli_end, now_ms).unwrap();
+        while let Some((_, payload)) = link.recv() {
+            rep.apply_message(&payload);
+        }
+        now_ms += 33;
+        rep.wizards
+            .iter()
+            .find(|w| w.id == actor.0)
+            .unwrap()
+            .dodging()
+    };
+    assert!(!tick(&mut srv, &mut host, None));
+    assert!(tick(
+        &mut srv,
+        &mut host,
+        Some(ClientCmd::Dodge { dx: 1.0, dz: 0.0 })
+    ));
+    assert!(srv.ecs.get(actor).unwrap().dodge.is_some());
+    let mut cleared = false;
+    for _ in 0..20 {
+        if !tick(&mut srv, &mut host, None) {
+            cleared = true;
+            break;
+        }
+    }
+    assert!(cleared, "the flag clears when the dash ends");
+}
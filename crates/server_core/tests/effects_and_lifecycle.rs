@@ -7,30 +7,23 @@ fn burning_ticks_damage_and_expires() {
     let mut s = server_core::ServerState::new();
     s.sync_wizards(&[Vec3::new(0.0, 0.6, 0.0)]);
     let z = s.spawn_undead(Vec3::new(3.0, 0.6, 0.0), 0.9, 50);
-    {
-        let a = s.ecs.get_mut(z).expect("undead exists");
-        a.apply_burning(10, 1.0, None);
-    }
+    s.apply_status_for(z, "burning", None, Some(1.0));
+    let tick = s.statuses.get("burning").expect("burning").fx.tick_damage;
     for _ in 0..10 {
         let _wiz: Vec<Vec3> = s
             .ecs
             .iter()
             .filter(|a| {
                 matches!(a.kind, server_core::ActorKind::Wizard)
-                    && a.faction == server_core::Faction::Pc
+                    && a.faction == server_core::Faction::PC
             })
             .map(|a| a.tr.pos)
             .collect();
         s.step_authoritative(0.1);
     }
     let a = s.ecs.get(z).expect("still present");
-    assert!(
-        a.hp.hp <= 41 && a.hp.hp >= 40,
-        "expected ~10 damage, hp={}",
-        a.hp.hp
-    );
-    let rem = a.burning.map(|b| b.remaining_s).unwrap_or(0.0);
-    assert!(rem <= 1.0e-3, "burning should expire, remaining={}", rem);
+    assert_eq!(a.hp.hp, 50 - 2 * tick, "expected two ticks");
+    assert!(a.statuses.is_empty(), "burning should expire");
 }
 
 #[test]
@@ -45,7 +38,7 @@ fn slow_scales_effective_speed() {
             .iter()
             .filter(|a| {
                 matches!(a.kind, server_core::ActorKind::Wizard)
-                    && a.faction == server_core::Faction::Pc
+                    && a.faction == server_core::Faction::PC
             })
             .map(|a| a.tr.pos)
             .collect();
@@ -57,10 +50,7 @@ fn slow_scales_effective_speed() {
     let mut s_slow = server_core::ServerState::new();
     s_slow.sync_wizards(&[Vec3::new(0.0, 0.6, 0.0)]);
     let z1 = s_slow.spawn_undead(Vec3::new(0.0, 0.6, 5.0), 0.9, 30);
-    {
-        let a = s_slow.ecs.get_mut(z1).unwrap();
-        a.apply_slow(0.5, 2.0);
-    }
+    s_slow.apply_status_for(z1, "slowed", None, Some(2.0));
     let start1 = s_slow.ecs.get(z1).unwrap().tr.pos;
     for _ in 0..10 {
         let _wiz: Vec<Vec3> = s_slow
@@ -68,7 +58,7 @@ fn slow_scales_effective_speed() {
             .iter()
             .filter(|a| {
                 matches!(a.kind, server_core::ActorKind::Wizard)
-                    && a.faction == server_core::Faction::Pc
+                    && a.faction == server_core::Faction::PC
             })
             .map(|a| a.tr.pos)
             .collect();
@@ -89,10 +79,8 @@ fn stun_blocks_cast() {
     use server_core::SpellId;
     let mut s = server_core::ServerState::new();
     s.sync_wizards(&[Vec3::new(0.0, 0.6, 0.0)]);
-    if let Some(pc) = s.pc_actor
-        && let Some(a) = s.ecs.get_mut(pc)
-    {
-        a.apply_stun(1.0);
+    if let Some(pc) = s.pc_actor {
+        s.apply_status(pc, "stunned", None);
     }
     s.enqueue_cast(
         Vec3::new(0.0, 0.6, 0.0),
@@ -104,7 +92,7 @@ fn stun_blocks_cast() {
         .iter()
         .filter(|a| {
             matches!(a.kind, server_core::ActorKind::Wizard)
-                && a.faction == server_core::Faction::Pc
+                && a.faction == server_core::Faction::PC
         })
         .map(|a| a.tr.pos)
         .collect();
@@ -117,21 +105,21 @@ fn stun_blocks_cast() {
 fn death_sets_despawn_or_removes_entity() {
     let mut s = server_core::ServerState::new();
     s.sync_wizards(&[glam::vec3(0.0, 0.6, 0.0)]);
-    let z = s.spawn_undead(glam::vec3(0.5, 0.6, 0.5), 0.9, 5);
-    {
-        let a = s.ecs.get_mut(z).unwrap();
-        a.apply_burning(50, 0.2, None);
-    }
+    let z = s.spawn_undead(glam::vec3(0.5, 0.6, 0.5), 0.9, 1);
+    s.apply_status(z, "burning", None);
     let _wiz: Vec<Vec3> = s
         .ecs
         .iter()
         .filter(|a| {
             matches!(a.kind, server_core::ActorKind::Wizard)
-                && a.faction == server_core::Faction::Pc
+                && a.faction == server_core::Faction::PC
         })
         .map(|a| a.tr.pos)
         .collect();
-    s.step_authoritative(0.1);
+    // The first burning tick lands after half a second.
+    for _ in 0..5 {
+        s.step_authoritative(0.1);
+    }
     match s.ecs.get(z) {
         None => {}
         Some(c) => {
@@ -1,12 +1,14 @@
 use glam::vec3;
+use server_core::Standing;
+use server_core::actor::Faction;
 
 #[test]
 fn pc_hitting_hostile_flips_faction_hostility() {
     let mut s = server_core::ServerState::new();
-    let _pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
     let wiz = s.spawn_wizard_npc(vec3(1.0, 0.6, 0.0));
     // Sanity: initially not hostile Pc<->Wizards
-    assert!(!s.factions.pc_vs_wizards_hostile);
+    assert_eq!(s.factions.standing(pc, Faction::WIZARDS), Standing::Neutral);
     // Fire a Firebolt from PC straight at the wizard
     s.enqueue_cast(
         vec3(0.0, 0.6, 0.0),
@@ -17,11 +19,13 @@ fn pc_hitting_hostile_flips_faction_hostility() {
     for _ in 0..10 {
         s.step_authoritative(0.05);
     }
-    // Wizard should have taken damage or at least the flip should have occurred via faction_flip_on_pc_hits_wizards
-    assert!(
-        s.factions.pc_vs_wizards_hostile,
-        "pc_vs_wizards_hostile should flip true after PC damages wizard"
+    // The hit costs the PC its standing with the wizards (reputation_from_hits)
+    assert_eq!(
+        s.factions.standing(pc, Faction::WIZARDS),
+        Standing::Hostile,
+        "PC should turn hostile with the wizards after damaging one"
     );
+    assert!(s.factions.hostile(pc, Faction::PC, wiz, Faction::WIZARDS));
     // Health drop check (best effort; wizard HP is 100 initially)
     if let Some(w) = s.ecs.get(wiz) {
         assert!(w.hp.hp <= 100);
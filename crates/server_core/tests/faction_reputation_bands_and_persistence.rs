@@ -0,0 +1,133 @@
+#![allow(clippy::unwrap_used)]
+//! Hostility goes through the faction registry (`data/config/factions.toml`):
+//! each player's reputation with a faction falls into a hostile, neutral or
+//! friendly band, hits and kills move it (kills spill over onto related
+//! factions), players' reputations are independent, and a named character
+//! gets its reputation back when it reconnects.
+
+mod common;
+
+use common::{DT, join};
+use glam::{Vec3, vec3};
+use server_core::actor::Faction;
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::{ServerState, SpellId, Standing};
+
+fn faction(s: &ServerState, key: &str) -> Faction {
+    s.factions.registry.id(key).unwrap()
+}
+
+#[test]
+fn reputation_bands_decide_hostility() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let wiz = s.spawn_wizard_npc(vec3(5.0, 0.6, 0.0));
+    let z = s.spawn_undead(vec3(-5.0, 0.6, 0.0), 0.9, 30);
+    let f = &mut s.factions;
+    assert_eq!(f.standing(pc, Faction::WIZARDS), Standing::Neutral);
+    assert_eq!(f.standing(pc, Faction::UNDEAD), Standing::Hostile);
+    assert!(!f.hostile(pc, Faction::PC, wiz, Faction::WIZARDS));
+    assert!(f.can_harm(pc, Faction::PC, wiz, Faction::WIZARDS));
+    assert!(f.hostile(z, Faction::UNDEAD, pc, Faction::PC));
+    assert!(f.hostile(wiz, Faction::WIZARDS, z, Faction::UNDEAD));
+
+    // One hit drops the PC into the wizards' hostile band; further hits on an
+    // already hostile faction change nothing.
+    f.on_hit(pc, Faction::WIZARDS);
+    let after_hit = f.reputation(pc, Faction::WIZARDS);
+    assert_eq!(f.standing(pc, Faction::WIZARDS), Standing::Hostile);
+    assert!(f.hostile(wiz, Faction::WIZARDS, pc, Faction::PC));
+    f.on_hit(pc, Faction::WIZARDS);
+    assert_eq!(f.reputation(pc, Faction::WIZARDS), after_hit);
+
+    // Earning it back past `friendly_at` makes them friends: no longer harmed.
+    f.adjust(pc, Faction::WIZARDS, 2000);
+    assert_eq!(f.standing(pc, Faction::WIZARDS), Standing::Friendly);
+    assert!(!f.can_harm(pc, Faction::PC, wiz, Faction::WIZARDS));
+
+    // Reputation is clamped to the registry's range.
+    let top = f.adjust(pc, Faction::WIZARDS, i32::MAX / 2);
+    assert_eq!(f.adjust(pc, Faction::WIZARDS, 1), top);
+}
+
+#[test]
+fn kills_move_reputation_with_spillover() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = s.spawn_undead(vec3(0.0, 0.6, 12.0), 0.9, 30);
+    let c = s.ecs.get_mut(z).unwrap();
+    c.tr.pos = vec3(0.0, 0.6, 12.0);
+    c.hp.hp = 1;
+    c.move_speed = None;
+    c.melee = None;
+    let seafarers = faction(&s, "seafarers");
+    let (wiz0, sea0) = (
+        s.factions.reputation(pc, Faction::WIZARDS),
+        s.factions.reputation(pc, seafarers),
+    );
+    s.enqueue_cast(vec3(0.0, 0.6, 0.0), Vec3::Z, SpellId::Fireball);
+    for _ in 0..60 {
+        s.step_authoritative(DT);
+    }
+    assert!(!s.ecs.get(z).is_some_and(|c| c.hp.alive()), "undead died");
+    let undead = s.factions.registry.get(Faction::UNDEAD).unwrap().clone();
+    let spill = |f: Faction| {
+        undead
+            .kill_spillover
+            .iter()
+            .find(|(o, _)| *o == f)
+            .map_or(0, |(_, d)| *d)
+    };
+    assert!(spill(Faction::WIZARDS) > 0);
+    assert_eq!(
+        s.factions.reputation(pc, Faction::WIZARDS),
+        wiz0 + spill(Faction::WIZARDS)
+    );
+    assert_eq!(
+        s.factions.reputation(pc, seafarers),
+        sea0 + spill(seafarers)
+    );
+}
+
+#[test]
+fn players_reputations_are_independent() {
+    let mut s = ServerState::new();
+    let a = s.spawn_pc(vec3(0.0, 0.6, 0.0));
+    let b = s.spawn_pc(vec3(3.0, 0.6, 0.0));
+    let wiz = s.spawn_wizard_npc(vec3(1.5, 0.6, 4.0));
+    s.factions.on_hit(a, Faction::WIZARDS);
+    assert!(s.factions.hostile(wiz, Faction::WIZARDS, a, Faction::PC));
+    assert!(!s.factions.hostile(wiz, Faction::WIZARDS, b, Faction::PC));
+    assert_eq!(s.factions.standing(b, Faction::WIZARDS), Standing::Neutral);
+    assert!(s.factions.player(b).is_none());
+}
+
+#[test]
+fn reputation_persists_per_character_across_reconnects() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig {
+        zone_slug: Some("wizard_woods".into()),
+        ..Default::default()
+    });
+    let (ada, actor) = join(&mut host, &mut srv, "Ada");
+    srv.factions.on_hit(actor, Faction::WIZARDS);
+    let rep = srv.factions.reputation(actor, Faction::WIZARDS);
+    host.disconnect(&mut srv, ada);
+    assert!(srv.factions.player(actor).is_none());
+
+    // Someone else starts from the defaults.
+    let (_, bob) = join(&mut host, &mut srv, "Bob");
+    assert_eq!(
+        srv.factions.standing(bob, Faction::WIZARDS),
+        Standing::Neutral
+    );
+
+    // Ada's standing comes back with the character, on a new actor.
+    let (_, again) = join(&mut host, &mut srv, "Ada");
+    assert_ne!(again, actor);
+    assert_eq!(srv.factions.reputation(again, Faction::WIZARDS), rep);
+    assert_eq!(
+        srv.factions.standing(again, Faction::WIZARDS),
+        Standing::Hostile
+    );
+}
@@ -12,14 +12,16 @@ fn caster_selection_uses_faction_not_kind() {
     let _u1 = s.spawn_undead(vec3(0.3, 0.6, 12.0), 0.9, 30);
     let _u2 = s.spawn_undead(vec3(-0.3, 0.6, 14.0), 0.9, 30);
     // Step a few frames to allow AI to face and cast
+    // Expect at least one projectile spawned even though 'kind' is not Wizard anymore
+    // (checked every frame: bolts can land before the last one)
+    let mut saw_any = false;
     for _ in 0..5 {
         s.step_authoritative(0.2);
+        saw_any |= s
+            .ecs
+            .iter()
+            .any(|c| c.projectile.is_some() && c.velocity.is_some());
     }
-    // Expect at least one projectile spawned even though 'kind' is not Wizard anymore
-    let saw_any = s
-        .ecs
-        .iter()
-        .any(|c| c.projectile.is_some() && c.velocity.is_some());
     assert!(
         saw_any,
         "caster selection should be based on faction/components, not ActorKind label"
@@ -2,9 +2,11 @@ use server_core as sc;
 
 #[test]
 fn flipping_pc_vs_wizards_does_not_affect_wizards_vs_undead() {
-    let _s = sc::ServerState::new();
+    let s = sc::ServerState::new();
     // Initial relation should be hostile
-    let initial_hostile =
-        sc::combat::are_hostile(sc::actor::Faction::Wizards, sc::actor::Faction::Undead);
+    let initial_hostile = s
+        .factions
+        .registry
+        .hostile(sc::actor::Faction::WIZARDS, sc::actor::Faction::UNDEAD);
     assert!(initial_hostile);
 }
@@ -38,7 +38,7 @@ fn firebolt_hits_hostile_reliably() {
         .iter()
         .filter(|a| {
             matches!(a.kind, server_core::ActorKind::Wizard)
-                && a.faction == server_core::Faction::Wizards
+                && a.faction == server_core::Faction::WIZARDS
         })
         .map(|a| a.id)
         .next()
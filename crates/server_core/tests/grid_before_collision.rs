@@ -8,7 +8,7 @@ fn grid_rebuild_precedes_collision_single_tick_hit() {
     let _pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
     let wiz = s.ecs.spawn(
         server_core::ActorKind::Wizard,
-        server_core::Faction::Wizards,
+        server_core::Faction::WIZARDS,
         server_core::Transform {
             pos: vec3(0.0, 0.6, 6.0),
             yaw: 0.0,
@@ -0,0 +1,186 @@
+#![allow(clippy::unwrap_used)]
+//! Remote clients join through `Hello` -> `Welcome`: the server spawns their
+//! PC only after the handshake, tells them the actor id, zone and tick rate,
+//! and every later message decodes under the pinned wire versions. Bad
+//! handshakes get a typed `Reject` and the connection is dropped.
+
+mod common;
+
+use client_core::replication::ReplicationBuffer;
+use common::Client;
+use net_core::command::ClientCmd;
+use net_core::handshake::{Hello, PROTOCOL_VERSION, RejectReason, ServerReply, WireVersions};
+use net_core::link::Channel;
+use net_core::snapshot::SnapshotDecode;
+use server_core::ServerState;
+use server_core::session::{ClientId, SessionConfig, SessionHost};
+
+const HZ: u32 = 30;
+const STEP_MS: u64 = 1000 / HZ as u64;
+
+fn host() -> SessionHost {
+    SessionHost::new(SessionConfig {
+        tick_hz: HZ,
+        interest_radius_m: 100.0,
+        zone_slug: Some("wizard_woods".into()),
+        zone_manifest_id: 7,
+        max_clients: 2,
+        ..Default::default()
+    })
+}
+
+fn tick(host: &mut SessionHost, srv: &mut ServerState) {
+    host.pump_inputs(srv);
+    srv.step_authoritative(1.0 / HZ as f32);
+    host.broadcast(srv);
+}
+
+/// Connect a fresh client to a fresh host, send `hello`, and decode the reply.
+fn handshake(hello: &Hello) -> (SessionHost, ServerState, ClientId, Client, ServerReply) {
+    let mut srv = ServerState::new();
+    let mut host = host();
+    let (id, mut c) = Client::accept(&mut host);
+    c.send(Channel::Reliable, hello);
+    tick(&mut host, &mut srv);
+    let first = c.recv_all(STEP_MS).into_iter().next().expect("a reply");
+    let reply = ServerReply::decode(&mut first.as_slice()).unwrap();
+    (host, srv, id, c, reply)
+}
+
+#[test]
+fn welcome_assigns_actor_and_pins_versions() {
+    let (mut host, mut srv, id, mut c, reply) = handshake(&Hello::new("wizard_woods", "Ada"));
+    let ServerReply::Welcome(w) = reply else {
+        panic!("expected welcome, got {reply:?}");
+    };
+    assert_eq!(w.protocol, PROTOCOL_VERSION);
+    assert_eq!(w.zone_manifest_id, 7);
+    assert_eq!(w.tick_hz, HZ as u16);
+    assert_eq!(w.versions, WireVersions::CURRENT);
+    assert_eq!(host.actor_of(id).map(|a| a.0), Some(w.actor_id));
+    assert_eq!(host.pending_len(), 0);
+    let actor = host.actor_of(id).unwrap();
+    assert_eq!(srv.ecs.get(actor).unwrap().name.as_deref(), Some("Ada"));
+
+    let mut rep = ReplicationBuffer::default();
+    rep.set_negotiated(w.versions);
+    let mut saw_self = false;
+    for t in 0..10u32 {
+        c.cmd(
+            Channel::Unreliable,
+            &ClientCmd::Move {
+                dx: 0.0,
+                dz: 1.0,
+                run: 0,
+                seq: t + 1,
+            },
+        );
+        tick(&mut host, &mut srv);
+        for m in c.recv_all(STEP_MS) {
+            rep.apply_message(&m);
+        }
+        saw_self |= rep.actors.iter().any(|a| a.id == w.actor_id);
+    }
+    assert!(saw_self, "pinned client should decode its own actor");
+    assert!(rep.last_input_seq() > 0);
+
+    // A message outside the pinned versions is dropped, not probed.
+    let mut stale = WireVersions::CURRENT;
+    stale.actor_delta = stale.actor_delta.wrapping_add(1);
+    let mut pinned = ReplicationBuffer::default();
+    pinned.set_negotiated(stale);
+    tick(&mut host, &mut srv);
+    for m in c.recv_all(STEP_MS) {
+        if m.first() == Some(&net_core::snapshot::TAG_ACTOR_SNAPSHOT_DELTA) {
+            assert!(!pinned.apply_message(&m));
+        }
+    }
+    assert!(pinned.actors.is_empty());
+}
+
+#[test]
+fn version_mismatch_is_rejected_and_dropped() {
+    let mut hello = Hello::new("wizard_woods", "Ada");
+    hello.protocol = PROTOCOL_VERSION + 1;
+    let (mut host, mut srv, id, mut c, reply) = handshake(&hello);
+    let ServerReply::Reject(r) = reply else {
+        panic!("expected reject, got {reply:?}");
+    };
+    assert_eq!(r.reason, RejectReason::VersionMismatch);
+    assert_eq!(r.server_protocol, PROTOCOL_VERSION);
+    assert!(host.actor_of(id).is_none());
+    assert!(host.is_empty());
+    // The reject is acked by the client's next pump; then the server lets go.
+    for _ in 0..3 {
+        c.recv_all(STEP_MS);
+        tick(&mut host, &mut srv);
+    }
+    assert_eq!(host.pending_len(), 0);
+}
+
+#[test]
+fn wrong_zone_bad_name_and_full_server_are_typed() {
+    let (.., reply) = handshake(&Hello::new("campaign_builder", "Ada"));
+    assert!(matches!(reply, ServerReply::Reject(r) if r.reason == RejectReason::UnknownZone));
+    let (.., reply) = handshake(&Hello::new("wizard_woods", "  "));
+    assert!(matches!(reply, ServerReply::Reject(r) if r.reason == RejectReason::BadName));
+    let (.., reply) = handshake(&Hello::new("wizard_woods", "bad\nname"));
+    assert!(matches!(reply, ServerReply::Reject(r) if r.reason == RejectReason::BadName));
+
+    // Commands before a hello are malformed.
+    let mut srv = ServerState::new();
+    let mut host = host();
+    let (_, mut c) = Client::accept(&mut host);
+    c.cmd(Channel::Reliable, &ClientCmd::Aim { yaw: 0.0 });
+    tick(&mut host, &mut srv);
+    let m = c.recv_all(STEP_MS).remove(0);
+    let reply = ServerReply::decode(&mut m.as_slice()).unwrap();
+    assert!(matches!(reply, ServerReply::Reject(r) if r.reason == RejectReason::Malformed));
+
+    // Capacity is checked at hello time.
+    let mut clients = Vec::new();
+    for name in ["A", "B", "C"] {
+        let (_, mut c) = Client::accept(&mut host);
+        c.send(Channel::Reliable, &Hello::new("wizard_woods", name));
+        clients.push(c);
+    }
+    tick(&mut host, &mut srv);
+    let replies: Vec<ServerReply> = clients
+        .iter_mut()
+        .map(|c| {
+            let m = c.recv_all(STEP_MS).remove(0);
+            ServerReply::decode(&mut m.as_slice()).unwrap()
+        })
+        .collect();
+    let welcomed = replies
+        .iter()
+        .filter(|r| matches!(r, ServerReply::Welcome(_)))
+        .count();
+    assert_eq!(welcomed, 2);
+    assert!(
+        replies
+            .iter()
+            .any(|r| matches!(r, ServerReply::Reject(r) if r.reason == RejectReason::ServerFull))
+    );
+}
+
+#[test]
+fn a_connected_name_cannot_join_twice() {
+    let (mut host, mut srv, first, mut c1, reply) = handshake(&Hello::new("wizard_woods", "Ada"));
+    assert!(matches!(reply, ServerReply::Welcome(_)));
+    let (second, mut c2) = Client::accept(&mut host);
+    c2.send(Channel::Reliable, &Hello::new("wizard_woods", "Ada"));
+    tick(&mut host, &mut srv);
+    let m = c2.recv_all(STEP_MS).remove(0);
+    let reply = ServerReply::decode(&mut m.as_slice()).unwrap();
+    assert!(matches!(reply, ServerReply::Reject(r) if r.reason == RejectReason::NameInUse));
+    assert!(host.actor_of(second).is_none());
+    // The first session is untouched, and other names still get in.
+    c1.recv_all(STEP_MS);
+    assert!(host.actor_of(first).is_some());
+    let (third, mut c3) = Client::accept(&mut host);
+    c3.send(Channel::Reliable, &Hello::new("wizard_woods", "Bea"));
+    tick(&mut host, &mut srv);
+    c3.recv_all(STEP_MS);
+    assert!(host.actor_of(third).is_some());
+}
@@ -0,0 +1,42 @@
+#![allow(clippy::unwrap_used)]
+//! HUD toasts are addressed: a cast rejected for lack of mana toasts only the
+//! client whose PC cast it, not everyone connected.
+
+mod common;
+
+use common::{Client, DT};
+use net_core::command::{CastTarget, ClientCmd};
+use net_core::link::Channel;
+use server_core::ServerState;
+use server_core::session::SessionHost;
+
+#[test]
+fn only_the_caster_sees_its_rejected_cast_toast() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(Default::default());
+    let (caster_id, mut caster) = Client::connect(&mut host, &mut srv);
+    let (_, mut other) = Client::connect(&mut host, &mut srv);
+    let pc = host.actor_of(caster_id).unwrap();
+    srv.ecs.get_mut(pc).unwrap().pool.as_mut().unwrap().mana = 0;
+
+    caster.cmd(
+        Channel::Reliable,
+        &ClientCmd::Cast {
+            ability_id: "wiz.fireball.srd521".into(),
+            target: CastTarget::Ground([10.0, 0.0, 0.0]),
+        },
+    );
+    for _ in 0..3 {
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(DT);
+        host.broadcast(&mut srv);
+        caster.replicate(50);
+        other.replicate(50);
+    }
+    assert_eq!(
+        caster.rep.toasts,
+        vec![1],
+        "the caster is told it lacks mana"
+    );
+    assert!(other.rep.toasts.is_empty(), "nobody else is");
+}
@@ -0,0 +1,271 @@
+#![allow(clippy::unwrap_used)]
+//! Server-authoritative inventory: PCs spawn with the archetype's items,
+//! `ClientCmd::Equip`/`Use`/`Drop` sent over the loopback transport change
+//! the bag and what is worn, the owner is sent the result as an
+//! `InventoryMsg`, and worn items feed Armor Class and weapon attack rolls.
+
+mod common;
+
+use common::{Client, DT, dummy};
+use glam::vec3;
+use net_core::command::{CastTarget, ClientCmd};
+use net_core::link::Channel;
+use net_core::snapshot::{
+    HITFX_WEAPON_CRIT, HITFX_WEAPON_HIT, WORN_BODY, WORN_NONE, WORN_OFF_HAND,
+};
+use server_core::actor::ActorId;
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::inventory::ItemReject;
+use server_core::systems::weapons::AttackReject;
+use server_core::{CombatRng, ServerState};
+use sim_core::rules::attack::{Advantage, roll_attack};
+use sim_core::rules::dice::{CritRule, Dice};
+
+const EAST: CastTarget = CastTarget::Direction([1.0, 0.0, 0.0]);
+
+/// A player on a loopback session of its own.
+struct Player {
+    host: SessionHost,
+    client: Client,
+    actor: ActorId,
+}
+
+impl Player {
+    fn join(srv: &mut ServerState) -> Self {
+        let mut host = SessionHost::new(SessionConfig::default());
+        let (id, client) = Client::connect(&mut host, srv);
+        let actor = host.actor_of(id).unwrap();
+        let mut p = Self {
+            host,
+            client,
+            actor,
+        };
+        p.tick(srv);
+        p
+    }
+
+    fn send(&mut self, cmd: ClientCmd) {
+        self.client.cmd(Channel::Reliable, &cmd);
+    }
+
+    /// Run one server tick and apply what comes back. Returns how many
+    /// inventory messages arrived.
+    fn tick(&mut self, srv: &mut ServerState) -> usize {
+        self.host.pump_inputs(srv);
+        srv.step_authoritative(DT);
+        self.host.broadcast(srv);
+        self.client
+            .replicate(50)
+            .iter()
+            .filter(|m| m.first() == Some(&net_core::snapshot::TAG_INVENTORY))
+            .count()
+    }
+
+    /// Client-side view: (item, count, worn) per stack.
+    fn bag(&self) -> Vec<(String, u16, u8)> {
+        self.client
+            .rep
+            .inventory
+            .iter()
+            .map(|i| (i.item.clone(), i.count, i.worn))
+            .collect()
+    }
+}
+
+fn item(id: &str, count: u16, worn: u8) -> (String, u16, u8) {
+    (id.to_string(), count, worn)
+}
+
+#[test]
+fn equip_use_and_drop_over_the_loopback_session() {
+    let mut srv = ServerState::new();
+    let mut c = Player::join(&mut srv);
+    let pc = c.actor;
+    assert_eq!(c.client.rep.inventory_slots, 20);
+    assert_eq!(
+        c.bag(),
+        [
+            item("potion_healing", 2, WORN_NONE),
+            item("leather_armor", 1, WORN_NONE)
+        ],
+        "the PC archetype's starting kit"
+    );
+    assert_eq!(c.tick(&mut srv), 0, "unchanged inventories are not resent");
+    let base_ac = srv.ecs.get(pc).unwrap().armor_class();
+
+    assert_eq!(srv.give_item(pc, "shield", 1), 1);
+    c.send(ClientCmd::Equip {
+        item_id: "shield".into(),
+    });
+    c.send(ClientCmd::Equip {
+        item_id: "leather_armor".into(),
+    });
+    assert_eq!(c.tick(&mut srv), 1);
+    assert_eq!(
+        c.bag(),
+        [
+            item("potion_healing", 2, WORN_NONE),
+            item("leather_armor", 1, WORN_BODY),
+            item("shield", 1, WORN_OFF_HAND)
+        ]
+    );
+    assert_eq!(srv.ecs.get(pc).unwrap().armor_class(), base_ac + 2 + 1);
+
+    // A potion heals 2d4+2 and is used up.
+    srv.ecs.get_mut(pc).unwrap().hp.hp -= 40;
+    let hurt = srv.ecs.get(pc).unwrap().hp.hp;
+    c.send(ClientCmd::Use {
+        item_id: "potion_healing".into(),
+    });
+    c.tick(&mut srv);
+    let healed = srv.ecs.get(pc).unwrap().hp.hp - hurt;
+    assert!((4..=10).contains(&healed), "{healed}");
+    assert_eq!(c.bag()[0], item("potion_healing", 1, WORN_NONE));
+
+    // Dropping more than is carried drops the whole stack; taking off the
+    // shield and dropping the worn armor both lower Armor Class.
+    c.send(ClientCmd::Drop {
+        item_id: "potion_healing".into(),
+        count: 5,
+    });
+    c.send(ClientCmd::Equip {
+        item_id: "shield".into(),
+    });
+    c.tick(&mut srv);
+    assert_eq!(
+        c.bag(),
+        [
+            item("leather_armor", 1, WORN_BODY),
+            item("shield", 1, WORN_NONE)
+        ]
+    );
+    assert_eq!(srv.ecs.get(pc).unwrap().armor_class(), base_ac + 1);
+    c.send(ClientCmd::Drop {
+        item_id: "leather_armor".into(),
+        count: 1,
+    });
+    c.tick(&mut srv);
+    assert_eq!(c.bag(), [item("shield", 1, WORN_NONE)]);
+    assert_eq!(srv.ecs.get(pc).unwrap().armor_class(), base_ac);
+
+    // Commands for items the PC lacks change nothing and send nothing.
+    let hp = srv.ecs.get(pc).unwrap().hp.hp;
+    c.send(ClientCmd::Use {
+        item_id: "potion_healing".into(),
+    });
+    c.send(ClientCmd::Equip {
+        item_id: "quarterstaff".into(),
+    });
+    assert_eq!(c.tick(&mut srv), 0);
+    assert_eq!(srv.ecs.get(pc).unwrap().hp.hp, hp);
+}
+
+#[test]
+fn worn_weapons_add_their_bonus_and_can_be_wielded_untrained() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = dummy(&mut s, vec3(2.0, 0.6, 0.0), 1000);
+    s.ecs
+        .get_mut(pc)
+        .unwrap()
+        .weapons
+        .as_mut()
+        .unwrap()
+        .known
+        .clear();
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "quarterstaff", EAST),
+        Err(AttackReject::NotEquipped)
+    );
+    s.give_item(pc, "quarterstaff_plus_one", 1);
+    s.equip_item(pc, "quarterstaff_plus_one").unwrap();
+    assert_eq!(s.ecs.get(pc).unwrap().weapon_bonus("quarterstaff"), 1);
+    assert_eq!(s.ecs.get(pc).unwrap().weapon_bonus("shortbow"), 0);
+
+    // Same seed, same rolls: the staff's bonus plus the item's.
+    let bonus = s.specs_weapons.get("quarterstaff").unwrap().attack_bonus + 1;
+    let ac = s.ecs.get(z).unwrap().armor_class();
+    let mut rng = CombatRng::seeded(s.combat_rng.seed());
+    for _ in 0..20 {
+        s.enqueue_weapon_attack(pc, "quarterstaff", EAST).unwrap();
+        s.step_authoritative(DT);
+        let hit = std::mem::take(&mut s.fx_hits)
+            .iter()
+            .any(|h| h.kind == HITFX_WEAPON_HIT || h.kind == HITFX_WEAPON_CRIT);
+        let roll = roll_attack(&mut rng.0, Advantage::Normal, bonus, ac);
+        assert_eq!(hit, roll.hit);
+        if roll.hit {
+            let damage = &s.specs_weapons.get("quarterstaff").unwrap().damage;
+            Dice::parse(damage).roll_damage(&mut rng.0, roll.crit, CritRule::Nat20DoubleDice);
+        }
+        while s.ecs.get(pc).unwrap().weapons.as_ref().unwrap().ready_in_s > 0.0 {
+            s.step_authoritative(DT);
+        }
+    }
+
+    // Taking the staff off takes the weapon with it.
+    s.equip_item(pc, "quarterstaff_plus_one").unwrap();
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "quarterstaff", EAST),
+        Err(AttackReject::NotEquipped)
+    );
+}
+
+#[test]
+fn bags_cap_stacks_and_refuse_bad_commands() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let max = s.specs_items.get("potion_healing").unwrap().max_stack;
+    // Starting kit: one potion stack and the armor, so 18 free slots.
+    let given = s.give_item(pc, "potion_healing", 1000);
+    assert_eq!(given, max - 2 + 18 * max);
+    let inv = s.ecs.get(pc).unwrap().inventory.clone().unwrap();
+    assert_eq!(inv.items.len(), inv.slots);
+    assert!(inv.items.iter().all(|st| st.count <= max));
+    assert_eq!(s.give_item(pc, "shield", 1), 0, "bag full");
+    assert_eq!(s.drop_item(pc, "potion_healing", 15), Ok(15));
+    assert_eq!(
+        inv.count("potion_healing") - 15,
+        s.ecs
+            .get(pc)
+            .unwrap()
+            .inventory
+            .as_ref()
+            .unwrap()
+            .count("potion_healing")
+    );
+
+    assert_eq!(
+        s.equip_item(pc, "dragon_scale"),
+        Err(ItemReject::UnknownItem)
+    );
+    assert_eq!(
+        s.equip_item(pc, "potion_healing"),
+        Err(ItemReject::NotEquippable)
+    );
+    assert_eq!(s.use_item(pc, "leather_armor"), Err(ItemReject::NotUsable));
+    assert_eq!(s.equip_item(pc, "shield"), Err(ItemReject::NotCarried));
+    assert_eq!(s.drop_item(pc, "shield", 1), Err(ItemReject::NotCarried));
+
+    // Consumables can apply statuses; the incapacitated can't drink.
+    s.drop_item(pc, "potion_healing", 1000).unwrap();
+    s.give_item(pc, "elixir_of_warding", 2);
+    s.use_item(pc, "elixir_of_warding").unwrap();
+    let shielded = s.statuses.get("shielded").unwrap().net_id;
+    assert!(s.ecs.get(pc).unwrap().status(shielded).is_some());
+    s.apply_status(pc, "stunned", None);
+    assert_eq!(
+        s.use_item(pc, "elixir_of_warding"),
+        Err(ItemReject::Incapacitated)
+    );
+    assert_eq!(
+        s.ecs
+            .get(pc)
+            .unwrap()
+            .inventory
+            .as_ref()
+            .unwrap()
+            .count("elixir_of_warding"),
+        1
+    );
+}
@@ -0,0 +1,86 @@
+#![allow(clippy::unwrap_used)]
+//! The in-process demo's actor stream (`session::local_actor_delta`) starts
+//! from a keyframe, diffs against what the renderer acks, and starts over from
+//! a keyframe when the demo resets its baselines for a new renderer.
+
+mod common;
+
+use client_core::replication::ReplicationBuffer;
+use common::{DT, dummy};
+use glam::vec3;
+use net_core::baseline::{BaselineTracker, NO_BASELINE};
+use net_core::snapshot::{ActorSnapshotDelta, SnapshotEncode};
+use server_core::ServerState;
+use server_core::session::local_actor_delta;
+
+const RADIUS_M: f32 = 60.0;
+
+/// One demo tick: step, build the delta around the PC, deliver it framed.
+fn pump(
+    srv: &mut ServerState,
+    baseline: &mut BaselineTracker,
+    buf: &mut ReplicationBuffer,
+    tick: u64,
+) -> ActorSnapshotDelta {
+    srv.step_authoritative(DT);
+    let center = srv.ecs.get(srv.pc_actor.unwrap()).unwrap().tr.pos;
+    let d = local_actor_delta(srv, baseline, tick, center, RADIUS_M);
+    let mut p = Vec::new();
+    d.encode(&mut p);
+    let mut framed = Vec::new();
+    net_core::frame::write_msg(&mut framed, &p);
+    buf.apply_message(&framed);
+    d
+}
+
+#[test]
+fn demo_stream_starts_from_keyframes_and_follows_acks() {
+    let mut srv = ServerState::new();
+    let pc = srv.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let near = dummy(&mut srv, vec3(6.0, 0.6, 0.0), 30);
+    dummy(&mut srv, vec3(200.0, 0.6, 0.0), 30);
+    let ids = |buf: &ReplicationBuffer| {
+        let mut v: Vec<u32> = buf.actors.iter().map(|a| a.id).collect();
+        v.sort_unstable();
+        v
+    };
+    let mut want = vec![pc.0, near.0];
+    want.sort_unstable();
+
+    let mut baseline = BaselineTracker::new();
+    let mut buf = ReplicationBuffer::default();
+    let d0 = pump(&mut srv, &mut baseline, &mut buf, 0);
+    assert_eq!(d0.baseline, NO_BASELINE, "the first delta is a keyframe");
+    assert_eq!(
+        ids(&buf),
+        want,
+        "actors in range render from the first tick"
+    );
+
+    // Once the renderer acks, deltas diff against the acked tick.
+    let mut tick = 1;
+    for _ in 0..10 {
+        baseline.ack(buf.last_applied_tick().unwrap());
+        let d = pump(&mut srv, &mut baseline, &mut buf, tick);
+        assert_eq!(d.baseline, tick - 1);
+        assert_eq!(ids(&buf), want);
+        tick += 1;
+    }
+    srv.ecs.get_mut(near).unwrap().hp.hp = 12;
+    baseline.ack(buf.last_applied_tick().unwrap());
+    pump(&mut srv, &mut baseline, &mut buf, tick);
+    assert_eq!(buf.actors.iter().find(|a| a.id == near.0).unwrap().hp, 12);
+
+    // A new renderer holds no baselines, so deltas on the old acks render
+    // nothing; the demo resets its tracker and restarts from a keyframe.
+    baseline.ack(tick);
+    let mut fresh = ReplicationBuffer::default();
+    let d = pump(&mut srv, &mut baseline, &mut fresh, tick + 1);
+    assert_eq!(d.baseline, tick);
+    assert!(fresh.actors.is_empty());
+    let mut baseline = BaselineTracker::new();
+    let mut fresh = ReplicationBuffer::default();
+    let d = pump(&mut srv, &mut baseline, &mut fresh, 0);
+    assert_eq!(d.baseline, NO_BASELINE);
+    assert_eq!(ids(&fresh), want);
+}
@@ -0,0 +1,196 @@
+#![allow(clippy::unwrap_used)]
+//! Loot over the loopback session: killing an Undead leaves personal drops
+//! for each contributor, each client is sent only its own (`LootMsg`), and
+//! `ClientCmd::PickUp` moves a drop into the bag when its owner stands close
+//! enough. Items dropped from the bag land on the ground the same way.
+
+mod common;
+
+use common::{Client, DT};
+use data_runtime::specs::loot::LootSpecDb;
+use glam::{Vec3, vec3};
+use net_core::command::{CastTarget, ClientCmd};
+use net_core::link::Channel;
+use server_core::ServerState;
+use server_core::actor::ActorId;
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::inventory::ItemReject;
+use server_core::systems::threat;
+
+struct Player {
+    client: Client,
+    actor: ActorId,
+}
+
+impl Player {
+    fn send(&mut self, cmd: ClientCmd) {
+        self.client.cmd(Channel::Reliable, &cmd);
+    }
+}
+
+struct Table {
+    srv: ServerState,
+    host: SessionHost,
+    players: Vec<Player>,
+}
+
+impl Table {
+    fn new(n: usize) -> Self {
+        let mut srv = ServerState::new();
+        let mut host = SessionHost::new(SessionConfig::default());
+        let players = (0..n)
+            .map(|_| {
+                let (id, client) = Client::connect(&mut host, &mut srv);
+                Player {
+                    client,
+                    actor: host.actor_of(id).unwrap(),
+                }
+            })
+            .collect();
+        Self { srv, host, players }
+    }
+
+    fn tick(&mut self) {
+        self.host.pump_inputs(&mut self.srv);
+        self.srv.step_authoritative(DT);
+        self.host.broadcast(&mut self.srv);
+        for c in &mut self.players {
+            c.client.replicate(50);
+        }
+    }
+
+    fn place(&mut self, who: usize, pos: Vec3) {
+        let a = self.players[who].actor;
+        self.srv.ecs.get_mut(a).unwrap().tr.pos = pos;
+    }
+
+    fn potions(&self, who: usize) -> u32 {
+        let a = self.players[who].actor;
+        let inv = self.srv.ecs.get(a).unwrap().inventory.as_ref().unwrap();
+        inv.count("potion_healing")
+    }
+}
+
+#[test]
+fn contributors_see_and_pick_up_only_their_own_drops() {
+    let mut t = Table::new(2);
+    // Every kill drops exactly two potions per contributor.
+    t.srv.specs_loot = LootSpecDb::parse(
+        r#"
+        [tables.Undead]
+        entries = [{ item = "potion_healing", weight = 1, min = 2, max = 2 }]
+        "#,
+    )
+    .unwrap();
+    t.place(0, vec3(0.0, 0.6, 0.0));
+    t.place(1, vec3(0.0, 0.6, 12.0));
+    let z = t.srv.spawn_undead(vec3(2.0, 0.6, 0.0), 0.9, 1);
+    {
+        let c = t.srv.ecs.get_mut(z).unwrap();
+        c.tr.pos = vec3(2.0, 0.6, 0.0);
+        c.move_speed = None;
+        c.melee = None;
+    }
+    let (a, b) = (t.players[0].actor, t.players[1].actor);
+    threat::on_damage(&mut t.srv, b, z, 1);
+    t.tick();
+    assert!(t.players.iter().all(|c| c.client.rep.loot.is_empty()));
+
+    // Player 0 swings until the Undead drops.
+    for _ in 0..200 {
+        if !t.srv.ecs.get(z).is_some_and(|c| c.hp.alive()) {
+            break;
+        }
+        t.players[0].send(ClientCmd::Attack {
+            weapon_id: "quarterstaff".into(),
+            target: CastTarget::Actor(z.0),
+        });
+        t.tick();
+    }
+    t.tick();
+    let (p0, p1) = (t.potions(0), t.potions(1));
+    let mine = t.players[0].client.rep.loot.clone();
+    let theirs = t.players[1].client.rep.loot.clone();
+    assert_eq!(mine.len(), 1);
+    assert_eq!(theirs.len(), 1);
+    assert_eq!(
+        (mine[0].item.as_str(), mine[0].count),
+        ("potion_healing", 2)
+    );
+    assert_ne!(mine[0].id, theirs[0].id, "personal drops");
+    assert_eq!(t.srv.drops.get(mine[0].id).unwrap().owner, a);
+    assert_eq!(t.srv.drops.get(theirs[0].id).unwrap().owner, b);
+
+    // Someone else's drop, or one too far away, can't be picked up.
+    t.players[1].send(ClientCmd::PickUp {
+        drop_id: mine[0].id,
+    });
+    t.players[1].send(ClientCmd::PickUp {
+        drop_id: theirs[0].id,
+    });
+    t.tick();
+    assert_eq!(t.players[0].client.rep.loot, mine);
+    assert_eq!(t.players[1].client.rep.loot, theirs);
+    assert_eq!((t.potions(0), t.potions(1)), (p0, p1));
+
+    // In range, each picks up their own.
+    t.place(1, vec3(1.0, 0.6, 1.0));
+    for i in 0..2 {
+        let drop_id = t.players[i].client.rep.loot[0].id;
+        t.players[i].send(ClientCmd::PickUp { drop_id });
+    }
+    t.tick();
+    assert_eq!((t.potions(0), t.potions(1)), (p0 + 2, p1 + 2));
+    assert!(t.players.iter().all(|c| c.client.rep.loot.is_empty()));
+    assert!(t.srv.drops.items.is_empty());
+    let inv = &t.players[0].client.rep.inventory;
+    let shown: u16 = inv
+        .iter()
+        .filter(|i| i.item == "potion_healing")
+        .map(|i| i.count)
+        .sum();
+    assert_eq!(u32::from(shown), p0 + 2, "the bag is replicated");
+}
+
+#[test]
+fn dropped_items_can_be_picked_up_again_until_the_bag_is_full() {
+    let mut t = Table::new(1);
+    t.tick();
+    t.players[0].send(ClientCmd::Drop {
+        item_id: "leather_armor".into(),
+        count: 1,
+    });
+    t.tick();
+    let loot = t.players[0].client.rep.loot.clone();
+    assert_eq!(loot.len(), 1);
+    assert_eq!((loot[0].item.as_str(), loot[0].count), ("leather_armor", 1));
+
+    // Fill the bag: nothing fits, so the drop stays put.
+    let a = t.players[0].actor;
+    t.srv.give_item(a, "quarterstaff", 100);
+    t.players[0].send(ClientCmd::PickUp {
+        drop_id: loot[0].id,
+    });
+    t.tick();
+    assert_eq!(t.players[0].client.rep.loot, loot);
+
+    t.srv.drop_item(a, "quarterstaff", 1).unwrap();
+    t.tick();
+    assert_eq!(t.players[0].client.rep.loot.len(), 2);
+    t.players[0].send(ClientCmd::PickUp {
+        drop_id: loot[0].id,
+    });
+    t.tick();
+    let inv = t.srv.ecs.get(a).unwrap().inventory.as_ref().unwrap();
+    assert_eq!(inv.count("leather_armor"), 1);
+    assert_eq!(
+        t.players[0].client.rep.loot.len(),
+        1,
+        "the staff is still down"
+    );
+
+    // A drop of an item the server doesn't know is refused as such.
+    let pos = t.srv.ecs.get(a).unwrap().tr.pos;
+    let bogus = t.srv.drops.spawn(a, "no_such_item", 1, pos);
+    assert_eq!(t.srv.pick_up(a, bogus), Err(ItemReject::UnknownItem));
+}
@@ -0,0 +1,159 @@
+#![allow(clippy::unwrap_used)]
+//! Loot tables roll on `ServerState::loot_rng`: a seeded server drops the
+//! same loot every time, and over many kills each entry drops about as often
+//! as its weight says. Every contributor to a kill rolls separately and owns
+//! their drops; bystanders get nothing. Drops expire, and vanish with their
+//! owner.
+
+use std::collections::HashMap;
+
+use glam::{Vec3, vec3};
+use server_core::actor::ActorId;
+use server_core::ecs::schedule::{Ctx, DeathEvent};
+use server_core::systems::loot::{DROP_TTL_S, loot_tick, roll_loot};
+use server_core::systems::threat;
+use server_core::{CombatRng, ServerState};
+
+const DT: f32 = 1.0 / 30.0;
+
+/// Credit `victim`'s death to `killer` and roll its loot.
+fn kill(s: &mut ServerState, victim: ActorId, killer: Option<ActorId>) {
+    let mut ctx = Ctx {
+        dt: DT,
+        ..Default::default()
+    };
+    ctx.deaths.push(DeathEvent { id: victim, killer });
+    loot_tick(s, &mut ctx);
+}
+
+fn drops_of(s: &ServerState, owner: ActorId) -> Vec<(String, u32)> {
+    s.drops
+        .owned_by(owner)
+        .map(|d| (d.item.clone(), d.count))
+        .collect()
+}
+
+#[test]
+fn undead_drops_follow_the_table_weights() {
+    let s = ServerState::new();
+    let table = s.specs_loot.get("Undead").unwrap().clone();
+    assert_eq!(table.rolls, 1);
+    let total = f64::from(table.total_weight());
+    let n = 20_000;
+    let mut rng = CombatRng::seeded(42);
+    // item -> (kills that dropped it, units dropped)
+    let mut seen: HashMap<String, (u32, u32)> = HashMap::new();
+    let mut empty = 0;
+    for _ in 0..n {
+        let got = roll_loot(&table, &mut rng.0);
+        if got.is_empty() {
+            empty += 1;
+        }
+        for (item, count) in got {
+            let e = seen.entry(item).or_default();
+            e.0 += 1;
+            e.1 += count;
+        }
+    }
+    for e in &table.entries {
+        let p = f64::from(e.weight) / total;
+        let (times, units) = if e.item.is_empty() {
+            (empty, 0)
+        } else {
+            seen[&e.item]
+        };
+        let share = f64::from(times) / f64::from(n);
+        assert!((share - p).abs() < 0.015, "{}: {share} vs {p}", e.item);
+        if !e.item.is_empty() {
+            let mean = f64::from(units) / f64::from(times);
+            let want = f64::from(e.min + e.max) / 2.0;
+            assert!((mean - want).abs() < 0.05, "{}: {mean} vs {want}", e.item);
+        }
+    }
+
+    // The same seed replays the same drops; another seed does not.
+    let seq = |seed| {
+        let mut rng = CombatRng::seeded(seed);
+        (0..64)
+            .map(|_| roll_loot(&table, &mut rng.0))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(seq(7), seq(7));
+    assert_ne!(seq(7), seq(8));
+}
+
+/// Two players fighting one Undead (one lands the kills) and a bystander.
+fn raid(seed: u64) -> (ServerState, [ActorId; 4]) {
+    let mut s = ServerState::new();
+    s.loot_rng = CombatRng::seeded(seed);
+    let a = s.spawn_pc(vec3(0.0, 0.6, 0.0));
+    let b = s.spawn_pc(vec3(3.0, 0.6, 0.0));
+    let bystander = s.spawn_pc(vec3(-3.0, 0.6, 0.0));
+    let z = s.spawn_undead(vec3(0.0, 0.6, 8.0), 0.9, 50);
+    threat::on_damage(&mut s, b, z, 5);
+    for _ in 0..500 {
+        kill(&mut s, z, Some(a));
+    }
+    (s, [a, b, bystander, z])
+}
+
+#[test]
+fn every_contributor_rolls_their_own_loot() {
+    let (s, [a, b, bystander, z]) = raid(11);
+    let (da, db) = (drops_of(&s, a), drops_of(&s, b));
+    assert!(drops_of(&s, bystander).is_empty());
+    // The Undead table drops on about 40% of kills, for each of them.
+    for d in [&da, &db] {
+        assert!((160..=240).contains(&d.len()), "{}", d.len());
+    }
+    assert_ne!(da, db, "separate rolls");
+    assert_eq!(s.drops.items.len(), da.len() + db.len());
+    let ids: std::collections::HashSet<u32> = s.drops.items.iter().map(|d| d.id).collect();
+    assert_eq!(ids.len(), s.drops.items.len(), "drop ids are unique");
+    // Drops lie around the body.
+    let body = s.ecs.get(z).unwrap().tr.pos;
+    assert!(s.drops.items.iter().all(|d| d.pos.distance(body) < 1.0));
+
+    let (again, _) = raid(11);
+    assert_eq!(again.drops.items, s.drops.items, "seeded");
+}
+
+#[test]
+fn kills_without_players_or_tables_drop_nothing() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc(Vec3::ZERO);
+    let other = s.spawn_pc(vec3(4.0, 0.6, 0.0));
+    let z = s.spawn_undead(vec3(0.0, 0.6, 8.0), 0.9, 50);
+    let dk = s.spawn_death_knight(vec3(8.0, 0.6, 8.0));
+    for _ in 0..50 {
+        kill(&mut s, z, Some(dk));
+        kill(&mut s, other, Some(pc));
+    }
+    assert!(s.drops.items.is_empty());
+}
+
+#[test]
+fn drops_expire_and_leave_with_their_owner() {
+    let mut s = ServerState::new();
+    let a = s.spawn_pc(Vec3::ZERO);
+    let b = s.spawn_pc(vec3(3.0, 0.6, 0.0));
+    let z = s.spawn_undead(vec3(0.0, 0.6, 8.0), 0.9, 50);
+    threat::on_damage(&mut s, b, z, 5);
+    while drops_of(&s, a).is_empty() || drops_of(&s, b).is_empty() {
+        kill(&mut s, z, Some(a));
+    }
+    s.ecs.apply_cmds(&mut server_core::ecs::CmdBuf {
+        spawns: Vec::new(),
+        despawns: vec![b],
+    });
+    let mut ctx = Ctx {
+        dt: DT,
+        ..Default::default()
+    };
+    loot_tick(&mut s, &mut ctx);
+    assert!(drops_of(&s, b).is_empty());
+    assert!(!drops_of(&s, a).is_empty());
+    ctx.dt = DROP_TTL_S;
+    loot_tick(&mut s, &mut ctx);
+    assert!(s.drops.items.is_empty());
+}
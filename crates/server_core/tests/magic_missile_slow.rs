@@ -17,7 +17,7 @@ fn magic_missile_applies_slow_on_hit() {
             .iter()
             .filter(|a| {
                 matches!(a.kind, server_core::ActorKind::Wizard)
-                    && a.faction == server_core::Faction::Pc
+                    && a.faction == server_core::Faction::PC
             })
             .map(|a| a.tr.pos)
             .collect();
@@ -26,8 +26,11 @@ fn magic_missile_applies_slow_on_hit() {
     if let Some(a) = s.ecs.get(z)
         && a.hp.alive()
     {
-        assert!(a.slow.is_some(), "expected Slow applied to intended target");
+        assert!(
+            a.speed_mul() < 1.0,
+            "expected Slow applied to intended target"
+        );
     }
-    let any_slow = s.ecs.iter().any(|c| c.slow.is_some());
+    let any_slow = s.ecs.iter().any(|c| c.speed_mul() < 1.0);
     assert!(any_slow, "expected at least one Slow after MM volley");
 }
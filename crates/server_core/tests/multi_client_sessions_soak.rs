@@ -0,0 +1,146 @@
+#![allow(clippy::unwrap_used)]
+//! 16 loopback clients each steer their own PC in a distinct direction; every
+//! client must see only its own actor follow its input.
+
+use net_core::baseline::{ActorSet, BaselineReceiver, NO_BASELINE};
+use net_core::command::ClientCmd;
+use net_core::link::{Channel, Endpoint};
+use net_core::snapshot::{ActorSnapshotDelta, SnapshotDecode, TAG_ACTOR_SNAPSHOT_DELTA};
+use net_core::transport::LocalLoopbackTransport;
+use server_core::ServerState;
+use server_core::session::{ClientId, SessionConfig, SessionHost};
+
+const CLIENTS: usize = 16;
+const TICKS: usize = 90;
+
+struct Client {
+    id: ClientId,
+    xport: LocalLoopbackTransport,
+    link: Endpoint,
+    dir: [f32; 2],
+    actor: u32,
+    baselines: BaselineReceiver,
+    seen: ActorSet,
+    deltas: usize,
+    keyframes: usize,
+}
+
+impl Client {
+    fn send(&mut self, cmd: ClientCmd) {
+        let mut p = Vec::new();
+        cmd.encode(&mut p);
+        self.link.send(Channel::Unreliable, p).unwrap();
+    }
+
+    fn drain(&mut self, now_ms: u64) {
+        self.link.pump(&self.xport, now_ms).unwrap();
+        while let Some((_, payload)) = self.link.recv() {
+            if payload.first() != Some(&TAG_ACTOR_SNAPSHOT_DELTA) {
+                continue;
+            }
+            let mut slice: &[u8] = &payload;
+            let d = ActorSnapshotDelta::decode(&mut slice).unwrap();
+            self.deltas += 1;
+            if d.baseline == NO_BASELINE {
+                self.keyframes += 1;
+            }
+            if let Some(set) = self.baselines.apply(&d) {
+                self.seen = set.clone();
+            }
+        }
+        if let Some(tick) = self.baselines.last_applied() {
+            self.send(ClientCmd::Ack { tick });
+        }
+    }
+}
+
+#[test]
+fn sixteen_clients_move_independently() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig {
+        interest_radius_m: 200.0,
+        ..Default::default()
+    });
+    let mut clients = Vec::new();
+    for i in 0..CLIENTS {
+        let (srv_end, cli_end) = LocalLoopbackTransport::new(1024);
+        let id = host.connect(&mut srv, Box::new(srv_end));
+        let a = i as f32 / CLIENTS as f32 * std::f32::consts::TAU;
+        clients.push(Client {
+            id,
+            xport: cli_end,
+            link: Endpoint::default(),
+            dir: [a.cos(), a.sin()],
+            actor: host.actor_of(id).unwrap().0,
+            baselines: BaselineReceiver::new(),
+            seen: ActorSet::new(),
+            deltas: 0,
+            keyframes: 0,
+        });
+    }
+    assert_eq!(host.len(), CLIENTS);
+    assert!(
+        srv.pc_actor.is_none(),
+        "sessions must not claim the singleton PC"
+    );
+
+    let start: Vec<[f32; 3]> = clients
+        .iter()
+        .map(|c| {
+            let p = srv.ecs.get(server_core::ActorId(c.actor)).unwrap().tr.pos;
+            [p.x, p.y, p.z]
+        })
+        .collect();
+    let dt = 1.0 / 30.0;
+    for t in 0..TICKS {
+        let now_ms = t as u64 * 33;
+        for c in &mut clients {
+            let (dx, dz) = (c.dir[0], c.dir[1]);
+            c.send(ClientCmd::Move {
+                dx,
+                dz,
+                run: 0,
+                seq: t as u32 + 1,
+            });
+            c.link.pump(&c.xport, now_ms).unwrap();
+        }
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(dt);
+        host.broadcast(&mut srv);
+        for c in &mut clients {
+            c.drain(now_ms);
+        }
+    }
+
+    for (i, c) in clients.iter().enumerate() {
+        assert_eq!(c.deltas, TICKS, "client {i} missed snapshots");
+        // Only the first delta (before any ack lands) is a keyframe.
+        assert_eq!(c.keyframes, 1, "client {i} acks were not honored");
+        // Every client replicates every PC (all within interest).
+        for other in &clients {
+            assert!(c.seen.contains_key(&other.actor), "client {i} lacks peer");
+        }
+        let p = c.seen[&c.actor].pos;
+        let moved = [p[0] - start[i][0], p[2] - start[i][2]];
+        let len = (moved[0] * moved[0] + moved[1] * moved[1]).sqrt();
+        assert!(len > 5.0, "client {i} barely moved: {len}");
+        let dot = (moved[0] * c.dir[0] + moved[1] * c.dir[1]) / len;
+        assert!(
+            dot > 0.99,
+            "client {i} moved off its own heading (dot={dot})"
+        );
+    }
+
+    // Dropping a connection despawns only that PC.
+    let gone = clients[3].actor;
+    host.disconnect(&mut srv, clients[3].id);
+    assert_eq!(host.len(), CLIENTS - 1);
+    assert!(srv.ecs.get(server_core::ActorId(gone)).is_none());
+    let now_ms = TICKS as u64 * 33;
+    let c0 = &mut clients[0];
+    c0.link.pump(&c0.xport, now_ms).unwrap();
+    host.pump_inputs(&mut srv);
+    host.broadcast(&mut srv);
+    clients[0].drain(now_ms);
+    assert!(!clients[0].seen.contains_key(&gone));
+}
@@ -0,0 +1,73 @@
+#![allow(clippy::unwrap_used)]
+//! Remote intents with non-finite floats are dropped before they reach the
+//! actor, so a NaN can't poison its position (and, through it, targeting and
+//! the saved character). Finite axes are clamped to `[-1, 1]`.
+
+mod common;
+
+use common::{Client, DT};
+use net_core::command::ClientCmd;
+use net_core::link::Channel;
+use server_core::ServerState;
+use server_core::persist::CharacterRecord;
+use server_core::session::SessionHost;
+
+#[test]
+fn nan_and_infinite_intents_leave_the_pc_usable() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(Default::default());
+    let (id, mut c) = Client::connect(&mut host, &mut srv);
+    let pc = host.actor_of(id).unwrap();
+    let start = srv.ecs.get(pc).unwrap().tr.pos;
+
+    let bad = [
+        ClientCmd::Move {
+            dx: f32::NAN,
+            dz: 1.0,
+            run: 0,
+            seq: 1,
+        },
+        ClientCmd::Move {
+            dx: 0.0,
+            dz: f32::INFINITY,
+            run: 0,
+            seq: 0,
+        },
+        ClientCmd::Aim { yaw: f32::NAN },
+        ClientCmd::Dodge {
+            dx: f32::NEG_INFINITY,
+            dz: 0.0,
+        },
+    ];
+    for cmd in &bad {
+        c.cmd(Channel::Unreliable, cmd);
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(DT);
+        host.broadcast(&mut srv);
+    }
+    let pc_c = srv.ecs.get(pc).unwrap();
+    assert_eq!(pc_c.tr.pos, start, "rejected intents don't move the PC");
+    assert!(pc_c.tr.yaw.is_finite());
+
+    // An oversized axis is clamped, not rejected.
+    c.cmd(
+        Channel::Unreliable,
+        &ClientCmd::Move {
+            dx: 0.0,
+            dz: 1e30,
+            run: 0,
+            seq: 2,
+        },
+    );
+    host.pump_inputs(&mut srv);
+    srv.step_authoritative(DT);
+    host.broadcast(&mut srv);
+    let pos = srv.ecs.get(pc).unwrap().tr.pos;
+    assert!(pos.is_finite() && pos.z > start.z);
+
+    // The character still saves and loads.
+    let mut rec = CharacterRecord::new("tester");
+    assert!(srv.capture_character(pc, None, &mut rec));
+    let back = CharacterRecord::from_json(&rec.to_json().unwrap()).unwrap();
+    assert_eq!(back.pos, rec.pos);
+}
@@ -0,0 +1,149 @@
+#![allow(clippy::unwrap_used)]
+//! Hostile NPCs path around static walls and destructible ruins instead of
+//! walking through them, and re-plan when a carve opens a passage.
+
+use collision_static::{Aabb, OBB, ShapeRef, StaticCollider, StaticIndex};
+use glam::{Mat3, Vec2, Vec3, vec3};
+use server_core::ServerState;
+use server_core::ecs::schedule::Ctx;
+
+const DT: f32 = 1.0 / 30.0;
+
+/// A 10 m long, 0.4 m thick, 3 m tall wall across X at z = 0.
+fn wall() -> StaticIndex {
+    let center = vec3(0.0, 1.5, 0.0);
+    let half = vec3(5.0, 1.5, 0.2);
+    StaticIndex {
+        colliders: vec![StaticCollider {
+            aabb: Aabb {
+                min: center - half,
+                max: center + half,
+            },
+            shape: ShapeRef::Box(OBB {
+                center,
+                half_extents: half,
+                rot3x3: Mat3::IDENTITY,
+            }),
+        }],
+    }
+}
+
+fn dist_xz(a: Vec3, b: Vec3) -> f32 {
+    Vec2::new(a.x - b.x, a.z - b.z).length()
+}
+
+#[test]
+fn zombie_walks_around_a_wall() {
+    let mut s = ServerState::new();
+    s.static_colliders = Some(wall());
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 6.0));
+    let z = s.spawn_undead(vec3(0.0, 0.6, -6.0), 0.6, 30);
+    s.step_authoritative(DT);
+
+    // The route is a couple of taut corners around an end of the wall.
+    let path = s
+        .nav
+        .find_path(Vec2::new(0.0, -6.0), Vec2::new(0.0, 6.0), 0.6)
+        .unwrap();
+    assert!(path.complete);
+    assert!(
+        (2..=4).contains(&path.waypoints.len()),
+        "{:?}",
+        path.waypoints
+    );
+
+    let pc_pos = s.ecs.get(pc).unwrap().tr.pos;
+    let mut max_x = 0.0f32;
+    let mut reached = false;
+    for _ in 0..(30 * 20) {
+        s.step_authoritative(DT);
+        let p = s.ecs.get(z).unwrap().tr.pos;
+        max_x = max_x.max(p.x.abs());
+        assert!(
+            !(p.x.abs() < 5.0 + 0.6 && p.z.abs() < 0.2 + 0.6 - 0.05),
+            "zombie overlapped the wall at {p}"
+        );
+        if dist_xz(p, pc_pos) < 0.6 + 0.7 + 0.5 {
+            reached = true;
+            break;
+        }
+    }
+    assert!(reached, "zombie never reached the PC");
+    assert!(max_x >= 5.0, "zombie did not go around the wall end");
+}
+
+#[test]
+fn carve_opens_a_passage_and_triggers_a_replan() {
+    let mut s = ServerState::new();
+    // Closed 16 x 16 m ruins spanning x, z 0..16 with 1 m walls.
+    server_core::scene_build::add_demo_ruins_destructible_at(&mut s, vec3(8.0, 0.0, 8.0), 1);
+    let pc = s.spawn_pc_at(vec3(8.0, 0.6, 8.0));
+    let z = s.spawn_undead(vec3(8.0, 0.6, -8.0), 0.6, 30);
+    for _ in 0..(30 * 5) {
+        s.step_authoritative(DT);
+    }
+    let p = s.ecs.get(z).unwrap().tr.pos;
+    assert!(p.z < 0.0, "zombie got into the sealed ruins: {p}");
+    let version = s.nav.version;
+
+    // Blow a hole through the south wall.
+    let mut ctx = Ctx::default();
+    ctx.carves.push(ecs_core::components::CarveRequest {
+        did: 1,
+        center_m: glam::DVec3::new(8.0, 1.0, 0.5),
+        radius_m: 2.5,
+        seed: 0,
+        impact_id: 1,
+    });
+    server_core::systems::destructible::destructible_apply_carves(&mut s, &mut ctx);
+
+    let pc_pos = s.ecs.get(pc).unwrap().tr.pos;
+    let mut reached = false;
+    for _ in 0..(30 * 10) {
+        s.step_authoritative(DT);
+        if dist_xz(s.ecs.get(z).unwrap().tr.pos, pc_pos) < 0.6 + 0.7 + 0.5 {
+            reached = true;
+            break;
+        }
+    }
+    assert!(s.nav.version > version, "carve did not update the nav grid");
+    assert!(reached, "zombie did not come through the new passage");
+}
+
+#[test]
+fn incremental_refresh_matches_a_rebuild() {
+    let mut s = ServerState::new();
+    server_core::scene_build::add_demo_ruins_destructible_at(&mut s, vec3(8.0, 0.0, 8.0), 1);
+    s.step_authoritative(DT);
+    // Unchanged obstacle sources leave the grid alone.
+    let version = s.nav.version;
+    s.step_authoritative(DT);
+    assert_eq!(s.nav.version, version);
+
+    let mut ctx = Ctx::default();
+    ctx.carves.push(ecs_core::components::CarveRequest {
+        did: 1,
+        center_m: glam::DVec3::new(8.0, 1.0, 0.5),
+        radius_m: 2.5,
+        seed: 0,
+        impact_id: 1,
+    });
+    server_core::systems::destructible::destructible_apply_carves(&mut s, &mut ctx);
+    server_core::nav::nav_refresh(&mut s);
+    assert!(s.nav.version > version);
+
+    // Rasterizing the dirty rect and a clearance band around it gives the
+    // same field as rebuilding the whole grid.
+    let fresh = server_core::nav::NavGrid::build(
+        s.nav.cfg,
+        s.static_colliders.as_ref(),
+        &s.destruct_registry,
+    );
+    for iz in -40..=104 {
+        for ix in -40..=104 {
+            let p = Vec2::new(ix as f32 * 0.25, iz as f32 * 0.25);
+            let (a, b) = (s.nav.clearance_at(p), fresh.clearance_at(p));
+            assert!((a - b).abs() < 1e-4, "clearance at {p}: {a} vs rebuilt {b}");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+#![allow(clippy::unwrap_used)]
+//! Hostile NPCs pick targets from threat tables: the nearest foe is pulled
+//! first, damage and healing earn threat, a challenger must beat the current
+//! target by 110% in melee or 130% at range, taunts switch at once, and
+//! threat decays until far-away foes are forgotten.
+
+mod common;
+
+use common::{DT, dummy};
+use glam::{Vec3, vec3};
+use server_core::ServerState;
+use server_core::actor::ActorId;
+use server_core::ecs::schedule::{Ctx, DamageEvent, apply_damage_to_ecs_for_test};
+use server_core::systems::threat::{on_heal, taunt, threat_update};
+
+const ORIGIN: Vec3 = Vec3::new(0.0, 0.6, 0.0);
+
+fn hit(s: &mut ServerState, src: ActorId, dst: ActorId, amount: i32) {
+    let mut ctx = Ctx::default();
+    ctx.dmg.push(DamageEvent {
+        src: Some(src),
+        dst,
+        amount,
+        damage_type: None,
+    });
+    apply_damage_to_ecs_for_test(s, &mut ctx);
+}
+
+fn target(s: &ServerState, npc: ActorId) -> Option<ActorId> {
+    s.ecs.get(npc)?.threat.as_ref()?.target()
+}
+
+fn threat(s: &ServerState, npc: ActorId, foe: ActorId) -> f32 {
+    s.ecs.get(npc).unwrap().threat.as_ref().unwrap().get(foe)
+}
+
+#[test]
+fn nearest_foe_is_pulled_first_and_ranged_needs_130_percent() {
+    let mut s = ServerState::new();
+    let z = dummy(&mut s, ORIGIN, 10_000);
+    let dps = s.spawn_pc(vec3(10.0, 0.6, 0.0));
+    let tank = s.spawn_pc(vec3(2.0, 0.6, 0.0));
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(tank), "closest foe is pulled first");
+
+    hit(&mut s, tank, z, 100);
+    hit(&mut s, dps, z, 125);
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(tank), "125% from range is not enough");
+
+    hit(&mut s, dps, z, 10);
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(dps), "135% from range pulls");
+}
+
+#[test]
+fn melee_challenger_needs_110_percent() {
+    let mut s = ServerState::new();
+    let z = dummy(&mut s, ORIGIN, 10_000);
+    let tank = s.spawn_pc(vec3(1.8, 0.6, 0.0));
+    let rogue = s.spawn_pc(vec3(-1.9, 0.6, 0.0));
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(tank));
+
+    hit(&mut s, tank, z, 100);
+    hit(&mut s, rogue, z, 108);
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(tank), "108% in melee is not enough");
+
+    hit(&mut s, rogue, z, 5);
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(rogue), "113% in melee pulls");
+}
+
+#[test]
+fn taunt_takes_the_target_immediately() {
+    let mut s = ServerState::new();
+    let z = dummy(&mut s, ORIGIN, 10_000);
+    let tank = s.spawn_pc(vec3(2.0, 0.6, 0.0));
+    let dps = s.spawn_pc(vec3(10.0, 0.6, 0.0));
+    hit(&mut s, dps, z, 500);
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(dps));
+
+    assert!(taunt(&mut s, tank, z));
+    assert_eq!(target(&s, z), Some(tank));
+    assert!((threat(&s, z, tank) - threat(&s, z, dps)).abs() < 1e-3);
+    // Holding after the taunt needs no extra threat: the DPS is now only even.
+    threat_update(&mut s, DT);
+    assert_eq!(target(&s, z), Some(tank));
+
+    let ally = s.spawn_undead(vec3(30.0, 0.6, 0.0), 0.9, 30);
+    assert!(!taunt(&mut s, ally, z), "allies cannot taunt");
+}
+
+#[test]
+fn healing_adds_threat_only_where_the_ally_is_fought() {
+    let mut s = ServerState::new();
+    let z = dummy(&mut s, ORIGIN, 10_000);
+    let tank = s.spawn_pc(vec3(2.0, 0.6, 0.0));
+    let healer = s.spawn_pc(vec3(40.0, 0.6, 0.0));
+    threat_update(&mut s, DT);
+    assert_eq!(threat(&s, z, healer), 0.0);
+    assert_eq!(on_heal(&mut s, healer, tank, 40), 1);
+    assert!((threat(&s, z, healer) - 40.0 * s.specs.threat.heal_mul).abs() < 1e-3);
+
+    let stranger = s.spawn_pc(vec3(80.0, 0.6, 0.0));
+    assert_eq!(on_heal(&mut s, healer, stranger, 40), 0);
+}
+
+#[test]
+fn threat_decays_and_distant_foes_are_forgotten() {
+    let mut s = ServerState::new();
+    let z = dummy(&mut s, ORIGIN, 10_000);
+    let tank = s.spawn_pc(vec3(2.0, 0.6, 0.0));
+    let dps = s.spawn_pc(vec3(10.0, 0.6, 0.0));
+    hit(&mut s, dps, z, 10);
+    threat_update(&mut s, 1.0);
+    let keep = 1.0 - s.specs.threat.decay_per_s;
+    assert!((threat(&s, z, dps) - 10.0 * keep).abs() < 1e-3);
+
+    // Out of aggro range the DPS stays on the table until its threat fades.
+    s.ecs.get_mut(dps).unwrap().tr.pos = vec3(200.0, 0.6, 0.0);
+    threat_update(&mut s, 1.0);
+    assert!(s.ecs.get(z).unwrap().threat.as_ref().unwrap().contains(dps));
+    threat_update(&mut s, 200.0);
+    let t = s.ecs.get(z).unwrap().threat.as_ref().unwrap();
+    assert!(!t.contains(dps));
+    assert_eq!(t.target(), Some(tank));
+}
+
+#[test]
+fn undead_chases_and_hits_its_threat_target() {
+    let mut s = ServerState::new();
+    let z = s.spawn_undead(Vec3::new(0.0, 0.6, 0.0), 0.9, 10_000);
+    let tank = s.spawn_pc(vec3(3.0, 0.6, 0.0));
+    let dps = s.spawn_pc(vec3(-15.0, 0.6, 0.0));
+    for _ in 0..5 {
+        s.step_authoritative(DT);
+    }
+    assert_eq!(target(&s, z), Some(tank));
+    hit(&mut s, dps, z, 50);
+    let dps_hp = s.ecs.get(dps).unwrap().hp.hp;
+    for _ in 0..(30 * 10) {
+        s.step_authoritative(DT);
+    }
+    assert_eq!(target(&s, z), Some(dps));
+    assert!(s.ecs.get(dps).unwrap().hp.hp < dps_hp, "dps never got hit");
+}
@@ -13,6 +13,7 @@ fn pc_death_sets_alive_false_and_despawns() {
         src: None,
         dst: pc,
         amount: 9999,
+        damage_type: None,
     });
     sc::ecs::schedule::apply_damage_to_ecs(&mut s, &mut ctx);
     // Cleanup should despawn dead PC
@@ -30,6 +31,7 @@ fn boss_death_despawns_like_zombie() {
         src: None,
         dst: dk,
         amount: 9999,
+        damage_type: None,
     });
     sc::ecs::schedule::apply_damage_to_ecs(&mut s, &mut ctx);
     sc::ecs::schedule::cleanup(&mut s, &mut ctx);
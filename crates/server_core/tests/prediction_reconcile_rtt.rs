@@ -0,0 +1,180 @@
+#![allow(clippy::unwrap_used)]
+//! Client prediction against the real session over a scripted 150 ms RTT
+//! loopback: the predicted PC leads the server by the round trip, matches it
+//! to quantization when the model agrees, and glides (no pops) back onto the
+//! server path when it doesn't (an unpredicted slow).
+
+use std::collections::VecDeque;
+
+use client_core::replication::ReplicationBuffer;
+use client_core::systems::prediction::{PredictParams, Predictor};
+use net_core::command::ClientCmd;
+use net_core::link::{Channel, Endpoint};
+use net_core::transport::{LocalLoopbackTransport, Transport};
+use server_core::ServerState;
+use server_core::session::{SessionConfig, SessionHost};
+
+const HZ: u64 = 30;
+const ONE_WAY_MS: u64 = 75;
+
+/// Shuttles messages between two loopback ends with a fixed one-way delay.
+struct DelayLine {
+    srv_side: LocalLoopbackTransport,
+    cli_side: LocalLoopbackTransport,
+    down: VecDeque<(u64, Vec<u8>)>,
+    up: VecDeque<(u64, Vec<u8>)>,
+}
+
+impl DelayLine {
+    fn pump(&mut self, now_ms: u64) {
+        while let Some(m) = self.srv_side.try_recv() {
+            self.down.push_back((now_ms + ONE_WAY_MS, m));
+        }
+        while let Some(m) = self.cli_side.try_recv() {
+            self.up.push_back((now_ms + ONE_WAY_MS, m));
+        }
+        while self.down.front().is_some_and(|(at, _)| *at <= now_ms) {
+            let (_, m) = self.down.pop_front().unwrap();
+            self.cli_side.try_send(m).unwrap();
+        }
+        while self.up.front().is_some_and(|(at, _)| *at <= now_ms) {
+            let (_, m) = self.up.pop_front().unwrap();
+            self.srv_side.try_send(m).unwrap();
+        }
+    }
+}
+
+struct Run {
+    /// Largest per-reconcile correction while the model matched the server.
+    max_correction_matched: f32,
+    /// Largest single-tick jump of the displayed position.
+    max_display_step: f32,
+    pending_at_end: usize,
+    final_gap: f32,
+    lead_while_moving: f32,
+}
+
+fn run(slow_from: Option<u64>) -> Run {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig {
+        tick_hz: HZ as u32,
+        interest_radius_m: 100.0,
+        ..Default::default()
+    });
+    let (srv_end, srv_side) = LocalLoopbackTransport::new(1024);
+    let (cli_side, cli_end) = LocalLoopbackTransport::new(1024);
+    let mut line = DelayLine {
+        srv_side,
+        cli_side,
+        down: VecDeque::new(),
+        up: VecDeque::new(),
+    };
+    let id = host.connect(&mut srv, Box::new(srv_end));
+    let actor = host.actor_of(id).unwrap();
+    let start = srv.ecs.get(actor).unwrap().tr.pos;
+    let params = PredictParams {
+        dt: 1.0 / HZ as f32,
+        ..Default::default()
+    };
+    let mut pr = Predictor::new(start, params);
+    let mut link = Endpoint::default();
+    let mut rep = ReplicationBuffer::default();
+
+    let mut out = Run {
+        max_correction_matched: 0.0,
+        max_display_step: 0.0,
+        pending_at_end: 0,
+        final_gap: 0.0,
+        lead_while_moving: 0.0,
+    };
+    let mut prev_display = pr.display_pos();
+    for t in 0..150u64 {
+        let now_ms = t * 1000 / HZ;
+        // Script: forward, then run-strafe, then stand still.
+        let (dx, dz, run) = match t {
+            0..40 => (0.0, 1.0, false),
+            40..80 => (1.0, 0.0, true),
+            _ => (0.0, 0.0, false),
+        };
+        let mut p = Vec::new();
+        pr.push_input(dx, dz, run).encode(&mut p);
+        link.send(Channel::Unreliable, p).unwrap();
+        link.pump(&cli_end, now_ms).unwrap();
+        line.pump(now_ms);
+
+        if slow_from.is_some_and(|s| t == s) {
+            srv.apply_status_for(actor, "slowed", None, Some(1.0));
+        }
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(params.dt);
+        host.broadcast(&mut srv);
+        line.pump(now_ms);
+
+        link.pump(&cli_end, now_ms).unwrap();
+        while let Some((_, payload)) = link.recv() {
+            if !rep.apply_message(&payload) {
+                continue;
+            }
+            let Some(me) = rep.actors.iter().find(|a| a.id == actor.0) else {
+                continue;
+            };
+            let c = pr.reconcile(me.pos, rep.last_input_seq());
+            if slow_from.is_none() {
+                out.max_correction_matched = out.max_correction_matched.max(c.length());
+            }
+        }
+        if let Some(tick) = rep.last_applied_tick() {
+            let mut p = Vec::new();
+            ClientCmd::Ack { tick }.encode(&mut p);
+            link.send(Channel::Unreliable, p).unwrap();
+        }
+        pr.advance(params.dt);
+        let d = pr.display_pos();
+        out.max_display_step = out.max_display_step.max((d - prev_display).length());
+        prev_display = d;
+        if t == 35 {
+            let server_z = srv.ecs.get(actor).unwrap().tr.pos.z;
+            out.lead_while_moving = pr.predicted_pos().z - server_z;
+        }
+    }
+    out.pending_at_end = pr.pending();
+    out.final_gap = (pr.display_pos() - srv.ecs.get(actor).unwrap().tr.pos).length();
+    out
+}
+
+#[test]
+fn prediction_tracks_server_over_150ms_rtt() {
+    let r = run(None);
+    // Quantized positions (1/64 m) are the only source of disagreement.
+    assert!(
+        r.max_correction_matched < 0.03,
+        "mispredicted with a matching model: {}",
+        r.max_correction_matched
+    );
+    // In flight: ~150 ms of inputs plus a tick of server queueing.
+    assert!(
+        (3..=8).contains(&r.pending_at_end),
+        "pending {}",
+        r.pending_at_end
+    );
+    // The client sees its own motion a round trip ahead of the server.
+    let step = 5.0 / HZ as f32;
+    assert!(
+        r.lead_while_moving > 2.0 * step,
+        "lead {}",
+        r.lead_while_moving
+    );
+    assert!(r.final_gap < 0.03, "final gap {}", r.final_gap);
+}
+
+#[test]
+fn unpredicted_slow_is_corrected_smoothly() {
+    let r = run(Some(20));
+    // A run step is 5 * 1.6 / 30 ≈ 0.27 m; corrections must not add a visible pop.
+    assert!(
+        r.max_display_step < 0.4,
+        "display popped {}",
+        r.max_display_step
+    );
+    assert!(r.final_gap < 0.03, "did not settle: {}", r.final_gap);
+}
@@ -6,7 +6,7 @@ fn firebolt_from_wizard_damages_undead() {
     // Spawn wizard caster
     let wid = s.ecs.spawn(
         server_core::ActorKind::Wizard,
-        server_core::Faction::Wizards,
+        server_core::Faction::WIZARDS,
         server_core::Transform {
             pos: vec3(0.0, 0.6, 0.0),
             yaw: 0.0,
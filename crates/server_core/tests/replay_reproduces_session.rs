@@ -0,0 +1,269 @@
+#![allow(clippy::unwrap_used)]
+//! A recorded session replays bit for bit: the runner re-drives the schedule
+//! from the log alone and every tick's `ActorSnapshot` hash matches, ruins
+//! carved before recording included. Edited logs and changed spec data are
+//! caught and name what diverged.
+
+mod common;
+
+use common::Client;
+use ecs_core::components::CarveRequest;
+use glam::DVec3;
+use net_core::command::{CastTarget, ClientCmd};
+use net_core::handshake::Hello;
+use net_core::link::Channel;
+use net_core::snapshot::{ActorSnapshotDelta, SnapshotDecode, TAG_ACTOR_SNAPSHOT_DELTA};
+use server_core::ServerState;
+use server_core::ecs::schedule::Ctx;
+use server_core::replay::{self, ReplayEvent, ReplayLog};
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::destructible::destructible_apply_carves;
+
+const HZ: u32 = 30;
+
+/// Drain replication; acks keep the baseline moving as on a real client.
+fn drain(c: &mut Client) {
+    let newest = c
+        .recv_all(1000 / u64::from(HZ))
+        .iter()
+        .rev()
+        .filter(|m| m.first() == Some(&TAG_ACTOR_SNAPSHOT_DELTA))
+        .find_map(|m| ActorSnapshotDelta::decode(&mut m.as_slice()).ok())
+        .map(|d| d.tick);
+    if let Some(tick) = newest {
+        c.cmd(Channel::Unreliable, &ClientCmd::Ack { tick });
+    }
+}
+
+/// Boot the demo zone, record a two-client session with moves, aims, casts
+/// and a disconnect, and return the log.
+fn record_session() -> ReplayLog {
+    record_session_seeded(server_core::DEFAULT_WORLD_SEED)
+}
+
+/// `record_session` with the random streams derived from `world_seed`.
+fn record_session_seeded(world_seed: u64) -> ReplayLog {
+    let mut srv = ServerState::new();
+    srv.seed_rngs(world_seed);
+    assert!(server_core::zones::boot_with_zone(&mut srv, "wizard_woods"));
+    record_session_from(srv)
+}
+
+/// `record_session` on an already booted world.
+fn record_session_from(mut srv: ServerState) -> ReplayLog {
+    let mut host = SessionHost::new(SessionConfig {
+        tick_hz: HZ,
+        interest_radius_m: 60.0,
+        zone_slug: Some("wizard_woods".into()),
+        ..Default::default()
+    });
+    host.start_recording(&srv);
+
+    let (a_id, mut a) = Client::connect(&mut host, &mut srv);
+    let (b_id, mut b) = Client::accept(&mut host);
+    b.send(Channel::Reliable, &Hello::new("wizard_woods", "Bea"));
+
+    for t in 0..120u32 {
+        let seq = t + 1;
+        a.cmd(
+            Channel::Unreliable,
+            &ClientCmd::Move {
+                dx: (t as f32 * 0.1).sin(),
+                dz: 1.0,
+                run: u8::from(t % 40 < 20),
+                seq,
+            },
+        );
+        if t % 10 == 0 {
+            a.cmd(
+                Channel::Reliable,
+                &ClientCmd::Cast {
+                    ability_id: "fire_bolt".into(),
+                    target: CastTarget::Direction([1.0, 0.0, 0.2]),
+                },
+            );
+        }
+        if t == 30 {
+            a.cmd(
+                Channel::Reliable,
+                &ClientCmd::Cast {
+                    ability_id: "wiz.fireball.srd521".into(),
+                    target: CastTarget::Ground([10.0, 0.0, 12.0]),
+                },
+            );
+        }
+        if host.actor_of(b_id).is_some() && t < 80 {
+            b.cmd(
+                Channel::Unreliable,
+                &ClientCmd::Aim {
+                    yaw: t as f32 * 0.05,
+                },
+            );
+            b.cmd(
+                Channel::Unreliable,
+                &ClientCmd::Move {
+                    dx: -1.0,
+                    dz: 0.0,
+                    run: 0,
+                    seq,
+                },
+            );
+            if t % 15 == 0 {
+                b.cmd(
+                    Channel::Reliable,
+                    &ClientCmd::Cast {
+                        ability_id: "magic_missile".into(),
+                        target: CastTarget::Direction([0.0, 0.0, -1.0]),
+                    },
+                );
+            }
+        }
+        if t == 80 {
+            host.disconnect(&mut srv, b_id);
+        }
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(1.0 / HZ as f32);
+        host.broadcast(&mut srv);
+        drain(&mut a);
+        drain(&mut b);
+    }
+    assert!(host.actor_of(a_id).is_some());
+    host.stop_recording().unwrap()
+}
+
+#[test]
+fn recorded_session_replays_bit_for_bit() {
+    let log = record_session();
+    assert_eq!(log.frames.len(), 120);
+    let joins = log
+        .events
+        .iter()
+        .filter(|(_, e)| matches!(e, ReplayEvent::Join { .. }))
+        .count();
+    assert_eq!(joins, 2);
+    assert!(
+        log.events
+            .iter()
+            .any(|(_, e)| matches!(e, ReplayEvent::Leave { .. }))
+    );
+    assert_eq!(replay::verify(&log).unwrap(), 120);
+
+    // The file format round-trips and the reloaded log replays too.
+    let back = ReplayLog::from_bytes(&log.to_bytes()).unwrap();
+    assert_eq!(back, log);
+    assert_eq!(replay::verify(&back).unwrap(), 120);
+}
+
+#[test]
+fn edited_commands_diverge_at_their_tick() {
+    let mut log = record_session();
+    let (tick, ev) = log
+        .events
+        .iter_mut()
+        .find(|(t, e)| {
+            *t >= 40
+                && matches!(
+                    e,
+                    ReplayEvent::Cmd {
+                        cmd: ClientCmd::Move { .. },
+                        ..
+                    }
+                )
+        })
+        .unwrap();
+    let ReplayEvent::Cmd {
+        cmd: ClientCmd::Move { dx, .. },
+        ..
+    } = ev
+    else {
+        unreachable!()
+    };
+    *dx = -*dx - 1.0;
+    let tick = *tick;
+    let err = replay::verify(&log).unwrap_err().to_string();
+    assert!(err.contains("snapshot hash"), "{err}");
+    let at: u64 = err
+        .trim_start_matches("tick ")
+        .split(':')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(at >= tick, "diverged at {at}, before the edit at {tick}");
+}
+
+#[test]
+fn spec_changes_and_corrupt_files_are_rejected() {
+    let log = record_session();
+    let mut changed = log.clone();
+    changed.header.specs.abilities ^= 1;
+    let err = replay::verify(&changed).unwrap_err().to_string();
+    assert!(err.contains("abilities"), "{err}");
+    type Field = fn(&mut replay::SpecHashes) -> &mut u64;
+    let tables: [(&str, Field); 5] = [
+        ("boss_scripts", |h| &mut h.boss_scripts),
+        ("statuses", |h| &mut h.statuses),
+        ("factions", |h| &mut h.factions),
+        ("items", |h| &mut h.items),
+        ("loot", |h| &mut h.loot),
+    ];
+    for (name, field) in tables {
+        let mut changed = log.clone();
+        *field(&mut changed.header.specs) ^= 1;
+        let err = replay::verify(&changed).unwrap_err().to_string();
+        assert!(err.contains(name), "{err}");
+    }
+    // Map-backed tables hash the same whatever their iteration order.
+    assert_eq!(
+        replay::SpecHashes::of(&ServerState::new()),
+        replay::SpecHashes::of(&ServerState::new())
+    );
+
+    let bytes = log.to_bytes();
+    assert!(ReplayLog::from_bytes(&bytes[..bytes.len() - 3]).is_err());
+    assert!(ReplayLog::from_bytes(b"NOTAREPLAY").is_err());
+}
+
+#[test]
+fn rng_seeds_are_per_stream_and_recorded() {
+    let log = record_session_seeded(7);
+    let h = &log.header;
+    assert_ne!(h.combat_seed, h.loot_seed);
+    assert_ne!(h.combat_seed, ServerState::new().combat_rng.seed());
+    let back = ReplayLog::from_bytes(&log.to_bytes()).unwrap();
+    assert_eq!(back.header, log.header);
+    assert_eq!(replay::verify(&log).unwrap(), 120);
+}
+
+#[test]
+fn ruins_carved_before_recording_replay_and_must_still_fit() {
+    let mut srv = ServerState::new();
+    assert!(server_core::zones::boot_with_zone(&mut srv, "wizard_woods"));
+    // Carve through the middle of the first ruin's west wall.
+    let (&did, p) = srv.destruct_registry.proxies.iter().next().unwrap();
+    let v = DVec3::new(0.5, 8.0, 16.0);
+    let center = p.grid.origin_m() + v * p.grid.voxel_m().0;
+    let center = p.world_from_object.transform_point3(center.as_vec3());
+    let mut ctx = Ctx::default();
+    ctx.carves.push(CarveRequest {
+        did: did.0,
+        center_m: center.as_dvec3(),
+        radius_m: 1.5,
+        seed: 0,
+        impact_id: 0,
+    });
+    destructible_apply_carves(&mut srv, &mut ctx);
+
+    let log = record_session_from(srv);
+    let ruins = log.header.ruins.as_ref().expect("carved ruins recorded");
+    assert!(!ruins.proxies[0].scars.is_empty());
+    assert_eq!(replay::verify(&log).unwrap(), 120);
+    let back = ReplayLog::from_bytes(&log.to_bytes()).unwrap();
+    assert_eq!(back, log);
+
+    // Ruins that no longer carve the same way refuse to replay.
+    let mut changed = log.clone();
+    changed.header.ruins.as_mut().unwrap().proxies[0].chunks[0].occ_hash ^= 1;
+    let err = replay::verify(&changed).unwrap_err().to_string();
+    assert!(err.contains("ruins"), "{err}");
+}
@@ -0,0 +1,110 @@
+#![allow(clippy::unwrap_used)]
+//! A tight per-client actor budget defers low-priority actors instead of
+//! dropping them: every NPC in interest still reaches the client, near ones are
+//! refreshed more often than far ones, and each tick stays within budget.
+
+use std::collections::HashMap;
+
+use glam::Vec3;
+use net_core::baseline::{ActorSet, BaselineReceiver, SPAWN_REC_BYTES, update_rec_bytes};
+use net_core::command::ClientCmd;
+use net_core::link::{Channel, Endpoint};
+use net_core::snapshot::{ActorSnapshotDelta, SnapshotDecode, TAG_ACTOR_SNAPSHOT_DELTA};
+use net_core::transport::LocalLoopbackTransport;
+use server_core::ServerState;
+use server_core::session::{SessionConfig, SessionHost};
+
+const TICKS: u64 = 120;
+const BUDGET_PER_TICK: usize = 240;
+
+#[test]
+fn budget_defers_far_actors_without_dropping_them() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig {
+        interest_radius_m: 60.0,
+        actor_budget_bytes_per_sec: (BUDGET_PER_TICK * 30) as u32,
+        ..Default::default()
+    });
+    let (srv_end, xport) = LocalLoopbackTransport::new(1024);
+    let id = host.connect(&mut srv, Box::new(srv_end));
+    let me = host.actor_of(id).unwrap().0;
+    // Two rings of undead: near (6 m) and far (45 m).
+    let mut near = Vec::new();
+    let mut far = Vec::new();
+    for i in 0..24 {
+        let a = i as f32 / 24.0 * std::f32::consts::TAU;
+        let dir = Vec3::new(a.cos(), 0.0, a.sin());
+        near.push(
+            srv.spawn_undead(Vec3::new(0.0, 0.6, 0.0) + dir * 6.0, 0.9, 30)
+                .0,
+        );
+        far.push(
+            srv.spawn_undead(Vec3::new(0.0, 0.6, 0.0) + dir * 45.0, 0.9, 30)
+                .0,
+        );
+    }
+
+    let mut link = Endpoint::default();
+    let mut rx = BaselineReceiver::new();
+    let mut seen = ActorSet::new();
+    let mut refreshes: HashMap<u32, u32> = HashMap::new();
+    let dt = 1.0 / 30.0;
+    for t in 0..TICKS {
+        let now_ms = t * 33;
+        host.pump_inputs(&mut srv);
+        srv.step_authoritative(dt);
+        // Every NPC changes every tick, so demand always exceeds the budget.
+        for id in near.iter().chain(&far) {
+            if let Some(c) = srv.ecs.get_mut(server_core::ActorId(*id)) {
+                c.hp.hp = 20 + (t % 10) as i32;
+            }
+        }
+        host.broadcast(&mut srv);
+        link.pump(&xport, now_ms).unwrap();
+        while let Some((_, payload)) = link.recv() {
+            if payload.first() != Some(&TAG_ACTOR_SNAPSHOT_DELTA) {
+                continue;
+            }
+            let mut slice: &[u8] = &payload;
+            let d = ActorSnapshotDelta::decode(&mut slice).unwrap();
+            let own: usize = d
+                .updates
+                .iter()
+                .filter(|u| u.id == me)
+                .map(update_rec_bytes)
+                .sum::<usize>()
+                + d.spawns.iter().filter(|a| a.id == me).count() * SPAWN_REC_BYTES;
+            let bytes = d.spawns.len() * SPAWN_REC_BYTES
+                + d.updates.iter().map(update_rec_bytes).sum::<usize>();
+            assert!(
+                bytes - own <= BUDGET_PER_TICK,
+                "tick {t}: {bytes} actor bytes over budget"
+            );
+            for u in &d.updates {
+                *refreshes.entry(u.id).or_default() += 1;
+            }
+            if let Some(set) = rx.apply(&d) {
+                seen = set.clone();
+            }
+        }
+        if let Some(tick) = rx.last_applied() {
+            let mut p = Vec::new();
+            ClientCmd::Ack { tick }.encode(&mut p);
+            link.send(Channel::Unreliable, p).unwrap();
+            link.pump(&xport, now_ms).unwrap();
+        }
+    }
+
+    for id in near.iter().chain(&far) {
+        assert!(seen.contains_key(id), "actor {id} never replicated");
+    }
+    let avg = |ids: &[u32]| {
+        ids.iter()
+            .map(|id| refreshes.get(id).copied().unwrap_or(0))
+            .sum::<u32>() as f32
+            / ids.len() as f32
+    };
+    let (n, f) = (avg(&near), avg(&far));
+    assert!(f > 0.0, "far actors were never refreshed");
+    assert!(n > f, "near actors should refresh more often ({n} vs {f})");
+}
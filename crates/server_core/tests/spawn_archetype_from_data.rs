@@ -0,0 +1,164 @@
+#![allow(clippy::unwrap_used)]
+//! `spawn_archetype` builds actors purely from `ArchetypeSpecDb` entries:
+//! movement, aggro, melee, spellbook, mana and faction come from data,
+//! overrides tweak single spawns, and the snapshot carries the archetype id.
+
+use data_runtime::specs::archetypes::{ArchetypeSpec, ArchetypeSpecDb};
+use glam::vec3;
+use server_core::archetype::{ArchetypeOverrides, check_archetypes};
+use server_core::{ActorKind, Faction, ServerState, SpellId};
+
+#[test]
+fn builtin_archetypes_fill_components_from_data() {
+    let mut s = ServerState::new();
+    let z = s
+        .spawn_archetype("Undead", vec3(20.0, 0.6, 0.0), &Default::default())
+        .unwrap();
+    let spec = s.specs_arche.entries["Undead"].clone();
+    let c = s.ecs.get(z).unwrap();
+    assert_eq!((c.kind, c.faction), (ActorKind::Zombie, Faction::UNDEAD));
+    assert_eq!(c.hp.max, spec.hp);
+    assert_eq!(c.move_speed.unwrap().mps, spec.move_speed_mps);
+    assert_eq!(c.aggro.unwrap().m, spec.aggro_radius_m);
+    assert_eq!(c.melee.unwrap().damage, spec.melee_damage);
+    assert!(c.spellbook.is_none() && c.pool.is_none());
+
+    let w = s
+        .spawn_archetype("WizardNPC", vec3(-20.0, 0.6, 0.0), &Default::default())
+        .unwrap();
+    let c = s.ecs.get(w).unwrap();
+    assert_eq!(c.faction, Faction::WIZARDS);
+    assert!(c.move_speed.is_none() && c.melee.is_none() && c.aggro.is_none());
+    assert!(
+        c.spellbook
+            .as_ref()
+            .unwrap()
+            .known
+            .contains(&SpellId::Fireball)
+    );
+    assert_eq!(c.pool.unwrap().max, s.specs_arche.entries["WizardNPC"].mana);
+
+    let dk = s
+        .spawn_archetype("DeathKnight", vec3(0.0, 0.6, 40.0), &Default::default())
+        .unwrap();
+    let c = s.ecs.get(dk).unwrap();
+    assert_eq!(c.kind, ActorKind::Boss);
+    assert_eq!(c.name.as_deref(), Some("Death Knight"));
+    assert!(c.melee.is_some() && c.spellbook.is_some());
+}
+
+#[test]
+fn overrides_apply_per_spawn() {
+    let mut s = ServerState::new();
+    let ov = ArchetypeOverrides {
+        hp: Some(7),
+        yaw: Some(1.0),
+        faction: Some(Faction::NEUTRAL),
+        aggro_m: Some(3.0),
+        ..Default::default()
+    };
+    let z = s
+        .spawn_archetype("Undead", vec3(20.0, 0.6, 0.0), &ov)
+        .unwrap();
+    let c = s.ecs.get(z).unwrap();
+    assert_eq!((c.hp.hp, c.hp.max), (7, 7));
+    assert_eq!(c.tr.yaw, 1.0);
+    assert_eq!(c.faction, Faction::NEUTRAL);
+    assert_eq!(c.aggro.unwrap().m, 3.0);
+}
+
+#[test]
+fn new_archetypes_need_no_code_and_replicate_their_id() {
+    let mut s = ServerState::new();
+    assert!(
+        s.spawn_archetype("Ghoul", vec3(20.0, 0.6, 0.0), &Default::default())
+            .is_none()
+    );
+    s.specs_arche.entries.insert(
+        "Ghoul".into(),
+        ArchetypeSpec {
+            radius_m: 0.8,
+            move_speed_mps: 3.0,
+            aggro_radius_m: 15.0,
+            attack_radius_m: 0.4,
+            melee_damage: 7,
+            melee_cooldown_s: 0.5,
+            net_id: 42,
+            kind: "zombie".into(),
+            faction: "undead".into(),
+            hp: 55,
+            ..Default::default()
+        },
+    );
+    let g = s
+        .spawn_archetype("Ghoul", vec3(20.0, 0.6, 0.0), &Default::default())
+        .unwrap();
+    let c = s.ecs.get(g).unwrap();
+    assert_eq!((c.hp.max, c.tr.radius), (55, 0.8));
+    assert_eq!(c.melee.unwrap().damage, 7);
+    let snap = s.tick_snapshot_actors(1);
+    let rep = snap.actors.iter().find(|a| a.id == g.0).unwrap();
+    assert_eq!(rep.archetype_id, 42);
+    assert_eq!(rep.kind, 1);
+}
+
+#[test]
+fn unique_archetypes_spawn_once() {
+    let mut s = ServerState::new();
+    let a = s
+        .spawn_archetype("Nivita", vec3(0.0, 0.6, 30.0), &Default::default())
+        .unwrap();
+    let b = s
+        .spawn_archetype("Nivita", vec3(0.0, 0.6, -30.0), &Default::default())
+        .unwrap();
+    assert_eq!(a, b);
+    assert_eq!(s.nivita_actor(), Some(a));
+    let rep = s.tick_snapshot_actors(1);
+    let nid = s.specs_arche.entries["Nivita"].net_id;
+    assert_eq!(
+        rep.actors.iter().filter(|r| r.archetype_id == nid).count(),
+        1
+    );
+    assert_eq!(
+        s.nivita_status().unwrap().max,
+        s.specs_arche.entries["Nivita"].hp
+    );
+
+    // Once she dies, the next spawn is a new Nivita.
+    s.ecs.get_mut(a).unwrap().hp.hp = 0;
+    assert_eq!(s.nivita_actor(), None);
+    let c = s
+        .spawn_archetype("Nivita", vec3(0.0, 0.6, 30.0), &Default::default())
+        .unwrap();
+    assert_ne!(c, a);
+    assert_eq!(s.nivita_actor(), Some(c));
+}
+
+#[test]
+fn unknown_kinds_and_factions_are_data_errors() {
+    let mut s = ServerState::new();
+    check_archetypes(&s.specs_arche, &s.factions.registry).unwrap();
+    check_archetypes(&ArchetypeSpecDb::builtin(), &s.factions.registry).unwrap();
+    for (kind, faction) in [("ghoul", "undead"), ("zombie", "ghouls")] {
+        let mut db = s.specs_arche.clone();
+        let spec = ArchetypeSpec {
+            kind: kind.into(),
+            faction: faction.into(),
+            hp: 10,
+            ..Default::default()
+        };
+        db.entries.insert("Ghoul".into(), spec.clone());
+        let err = check_archetypes(&db, &s.factions.registry).unwrap_err();
+        assert!(err.to_string().contains("Ghoul"), "{err}");
+
+        // Unchecked entries don't spawn as a guessed kind or faction.
+        s.specs_arche.entries.insert("Ghoul".into(), spec);
+        assert!(
+            s.spawn_archetype("Ghoul", vec3(20.0, 0.6, 0.0), &Default::default())
+                .is_none()
+        );
+    }
+    let mut db = s.specs_arche.clone();
+    db.entries.remove("PC");
+    assert!(check_archetypes(&db, &s.factions.registry).is_err());
+}
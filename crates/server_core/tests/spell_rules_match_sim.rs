@@ -0,0 +1,243 @@
+#![allow(clippy::unwrap_used)]
+//! Server spell hits follow the shared SRD rules: Fire Bolt rolls to hit
+//! against Armor Class, Fireball rolls its damage once per blast and each
+//! target makes a DEX save for half, and fire-resistant targets take half. Golden runs compare every outcome with
+//! `sim_core`'s systems seeded alike.
+
+mod common;
+
+use common::{DT, dummy};
+use ecs_core::components::{DamageType, Resistances};
+use glam::{Vec2, Vec3, vec3};
+use server_core::actor::ActorId;
+use server_core::ecs::schedule::{
+    Ctx, DamageEvent, ExplodeEvent, aoe_apply_explosions_for_test, apply_damage_to_ecs_for_test,
+};
+use server_core::{CombatRng, ProjKind, ServerState};
+use sim_core::combat::damage::DamageType as SimDamageType;
+use sim_core::sim::events::SimEvent;
+use sim_core::sim::state::{ActorSim, SimState};
+use sim_core::sim::systems;
+
+const SEED: u64 = 0;
+const TARGET_AC: i32 = 14;
+const TARGET_HP: i32 = 10_000;
+
+/// A server whose combat dice start from `SEED`, like the simulator's.
+fn server() -> ServerState {
+    let mut s = ServerState::new();
+    s.combat_rng = CombatRng::seeded(SEED);
+    s
+}
+
+/// A stationary Undead at `pos` with `TARGET_AC` and a +1 DEX save (the
+/// simulator's default for non-bosses).
+fn target(s: &mut ServerState, pos: Vec3, fire_resistant: bool) -> ActorId {
+    let z = dummy(s, pos, TARGET_HP);
+    let c = s.ecs.get_mut(z).unwrap();
+    c.armor.as_mut().unwrap().ac = TARGET_AC;
+    c.saves.as_mut().unwrap().dex_mod = 1;
+    if fire_resistant {
+        c.resist = Some(Resistances {
+            damage: vec![DamageType::Fire],
+        });
+    }
+    z
+}
+
+fn hp(s: &ServerState, id: ActorId) -> i32 {
+    s.ecs.get(id).unwrap().hp.hp
+}
+
+fn sim_actor(id: &str, team: &str) -> ActorSim {
+    ActorSim {
+        id: id.into(),
+        role: "dps".into(),
+        class: None,
+        team: Some(team.into()),
+        hp: TARGET_HP,
+        ac_base: TARGET_AC,
+        ac_temp_bonus: 0,
+        ability_ids: vec![],
+        action: Default::default(),
+        gcd: Default::default(),
+        target: None,
+        char_level: 1,
+        spell_attack_bonus: 5,
+        spell_save_dc: 13,
+        statuses: vec![],
+        blessed_ms: 0,
+        reaction_ready: true,
+        next_ability_idx: 0,
+        temp_hp: 0,
+        concentration: None,
+        ability_cooldowns: Default::default(),
+        threat: Default::default(),
+        resist: vec![],
+    }
+}
+
+/// The simulator's caster (a first-level wizard, like the server PC) aimed
+/// at a target matching `dummy`.
+fn sim(spell: &str, fire_resistant: bool) -> SimState {
+    let mut st = SimState::new(50, SEED);
+    let spec = st.spec_db.get_spell(spell).unwrap().clone();
+    st.spells.insert(spell.into(), spec);
+    let mut caster = sim_actor("wiz", "players");
+    caster.target = Some(1);
+    let mut target = sim_actor("dummy", "undead");
+    if fire_resistant {
+        target.resist.push(SimDamageType::Fire);
+    }
+    st.actors.push(caster);
+    st.actors.push(target);
+    st
+}
+
+/// One cast through the simulator's save, attack and damage systems.
+/// Returns the damage dealt and whether the target saved.
+fn sim_cast(st: &mut SimState, spell: &str) -> (i32, Option<bool>) {
+    st.events.clear();
+    let before = st.actors[1].hp;
+    st.cast_completed.push((0, spell.into()));
+    systems::saving_throw::run(st);
+    systems::attack_roll::run(st);
+    systems::damage::run(st);
+    let saved = st.events.iter().find_map(|e| match e {
+        SimEvent::SaveResolved { success, .. } => Some(*success),
+        _ => None,
+    });
+    (before - st.actors[1].hp, saved)
+}
+
+/// Fire Bolt from the server PC at a dummy; returns the damage dealt.
+fn server_fire_bolt(s: &mut ServerState, pc: ActorId, z: ActorId) -> i32 {
+    let before = hp(s, z);
+    let from = s.ecs.get(pc).unwrap().tr.pos;
+    s.spawn_projectile_from(pc, from, Vec3::Z, ProjKind::Firebolt);
+    s.step_authoritative(DT);
+    while s.ecs.iter().any(|c| c.projectile.is_some()) {
+        s.step_authoritative(DT);
+    }
+    before - hp(s, z)
+}
+
+/// A Fireball blast from `pc` centered on `z`; returns the damage dealt.
+fn server_fireball(s: &mut ServerState, pc: ActorId, z: ActorId) -> i32 {
+    let before = hp(s, z);
+    let p = s.ecs.get(z).unwrap().tr.pos;
+    let mut ctx = Ctx::default();
+    ctx.boom.push(ExplodeEvent {
+        center_xz: Vec2::new(p.x, p.z),
+        r2: 1.0,
+        src: Some(pc),
+    });
+    aoe_apply_explosions_for_test(s, &mut ctx);
+    apply_damage_to_ecs_for_test(s, &mut ctx);
+    before - hp(s, z)
+}
+
+#[test]
+fn fire_bolt_rolls_to_hit_like_the_sim() {
+    let mut s = server();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = target(&mut s, vec3(0.0, 0.6, 4.0), false);
+    let mut st = sim("fire_bolt", false);
+    let mut outcomes = Vec::new();
+    for _ in 0..30 {
+        let got = server_fire_bolt(&mut s, pc, z);
+        let (want, _) = sim_cast(&mut st, "fire_bolt");
+        assert_eq!(got, want);
+        outcomes.push(got);
+    }
+    assert!(outcomes.contains(&0), "some bolts miss AC {TARGET_AC}");
+    assert!(outcomes.iter().any(|d| *d > 0), "some bolts hit");
+}
+
+#[test]
+fn fireball_dex_save_halves_like_the_sim() {
+    let mut s = server();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = target(&mut s, vec3(0.0, 0.6, 10.0), false);
+    let mut st = sim("fireball", false);
+    let mut saves = Vec::new();
+    for _ in 0..30 {
+        let got = server_fireball(&mut s, pc, z);
+        let (want, saved) = sim_cast(&mut st, "fireball");
+        assert_eq!(got, want);
+        assert!(got > 0, "8d6 halved is never zero");
+        saves.push(saved.unwrap());
+    }
+    assert!(saves.contains(&true) && saves.contains(&false));
+}
+
+#[test]
+fn fireball_rolls_damage_once_per_blast() {
+    let mut s = server();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let mut dex = |pos, dex_mod| {
+        let z = target(&mut s, pos, false);
+        s.ecs.get_mut(z).unwrap().saves.as_mut().unwrap().dex_mod = dex_mod;
+        z
+    };
+    // Two targets that always fail the save and one that always makes it.
+    let fail_a = dex(vec3(-2.0, 0.6, 10.0), -30);
+    let fail_b = dex(vec3(2.0, 0.6, 10.0), -30);
+    let saver = dex(vec3(0.0, 0.6, 12.0), 30);
+    let mut rolls = Vec::new();
+    for _ in 0..20 {
+        let before = [fail_a, fail_b, saver].map(|z| hp(&s, z));
+        let mut ctx = Ctx::default();
+        ctx.boom.push(ExplodeEvent {
+            center_xz: Vec2::new(0.0, 10.0),
+            r2: 9.0,
+            src: Some(pc),
+        });
+        aoe_apply_explosions_for_test(&mut s, &mut ctx);
+        apply_damage_to_ecs_for_test(&mut s, &mut ctx);
+        let [a, b, saved] = [fail_a, fail_b, saver].map(|z| hp(&s, z));
+        let taken = (before[0] - a, before[1] - b, before[2] - saved);
+        assert_eq!(taken.0, taken.1, "one roll for the whole blast");
+        assert_eq!(taken.2, taken.0 / 2, "a successful save halves that roll");
+        rolls.push(taken.0);
+    }
+    assert!(
+        rolls.iter().any(|r| *r != rolls[0]),
+        "each blast rolls anew"
+    );
+}
+
+#[test]
+fn fire_resistance_halves_spell_damage_like_the_sim() {
+    let mut s = server();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = target(&mut s, vec3(0.0, 0.6, 10.0), true);
+    let mut st = sim("fireball", true);
+    for _ in 0..20 {
+        assert_eq!(
+            server_fireball(&mut s, pc, z),
+            sim_cast(&mut st, "fireball").0
+        );
+    }
+}
+
+#[test]
+fn resistance_only_halves_matching_damage_types() {
+    let mut s = server();
+    let z = target(&mut s, vec3(0.0, 0.6, 10.0), true);
+    let mut hit = |damage_type| {
+        let before = hp(&s, z);
+        let mut ctx = Ctx::default();
+        ctx.dmg.push(DamageEvent {
+            src: None,
+            dst: z,
+            amount: 11,
+            damage_type,
+        });
+        apply_damage_to_ecs_for_test(&mut s, &mut ctx);
+        before - hp(&s, z)
+    };
+    assert_eq!(hit(Some(DamageType::Fire)), 5, "halved, rounded down");
+    assert_eq!(hit(Some(DamageType::Cold)), 11);
+    assert_eq!(hit(None), 11);
+}
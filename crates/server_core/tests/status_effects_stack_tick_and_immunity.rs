@@ -0,0 +1,199 @@
+#![allow(clippy::unwrap_used)]
+//! Status effects come from `data/config/statuses.toml`: reapplication follows
+//! each status's stacking rule, ticks scale with stacks, modifiers fold into
+//! the actor, condition immunities block statuses, and active statuses
+//! replicate to clients as `StatusMsg`.
+
+mod common;
+
+use client_core::replication::ReplicationBuffer;
+use common::{Client, Stall};
+use glam::vec3;
+use net_core::link::Endpoint;
+use net_core::transport::LocalLoopbackTransport;
+use server_core::ServerState;
+use server_core::actor::ActorId;
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::status::StatusOutcome;
+
+const DT: f32 = 0.1;
+
+fn net_id(s: &ServerState, name: &str) -> u16 {
+    s.statuses.get(name).unwrap().net_id
+}
+
+fn stacks(s: &ServerState, id: ActorId, name: &str) -> u8 {
+    let c = s.ecs.get(id).unwrap();
+    c.status(net_id(s, name)).map_or(0, |st| st.stacks)
+}
+
+#[test]
+fn stacking_rules_and_ticks_scale_with_stacks() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let max = s.statuses.get("poisoned").unwrap().max_stacks;
+    assert_eq!(s.apply_status(pc, "poisoned", None), StatusOutcome::Applied);
+    for _ in 1..max {
+        assert_eq!(s.apply_status(pc, "poisoned", None), StatusOutcome::Stacked);
+    }
+    assert_eq!(
+        s.apply_status(pc, "poisoned", None),
+        StatusOutcome::Refreshed
+    );
+    assert_eq!(stacks(&s, pc, "poisoned"), max);
+
+    // Burning refreshes instead of stacking.
+    s.apply_status(pc, "burning", None);
+    assert_eq!(
+        s.apply_status(pc, "burning", None),
+        StatusOutcome::Refreshed
+    );
+    assert_eq!(stacks(&s, pc, "burning"), 1);
+    assert_eq!(
+        s.apply_status(pc, "no_such_status", None),
+        StatusOutcome::Unknown
+    );
+
+    let poison = s.statuses.get("poisoned").unwrap().fx;
+    let burn = s.statuses.get("burning").unwrap().fx;
+    let hp0 = s.ecs.get(pc).unwrap().hp.hp;
+    for _ in 0..10 {
+        s.step_authoritative(DT);
+    }
+    let per_s_burn = (1.0 / burn.tick_s).round() as i32 * burn.tick_damage;
+    let expected = poison.tick_damage * i32::from(max) + per_s_burn;
+    assert_eq!(hp0 - s.ecs.get(pc).unwrap().hp.hp, expected);
+}
+
+#[test]
+fn modifiers_fold_into_the_actor_and_expire() {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    s.apply_status(pc, "shielded", None);
+    s.apply_status(pc, "slowed", None);
+    s.apply_status(pc, "poisoned", None);
+    s.apply_status(pc, "poisoned", None);
+    let shield = s.statuses.get("shielded").unwrap().fx;
+    let slow = s.statuses.get("slowed").unwrap().fx;
+    let poison = s.statuses.get("poisoned").unwrap().fx;
+    let c = s.ecs.get(pc).unwrap();
+    assert_eq!(c.ac_bonus(), shield.ac_bonus);
+    assert!((c.speed_mul() - slow.speed_mul).abs() < 1e-5);
+    assert!((c.damage_mul() - poison.damage_mul.powi(2)).abs() < 1e-5);
+    assert!(!c.incapacitated());
+
+    s.apply_status(pc, "stunned", None);
+    let c = s.ecs.get(pc).unwrap();
+    assert!(c.incapacitated() && c.attacked_with_advantage());
+
+    for _ in 0..70 {
+        s.step_authoritative(DT);
+    }
+    let c = s.ecs.get(pc).unwrap();
+    assert!(c.statuses.is_empty(), "{:?}", c.statuses);
+    assert_eq!(c.ac_bonus(), 0);
+    assert!((c.speed_mul() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn archetype_immunities_block_statuses() {
+    let mut s = ServerState::new();
+    s.sync_wizards(&[vec3(0.0, 0.6, 0.0)]);
+    let z = s.spawn_undead(vec3(20.0, 0.6, 0.0), 0.9, 30);
+    assert_eq!(s.apply_status(z, "poisoned", None), StatusOutcome::Immune);
+    assert_eq!(stacks(&s, z, "poisoned"), 0);
+    assert_eq!(s.apply_status(z, "burning", None), StatusOutcome::Applied);
+}
+
+#[test]
+fn death_clears_statuses() {
+    let mut s = ServerState::new();
+    s.sync_wizards(&[vec3(0.0, 0.6, 0.0)]);
+    let z = s.spawn_undead(vec3(20.0, 0.6, 0.0), 0.9, 1);
+    s.apply_status(z, "burning", None);
+    s.apply_status(z, "slowed", None);
+    for _ in 0..6 {
+        s.step_authoritative(DT);
+    }
+    let c = s.ecs.get(z).unwrap();
+    assert!(!c.hp.alive());
+    assert!(c.statuses.is_empty());
+    assert_eq!(s.apply_status(z, "burning", None), StatusOutcome::Ignored);
+}
+
+#[test]
+fn statuses_replicate_and_clear_on_the_client() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig {
+        interest_radius_m: 100.0,
+        ..Default::default()
+    });
+    let (srv_end, cli_end) = LocalLoopbackTransport::new(1024);
+    let id = host.connect(&mut srv, Box::new(srv_end));
+    let actor = host.actor_of(id).unwrap();
+    let mut link = Endpoint::default();
+    let mut rep = ReplicationBuffer::default();
+    let mut now_ms = 0;
+    let mut tick = |srv: &mut ServerState, host: &mut SessionHost| {
+        host.pump_inputs(srv);
+        srv.step_authoritative(DT);
+        host.broadcast(srv);
+        link.pump(&cli_end, now_ms).unwrap();
+        while let Some((_, payload)) = link.recv() {
+            rep.apply_message(&payload);
+        }
+        now_ms += 100;
+        rep.statuses.clone()
+    };
+    assert!(tick(&mut srv, &mut host).is_empty());
+
+    srv.apply_status(actor, "poisoned", None);
+    srv.apply_status(actor, "poisoned", None);
+    let shown = tick(&mut srv, &mut host);
+    assert_eq!(shown.len(), 1);
+    assert_eq!(shown[0].actor, actor.0);
+    assert_eq!(shown[0].status, net_id(&srv, "poisoned"));
+    assert_eq!(shown[0].stacks, 2);
+    assert!(shown[0].remaining_ms > 0);
+
+    let mut cleared = false;
+    for _ in 0..80 {
+        if tick(&mut srv, &mut host).is_empty() {
+            cleared = true;
+            break;
+        }
+    }
+    assert!(cleared, "the list clears when the status ends");
+}
+
+#[test]
+fn a_stalled_update_cannot_bring_back_cleared_statuses() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig::default());
+    let (id, mut c, stall) = Stall::connect(&mut host, &mut srv);
+    let actor = host.actor_of(id).unwrap();
+    let mut tick = |srv: &mut ServerState, c: &mut Client| {
+        host.pump_inputs(srv);
+        srv.step_authoritative(DT);
+        host.broadcast(srv);
+        c.replicate(100);
+    };
+    srv.apply_status(actor, "poisoned", None);
+    tick(&mut srv, &mut c);
+    assert_eq!(c.rep.statuses.len(), 1);
+
+    // Stall the last updates; the clear gets through first.
+    stall.hold(true);
+    for _ in 0..80 {
+        if srv.ecs.get(actor).unwrap().statuses.is_empty() {
+            break;
+        }
+        tick(&mut srv, &mut c);
+    }
+    stall.hold(false);
+    tick(&mut srv, &mut c);
+    assert!(c.rep.statuses.is_empty());
+    stall.release();
+    c.replicate(100);
+    assert!(c.rep.statuses.is_empty(), "stale update dropped");
+}
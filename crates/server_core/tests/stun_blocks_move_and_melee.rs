@@ -8,9 +8,7 @@ fn stunned_undead_neither_moves_nor_melees() {
     let z = s.spawn_undead(Vec3::new(0.0, 0.6, 1.2), 0.9, 30);
 
     // Stun the undead for long enough to cover the frame we test
-    if let Some(a) = s.ecs.get_mut(z) {
-        a.apply_stun(1.0);
-    }
+    s.apply_status(z, "stunned", None);
 
     // Snapshot wizard hp and zombie position
     let wiz_id = s
@@ -18,7 +16,7 @@ fn stunned_undead_neither_moves_nor_melees() {
         .iter()
         .find(|c| {
             matches!(c.kind, server_core::ActorKind::Wizard)
-                && c.faction == server_core::Faction::Pc
+                && c.faction == server_core::Faction::PC
         })
         .unwrap()
         .id;
@@ -32,7 +30,7 @@ fn stunned_undead_neither_moves_nor_melees() {
             .iter()
             .filter(|a| {
                 matches!(a.kind, server_core::ActorKind::Wizard)
-                    && a.faction == server_core::Faction::Pc
+                    && a.faction == server_core::Faction::PC
             })
             .map(|a| a.tr.pos)
             .collect();
@@ -0,0 +1,264 @@
+#![allow(clippy::unwrap_used)]
+//! PC weapon attacks: melee swings land inside the arc and reach, bow shots
+//! hit the first hostile along the aim, every attack rolls d20 + bonus
+//! against the target's Armor Class with the shared `sim_core` rules (same
+//! seed, same dice), and hits and misses replicate as `HitFx`.
+
+mod common;
+
+use common::{Client, DT, dummy};
+use glam::{Vec3, vec3};
+use net_core::command::{CastTarget, ClientCmd};
+use net_core::link::Channel;
+use net_core::snapshot::{
+    ActorSnapshotDelta, HITFX_WEAPON_CRIT, HITFX_WEAPON_HIT, HITFX_WEAPON_MISS, SnapshotDecode,
+    TAG_ACTOR_SNAPSHOT_DELTA,
+};
+use server_core::actor::{ActorId, Faction};
+use server_core::session::{SessionConfig, SessionHost};
+use server_core::systems::weapons::{AttackReject, check_weapons};
+use server_core::{CombatRng, ServerState};
+use sim_core::rules::attack::{Advantage, roll_attack};
+use sim_core::rules::dice::{CritRule, Dice};
+
+const EAST: CastTarget = CastTarget::Direction([1.0, 0.0, 0.0]);
+
+/// A PC at the origin and a dummy at `pos`.
+fn setup(pos: Vec3) -> (ServerState, ActorId, ActorId) {
+    let mut s = ServerState::new();
+    let pc = s.spawn_pc_at(vec3(0.0, 0.6, 0.0));
+    let z = dummy(&mut s, pos, 1000);
+    (s, pc, z)
+}
+
+fn hp(s: &ServerState, id: ActorId) -> i32 {
+    s.ecs.get(id).unwrap().hp.hp
+}
+
+/// Attack once and step; returns the weapon `HitFx` kinds emitted.
+fn attack(s: &mut ServerState, pc: ActorId, weapon: &str, target: CastTarget) -> Vec<u8> {
+    s.enqueue_weapon_attack(pc, weapon, target).unwrap();
+    s.step_authoritative(DT);
+    std::mem::take(&mut s.fx_hits)
+        .into_iter()
+        .map(|h| h.kind)
+        .filter(|k| (HITFX_WEAPON_HIT..=HITFX_WEAPON_CRIT).contains(k))
+        .collect()
+}
+
+/// Let the shared weapon cooldown run out.
+fn wait_ready(s: &mut ServerState, pc: ActorId) {
+    while s.ecs.get(pc).unwrap().weapons.as_ref().unwrap().ready_in_s > 0.0 {
+        s.step_authoritative(DT);
+    }
+}
+
+#[test]
+fn rolls_match_the_shared_rules_for_the_same_seed() {
+    let (mut s, pc, z) = setup(vec3(2.0, 0.6, 0.0));
+    let staff = s.specs_weapons.get("quarterstaff").unwrap().clone();
+    let ac = s.ecs.get(z).unwrap().armor.unwrap().ac;
+    assert_eq!(ac, s.specs_arche.entries["Undead"].ac);
+    let mut rng = CombatRng::seeded(s.combat_rng.seed());
+    let dice = Dice::parse(&staff.damage);
+    for _ in 0..20 {
+        let before = hp(&s, z);
+        let kinds = attack(&mut s, pc, "quarterstaff", EAST);
+        let roll = roll_attack(&mut rng.0, Advantage::Normal, staff.attack_bonus, ac);
+        let want = if roll.hit {
+            dice.roll_damage(&mut rng.0, roll.crit, CritRule::Nat20DoubleDice)
+        } else {
+            0
+        };
+        assert_eq!(before - hp(&s, z), want, "{roll:?}");
+        let kind = match (roll.hit, roll.crit) {
+            (true, true) => HITFX_WEAPON_CRIT,
+            (true, false) => HITFX_WEAPON_HIT,
+            _ => HITFX_WEAPON_MISS,
+        };
+        assert_eq!(kinds, vec![kind]);
+        wait_ready(&mut s, pc);
+    }
+}
+
+#[test]
+fn armor_class_gates_hits_and_natural_twenty_always_crits() {
+    let (mut s, pc, z) = setup(vec3(2.0, 0.6, 0.0));
+    s.ecs.get_mut(z).unwrap().armor.as_mut().unwrap().ac = 100;
+    let mut seen = Vec::new();
+    for _ in 0..60 {
+        let before = hp(&s, z);
+        let kinds = attack(&mut s, pc, "quarterstaff", EAST);
+        assert_eq!(kinds.len(), 1);
+        assert_eq!(
+            kinds[0] == HITFX_WEAPON_CRIT,
+            hp(&s, z) < before,
+            "only crits get through AC 100"
+        );
+        seen.push(kinds[0]);
+        wait_ready(&mut s, pc);
+    }
+    assert!(seen.contains(&HITFX_WEAPON_MISS));
+    assert!(!seen.contains(&HITFX_WEAPON_HIT));
+}
+
+#[test]
+fn melee_swings_respect_arc_and_reach() {
+    let (mut s, pc, front) = setup(vec3(2.0, 0.6, 0.0));
+    let behind = dummy(&mut s, vec3(-2.0, 0.6, 0.0), 1000);
+    s.ecs.get_mut(front).unwrap().armor.as_mut().unwrap().ac = -100;
+    s.ecs.get_mut(behind).unwrap().armor.as_mut().unwrap().ac = -100;
+    assert_eq!(attack(&mut s, pc, "quarterstaff", EAST).len(), 1);
+    assert!(hp(&s, front) < 1000);
+    assert_eq!(hp(&s, behind), 1000, "outside the swing arc");
+
+    // Out of reach: the swing whiffs without a roll or effect.
+    s.ecs.get_mut(front).unwrap().tr.pos = vec3(6.0, 0.6, 0.0);
+    wait_ready(&mut s, pc);
+    let before = hp(&s, front);
+    assert!(attack(&mut s, pc, "quarterstaff", EAST).is_empty());
+    assert_eq!(hp(&s, front), before);
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "quarterstaff", CastTarget::Actor(front.0)),
+        Err(AttackReject::OutOfRange)
+    );
+}
+
+#[test]
+fn bow_shots_hit_the_first_hostile_along_the_aim() {
+    let (mut s, pc, near) = setup(vec3(10.0, 0.6, 0.0));
+    let far = dummy(&mut s, vec3(20.0, 0.6, 0.0), 1000);
+    for id in [near, far] {
+        s.ecs.get_mut(id).unwrap().armor.as_mut().unwrap().ac = -100;
+    }
+    assert_eq!(attack(&mut s, pc, "shortbow", EAST).len(), 1);
+    assert!(hp(&s, near) < 1000);
+    assert_eq!(hp(&s, far), 1000, "the nearer body takes the arrow");
+
+    wait_ready(&mut s, pc);
+    assert_eq!(
+        attack(&mut s, pc, "shortbow", CastTarget::Actor(far.0)).len(),
+        1
+    );
+    assert!(hp(&s, far) < 1000, "an explicit target is shot directly");
+
+    let long = s.specs_weapons.get("shortbow").unwrap().long_range_m;
+    let gone = dummy(&mut s, vec3(0.0, 0.6, long + 10.0), 1000);
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "shortbow", CastTarget::Actor(gone.0)),
+        Err(AttackReject::OutOfRange)
+    );
+}
+
+#[test]
+fn bows_cannot_shoot_pcs_or_friendly_targets() {
+    let (mut s, pc, z) = setup(vec3(10.0, 0.6, 0.0));
+    s.ecs.get_mut(z).unwrap().armor.as_mut().unwrap().ac = -100;
+    let other = s.spawn_pc(vec3(0.0, 0.6, 10.0));
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "shortbow", CastTarget::Actor(other.0)),
+        Err(AttackReject::BadTarget)
+    );
+    assert!(s.pending_attacks.is_empty());
+
+    // A target that turns friendly before the shot resolves is spared too.
+    s.enqueue_weapon_attack(pc, "shortbow", CastTarget::Actor(z.0))
+        .unwrap();
+    s.ecs.get_mut(z).unwrap().faction = Faction::PC;
+    s.step_authoritative(DT);
+    assert_eq!(hp(&s, z), 1000);
+    assert_eq!(hp(&s, other), s.ecs.get(other).unwrap().hp.max);
+}
+
+#[test]
+fn commands_are_validated_and_cooldowns_hold() {
+    let (mut s, pc, z) = setup(vec3(2.0, 0.6, 0.0));
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "greataxe", EAST),
+        Err(AttackReject::UnknownWeapon)
+    );
+    assert_eq!(
+        s.enqueue_weapon_attack(z, "quarterstaff", EAST),
+        Err(AttackReject::NotEquipped)
+    );
+    assert_eq!(
+        s.enqueue_weapon_attack(pc, "shortbow", CastTarget::Actor(pc.0)),
+        Err(AttackReject::BadTarget)
+    );
+    assert!(s.pending_attacks.is_empty());
+
+    // A second swing inside the cooldown is dropped.
+    s.enqueue_weapon_attack(pc, "quarterstaff", EAST).unwrap();
+    s.enqueue_weapon_attack(pc, "quarterstaff", EAST).unwrap();
+    s.step_authoritative(DT);
+    let kinds: Vec<u8> = s.fx_hits.iter().map(|h| h.kind).collect();
+    assert_eq!(kinds.len(), 1);
+
+    // Stunned attackers cannot attack.
+    wait_ready(&mut s, pc);
+    s.fx_hits.clear();
+    s.apply_status_for(pc, "stunned", None, Some(5.0));
+    assert!(attack(&mut s, pc, "quarterstaff", EAST).is_empty());
+}
+
+#[test]
+fn attacks_replicate_as_hitfx_over_the_wire() {
+    let mut srv = ServerState::new();
+    let mut host = SessionHost::new(SessionConfig::default());
+    let (id, mut c) = Client::connect(&mut host, &mut srv);
+    let me = host.actor_of(id).unwrap();
+    let pos = srv.ecs.get(me).unwrap().tr.pos;
+    let z = srv.spawn_undead(pos + vec3(0.0, 0.0, 8.0), 0.9, 1000);
+    srv.ecs.get_mut(z).unwrap().move_speed = None;
+
+    c.cmd(
+        Channel::Reliable,
+        &ClientCmd::Attack {
+            weapon_id: "shortbow".into(),
+            target: CastTarget::Actor(z.0),
+        },
+    );
+    host.pump_inputs(&mut srv);
+    srv.step_authoritative(DT);
+    host.broadcast(&mut srv);
+
+    let mut hits = Vec::new();
+    for payload in c.recv_all(33) {
+        if payload.first() == Some(&TAG_ACTOR_SNAPSHOT_DELTA) {
+            let d = ActorSnapshotDelta::decode(&mut payload.as_slice()).unwrap();
+            hits.extend(d.hits);
+        }
+    }
+    assert_eq!(hits.len(), 1);
+    assert!((HITFX_WEAPON_HIT..=HITFX_WEAPON_CRIT).contains(&hits[0].kind));
+    let zp = srv.ecs.get(z).unwrap().tr.pos;
+    assert!((Vec3::from(hits[0].pos) - zp).length() < 1e-3);
+}
+
+#[test]
+fn malformed_dice_and_crit_rules_fail_the_weapons_check() {
+    assert_eq!(
+        Dice::try_parse("2d6-1"),
+        Some(Dice {
+            n: 2,
+            sides: 6,
+            flat: -1
+        })
+    );
+    for bad in [
+        "dd", "1d", "d6", "0d6", "1d0", "xd6", "1d6+", "1d6+x", "1x6",
+    ] {
+        assert_eq!(Dice::try_parse(bad), None, "{bad}");
+    }
+    assert_eq!(CritRule::try_parse("none"), Some(CritRule::None));
+    assert_eq!(CritRule::try_parse("nat20_triple"), None);
+
+    let s = ServerState::new();
+    check_weapons(&s.specs_weapons).unwrap();
+    let mut db = s.specs_weapons.clone();
+    db.weapons.get_mut("quarterstaff").unwrap().damage = "dd".into();
+    assert!(check_weapons(&db).is_err());
+    let mut db = s.specs_weapons.clone();
+    db.weapons.get_mut("quarterstaff").unwrap().crit = "nat20_triple".into();
+    assert!(check_weapons(&db).is_err());
+}
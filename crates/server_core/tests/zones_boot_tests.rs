@@ -1,4 +1,12 @@
+#![allow(clippy::unwrap_used)]
+//! Zone boot spawns what the zone's encounter spec and scene describe, and
+//! rejects bad references before spawning anything.
+
+use data_runtime::encounter::EncounterSpec;
+use data_runtime::zone_scene::ZoneScene;
 use server_core::ServerState;
+use server_core::actor::{ActorKind, Faction};
+use server_core::zones::{apply_plan, plan_encounter};
 
 #[test]
 fn boot_with_zone_spawns_demo_for_wizard_woods() {
@@ -6,8 +14,11 @@ fn boot_with_zone_spawns_demo_for_wizard_woods() {
     let spawned = server_core::zones::boot_with_zone(&mut s, "wizard_woods");
     assert!(spawned, "wizard_woods should spawn demo content");
     // Expect at least DK + some wizards in ECS
-    assert!(s.ecs.len() > 0, "server ECS should have actors after boot");
-    assert!(s.nivita_actor_id.is_some(), "unique boss should be spawned");
+    assert!(
+        !s.ecs.is_empty(),
+        "server ECS should have actors after boot"
+    );
+    assert!(s.nivita_actor().is_some(), "unique boss should be spawned");
     assert!(
         !s.destruct_instances.is_empty(),
         "demo destructible instance should be registered"
@@ -23,7 +34,7 @@ fn boot_with_zone_no_spawns_for_campaign_builder() {
         "campaign_builder should not spawn any demo content"
     );
     assert_eq!(s.ecs.len(), 0, "no actors spawned");
-    assert!(s.nivita_actor_id.is_none(), "no unique boss spawned");
+    assert!(s.nivita_actor().is_none(), "no unique boss spawned");
     assert!(
         s.destruct_instances.is_empty(),
         "no destructible instances should be registered"
@@ -37,5 +48,122 @@ fn boot_with_zone_unknown_slug_is_noop() {
     assert!(!spawned, "unknown zones must not spawn anything");
     assert!(s.ecs.is_empty());
     assert!(s.destruct_instances.is_empty());
-    assert!(s.nivita_actor_id.is_none());
+    assert!(s.nivita_actor().is_none());
+}
+
+#[test]
+fn wizard_woods_spawns_its_encounter_spec() {
+    let mut s = ServerState::new();
+    assert!(server_core::zones::boot_with_zone(&mut s, "wizard_woods"));
+    let count = |k: ActorKind| s.ecs.iter().filter(|a| a.kind == k).count();
+    assert_eq!(
+        count(ActorKind::Zombie),
+        8 + 12 + 12,
+        "outer ring is capped at 12"
+    );
+    assert_eq!(count(ActorKind::Wizard), 4);
+    assert_eq!(count(ActorKind::Boss), 2, "Nivita and the Death Knight");
+    let dk = s
+        .ecs
+        .iter()
+        .find(|a| a.name.as_deref() == Some("Death Knight"))
+        .unwrap();
+    assert!((dk.tr.pos.x - 60.0).abs() < 2.0, "{}", dk.tr.pos);
+    assert!(
+        s.ecs
+            .iter()
+            .any(|a| a.kind == ActorKind::Zombie && a.hp.max == 20)
+    );
+    assert_eq!(
+        server_core::zones::pc_spawn(&s, "wizard_woods"),
+        Some(glam::vec3(0.0, 0.6, 0.0))
+    );
+}
+
+fn scene() -> ZoneScene {
+    serde_json::from_str(
+        r#"{
+            "version": "1.0.0", "seed": 0, "layers": [], "instances": [],
+            "logic": {
+                "triggers": [], "links": [],
+                "spawns": [
+                    { "id": "camp", "kind": "npc.ring", "pos": [100.0, 0.6, 0.0],
+                      "yaw_deg": 90.0, "radius_m": 5.0 },
+                    { "id": "oak", "kind": "tree.default", "pos": [3.0, 0.0, 3.0], "yaw_deg": 0.0 }
+                ],
+                "waypoints": [ { "id": "gate", "pos": [0.0, 0.6, -80.0] } ]
+            }
+        }"#,
+    )
+    .unwrap()
+}
+
+fn encounter(npcs: &str) -> EncounterSpec {
+    serde_json::from_str(&format!(r#"{{ "version": "1.0.0", "npcs": {npcs} }}"#)).unwrap()
+}
+
+#[test]
+fn new_zone_content_needs_no_code() {
+    let mut s = ServerState::new();
+    let enc = encounter(
+        r#"[
+            { "archetype": "Undead", "count": 4, "spawn": "camp", "hp": 12 },
+            { "archetype": "WizardNPC", "spawn": "gate", "faction": "neutral" }
+        ]"#,
+    );
+    let plan = plan_encounter(&s, &enc, Some(&scene())).unwrap();
+    assert_eq!(plan.groups.len(), 2);
+    // Four undead evenly on the 5 m ring around the camp marker.
+    let camp = &plan.groups[0];
+    assert_eq!(camp.slots.len(), 4);
+    assert!((camp.template.yaw - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    for (i, p) in camp.slots.iter().enumerate() {
+        let a = i as f32 / 4.0 * std::f32::consts::TAU;
+        let want = glam::vec3(100.0 + 5.0 * a.cos(), 0.6, 5.0 * a.sin());
+        assert!(p.distance(want) < 1e-4, "{p} != {want}");
+    }
+    assert_eq!(apply_plan(&mut s, &plan), 5);
+
+    let undead: Vec<_> = s
+        .ecs
+        .iter()
+        .filter(|a| a.kind == ActorKind::Zombie)
+        .collect();
+    assert_eq!(undead.len(), 4);
+    let spec = &s.specs_arche.entries["Undead"];
+    for u in &undead {
+        assert_eq!(u.hp.max, 12);
+        assert_eq!(u.tr.radius, spec.radius_m);
+        assert_eq!(u.aggro.unwrap().m, spec.aggro_radius_m);
+    }
+    let wiz = s.ecs.iter().find(|a| a.kind == ActorKind::Wizard).unwrap();
+    assert_eq!(wiz.faction, Faction::NEUTRAL);
+    assert!(wiz.tr.pos.distance(glam::vec3(0.0, 0.6, -80.0)) < 1e-4);
+}
+
+#[test]
+fn bad_references_spawn_nothing() {
+    let s = ServerState::new();
+    for (npcs, want) in [
+        (
+            r#"[{ "archetype": "Undead", "spawn": "camp" }, { "archetype": "Dragon", "spawn": "camp" }]"#,
+            "unknown archetype 'Dragon'",
+        ),
+        (
+            r#"[{ "archetype": "Undead", "spawn": "nowhere" }]"#,
+            "unknown spawn point 'nowhere'",
+        ),
+        (
+            r#"[{ "archetype": "Undead", "spawn": "camp", "faction": "Pirates" }]"#,
+            "unknown faction 'Pirates'",
+        ),
+        (
+            r#"[{ "archetype": "Nivita", "spawn": "gate" }]"#,
+            "is unique",
+        ),
+    ] {
+        let err = plan_encounter(&s, &encounter(npcs), Some(&scene())).unwrap_err();
+        assert!(format!("{err:#}").contains(want), "{err:#}");
+    }
+    assert!(s.ecs.is_empty());
 }
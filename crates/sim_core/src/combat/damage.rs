@@ -16,3 +16,25 @@ pub enum DamageType {
     Force,
     Thunder,
 }
+
+impl DamageType {
+    /// Parse a spec value (`"fire"`, any case).
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s.to_ascii_lowercase().as_str() {
+            "bludgeoning" => Self::Bludgeoning,
+            "piercing" => Self::Piercing,
+            "slashing" => Self::Slashing,
+            "fire" => Self::Fire,
+            "cold" => Self::Cold,
+            "lightning" => Self::Lightning,
+            "acid" => Self::Acid,
+            "poison" => Self::Poison,
+            "psychic" => Self::Psychic,
+            "radiant" => Self::Radiant,
+            "necrotic" => Self::Necrotic,
+            "force" => Self::Force,
+            "thunder" => Self::Thunder,
+            _ => return None,
+        })
+    }
+}
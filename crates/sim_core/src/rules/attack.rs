@@ -1,8 +1,66 @@
-//! Attack roll scaffolding.
+//! Attack rolls: d20 + bonus against Armor Class.
+//!
+//! SRD 5.2.1: any number of advantage sources and any number of disadvantage
+//! sources cancel out; a natural 20 always hits and is a critical hit, a
+//! natural 1 always misses.
 
-#[derive(Copy, Clone, Debug)]
+use rand::Rng;
+
+use crate::rules::dice;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum Advantage {
+    #[default]
     Normal,
     Advantage,
     Disadvantage,
 }
+
+impl Advantage {
+    /// Net result of having any advantage and/or any disadvantage source.
+    pub fn from_sources(adv: bool, dis: bool) -> Self {
+        match (adv, dis) {
+            (true, false) => Self::Advantage,
+            (false, true) => Self::Disadvantage,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// Outcome of one attack roll.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AttackRoll {
+    /// The kept d20.
+    pub d20: i32,
+    pub bonus: i32,
+    pub total: i32,
+    pub ac: i32,
+    pub hit: bool,
+    pub crit: bool,
+}
+
+/// Whether a kept `d20` with `total` hits `ac`.
+pub fn hits(d20: i32, total: i32, ac: i32) -> bool {
+    d20 == 20 || (d20 != 1 && total >= ac)
+}
+
+/// Roll to hit `ac` with `bonus`.
+pub fn roll_attack<R: Rng + ?Sized>(
+    rng: &mut R,
+    adv: Advantage,
+    bonus: i32,
+    ac: i32,
+) -> AttackRoll {
+    let d20 = dice::d20(rng, adv);
+    let total = d20 + bonus;
+    let crit = d20 == 20;
+    let hit = hits(d20, total, ac);
+    AttackRoll {
+        d20,
+        bonus,
+        total,
+        ac,
+        hit,
+        crit,
+    }
+}
@@ -1,6 +1,125 @@
 //! Dice helpers; deterministic at call sites via injected RNG.
+//!
+//! Every die is `rng.random::<u32>() % sides + 1`, so the same seed yields the
+//! same rolls in the simulator and on the server.
 
+use rand::Rng;
+
+use crate::rules::attack::Advantage;
+
+/// How a critical hit scales damage.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum CritRule {
+    #[default]
     None,
+    /// SRD: roll the damage dice twice; flat bonuses are not doubled.
     Nat20DoubleDice,
 }
+
+impl CritRule {
+    /// Parse a spec value (`"none"`, `"nat20_double_dice"`); `None` if unknown.
+    pub fn try_parse(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(Self::None),
+            "nat20_double_dice" => Some(Self::Nat20DoubleDice),
+            _ => None,
+        }
+    }
+
+    /// Like `try_parse`, but anything unknown is `None`.
+    pub fn parse(s: &str) -> Self {
+        Self::try_parse(s).unwrap_or_default()
+    }
+}
+
+/// One die with `sides` faces (at least 1).
+pub fn die<R: Rng + ?Sized>(rng: &mut R, sides: u32) -> i32 {
+    (rng.random::<u32>() % sides.max(1) + 1) as i32
+}
+
+/// A d20: one die normally, the higher of two with advantage and the lower of
+/// two with disadvantage.
+pub fn d20<R: Rng + ?Sized>(rng: &mut R, adv: Advantage) -> i32 {
+    let a = die(rng, 20);
+    match adv {
+        Advantage::Normal => a,
+        Advantage::Advantage => a.max(die(rng, 20)),
+        Advantage::Disadvantage => a.min(die(rng, 20)),
+    }
+}
+
+/// `NdM+K` dice expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Dice {
+    pub n: i32,
+    pub sides: i32,
+    pub flat: i32,
+}
+
+impl Dice {
+    /// Parse `NdM`, optionally with `+K`/`-K` (e.g., `3d4+3`, `2d6-1`), with
+    /// `N` and `M` at least 1. `None` if `s` is anything else.
+    pub fn try_parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (base, flat) = match s.rfind(['+', '-']) {
+            Some(pos) => {
+                let (left, right) = s.split_at(pos);
+                let k = right[1..].parse::<u32>().ok()?;
+                let k = i32::try_from(k).ok()?;
+                (left, if right.starts_with('+') { k } else { -k })
+            }
+            None => (s, 0),
+        };
+        let (n, sides) = base.split_once('d')?;
+        let (n, sides) = (n.parse::<u32>().ok()?, sides.parse::<u32>().ok()?);
+        if n == 0 || sides == 0 {
+            return None;
+        }
+        Some(Self {
+            n: i32::try_from(n).ok()?,
+            sides: i32::try_from(sides).ok()?,
+            flat,
+        })
+    }
+
+    /// Lenient `try_parse` for trusted expressions: malformed parts fall back
+    /// to 1 (so garbage reads as `1d1`).
+    pub fn parse(s: &str) -> Self {
+        let mut base = s.trim();
+        let mut flat = 0;
+        if let Some(pos) = base.rfind(['+', '-'])
+            && pos > 0
+        {
+            let (left, right) = base.split_at(pos);
+            base = left;
+            let k = right[1..].parse::<i32>().unwrap_or(0);
+            flat = if right.starts_with('+') { k } else { -k };
+        }
+        let (n, sides) = match base.split_once('d') {
+            Some((n, m)) => (n.parse().unwrap_or(1), m.parse().unwrap_or(1)),
+            None => (1, 1),
+        };
+        Self { n, sides, flat }
+    }
+
+    /// Sum of the dice alone.
+    pub fn roll_dice<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        (0..self.n.max(0))
+            .map(|_| die(rng, self.sides.max(1) as u32))
+            .sum()
+    }
+
+    /// Dice plus the flat modifier.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> i32 {
+        self.roll_dice(rng) + self.flat
+    }
+
+    /// Damage roll; a crit under `Nat20DoubleDice` rolls the dice twice.
+    pub fn roll_damage<R: Rng + ?Sized>(&self, rng: &mut R, crit: bool, rule: CritRule) -> i32 {
+        let mut total = self.roll(rng);
+        if crit && rule == CritRule::Nat20DoubleDice {
+            total += self.roll_dice(rng);
+        }
+        total.max(0)
+    }
+}
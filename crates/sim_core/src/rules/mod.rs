@@ -3,3 +3,4 @@
 pub mod attack;
 pub mod dice;
 pub mod saves;
+pub mod spell;
@@ -1,6 +1,14 @@
-//! Saving throw scaffolding.
+//! Saving throws: d20 + save modifier against a DC.
+//!
+//! SRD 5.2.1: the save succeeds when the total meets or beats the DC; natural
+//! 20s and 1s have no special meaning.
 
-#[derive(Copy, Clone, Debug)]
+use rand::Rng;
+
+use crate::rules::attack::Advantage;
+use crate::rules::dice;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SaveKind {
     Str,
     Dex,
@@ -9,3 +17,43 @@ pub enum SaveKind {
     Wis,
     Cha,
 }
+
+impl SaveKind {
+    /// Parse a spec value (`"dex"`, `"dexterity"`, any case); unknown names
+    /// read as Dexterity.
+    pub fn parse(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "str" | "strength" => Self::Str,
+            "dex" | "dexterity" => Self::Dex,
+            "con" | "constitution" => Self::Con,
+            "int" | "intelligence" => Self::Int,
+            "wis" | "wisdom" => Self::Wis,
+            "cha" | "charisma" => Self::Cha,
+            _ => Self::Dex,
+        }
+    }
+}
+
+/// Outcome of one saving throw.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SaveRoll {
+    /// The kept d20.
+    pub d20: i32,
+    pub bonus: i32,
+    pub total: i32,
+    pub dc: i32,
+    pub success: bool,
+}
+
+/// Roll a save with `bonus` against `dc`.
+pub fn roll_save<R: Rng + ?Sized>(rng: &mut R, adv: Advantage, bonus: i32, dc: i32) -> SaveRoll {
+    let d20 = dice::d20(rng, adv);
+    let total = d20 + bonus;
+    SaveRoll {
+        d20,
+        bonus,
+        total,
+        dc,
+        success: total >= dc,
+    }
+}
@@ -0,0 +1,208 @@
+//! Spell resolution against one target, shared by the simulator systems and
+//! the server.
+//!
+//! `SpellRules` is built once from a `SpellSpec`. `resolve` rolls the saving
+//! throw (if the spell has one), then the spell attack (if any), then the
+//! damage dice. Area spells roll each target's save (`saving_throw`), then
+//! the damage once for all of them (`roll_damage`). A natural 20 on the
+//! attack doubles the dice under the spell's crit rule. A successful save
+//! halves the damage when the spell says so and negates it otherwise.
+//! Resistance is applied afterwards by whoever owns hit points (`resist`), so
+//! it also covers damage that never went through `resolve`.
+
+use rand::Rng;
+
+use data_runtime::spell::{DamageSpec, SpellSpec};
+
+use crate::rules::attack::{Advantage, AttackRoll, roll_attack};
+use crate::rules::dice::{CritRule, Dice};
+use crate::rules::saves::{SaveKind, SaveRoll, roll_save};
+
+/// Dice rolled when a spell has damage but no usable level band.
+const FALLBACK_DICE: &str = "1d10";
+
+/// The spell's saving throw.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SpellSave {
+    pub kind: SaveKind,
+    /// Fixed DC; `None` uses the caster's spell save DC.
+    pub dc: Option<i32>,
+    pub half_on_success: bool,
+}
+
+/// The spell's damage: type name and dice per character level band.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpellDamage {
+    /// Lower-case damage type (e.g., `"fire"`).
+    pub damage_type: String,
+    /// `(min_level, max_level, dice)`, sorted by `min_level`.
+    pub bands: Vec<(u8, u8, Dice)>,
+}
+
+impl SpellDamage {
+    fn from_spec(d: &DamageSpec) -> Self {
+        let mut bands: Vec<(u8, u8, Dice)> = d
+            .dice_by_level_band
+            .iter()
+            .flatten()
+            .filter_map(|(k, v)| {
+                let (lo, hi) = k.split_once('-').unwrap_or((k, k));
+                Some((
+                    lo.trim().parse().ok()?,
+                    hi.trim().parse().ok()?,
+                    Dice::parse(v),
+                ))
+            })
+            .collect();
+        bands.sort_by_key(|b| (b.0, b.1));
+        Self {
+            damage_type: d.damage_type.to_ascii_lowercase(),
+            bands,
+        }
+    }
+
+    /// Dice for a caster of `level`: the band containing it, else the highest
+    /// band, else `1d10`.
+    pub fn dice(&self, level: u8) -> Dice {
+        self.bands
+            .iter()
+            .find(|(lo, hi, _)| (*lo..=*hi).contains(&level))
+            .or_else(|| self.bands.iter().max_by_key(|b| b.1))
+            .map(|b| b.2)
+            .unwrap_or_else(|| Dice::parse(FALLBACK_DICE))
+    }
+}
+
+/// What a spell does to a single target, from its spec.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpellRules {
+    /// Spell attack roll and its crit rule; `None` when the spell auto-hits.
+    pub attack: Option<CritRule>,
+    pub save: Option<SpellSave>,
+    pub damage: Option<SpellDamage>,
+}
+
+impl SpellRules {
+    pub fn from_spec(spec: &SpellSpec) -> Self {
+        Self {
+            attack: spec.attack.as_ref().map(|a| {
+                a.crit_rule
+                    .as_deref()
+                    .map(CritRule::parse)
+                    .unwrap_or_default()
+            }),
+            save: spec.save.as_ref().map(|s| SpellSave {
+                kind: SaveKind::parse(&s.kind),
+                dc: s.dc,
+                half_on_success: s.half_on_success,
+            }),
+            damage: spec.damage.as_ref().map(SpellDamage::from_spec),
+        }
+    }
+
+    /// Roll the spell against one target; see the module docs for the order.
+    pub fn resolve<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        caster: &Caster,
+        target: &Target,
+    ) -> SpellOutcome {
+        let save = self.saving_throw(rng, caster, target);
+        let attack = self
+            .attack
+            .map(|_| roll_attack(rng, target.attack_adv, caster.attack_bonus, target.ac));
+        let hit = attack.is_none_or(|a| a.hit);
+        let crit = attack.is_some_and(|a| a.crit);
+        let rolled = match &self.damage {
+            Some(d) if hit => {
+                d.dice(caster.level)
+                    .roll_damage(rng, crit, self.attack.unwrap_or_default())
+            }
+            _ => 0,
+        };
+        SpellOutcome {
+            attack,
+            save,
+            damage: self.after_save(rolled, save),
+        }
+    }
+
+    /// The saving throw `target` makes against the spell, if it has one.
+    pub fn saving_throw<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        caster: &Caster,
+        target: &Target,
+    ) -> Option<SaveRoll> {
+        self.save.map(|s| {
+            let dc = s.dc.unwrap_or(caster.save_dc);
+            roll_save(rng, Advantage::Normal, target.save_mod, dc)
+        })
+    }
+
+    /// Roll the damage dice once, without an attack (so no crit): an area
+    /// spell shares one roll between every target, each of which then
+    /// applies its own save with `after_save`.
+    pub fn roll_damage<R: Rng + ?Sized>(&self, rng: &mut R, caster: &Caster) -> i32 {
+        self.damage.as_ref().map_or(0, |d| {
+            d.dice(caster.level)
+                .roll_damage(rng, false, self.attack.unwrap_or_default())
+        })
+    }
+
+    /// `rolled` damage after the target's saving throw.
+    pub fn after_save(&self, rolled: i32, save: Option<SaveRoll>) -> i32 {
+        match (self.save, save) {
+            (Some(s), Some(r)) => save_damage(rolled, r.success, s.half_on_success),
+            _ => rolled,
+        }
+    }
+}
+
+/// The caster's spellcasting numbers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Caster {
+    pub attack_bonus: i32,
+    pub save_dc: i32,
+    /// Character level, for cantrip dice bands.
+    pub level: u8,
+}
+
+/// What the target brings to the roll.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Target {
+    pub ac: i32,
+    /// Modifier for the spell's save kind.
+    pub save_mod: i32,
+    /// Advantage on spell attacks against this target.
+    pub attack_adv: Advantage,
+}
+
+/// Result of `SpellRules::resolve`; `damage` is before resistance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SpellOutcome {
+    pub attack: Option<AttackRoll>,
+    pub save: Option<SaveRoll>,
+    pub damage: i32,
+}
+
+impl SpellOutcome {
+    /// Whether the spell landed (no attack roll, or the attack hit).
+    pub fn hit(&self) -> bool {
+        self.attack.is_none_or(|a| a.hit)
+    }
+}
+
+/// Damage after a saving throw: half (rounded down) or none on a success.
+pub fn save_damage(amount: i32, success: bool, half_on_success: bool) -> i32 {
+    match (success, half_on_success) {
+        (false, _) => amount,
+        (true, true) => amount / 2,
+        (true, false) => 0,
+    }
+}
+
+/// Damage after resistance: halved, rounded down.
+pub fn resist(amount: i32, resistant: bool) -> i32 {
+    if resistant { amount / 2 } else { amount }
+}
@@ -1,4 +1,185 @@
-//! Threat per NPC (placeholder)
+//! Threat per NPC: who it is fighting and how much each foe has earned.
+//!
+//! Shared by the raid simulator and `server_core` AI so both tank the same way:
+//! - damage adds `amount * damage_mul` threat to the attacker;
+//! - healing adds `amount * heal_mul` to the healer on every table that
+//!   already lists the healed ally;
+//! - a taunt lifts the taunter to the top threat and takes the target at once;
+//! - all threat decays exponentially at `decay_per_s`;
+//! - a challenger takes the target only once its threat exceeds the current
+//!   target's by the overtake margin: `overtake_melee` when it stands in melee
+//!   range of the NPC, `overtake_ranged` otherwise.
+//!
+//! Entries keep insertion order, so ties go to whoever engaged first.
 
-#[derive(Default)]
-pub struct Threat;
+/// Tunables for threat generation, decay and target switching.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThreatRules {
+    pub damage_mul: f32,
+    pub heal_mul: f32,
+    /// Fraction of threat lost per second (exponential).
+    pub decay_per_s: f32,
+    /// Challenger/current threat ratio needed to pull from melee range.
+    pub overtake_melee: f32,
+    /// Challenger/current threat ratio needed to pull from range.
+    pub overtake_ranged: f32,
+}
+
+impl Default for ThreatRules {
+    fn default() -> Self {
+        Self {
+            damage_mul: 1.0,
+            heal_mul: 0.5,
+            decay_per_s: 0.02,
+            overtake_melee: 1.10,
+            overtake_ranged: 1.30,
+        }
+    }
+}
+
+/// One NPC's threat table keyed by actor handle (`usize` index in the sim).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Threat<K = usize> {
+    entries: Vec<(K, f32)>,
+    target: Option<K>,
+}
+
+impl<K> Default for Threat<K> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            target: None,
+        }
+    }
+}
+
+impl<K: Copy + PartialEq> Threat<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn contains(&self, k: K) -> bool {
+        self.entries.iter().any(|(e, _)| *e == k)
+    }
+
+    /// Current threat for `k` (0 when absent).
+    pub fn get(&self, k: K) -> f32 {
+        self.entries
+            .iter()
+            .find(|(e, _)| *e == k)
+            .map_or(0.0, |(_, t)| *t)
+    }
+
+    /// Entries in engagement order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, f32)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    /// The foe currently being attacked.
+    pub fn target(&self) -> Option<K> {
+        self.target
+    }
+
+    /// Highest threat; ties go to the earliest entry.
+    pub fn top(&self) -> Option<(K, f32)> {
+        self.entries
+            .iter()
+            .copied()
+            .fold(None, |best, e| match best {
+                Some((_, t)) if t >= e.1 => best,
+                _ => Some(e),
+            })
+    }
+
+    /// Put `k` on the table (at zero threat if new), e.g. on entering aggro range.
+    pub fn engage(&mut self, k: K) {
+        if !self.contains(k) {
+            self.entries.push((k, 0.0));
+        }
+    }
+
+    /// Add raw threat for `k`, engaging it if needed.
+    pub fn add(&mut self, k: K, amount: f32) {
+        if let Some(e) = self.entries.iter_mut().find(|(e, _)| *e == k) {
+            e.1 = (e.1 + amount).max(0.0);
+        } else {
+            self.entries.push((k, amount.max(0.0)));
+        }
+    }
+
+    pub fn add_damage(&mut self, rules: &ThreatRules, attacker: K, amount: i32) {
+        self.add(attacker, amount.max(0) as f32 * rules.damage_mul);
+    }
+
+    /// Healing threat lands only on tables already fighting the healed ally.
+    /// Returns whether threat was added.
+    pub fn add_heal(&mut self, rules: &ThreatRules, healer: K, healed: K, amount: i32) -> bool {
+        if !self.contains(healed) {
+            return false;
+        }
+        self.add(healer, amount.max(0) as f32 * rules.heal_mul);
+        true
+    }
+
+    /// Match the top threat and take the target immediately.
+    pub fn taunt(&mut self, k: K) {
+        let top = self.top().map_or(0.0, |(_, t)| t);
+        let have = self.get(k);
+        self.add(k, (top - have).max(0.0));
+        self.target = Some(k);
+    }
+
+    pub fn decay(&mut self, rules: &ThreatRules, dt_s: f32) {
+        let keep = (1.0 - rules.decay_per_s.clamp(0.0, 1.0)).powf(dt_s.max(0.0));
+        for e in &mut self.entries {
+            e.1 *= keep;
+        }
+    }
+
+    /// Drop entries failing `f` (dead, despawned, out of reach).
+    pub fn retain(&mut self, mut f: impl FnMut(K, f32) -> bool) {
+        self.entries.retain(|(k, t)| f(*k, *t));
+        if self.target.is_some_and(|t| !self.contains(t)) {
+            self.target = None;
+        }
+    }
+
+    pub fn remove(&mut self, k: K) {
+        self.retain(|e, _| e != k);
+    }
+
+    /// Forget everything (evade/leash reset).
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.target = None;
+    }
+
+    /// Re-evaluate the target. `in_melee(k)` says whether `k` is in melee
+    /// range of this NPC, which picks the overtake margin it must beat.
+    pub fn select(&mut self, rules: &ThreatRules, in_melee: impl Fn(K) -> bool) -> Option<K> {
+        let (top, top_t) = self.top()?;
+        match self.target {
+            Some(cur) if cur != top => {
+                let margin = if in_melee(top) {
+                    rules.overtake_melee
+                } else {
+                    rules.overtake_ranged
+                };
+                if top_t > self.get(cur) * margin {
+                    self.target = Some(top);
+                }
+            }
+            Some(_) => {}
+            None => self.target = Some(top),
+        }
+        self.target
+    }
+}
@@ -69,4 +69,13 @@ pub enum SimEvent {
         caster: String,
         duration_ms: u32,
     },
+    Taunted {
+        actor: String,
+        target: String,
+    },
+    TargetChanged {
+        actor: String,
+        target: String,
+        threat: f32,
+    },
 }
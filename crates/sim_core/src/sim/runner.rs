@@ -54,6 +54,8 @@ pub fn run_scenario(scn: &Scenario, result_only: bool) {
             temp_hp: 0,
             concentration: None,
             ability_cooldowns: std::collections::HashMap::new(),
+            threat: Default::default(),
+            resist: Vec::new(),
         };
         if actor.role == "boss" && actor.ability_ids.is_empty() {
             actor.ability_ids.push("boss.tentacle".into());
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
-use rand::{Rng, SeedableRng};
+use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
 use crate::combat::fsm::{ActionDone, ActionState, Gcd};
 use crate::rules::attack::Advantage;
+use crate::sim::components::threat::{Threat, ThreatRules};
 use crate::sim::events::SimEvent;
 use data_runtime::loader::{load_class_spec, load_monster_spec};
 use data_runtime::specdb::SpecDb;
@@ -37,6 +38,10 @@ pub struct ActorSim {
     pub concentration: Option<String>,
     // Per-ability cooldown timers in milliseconds.
     pub ability_cooldowns: HashMap<String, u32>,
+    // Threat table (bosses/NPCs): who this actor attacks.
+    pub threat: Threat,
+    // Damage types this actor takes half damage from.
+    pub resist: Vec<crate::combat::damage::DamageType>,
 }
 
 pub struct SimState {
@@ -47,9 +52,12 @@ pub struct SimState {
     pub spec_db: SpecDb,
     pub cast_completed: Vec<(usize, String)>,
     pub pending_damage: Vec<(usize, String, bool)>,
+    /// Casts whose target made its saving throw this tick (caster, ability).
+    pub saves_succeeded: Vec<(usize, String)>,
     pub pending_status: Vec<(usize, crate::combat::conditions::Condition, u32)>,
     pub events: Vec<SimEvent>,
     pub underwater: bool,
+    pub threat_rules: ThreatRules,
 }
 
 impl SimState {
@@ -62,9 +70,11 @@ impl SimState {
             spec_db: SpecDb::load_default(),
             cast_completed: Vec::new(),
             pending_damage: Vec::new(),
+            saves_succeeded: Vec::new(),
             pending_status: Vec::new(),
             events: Vec::new(),
             underwater: false,
+            threat_rules: ThreatRules::default(),
         }
     }
 
@@ -131,41 +141,48 @@ impl SimState {
         }
     }
 
-    pub fn roll_d20(&mut self, _adv: Advantage) -> (i32, bool) {
-        let v: i32 = (self.rng.random::<u32>() % 20 + 1) as i32;
+    pub fn roll_d20(&mut self, adv: Advantage) -> (i32, bool) {
+        let v = crate::rules::dice::d20(&mut self.rng, adv);
         (v, v == 20)
     }
 
+    /// Roll an `NdM+K` expression (see `rules::dice::Dice::parse`).
     pub fn roll_dice_str(&mut self, dice: &str) -> i32 {
-        // Parser for NdM, optionally with +K/-K (e.g., 3d4+3, 2d6-1). Whitespace not expected.
-        // Fallbacks default to 1d1.
-        let mut base = dice.trim();
-        let mut flat: i32 = 0;
-        // Handle +K or -K suffix
-        if let Some(pos) = base.rfind(['+', '-'])
-            && pos > 0
-        {
-            let (left, right) = base.split_at(pos);
-            base = left;
-            let sign = &right[..1];
-            let k = right[1..].parse::<i32>().unwrap_or(0);
-            flat = if sign == "+" { k } else { -k };
-        }
-        let (n, m) = if let Some((n, m)) = base.split_once('d') {
-            (n.parse::<i32>().unwrap_or(1), m.parse::<i32>().unwrap_or(1))
-        } else {
-            (1, 1)
-        };
-        let mut sum = 0;
-        for _ in 0..n.max(0) {
-            sum += (self.rng.random::<u32>() % (m.max(1) as u32) + 1) as i32;
-        }
-        sum + flat
+        crate::rules::dice::Dice::parse(dice).roll(&mut self.rng)
     }
 
     pub fn actor_alive(&self, idx: usize) -> bool {
         self.actors.get(idx).map(|a| a.hp > 0).unwrap_or(false)
     }
+
+    /// Range of an ability in feet, from loaded specs, the spec DB or builtins.
+    pub fn ability_range_ft(&self, id: &str) -> Option<u32> {
+        if let Some(s) = self.spells.get(id).or_else(|| self.spec_db.get_spell(id)) {
+            return Some(s.range_ft);
+        }
+        match id {
+            "basic_attack" => Some(Self::builtin_basic_attack_spec().range_ft),
+            "boss.tentacle" => Some(Self::builtin_boss_tentacle_spec().range_ft),
+            _ => None,
+        }
+    }
+
+    /// Whether an actor fights in melee (any ability with 5 ft reach).
+    pub fn fights_in_melee(&self, idx: usize) -> bool {
+        self.actors.get(idx).is_some_and(|a| {
+            a.ability_ids
+                .iter()
+                .any(|id| self.ability_range_ft(id).is_some_and(|r| r <= 5))
+        })
+    }
+
+    /// Healing threat: every NPC fighting `healed` takes note of `healer`.
+    pub fn add_heal_threat(&mut self, healer: usize, healed: usize, amount: i32) {
+        let rules = self.threat_rules;
+        for a in &mut self.actors {
+            a.threat.add_heal(&rules, healer, healed, amount);
+        }
+    }
 }
 
 // Built-in fallback specs
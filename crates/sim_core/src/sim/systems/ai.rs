@@ -1,7 +1,17 @@
-//! Simple AI: ensure bosses target an alive player; ensure players target boss.
+//! Simple AI: bosses attack by threat; players target the boss.
+//!
+//! Each tick a boss engages every living player (newcomers at zero threat),
+//! forgets the dead, decays threat and re-selects its target under the shared
+//! overtake rules (`components::threat`). Tanks taunt whenever the boss is on
+//! someone else and their taunt is off cooldown.
 
+use crate::sim::events::SimEvent;
 use crate::sim::state::SimState;
 
+/// Cooldown key in `ActorSim::ability_cooldowns` for the tank taunt.
+pub const TAUNT_ID: &str = "taunt";
+const TAUNT_COOLDOWN_MS: u32 = 8000;
+
 pub fn run(state: &mut SimState) {
     // Find an alive boss index and an alive player index
     let boss_idxs: Vec<usize> = state
@@ -18,17 +28,50 @@ pub fn run(state: &mut SimState) {
         .filter(|(_, a)| a.team.as_deref() == Some("players") && a.hp > 0)
         .map(|(i, _)| i)
         .collect();
+    let rules = state.threat_rules;
+    let dt_s = state.tick_ms as f32 / 1000.0;
+    let alive: Vec<bool> = state.actors.iter().map(|a| a.hp > 0).collect();
+    let melee: Vec<bool> = (0..state.actors.len())
+        .map(|i| state.fights_in_melee(i))
+        .collect();
 
     for &b in &boss_idxs {
-        if state.actors[b]
-            .target
-            .map(|t| state.actor_alive(t))
-            .unwrap_or(false)
-        {
-            continue;
+        for &p in &player_idxs {
+            let off_tank = state.actors[b].threat.target().is_some_and(|t| t != p);
+            let tank = &mut state.actors[p];
+            if tank.role != "tank"
+                || !off_tank
+                || tank.ability_cooldowns.get(TAUNT_ID).copied().unwrap_or(0) > 0
+            {
+                continue;
+            }
+            tank.ability_cooldowns
+                .insert(TAUNT_ID.into(), TAUNT_COOLDOWN_MS);
+            let tank_id = tank.id.clone();
+            state.actors[b].threat.taunt(p);
+            state.events.push(SimEvent::Taunted {
+                actor: tank_id,
+                target: state.actors[b].id.clone(),
+            });
         }
-        if let Some(&p) = player_idxs.first() {
-            state.actors[b].target = Some(p);
+        let boss = &mut state.actors[b];
+        for &p in &player_idxs {
+            boss.threat.engage(p);
+        }
+        boss.threat
+            .retain(|k, _| alive.get(k).copied().unwrap_or(false));
+        boss.threat.decay(&rules, dt_s);
+        let before = boss.target;
+        boss.target = boss.threat.select(&rules, |k| melee[k]);
+        if boss.target != before
+            && let Some(t) = boss.target
+        {
+            let ev = SimEvent::TargetChanged {
+                actor: boss.id.clone(),
+                target: state.actors[t].id.clone(),
+                threat: state.actors[b].threat.get(t),
+            };
+            state.events.push(ev);
         }
     }
     // Ensure players target the boss
@@ -1,6 +1,6 @@
 //! Resolve attack rolls for newly completed casts.
 
-use crate::rules::attack::Advantage;
+use crate::rules::attack::{Advantage, hits};
 use crate::sim::events::SimEvent;
 use crate::sim::state::SimState;
 
@@ -47,7 +47,7 @@ pub fn run(state: &mut SimState) {
             }
             let total = roll + bonus;
             let mut target_ac = target_ac_initial;
-            let would_hit = total >= target_ac;
+            let would_hit = hits(roll, total, target_ac);
             // Reaction: Shield (+5 AC) if target has shield and reaction ready and would be hit
             if let Some(tgt_idx) = state.actors[actor_idx].target
                 && would_hit
@@ -65,7 +65,7 @@ pub fn run(state: &mut SimState) {
                     new_ac: target_ac,
                 });
             }
-            let hit = total >= target_ac;
+            let hit = hits(roll, total, target_ac);
             let actor_id = state.actors[actor_idx].id.clone();
             state.events.push(SimEvent::AttackResolved {
                 actor: actor_id,
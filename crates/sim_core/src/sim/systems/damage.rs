@@ -1,56 +1,37 @@
 //! Apply damage for pending hits.
+//!
+//! Dice, crits, saves for half and resistance follow `rules::spell`, the same
+//! rules the server resolves spells with.
 
+use crate::combat::damage::DamageType;
+use crate::rules::spell::{SpellRules, resist, save_damage};
 use crate::sim::events::SimEvent;
 use crate::sim::state::SimState;
 
-fn level_band(lvl: u8) -> &'static str {
-    if lvl <= 4 {
-        "1-4"
-    } else if lvl <= 10 {
-        "5-10"
-    } else if lvl <= 16 {
-        "11-16"
-    } else {
-        "17-20"
-    }
-}
-
-fn pick_dice_for_level(dmg: &data_runtime::spell::DamageSpec, lvl: u8) -> String {
-    if let Some(map) = &dmg.dice_by_level_band {
-        let key = level_band(lvl);
-        if let Some(s) = map.get(key) {
-            return s.clone();
-        }
-        // Fallback to highest available band if exact key is missing
-        for k in ["17-20", "11-16", "5-10", "1-4"] {
-            if let Some(s) = map.get(k) {
-                return s.clone();
-            }
-        }
-    }
-    "1d10".to_string()
-}
-
 pub fn run(state: &mut SimState) {
     let pending = std::mem::take(&mut state.pending_damage);
+    let saved = std::mem::take(&mut state.saves_succeeded);
     for (actor_idx, ability_id, crit) in pending {
         let Some(spec) = state.spells.get(&ability_id) else {
             continue;
         };
-        let Some(dmg) = &spec.damage else { continue };
-        // Copy fields we need before mutably borrowing state
+        let rules = SpellRules::from_spec(spec);
+        let Some(dmg) = &rules.damage else { continue };
         let lvl = state
             .actors
             .get(actor_idx)
             .map(|a| a.char_level)
             .unwrap_or(1);
-        let dice: String = pick_dice_for_level(dmg, lvl);
-        let dmg_type = dmg.damage_type.to_ascii_lowercase();
-        let _ = spec;
+        let dmg_type = DamageType::parse(&dmg.damage_type);
         // Roll
-        let mut total = state.roll_dice_str(&dice);
-        if crit {
-            total += state.roll_dice_str(&dice);
+        let mut total =
+            dmg.dice(lvl)
+                .roll_damage(&mut state.rng, crit, rules.attack.unwrap_or_default());
+        if let Some(save) = rules.save {
+            let success = saved
+                .iter()
+                .any(|(a, id)| *a == actor_idx && *id == ability_id);
+            total = save_damage(total, success, save.half_on_success);
         }
         if let Some(tgt_idx) = state.actors[actor_idx].target {
             if !state.actor_alive(actor_idx) || !state.actor_alive(tgt_idx) {
@@ -61,10 +42,17 @@ pub fn run(state: &mut SimState) {
             }
             let hp_before = state.actors[tgt_idx].hp;
             let original_total = total;
-            // Underwater fire resistance (prototype): halve fire damage
-            if state.underwater && dmg_type == "fire" {
-                total = (total / 2).max(0);
-            }
+            // Underwater counts as fire resistance (prototype)
+            let resistant = dmg_type.is_some_and(|t| {
+                state.actors[tgt_idx].resist.contains(&t)
+                    || (state.underwater && t == DamageType::Fire)
+            });
+            total = resist(total, resistant);
+            // Threat counts damage dealt, including what THP absorbs
+            let rules = state.threat_rules;
+            state.actors[tgt_idx]
+                .threat
+                .add_damage(&rules, actor_idx, total);
             // Apply Temporary Hit Points before HP
             if state.actors[tgt_idx].temp_hp > 0 && total > 0 {
                 let absorbed = total.min(state.actors[tgt_idx].temp_hp);
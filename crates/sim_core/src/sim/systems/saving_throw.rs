@@ -1,22 +1,14 @@
 //! Resolve saving throws for completed casts that specify a save.
+//!
+//! Failed saves apply the spell's `on_fail` condition; successes are recorded
+//! in `saves_succeeded` so the damage system can halve or negate the damage.
 
 use crate::combat::conditions::Condition;
-use crate::rules::saves::SaveKind;
+use crate::rules::attack::Advantage;
+use crate::rules::saves::{SaveKind, roll_save};
 use crate::sim::events::SimEvent;
 use crate::sim::state::SimState;
 
-fn parse_save_kind(s: &str) -> SaveKind {
-    match s.to_ascii_lowercase().as_str() {
-        "str" | "strength" => SaveKind::Str,
-        "dex" | "dexterity" => SaveKind::Dex,
-        "con" | "constitution" => SaveKind::Con,
-        "int" | "intelligence" => SaveKind::Int,
-        "wis" | "wisdom" => SaveKind::Wis,
-        "cha" | "charisma" => SaveKind::Cha,
-        _ => SaveKind::Dex,
-    }
-}
-
 fn parse_condition(s: &str) -> Option<Condition> {
     match s.to_ascii_lowercase().as_str() {
         "prone" => Some(Condition::Prone),
@@ -50,13 +42,12 @@ pub fn run(state: &mut SimState) {
             continue;
         }
         let dc = save_dc_opt.unwrap_or(state.actors[actor_idx].spell_save_dc);
-        let kind = parse_save_kind(&save_kind_s);
+        let kind = SaveKind::parse(&save_kind_s);
         let mod_bonus = actor_save_mod(state, tgt_idx, kind);
-        let (roll, _nat20) = state.roll_d20(crate::rules::attack::Advantage::Normal);
-        let total = roll + mod_bonus;
+        let save = roll_save(&mut state.rng, Advantage::Normal, mod_bonus, dc);
+        let (total, ok) = (save.total, save.success);
         let caster_id = state.actors[actor_idx].id.clone();
         let tgt_id = state.actors[tgt_idx].id.clone();
-        let ok = total >= dc;
         state.events.push(SimEvent::SaveResolved {
             caster: caster_id.clone(),
             target: tgt_id.clone(),
@@ -66,6 +57,9 @@ pub fn run(state: &mut SimState) {
             dc,
             success: ok,
         });
+        if ok {
+            state.saves_succeeded.push((actor_idx, ability_id.clone()));
+        }
         if !ok
             && let Some(of) = on_fail
             && let Some(name) = of.apply_condition.as_deref()
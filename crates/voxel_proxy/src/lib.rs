@@ -89,6 +89,21 @@ impl VoxelGrid {
             + (z as usize) * (d.x as usize) * (d.y as usize)
     }
 
+    /// Inverse of [`Self::index`]; `None` outside the grid.
+    #[inline]
+    pub fn coords(&self, idx: usize) -> Option<UVec3> {
+        if idx >= self.occ.len() {
+            return None;
+        }
+        let d = self.meta.dims;
+        let (nx, ny) = (d.x as usize, d.y as usize);
+        Some(UVec3::new(
+            (idx % nx) as u32,
+            ((idx / nx) % ny) as u32,
+            (idx / (nx * ny)) as u32,
+        ))
+    }
+
     /// Mark occupancy at (x,y,z).
     #[inline]
     pub fn set(&mut self, x: u32, y: u32, z: u32, solid: bool) {
@@ -311,6 +326,7 @@ pub fn carve_sphere(grid: &mut VoxelGrid, center_m: DVec3, radius: Length) -> Re
     let yi1 = min(max_v.y.ceil() as u32, d.y - 1);
     let zi1 = min(max_v.z.ceil() as u32, d.z - 1);
     let mut removed_centers = Vec::new();
+    let mut removed = Vec::new();
     let mut chunks = HashSet::new();
     for z in zi0..=zi1 {
         for y in yi0..=yi1 {
@@ -330,6 +346,7 @@ pub fn carve_sphere(grid: &mut VoxelGrid, center_m: DVec3, radius: Length) -> Re
                     let cc = grid.chunk_of(x, y, z);
                     chunks.insert((cc.x, cc.y, cc.z));
                     removed_centers.push(p_m);
+                    removed.push(UVec3::new(x, y, z));
                 }
             }
         }
@@ -339,6 +356,7 @@ pub fn carve_sphere(grid: &mut VoxelGrid, center_m: DVec3, radius: Length) -> Re
     }
     RemovedVoxels {
         centers_m: removed_centers,
+        voxels: removed,
         chunks_touched: chunks
             .into_iter()
             .map(|(x, y, z)| UVec3::new(x, y, z))
@@ -349,6 +367,8 @@ pub fn carve_sphere(grid: &mut VoxelGrid, center_m: DVec3, radius: Length) -> Re
 /// Summary of carve operation for debris spawning and remesh scheduling.
 pub struct RemovedVoxels {
     pub centers_m: Vec<DVec3>,
+    /// Voxel coordinates cleared, parallel to `centers_m`.
+    pub voxels: Vec<UVec3>,
     pub chunks_touched: Vec<UVec3>,
 }
 
@@ -419,10 +439,12 @@ mod tests {
                     let i = g.index(x, y, z);
                     g.occ[i] = 1;
                     assert!(g.is_solid(x, y, z));
+                    assert_eq!(g.coords(i), Some(UVec3::new(x, y, z)));
                 }
             }
         }
         assert_eq!(g.solid_count(), 8 * 9 * 10);
+        assert_eq!(g.coords(8 * 9 * 10), None);
     }
 
     #[test]